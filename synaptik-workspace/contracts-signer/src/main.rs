@@ -7,8 +7,8 @@ use base64::engine::general_purpose::STANDARD as B64;
 use blake3;
 use chrono::Utc;
 use clap::Parser;
+use contracts::{ContractPack, PackFileEntry};
 use ed25519_dalek::{SigningKey, Signature, Signer};
-use serde::{Deserialize, Serialize};
 use serde_json::json;
 use walkdir::WalkDir;
 use toml_edit::DocumentMut;
@@ -30,23 +30,6 @@ struct Cli {
     key_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct PackFileEntry { path: String, blake3: String, size: u64 }
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ContractPack {
-    version: String,
-    algo: String,
-    canon_hash: String,
-    files: Vec<PackFileEntry>,
-    blobs: std::collections::BTreeMap<String, String>,
-    policy: serde_json::Value,
-    #[serde(default)]
-    signature: Option<String>,
-    #[serde(default)]
-    signing_key_id: Option<String>,
-}
-
 fn main() -> Result<()> {
     let cli = Cli::parse();
     let base = PathBuf::from(&cli.dir);
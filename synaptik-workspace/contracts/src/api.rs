@@ -28,11 +28,36 @@ pub struct CapsAnnot {
     pub ts_ms: u64,
 }
 
+/// Declarative schema for a [`Contract`]: what it looks at and what it can
+/// say, so callers and tooling can reason about it without running
+/// `evaluate` blind or grepping source for label strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractMetadata {
+    pub name: &'static str,
+    pub version: &'static str,
+    /// `SimCapsule`/context fields this contract inspects to reach a verdict
+    /// (e.g. `"text"`, `"source"`, `"artifacts"`).
+    #[serde(default)]
+    pub inspects: Vec<&'static str>,
+    /// Labels this contract may attach to the `CapsAnnot` it produces.
+    #[serde(default)]
+    pub labels: Vec<&'static str>,
+    /// `Verdict` values this contract can produce.
+    pub verdicts: Vec<Verdict>,
+    /// Policy/bundle version this contract stamps onto its annotations.
+    pub policy_ver: &'static str,
+}
+
 /// Lightweight contract trait for evaluating a capsule.
 pub trait Contract {
     fn name(&self) -> &'static str;
     fn version(&self) -> &'static str;
     fn evaluate(&self, cap: &SimCapsule) -> CapsAnnot;
+    /// Structured description of what this contract inspects and can emit.
+    /// Lets a [`crate::registry::ContractRegistry`] (or ad-hoc tooling)
+    /// discover labels and required fields instead of treating them as
+    /// magic strings baked into callers like `reward_from_annotation`.
+    fn metadata(&self) -> ContractMetadata;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
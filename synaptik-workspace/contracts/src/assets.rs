@@ -1,21 +1,76 @@
 use anyhow::{Context, Result};
 use blake3;
+use once_cell::sync::OnceCell;
 use std::{
     borrow::Cow,
+    collections::HashMap,
     fs,
+    io::Read,
     path::{Path, PathBuf},
 };
 
+use crate::sandbox_fs::Sandbox;
+
 /// === Embedded canon contracts ===
 pub const NONVIOLENCE_TOML_NAME: &str = "nonviolence.toml";
 pub const NONVIOLENCE_TOML: &str = include_str!("../assets/nonviolence.toml");
 
+/// One canon contract bundled into the binary: its on-disk file name and
+/// embedded text. To ship another default contract, add an `include_str!`
+/// constant and an entry here -- `write_default_contracts` and
+/// `read_verified_or_embedded`/`verify_all` pick it up automatically.
+pub struct ContractManifestEntry {
+    pub name: &'static str,
+    pub text: &'static str,
+}
+
+/// Every canon contract this binary knows how to seed and verify.
+pub const CONTRACT_MANIFEST: &[ContractManifestEntry] = &[ContractManifestEntry {
+    name: NONVIOLENCE_TOML_NAME,
+    text: NONVIOLENCE_TOML,
+}];
+
+/// Each manifest entry's blake3, computed once on first use rather than
+/// re-hashed on every `read_verified_or_embedded`/`verify_all` call.
+fn expected_hashes() -> &'static HashMap<&'static str, String> {
+    static HASHES: OnceCell<HashMap<&'static str, String>> = OnceCell::new();
+    HASHES.get_or_init(|| {
+        CONTRACT_MANIFEST
+            .iter()
+            .map(|e| (e.name, blake3::hash(e.text.as_bytes()).to_hex().to_string()))
+            .collect()
+    })
+}
+
 /// Return the embedded text for a known contract, if any.
 pub fn default_contract_text(name: &str) -> Option<&'static str> {
-    match name {
-        NONVIOLENCE_TOML_NAME => Some(NONVIOLENCE_TOML),
-        _ => None,
+    CONTRACT_MANIFEST
+        .iter()
+        .find(|e| e.name == name)
+        .map(|e| e.text)
+}
+
+/// The locked-in expected blake3 for a known contract, if any.
+fn expected_blake3(name: &str) -> Option<&'static str> {
+    expected_hashes().get(name).map(|s| s.as_str())
+}
+
+/// Hash a reader's contents through a streaming `blake3::Hasher` in fixed
+/// 64 KiB chunks, so verifying a large contract file never requires
+/// holding the whole thing in memory at once just to hash it (the caller
+/// still decides separately whether it needs the bytes too).
+fn hash_streamed(mut reader: impl Read) -> Result<String> {
+    const BUF_SIZE: usize = 64 * 1024;
+    let mut buf = [0u8; BUF_SIZE];
+    let mut hasher = blake3::Hasher::new();
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
     }
+    Ok(hasher.finalize().to_hex().to_string())
 }
 
 /// Seed missing default contracts into a destination directory (idempotent).
@@ -23,85 +78,168 @@ pub fn default_contract_text(name: &str) -> Option<&'static str> {
 pub fn write_default_contracts(dir: impl AsRef<Path>) -> Result<Vec<String>> {
     let dir = dir.as_ref();
     fs::create_dir_all(dir).with_context(|| format!("create_dir_all({:?})", dir))?;
+    let sandbox = Sandbox::open(dir).with_context(|| format!("open sandbox at {:?}", dir))?;
 
     let mut created = Vec::new();
 
-    for (name, text) in [(NONVIOLENCE_TOML_NAME, NONVIOLENCE_TOML)] {
-        let path = dir.join(name);
-        if !path.exists() {
-            fs::write(&path, text).with_context(|| format!("write {:?}", path))?;
-            created.push(name.to_string());
+    for entry in CONTRACT_MANIFEST {
+        if !dir.join(entry.name).exists() {
+            let mut f = sandbox
+                .create_write(Path::new(entry.name))
+                .with_context(|| format!("write {:?}/{}", dir, entry.name))?;
+            std::io::Write::write_all(&mut f, entry.text.as_bytes())
+                .with_context(|| format!("write {:?}/{}", dir, entry.name))?;
+            created.push(entry.name.to_string());
         }
     }
 
     Ok(created)
 }
 
-/// Verified reader with “locked” mode.
-///
-/// - If `path` exists:
-///   - compute blake3(file) and compare to blake3(embedded) **if** we know an embedded copy.
-///   - if hashes match → return file contents.
-///   - if mismatch and `locked`:
-///       * overwrite file with embedded
-///       * return embedded
-///   - if mismatch and **not** locked:
-///       * return file (but you can log a warning upstream)
-///
-/// - If `path` missing and we know the embedded copy:
-///   - if parent dir exists (or can be created), write the embedded to disk
-///   - return embedded
-///
-/// - If we don’t have an embedded copy for `name`, just try to read the file best-effort.
-pub fn read_verified_or_embedded(
-    path: &Path,
-    name: &str,
-    locked: bool,
-) -> Result<Cow<'static, str>> {
+/// Outcome of checking one on-disk contract file against the embedded
+/// manifest, as reported by [`verify_all`] (and, implicitly, by each
+/// [`read_verified_or_embedded`] call).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractStatus {
+    /// The file matched the manifest hash (or was missing and has now
+    /// been seeded from it); nothing needed healing.
+    Verified { name: String },
+    /// The file was missing or its hash didn't match the manifest, and
+    /// `locked` restored it to the embedded canonical text.
+    Healed { name: String },
+    /// The file's hash didn't match the manifest, but `locked` was false
+    /// so the local edit was left in place.
+    DriftedUnlocked { name: String },
+}
+
+/// Shared implementation behind [`read_verified_or_embedded`] and
+/// [`verify_all`]: returns the resolved contents alongside what happened.
+fn check_one(path: &Path, name: &str, locked: bool) -> Result<(Cow<'static, str>, ContractStatus)> {
     let embedded_opt = default_contract_text(name);
+    let parent = path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("invalid path: no parent: {:?}", path))?;
+    let leaf = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("invalid path: no file name: {:?}", path))?;
 
     // Try reading local file if present.
     if path.exists() {
-        let file_bytes = fs::read(path).with_context(|| format!("read {:?}", path))?;
-        if let Some(embedded) = embedded_opt {
-            let file_hash = blake3::hash(&file_bytes).to_hex().to_string();
-            let embedded_hash = blake3::hash(embedded.as_bytes()).to_hex().to_string();
-            if file_hash == embedded_hash {
-                // Verified
-                return Ok(Cow::Owned(String::from_utf8_lossy(&file_bytes).to_string()));
+        let sandbox =
+            Sandbox::open(parent).with_context(|| format!("open sandbox at {:?}", parent))?;
+        let f = sandbox
+            .open_read(Path::new(leaf))
+            .with_context(|| format!("read {:?}", path))?;
+        let file_hash = hash_streamed(f).with_context(|| format!("hash {:?}", path))?;
+        let reread = |sandbox: &Sandbox| -> Result<String> {
+            let mut f = sandbox
+                .open_read(Path::new(leaf))
+                .with_context(|| format!("read {:?}", path))?;
+            let mut bytes = Vec::new();
+            f.read_to_end(&mut bytes)
+                .with_context(|| format!("read {:?}", path))?;
+            Ok(String::from_utf8_lossy(&bytes).to_string())
+        };
+
+        if let (Some(embedded), Some(expected)) = (embedded_opt, expected_blake3(name)) {
+            if file_hash == expected {
+                // Verified: re-read (hashing didn't retain the bytes) is
+                // only needed for its contents now.
+                return Ok((
+                    Cow::Owned(reread(&sandbox)?),
+                    ContractStatus::Verified { name: name.to_string() },
+                ));
             }
             // Mismatch
             if locked {
                 // Auto-heal: restore canonical embedded
-                if let Some(dir) = path.parent() {
-                    fs::create_dir_all(dir).ok();
-                }
-                fs::write(path, embedded)
+                let mut wf = sandbox
+                    .create_write(Path::new(leaf))
+                    .with_context(|| format!("restore embedded {:?}", path))?;
+                std::io::Write::write_all(&mut wf, embedded.as_bytes())
                     .with_context(|| format!("restore embedded {:?}", path))?;
-                return Ok(Cow::Borrowed(embedded));
+                return Ok((
+                    Cow::Borrowed(embedded),
+                    ContractStatus::Healed { name: name.to_string() },
+                ));
             } else {
                 // Allow local edits in unlocked mode
-                return Ok(Cow::Owned(String::from_utf8_lossy(&file_bytes).to_string()));
+                return Ok((
+                    Cow::Owned(reread(&sandbox)?),
+                    ContractStatus::DriftedUnlocked { name: name.to_string() },
+                ));
             }
         } else {
             // Unknown name: no embedded baseline; return local as-is
-            return Ok(Cow::Owned(String::from_utf8_lossy(&file_bytes).to_string()));
+            return Ok((
+                Cow::Owned(reread(&sandbox)?),
+                ContractStatus::Verified { name: name.to_string() },
+            ));
         }
     }
 
     // File missing: if we have embedded, write it; else return empty.
     if let Some(embedded) = embedded_opt {
-        if let Some(dir) = path.parent() {
-            fs::create_dir_all(dir).ok();
-        }
+        fs::create_dir_all(parent).ok();
         // Best-effort write; ignore failures (caller still gets embedded in memory)
-        let _ = fs::write(path, embedded);
-        Ok(Cow::Borrowed(embedded))
+        if let Ok(sandbox) = Sandbox::open(parent) {
+            if let Ok(mut f) = sandbox.create_write(Path::new(leaf)) {
+                let _ = std::io::Write::write_all(&mut f, embedded.as_bytes());
+            }
+        }
+        Ok((
+            Cow::Borrowed(embedded),
+            ContractStatus::Healed { name: name.to_string() },
+        ))
     } else {
-        Ok(Cow::Owned(String::new()))
+        Ok((
+            Cow::Owned(String::new()),
+            ContractStatus::Verified { name: name.to_string() },
+        ))
     }
 }
 
+/// Verified reader with “locked” mode.
+///
+/// - If `path` exists:
+///   - compute blake3(file) and compare to the manifest's locked-in hash
+///     **if** we know an embedded copy.
+///   - if hashes match → return file contents.
+///   - if mismatch and `locked`:
+///       * overwrite file with embedded
+///       * return embedded
+///   - if mismatch and **not** locked:
+///       * return file (but you can log a warning upstream)
+///
+/// - If `path` missing and we know the embedded copy:
+///   - if parent dir exists (or can be created), write the embedded to disk
+///   - return embedded
+///
+/// - If we don’t have an embedded copy for `name`, just try to read the file best-effort.
+pub fn read_verified_or_embedded(
+    path: &Path,
+    name: &str,
+    locked: bool,
+) -> Result<Cow<'static, str>> {
+    check_one(path, name, locked).map(|(text, _status)| text)
+}
+
+/// Check every contract in [`CONTRACT_MANIFEST`] under `root` (the same
+/// directory `write_default_contracts`/`read_verified_or_embedded` are
+/// pointed at), healing or reporting drift per [`read_verified_or_embedded`]'s
+/// `locked` semantics, and return one [`ContractStatus`] per contract so a
+/// caller can audit the whole bundle in one call instead of checking each
+/// file individually.
+pub fn verify_all(root: &Path, locked: bool) -> Result<Vec<ContractStatus>> {
+    CONTRACT_MANIFEST
+        .iter()
+        .map(|entry| {
+            let path = root.join(entry.name);
+            check_one(&path, entry.name, locked).map(|(_text, status)| status)
+        })
+        .collect()
+}
+
 /// Convenience: resolve `<root>/contracts/<name>`
 pub fn contracts_path(root: &Path, name: &str) -> PathBuf {
     root.join("contracts").join(name)
@@ -0,0 +1,118 @@
+//! Storage abstraction behind [`ContractsStore`](crate::store::ContractsStore),
+//! so the same capsule/annotation logic can run against local disk or a
+//! remote S3-compatible bucket (e.g. Garage) with no caller-visible changes.
+//!
+//! Keys are logical, `/`-separated paths (e.g. `capsules/<id>.json`,
+//! `annotations/<id>.jsonl`) — backends are free to map them onto whatever
+//! physical layout suits the medium (a single file, or numbered objects).
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "s3_backend")]
+pub mod s3;
+
+/// A pluggable persistence medium for [`ContractsStore`](crate::store::ContractsStore).
+pub trait CapsuleBackend: Send + Sync {
+    /// Atomically replace the object at `key` with `bytes` — readers never
+    /// observe a partially-written value.
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Read the full current contents addressed by `key`, or `None` if absent.
+    /// For a key that's been written via [`CapsuleBackend::append`], this
+    /// returns every appended entry concatenated in append order.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Append `bytes` (already including its own line terminator, if any) as
+    /// the next entry under `key`. Object stores have no native append, so a
+    /// backend may implement this as a new numbered object under `key`;
+    /// [`CapsuleBackend::get`] must then stitch those back together in order.
+    fn append(&self, key: &str, bytes: &[u8]) -> Result<()>;
+
+    /// List every key currently stored under `prefix`, in ascending order.
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Remove `key` (and, for append-backed keys, every entry under it) —
+    /// used when folding an annotation chain into a checkpoint.
+    fn remove(&self, key: &str) -> Result<()>;
+}
+
+/// Default backend: the local filesystem, rooted at a directory. `append`
+/// maps directly onto a growing file, matching the repo's original
+/// (pre-backend) on-disk layout exactly.
+pub struct FsBackend {
+    root: PathBuf,
+}
+
+impl FsBackend {
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join("capsules"))?;
+        fs::create_dir_all(root.join("annotations"))?;
+        fs::create_dir_all(root.join("handles"))?;
+        Ok(Self { root })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl CapsuleBackend for FsBackend {
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        {
+            let mut f = fs::File::create(&tmp)?;
+            f.write_all(bytes)?;
+            f.sync_all()?;
+        }
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(&path).with_context(|| format!("read {key}"))?))
+    }
+
+    fn append(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut f = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        f.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let dir = self.path(prefix);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut names: Vec<String> = fs::read_dir(&dir)?
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .map(|name| format!("{prefix}/{name}"))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+}
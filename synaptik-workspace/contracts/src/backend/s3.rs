@@ -0,0 +1,94 @@
+//! S3-compatible backend (tested against Garage; also works against
+//! MinIO/AWS S3) behind the `s3_backend` feature. Keeps
+//! [`ContractsStore`](crate::store::ContractsStore) fully synchronous like
+//! the rest of this crate, rather than pulling in an async runtime just for
+//! object storage.
+
+use super::CapsuleBackend;
+use anyhow::{Context, Result};
+use s3::bucket::Bucket;
+use s3::creds::Credentials;
+use s3::Region;
+
+/// `put_atomic` maps onto a single versioned `PUT` — S3-compatible stores
+/// already serve either the old or the new object body to concurrent
+/// readers, never a torn one. `append` has no native equivalent, so each
+/// append becomes its own numbered object under `key/` (`key/000001.part`,
+/// `key/000002.part`, ...); `get` lists and concatenates them back in order.
+pub struct S3Backend {
+    bucket: Bucket,
+}
+
+impl S3Backend {
+    pub fn new(bucket: &str, region: Region, credentials: Credentials) -> Result<Self> {
+        let bucket = Bucket::new(bucket, region, credentials)
+            .context("construct s3 bucket handle")?
+            .with_path_style();
+        Ok(Self { bucket })
+    }
+}
+
+impl CapsuleBackend for S3Backend {
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        self.bucket
+            .put_object(key, bytes)
+            .map(|_| ())
+            .with_context(|| format!("s3 put_object {key}"))
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        // Plain objects (written via put_atomic) live directly at `key`.
+        if let Ok(resp) = self.bucket.get_object(key) {
+            if resp.status_code() == 200 {
+                return Ok(Some(resp.bytes().to_vec()));
+            }
+        }
+        // Append-backed keys are a "directory" of numbered parts; stitch them back together.
+        let parts = self.list(key)?;
+        if parts.is_empty() {
+            return Ok(None);
+        }
+        let mut out = Vec::new();
+        for part in parts {
+            let resp = self
+                .bucket
+                .get_object(&part)
+                .with_context(|| format!("s3 get_object {part}"))?;
+            out.extend_from_slice(resp.bytes());
+        }
+        Ok(Some(out))
+    }
+
+    fn append(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let next_seq = self.list(key)?.len() as u64 + 1;
+        let part_key = format!("{key}/{next_seq:06}.part");
+        self.bucket
+            .put_object(&part_key, bytes)
+            .map(|_| ())
+            .with_context(|| format!("s3 append {part_key}"))
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let pages = self
+            .bucket
+            .list(format!("{prefix}/"), None)
+            .with_context(|| format!("s3 list {prefix}"))?;
+        let mut keys: Vec<String> = pages
+            .into_iter()
+            .flat_map(|page| page.contents)
+            .map(|obj| obj.key)
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let _ = self.bucket.delete_object(key);
+        for part in self.list(key)? {
+            self.bucket
+                .delete_object(&part)
+                .with_context(|| format!("s3 delete_object {part}"))?;
+        }
+        Ok(())
+    }
+}
@@ -62,6 +62,15 @@ pub struct CapsuleMeta {
     /// Optional parent capsule id (for derived/sim capsules).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_id: Option<String>,
+
+    /// Present only when this capsule's `inputs`/`outputs` were sealed under
+    /// a store master key (see `ContractsStore::with_master_key`): the
+    /// per-capsule data key, wrapped (XChaCha20-Poly1305) by that master key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sealed_data_key: Option<String>,
+    /// Nonce used to wrap `sealed_data_key`, base64-encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sealed_key_nonce: Option<String>,
 }
 
 /// Atomic experience unit used by contracts for evaluation and gating.
@@ -19,6 +19,60 @@ pub struct EvaluationResult {
     // NEW: binding, deduped constraints from matched rules
     #[serde(default)]
     pub constraints: Vec<String>,
+
+    /// Weighted ATP spent evaluating this input (see [`rule_atp_cost`]).
+    #[serde(default)]
+    pub atp_spent: u64,
+    /// Set when [`evaluate_with_atp_budget`] stopped early because `atp_spent`
+    /// would have exceeded the caller's budget; `passed` is forced to `false`
+    /// in that case (fail closed, not fail open).
+    #[serde(default)]
+    pub atp_budget_exceeded: bool,
+
+    /// Id of the [`ConsentGrant`] that downgraded a rule from a hard
+    /// violation to `allow_with_constraints`, if any. `None` when no grant
+    /// applied — either because no [`ConsentContext`] was supplied, or
+    /// nothing in it matched an active rule. Callers log this alongside the
+    /// decision so it's clear which grant suppressed which rule.
+    #[serde(default)]
+    pub matched_consent_id: Option<String>,
+}
+
+// ----------------- Consent -----------------
+
+/// A single time-bounded consent grant, already looked up by the caller
+/// (typically from a `user_consent` table keyed by `(consenting_party,
+/// consented_party, violation_code)`) and passed in here so evaluation stays
+/// a pure function of its inputs rather than reaching out to a store itself.
+#[derive(Debug, Clone)]
+pub struct ConsentGrant {
+    /// Identifier returned in [`EvaluationResult::matched_consent_id`] when
+    /// this grant is the one that applied.
+    pub id: String,
+    /// Exact [`ContractRule::violation_code`] this grant covers. Matching is
+    /// scoped to this code, never to `ContractRule::action`.
+    pub violation_code: String,
+    /// Unix seconds after which this grant is inert.
+    pub expires_at_unix: i64,
+}
+
+/// Grants in force for one evaluation call, plus the clock to check them
+/// against. `now_unix` is supplied by the caller rather than read from the
+/// system clock, so an expired grant behaves identically to no grant
+/// regardless of when the caller's lookup happened to run.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsentContext<'a> {
+    pub grants: &'a [ConsentGrant],
+    pub now_unix: i64,
+}
+
+impl<'a> ConsentContext<'a> {
+    /// The first non-expired grant covering `violation_code`, if any.
+    fn find(&self, violation_code: &str) -> Option<&'a ConsentGrant> {
+        self.grants
+            .iter()
+            .find(|g| g.violation_code == violation_code && g.expires_at_unix > self.now_unix)
+    }
 }
 
 // ----------------- I/O -----------------
@@ -152,11 +206,108 @@ fn extend_constraints(dst: &mut HashSet<String>, rule: &ContractRule) {
 
 // ----------------- Core -----------------
 
+/// Weighted ATP cost of evaluating one rule: a flat per-rule charge plus one
+/// unit per pattern it has to scan (`contains` + `contains_any` + `matches_any`),
+/// since that's what actually drives the cost of [`rule_matches`].
+pub fn rule_atp_cost(rule: &ContractRule) -> u64 {
+    let patterns = rule.contains.len()
+        + rule.contains_any.as_ref().map(|v| v.len()).unwrap_or(0)
+        + rule.matches_any.as_ref().map(|v| v.len()).unwrap_or(0);
+    ATP_COST_EVAL_RULE + patterns as u64
+}
+
 pub fn evaluate_input_against_rules(input: &str, contract: &MoralContract) -> EvaluationResult {
+    evaluate_inner(input, contract, None, None)
+}
+
+/// Same as [`evaluate_input_against_rules`], but aborts as soon as the
+/// weighted ATP cost of the rules scanned so far would exceed `budget`.
+/// An aborted evaluation fails closed: `passed: false`,
+/// `atp_budget_exceeded: true`, rather than silently returning whatever
+/// partial pass/fail verdict it had scanned up to that point.
+pub fn evaluate_with_atp_budget(
+    input: &str,
+    contract: &MoralContract,
+    budget: u64,
+) -> EvaluationResult {
+    evaluate_inner(input, contract, Some(budget), None)
+}
+
+/// Same as [`evaluate_input_against_rules`], but takes an explicit
+/// [`ConsentContext`]. A rule whose `violation_code` matches an active,
+/// non-expired grant in `consent` is reclassified from a hard violation to
+/// `allow_with_constraints`: its `constraints` still merge into the result,
+/// but it no longer fails `passed`. Pass `None` to evaluate exactly as
+/// [`evaluate_input_against_rules`] would.
+pub fn evaluate_with_consent(
+    input: &str,
+    contract: &MoralContract,
+    consent: Option<ConsentContext>,
+) -> EvaluationResult {
+    evaluate_inner(input, contract, None, consent)
+}
+
+/// Sink for one evaluation's outcome, invoked by [`evaluate_and_record`].
+/// Kept as a trait rather than a concrete logger so this crate stays
+/// storage-agnostic — `synaptik-core` implements it against its own
+/// `contract_events` table, the same way [`crate::backend::CapsuleBackend`]
+/// lets capsule storage stay agnostic of the actual backing store.
+pub trait ViolationRecorder {
+    fn record(&self, contract: &MoralContract, result: &EvaluationResult);
+}
+
+/// Opt-in variant of [`evaluate_with_consent`] that also hands the result to
+/// `recorder` before returning it, so a caller can persist an audit trail
+/// (violation_code, severity, passed, constraints) without duplicating the
+/// evaluation logic. Plain [`evaluate_input_against_rules`] never records.
+pub fn evaluate_and_record(
+    input: &str,
+    contract: &MoralContract,
+    consent: Option<ConsentContext>,
+    recorder: &dyn ViolationRecorder,
+) -> EvaluationResult {
+    let result = evaluate_inner(input, contract, None, consent);
+    recorder.record(contract, &result);
+    result
+}
+
+fn evaluate_inner(
+    input: &str,
+    contract: &MoralContract,
+    budget: Option<u64>,
+    consent: Option<ConsentContext>,
+) -> EvaluationResult {
+    let mut atp_spent: u64 = 0;
+
+    macro_rules! charge_or_abort {
+        ($rule:expr) => {{
+            let cost = rule_atp_cost($rule);
+            if let Some(budget) = budget {
+                if atp_spent + cost > budget {
+                    return EvaluationResult {
+                        passed: false,
+                        violated_rules: vec![],
+                        reason: format!(
+                            "ATP budget exceeded ({atp_spent}/{budget}) before contract evaluation completed."
+                        ),
+                        primary_violation_code: None,
+                        action_suggestion: None,
+                        constraints: vec![],
+                        atp_spent,
+                        atp_budget_exceeded: true,
+                        matched_consent_id: None,
+                    };
+                }
+            }
+            atp_spent += cost;
+            ATP_COUNTER.fetch_add(cost, _Ordering::Relaxed);
+        }};
+    }
+
     // Pass 1: allowlist (takes precedence)
     let mut allow_constraints: HashSet<String> = HashSet::new();
     for rule in &contract.rules {
-        ATP_COUNTER.fetch_add(ATP_COST_EVAL_RULE, _Ordering::Relaxed);
+        charge_or_abort!(rule);
         let eff = rule.effect.as_deref().unwrap_or("");
         let is_allow = eff.eq_ignore_ascii_case("allow")
             || rule
@@ -176,6 +327,9 @@ pub fn evaluate_input_against_rules(input: &str, contract: &MoralContract) -> Ev
                 primary_violation_code: None,
                 action_suggestion: None,
                 constraints: allow_constraints.into_iter().collect(),
+                atp_spent,
+                atp_budget_exceeded: false,
+                matched_consent_id: None,
             };
         }
     }
@@ -183,9 +337,10 @@ pub fn evaluate_input_against_rules(input: &str, contract: &MoralContract) -> Ev
     // Pass 2: collect violations
     let mut violations: Vec<ContractRule> = Vec::new();
     let mut constraints: HashSet<String> = HashSet::new();
+    let mut matched_consent_id: Option<String> = None;
 
     for rule in &contract.rules {
-        ATP_COUNTER.fetch_add(ATP_COST_EVAL_RULE, _Ordering::Relaxed);
+        charge_or_abort!(rule);
         // Skip allow rules in violation pass
         let eff = rule.effect.as_deref().unwrap_or("");
         let is_allow = eff.eq_ignore_ascii_case("allow")
@@ -200,19 +355,40 @@ pub fn evaluate_input_against_rules(input: &str, contract: &MoralContract) -> Ev
         }
 
         if rule_matches(rule, input) {
+            // An active consent grant for this exact violation_code
+            // downgrades the rule to allow_with_constraints: its
+            // constraints still apply, but it's not a hard violation.
+            let grant = rule
+                .violation_code
+                .as_deref()
+                .and_then(|code| consent.and_then(|c| c.find(code)));
             extend_constraints(&mut constraints, rule);
+            if let Some(grant) = grant {
+                if matched_consent_id.is_none() {
+                    matched_consent_id = Some(grant.id.clone());
+                }
+                continue;
+            }
             violations.push(rule.clone());
         }
     }
 
     if violations.is_empty() {
+        let reason = if matched_consent_id.is_some() {
+            "Allowed with constraints under an active consent grant.".to_string()
+        } else {
+            "No violations detected.".to_string()
+        };
         return EvaluationResult {
             passed: true,
             violated_rules: vec![],
-            reason: "No violations detected.".into(),
+            reason,
             primary_violation_code: None,
             action_suggestion: None,
-            constraints: vec![],
+            constraints: constraints.into_iter().collect(),
+            atp_spent,
+            atp_budget_exceeded: false,
+            matched_consent_id,
         };
     }
 
@@ -259,5 +435,8 @@ pub fn evaluate_input_against_rules(input: &str, contract: &MoralContract) -> Ev
         primary_violation_code: primary.violation_code.clone(),
         action_suggestion: primary.action_suggestion.clone(),
         constraints: constraints.into_iter().collect(),
+        atp_spent,
+        atp_budget_exceeded: false,
+        matched_consent_id,
     }
 }
@@ -0,0 +1,158 @@
+//! Layered contract loading, modeled on Mercurial's config layering
+//! (`%include`/`%unset` in `hgrc` files): a contract file can `%include`
+//! another contract file to pull in its rules, and `%unset <violation_code>`
+//! to drop an inherited rule it doesn't want. This lets a deployment keep one
+//! base nonviolence contract and layer small domain-specific override files
+//! on top, instead of copy-pasting the whole ruleset per domain.
+//!
+//! Directives are plain lines above (or interleaved with) the TOML body:
+//!
+//! ```toml
+//! %include base/nonviolence.toml
+//! %unset weapons_how_to
+//!
+//! [[rules]]
+//! action = "chat"
+//! violation_code = "extra_domain_rule"
+//! ...
+//! ```
+//!
+//! Layers are resolved depth-first, includes before the including file's own
+//! rules, so resolution order is deterministic and later layers win: a rule
+//! is keyed by `violation_code` (rules without one are never overridden, only
+//! appended), and `%unset` always applies after its own file's rules have
+//! been merged in. An include cycle (a file transitively including itself)
+//! is rejected rather than recursing forever.
+
+use crate::types::{ContractRule, MoralContract};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub type LayerResult<T> = std::result::Result<T, LayerError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LayerError {
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse path={path} error={err}")]
+    Parse { path: String, err: String },
+    #[error("include cycle detected at {0}")]
+    Cycle(String),
+}
+
+#[derive(Debug, Default)]
+struct Directives {
+    includes: Vec<String>,
+    unsets: Vec<String>,
+}
+
+/// Split `%include`/`%unset` directive lines out of a contract file's text,
+/// returning the directives alongside the remaining TOML body.
+fn parse_directives(text: &str) -> (Directives, String) {
+    let mut directives = Directives::default();
+    let mut body = String::with_capacity(text.len());
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("%include ") {
+            directives.includes.push(rest.trim().to_string());
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix("%unset ") {
+            directives.unsets.push(rest.trim().to_string());
+            continue;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    (directives, body)
+}
+
+/// Load `path`, resolving every `%include` transitively and applying every
+/// `%unset` after its own layer's rules are merged in, producing one
+/// flattened [`MoralContract`]. The outermost file's `name`/`version`/
+/// `description` win over anything pulled in via `%include`.
+pub fn load_layered_contract(path: impl AsRef<Path>) -> LayerResult<MoralContract> {
+    let mut seen = HashSet::new();
+    let mut meta = LayerMeta::default();
+    let mut rules: Vec<ContractRule> = Vec::new();
+
+    load_into(path.as_ref(), &mut seen, &mut meta, &mut rules)?;
+
+    Ok(MoralContract {
+        name: meta.name.unwrap_or_else(|| "layered".to_string()),
+        version: meta.version.unwrap_or_else(|| "0".to_string()),
+        description: meta.description,
+        rules,
+    })
+}
+
+#[derive(Default)]
+struct LayerMeta {
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+}
+
+fn load_into(
+    path: &Path,
+    seen: &mut HashSet<PathBuf>,
+    meta: &mut LayerMeta,
+    rules: &mut Vec<ContractRule>,
+) -> LayerResult<()> {
+    let canon = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if !seen.insert(canon.clone()) {
+        return Err(LayerError::Cycle(path.display().to_string()));
+    }
+
+    let text = fs::read_to_string(path)?;
+    let (directives, body) = parse_directives(&text);
+
+    // Includes land first, so this file's own rules (and its `%unset`s)
+    // merge in afterward and can override or remove them.
+    let base_dir = path.parent();
+    for include in &directives.includes {
+        let include_path = match base_dir {
+            Some(dir) => dir.join(include),
+            None => PathBuf::from(include),
+        };
+        load_into(&include_path, seen, meta, rules)?;
+    }
+
+    if !body.trim().is_empty() {
+        let parsed: MoralContract = toml::from_str(&body).map_err(|e| LayerError::Parse {
+            path: path.display().to_string(),
+            err: e.to_string(),
+        })?;
+        meta.name = Some(parsed.name);
+        meta.version = Some(parsed.version);
+        if parsed.description.is_some() {
+            meta.description = parsed.description;
+        }
+        for rule in parsed.rules {
+            upsert_rule(rules, rule);
+        }
+    }
+
+    for unset in &directives.unsets {
+        rules.retain(|r| r.violation_code.as_deref() != Some(unset.as_str()));
+    }
+
+    seen.remove(&canon);
+    Ok(())
+}
+
+/// Insert `rule`, replacing any existing rule with the same `violation_code`
+/// (last-writer-wins); rules with no `violation_code` are always appended.
+fn upsert_rule(rules: &mut Vec<ContractRule>, rule: ContractRule) {
+    if let Some(code) = rule.violation_code.clone() {
+        if let Some(existing) = rules
+            .iter_mut()
+            .find(|r| r.violation_code.as_deref() == Some(code.as_str()))
+        {
+            *existing = rule;
+            return;
+        }
+    }
+    rules.push(rule);
+}
@@ -2,25 +2,54 @@
 pub mod api;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod assets;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod backend;
 pub mod capsule;
 pub mod evaluator;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod layer;
 pub mod normalize;
+pub mod pack;
 pub mod patch;
+pub mod registry;
+pub mod schema;
+#[cfg(not(target_arch = "wasm32"))]
+mod sandbox_fs;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod seal;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod store;
 pub mod types;
 
-pub use api::{CapsAnnot, CapsId, Contract, Denied, PatchId, Purpose, Verdict};
+pub use api::{CapsAnnot, CapsId, Contract, ContractMetadata, Denied, PatchId, Purpose, Verdict};
+#[cfg(not(target_arch = "wasm32"))]
+pub use assets::{
+    default_contract_text, read_verified_or_embedded, verify_all, write_default_contracts,
+    ContractManifestEntry, ContractStatus, CONTRACT_MANIFEST,
+};
 #[cfg(not(target_arch = "wasm32"))]
-pub use assets::{default_contract_text, write_default_contracts};
+pub use backend::{CapsuleBackend, FsBackend};
+#[cfg(all(not(target_arch = "wasm32"), feature = "s3_backend"))]
+pub use backend::s3::S3Backend;
 pub use capsule::{ArtifactRef, CapsuleMeta, CapsuleSource, SimCapsule};
-pub use evaluator::{evaluate_input_against_rules, EvaluationResult};
+pub use evaluator::{
+    evaluate_and_record, evaluate_input_against_rules, evaluate_with_atp_budget,
+    evaluate_with_consent, ConsentContext, ConsentGrant, EvaluationResult, ViolationRecorder,
+};
 #[cfg(not(target_arch = "wasm32"))]
 pub use evaluator::load_contract_from_file;
+#[cfg(not(target_arch = "wasm32"))]
+pub use layer::{load_layered_contract, LayerError, LayerResult};
 pub use normalize::for_rules as normalize_for_rules;
-pub use patch::{PatchOp, PatchPlan};
+pub use pack::{verify_pack, ContractPack, PackFileEntry};
+pub use registry::ContractRegistry;
+pub use schema::{migrate_to_current, supports_patch_labels, Migration, CURRENT, MIN_SUPPORTED};
+pub use patch::{
+    apply_masks, apply_masks_with_spans, apply_regex_masks, apply_regex_masks_with_spans, PatchOp,
+    PatchPlan, MASK_TOKEN,
+};
 #[cfg(not(target_arch = "wasm32"))]
-pub use store::{CapsHandle, ContractsStore};
+pub use store::{resolve_siblings, verify_issuer_signature, CapsHandle, ContractsStore, VectorClock};
 pub use types::MoralContract;
 
 // Experimental host-side sandbox runner (host-only)
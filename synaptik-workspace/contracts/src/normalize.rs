@@ -1,24 +1,166 @@
 //! Text normalization helpers used for rule matching, stop phrases, and masking.
 //!
 //! Policy:
-//! - Drop control characters.
-//! - Drop common zero-width characters (ZWS/ZWNJ/ZWJ/WJ/BOM).
+//! - Strip C0/C1 control characters, keeping `\t` and `\n`.
+//! - Strip ANSI CSI escape sequences (ESC `[` ... final byte 0x40-0x7E) so a
+//!   banned phrase wrapped in color codes still reads as contiguous text.
+//! - Drop common zero-width/format characters (ZWS/ZWNJ/ZWJ/WJ/BOM) so a
+//!   phrase split by invisible characters still reads as contiguous text.
 //! - Unicode-aware lowercasing (char.to_lowercase()).
 //!
-//! Keep this logic single-sourced to avoid drift between evaluators and runtime gates.
+//! Keep this logic single-sourced to avoid drift between evaluators, runtime
+//! gates, and masking. Callers that need a byte-span mapping back to the
+//! original text (masking, which replaces matched ranges in place) should use
+//! [`normalized_with_spans`] rather than re-deriving this scan per character —
+//! ANSI sequences span multiple original characters, so per-character
+//! normalization can't recognize them.
+//!
+//! [`folded_with_spans`] layers a second, stricter pass on top of the above
+//! for literal banned-phrase matching (mask rules, stop phrases, streaming
+//! rule violations): combining-mark stripping, a small bundled confusables
+//! table, and separator-dropping/duplicate-letter collapsing, so "k i l l",
+//! "k1ll", and Cyrillic "ѕecret" all fold down and match the same entry
+//! "kill"/"secret" would. This stricter fold is deliberately *not* applied to
+//! [`for_rules`]/[`normalized_with_spans`] themselves -- dropping separators
+//! and collapsing repeats is too aggressive for general rule text and would
+//! also corrupt regex character classes (`\d`, etc.) if applied ahead of a
+//! regex engine.
+
+const ESC: char = '\u{1B}';
 
 /// Normalize text for rule matching and case-insensitive search.
 pub fn for_rules(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for ch in s.chars() {
-        if ch.is_control() { continue; }
+    normalized_with_spans(s).0.into_iter().collect()
+}
+
+/// Build a normalized character view of `s` alongside the original byte span
+/// each normalized char came from. Characters removed by normalization
+/// (control, ANSI CSI, zero-width) emit no span entries, so span-based
+/// replacement (masking) can't have offsets shift and reveal trailing
+/// suffixes.
+pub fn normalized_with_spans(s: &str) -> (Vec<char>, Vec<(usize, usize)>) {
+    let mut chars = Vec::new();
+    let mut spans = Vec::new();
+    let mut iter = s.char_indices().peekable();
+
+    while let Some((start, ch)) = iter.next() {
+        // ANSI CSI sequence: ESC '[' ... final byte 0x40-0x7E. Drop the whole
+        // sequence, emitting no span entries for any byte it covers.
+        if ch == ESC {
+            if let Some(&(_, '[')) = iter.peek() {
+                iter.next(); // consume '['
+                while let Some(&(_, c)) = iter.peek() {
+                    iter.next();
+                    if ('\u{40}'..='\u{7E}').contains(&c) {
+                        break;
+                    }
+                }
+                continue;
+            }
+        }
+
+        if ch.is_control() && ch != '\t' && ch != '\n' {
+            continue;
+        }
+
+        let end = start + ch.len_utf8();
         for lc in ch.to_lowercase() {
             match lc {
-                '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => {},
-                _ => out.push(lc),
+                '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => {}
+                _ => {
+                    chars.push(lc);
+                    spans.push((start, end));
+                }
             }
         }
     }
-    out
+    (chars, spans)
+}
+
+// ----------------------------------------------------------------------
+// Stricter folding for literal banned-phrase matching (see module doc).
+// ----------------------------------------------------------------------
+
+/// True for combining marks commonly used to hide a letter behind a stray
+/// accent (e.g. "k\u{307}i\u{307}l\u{307}l\u{307}"). Covers the combining
+/// diacritical marks blocks; not every Unicode Mn codepoint, but the ones
+/// actually seen in this kind of evasion.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c as u32, 0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF)
+}
+
+/// Bundled confusables table: maps a handful of frequently abused look-alike
+/// codepoints (Cyrillic/Greek homoglyphs, common leetspeak digit/symbol
+/// substitutions) to an ASCII skeleton letter. Not the full Unicode UTS #39
+/// confusables table -- just the characters actually seen in practice for
+/// this kind of evasion.
+fn confusable_skeleton(c: char) -> char {
+    match c {
+        'а' | 'ɑ' | 'α' | '@' => 'a',
+        'е' | 'ё' | 'є' | '3' => 'e',
+        'і' | 'ı' | 'ι' | '1' | '!' => 'i',
+        'о' | 'ο' | '0' => 'o',
+        'р' | 'ρ' => 'p',
+        'с' | 'ϲ' => 'c',
+        'х' | 'χ' => 'x',
+        'у' | 'γ' => 'y',
+        'ѕ' | '$' | '5' => 's',
+        'ј' => 'j',
+        'ԁ' => 'd',
+        'ɡ' | 'ց' => 'g',
+        'ո' => 'n',
+        'ѵ' | 'ν' => 'v',
+        '4' => 'a',
+        '7' | '+' => 't',
+        other => other,
+    }
+}
+
+/// True for characters dropped between folded letters so "k i l l" and
+/// "k-i-l-l" read the same as "kill".
+fn is_fold_separator(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '-' | '_' | '.' | '*' | '\u{00B7}')
+}
+
+/// Build a folded character view of `s` -- [`normalized_with_spans`] plus
+/// combining-mark stripping, confusable-to-ASCII folding, separator
+/// dropping, and duplicate-letter collapsing -- alongside the original byte
+/// span each folded char came from, for span-correct masking/early-stop.
+/// See the module doc for why this is a separate, stricter pass rather than
+/// a change to [`normalized_with_spans`] itself.
+pub fn folded_with_spans(s: &str) -> (Vec<char>, Vec<(usize, usize)>) {
+    let (chars, spans) = normalized_with_spans(s);
+
+    let mut skeleton = Vec::with_capacity(chars.len());
+    let mut sk_spans = Vec::with_capacity(chars.len());
+    for (ch, span) in chars.into_iter().zip(spans) {
+        if is_combining_mark(ch) {
+            continue;
+        }
+        let folded = confusable_skeleton(ch);
+        if is_fold_separator(folded) {
+            continue;
+        }
+        skeleton.push(folded);
+        sk_spans.push(span);
+    }
+
+    // Collapse consecutive duplicate folded chars (e.g. "kkkiiillll" ->
+    // "kill"), keeping the span of the first occurrence in each run.
+    let mut out_chars = Vec::with_capacity(skeleton.len());
+    let mut out_spans = Vec::with_capacity(skeleton.len());
+    for (i, &ch) in skeleton.iter().enumerate() {
+        if i > 0 && skeleton[i - 1] == ch {
+            continue;
+        }
+        out_chars.push(ch);
+        out_spans.push(sk_spans[i]);
+    }
+    (out_chars, out_spans)
 }
 
+/// Folded text only, for callers that don't need the span map (e.g.
+/// stop-phrase early-stop, which only needs a yes/no match).
+pub fn fold_for_matching(s: &str) -> String {
+    folded_with_spans(s).0.into_iter().collect()
+}
@@ -0,0 +1,105 @@
+//! Signed contract-pack format shared by `contracts-signer` (produces packs) and
+//! this crate (verifies them before a pack is trusted/loaded).
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as B64;
+use ed25519_dalek::{Signature, VerifyingKey, Verifier};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackFileEntry {
+    pub path: String,
+    pub blake3: String,
+    pub size: u64,
+}
+
+/// A signed, content-addressed bundle of contract files (as produced by
+/// `contracts-signer`). `canon_hash` is blake3 over the concatenation of each
+/// `files[i].blake3` (in the order stored), and `signature` (when present) is
+/// an Ed25519 signature over this struct with `signature` cleared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractPack {
+    pub version: String,
+    pub algo: String,
+    pub canon_hash: String,
+    pub files: Vec<PackFileEntry>,
+    pub blobs: BTreeMap<String, String>,
+    pub policy: serde_json::Value,
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub signing_key_id: Option<String>,
+}
+
+/// Recompute every file hash from `pack.blobs`, the canon hash over them, and
+/// (if signed) the Ed25519 signature against `trusted_keys[signing_key_id]`.
+///
+/// Rejects on the first mismatch: a missing blob, a blake3 mismatch, a
+/// canon-hash mismatch, an unsigned pack that claims a `signing_key_id`, a
+/// `signing_key_id` absent from `trusted_keys`, or a signature that doesn't
+/// verify.
+pub fn verify_pack(
+    pack: &ContractPack,
+    trusted_keys: &BTreeMap<String, VerifyingKey>,
+) -> Result<()> {
+    let mut concat = String::new();
+    for entry in &pack.files {
+        let blob_b64 = pack
+            .blobs
+            .get(&entry.path)
+            .ok_or_else(|| anyhow!("pack missing blob for {:?}", entry.path))?;
+        let bytes = B64
+            .decode(blob_b64)
+            .map_err(|e| anyhow!("bad base64 for {:?}: {e}", entry.path))?;
+        let hash = blake3::hash(&bytes).to_hex().to_string();
+        if hash != entry.blake3 {
+            return Err(anyhow!(
+                "blake3 mismatch for {:?}: manifest={} actual={}",
+                entry.path,
+                entry.blake3,
+                hash
+            ));
+        }
+        concat.push_str(&entry.blake3);
+    }
+
+    let canon_hash = blake3::hash(concat.as_bytes()).to_hex().to_string();
+    if canon_hash != pack.canon_hash {
+        return Err(anyhow!(
+            "canon_hash mismatch: manifest={} actual={}",
+            pack.canon_hash,
+            canon_hash
+        ));
+    }
+
+    let key_id = pack
+        .signing_key_id
+        .as_deref()
+        .ok_or_else(|| anyhow!("pack has no signing_key_id"))?;
+    let sig_b64 = pack
+        .signature
+        .as_deref()
+        .ok_or_else(|| anyhow!("pack signed by {key_id:?} but has no signature"))?;
+    let verifying_key = trusted_keys
+        .get(key_id)
+        .ok_or_else(|| anyhow!("signing_key_id {key_id:?} is not a trusted key"))?;
+
+    let sig_bytes = B64
+        .decode(sig_b64)
+        .map_err(|e| anyhow!("bad base64 signature: {e}"))?;
+    let sig_bytes: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| anyhow!("signature must be 64 bytes"))?;
+    let signature = Signature::from_bytes(&sig_bytes);
+
+    let mut unsigned = pack.clone();
+    unsigned.signature = None;
+    let msg = serde_json::to_vec(&unsigned).map_err(|e| anyhow!("re-serializing pack: {e}"))?;
+
+    verifying_key
+        .verify(&msg, &signature)
+        .map_err(|e| anyhow!("signature verification failed: {e}"))
+}
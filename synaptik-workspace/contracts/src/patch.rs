@@ -1,18 +1,41 @@
+use crate::normalize::{folded_with_spans, normalized_with_spans};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Replacement token left in place of a masked span.
+pub const MASK_TOKEN: &str = "[masked]";
+
 /// Minimal patch operations supported by the runtime and store.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "op", rename_all = "snake_case")]
 pub enum PatchOp {
-    /// Case-insensitive text masking; pattern semantics are implementation-defined
-    /// (typically a literal substring or simple glob) and applied by the consumer.
+    /// Case-insensitive literal-substring masking, obfuscation-aware (see [`apply_masks`]).
     MaskText { pattern: String },
 
+    /// Case-insensitive regex masking, obfuscation-aware (see [`apply_regex_masks`]).
+    /// An invalid pattern is logged and skipped rather than panicking.
+    MaskRegex { pattern: String },
+
     /// Swap a named artifact for an alternate CID (precomputed redaction/blur/etc.).
     SwapArtifact { name: String, cid: String },
 }
 
+impl PatchOp {
+    /// Apply this op to `text`, if it's a text-affecting op. `SwapArtifact`
+    /// doesn't touch text and is returned unchanged; callers resolve it
+    /// against `alt_artifacts` separately.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            PatchOp::MaskText { pattern } => apply_masks(text, std::slice::from_ref(pattern)),
+            PatchOp::MaskRegex { pattern } => {
+                apply_regex_masks(text, std::slice::from_ref(pattern))
+            }
+            PatchOp::SwapArtifact { .. } => text.to_string(),
+        }
+    }
+}
+
 /// A patch plan that may include text masks and/or alternate artifacts.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PatchPlan {
@@ -24,3 +47,170 @@ pub struct PatchPlan {
     pub alt_artifacts: HashMap<String, String>,
 }
 
+impl PatchPlan {
+    /// Apply every `MaskText` op in this plan to `text`, left to right.
+    pub fn apply_text(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for op in &self.ops {
+            out = op.apply(&out);
+        }
+        out
+    }
+}
+
+/// Case-insensitive, obfuscation-aware masking of literal `patterns` in
+/// `input`: matching runs over [`crate::normalize::folded_with_spans`]-folded
+/// text, so a pattern split by zero-width characters, wrapped in ANSI
+/// escapes, spaced out ("k i l l"), doubled up ("kkiillll"), or substituted
+/// with a confusable/leet look-alike ("k1ll", Cyrillic "ѕecret") still
+/// matches, and masked spans map back to the original bytes so no unmasked
+/// fragment of a match can leak.
+pub fn apply_masks(input: &str, patterns: &[String]) -> String {
+    apply_masks_with_spans(input, patterns).0
+}
+
+/// As [`apply_masks`], but also returns the original-byte-span ranges that
+/// were replaced, in ascending order, after merging any overlapping matches.
+pub fn apply_masks_with_spans(input: &str, patterns: &[String]) -> (String, Vec<(usize, usize)>) {
+    let mut out = input.to_string();
+    let mut all_replaced: Vec<(usize, usize)> = Vec::new();
+
+    for pat in patterns {
+        if pat.is_empty() {
+            continue;
+        }
+        let pat_chars: Vec<char> = crate::normalize::fold_for_matching(pat).chars().collect();
+        if pat_chars.is_empty() {
+            continue;
+        }
+
+        // Recompute the folded view of `out` so earlier replacements in this
+        // loop are visible to later patterns.
+        let (norm_chars, spans) = folded_with_spans(&out);
+        if norm_chars.is_empty() || pat_chars.len() > norm_chars.len() {
+            continue;
+        }
+
+        let plen = pat_chars.len();
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        let mut i = 0usize;
+        while i + plen <= norm_chars.len() {
+            let matches = (0..plen).all(|j| norm_chars[i + j] == pat_chars[j]);
+            if matches {
+                let (s, _) = spans[i];
+                let (_, e) = spans[i + plen - 1];
+                ranges.push((s, e));
+                // Advance by 1 to allow overlapping matches (e.g. "aa" in "aaa").
+                i += 1;
+            } else {
+                i += 1;
+            }
+        }
+        if ranges.is_empty() {
+            continue;
+        }
+        all_replaced.extend(merge_and_mask(&mut out, ranges));
+    }
+
+    all_replaced.sort_by_key(|r| r.0);
+    (out, all_replaced)
+}
+
+/// As [`apply_masks`], but `patterns` are regular expressions compiled via
+/// the `regex` crate and matched against the normalized char stream, so
+/// `p\u{200b}assword` still matches `pass?word`. An invalid pattern is
+/// logged and skipped rather than aborting the whole plan.
+pub fn apply_regex_masks(input: &str, patterns: &[String]) -> String {
+    apply_regex_masks_with_spans(input, patterns).0
+}
+
+/// As [`apply_regex_masks`], but also returns the original-byte-span ranges
+/// that were replaced, in ascending order, after merging any overlapping
+/// matches.
+pub fn apply_regex_masks_with_spans(
+    input: &str,
+    patterns: &[String],
+) -> (String, Vec<(usize, usize)>) {
+    let mut out = input.to_string();
+    let mut all_replaced: Vec<(usize, usize)> = Vec::new();
+
+    for pat in patterns {
+        if pat.is_empty() {
+            continue;
+        }
+        let re = match Regex::new(pat) {
+            Ok(re) => re,
+            Err(e) => {
+                eprintln!("[patch] invalid mask regex {pat:?}: {e}");
+                continue;
+            }
+        };
+
+        let (norm_chars, spans) = normalized_with_spans(&out);
+        if norm_chars.is_empty() {
+            continue;
+        }
+        let normalized: String = norm_chars.iter().collect();
+
+        // Byte offset of each normalized char, plus a trailing sentinel for
+        // the end of the string, so a regex match's byte range (guaranteed to
+        // fall on char boundaries) can be mapped back to `spans` indices.
+        let mut offsets: Vec<usize> = Vec::with_capacity(norm_chars.len() + 1);
+        let mut b = 0usize;
+        for ch in &norm_chars {
+            offsets.push(b);
+            b += ch.len_utf8();
+        }
+        offsets.push(b);
+
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for m in re.find_iter(&normalized) {
+            let start_idx = match offsets.binary_search(&m.start()) {
+                Ok(i) => i,
+                Err(_) => continue,
+            };
+            let end_idx = match offsets.binary_search(&m.end()) {
+                Ok(i) => i,
+                Err(_) => continue,
+            };
+            if end_idx <= start_idx {
+                continue; // empty match
+            }
+            let (s, _) = spans[start_idx];
+            let (_, e) = spans[end_idx - 1];
+            ranges.push((s, e));
+        }
+        if ranges.is_empty() {
+            continue;
+        }
+        all_replaced.extend(merge_and_mask(&mut out, ranges));
+    }
+
+    all_replaced.sort_by_key(|r| r.0);
+    (out, all_replaced)
+}
+
+/// Sort `ranges`, merge any that overlap or touch, replace each merged span
+/// with [`MASK_TOKEN`] right-to-left (so earlier byte offsets in `out` stay
+/// valid), and return the merged ranges that were actually replaced.
+fn merge_and_mask(out: &mut String, mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_by_key(|r| r.0);
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (s, e) in ranges {
+        if let Some(last) = merged.last_mut() {
+            if s <= last.1 {
+                last.1 = last.1.max(e);
+                continue;
+            }
+        }
+        merged.push((s, e));
+    }
+    for (s, e) in merged.iter().copied().rev() {
+        if s >= e || e > out.len() {
+            continue;
+        }
+        out.replace_range(s..e, MASK_TOKEN);
+    }
+    merged
+}
+
@@ -0,0 +1,50 @@
+//! Collects [`ContractMetadata`] for registered [`Contract`] impls so
+//! callers can ask "which contract emits label X" or dump the full schema,
+//! instead of treating labels like `"success"`/`"patched"` (see
+//! `synaptik-core`'s `reward_from_annotation`) as magic strings.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+
+use crate::api::{Contract, ContractMetadata};
+
+#[derive(Default)]
+pub struct ContractRegistry {
+    contracts: BTreeMap<&'static str, ContractMetadata>,
+}
+
+impl ContractRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a contract's metadata, keyed by `contract.name()`. Later
+    /// registrations for the same name overwrite earlier ones.
+    pub fn register(&mut self, contract: &dyn Contract) {
+        self.contracts.insert(contract.name(), contract.metadata());
+    }
+
+    /// Metadata for a registered contract by name, if any.
+    pub fn get(&self, name: &str) -> Option<&ContractMetadata> {
+        self.contracts.get(name)
+    }
+
+    /// All registered contracts, in name order.
+    pub fn all(&self) -> impl Iterator<Item = &ContractMetadata> {
+        self.contracts.values()
+    }
+
+    /// Which registered contracts may attach `label` (case-insensitive)?
+    pub fn by_label(&self, label: &str) -> Vec<&ContractMetadata> {
+        self.contracts
+            .values()
+            .filter(|m| m.labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+            .collect()
+    }
+
+    /// Dump the full schema as pretty-printed JSON, keyed by contract name.
+    pub fn schema_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.contracts).context("serialize contract registry schema")
+    }
+}
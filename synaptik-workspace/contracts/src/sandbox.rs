@@ -1,63 +1,237 @@
 #![allow(dead_code)]
 // Host-side WASM sandbox (native execution of guest contract bytecode) — EXPERIMENTAL.
-// Only compiled when the `wasm` feature is enabled on this crate (see Cargo.toml).
-// Not yet wired for production use; ABI and memory passing are placeholders.
+// Only compiled when the `wasm_host` feature is enabled on this crate (see Cargo.toml).
 //
-// Roadmap:
-// * Define guest ABI (allocate(len)->ptr; evaluate(ptr,len)->(ptr,len))
-// * Implement safe string/buffer marshalling
-// * Module validation (imports, memory limits, start fn behavior)
-// * Robust error taxonomy & logging (fuel exhaustion vs contract fault)
+// Guest ABI (implemented below):
+// * allocate(len: i32) -> i32            — guest reserves `len` bytes, returns a pointer
+// * evaluate(ptr: i32, len: i32) -> (i32, i32) — guest reads the input at (ptr,len),
+//   returns (out_ptr, out_len) for the host to read back out of guest memory.
+//
+// Roadmap still open:
 // * Fuzzing & differential tests vs native evaluator
 // * Determinism checks across platforms
-//
-// At present run_wasm_contract only demonstrates engine setup, fuel, and a stub call.
 
+use std::collections::HashSet;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
 use wasmtime::*;
 
 // Hardened defaults for contract sandboxing.
 // - Memory cap: 64 MiB (fits guidance 16–64 MiB range)
 // - ATP budget: 10M instruction-steps per invocation (guidance 5–20M)
+// - Deadline: 250ms wall-clock watchdog, same figure `services::wasm_decider`
+//   uses, so a guest that dodges fuel metering (e.g. a loop cheap enough to
+//   outrun its fuel budget before 250ms is up) is still bounded in real time.
 const WASM_MEMORY_MAX_BYTES: usize = 64 * 1024 * 1024;
 const DEFAULT_ATP_BUDGET: u64 = 10_000_000;
+const DEFAULT_DEADLINE: Duration = Duration::from_millis(250);
 
-pub fn run_wasm_contract(wasm_bytes: &[u8], input: &str) -> anyhow::Result<String> {
-    // Restrict WASM memory and CPU (ATP budgeting) to prevent abuse
-    let mut config = Config::new();
-    config.wasm_memory64(false); // forbid 64-bit linear memory
-    config.static_memory_maximum_size(WASM_MEMORY_MAX_BYTES as u64); // cap linear memory
-    config.consume_fuel(true); // enable ATP metering (fuel)
-    // Optional watchdog alternative:
-    // config.epoch_interruption(true);
-    let engine = Engine::new(&config)?;
+/// Why a guest invocation failed to produce a result. Distinguishes the
+/// sandbox's own limits (fuel exhaustion, the epoch-interruption wall-clock
+/// watchdog) from an ordinary guest-side fault (bad export, malformed
+/// output, a trap unrelated to resource limits) so callers -- and whoever
+/// reads the logs -- can tell a runaway contract from a broken one.
+#[derive(Debug)]
+pub enum SandboxError {
+    /// The guest burned through its fuel (ATP) budget before returning.
+    FuelExhausted,
+    /// The guest ran past its wall-clock deadline; the epoch-interruption
+    /// watchdog cut it off. Catches loops that don't burn fuel fast enough
+    /// to hit `FuelExhausted` first.
+    Timeout,
+    /// Anything else: bad exports, malformed I/O, a guest trap unrelated to
+    /// resource limits.
+    ContractFault(anyhow::Error),
+}
 
-    let linker = Linker::new(&engine);
+impl std::fmt::Display for SandboxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SandboxError::FuelExhausted => write!(f, "wasm contract exhausted its fuel budget"),
+            SandboxError::Timeout => write!(f, "wasm contract exceeded its wall-clock deadline"),
+            SandboxError::ContractFault(e) => write!(f, "wasm contract fault: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SandboxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SandboxError::ContractFault(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Traps raised by fuel exhaustion or epoch interruption surface through
+/// wasmtime's error chain with these markers; anything else is a guest-side
+/// fault. Mirrors `services::wasm_decider::is_timeout_error`, but classifies
+/// into a real error type instead of a single timeout/not-timeout bool, since
+/// fuel exhaustion and a wall-clock interrupt call for different remediation
+/// (tighten the ATP budget vs. the contract is simply too slow).
+fn classify_trap(e: anyhow::Error) -> SandboxError {
+    let msg = e.to_string();
+    if msg.contains("fuel") {
+        SandboxError::FuelExhausted
+    } else if msg.contains("epoch") || msg.contains("interrupt") {
+        SandboxError::Timeout
+    } else {
+        SandboxError::ContractFault(e)
+    }
+}
+
+/// Contract guests get no host functions at all: no WASI, no FS, no network,
+/// no clock. The allow-list exists so a future host capability can be added
+/// deliberately (see chunk5-3/chunk11-3) without silently widening what every
+/// existing guest can already do.
+fn allowed_imports() -> HashSet<(&'static str, &'static str)> {
+    HashSet::new()
+}
+
+/// Reject the module up front if it imports anything outside `allowed_imports()`,
+/// rather than letting `Linker::instantiate` fail with a generic "unknown import"
+/// trap deep in instantiation. Also rejects "dead" imports: entries the guest
+/// declares but that resolve to nothing the linker could ever satisfy, which is
+/// every import given an empty allow-list — so this doubles as the dead-import
+/// check until a host capability is actually allow-listed.
+fn validate_imports(module: &Module) -> Result<()> {
+    let allowed = allowed_imports();
+    for import in module.imports() {
+        let key = (import.module(), import.name());
+        if !allowed.contains(&key) {
+            return Err(anyhow!(
+                "rejected wasm contract: disallowed import {}::{} (no host capability is allow-listed)",
+                key.0,
+                key.1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Run `wasm_bytes` against `input` with the default fuel budget and deadline.
+pub fn run_wasm_contract(wasm_bytes: &[u8], input: &str) -> Result<String, SandboxError> {
+    run_wasm_contract_with_deadline(wasm_bytes, input, DEFAULT_DEADLINE)
+}
+
+/// Same as [`run_wasm_contract`] but with an explicit wall-clock `deadline`,
+/// so callers (and tests) can tighten or loosen the epoch-interruption
+/// watchdog without touching the fuel budget.
+pub fn run_wasm_contract_with_deadline(
+    wasm_bytes: &[u8],
+    input: &str,
+    deadline: Duration,
+) -> Result<String, SandboxError> {
+    let (engine, module) = (|| -> Result<(Engine, Module)> {
+        // Restrict WASM memory and CPU (ATP budgeting) to prevent abuse.
+        let mut config = Config::new();
+        config.wasm_memory64(false); // forbid 64-bit linear memory
+        config.static_memory_maximum_size(WASM_MEMORY_MAX_BYTES as u64); // cap linear memory
+        config.consume_fuel(true); // enable ATP metering (fuel)
+        config.epoch_interruption(true); // wall-clock watchdog backstop, see below
+        let engine = Engine::new(&config)?;
+
+        let module = Module::new(&engine, wasm_bytes)?;
+        validate_imports(&module)?;
+        Ok((engine, module))
+    })()
+    .map_err(SandboxError::ContractFault)?;
 
-    // No WASI imports — contract can't touch FS/network/clock
-    let module = Module::new(&engine, wasm_bytes)?;
+    let linker = Linker::new(&engine);
     let mut store = Store::new(&engine, ());
     // Allocate per-invocation ATP budget (fuel). Traps cleanly when exhausted.
-    store.add_fuel(DEFAULT_ATP_BUDGET)?;
+    store
+        .add_fuel(DEFAULT_ATP_BUDGET)
+        .map_err(SandboxError::ContractFault)?;
+    store.set_epoch_deadline(1);
 
-    // Instantiate module without giving any host functions
-    let instance = linker.instantiate(&mut store, &module)?;
+    // Watchdog: bump the engine's epoch after `deadline` so a looping or
+    // stalled guest is interrupted deterministically even if it never burns
+    // through its fuel budget (e.g. a tight loop whose body is cheap enough,
+    // relative to the fuel budget, to run past `deadline` first). Waits on a
+    // condvar rather than unconditionally sleeping the full `deadline`, so
+    // the common case -- a guest that returns in microseconds -- doesn't
+    // turn into a `deadline`-long call just to join a thread that was always
+    // going to find nothing to interrupt.
+    let watchdog_engine = engine.clone();
+    let done = Arc::new((Mutex::new(false), Condvar::new()));
+    let done_watchdog = done.clone();
+    let watchdog = std::thread::spawn(move || {
+        let (lock, cvar) = &*done_watchdog;
+        let guard = lock.lock().unwrap();
+        let (_guard, timeout) = cvar.wait_timeout_while(guard, deadline, |done| !*done).unwrap();
+        if timeout.timed_out() {
+            watchdog_engine.increment_epoch();
+        }
+    });
 
-    // Call exported evaluate function
-    let evaluate = instance
-        .get_func(&mut store, "evaluate")
-        .ok_or_else(|| anyhow::anyhow!("missing exported function 'evaluate'"))?;
+    let result = run_guest(&linker, &mut store, &module, input);
 
-    // Attempt zero-arity first
-    if let Ok(f0) = evaluate.typed::<(), ()>(&store) {
-        f0.call(&mut store, ())?;
-        return Ok("wasm_sandbox_stub".into());
+    {
+        let (lock, cvar) = &*done;
+        *lock.lock().unwrap() = true;
+        cvar.notify_one();
     }
-    // Attempt (i32,i32)->i32 legacy style
-    if let Ok(f_legacy) = evaluate.typed::<(i32, i32), i32>(&store) {
-        let _ = f_legacy.call(&mut store, (0, input.len() as i32))?;
-        return Ok("wasm_sandbox_stub".into());
+    let _ = watchdog.join();
+
+    result
+}
+
+fn run_guest(
+    linker: &Linker<()>,
+    store: &mut Store<()>,
+    module: &Module,
+    input: &str,
+) -> Result<String, SandboxError> {
+    let instance = linker
+        .instantiate(&mut *store, module)
+        .map_err(classify_trap)?;
+
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| {
+            SandboxError::ContractFault(anyhow!("guest did not export linear memory 'memory'"))
+        })?;
+
+    let allocate = instance
+        .get_typed_func::<i32, i32>(&mut *store, "allocate")
+        .map_err(|e| {
+            SandboxError::ContractFault(
+                e.context("missing exported function 'allocate(len: i32) -> i32'"),
+            )
+        })?;
+    let evaluate = instance
+        .get_typed_func::<(i32, i32), (i32, i32)>(&mut *store, "evaluate")
+        .map_err(|e| {
+            SandboxError::ContractFault(
+                e.context("missing exported function 'evaluate(ptr: i32, len: i32) -> (i32, i32)'"),
+            )
+        })?;
+
+    let input_bytes = input.as_bytes();
+    let in_ptr = allocate
+        .call(&mut *store, input_bytes.len() as i32)
+        .map_err(classify_trap)?;
+    memory
+        .write(&mut *store, in_ptr as usize, input_bytes)
+        .map_err(|e| SandboxError::ContractFault(anyhow!("writing input into guest memory: {e}")))?;
+
+    let (out_ptr, out_len) = evaluate
+        .call(&mut *store, (in_ptr, input_bytes.len() as i32))
+        .map_err(classify_trap)?;
+    if out_len < 0 {
+        return Err(SandboxError::ContractFault(anyhow!(
+            "guest returned negative output length: {out_len}"
+        )));
     }
-    Err(anyhow::anyhow!(
-        "unsupported 'evaluate' signature (expected () or (i32,i32)->i32)"
-    ))
+
+    let mut out_bytes = vec![0u8; out_len as usize];
+    memory
+        .read(&*store, out_ptr as usize, &mut out_bytes)
+        .map_err(|e| SandboxError::ContractFault(anyhow!("reading output from guest memory: {e}")))?;
+
+    String::from_utf8(out_bytes)
+        .map_err(|e| SandboxError::ContractFault(anyhow!("guest output was not valid UTF-8: {e}")))
 }
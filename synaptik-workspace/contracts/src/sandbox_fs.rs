@@ -0,0 +1,236 @@
+//! TOCTOU-safe directory handle for [`crate::assets`]'s verified-contract
+//! reads/writes.
+//!
+//! `contracts` sits below `synaptik-core` in the dependency graph (core
+//! reexports and calls into this crate, not the other way around), so it
+//! can't reach `synaptik-core`'s `utils::path::Sandbox` -- this is a small,
+//! self-contained copy of the same idea, scoped to exactly what
+//! `write_default_contracts`/`read_verified_or_embedded` need: open a
+//! single flat file by name under an already-open root directory handle,
+//! refusing to follow a symlink, rather than canonicalizing a `PathBuf`
+//! and handing it back for the caller to open later.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+pub struct Sandbox {
+    inner: imp::SandboxImpl,
+}
+
+impl Sandbox {
+    /// Open a sandbox rooted at `root_abs`, which must already exist.
+    pub fn open(root_abs: &Path) -> Result<Self> {
+        Ok(Self {
+            inner: imp::SandboxImpl::open(root_abs)?,
+        })
+    }
+
+    /// Open `leaf` (a single file name directly under the root) for
+    /// reading, refusing to follow it if it's a symlink.
+    pub fn open_read(&self, leaf: &Path) -> Result<std::fs::File> {
+        self.inner.open_read(leaf)
+    }
+
+    /// Open `leaf` for writing, creating/truncating it and refusing to
+    /// follow it if it's already a symlink.
+    pub fn create_write(&self, leaf: &Path) -> Result<std::fs::File> {
+        self.inner.create_write(leaf)
+    }
+}
+
+fn only_file_name(leaf: &Path) -> Result<&std::ffi::OsStr> {
+    anyhow::ensure!(
+        leaf.parent().map_or(true, |p| p.as_os_str().is_empty()),
+        "expected a single file name, got {:?}",
+        leaf
+    );
+    leaf.file_name()
+        .ok_or_else(|| anyhow::anyhow!("expected a single file name, got {:?}", leaf))
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::{only_file_name, Result};
+    use anyhow::Context;
+    use std::ffi::{CString, OsStr};
+    use std::fs::File;
+    use std::os::raw::c_char;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+    use std::path::Path;
+
+    // O_*/AT_FDCWD numeric values are NOT shared across the unix family --
+    // Linux and macOS (let alone the other BSDs) use different bit patterns
+    // for O_NOFOLLOW/O_DIRECTORY/O_CLOEXEC. Getting this wrong doesn't fail
+    // to compile or trap at runtime: it silently hands `openat` the *wrong*
+    // flags, so on an affected platform O_NOFOLLOW is never actually
+    // requested and a symlink swapped into the path is followed instead of
+    // refused -- defeating this module's entire purpose without an error.
+    // Kept in sync with `synaptik-core`'s `utils::path` copy of this table.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    mod consts {
+        pub const AT_FDCWD: i32 = -100;
+        pub const O_RDONLY: i32 = 0o0;
+        pub const O_WRONLY: i32 = 0o1;
+        pub const O_CREAT: i32 = 0o100;
+        pub const O_TRUNC: i32 = 0o1000;
+        pub const O_DIRECTORY: i32 = 0o200_000;
+        pub const O_NOFOLLOW: i32 = 0o400_000;
+        pub const O_CLOEXEC: i32 = 0o2_000_000;
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos"
+    ))]
+    mod consts {
+        pub const AT_FDCWD: i32 = -2;
+        pub const O_RDONLY: i32 = 0x0000;
+        pub const O_WRONLY: i32 = 0x0001;
+        pub const O_CREAT: i32 = 0x0200;
+        pub const O_TRUNC: i32 = 0x0400;
+        pub const O_NOFOLLOW: i32 = 0x0100;
+        pub const O_DIRECTORY: i32 = 0x0010_0000;
+        pub const O_CLOEXEC: i32 = 0x0100_0000;
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+    mod consts {
+        pub const AT_FDCWD: i32 = -100;
+        pub const O_RDONLY: i32 = 0x0000;
+        pub const O_WRONLY: i32 = 0x0001;
+        pub const O_CREAT: i32 = 0x0200;
+        pub const O_TRUNC: i32 = 0x0400;
+        pub const O_NOFOLLOW: i32 = 0x0100;
+        pub const O_DIRECTORY: i32 = 0x0002_0000;
+        pub const O_CLOEXEC: i32 = 0x0010_0000;
+    }
+
+    #[cfg(target_os = "netbsd")]
+    mod consts {
+        pub const AT_FDCWD: i32 = -100;
+        pub const O_RDONLY: i32 = 0x0000;
+        pub const O_WRONLY: i32 = 0x0001;
+        pub const O_CREAT: i32 = 0x0200;
+        pub const O_TRUNC: i32 = 0x0400;
+        pub const O_NOFOLLOW: i32 = 0x0100;
+        pub const O_DIRECTORY: i32 = 0x0020_0000;
+        pub const O_CLOEXEC: i32 = 0x0040_0000;
+    }
+
+    #[cfg(target_os = "openbsd")]
+    mod consts {
+        pub const AT_FDCWD: i32 = -100;
+        pub const O_RDONLY: i32 = 0x0000;
+        pub const O_WRONLY: i32 = 0x0001;
+        pub const O_CREAT: i32 = 0x0200;
+        pub const O_TRUNC: i32 = 0x0400;
+        pub const O_NOFOLLOW: i32 = 0x0100;
+        pub const O_DIRECTORY: i32 = 0x0002_0000;
+        pub const O_CLOEXEC: i32 = 0x0001_0000;
+    }
+
+    use consts::{AT_FDCWD, O_CLOEXEC, O_CREAT, O_DIRECTORY, O_NOFOLLOW, O_RDONLY, O_TRUNC, O_WRONLY};
+
+    extern "C" {
+        fn openat(dirfd: i32, pathname: *const c_char, flags: i32, mode: u32) -> i32;
+    }
+
+    fn to_cstring(name: &OsStr) -> Result<CString> {
+        CString::new(name.as_bytes())
+            .with_context(|| format!("path component contains a NUL byte: {:?}", name))
+    }
+
+    fn openat_raw(dirfd: RawFd, name: &CString, flags: i32, mode: u32) -> std::io::Result<RawFd> {
+        let fd = unsafe { openat(dirfd, name.as_ptr(), flags, mode) };
+        if fd < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(fd)
+        }
+    }
+
+    pub struct SandboxImpl {
+        root: File,
+    }
+
+    impl SandboxImpl {
+        pub fn open(root_abs: &Path) -> Result<Self> {
+            let canon = root_abs
+                .canonicalize()
+                .with_context(|| format!("canonicalize sandbox root {:?}", root_abs))?;
+            let cname = to_cstring(canon.as_os_str())?;
+            let fd = openat_raw(AT_FDCWD, &cname, O_DIRECTORY | O_NOFOLLOW | O_CLOEXEC, 0)
+                .with_context(|| format!("open sandbox root {:?}", canon))?;
+            Ok(Self {
+                root: unsafe { File::from_raw_fd(fd) },
+            })
+        }
+
+        pub fn open_read(&self, leaf: &Path) -> Result<File> {
+            let name = only_file_name(leaf)?;
+            let cname = to_cstring(name)?;
+            let fd = openat_raw(
+                self.root.as_raw_fd(),
+                &cname,
+                O_RDONLY | O_NOFOLLOW | O_CLOEXEC,
+                0,
+            )
+            .with_context(|| format!("openat {:?} for read (symlinks are refused)", name))?;
+            Ok(unsafe { File::from_raw_fd(fd) })
+        }
+
+        pub fn create_write(&self, leaf: &Path) -> Result<File> {
+            let name = only_file_name(leaf)?;
+            let cname = to_cstring(name)?;
+            let flags = O_WRONLY | O_CREAT | O_TRUNC | O_NOFOLLOW | O_CLOEXEC;
+            let fd = openat_raw(self.root.as_raw_fd(), &cname, flags, 0o644)
+                .with_context(|| format!("openat {:?} for write (symlinks are refused)", name))?;
+            Ok(unsafe { File::from_raw_fd(fd) })
+        }
+    }
+}
+
+/// Canonicalize-then-check fallback for non-Unix targets; see
+/// `synaptik-core`'s `utils::path` module for the same tradeoff spelled
+/// out in full.
+#[cfg(not(unix))]
+mod imp {
+    use super::{only_file_name, Result};
+    use anyhow::Context;
+    use std::fs::{File, OpenOptions};
+    use std::path::{Path, PathBuf};
+
+    pub struct SandboxImpl {
+        root: PathBuf,
+    }
+
+    impl SandboxImpl {
+        pub fn open(root_abs: &Path) -> Result<Self> {
+            Ok(Self {
+                root: root_abs
+                    .canonicalize()
+                    .with_context(|| format!("canonicalize sandbox root {:?}", root_abs))?,
+            })
+        }
+
+        fn checked(&self, leaf: &Path) -> Result<PathBuf> {
+            let name = only_file_name(leaf)?;
+            Ok(self.root.join(name))
+        }
+
+        pub fn open_read(&self, leaf: &Path) -> Result<File> {
+            Ok(File::open(self.checked(leaf)?)?)
+        }
+
+        pub fn create_write(&self, leaf: &Path) -> Result<File> {
+            Ok(OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(self.checked(leaf)?)?)
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Capsule `schema_ver` negotiation and migration-on-read, so a future
+//! change to `SimCapsule`/`CapsuleMeta`'s on-disk shape doesn't silently
+//! break `ContractsStore::load_capsule` for capsules written under an older
+//! build. Mirrors `registry::ContractRegistry`'s "declare a registry, look
+//! things up by key" shape, keyed by schema version instead of contract name.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// Oldest `schema_ver` this build still knows how to migrate forward from.
+/// A capsule stamped older than this is rejected rather than guessed at.
+pub const MIN_SUPPORTED: &str = "1.0";
+
+/// `schema_ver` this build stamps on freshly-ingested/replaced capsules.
+/// Bump this whenever `SimCapsule`/`CapsuleMeta`'s shape changes, and add the
+/// matching step to [`MIGRATIONS`].
+pub const CURRENT: &str = "1.0";
+
+/// One step in the migration chain: rewrites a capsule's raw JSON from
+/// `from_ver` to `to_ver`. Steps only need to know about their immediate
+/// successor -- [`migrate_to_current`] chains them transitively.
+pub struct Migration {
+    pub from_ver: &'static str,
+    pub to_ver: &'static str,
+    pub migrate: fn(Value) -> Result<Value>,
+}
+
+/// Ordered (oldest-first) registry of migration steps. Empty today since the
+/// capsule schema hasn't changed yet; add an entry here alongside bumping
+/// `CURRENT` the first time it does.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// `(major, minor)` ordering for `"<major>.<minor>"`-style version strings,
+/// so `"1.10" > "1.9"` instead of comparing lexicographically. Unparseable
+/// components rank as `0`.
+fn version_rank(ver: &str) -> (u32, u32) {
+    let mut parts = ver.splitn(2, '.');
+    let major = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    let minor = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    (major, minor)
+}
+
+/// Walk a capsule's raw JSON from `stored_ver` to [`CURRENT`] via
+/// [`MIGRATIONS`]. Rejects capsules newer than `CURRENT` (this build can't
+/// read them) or older than [`MIN_SUPPORTED`] (no migration path kept that
+/// far back); a gap in the chain between `stored_ver` and `CURRENT` is also
+/// an error rather than a best-effort pass-through.
+pub fn migrate_to_current(stored_ver: &str, mut value: Value) -> Result<Value> {
+    if version_rank(stored_ver) > version_rank(CURRENT) {
+        return Err(anyhow!(
+            "capsule schema_ver {stored_ver} is newer than this build supports (CURRENT={CURRENT})"
+        ));
+    }
+    if version_rank(stored_ver) < version_rank(MIN_SUPPORTED) {
+        return Err(anyhow!(
+            "capsule schema_ver {stored_ver} is older than MIN_SUPPORTED={MIN_SUPPORTED}"
+        ));
+    }
+
+    let mut ver = stored_ver.to_string();
+    while ver != CURRENT {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from_ver == ver)
+            .ok_or_else(|| {
+                anyhow!("no migration registered from schema_ver {ver} toward {CURRENT}")
+            })?;
+        value = (step.migrate)(value)?;
+        ver = step.to_ver.to_string();
+    }
+    Ok(value)
+}
+
+/// Whether a capsule at `schema_ver` supports carrying a `"patched"` label
+/// alongside `"summary"` on its annotation (the compactor's masked-replacement
+/// path) -- true for every version that has existed so far, but kept as a
+/// version-keyed predicate so the compactor can branch on capability instead
+/// of assuming the latest shape once an older capsule is migrated forward.
+pub fn supports_patch_labels(schema_ver: &str) -> bool {
+    version_rank(schema_ver) >= version_rank("1.0")
+}
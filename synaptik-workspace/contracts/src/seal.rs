@@ -0,0 +1,96 @@
+//! Envelope encryption for capsule payloads at rest (XChaCha20-Poly1305).
+//!
+//! Every capsule gets its own random data key; that data key is itself
+//! wrapped (encrypted) under the store's long-lived master key and persisted
+//! alongside the capsule, the way Aerogramme seals every stored object:
+//! compromising one capsule's on-disk blob never exposes another capsule's
+//! data key, and rotating the master key only means re-wrapping data keys,
+//! not re-encrypting every payload.
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine as _;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedValue {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// A freshly-generated per-capsule data key, plus that key wrapped under the
+/// store's master key — the only form of it that ever reaches disk.
+pub struct DataKey {
+    pub plaintext: [u8; 32],
+    pub wrapped_b64: String,
+    pub wrap_nonce_b64: String,
+}
+
+/// Generate a random per-capsule data key and wrap it under `master_key`.
+pub fn generate_data_key(master_key: &[u8; 32]) -> Result<DataKey> {
+    let plaintext: [u8; 32] = XChaCha20Poly1305::generate_key(&mut OsRng).into();
+    let cipher = XChaCha20Poly1305::new_from_slice(master_key).map_err(|e| anyhow!("bad master key: {e}"))?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let wrapped = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|e| anyhow!("wrap data key: {e}"))?;
+    Ok(DataKey {
+        plaintext,
+        wrapped_b64: B64.encode(wrapped),
+        wrap_nonce_b64: B64.encode(nonce),
+    })
+}
+
+/// Reverse of [`generate_data_key`]'s wrapping step.
+pub fn unwrap_data_key(master_key: &[u8; 32], wrapped_b64: &str, nonce_b64: &str) -> Result<[u8; 32]> {
+    let cipher = XChaCha20Poly1305::new_from_slice(master_key).map_err(|e| anyhow!("bad master key: {e}"))?;
+    let nonce_bytes = B64.decode(nonce_b64).context("bad base64 wrap nonce")?;
+    let nonce = XNonce::from_slice(&nonce_bytes);
+    let wrapped = B64.decode(wrapped_b64).context("bad base64 wrapped data key")?;
+    let plaintext = cipher
+        .decrypt(nonce, wrapped.as_slice())
+        .map_err(|e| anyhow!("unwrap data key (wrong master key?): {e}"))?;
+    plaintext
+        .try_into()
+        .map_err(|_| anyhow!("unwrapped data key has the wrong length"))
+}
+
+/// Seal a JSON value under `data_key`, returning a JSON object that
+/// [`unseal_value`] can invert. The ciphertext embeds its own nonce, so
+/// sealing the same value twice yields different (but equally valid) output.
+pub fn seal_value(data_key: &[u8; 32], plaintext: &Value) -> Result<Value> {
+    let cipher = XChaCha20Poly1305::new_from_slice(data_key).map_err(|e| anyhow!("bad data key: {e}"))?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let bytes = serde_json::to_vec(plaintext).context("serialize value to seal")?;
+    let ciphertext = cipher
+        .encrypt(&nonce, bytes.as_slice())
+        .map_err(|e| anyhow!("seal value: {e}"))?;
+    Ok(serde_json::json!({
+        "__sealed__": SealedValue {
+            nonce: B64.encode(nonce),
+            ciphertext: B64.encode(ciphertext),
+        }
+    }))
+}
+
+/// Unseal a value produced by [`seal_value`]. A value that was never sealed
+/// (no `__sealed__` wrapper) is returned unchanged, so callers can use this
+/// uniformly regardless of whether the store had a master key at ingest time.
+pub fn unseal_value(data_key: &[u8; 32], value: &Value) -> Result<Value> {
+    let Some(sealed) = value.get("__sealed__") else {
+        return Ok(value.clone());
+    };
+    let sealed: SealedValue =
+        serde_json::from_value(sealed.clone()).context("parse sealed payload envelope")?;
+    let cipher = XChaCha20Poly1305::new_from_slice(data_key).map_err(|e| anyhow!("bad data key: {e}"))?;
+    let nonce_bytes = B64.decode(&sealed.nonce).context("bad base64 seal nonce")?;
+    let nonce = XNonce::from_slice(&nonce_bytes);
+    let ciphertext = B64.decode(&sealed.ciphertext).context("bad base64 ciphertext")?;
+    let bytes = cipher
+        .decrypt(nonce, ciphertext.as_slice())
+        .map_err(|e| anyhow!("unseal value (wrong data key or tampered ciphertext): {e}"))?;
+    serde_json::from_slice(&bytes).context("parse unsealed value json")
+}
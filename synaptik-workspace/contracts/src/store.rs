@@ -1,10 +1,14 @@
 use crate::api::{uuidv7, CapsAnnot, CapsId, Denied, Purpose, Verdict};
+use crate::backend::{CapsuleBackend, FsBackend};
 use crate::capsule::SimCapsule;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as B64;
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::sync::Arc;
 
 /// Handle returned from ingest
 #[derive(Debug, Clone)]
@@ -13,28 +17,65 @@ pub struct CapsHandle {
     pub hash: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ContractsStore {
-    root: PathBuf,
+    backend: Arc<dyn CapsuleBackend>,
+    /// Optional issuer key: when set, every ingested capsule is signed over
+    /// its own `capsule_hash` (blake3 content hash) so a downstream verifier
+    /// can later confirm which issuer vouched for it.
+    issuer_key: Option<Arc<SigningKey>>,
+    /// Optional master key: when set, every ingested capsule's `inputs`/
+    /// `outputs` are sealed at rest (see [`crate::seal`]). Absent, capsules
+    /// persist as plaintext JSON — today's behavior, preserved for callers
+    /// that never opt in.
+    master_key: Option<Arc<[u8; 32]>>,
+}
+
+impl std::fmt::Debug for ContractsStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContractsStore")
+            .field("issuer_key", &self.issuer_key.as_ref().map(|_| "<redacted>"))
+            .field("master_key", &self.master_key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
 }
 
 impl ContractsStore {
+    /// Local-disk store rooted at `root`, matching this crate's original
+    /// (pre-[`CapsuleBackend`]) on-disk layout exactly. Use
+    /// [`ContractsStore::new_with_backend`] to target S3/Garage instead.
     pub fn new<P: AsRef<Path>>(root: P) -> Result<Self> {
-        let root = root.as_ref().to_path_buf();
-        fs::create_dir_all(root.join("capsules"))?;
-        fs::create_dir_all(root.join("annotations"))?;
-        fs::create_dir_all(root.join("handles"))?;
-        Ok(Self { root })
+        Self::new_with_backend(Arc::new(FsBackend::new(root)?))
     }
 
-    fn capsules_dir(&self) -> PathBuf {
-        self.root.join("capsules")
+    /// Build a store against any [`CapsuleBackend`] — local disk
+    /// ([`FsBackend`]), an S3-compatible bucket (`S3Backend`, behind the
+    /// `s3_backend` feature), or a test double.
+    pub fn new_with_backend(backend: Arc<dyn CapsuleBackend>) -> Result<Self> {
+        Ok(Self {
+            backend,
+            issuer_key: None,
+            master_key: None,
+        })
     }
-    fn ann_dir(&self) -> PathBuf {
-        self.root.join("annotations")
+
+    /// Same as [`ContractsStore::new`], but every capsule ingested afterward
+    /// is signed with `issuer_key` (see [`ContractsStore::ingest_capsule`]).
+    pub fn new_with_issuer<P: AsRef<Path>>(root: P, issuer_key: SigningKey) -> Result<Self> {
+        let mut store = Self::new(root)?;
+        store.issuer_key = Some(Arc::new(issuer_key));
+        Ok(store)
     }
-    fn handle_dir(&self) -> PathBuf {
-        self.root.join("handles")
+
+    /// Layer envelope encryption for capsule payloads on top of any store
+    /// variant: afterward, [`ContractsStore::ingest_capsule`] seals every
+    /// capsule's `inputs`/`outputs` under a fresh per-capsule data key
+    /// wrapped by `master_key`, and [`ContractsStore::load_capsule`]
+    /// transparently unseals them. Composes with [`ContractsStore::new_with_issuer`]:
+    /// `ContractsStore::new_with_issuer(dir, key)?.with_master_key(master)`.
+    pub fn with_master_key(mut self, master_key: [u8; 32]) -> Self {
+        self.master_key = Some(Arc::new(master_key));
+        self
     }
 
     /// Ingest a capsule: assign id if absent, compute canonical hash, and persist JSON.
@@ -54,44 +95,307 @@ impl ContractsStore {
         let hash = canonical_hash(&v);
         cap.meta.capsule_hash = Some(hash.clone());
 
-        // Persist capsule JSON pretty-printed for auditability
-        let path = self.capsules_dir().join(format!("{}.json", sanitize(&id)));
-        write_atomic(&path, &serde_json::to_vec_pretty(&cap)?)?;
+        if let Some(issuer_key) = &self.issuer_key {
+            let sig = issuer_key.sign(hash.as_bytes());
+            cap.meta.issuer_signature = Some(B64.encode(sig.to_bytes()));
+        }
 
-        // Optional dev auto-allow (scoped) — controlled via env vars.
+        // Optional dev auto-allow (scoped) — controlled via env vars. Must see
+        // plaintext outputs (its size cap is measured pre-seal), so this runs
+        // before the sealing step below.
         maybe_dev_auto_allow(self, &cap, &id)?;
 
+        // Seal inputs/outputs at rest, if this store has a master key. Done
+        // after the hash/signature above so both are computed over plaintext.
+        if let Some(master_key) = &self.master_key {
+            let data_key = crate::seal::generate_data_key(master_key)?;
+            cap.inputs = crate::seal::seal_value(&data_key.plaintext, &cap.inputs)?;
+            cap.outputs = crate::seal::seal_value(&data_key.plaintext, &cap.outputs)?;
+            cap.meta.sealed_data_key = Some(data_key.wrapped_b64);
+            cap.meta.sealed_key_nonce = Some(data_key.wrap_nonce_b64);
+        }
+
+        // Persist capsule JSON pretty-printed for auditability
+        self.backend
+            .put_atomic(&capsule_key(&id), &serde_json::to_vec_pretty(&cap)?)?;
+
         Ok(CapsHandle { id, hash })
     }
 
-    /// Append an annotation entry for a capsule (JSONL per capsule id) and write latest.json.
+    /// Append an annotation entry for a capsule (JSONL per capsule id, hash-chained —
+    /// see [`ChainedAnnotation`]) and write latest.json. The entry is recorded as
+    /// already committed; use [`ContractsStore::annotate_tentative`] for a
+    /// Bayou-style speculative write that can be committed (or left pending) later.
     pub fn annotate(&self, id: &CapsId, annot: &CapsAnnot) -> Result<()> {
-        let dir = self.ann_dir();
-        let file = dir.join(format!("{}.jsonl", sanitize(id)));
-        // append line
-        let line = serde_json::to_vec(annot)?;
-        append_line(&file, &line)?;
-
-        // also write latest.json (overwrite)
-        let latest = dir.join(format!("{}.latest.json", sanitize(id)));
-        write_atomic(&latest, &serde_json::to_vec_pretty(annot)?)?;
+        self.append_chain_entry(id, annot, true)?;
+        self.backend
+            .put_atomic(&latest_key(id), &serde_json::to_vec_pretty(annot)?)?;
+        Ok(())
+    }
+
+    /// Append a tentative annotation: recorded in the hash-chained log like
+    /// [`ContractsStore::annotate`], but NOT reflected in `latest.json` until
+    /// [`ContractsStore::commit_tentative`] confirms it — mirrors Bayou's
+    /// tentative writes, which are ordered speculatively and only become part
+    /// of the stable state once committed. Returns the entry's sequence number.
+    pub fn annotate_tentative(&self, id: &CapsId, annot: &CapsAnnot) -> Result<u64> {
+        self.append_chain_entry(id, annot, false)
+    }
+
+    /// Commit a previously-tentative entry: appends a new chained entry
+    /// carrying the same annotation content marked `committed`, and — if it is
+    /// the newest entry in the log — promotes it to `latest.json`.
+    pub fn commit_tentative(&self, id: &CapsId, seq: u64) -> Result<()> {
+        let key = chain_key(id);
+        let bytes = self
+            .backend
+            .get(&key)?
+            .with_context(|| format!("no annotation log for {id}"))?;
+        let contents = String::from_utf8(bytes).context("annotation log is not utf8")?;
+        let target = contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str::<ChainedAnnotation>(l).context("parse chain entry"))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .find(|e| e.seq == seq)
+            .ok_or_else(|| anyhow::anyhow!("no annotation entry with seq {seq} for {id}"))?;
+
+        let is_newest = last_chained_entry(self.backend.as_ref(), id)?
+            .map(|last| last.seq == seq)
+            .unwrap_or(false);
+
+        self.append_chain_entry(id, &target.annot, true)?;
+        if is_newest {
+            self.backend
+                .put_atomic(&latest_key(id), &serde_json::to_vec_pretty(&target.annot)?)?;
+        }
+        Ok(())
+    }
+
+    /// Bayou-style checkpoint: once every entry currently in the log is
+    /// committed (no tentative writes outstanding), fold the whole chain into
+    /// a single [`AnnotationCheckpoint`] and truncate the log — bounding its
+    /// growth instead of replaying the full history on every verification.
+    /// Errors (without truncating anything) if a tentative entry is pending.
+    pub fn checkpoint_annotations(&self, id: &CapsId) -> Result<()> {
+        let key = chain_key(id);
+        let Some(bytes) = self.backend.get(&key)? else {
+            return Ok(());
+        };
+        let contents = String::from_utf8(bytes).context("annotation log is not utf8")?;
+        let mut last: Option<ChainedAnnotation> = None;
+        for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+            let entry: ChainedAnnotation = serde_json::from_str(line).context("parse chain entry")?;
+            last = Some(entry);
+        }
+        let Some(last) = last else {
+            return Ok(()); // nothing to fold
+        };
+        if !last.committed {
+            return Err(anyhow::anyhow!(
+                "cannot checkpoint {id}: newest entry (seq {}) is still tentative",
+                last.seq
+            ));
+        }
+
+        let checkpoint = AnnotationCheckpoint {
+            through_seq: last.seq,
+            chain_hash: last.entry_hash,
+            state: last.annot,
+        };
+        self.backend
+            .put_atomic(&checkpoint_key(id), &serde_json::to_vec_pretty(&checkpoint)?)?;
+        self.backend
+            .remove(&key)
+            .context("truncate annotation chain after checkpoint")?;
         Ok(())
     }
 
-    /// Read the latest annotation if available.
+    /// Concurrency-safe annotation write for multiple annotators racing on the
+    /// same capsule (e.g. two contract evaluators running on different hosts).
+    /// `writer_id` identifies the caller in the vector clock; the returned
+    /// clock should be passed back on the writer's next call so its causal
+    /// history is preserved. Unlike [`ContractsStore::annotate`] (a single
+    /// linear hash chain), concurrent writes that can't be causally ordered
+    /// are kept as siblings rather than one silently clobbering the other —
+    /// see [`ContractsStore::annotation_heads`] and [`resolve_siblings`].
+    pub fn annotate_concurrent(
+        &self,
+        id: &CapsId,
+        annot: &CapsAnnot,
+        writer_id: &str,
+    ) -> Result<VectorClock> {
+        let mut heads = self.read_heads(id)?;
+
+        let mut clock = VectorClock::default();
+        for (existing_clock, _) in &heads {
+            clock.merge(existing_clock);
+        }
+        clock.increment(writer_id);
+
+        // Drop any existing head now causally dominated by the new write;
+        // anything left over (Concurrent or Equal-but-different) survives as a sibling.
+        heads.retain(|(existing_clock, _)| !existing_clock.happens_before(&clock));
+        heads.push((clock.clone(), annot.clone()));
+
+        self.write_heads(id, &heads)?;
+        Ok(clock)
+    }
+
+    /// Current causal heads for a capsule's concurrent annotations: a single
+    /// entry if all writers have seen each other's work, or multiple entries
+    /// ("siblings") if two annotations were produced concurrently. Feed
+    /// siblings through [`resolve_siblings`] to get one answer back.
+    pub fn annotation_heads(&self, id: &CapsId) -> Result<Vec<CapsAnnot>> {
+        Ok(self
+            .read_heads(id)?
+            .into_iter()
+            .map(|(_, annot)| annot)
+            .collect())
+    }
+
+    fn read_heads(&self, id: &CapsId) -> Result<Vec<(VectorClock, CapsAnnot)>> {
+        let Some(bytes) = self.backend.get(&heads_key(id))? else {
+            return Ok(Vec::new());
+        };
+        let heads: Vec<(VectorClock, CapsAnnot)> =
+            serde_json::from_slice(&bytes).context("parse annotation heads")?;
+        Ok(heads)
+    }
+
+    fn write_heads(&self, id: &CapsId, heads: &[(VectorClock, CapsAnnot)]) -> Result<()> {
+        self.backend
+            .put_atomic(&heads_key(id), &serde_json::to_vec_pretty(heads)?)
+    }
+
+    fn append_chain_entry(&self, id: &CapsId, annot: &CapsAnnot, committed: bool) -> Result<u64> {
+        let (seq, prev_hash) = match last_chained_entry(self.backend.as_ref(), id)? {
+            Some(prev) => (prev.seq + 1, prev.entry_hash),
+            None => match read_checkpoint(self.backend.as_ref(), id)? {
+                Some(cp) => (cp.through_seq + 1, cp.chain_hash),
+                None => (0, GENESIS_HASH.to_string()),
+            },
+        };
+        let entry_hash = chain_entry_hash(&prev_hash, seq, committed, annot)?;
+        let entry = ChainedAnnotation {
+            seq,
+            prev_hash,
+            entry_hash,
+            committed,
+            annot: annot.clone(),
+        };
+
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        self.backend.append(&chain_key(id), &line)?;
+        Ok(seq)
+    }
+
+    /// Read the latest annotation if available. When concurrent sibling
+    /// writes exist (see [`ContractsStore::annotate_concurrent`]), this
+    /// folds them via [`resolve_siblings`] instead of reading `latest.json`
+    /// -- which `annotate_concurrent` never updates -- so a deny recorded as
+    /// one of several siblings is never silently shadowed by a concurrent
+    /// allow.
     pub fn latest_annotation(&self, id: &CapsId) -> Result<Option<CapsAnnot>> {
-        let latest = self.ann_dir().join(format!("{}.latest.json", sanitize(id)));
-        if !latest.exists() {
-            return Ok(None);
+        let heads = self.read_heads(id)?;
+        if !heads.is_empty() {
+            let annots: Vec<CapsAnnot> = heads.into_iter().map(|(_, annot)| annot).collect();
+            return Ok(resolve_siblings(&annots));
         }
-        let bytes = fs::read(&latest)?;
+        let Some(bytes) = self.backend.get(&latest_key(id))? else {
+            return Ok(None);
+        };
         let v: CapsAnnot = serde_json::from_slice(&bytes).context("parse latest annot")?;
         Ok(Some(v))
     }
 
+    /// Re-derive every entry's hash from `(prev_hash, seq, annot)` and confirm it
+    /// chains from the genesis hash (or the last [`ContractsStore::checkpoint_annotations`],
+    /// if any) with no gaps, reusing the exact same `chain_entry_hash` the writer
+    /// used — a mismatch means the log was edited or reordered out-of-band.
+    pub fn verify_annotation_chain(&self, id: &CapsId) -> Result<()> {
+        let (mut expected_seq, mut expected_prev) = match read_checkpoint(self.backend.as_ref(), id)? {
+            Some(cp) => (cp.through_seq + 1, cp.chain_hash),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+        let Some(bytes) = self.backend.get(&chain_key(id))? else {
+            return Ok(());
+        };
+        let contents = String::from_utf8(bytes).context("annotation log is not utf8")?;
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: ChainedAnnotation = serde_json::from_str(line)
+                .with_context(|| format!("parse annotation chain entry {i} for {id}"))?;
+            if entry.seq != expected_seq {
+                return Err(anyhow::anyhow!(
+                    "annotation chain for {id}: expected seq {expected_seq}, found {}",
+                    entry.seq
+                ));
+            }
+            if entry.prev_hash != expected_prev {
+                return Err(anyhow::anyhow!(
+                    "annotation chain for {id}: broken link at seq {expected_seq} (prev_hash mismatch)"
+                ));
+            }
+            let recomputed = chain_entry_hash(&entry.prev_hash, entry.seq, entry.committed, &entry.annot)?;
+            if recomputed != entry.entry_hash {
+                return Err(anyhow::anyhow!(
+                    "annotation chain for {id}: tampered entry at seq {expected_seq}"
+                ));
+            }
+            expected_prev = entry.entry_hash;
+            expected_seq += 1;
+        }
+        Ok(())
+    }
+
+    /// Entries appended by [`ContractsStore::annotate_tentative`] at or after
+    /// `since_seq` that have no later [`ContractsStore::commit_tentative`]
+    /// call for them yet — i.e. still pending. Built for write-ahead-log
+    /// style replay (see `synaptik-core`'s `services::commit_log::CommitLog`):
+    /// a caller persists `since_seq` as its own applied watermark and
+    /// re-fetches only what it hasn't applied.
+    pub fn tentative_since(&self, id: &CapsId, since_seq: u64) -> Result<Vec<(u64, CapsAnnot)>> {
+        let Some(bytes) = self.backend.get(&chain_key(id))? else {
+            return Ok(Vec::new());
+        };
+        let contents = String::from_utf8(bytes).context("annotation log is not utf8")?;
+        let entries: Vec<ChainedAnnotation> = contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l).context("parse chain entry"))
+            .collect::<Result<_>>()?;
+        // commit_tentative appends a fresh committed entry carrying the same
+        // annot content as the tentative one it confirms, so "applied" means
+        // some later entry repeats this one's annot with committed = true.
+        let committed: std::collections::HashSet<String> = entries
+            .iter()
+            .filter(|e| e.committed)
+            .map(|e| annot_identity(&e.annot))
+            .collect();
+        Ok(entries
+            .into_iter()
+            .filter(|e| e.seq >= since_seq && !e.committed && !committed.contains(&annot_identity(&e.annot)))
+            .map(|e| (e.seq, e.annot))
+            .collect())
+    }
+
     /// Hard gate for replay/use surfaces.
-    /// Policy: if missing annotation → deny as pending; AllowWithPatch → caller applies patch.
+    /// Policy: a tampered/broken annotation chain denies outright (quarantine);
+    /// otherwise if missing annotation → deny as pending; AllowWithPatch → caller applies patch.
     pub fn gate_replay(&self, id: &CapsId, _purpose: Purpose) -> std::result::Result<(), Denied> {
+        if let Err(e) = self.verify_annotation_chain(id) {
+            return Err(Denied {
+                reason: format!("chain_broken: {e}"),
+                verdict: Verdict::Quarantine,
+                risk: 1.0,
+                labels: vec!["chain_broken".into()],
+            });
+        }
         match self.latest_annotation(id).map_err(|e| Denied {
             reason: format!("store error: {e}"),
             verdict: Verdict::Quarantine,
@@ -140,26 +444,14 @@ impl ContractsStore {
 
     /// Optional mapping helpers (memory_id → capsule_id) for quick lookup by services.
     pub fn map_memory(&self, memory_id: &str, caps_id: &CapsId) -> Result<()> {
-        let p = self
-            .handle_dir()
-            .join("memory")
-            .join(format!("{}.txt", sanitize(memory_id)));
-        if let Some(parent) = p.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        write_atomic(&p, caps_id.as_bytes())
+        self.backend.put_atomic(&memory_key(memory_id), caps_id.as_bytes())
     }
 
     /// Load capsule id for a given memory row, if mapped.
     pub fn capsule_for_memory(&self, memory_id: &str) -> Result<Option<CapsId>> {
-        let p = self
-            .handle_dir()
-            .join("memory")
-            .join(format!("{}.txt", sanitize(memory_id)));
-        if !p.exists() {
+        let Some(bytes) = self.backend.get(&memory_key(memory_id))? else {
             return Ok(None);
-        }
-        let bytes = fs::read(&p)?;
+        };
         let s = String::from_utf8_lossy(&bytes).trim().to_string();
         if s.is_empty() {
             Ok(None)
@@ -169,51 +461,259 @@ impl ContractsStore {
     }
 
     pub fn load_capsule(&self, id: &CapsId) -> Result<Option<SimCapsule>> {
-        let p = self.capsules_dir().join(format!("{}.json", sanitize(id)));
-        if !p.exists() {
+        let Some(bytes) = self.backend.get(&capsule_key(id))? else {
             return Ok(None);
+        };
+        let raw: Value = serde_json::from_slice(&bytes).context("parse capsule json")?;
+        let stored_ver = raw
+            .get("meta")
+            .and_then(|m| m.get("schema_ver"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(crate::schema::MIN_SUPPORTED)
+            .to_string();
+        let raw = crate::schema::migrate_to_current(&stored_ver, raw)
+            .with_context(|| format!("capsule {id}: schema_ver {stored_ver}"))?;
+        let mut v: SimCapsule =
+            serde_json::from_value(raw).context("parse capsule json (post-migration)")?;
+        if let (Some(wrapped), Some(nonce)) = (&v.meta.sealed_data_key, &v.meta.sealed_key_nonce) {
+            let master_key = self
+                .master_key
+                .as_ref()
+                .ok_or_else(|| anyhow!("capsule {id} is sealed but this store has no master key"))?;
+            let data_key = crate::seal::unwrap_data_key(master_key, wrapped, nonce)?;
+            v.inputs = crate::seal::unseal_value(&data_key, &v.inputs)?;
+            v.outputs = crate::seal::unseal_value(&data_key, &v.outputs)?;
         }
-        let bytes = fs::read(&p)?;
-        let v: SimCapsule = serde_json::from_slice(&bytes).context("parse capsule json")?;
         Ok(Some(v))
     }
 }
 
-// -------------- helpers --------------
+// -------------- logical storage keys --------------
+
+fn capsule_key(id: &CapsId) -> String {
+    format!("capsules/{}.json", sanitize(id))
+}
+fn chain_key(id: &CapsId) -> String {
+    format!("annotations/{}.jsonl", sanitize(id))
+}
+fn latest_key(id: &CapsId) -> String {
+    format!("annotations/{}.latest.json", sanitize(id))
+}
+fn checkpoint_key(id: &CapsId) -> String {
+    format!("annotations/{}.checkpoint.json", sanitize(id))
+}
+fn heads_key(id: &CapsId) -> String {
+    format!("annotations/{}.heads.json", sanitize(id))
+}
+fn memory_key(memory_id: &str) -> String {
+    format!("handles/memory/{}.txt", sanitize(memory_id))
+}
 
-fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+// -------------- causal-context concurrent annotations --------------
+
+/// Per-writer logical clock (Lamport-style vector clock) used to tell whether
+/// one annotation causally saw another, or whether the two were produced
+/// concurrently and must be kept as siblings. Writer ids are caller-chosen
+/// (e.g. a host name or evaluator instance id) — there's no coordination
+/// required beyond each writer bumping its own entry before writing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorClock(std::collections::BTreeMap<String, u64>);
+
+impl VectorClock {
+    fn increment(&mut self, writer_id: &str) {
+        *self.0.entry(writer_id.to_string()).or_insert(0) += 1;
     }
-    let tmp = path.with_extension(".tmp");
-    {
-        let mut f = fs::File::create(&tmp)?;
-        f.write_all(bytes)?;
-        f.sync_all()?;
+
+    fn merge(&mut self, other: &VectorClock) {
+        for (writer, &count) in &other.0 {
+            let entry = self.0.entry(writer.clone()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
     }
-    fs::rename(&tmp, path)?;
-    Ok(())
+
+    /// `self` happens-before `other`: every counter in `self` is <= the
+    /// matching counter in `other`, and at least one is strictly less (or
+    /// `other` tracks a writer `self` never saw). Neither `happens_before`
+    /// holding in both directions means the two clocks are concurrent.
+    fn happens_before(&self, other: &VectorClock) -> bool {
+        if self == other {
+            return false;
+        }
+        self.0
+            .iter()
+            .all(|(writer, count)| *count <= other.0.get(writer).copied().unwrap_or(0))
+    }
+}
+
+/// Deterministically fold concurrent sibling annotations into one:
+/// most-restrictive verdict wins first (`Quarantine` > `AllowWithPatch` >
+/// `Allow`), `risk` breaks ties within the same verdict, and `ts_ms` breaks
+/// ties within the same risk -- so a deny is never lost to a concurrent
+/// allow just because the allow happened to carry a higher risk score.
+/// Returns `None` for an empty slice; a single head is returned unchanged.
+pub fn resolve_siblings(heads: &[CapsAnnot]) -> Option<CapsAnnot> {
+    heads
+        .iter()
+        .max_by(|a, b| {
+            verdict_rank(&a.verdict)
+                .cmp(&verdict_rank(&b.verdict))
+                .then_with(|| {
+                    a.risk
+                        .partial_cmp(&b.risk)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then_with(|| a.ts_ms.cmp(&b.ts_ms))
+        })
+        .cloned()
 }
 
-fn append_line(path: &Path, line: &[u8]) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+/// Restrictiveness rank for [`resolve_siblings`]: higher wins.
+fn verdict_rank(v: &Verdict) -> u8 {
+    match v {
+        Verdict::Allow => 0,
+        Verdict::AllowWithPatch => 1,
+        Verdict::Quarantine => 2,
     }
-    let mut f = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)?;
-    f.write_all(line)?;
-    f.write_all(b"\n")?;
-    Ok(())
 }
 
+// -------------- hash-chained annotation log --------------
+
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One link in a capsule's tamper-evident annotation log: `entry_hash` is
+/// `blake3(prev_hash || seq || canonical_json(annot))`, so editing or
+/// reordering any past `annot` breaks every hash after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChainedAnnotation {
+    seq: u64,
+    prev_hash: String,
+    entry_hash: String,
+    /// Bayou-style tentative/committed flag. Tentative entries are written to
+    /// the log for ordering but excluded from `latest.json` until committed.
+    #[serde(default = "default_true")]
+    committed: bool,
+    annot: CapsAnnot,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A folded prefix of a capsule's annotation chain, written by
+/// [`ContractsStore::checkpoint_annotations`] once every entry up to
+/// `through_seq` is committed. Verification and new appends resume from
+/// `chain_hash` instead of replaying the whole (now-truncated) history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AnnotationCheckpoint {
+    through_seq: u64,
+    chain_hash: String,
+    state: CapsAnnot,
+}
+
+fn read_checkpoint(backend: &dyn CapsuleBackend, id: &CapsId) -> Result<Option<AnnotationCheckpoint>> {
+    let Some(bytes) = backend.get(&checkpoint_key(id))? else {
+        return Ok(None);
+    };
+    Ok(Some(
+        serde_json::from_slice(&bytes).context("parse annotation checkpoint")?,
+    ))
+}
+
+/// Stable key for comparing two [`CapsAnnot`]s by value (it has no
+/// `PartialEq`) — used by [`ContractsStore::tentative_since`] to match a
+/// tentative entry against the committed entry that later confirmed it.
+fn annot_identity(annot: &CapsAnnot) -> String {
+    serde_json::to_string(annot).unwrap_or_default()
+}
+
+fn chain_entry_hash(prev_hash: &str, seq: u64, committed: bool, annot: &CapsAnnot) -> Result<String> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(prev_hash.as_bytes());
+    buf.extend_from_slice(&seq.to_le_bytes());
+    buf.push(committed as u8);
+    buf.extend_from_slice(&serde_json::to_vec(&canonicalize(
+        &serde_json::to_value(annot).context("serialize annot for chain hash")?,
+    ))?);
+    Ok(blake3::hash(&buf).to_hex().to_string())
+}
+
+/// Last entry of a capsule's chain log, if any (the backend concatenates a
+/// key's append history in order, so this is a cheap tail read either way —
+/// a single growing file on disk, or the last numbered object in a bucket).
+fn last_chained_entry(backend: &dyn CapsuleBackend, id: &CapsId) -> Result<Option<ChainedAnnotation>> {
+    let Some(bytes) = backend.get(&chain_key(id))? else {
+        return Ok(None);
+    };
+    let contents = String::from_utf8(bytes).context("annotation log is not utf8")?;
+    match contents.lines().filter(|l| !l.trim().is_empty()).last() {
+        Some(line) => Ok(Some(
+            serde_json::from_str(line).context("parse last annotation chain entry")?,
+        )),
+        None => Ok(None),
+    }
+}
+
+// -------------- helpers --------------
+
 fn sanitize(s: &str) -> String {
     s.chars()
         .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
         .collect()
 }
 
+/// Verify that `cap.meta.issuer_signature` is a valid Ed25519 signature over
+/// `cap.meta.capsule_hash` by `issuer_key` *and* that `capsule_hash` itself
+/// still matches the capsule's actual content -- recomputed the same way
+/// [`ContractsStore::ingest_capsule`] originally computed it: canonical JSON
+/// hash with `capsule_hash` stripped, *before* `issuer_signature` was set and
+/// *before* `inputs`/`outputs` were sealed (if this store has a master key).
+/// A capsule loaded via [`ContractsStore::load_capsule`] has
+/// `issuer_signature`/`sealed_data_key`/`sealed_key_nonce` already populated
+/// and `inputs`/`outputs` already unsealed back to plaintext -- matching the
+/// ingest-time content but not the ingest-time *metadata* -- so all three
+/// fields must be stripped the same way `capsule_hash` is, or every sealed
+/// (or even just signed) capsule would fail verification. Without any of
+/// this, tampering with another field while leaving `capsule_hash`/
+/// `issuer_signature` alone would also pass. Fails closed: a missing hash or
+/// signature, or a mismatched one, is an error, not an implicit pass.
+pub fn verify_issuer_signature(cap: &SimCapsule, issuer_key: &VerifyingKey) -> Result<()> {
+    let hash = cap
+        .meta
+        .capsule_hash
+        .as_deref()
+        .ok_or_else(|| anyhow!("capsule has no capsule_hash to verify against"))?;
+
+    let mut v = serde_json::to_value(cap).context("serialize capsule")?;
+    if let Some(m) = v.get_mut("meta").and_then(|m| m.as_object_mut()) {
+        m.remove("capsule_hash");
+        m.remove("issuer_signature");
+        m.remove("sealed_data_key");
+        m.remove("sealed_key_nonce");
+    }
+    let recomputed = canonical_hash(&v);
+    if recomputed != hash {
+        return Err(anyhow!(
+            "capsule_hash mismatch: capsule content hashes to {recomputed}, not the declared {hash} (content was tampered with)"
+        ));
+    }
+
+    let sig_b64 = cap
+        .meta
+        .issuer_signature
+        .as_deref()
+        .ok_or_else(|| anyhow!("capsule has no issuer_signature"))?;
+    let sig_bytes = B64
+        .decode(sig_b64)
+        .map_err(|e| anyhow!("bad base64 issuer_signature: {e}"))?;
+    let sig_bytes: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| anyhow!("issuer_signature must be 64 bytes"))?;
+    let signature = ed25519_dalek::Signature::from_bytes(&sig_bytes);
+    issuer_key
+        .verify(hash.as_bytes(), &signature)
+        .map_err(|e| anyhow!("issuer signature verification failed: {e}"))
+}
+
 /// Deterministic JSON canonicalization (sort object keys recursively) then Blake3 hash hex.
 fn canonical_hash(v: &Value) -> String {
     let cv = canonicalize(v);
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use contracts::api::{CapsAnnot, Verdict};
+use contracts::capsule::{CapsuleMeta, CapsuleSource, SimCapsule};
+use contracts::{CapsuleBackend, ContractsStore};
+
+/// Object-store-shaped test double: `append` never overwrites, it stores a
+/// new numbered part under `key`, same as a real S3-compatible backend would
+/// have to since object stores have no native append.
+#[derive(Default)]
+struct InMemoryBackend {
+    objects: Mutex<BTreeMap<String, Vec<u8>>>,
+    parts: Mutex<BTreeMap<String, Vec<Vec<u8>>>>,
+}
+
+impl CapsuleBackend for InMemoryBackend {
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        self.objects.lock().unwrap().insert(key.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(bytes) = self.objects.lock().unwrap().get(key) {
+            return Ok(Some(bytes.clone()));
+        }
+        let parts = self.parts.lock().unwrap();
+        match parts.get(key) {
+            Some(chunks) => Ok(Some(chunks.concat())),
+            None => Ok(None),
+        }
+    }
+
+    fn append(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        self.parts
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .push(bytes.to_vec());
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .parts
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        self.objects.lock().unwrap().remove(key);
+        self.parts.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+fn sample_capsule(now_ms: u64) -> SimCapsule {
+    SimCapsule {
+        inputs: serde_json::json!({}),
+        context: serde_json::json!({"lobe": "chat"}),
+        actions: serde_json::json!(["ingest_text"]),
+        outputs: serde_json::json!({"text": "hello"}),
+        trace: serde_json::json!({}),
+        artifacts: vec![],
+        meta: CapsuleMeta {
+            capsule_id: None,
+            agent_id: Some("core".into()),
+            lobe: Some("chat".into()),
+            t_start_ms: now_ms,
+            t_end_ms: now_ms,
+            source: CapsuleSource::Real,
+            schema_ver: "1.0".into(),
+            capsule_hash: None,
+            issuer_signature: None,
+            parent_id: None,
+        },
+    }
+}
+
+#[test]
+fn contracts_store_runs_unchanged_against_a_non_filesystem_backend() {
+    let store = ContractsStore::new_with_backend(Arc::new(InMemoryBackend::default())).expect("store");
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let handle = store
+        .ingest_capsule(sample_capsule(now_ms))
+        .expect("ingest");
+
+    assert!(store.load_capsule(&handle.id).expect("load").is_some());
+
+    let ann = CapsAnnot {
+        verdict: Verdict::Allow,
+        risk: 0.0,
+        labels: vec!["ok".into()],
+        policy_ver: "test".into(),
+        patch_id: None,
+        ts_ms: now_ms,
+    };
+    store.annotate(&handle.id, &ann).expect("annotate");
+    store
+        .verify_annotation_chain(&handle.id)
+        .expect("chain verifies against an append-as-parts backend");
+    assert_eq!(
+        store.latest_annotation(&handle.id).expect("read").unwrap().labels,
+        vec!["ok".to_string()]
+    );
+}
@@ -1,4 +1,6 @@
-use contracts::evaluator::{evaluate_input_against_rules, load_contract_from_file};
+use contracts::evaluator::{
+    evaluate_input_against_rules, evaluate_with_atp_budget, load_contract_from_file,
+};
 use contracts::types::MoralContract;
 
 fn setup_nonviolence_contract() -> MoralContract {
@@ -84,3 +86,22 @@ fn test_justification_of_violence_with_constraints() {
     assert!(cs.iter().any(|c| c == "offer_deescalation"));
     assert!(cs.iter().any(|c| c == "avoid_justification_language"));
 }
+
+#[test]
+fn test_atp_budget_passes_through_with_headroom() {
+    let contract = setup_nonviolence_contract();
+    let result = evaluate_with_atp_budget("Hello, how are you?", &contract, 1_000_000);
+    assert!(result.passed);
+    assert!(!result.atp_budget_exceeded);
+    assert!(result.atp_spent > 0);
+}
+
+#[test]
+fn test_atp_budget_of_zero_aborts_before_any_rule() {
+    let contract = setup_nonviolence_contract();
+    let result = evaluate_with_atp_budget("I'm going to hurt someone.", &contract, 0);
+    assert!(!result.passed, "a budget-exhausted eval must fail closed");
+    assert!(result.atp_budget_exceeded);
+    assert_eq!(result.atp_spent, 0);
+    assert!(result.violated_rules.is_empty());
+}
@@ -0,0 +1,47 @@
+use contracts::{apply_masks, apply_regex_masks, PatchOp, PatchPlan};
+
+#[test]
+fn apply_masks_matches_through_zero_width_obfuscation() {
+    let masked = apply_masks("pa\u{200b}ssword token", &["password".to_string()]);
+    assert!(!masked.contains("ssword"));
+    assert!(masked.contains("[masked]"));
+}
+
+#[test]
+fn mask_text_op_resolves_through_apply_masks() {
+    let op = PatchOp::MaskText { pattern: "secret".to_string() };
+    assert_eq!(op.apply("the secret plan"), "the [masked] plan");
+}
+
+#[test]
+fn patch_plan_applies_all_mask_ops_and_ignores_swap_artifact() {
+    let plan = PatchPlan {
+        id: "p1".to_string(),
+        ops: vec![
+            PatchOp::MaskText { pattern: "secret".to_string() },
+            PatchOp::SwapArtifact { name: "frame_0001.png".to_string(), cid: "blake3:abc".to_string() },
+        ],
+        alt_artifacts: Default::default(),
+    };
+    assert_eq!(plan.apply_text("the secret plan"), "the [masked] plan");
+}
+
+#[test]
+fn apply_regex_masks_matches_digit_runs_through_obfuscation() {
+    // Zero-width space splits the digit run; regex still sees it as contiguous.
+    let masked = apply_regex_masks("card: 4111\u{200b}111122223333 exp", &[r"\d{12,}".to_string()]);
+    assert!(!masked.contains("1111"));
+    assert!(masked.contains("[masked]"));
+}
+
+#[test]
+fn apply_regex_masks_skips_invalid_pattern_without_panicking() {
+    let out = apply_regex_masks("hello world", &["(unterminated".to_string()]);
+    assert_eq!(out, "hello world");
+}
+
+#[test]
+fn mask_regex_op_resolves_through_apply_regex_masks() {
+    let op = PatchOp::MaskRegex { pattern: r"\d{3}-\d{2}-\d{4}".to_string() };
+    assert_eq!(op.apply("ssn: 123-45-6789 done"), "ssn: [masked] done");
+}
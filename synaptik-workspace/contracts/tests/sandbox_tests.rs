@@ -0,0 +1,86 @@
+#![cfg(feature = "wasm_host")]
+
+use std::time::Duration;
+
+use contracts::sandbox::{run_wasm_contract, run_wasm_contract_with_deadline, SandboxError};
+
+// A guest that loops forever and never returns. Wasmtime's `Module::new`
+// accepts WAT text directly (it parses text to binary internally), so this
+// is written by hand rather than checked in as a compiled `.wasm` fixture.
+const SPINNING_GUEST_WAT: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "allocate") (param i32) (result i32)
+    i32.const 0)
+  (func (export "evaluate") (param i32 i32) (result i32 i32)
+    (loop $spin
+      br $spin)
+    i32.const 0
+    i32.const 0))
+"#;
+
+const ECHO_GUEST_WAT: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "allocate") (param $len i32) (result i32)
+    i32.const 0)
+  (func (export "evaluate") (param $ptr i32) (param $len i32) (result i32 i32)
+    local.get $ptr
+    local.get $len))
+"#;
+
+#[test]
+fn echo_guest_runs_to_completion_within_default_limits() {
+    let out = run_wasm_contract(ECHO_GUEST_WAT.as_bytes(), "hello").expect("echo guest to run");
+    assert_eq!(out, "hello");
+}
+
+#[test]
+fn spinning_guest_exhausts_its_fuel_budget() {
+    // Default deadline (250ms) comfortably outlasts the default 10M-fuel
+    // budget for a trivial one-instruction loop body, so fuel runs out first.
+    let err = run_wasm_contract(SPINNING_GUEST_WAT.as_bytes(), "x")
+        .expect_err("an infinite loop must not run forever");
+    assert!(
+        matches!(err, SandboxError::FuelExhausted),
+        "expected FuelExhausted, got {err:?}"
+    );
+}
+
+#[test]
+fn spinning_guest_is_interrupted_by_the_watchdog_before_fuel_runs_out() {
+    // A deadline far shorter than the time it takes to burn through the
+    // default fuel budget forces the epoch-interruption watchdog to fire
+    // first, distinguishing a hung contract from one that merely overspent.
+    let err = run_wasm_contract_with_deadline(
+        SPINNING_GUEST_WAT.as_bytes(),
+        "x",
+        Duration::from_millis(1),
+    )
+    .expect_err("an infinite loop must not run forever");
+    assert!(
+        matches!(err, SandboxError::Timeout),
+        "expected Timeout, got {err:?}"
+    );
+}
+
+#[test]
+fn disallowed_import_is_rejected_before_instantiation() {
+    const IMPORTING_GUEST_WAT: &str = r#"
+    (module
+      (import "env" "log" (func $log (param i32 i32)))
+      (memory (export "memory") 1)
+      (func (export "allocate") (param i32) (result i32)
+        i32.const 0)
+      (func (export "evaluate") (param i32 i32) (result i32 i32)
+        i32.const 0
+        i32.const 0))
+    "#;
+
+    let err = run_wasm_contract(IMPORTING_GUEST_WAT.as_bytes(), "x")
+        .expect_err("no host capability is allow-listed");
+    assert!(
+        matches!(err, SandboxError::ContractFault(_)),
+        "expected ContractFault, got {err:?}"
+    );
+}
@@ -0,0 +1,29 @@
+use contracts::schema::{migrate_to_current, supports_patch_labels, CURRENT, MIN_SUPPORTED};
+
+#[test]
+fn current_migrates_to_itself_as_a_no_op() {
+    let v = serde_json::json!({"a": 1});
+    assert_eq!(migrate_to_current(CURRENT, v.clone()).unwrap(), v);
+}
+
+#[test]
+fn rejects_newer_than_current() {
+    let v = serde_json::json!({});
+    assert!(migrate_to_current("99.0", v).is_err());
+}
+
+#[test]
+fn rejects_older_than_min_supported() {
+    let v = serde_json::json!({});
+    assert!(migrate_to_current("0.1", v).is_err());
+}
+
+#[test]
+fn min_supported_is_not_newer_than_current() {
+    assert!(migrate_to_current(MIN_SUPPORTED, serde_json::json!({})).is_ok());
+}
+
+#[test]
+fn current_schema_supports_patch_labels() {
+    assert!(supports_patch_labels(CURRENT));
+}
@@ -30,6 +30,14 @@ enum Cmd {
         sk_hex: Option<String>,
         #[arg(long)]
         key_id: Option<String>,
+        /// Webhook URL to notify with the PUBLISH event JSON (best-effort;
+        /// a delivery failure is logged, not fatal to registry_init).
+        #[arg(long)]
+        notify_url: Option<String>,
+        /// Extra header to send with the notification, as "Name: Value".
+        /// Repeatable.
+        #[arg(long = "notify-header", value_parser = parse_header)]
+        notify_headers: Vec<(String, String)>,
     },
     /// Promote a channel version to another channel (append to registry.jsonl)
     RegistryPromote {
@@ -41,9 +49,24 @@ enum Cmd {
         to: String,
         #[arg(long)]
         version: String,
+        /// Webhook URL to notify with the PROMOTE event JSON (best-effort;
+        /// a delivery failure is logged, not fatal to registry_promote).
+        #[arg(long)]
+        notify_url: Option<String>,
+        /// Extra header to send with the notification, as "Name: Value".
+        /// Repeatable.
+        #[arg(long = "notify-header", value_parser = parse_header)]
+        notify_headers: Vec<(String, String)>,
     },
 }
 
+fn parse_header(s: &str) -> Result<(String, String)> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("expected \"Name: Value\", got {s:?}"))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli.cmd {
@@ -53,14 +76,58 @@ fn main() -> Result<()> {
             channel,
             sk_hex,
             key_id,
-        } => registry_init(&dir, &out, &channel, sk_hex.as_deref(), key_id.as_deref()),
+            notify_url,
+            notify_headers,
+        } => registry_init(
+            &dir,
+            &out,
+            &channel,
+            sk_hex.as_deref(),
+            key_id.as_deref(),
+            notify_url.as_deref(),
+            &notify_headers,
+        ),
         Cmd::RegistryPromote {
             out,
             from,
             to,
             version,
-        } => registry_promote(&out, &from, &to, &version),
+            notify_url,
+            notify_headers,
+        } => registry_promote(&out, &from, &to, &version, notify_url.as_deref(), &notify_headers),
+    }
+}
+
+/// Best-effort webhook notification for a registry event: POST `ev` as
+/// JSON to `url` with `headers` attached, retrying a few times with
+/// exponential backoff. A delivery failure is printed to stderr rather
+/// than propagated -- the registry.jsonl append (the durable record) has
+/// already succeeded by the time this runs.
+fn notify_registry_event(url: &str, headers: &[(String, String)], ev: &serde_json::Value) {
+    const MAX_RETRIES: u32 = 3;
+    const BASE_BACKOFF: std::time::Duration = std::time::Duration::from_millis(200);
+
+    let mut last_err = None;
+    for attempt in 0..=MAX_RETRIES {
+        let mut req = ureq::post(url);
+        for (name, value) in headers {
+            req = req.set(name, value);
+        }
+        match req.send_json(ev.clone()) {
+            Ok(_) => return,
+            Err(e) => {
+                last_err = Some(e.to_string());
+                if attempt < MAX_RETRIES {
+                    std::thread::sleep(BASE_BACKOFF * 2u32.saturating_pow(attempt));
+                }
+            }
+        }
     }
+    eprintln!(
+        "warning: registry event notification to {url} failed after {} attempt(s): {}",
+        MAX_RETRIES + 1,
+        last_err.unwrap_or_default()
+    );
 }
 
 fn registry_init(
@@ -69,6 +136,8 @@ fn registry_init(
     channel: &str,
     sk_hex: Option<&str>,
     key_id: Option<&str>,
+    notify_url: Option<&str>,
+    notify_headers: &[(String, String)],
 ) -> Result<()> {
     let outp = PathBuf::from(out);
     let packs = outp.join("packs");
@@ -102,11 +171,21 @@ fn registry_init(
             std::io::Write::write_all(&mut f, format!("{ev}\n").as_bytes())
         })
         .with_context(|| format!("append registry event to {:?}", reg))?;
+    if let Some(url) = notify_url {
+        notify_registry_event(url, notify_headers, &ev);
+    }
     println!("initialized registry at {}", reg.display());
     Ok(())
 }
 
-fn registry_promote(out: &str, from: &str, to: &str, version: &str) -> Result<()> {
+fn registry_promote(
+    out: &str,
+    from: &str,
+    to: &str,
+    version: &str,
+    notify_url: Option<&str>,
+    notify_headers: &[(String, String)],
+) -> Result<()> {
     let reg = PathBuf::from(out).join("registry.jsonl");
     anyhow::ensure!(reg.exists(), "registry.jsonl missing at {}", reg.display());
     let ev = serde_json::json!({
@@ -124,6 +203,9 @@ fn registry_promote(out: &str, from: &str, to: &str, version: &str) -> Result<()
             std::io::Write::write_all(&mut f, format!("{ev}\n").as_bytes())
         })
         .context("append registry")?;
+    if let Some(url) = notify_url {
+        notify_registry_event(url, notify_headers, &ev);
+    }
     println!("promoted {from} -> {to} version {version}");
     Ok(())
 }
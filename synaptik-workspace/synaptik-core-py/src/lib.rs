@@ -3,10 +3,14 @@ use ::synaptik_core::commands::init;
 
 mod py_helpers;
 mod py_streamgate;
+mod py_streamruntime;
+mod py_relay;
 mod py_commands;
 
 pub use py_commands::PyCommands;
 pub use py_streamgate::{PyGateDecision, PyStreamGate};
+pub use py_streamruntime::PyStreamRuntime;
+pub use py_relay::PyGateRelay;
 
 #[pymodule]
 fn synaptik_core(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -17,5 +21,7 @@ fn synaptik_core(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyCommands>()?;
     m.add_class::<PyStreamGate>()?;
     m.add_class::<PyGateDecision>()?;
+    m.add_class::<PyStreamRuntime>()?;
+    m.add_class::<PyGateRelay>()?;
     Ok(())
 }
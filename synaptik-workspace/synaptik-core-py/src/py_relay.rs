@@ -0,0 +1,62 @@
+use pyo3::prelude::*;
+
+use serde_json::Value;
+use synaptik_core as syn_core;
+use syn_core::services::relay::RelayClient as CoreRelayClient;
+
+use crate::py_helpers::{json_to_py, pyerr};
+
+/// A worker's connection to a dataspace relay. See
+/// `synaptik_core::services::relay` for the wire protocol and semantics.
+#[pyclass(name = "GateRelay")]
+pub struct PyGateRelay {
+    client: CoreRelayClient,
+}
+
+#[pymethods]
+impl PyGateRelay {
+    #[new]
+    fn new(addr: &str) -> PyResult<Self> {
+        let client = CoreRelayClient::connect(addr).map_err(pyerr)?;
+        Ok(Self { client })
+    }
+
+    /// Register interest in `action`'s contract. The relay's reply (the
+    /// current contract, or a retraction if none is asserted) arrives via
+    /// `recv`.
+    fn assert_interest(&self, action: &str) -> PyResult<()> {
+        self.client.assert_interest(action).map_err(pyerr)
+    }
+
+    /// Assert (publish or replace) the contract for `action`, given as a
+    /// JSON string, on behalf of this peer.
+    fn assert_contract(&self, action: &str, contract_json: &str) -> PyResult<()> {
+        let contract: Value = serde_json::from_str(contract_json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        self.client.assert_contract(action, contract).map_err(pyerr)
+    }
+
+    fn retract_contract(&self, action: &str) -> PyResult<()> {
+        self.client.retract_contract(action).map_err(pyerr)
+    }
+
+    /// Publish a `CutAndReplace`/violation for `action`, typically called
+    /// right after a `StreamGate.push`/`finalize` call returns one.
+    fn publish_violation(&self, action: &str, message: &str) -> PyResult<()> {
+        self.client.publish_violation(action, message).map_err(pyerr)
+    }
+
+    /// Block for the next message the relay sends this peer, returned as a
+    /// plain dict (`{"type": "assert_contract", ...}`), or `None` if the
+    /// relay closed the connection.
+    fn recv(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let msg = self.client.recv().map_err(pyerr)?;
+        match msg {
+            Some(msg) => {
+                let value = serde_json::to_value(&msg).map_err(pyerr)?;
+                Ok(Some(json_to_py(py, &value)))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use pyo3::prelude::*;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use contracts::types::MoralContract;
 use synaptik_core as syn_core;
@@ -28,7 +28,9 @@ fn decision_to_py(decision: CoreGateDecision) -> PyGateDecision {
 pub struct PyStreamGate {
     #[allow(dead_code)]
     pub(crate) index: Arc<StreamingIndex>,
-    pub(crate) gate: CoreStreamGate,
+    // Shared (not just owned) so `push_async` can hand a clone to a blocking
+    // thread-pool task without holding `&mut self` across an `.await`.
+    pub(crate) gate: Arc<Mutex<CoreStreamGate>>,
 }
 
 #[pymethods]
@@ -52,17 +54,40 @@ impl PyStreamGate {
             arc.clone(),
             StreamGateConfig { budget_ms, window_bytes, fail_closed_on_finalize },
         );
-        Ok(Self { index: arc, gate })
+        Ok(Self { index: arc, gate: Arc::new(Mutex::new(gate)) })
     }
 
     /// Push a chunk of text to the stream gate. Returns a GateDecision (Pass/Hold/CutAndReplace).
     fn push(&mut self, chunk: &str) -> PyResult<PyGateDecision> {
-        Ok(decision_to_py(self.gate.push(chunk)))
+        Ok(decision_to_py(self.gate.lock().unwrap().push(chunk)))
+    }
+
+    /// Non-blocking counterpart to `push`, for callers polling many gates
+    /// off one event loop: buffers `chunk` and returns `None` while the
+    /// gate is still accumulating inside `window_bytes`, or the
+    /// `GateDecision` once one is available.
+    fn poll_push(&mut self, chunk: &str) -> PyResult<Option<PyGateDecision>> {
+        Ok(self.gate.lock().unwrap().poll_push(chunk).map(decision_to_py))
+    }
+
+    /// Async counterpart to `push`: runs the (synchronous, CPU-bound) gate
+    /// evaluation on a blocking thread-pool task via
+    /// `pyo3_asyncio::tokio::future_into_py` and awaits it, so a Python
+    /// event loop multiplexing many concurrent streams isn't stalled while
+    /// one of them is being evaluated.
+    fn push_async<'p>(&self, py: Python<'p>, chunk: String) -> PyResult<Bound<'p, PyAny>> {
+        let gate = self.gate.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let decision = tokio::task::spawn_blocking(move || gate.lock().unwrap().push(&chunk))
+                .await
+                .map_err(pyerr)?;
+            Ok(decision_to_py(decision))
+        })
     }
 
     /// Finalize the stream. Returns a GateDecision (Pass/Hold/CutAndReplace).
     fn finalize(&mut self) -> PyResult<PyGateDecision> {
-        Ok(decision_to_py(self.gate.finalize()))
+        Ok(decision_to_py(self.gate.lock().unwrap().finalize()))
     }
 }
 
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use pyo3::exceptions::PyStopIteration;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use contracts::types::MoralContract;
+use synaptik_core as syn_core;
+use syn_core::services::ethos::{MoralContractDecider, Proposal};
+use syn_core::services::{FinalizedStatus, GateError, LlmClient, StreamRuntime};
+
+use crate::py_helpers::pyerr;
+
+/// Iterates a Python iterator's `__next__` one GIL acquisition at a time,
+/// stopping cleanly on `StopIteration` -- the Rust-side half of the token
+/// source a `StreamRuntime` pulls from via [`LlmClient::stream`].
+struct PyTokenStream {
+    iter: Py<PyAny>,
+}
+
+impl Iterator for PyTokenStream {
+    type Item = String;
+    fn next(&mut self) -> Option<String> {
+        Python::with_gil(|py| match self.iter.call_method0(py, "__next__") {
+            Ok(v) => v.extract::<String>(py).ok(),
+            Err(e) if e.is_instance_of::<PyStopIteration>(py) => None,
+            Err(_) => None,
+        })
+    }
+}
+
+/// Adapts a Python callable (`system_prompt -> Iterable[str]`) into
+/// `LlmClient`, so `StreamRuntime` can pull tokens from a Python-side model
+/// (an OpenAI/Anthropic streaming client, a test double, etc.).
+struct PyTokenModel {
+    factory: Py<PyAny>,
+}
+
+impl LlmClient for PyTokenModel {
+    type Stream = PyTokenStream;
+    fn stream(&self, system_prompt: String) -> Result<Self::Stream, GateError> {
+        Python::with_gil(|py| {
+            let tokens = self
+                .factory
+                .call1(py, (system_prompt,))
+                .map_err(|e| GateError(e.to_string()))?;
+            let iter = tokens
+                .call_method0(py, "__iter__")
+                .map_err(|e| GateError(e.to_string()))?;
+            Ok(PyTokenStream { iter })
+        })
+    }
+}
+
+/// Runs Synaptik's full contract-enforced `StreamRuntime` -- mask-rule
+/// redaction, stop-phrase early-stop, and `max_tokens` enforcement -- over a
+/// Python-supplied token source and an ad hoc contract, without requiring
+/// the lower-level `StreamGate` or a contract registered on disk.
+#[pyclass(name = "StreamRuntime")]
+pub struct PyStreamRuntime {
+    contract: Arc<MoralContract>,
+    token_source: Py<PyAny>,
+}
+
+#[pymethods]
+impl PyStreamRuntime {
+    /// `token_source` is a Python callable taking the compiled system
+    /// prompt and returning an iterable of token strings.
+    #[new]
+    fn new(contract_json: &str, token_source: Py<PyAny>) -> PyResult<Self> {
+        let contract: MoralContract = serde_json::from_str(contract_json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(Self { contract: Arc::new(contract), token_source })
+    }
+
+    /// Run `generate` for a `Proposal` built from `intent`/`input` and
+    /// return a dict exposing `status` (`"ok"`/`"violated"`/`"stopped"`/
+    /// `"escalated"`) and `text`.
+    #[pyo3(signature = (intent, input))]
+    fn generate(&self, py: Python<'_>, intent: &str, input: &str) -> PyResult<PyObject> {
+        let proposal = Proposal {
+            intent: intent.to_string(),
+            input: input.to_string(),
+            prior: None,
+            tools_requested: vec![],
+        };
+        let decider = MoralContractDecider { contract: self.contract.clone() };
+        let model = PyTokenModel { factory: self.token_source.clone_ref(py) };
+        let rt = StreamRuntime { contract: decider, model };
+
+        let res = rt.generate(proposal).map_err(pyerr)?;
+
+        let status = match res.status {
+            FinalizedStatus::Ok => "ok",
+            FinalizedStatus::Violated => "violated",
+            FinalizedStatus::Stopped => "stopped",
+            FinalizedStatus::Escalated => "escalated",
+        };
+        let d = PyDict::new_bound(py);
+        d.set_item("status", status)?;
+        d.set_item("text", res.text)?;
+        d.set_item("violation_label", res.violation_label)?;
+        Ok(d.into_any().into_py(py))
+    }
+}
@@ -5,17 +5,126 @@ use std::time::{Duration, Instant};
 
 use crossbeam_channel::{bounded, Receiver, Sender};
 use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, SeedableRng};
+use serde_json::json;
 use sysinfo::System;
 
 use synaptik_core::commands::init::{ensure_initialized_once};
 use synaptik_core::services::memory::Memory;
+use synaptik_core::services::storage_backend::StorageBackend;
+#[cfg(feature = "sled_backend")]
+use synaptik_core::services::sled_backend::SledBackend;
+
+/// Which `StorageBackend` impl `run_bench` drives the workload against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BackendKind {
+    Sqlite,
+    Sled,
+}
+
+impl BackendKind {
+    fn label(self) -> &'static str {
+        match self {
+            BackendKind::Sqlite => "sqlite",
+            BackendKind::Sled => "sled",
+        }
+    }
+
+    /// Build the backend for this workload run, rooted at `storage_path`
+    /// (a file for SQLite, a directory for sled).
+    fn open(self, storage_path: &std::path::Path) -> anyhow::Result<Box<dyn StorageBackend>> {
+        match self {
+            BackendKind::Sqlite => {
+                Ok(Box::new(Memory::open(&storage_path.to_string_lossy())?))
+            }
+            #[cfg(feature = "sled_backend")]
+            BackendKind::Sled => Ok(Box::new(SledBackend::open(storage_path)?)),
+            #[cfg(not(feature = "sled_backend"))]
+            BackendKind::Sled => anyhow::bail!(
+                "sled backend requested but this binary wasn't built with --features sled_backend"
+            ),
+        }
+    }
+}
+
+/// Recursively sum file sizes under `path` (or a single file's size), so the
+/// same measurement works whether the backend under test stores in one
+/// SQLite file or a sled directory of segment files.
+fn storage_size_mb(path: &std::path::Path) -> f64 {
+    fn walk(path: &std::path::Path) -> u64 {
+        let Ok(meta) = fs::metadata(path) else { return 0 };
+        if meta.is_dir() {
+            let Ok(entries) = fs::read_dir(path) else { return 0 };
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| walk(&e.path()))
+                .sum()
+        } else {
+            meta.len()
+        }
+    }
+    walk(path) as f64 / (1024.0 * 1024.0)
+}
 
 #[derive(Clone, Debug)]
 struct BenchCfg {
     interactions_per_session: usize, // N
     parallel_sessions: usize,        // M
+    /// Extra interactions run (and discarded) per session before
+    /// measurement starts, so cold caches/connections don't skew the
+    /// reported percentiles.
+    warmup_per_session: usize,
     lobe: String,
     key_prefix: String,
+    backend: BackendKind,
+}
+
+/// SLO targets a concurrency level is judged against; overridable via env so
+/// CI can tighten/loosen them without a code change.
+#[derive(Clone, Copy, Debug)]
+struct SloTargets {
+    tput_per_sec: f64,
+    p95_commit_ms: f64,
+    p95_replay_ms: f64,
+    error_pct: f64,
+}
+
+impl SloTargets {
+    fn from_env() -> Self {
+        fn env_f64(name: &str, default: f64) -> f64 {
+            std::env::var(name).ok().and_then(|s| s.parse().ok()).unwrap_or(default)
+        }
+        Self {
+            tput_per_sec: env_f64("SYN_BENCH_TARGET_TPUT", 5000.0 / 60.0), // 5k/min => per second
+            p95_commit_ms: env_f64("SYN_BENCH_TARGET_P95_COMMIT_MS", 40.0),
+            p95_replay_ms: env_f64("SYN_BENCH_TARGET_P95_REPLAY_MS", 60.0),
+            error_pct: env_f64("SYN_BENCH_TARGET_ERROR_PCT", 0.1),
+        }
+    }
+}
+
+/// Parse a comma-separated list of concurrency levels (`SYN_BENCH_CONCURRENCY`,
+/// e.g. `"1,2,4,8"`), defaulting to `[1, 2, 4, 8]`.
+fn concurrency_sweep() -> Vec<usize> {
+    match std::env::var("SYN_BENCH_CONCURRENCY") {
+        Ok(raw) => {
+            let parsed: Vec<usize> = raw.split(',').filter_map(|s| s.trim().parse().ok()).collect();
+            if parsed.is_empty() { vec![1, 2, 4, 8] } else { parsed }
+        }
+        Err(_) => vec![1, 2, 4, 8],
+    }
+}
+
+/// Render `n` with `,` thousands separators, e.g. `12345` -> `"12,345"`.
+fn fmt_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out.chars().rev().collect()
 }
 
 #[derive(Debug, Clone)]
@@ -29,7 +138,7 @@ struct Metrics {
     end: Instant,
     max_rss_mb: f64,
     avg_cpu_percent: f64,
-    sqlite_size_mb: f64,
+    storage_size_mb: f64,
 }
 
 impl Default for Metrics {
@@ -44,7 +153,7 @@ impl Default for Metrics {
             end: Instant::now(),
             max_rss_mb: 0.0,
             avg_cpu_percent: 0.0,
-            sqlite_size_mb: 0.0,
+            storage_size_mb: 0.0,
         }
     }
 }
@@ -86,37 +195,45 @@ fn run_bench(cfg: BenchCfg) -> anyhow::Result<Metrics> {
     // Ensure filesystem layout and config exist
     let init = ensure_initialized_once()?;
 
-    // Open Memory (SQLite) in this isolated root
-    let db_path = init.config.memory.cache_path.to_string_lossy().to_string();
-    let base_sqlite_size_mb = fs::metadata(&init.config.memory.cache_path)
-        .map(|m| m.len() as f64 / (1024.0 * 1024.0))
-        .unwrap_or(0.0);
-
-    // Single-writer queue, to respect Memory's one-writer design
+    // Root the backend under test in this isolated root: a file path for
+    // SQLite, a directory for sled.
+    let storage_path = match cfg.backend {
+        BackendKind::Sqlite => init.config.memory.cache_path.clone(),
+        BackendKind::Sled => init
+            .config
+            .memory
+            .cache_path
+            .with_file_name(format!("{}.sled", cfg.backend.label())),
+    };
+    let base_storage_size_mb = storage_size_mb(&storage_path);
+
+    // Single-writer queue, to respect Memory's one-writer design. Replay is
+    // deliberately NOT routed through this channel: `recall_snapshot` reads
+    // the content-addressed DAG on disk directly (see
+    // `services::storage_backend`/`memory::dag`) rather than through a
+    // backend's writer connection, so it can run concurrently with
+    // in-flight commits instead of queuing behind them.
     #[derive(Debug, Clone)]
     enum Op {
         Remember { id: String, key: String, data: Vec<u8>, ack: Sender<()> },
         Promote { id: String, ack: Sender<()> },
-        Replay { hash: String, ack: Sender<()> },
         Stop,
     }
 
     let (tx, rx): (Sender<Op>, Receiver<Op>) = bounded(2048);
     let lobe_for_writer = cfg.lobe.clone();
+    let backend_for_writer = cfg.backend;
+    let storage_path_for_writer = storage_path.clone();
     let writer_handle = thread::spawn(move || -> anyhow::Result<()> {
-        let mem = Memory::open(&db_path)?;
+        let backend = backend_for_writer.open(&storage_path_for_writer)?;
         loop {
             match rx.recv() {
                 Ok(Op::Remember { id, key, data, ack }) => {
-                    mem.remember(&id, &lobe_for_writer, &key, &data)?;
+                    backend.remember(&id, &lobe_for_writer, &key, &data)?;
                     let _ = ack.send(());
                 }
                 Ok(Op::Promote { id, ack }) => {
-                    let _ = mem.promote_to_dag(&id);
-                    let _ = ack.send(());
-                }
-                Ok(Op::Replay { hash, ack }) => {
-                    let _ = mem.recall_snapshot(&hash);
+                    let _ = backend.promote_to_dag(&id);
                     let _ = ack.send(());
                 }
                 Ok(Op::Stop) | Err(_) => break,
@@ -136,6 +253,7 @@ fn run_bench(cfg: BenchCfg) -> anyhow::Result<Metrics> {
         let mref = Arc::clone(&metrics);
         let key_prefix = key_prefix_for_workers.clone();
         let n = cfg.interactions_per_session;
+        let warmup = cfg.warmup_per_session;
         workers.push(thread::spawn(move || {
             let mut rng = StdRng::seed_from_u64(0xC0FFEE + sidx as u64);
             let mut commit_lat = Vec::with_capacity(n);
@@ -144,7 +262,8 @@ fn run_bench(cfg: BenchCfg) -> anyhow::Result<Metrics> {
             let mut writes = 0usize;
             let mut replays = 0usize;
 
-            for i in 0..n {
+            for i in 0..(warmup + n) {
+                let measuring = i >= warmup;
                 let id = format!("sess{}-i{}", sidx, i);
                 let key = format!("{}-{}", key_prefix, sidx);
                 let content = random_text(&mut rng).into_bytes();
@@ -153,28 +272,37 @@ fn run_bench(cfg: BenchCfg) -> anyhow::Result<Metrics> {
                 let t0 = Instant::now();
                 let (ack_r_tx, ack_r_rx) = bounded::<()>(0);
                 if txc.send(Op::Remember { id: id.clone(), key, data: content, ack: ack_r_tx }).is_err() {
-                    errors += 1;
+                    if measuring { errors += 1; }
                     continue;
                 }
                 let _ = ack_r_rx.recv();
 
                 // promote (commit) to DAG; wait for completion
                 let (ack_p_tx, ack_p_rx) = bounded::<()>(0);
-                if txc.send(Op::Promote { id: id.clone(), ack: ack_p_tx }).is_err() { errors += 1; continue; }
+                if txc.send(Op::Promote { id: id.clone(), ack: ack_p_tx }).is_err() {
+                    if measuring { errors += 1; }
+                    continue;
+                }
                 let _ = ack_p_rx.recv();
                 let t1 = Instant::now();
-                commit_lat.push((t1 - t0).as_secs_f64() * 1000.0);
-                writes += 1;
+                if measuring {
+                    commit_lat.push((t1 - t0).as_secs_f64() * 1000.0);
+                    writes += 1;
+                }
 
-                // Replay: recall by content hash we just wrote
+                // Replay: recall by content hash we just wrote, straight off
+                // the DAG on this thread -- no writer-channel queueing.
                 let t2 = Instant::now();
-                let (ack_x_tx, ack_x_rx) = bounded::<()>(0);
-                if txc.send(Op::Replay { hash: content_hash.clone(), ack: ack_x_tx }).is_err() { errors += 1; continue; }
-                let _ = ack_x_rx.recv();
+                let replay_ok = synaptik_core::memory::dag::recall_snapshot(&content_hash).is_ok();
                 let t3 = Instant::now();
-                replay_lat.push((t3 - t2).as_secs_f64() * 1000.0);
-                replays += 1;
-
+                if measuring {
+                    if replay_ok {
+                        replay_lat.push((t3 - t2).as_secs_f64() * 1000.0);
+                        replays += 1;
+                    } else {
+                        errors += 1;
+                    }
+                }
             }
 
             let mut m = mref.lock().unwrap();
@@ -221,11 +349,8 @@ fn run_bench(cfg: BenchCfg) -> anyhow::Result<Metrics> {
     result.max_rss_mb = max_rss;
     result.avg_cpu_percent = avg_cpu;
 
-    // Measure SQLite file size
-    let sqlite_size_mb = fs::metadata(&init.config.memory.cache_path)
-        .map(|m| m.len() as f64 / (1024.0 * 1024.0))
-        .unwrap_or(0.0);
-    result.sqlite_size_mb = (sqlite_size_mb - base_sqlite_size_mb).max(0.0);
+    // Measure storage footprint growth
+    result.storage_size_mb = (storage_size_mb(&storage_path) - base_storage_size_mb).max(0.0);
 
     // Sort latencies once for percentile calculation
     result.commit_latencies_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
@@ -234,54 +359,175 @@ fn run_bench(cfg: BenchCfg) -> anyhow::Result<Metrics> {
     Ok(result)
 }
 
-fn main() -> anyhow::Result<()> {
-    // Load N and M overrides from env
-    let n: usize = std::env::var("SYN_BENCH_N").ok().and_then(|s| s.parse().ok()).unwrap_or(1000);
-    let m: usize = std::env::var("SYN_BENCH_M").ok().and_then(|s| s.parse().ok()).unwrap_or(4);
+struct Report {
+    backend: BackendKind,
+    throughput: f64,
+    p50c: f64,
+    p95c: f64,
+    p99c: f64,
+    p50r: f64,
+    p95r: f64,
+    p99r: f64,
+    error_rate: f64,
+    metrics: Metrics,
+}
+
+fn report_for(backend: BackendKind, n: usize, m: usize, warmup: usize) -> anyhow::Result<Report> {
     let cfg = BenchCfg {
         interactions_per_session: n,
         parallel_sessions: m,
+        warmup_per_session: warmup,
         lobe: "chat".to_string(),
         key_prefix: "load".to_string(),
+        backend,
     };
 
-    eprintln!("Running workload: ingest+commit+replay — N={} M={}", n, m);
+    eprintln!(
+        "Running workload: ingest+commit+replay — backend={} N={} M={} warmup={}",
+        backend.label(),
+        n,
+        m,
+        warmup
+    );
     let metrics = run_bench(cfg)?;
 
-    // Compute throughput and latency percentiles
     let dur_s = (metrics.end - metrics.start).as_secs_f64();
     let total_interactions = metrics.writes as f64; // per interaction we counted one write
     let throughput = if dur_s > 0.0 { total_interactions / dur_s } else { 0.0 };
 
-    let p50c = pct(&metrics.commit_latencies_ms, 0.50);
-    let p95c = pct(&metrics.commit_latencies_ms, 0.95);
-    let p99c = pct(&metrics.commit_latencies_ms, 0.99);
-    let p50r = pct(&metrics.replay_latencies_ms, 0.50);
-    let p95r = pct(&metrics.replay_latencies_ms, 0.95);
-    let p99r = pct(&metrics.replay_latencies_ms, 0.99);
-
     let total_ops = (metrics.writes + metrics.replays) as f64;
     let error_rate = if total_ops > 0.0 { metrics.errors as f64 / total_ops * 100.0 } else { 0.0 };
 
-    // Targets
-    let target_tput = 5000.0 / 60.0; // 5k/min => per second
-    let target_p95_commit = 40.0;
-    let target_p95_replay = 60.0;
-    let target_error_pct = 0.1;
-
-    println!("--- Synaptik Core Load Bench: Ingest + Commit + Replay ---");
-    println!("Throughput: {:.1} interactions/sec (target {:.1})", throughput, target_tput);
-    println!("Latency commit ms: p50 {:.1} p95 {:.1} p99 {:.1} (target p95 < {:.0})", p50c, p95c, p99c, target_p95_commit);
-    println!("Latency replay ms: p50 {:.1} p95 {:.1} p99 {:.1} (target p95 < {:.0})", p50r, p95r, p99r, target_p95_replay);
-    println!("Resource: max RSS {:.1} MB, avg CPU {:.1}%, SQLite size +{:.1} MB", metrics.max_rss_mb, metrics.avg_cpu_percent, metrics.sqlite_size_mb);
-    println!("Errors: {} ({:.3}%) (target < {:.3}%)", metrics.errors, error_rate, target_error_pct);
-
-    // Simple SLO verdicts
-    let ok_tput = throughput >= target_tput;
-    let ok_commit = p95c < target_p95_commit;
-    let ok_replay = p95r < target_p95_replay;
-    let ok_err = error_rate < target_error_pct;
-    println!("SLOs: throughput={} commit={} replay={} errors={}", ok_tput, ok_commit, ok_replay, ok_err);
+    Ok(Report {
+        backend,
+        throughput,
+        p50c: pct(&metrics.commit_latencies_ms, 0.50),
+        p95c: pct(&metrics.commit_latencies_ms, 0.95),
+        p99c: pct(&metrics.commit_latencies_ms, 0.99),
+        p50r: pct(&metrics.replay_latencies_ms, 0.50),
+        p95r: pct(&metrics.replay_latencies_ms, 0.95),
+        p99r: pct(&metrics.replay_latencies_ms, 0.99),
+        error_rate,
+        metrics,
+    })
+}
+
+fn main() -> anyhow::Result<()> {
+    // Load N, warmup and the concurrency sweep from env
+    let n: usize = std::env::var("SYN_BENCH_N").ok().and_then(|s| s.parse().ok()).unwrap_or(1000);
+    let warmup: usize = std::env::var("SYN_BENCH_WARMUP").ok().and_then(|s| s.parse().ok()).unwrap_or(100);
+    let concurrency = concurrency_sweep();
+    let slo = SloTargets::from_env();
+
+    let mut backends = vec![BackendKind::Sqlite];
+    if cfg!(feature = "sled_backend") {
+        backends.push(BackendKind::Sled);
+    } else {
+        eprintln!("sled backend skipped: binary wasn't built with --features sled_backend");
+    }
+
+    println!("--- Synaptik Core Load Bench: Ingest + Commit + Replay (concurrency sweep) ---");
+    let mut json_lines = Vec::new();
+    let mut csv_rows = vec![
+        "backend,parallel_sessions,throughput_per_sec,p50_commit_ms,p95_commit_ms,p99_commit_ms,\
+p50_replay_ms,p95_replay_ms,p99_replay_ms,errors,error_pct,storage_size_mb,ok"
+            .to_string(),
+    ];
+    let mut any_violated = false;
+
+    for backend in &backends {
+        for &m in &concurrency {
+            let r = report_for(*backend, n, m, warmup)?;
+
+            let ok_tput = r.throughput >= slo.tput_per_sec;
+            let ok_commit = r.p95c < slo.p95_commit_ms;
+            let ok_replay = r.p95r < slo.p95_replay_ms;
+            let ok_err = r.error_rate < slo.error_pct;
+            let ok = ok_tput && ok_commit && ok_replay && ok_err;
+            if !ok {
+                any_violated = true;
+            }
+
+            println!("[{} M={}]", r.backend.label(), m);
+            println!(
+                "  Throughput: {:.1} interactions/sec (target {:.1})",
+                r.throughput, slo.tput_per_sec
+            );
+            println!(
+                "  Latency commit ms: p50 {:.1} p95 {:.1} p99 {:.1} (target p95 < {:.0})",
+                r.p50c, r.p95c, r.p99c, slo.p95_commit_ms
+            );
+            println!(
+                "  Latency replay ms: p50 {:.1} p95 {:.1} p99 {:.1} (target p95 < {:.0})",
+                r.p50r, r.p95r, r.p99r, slo.p95_replay_ms
+            );
+            println!(
+                "  Resource: max RSS {:.1} MB, avg CPU {:.1}%, storage size +{:.1} MB",
+                r.metrics.max_rss_mb, r.metrics.avg_cpu_percent, r.metrics.storage_size_mb
+            );
+            println!(
+                "  Writes: {} Replays: {} Errors: {} ({:.3}%) (target < {:.3}%)",
+                fmt_thousands(r.metrics.writes),
+                fmt_thousands(r.metrics.replays),
+                fmt_thousands(r.metrics.errors),
+                r.error_rate,
+                slo.error_pct
+            );
+            println!("  SLOs: throughput={} commit={} replay={} errors={}", ok_tput, ok_commit, ok_replay, ok_err);
+
+            json_lines.push(
+                json!({
+                    "backend": r.backend.label(),
+                    "parallel_sessions": m,
+                    "throughput_per_sec": r.throughput,
+                    "p50_commit_ms": r.p50c,
+                    "p95_commit_ms": r.p95c,
+                    "p99_commit_ms": r.p99c,
+                    "p50_replay_ms": r.p50r,
+                    "p95_replay_ms": r.p95r,
+                    "p99_replay_ms": r.p99r,
+                    "writes": r.metrics.writes,
+                    "replays": r.metrics.replays,
+                    "errors": r.metrics.errors,
+                    "error_pct": r.error_rate,
+                    "storage_size_mb": r.metrics.storage_size_mb,
+                    "ok": ok,
+                })
+                .to_string(),
+            );
+            csv_rows.push(format!(
+                "{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{:.4},{:.3},{}",
+                r.backend.label(),
+                m,
+                r.throughput,
+                r.p50c,
+                r.p95c,
+                r.p99c,
+                r.p50r,
+                r.p95r,
+                r.p99r,
+                r.metrics.errors,
+                r.error_rate,
+                r.metrics.storage_size_mb,
+                ok,
+            ));
+        }
+    }
+
+    println!("--- json (one object per concurrency level) ---");
+    for line in &json_lines {
+        println!("{}", line);
+    }
+
+    println!("--- csv ---");
+    for row in &csv_rows {
+        println!("{}", row);
+    }
+
+    if any_violated {
+        eprintln!("one or more concurrency levels violated an SLO target");
+        std::process::exit(1);
+    }
 
     Ok(())
 }
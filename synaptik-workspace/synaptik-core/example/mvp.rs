@@ -1,10 +1,14 @@
 // examples/mvp.rs
-// Minimal CLI for Synaptik MVP: remember / reflect / stats
+// Minimal CLI for Synaptik MVP: remember / reflect / stats / precheck
 //
 // Build/run:
 //   cargo run --example mvp -- --db ./data/memory.sqlite3 remember notes "User prefers concise explanations"
 //   cargo run --example mvp -- --db ./data/memory.sqlite3 reflect notes 20
 //   cargo run --example mvp -- --db ./data/memory.sqlite3 stats
+//   cargo run --example mvp -- --db ./data/memory.sqlite3 precheck chat "some candidate text"
+//
+// Pass --json (anywhere before the subcommand) for machine-readable output;
+// exit code is 0 except where noted below.
 //
 // Optional cold archive (files + index DB):
 //   cargo run --example mvp -- \
@@ -18,26 +22,37 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+use serde_json::json;
+
 use synaptik_core::commands::Commands;
 use synaptik_core::services::archivist::Archivist;
+use synaptik_core::services::ethos::{decision_gate, precheck, Decision};
+
+/// Exit code used when `precheck`'s gate decision is `Decision::Block`,
+/// distinct from the generic `1` (runtime error) and `2` (usage error) codes
+/// so shell pipelines/CI can tell "the gate blocked this" from "the CLI itself failed".
+const EXIT_GATE_BLOCKED: u8 = 3;
 
 fn usage() -> ! {
     eprintln!(
         "Synaptik MVP CLI
 
 USAGE:
-  mvp --db <PATH> remember <lobe> [--key <key>] <content>
-  mvp --db <PATH> reflect  <lobe> [window]
-  mvp --db <PATH> stats    [--lobe <lobe>]
+  mvp --db <PATH> remember  <lobe> [--key <key>] <content>
+  mvp --db <PATH> reflect   <lobe> [window]
+  mvp --db <PATH> stats     [--lobe <lobe>]
+  mvp --db <PATH> precheck  <intent> <content>
 
 GLOBAL OPTIONS:
   --db <PATH>              SQLite file for Memory (required)
   --archive-root <DIR>     Optional: directory where Archivist stores CIDs (files)
   --archive-index <PATH>   Optional: SQLite file for Archivist index (defaults to <archive-root>/archive_index.sqlite3)
+  --json                   Emit structured JSON instead of plain text
 
 NOTES:
   - <content> can be '-' to read from STDIN.
   - If --key is omitted for 'remember', a timestamped key is generated.
+  - 'precheck' exits {EXIT_GATE_BLOCKED} when the gate decision is Block, 0 otherwise.
 "
     );
     std::process::exit(2);
@@ -53,6 +68,7 @@ fn main() -> ExitCode {
     let mut db_path: Option<String> = None;
     let mut archive_root: Option<PathBuf> = None;
     let mut archive_index: Option<PathBuf> = None;
+    let mut json = false;
 
     // Pull out global flags (consume pairs)
     let mut i = 0;
@@ -70,6 +86,10 @@ fn main() -> ExitCode {
                 archive_index = Some(PathBuf::from(args.remove(i + 1)));
                 args.remove(i);
             }
+            "--json" => {
+                json = true;
+                args.remove(i);
+            }
             _ => i += 1,
         }
     }
@@ -177,7 +197,11 @@ fn main() -> ExitCode {
 
             match cmds.remember(&lobe, key.as_deref(), &content) {
                 Ok(id) => {
-                    println!("{}", id);
+                    if json {
+                        println!("{}", json!({ "memory_id": id }));
+                    } else {
+                        println!("{}", id);
+                    }
                     ExitCode::SUCCESS
                 }
                 Err(e) => {
@@ -201,7 +225,11 @@ fn main() -> ExitCode {
 
             match cmds.reflect(&lobe, window) {
                 Ok(note) => {
-                    println!("{}", note);
+                    if json {
+                        println!("{}", json!({ "note": note }));
+                    } else {
+                        println!("{}", note);
+                    }
                     ExitCode::SUCCESS
                 }
                 Err(e) => {
@@ -225,9 +253,14 @@ fn main() -> ExitCode {
             }
             match cmds.stats(lobe.as_deref()) {
                 Ok(s) => {
-                    // lightweight JSON-ish print without adding serde_json here
-                    println!("{{\"total\":{},\"archived\":{},\"by_lobe\":{:?},\"last_updated\":{:?}}}",
-                        s.total, s.archived, s.by_lobe, s.last_updated);
+                    if json {
+                        println!("{}", serde_json::to_string(&s).expect("serialize Stats"));
+                    } else {
+                        println!(
+                            "{{\"total\":{},\"archived\":{},\"by_lobe\":{:?},\"last_updated\":{:?}}}",
+                            s.total, s.archived, s.by_lobe, s.last_updated
+                        );
+                    }
                     ExitCode::SUCCESS
                 }
                 Err(e) => {
@@ -237,6 +270,50 @@ fn main() -> ExitCode {
             }
         }
 
+        "precheck" => {
+            // precheck <intent> <content>
+            if args.len() < 2 {
+                eprintln!("error: precheck requires <intent> <content>");
+                return ExitCode::from(2);
+            }
+            let intent = args.remove(0);
+            let content_arg = args.remove(0);
+            let content = if content_arg == "-" {
+                let mut s = String::new();
+                if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut s) {
+                    eprintln!("error: reading stdin: {e}");
+                    return ExitCode::from(1);
+                }
+                s
+            } else {
+                content_arg
+            };
+
+            match precheck(&content, &intent) {
+                Ok(verdict) => {
+                    let decision = decision_gate(&verdict);
+                    if json {
+                        println!("{}", json!({ "verdict": verdict, "decision": decision }));
+                    } else {
+                        println!(
+                            "risk={} passed={} constraints={:?} reason={}",
+                            verdict.risk, verdict.passed, verdict.constraints, verdict.reason
+                        );
+                        println!("decision={decision:?}");
+                    }
+                    if decision == Decision::Block {
+                        ExitCode::from(EXIT_GATE_BLOCKED)
+                    } else {
+                        ExitCode::SUCCESS
+                    }
+                }
+                Err(e) => {
+                    eprintln!("error: precheck: {e}");
+                    ExitCode::from(1)
+                }
+            }
+        }
+
         _ => {
             eprintln!("error: unknown subcommand '{}'\n", cmd);
             usage();
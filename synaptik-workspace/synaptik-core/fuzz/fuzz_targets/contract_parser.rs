@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use synaptik_core::fuzz::check_contract_parse;
+
+// Proves the TOML -> MoralContract parse and evaluate_input_against_rules
+// path `precheck_text` relies on never panics on malformed rules.
+fuzz_target!(|data: &[u8]| {
+    check_contract_parse(data);
+});
@@ -0,0 +1,32 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use synaptik_core::commands::{ensure_initialized_once, Commands};
+use synaptik_core::fuzz::{decode_ops, next_run_prefix, run_ops};
+use synaptik_core::services::archivist::Archivist;
+use synaptik_core::services::memory::Memory;
+
+// Drives Memory + Archivist + Commands with a decoded operation stream and
+// asserts the cross-tier recall invariant `synaptik_core::fuzz::run_ops`
+// checks (see its doc comment). A caught `Err` here is a real violation --
+// not malformed input, since `decode_ops` never errors.
+fuzz_target!(|data: &[u8]| {
+    let Ok(report) = ensure_initialized_once() else {
+        return;
+    };
+    let db_path = report.config.memory.cache_path.clone();
+    let Ok(mem) = Memory::open(db_path.to_str().unwrap_or_default()) else {
+        return;
+    };
+    let Ok(arch) = Archivist::open(&report.config.memory.archive_path) else {
+        return;
+    };
+    let Ok(cmds) = Commands::new("ignored", None) else {
+        return;
+    };
+
+    let ops = decode_ops(data, 64);
+    let prefix = next_run_prefix();
+    run_ops(&mem, &arch, &cmds, &db_path, &prefix, &ops)
+        .expect("recall-parity invariant violated");
+});
@@ -1,13 +1,13 @@
 // src/commands/mod.rs
 use anyhow::{Result, anyhow};
 use serde::Serialize;
-use serde_json::{Value, json};
+use serde_json::json;
 use std::path::PathBuf;
 
 use crate::config::CoreConfig;
-use crate::memory::dag::MemoryState as DagMemoryState;
 use crate::services::archivist::Archivist;
 use crate::services::audit::{lock_contracts, record_action, unlock_contracts};
+use crate::services::cold_store::ColdStore;
 use crate::services::ethos::{Decision, decision_gate, precheck};
 use crate::services::{
     FinalizedStatus, LlmClient, StreamRuntime,
@@ -16,7 +16,8 @@ use crate::services::ethos::{ContractsDecider, Proposal};
 use crate::services::audit as audit_svc;
 use crate::services::librarian::{Librarian, LibrarianSettings};
 use crate::services::memory::Memory;
-use crate::utils::pons::{ObjectMetadata as PonsMetadata, ObjectRef as PonsObjectRef, PonsStore};
+use crate::services::memory_backend::MemoryBackend;
+use crate::utils::pons::PonsStore;
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
 
@@ -29,6 +30,9 @@ pub struct Commands {
     config: CoreConfig,
     root: PathBuf,
     pons_store: OnceCell<Arc<PonsStore>>, // lazily initialized, shared store
+    // Overrides `memory`'s row/snapshot bookkeeping (see `MemoryBackend`) when
+    // set via `CommandsBuilder::with_backend`; falls back to `memory` itself.
+    backend_override: Option<Box<dyn MemoryBackend>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -41,12 +45,36 @@ pub struct EthosReport {
     pub violation_code: Option<String>,
 }
 
+/// Result of [`Commands::govern_with_tools`]: the usual governance status
+/// plus which of the requested tools actually had a covering capability
+/// grant.
+#[derive(Debug, Serialize)]
+pub struct ToolGovernanceReport {
+    pub status: String,
+    pub text: Option<String>,
+    pub violation_label: Option<String>,
+    pub allowed_tools: Vec<String>,
+    pub denied_tools: Vec<String>,
+}
+
+/// Result of [`Commands::dag_scrub`]: every archived id checked in the
+/// requested lobe, bucketed by whether its blob was missing outright or
+/// present but corrupt (recomputed hash didn't match its CID).
+#[derive(Debug, Serialize)]
+pub struct DagScrubReport {
+    pub lobe: String,
+    pub examined: usize,
+    pub corrupted: Vec<String>,
+    pub missing: Vec<String>,
+}
+
 pub struct CommandsBuilder {
     config: CoreConfig,
     memory: Option<Memory>,
-    archivist: Option<Archivist>,
+    cold_store: Option<Arc<dyn ColdStore>>,
     librarian: Option<Librarian>,
     root: PathBuf,
+    backend: Option<Box<dyn MemoryBackend>>,
 }
 
 impl CommandsBuilder {
@@ -55,9 +83,10 @@ impl CommandsBuilder {
         Ok(Self {
             config: report.config.clone(),
             memory: None,
-            archivist: None,
+            cold_store: None,
             librarian: None,
             root: report.root.clone(),
+            backend: None,
         })
     }
 
@@ -72,7 +101,16 @@ impl CommandsBuilder {
     }
 
     pub fn with_archivist(mut self, archivist: Archivist) -> Self {
-        self.archivist = Some(archivist);
+        self.cold_store = Some(Arc::new(archivist));
+        self
+    }
+
+    /// Swap the `ColdStore` backend used for the cold tier -- the default
+    /// built from `config.memory.cold_store` is `Archivist` (filesystem);
+    /// pass an `S3ColdStore` (or any other impl) to point cold recall at a
+    /// remote store instead.
+    pub fn with_cold_store(mut self, cold_store: Arc<dyn ColdStore>) -> Self {
+        self.cold_store = Some(cold_store);
         self
     }
 
@@ -81,6 +119,18 @@ impl CommandsBuilder {
         self
     }
 
+    /// Swap the row/snapshot bookkeeping `Commands` uses (counts, lobe
+    /// grouping, recency, hot promotion, archived-cid pointer) for
+    /// something other than the SQLite `Memory` it's built with -- an
+    /// [`crate::services::memory_backend::InMemoryBackend`] in tests, or
+    /// any other [`MemoryBackend`] impl. Leaves `memory` itself wired up
+    /// for everything else (`remember`, `recall`, consent, contract
+    /// events), since those aren't part of this trait.
+    pub fn with_backend(mut self, backend: Box<dyn MemoryBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
     pub fn build(mut self) -> Result<Commands> {
         let memory = if let Some(memory) = self.memory.take() {
             memory
@@ -94,10 +144,13 @@ impl CommandsBuilder {
             Memory::open(db_path)?
         };
 
-        let archivist = if let Some(archivist) = self.archivist.take() {
-            Some(archivist)
+        let cold_store = if let Some(cold_store) = self.cold_store.take() {
+            Some(cold_store)
         } else if self.config.services.librarian_enabled {
-            Some(Archivist::open(&self.config.memory.archive_path)?)
+            Some(crate::services::cold_store::build_cold_store(
+                &self.config.memory.archive_path,
+                &self.config.memory.cold_store,
+            )?)
         } else {
             None
         };
@@ -109,7 +162,7 @@ impl CommandsBuilder {
                 &self.config.policies,
                 self.config.services.librarian_enabled,
             );
-            Librarian::new(archivist.clone(), settings)
+            Librarian::new(cold_store.clone(), settings)
         };
 
         Ok(Commands {
@@ -118,6 +171,7 @@ impl CommandsBuilder {
             config: self.config,
             root: self.root.clone(),
             pons_store: OnceCell::new(),
+            backend_override: self.backend.take(),
         })
     }
 }
@@ -130,9 +184,66 @@ impl Commands {
         Ok(Arc::clone(store_ref))
     }
 
+    /// The active [`MemoryBackend`]: whatever `CommandsBuilder::with_backend`
+    /// supplied, else `memory` itself (SQLite, via `commands::helpers`).
+    fn backend(&self) -> &dyn MemoryBackend {
+        self.backend_override
+            .as_deref()
+            .unwrap_or(&self.memory as &dyn MemoryBackend)
+    }
+
+    /// The active [`ColdStore`]: whatever `librarian` was built with
+    /// (`CommandsBuilder::with_cold_store`/`with_archivist`, or the backend
+    /// chosen by `config.memory.cold_store`), falling back to opening one
+    /// fresh from config if the librarian has none wired up (e.g.
+    /// `services.librarian_enabled = false`).
+    fn cold_store(&self) -> Result<Arc<dyn ColdStore>> {
+        if let Some(cold_store) = self.librarian.cold_store() {
+            return Ok(cold_store);
+        }
+        crate::services::cold_store::build_cold_store(
+            &self.config.memory.archive_path,
+            &self.config.memory.cold_store,
+        )
+    }
+
     /// Run content through the contract-enforced runtime. Returns sanitized text on success,
     /// or Ok(None) if the runtime stopped/escalated/violated (barrier applied).
     fn govern_text(&self, intent: &str, input: &str) -> Result<Option<String>> {
+        let result = self.govern_proposal(intent, input, &[])?;
+        match result.status {
+            FinalizedStatus::Ok => Ok(Some(result.text)),
+            FinalizedStatus::Stopped => {
+                audit_svc::record_action(
+                    "commands",
+                    "govern_stopped",
+                    &json!({"intent": intent}),
+                    "medium",
+                );
+                Ok(None)
+            }
+            FinalizedStatus::Escalated | FinalizedStatus::Violated => {
+                audit_svc::record_action(
+                    "commands",
+                    "govern_blocked",
+                    &json!({"intent": intent, "status": format!("{:?}", result.status)}),
+                    "high",
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Run a proposal (with optional requested tools) through the
+    /// contract-enforced runtime, echoing `input` back as the model's only
+    /// token so governance runs against it unchanged. Shared by
+    /// `govern_text` and `govern_with_tools`.
+    fn govern_proposal(
+        &self,
+        intent: &str,
+        input: &str,
+        tools_requested: &[String],
+    ) -> Result<crate::services::Finalized> {
         struct EchoStream { yielded: bool, text: String }
         impl Iterator for EchoStream {
             type Item = String;
@@ -152,34 +263,80 @@ impl Commands {
             intent: intent.to_string(),
             input: input.to_string(),
             prior: None,
-            tools_requested: vec![],
+            tools_requested: tools_requested.to_vec(),
         };
         let contract = ContractsDecider;
         let model = TextEchoModel { text: input.to_string() };
         let runtime = StreamRuntime { contract, model };
-        let result = runtime.generate(proposal).map_err(|e| anyhow!(e.0))?;
+        runtime.generate(proposal).map_err(|e| anyhow!(e.0))
+    }
 
-        match result.status {
-            FinalizedStatus::Ok => Ok(Some(result.text)),
-            FinalizedStatus::Stopped => {
-                audit_svc::record_action(
-                    "commands",
-                    "govern_stopped",
-                    &json!({"intent": intent}),
-                    "medium",
-                );
-                Ok(None)
-            }
-            FinalizedStatus::Escalated | FinalizedStatus::Violated => {
-                audit_svc::record_action(
-                    "commands",
-                    "govern_blocked",
-                    &json!({"intent": intent, "status": format!("{:?}", result.status)}),
-                    "high",
-                );
-                Ok(None)
-            }
+    /// Record a brand-new capability grant: `tool` may be used against any
+    /// resource matching `scope` (e.g. `"memory_storage"`, or `"*"` for
+    /// every intent), subject to `constraints`. Returns the grant's
+    /// content-addressed hash.
+    pub fn grant_capability(
+        &self,
+        tool: &str,
+        scope: &str,
+        constraints: serde_json::Value,
+    ) -> Result<String> {
+        crate::services::capability::grant_capability(tool, scope, constraints)
+    }
+
+    /// Derive a narrower capability from an existing grant (by hash).
+    /// Refuses to widen scope or drop a constraint the parent required.
+    pub fn attenuate_capability(
+        &self,
+        parent_hash: &str,
+        scope: Option<&str>,
+        constraints: Option<serde_json::Value>,
+    ) -> Result<String> {
+        crate::services::capability::attenuate_capability(parent_hash, scope, constraints)
+    }
+
+    /// Like `govern_text`, but also names the tools the caller intends to
+    /// invoke for `intent`: each is checked against the active capability
+    /// grant set, and any without a covering grant escalates the whole
+    /// proposal (`FinalizedStatus::Escalated`) rather than running it.
+    /// Returns the allowed/denied tool split alongside the usual status.
+    pub fn govern_with_tools(
+        &self,
+        intent: &str,
+        input: &str,
+        tools: &[String],
+    ) -> Result<ToolGovernanceReport> {
+        let result = self.govern_proposal(intent, input, tools)?;
+
+        let (allowed_tools, denied_tools) = if result.status == FinalizedStatus::Escalated {
+            let denied = crate::services::capability::unsatisfied(tools, intent)
+                .unwrap_or_else(|_| tools.to_vec());
+            let allowed = tools
+                .iter()
+                .filter(|t| !denied.contains(t))
+                .cloned()
+                .collect();
+            (allowed, denied)
+        } else {
+            (tools.to_vec(), Vec::new())
+        };
+
+        if result.status != FinalizedStatus::Ok {
+            audit_svc::record_action(
+                "commands",
+                "govern_with_tools_blocked",
+                &json!({"intent": intent, "status": format!("{:?}", result.status), "denied_tools": denied_tools}),
+                "high",
+            );
         }
+
+        Ok(ToolGovernanceReport {
+            status: format!("{:?}", result.status),
+            text: if result.status == FinalizedStatus::Ok { Some(result.text) } else { None },
+            violation_label: result.violation_label,
+            allowed_tools,
+            denied_tools,
+        })
     }
 
     // -------------------- Path/name helpers --------------------
@@ -195,6 +352,14 @@ impl Commands {
         crate::memory::dag::path_head_hash(path_name)
     }
 
+    /// Resolve an unambiguous short hex prefix (e.g. the first 8-12 chars
+    /// of a snapshot hash) to the one full snapshot hash it names. `recall`,
+    /// `diverge`, and head-update paths already accept a prefix directly;
+    /// this is for callers that want to resolve one up front.
+    pub fn resolve_snapshot_prefix(&self, prefix: &str) -> Result<String> {
+        crate::memory::dag::resolve_snapshot_prefix(prefix).map_err(|e| anyhow!(e.to_string()))
+    }
+
     /// Update a named path head to a specific snapshot hash.
     pub fn update_path_head(&self, path_name: &str, snapshot_hash: &str) -> Result<()> {
         let r = crate::memory::dag::set_path_head(path_name, snapshot_hash);
@@ -209,76 +374,190 @@ impl Commands {
         r
     }
 
-    // Keep the signature for now; ignore the args. Prefix with _ to silence warnings.
-    pub fn new(_db_path: &str, _archivist: Option<Archivist>) -> Result<Self> {
-        Self::builder()?.build()
+    /// Compact proof that `descendant_cid` descends from `ancestor_cid`,
+    /// shippable to a caller that only holds the two hashes and wants to
+    /// check the claim itself via [`Commands::verify_ancestry_proof`].
+    pub fn dag_ancestry_proof(
+        &self,
+        descendant_cid: &str,
+        ancestor_cid: &str,
+    ) -> Result<Option<crate::memory::dag::AncestryProof>> {
+        crate::memory::dag::dag_ancestry_proof(descendant_cid, ancestor_cid)
     }
 
-    pub fn builder() -> Result<CommandsBuilder> {
-        CommandsBuilder::from_environment()
+    /// Verify a [`Commands::dag_ancestry_proof`] result with no DAG or DB
+    /// access at all.
+    pub fn verify_ancestry_proof(
+        &self,
+        proof: &crate::memory::dag::AncestryProof,
+        descendant: &str,
+        ancestor: &str,
+    ) -> bool {
+        crate::memory::dag::verify_ancestry_proof(proof, descendant, ancestor)
     }
 
-    pub fn config(&self) -> &CoreConfig {
-        &self.config
+    /// [`Commands::dag_ancestry_proof`], but named by a path's current head
+    /// instead of an explicit descendant hash -- resolves `path_name`'s
+    /// head, then proves `snapshot_id` is one of its ancestors. Lets a
+    /// remote/light client trust a citation from `dag_cite_sources` without
+    /// holding the whole DAG.
+    pub fn path_ancestry_proof(
+        &self,
+        path_name: &str,
+        snapshot_id: &str,
+    ) -> Result<Option<crate::memory::dag::AncestryProof>> {
+        let Some(head) = self.dag_head(path_name)? else {
+            return Ok(None);
+        };
+        crate::memory::dag::dag_ancestry_proof(&head, snapshot_id)
     }
 
-    /// Ensure a pons namespace exists under the shared root.
-    pub fn pons_create(&self, pons: &str) -> Result<()> {
-        let store = self.pons_store()?;
-        store.create_pons(pons)
+    /// Verify a [`Commands::path_ancestry_proof`] result against the path
+    /// head it claims to start from. The proof already records its own
+    /// claimed ancestor as `links.last().cid`, so only `head_cid` (the
+    /// descendant) needs to be supplied.
+    pub fn verify_path_ancestry_proof(
+        &self,
+        head_cid: &str,
+        proof: &crate::memory::dag::AncestryProof,
+    ) -> Result<bool> {
+        let Some(ancestor) = proof.links.last() else {
+            return Ok(false);
+        };
+        Ok(crate::memory::dag::verify_ancestry_proof(
+            proof,
+            head_cid,
+            &ancestor.cid,
+        ))
     }
 
-    /// Write bytes plus optional metadata into a pons/key stream.
-    pub fn pons_put_object(
-        &self,
-        pons: &str,
-        key: &str,
-        data: &[u8],
-        media_type: Option<&str>,
-        extra: Option<Value>,
-    ) -> Result<PonsObjectRef> {
-        let store = self.pons_store()?;
-        // Filesystem paths are an internal detail of the Pons store.
-        // We expose only the content-addressed ObjectRef; callers shouldn't rely on on-disk paths.
-        let (obj, path) = store.put_object_with_meta(pons, key, data, media_type, extra)?;
-        let _ = path; // explicitly discard internal path to make intent clear
-        Ok(obj)
-    }
-
-    /// Read newest bytes for a pons/key.
-    pub fn pons_get_latest_bytes(&self, pons: &str, key: &str) -> Result<Vec<u8>> {
-        let store = self.pons_store()?;
-        store.get_object_latest(pons, key)
-    }
-
-    /// Fetch newest ObjectRef for a pons/key.
-    pub fn pons_get_latest_ref(&self, pons: &str, key: &str) -> Result<PonsObjectRef> {
-        let store = self.pons_store()?;
-        store.get_object_latest_ref(pons, key)
-    }
-
-    /// Fetch a specific version's bytes and metadata.
-    pub fn pons_get_version_with_meta(
+    /// Compact inclusion proof that `target_hash` belongs to `path_name`'s
+    /// history, self-checking and carrying its own provenance -- unlike
+    /// [`Commands::path_ancestry_proof`], a holder of only the head hash can
+    /// recover the cited sources via [`Commands::dag_verify_proof`] without
+    /// ever calling back into this store.
+    pub fn dag_prove_membership(
         &self,
-        pons: &str,
-        key: &str,
-        version: &str,
-    ) -> Result<(Vec<u8>, PonsMetadata)> {
-        let store = self.pons_store()?;
-        store.get_object_version_with_meta(pons, key, version)
+        path_name: &str,
+        target_hash: &str,
+    ) -> Result<Option<crate::memory::dag::MembershipProof>> {
+        crate::memory::dag::dag_prove_membership(path_name, target_hash)
     }
 
-    /// List the latest refs under a pons namespace.
-    pub fn pons_list_latest(
+    /// Verify a [`Commands::dag_prove_membership`] proof against the head
+    /// hash it claims to start from, with no DAG or DB access. Returns the
+    /// de-duplicated provenance sources proven reachable from `head_hash`,
+    /// or `None` if the proof doesn't check out.
+    pub fn dag_verify_proof(
         &self,
-        pons: &str,
-        prefix: Option<&str>,
-        limit: usize,
-    ) -> Result<Vec<PonsObjectRef>> {
-        let store = self.pons_store()?;
-        store.list_latest(pons, prefix, limit)
+        head_hash: &str,
+        proof: &crate::memory::dag::MembershipProof,
+    ) -> Result<Option<Vec<serde_json::Value>>> {
+        crate::memory::dag::dag_verify_proof(head_hash, proof)
+    }
+
+    /// Every current branch tip across the whole DAG, as reported by
+    /// [`crate::memory::dag::dag_list_leaves`]: `{hash, lobe, key, path,
+    /// depth}` per leaf.
+    pub fn dag_list_leaves(&self) -> Result<Vec<serde_json::Value>> {
+        crate::memory::dag::dag_list_leaves()
+    }
+
+    /// Fork-choice for a `(lobe, key)` stream: if only one leaf shares it,
+    /// that's the canonical head. If several do (forked via
+    /// `diverge_from`/`extend_path`), pick the one whose branch accumulates
+    /// the highest RL value -- summing `values.value` (the same `"values"`
+    /// table `services::memory::Memory::select_compaction_candidates`
+    /// already reads) over every node's memory id from leaf back to root.
+    /// Branches with no scored nodes accumulate 0. Returns `None` if the
+    /// stream currently has no live leaf.
+    pub fn dag_canonical_head(&self, lobe: &str, key: &str) -> Result<Option<String>> {
+        let leaves = crate::memory::dag::dag_list_leaves()?;
+        let matching: Vec<&serde_json::Value> = leaves
+            .iter()
+            .filter(|l| {
+                l.get("lobe").and_then(|v| v.as_str()) == Some(lobe)
+                    && l.get("key").and_then(|v| v.as_str()) == Some(key)
+            })
+            .collect();
+
+        let mut best: Option<(String, f64)> = None;
+        for leaf in matching {
+            let Some(hash) = leaf.get("hash").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let ids = crate::memory::dag::leaf_branch_ids(hash)?;
+            let mut total = 0.0f64;
+            for id in &ids {
+                if let Ok(v) = self.memory.db.query_row(
+                    "SELECT value FROM \"values\" WHERE state_id=?1",
+                    [id],
+                    |r| r.get::<_, f64>(0),
+                ) {
+                    total += v;
+                }
+            }
+            if best.as_ref().map(|(_, b)| total > *b).unwrap_or(true) {
+                best = Some((hash.to_string(), total));
+            }
+        }
+        Ok(best.map(|(h, _)| h))
+    }
+
+    /// Cheap fsck for the content-addressed archive tier: for every row in
+    /// `lobe` with an `archived_cid`, checks it's still present and still
+    /// hashes to itself, without ever loading a whole object into memory
+    /// ([`crate::services::archivist::Archivist::verify`]/`ColdStore::verify`
+    /// stream it). `exists()` runs first so a plain "never archived"/"GC'd"
+    /// gap is reported as missing rather than mistaken for corruption.
+    pub fn dag_scrub(&self, lobe: &str) -> Result<DagScrubReport> {
+        let cold_store = self.cold_store()?;
+        let mut stmt = self
+            .memory
+            .db
+            .prepare("SELECT memory_id, archived_cid FROM memories WHERE lobe=?1 AND archived_cid IS NOT NULL")?;
+        let rows: Vec<(String, String)> = stmt
+            .query_map([lobe], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut report = DagScrubReport {
+            lobe: lobe.to_string(),
+            examined: rows.len(),
+            corrupted: Vec::new(),
+            missing: Vec::new(),
+        };
+        for (memory_id, cid) in rows {
+            match cold_store.exists(&cid) {
+                Ok(true) => {
+                    if cold_store.verify(&cid).is_err() {
+                        report.corrupted.push(memory_id);
+                    }
+                }
+                Ok(false) => report.missing.push(memory_id),
+                Err(_) => report.missing.push(memory_id),
+            }
+        }
+        Ok(report)
+    }
+
+    // Keep the signature for now; ignore the args. Prefix with _ to silence warnings.
+    pub fn new(_db_path: &str, _archivist: Option<Archivist>) -> Result<Self> {
+        Self::builder()?.build()
     }
 
+    pub fn builder() -> Result<CommandsBuilder> {
+        CommandsBuilder::from_environment()
+    }
+
+    pub fn config(&self) -> &CoreConfig {
+        &self.config
+    }
+
+    // Pons object-store commands (`pons_create`, `pons_put_object`,
+    // `pons_get_latest_bytes`/`_ref`, `pons_get_version_with_meta`,
+    // `pons_list_latest`, and friends) live in `commands/pons.rs`.
+
     /// Gate arbitrary text with Ethos (for normal chat).
     pub fn precheck_text(&self, text: &str, purpose: &str) -> Result<EthosReport> {
         if !self.config.services.ethos_enabled {
@@ -320,10 +599,8 @@ impl Commands {
         })
     }
 
-    /// Newest → oldest memory_ids for a lobe.
-    pub fn recent(&self, lobe: &str, n: usize) -> Result<Vec<String>> {
-        recent_ids_in_lobe(&self.memory, lobe, n)
-    }
+    // `recent` now lives in `commands/recall.rs` alongside the rest of the
+    // recall surface.
 
     /// Recall full text (auto: hot → archive → dag). Returns just the content string.
     pub fn recall(&self, memory_id: &str) -> Result<Option<String>> {
@@ -403,8 +680,8 @@ impl Commands {
 
         // 2) AUTO-PROMOTE RULE (count-based)
         //    Hot = total - archived (we reuse existing tiny helpers here).
-        let total = count_rows(&self.memory, Some(lobe_eff))?;
-        let archived = count_archived(&self.memory, Some(lobe_eff))?;
+        let total = self.backend().count_rows(Some(lobe_eff))?;
+        let archived = self.backend().count_archived(Some(lobe_eff))?;
         let hot = total.saturating_sub(archived);
 
         // 2a) AUTO-PRUNE (exact duplicates) after every write to keep hot store clean.
@@ -421,7 +698,7 @@ impl Commands {
 
         let promote_threshold = self.config.policies.promote_hot_threshold as u64;
         if promote_threshold > 0 && hot >= promote_threshold {
-            if let Ok(promoted) = self.memory.promote_all_hot_in_lobe(lobe_eff) {
+            if let Ok(promoted) = self.backend().promote_all_hot_in_lobe(lobe_eff) {
                 record_action(
                     "commands",
                     "auto_promote_to_dag",
@@ -441,6 +718,25 @@ impl Commands {
             }
         }
 
+        // 2b) QUOTA ENFORCEMENT (byte-based): evict oldest hot rows in this
+        // lobe until under budget, independent of the count-based threshold.
+        if let Some(max_hot_bytes) = self.config.policies.max_hot_bytes_per_lobe {
+            let comp = crate::services::compactor::Compactor {
+                memory: &self.memory,
+                pons: None,
+            };
+            if let Ok(evicted) = comp.evict_to_quota(lobe_eff, max_hot_bytes) {
+                if evicted > 0 {
+                    record_action(
+                        "commands",
+                        "quota_evicted",
+                        &json!({"lobe": lobe_eff, "evicted": evicted, "max_hot_bytes": max_hot_bytes}),
+                        "low",
+                    );
+                }
+            }
+        }
+
         Ok(id)
     }
 
@@ -452,11 +748,15 @@ impl Commands {
             "low",
         );
 
-        let pool = self.memory.recent_summaries_by_lobe(lobe, window)?;
+        // recent_summaries_by_lobe returns newest-first; compute_reflection
+        // wants oldest-first so it can recency-weight by index.
+        let mut pool = self.memory.recent_summaries_by_lobe(lobe, window)?;
+        pool.reverse();
         let note = compute_reflection(
             &pool,
             self.config.policies.reflection_min_count,
             self.config.policies.reflection_max_keywords,
+            self.config.policies.reflection_recency_lambda,
         );
         if note.is_empty() {
             record_action(
@@ -512,10 +812,12 @@ impl Commands {
         record_action("commands", "stats_called", &json!({"lobe": lobe}), "low");
         let _ = precheck("stats_request", "metadata_access");
 
-        let total = count_rows(&self.memory, lobe)?;
-        let archived = count_archived(&self.memory, lobe)?;
-        let by_lobe = group_by_lobe(&self.memory, 20)?;
+        let total = self.backend().count_rows(lobe)?;
+        let archived = self.backend().count_archived(lobe)?;
+        let by_lobe = self.backend().group_by_lobe(20)?;
         let last_updated = max_updated(&self.memory)?;
+        let (bytes_hot, bytes_archived) = self.memory.byte_totals(lobe)?;
+        let total_weight = self.memory.lobe_weight_total(lobe)?;
         record_action(
             "commands",
             "stats_returned",
@@ -528,49 +830,20 @@ impl Commands {
             archived,
             by_lobe,
             last_updated,
+            bytes_hot,
+            bytes_archived,
+            dag_nodes: archived,
+            total_weight,
         })
     }
 
     // ---------------------------------------------------------------------
     // Replay (Rewind & Diverge) helpers exposed via Commands
     // ---------------------------------------------------------------------
-
-    /// Recall an immutable snapshot by content-addressed id (blake3 hex).
-    pub fn replay_recall_snapshot(&self, snapshot_id: &str) -> Result<DagMemoryState> {
-        // Read-only; no audit log to reduce noise.
-        self.memory.recall_snapshot(snapshot_id)
-    }
-
-    /// Create or reset a named path diverging from the given snapshot. Returns path_id.
-    pub fn replay_diverge_from(&self, snapshot_id: &str, path_name: &str) -> Result<String> {
-        let id = self.memory.diverge_from(snapshot_id, path_name)?;
-        record_action(
-            "commands",
-            "replay_diverge_from",
-            &json!({
-                "snapshot_id": snapshot_id,
-                "path_name": path_name,
-                "path_id": id
-            }),
-            "low",
-        );
-        Ok(id)
-    }
-
-    /// Append a new immutable snapshot to a named path and advance its head. Returns new hash.
-    pub fn replay_extend_path(&self, path_name: &str, state: DagMemoryState) -> Result<String> {
-        let new_id = self.memory.extend_path(path_name, state)?;
-        record_action(
-            "commands",
-            "replay_extend_path",
-            &json!({
-                "path_name": path_name,
-                "new_hash": new_id
-            }),
-            "low",
-        );
-        Ok(new_id)
-    }
+    //
+    // `replay_recall_snapshot`, `replay_diverge_from`, and `replay_extend_path`
+    // now live in `commands/replay.rs` alongside the rest of the replay/merge
+    // surface (`reconsolidate_paths`, `merge_paths`, `branch`, `append`).
 
     // ---------------------------------------------------------------------
     // High-level branch/append/consolidate APIs (idempotent, ethos-gated)
@@ -598,150 +871,18 @@ impl Commands {
         Ok(base)
     }
 
-    /// Fast-forward the target path to the source head.
-    /// Neuroscience: systems consolidation (stabilize the trace into 'cortex'/`dst_path`).
-    pub fn systems_consolidate(&self, src_path: &str, dst_path: &str) -> Result<String> {
-        let src_head = self.dag_head(src_path)?.ok_or(anyhow!("no src head"))?;
-        // If dst missing or behind: repoint head to src (FF). If already equal: noop.
-        if let Some(dst_head) = self.dag_head(dst_path)? {
-            if dst_head == src_head {
-                return Ok(src_head);
-            }
-            // Only fast-forward when ancestor; otherwise caller should request merge.
-            if crate::memory::dag::is_ancestor(&dst_head, &src_head)? {
-                self.update_path_head(dst_path, &src_head)?;
-            } else {
-                return Err(anyhow!("non-fast-forward: dst is not ancestor of src"));
-            }
-        } else {
-            // Create path at src head
-            self.update_path_head(dst_path, &src_head)?;
-        }
-        Ok(src_head)
-    }
-
-    /// Create a merge snapshot with parents [main_head, feature_head] and move main to it.
-    /// Neuroscience: reconsolidation—integrate multiple traces into one memory.
-    /// Note: DAG presently supports single-parent. Until merge nodes are supported, this returns an error
-    /// when a fast-forward is not possible.
-    pub fn reconsolidate_paths(
-        &self,
-        main_path: &str,
-        feature_path: &str,
-        _note: &str,
-    ) -> Result<String> {
-        let main_head = self.dag_head(main_path)?.ok_or(anyhow!("no main head"))?;
-        let feat_head = self
-            .dag_head(feature_path)?
-            .ok_or(anyhow!("no feature head"))?;
-        if main_head == feat_head {
-            return Ok(main_head);
-        }
-        if crate::memory::dag::is_ancestor(&main_head, &feat_head)? {
-            self.update_path_head(main_path, &feat_head)?;
-            return Ok(feat_head);
-        }
-        Err(anyhow!(
-            "merge commits not yet supported; non-FF reconsolidation blocked"
-        ))
-    }
-
-    /// Idempotent, normalized: create a branch at a resolved base.
-    /// base may be a snapshot hash or a path name; if None, lobe or 'main' are used.
-    pub fn branch(&self, path: &str, base: Option<&str>, lobe: Option<&str>) -> Result<String> {
-        let path_norm = self.normalize_path_name(path);
-        // If path exists already, return its recorded base snapshot id.
-        if crate::memory::dag::path_exists(&path_norm)? {
-            if let Some(b) = crate::memory::dag::path_base_snapshot(&path_norm)? {
-                return Ok(b);
-            }
-        }
-
-        // Resolve base: explicit hash or path, or by lobe/main fallback.
-        let resolved_base = if let Some(b) = base {
-            let b_norm = self.normalize_path_name(b);
-            // treat as path name if a path exists; else assume it's a cid
-            if crate::memory::dag::path_exists(&b_norm)? {
-                self.dag_head(&b_norm)?
-            } else {
-                Some(b.to_string())
-            }
-        } else if let Some(l) = lobe {
-            self.replay_base_from_lobe(l)?
-        } else if let Some(h) = self.dag_head("main")? {
-            Some(h)
-        } else {
-            self.replay_base_from_lobe("chat")?
-        }
-        .ok_or(anyhow!("no base available to branch from"))?;
+    // `systems_consolidate`, `reconsolidate_paths`, `branch`, and `append` now
+    // live in `commands/replay.rs`, where `reconsolidate_paths` does a real
+    // multi-parent merge (LCA + three-way bind) instead of only fast-forwarding.
 
-        let _ = self.replay_diverge_from(&resolved_base, &path_norm)?;
-        record_action(
-            "commands",
-            "branch_created",
-            &json!({ "path": path_norm, "base": resolved_base }),
-            "low",
-        );
-        Ok(resolved_base)
-    }
-
-    /// Append content to a named path with provenance and ethos gating.
-    pub fn append(&self, path: &str, content: &str, meta: Option<Value>) -> Result<String> {
-        let path_norm = self.normalize_path_name(path);
-        if !crate::memory::dag::path_exists(&path_norm)? {
-            return Err(anyhow!(format!(
-                "path '{}' not found; call branch() first",
-                path_norm
-            )));
-        }
-
-        // Governance: runtime enforcement for append content
-        let governed_text = if self.config.services.ethos_enabled {
-            match self.govern_text("replay_append", content) {
-                Ok(Some(s)) => s,
-                Ok(None) => return Err(anyhow!("blocked by runtime")),
-                Err(e) => return Err(anyhow!("runtime error: {}", e)),
-            }
-        } else {
-            content.to_string()
-        };
-
-        let parent = self.dag_head(&path_norm)?;
-        let base = crate::memory::dag::path_base_snapshot(&path_norm)?;
-        let enrich = json!({
-            "op": "append",
-            "ts": chrono::Utc::now().to_rfc3339(),
-            "actor": "core",
-            "path": path_norm,
-            "parents": parent.clone().into_iter().collect::<Vec<_>>() ,
-            "base": base,
-            "content_hash": blake3::hash(governed_text.as_bytes()).to_hex().to_string(),
-        });
-        let merged_meta = match meta.unwrap_or_else(|| json!({})) {
-            Value::Object(mut m) => {
-                if let Value::Object(e) = enrich {
-                    m.extend(e);
-                }
-                Value::Object(m)
-            }
-            _ => enrich,
-        };
-        let state = DagMemoryState {
-            content: governed_text,
-            meta: merged_meta,
-        };
-        let id = self.replay_extend_path(&path_norm, state)?;
-        Ok(id)
-    }
-
-    /// Fast-forward if possible; else no-op with error until merges are supported.
+    /// Fast-forward if possible; else merge via [`Commands::reconsolidate_paths`].
     pub fn consolidate(&self, src_path: &str, dst_path: &str) -> Result<String> {
         self.systems_consolidate(src_path, dst_path)
     }
 
-    /// Placeholder for future two-parent merge support. Errors today if non-FF.
+    /// Merge `src_path` into `dst_path`, creating a two-parent snapshot when a
+    /// fast-forward isn't possible. See [`Commands::reconsolidate_paths`].
     pub fn merge(&self, src_path: &str, dst_path: &str, note: &str) -> Result<String> {
-        let _ = note; // reserved for future merge-commit message
         self.reconsolidate_paths(dst_path, src_path, note)
     }
 
@@ -780,6 +921,61 @@ impl Commands {
         crate::memory::dag::search_content_words(&words, limit)
     }
 
+    /// Render the DAG reachable from a path's head (or an explicit
+    /// `root_snapshot`) as a Graphviz `digraph` string, up to `limit` nodes.
+    /// `dag_trace_path`/`dag_cite_sources` only hand back JSON arrays, which
+    /// is awkward for seeing branch/diverge shape at a glance; this is meant
+    /// to be piped straight into `dot -Tsvg`.
+    ///
+    /// Nodes are colored by the same hot/archive/dag tiering `recall_any`
+    /// uses, read-only here (a hit only checks presence, it never restores
+    /// from the archive the way an actual recall would). Divergence points
+    /// (a snapshot with more than one child) render as diamonds.
+    pub fn dag_export_dot(
+        &self,
+        path_name: Option<&str>,
+        root_snapshot: Option<&str>,
+        limit: usize,
+    ) -> Result<String> {
+        let mut nodes = crate::memory::dag::walk_for_dot(path_name, root_snapshot, limit)?;
+        for node in nodes.iter_mut() {
+            node.color = Some(self.dot_tier_color(&node.id)?);
+        }
+        Ok(crate::memory::dag::render_dot(
+            &nodes,
+            crate::memory::dag::GraphKind::Digraph,
+        ))
+    }
+
+    /// Render every DAG node tagged with `lobe` as a Graphviz `digraph`,
+    /// newest-first. Unlike `dag_export_dot`, this isn't a single-head
+    /// ancestry walk -- a lobe can span several named paths, or none -- so
+    /// it scans every known node for the matching `lobe` field instead.
+    pub fn dag_export_dot_lobe(&self, lobe: &str, limit: usize) -> Result<String> {
+        let mut nodes = crate::memory::dag::walk_for_dot_lobe(lobe, limit)?;
+        for node in nodes.iter_mut() {
+            node.color = Some(self.dot_tier_color(&node.id)?);
+        }
+        Ok(crate::memory::dag::render_dot(
+            &nodes,
+            crate::memory::dag::GraphKind::Digraph,
+        ))
+    }
+
+    /// Read-only hot/archive/dag tier color for one node's memory id, for
+    /// `dag_export_dot`.
+    fn dot_tier_color(&self, memory_id: &str) -> Result<String> {
+        if !memory_id.is_empty() {
+            if self.memory.recall(memory_id)?.is_some() {
+                return Ok("lightgreen".to_string());
+            }
+            if self.backend().get_archived_cid(memory_id)?.is_some() {
+                return Ok("lightyellow".to_string());
+            }
+        }
+        Ok("lightgrey".to_string())
+    }
+
     /// Prune exact duplicates. If `lobe` is Some, prunes within that lobe; otherwise all lobes.
     pub fn prune_duplicates(&self, lobe: Option<&str>) -> Result<usize> {
         let total = if let Some(l) = lobe {
@@ -897,8 +1093,8 @@ impl Commands {
     /// Returns Some(cid) if ensured, None if the memory could not be found.
     pub fn ensure_archive_for(&self, memory_id: &str) -> Result<Option<String>> {
         // If CID already set, ensure the blob exists; if missing, reconstruct from hot or DAG.
-        if let Some(existing_cid) = self.memory.get_archived_cid(memory_id)? {
-            let arch = Archivist::open(&self.config.memory.archive_path)?;
+        if let Some(existing_cid) = self.backend().get_archived_cid(memory_id)? {
+            let arch = self.cold_store()?;
             match arch.retrieve(&existing_cid) {
                 Ok(bytes) => {
                     // Re-cache hot under original lobe/key if possible
@@ -929,11 +1125,18 @@ impl Commands {
             None => crate::memory::dag::content_by_id(memory_id)?.map(|s| s.into_bytes()),
         };
         if let Some(bytes) = bytes_opt {
-            // Write archive blob and set DB pointer (open archivist at canonical path)
-            let arch = Archivist::open(&self.config.memory.archive_path)?;
+            // Write archive blob and set DB pointer via the configured cold store
+            let arch = self.cold_store()?;
             let cid = arch.archive(memory_id, &bytes)?;
             let now = chrono::Utc::now().to_rfc3339();
-            self.memory.mark_archived(memory_id, &cid, &now)?;
+            self.backend().mark_archived(memory_id, &cid, &now)?;
+            let lobe = self
+                .memory
+                .lobe_key(memory_id)?
+                .map(|(l, _)| l)
+                .unwrap_or_else(|| "unknown".to_string());
+            self.memory
+                .accrue_weight(&lobe, crate::services::weight::op_weight(bytes.len()))?;
             return Ok(Some(cid));
         }
         Ok(None)
@@ -1043,8 +1246,50 @@ fn parse_prefer(s: Option<&str>) -> Prefer {
     }
 }
 
-/// Tiny, deterministic keyword theme line (command-level helper).
-fn compute_reflection(summaries: &[String], min_count: usize, max_keywords: usize) -> String {
+/// Deterministic keyword theme line (command-level helper), scored by
+/// recency-weighted TF-IDF rather than raw frequency so that terms which
+/// recur recently and distinctively outrank generic boilerplate.
+///
+/// `summaries` must be oldest-first (index `0` = oldest, `n-1` = newest);
+/// `recency_lambda` controls how sharply older summaries are discounted —
+/// see [`recency_weighted_tfidf`].
+fn compute_reflection(
+    summaries: &[String],
+    min_count: usize,
+    max_keywords: usize,
+    recency_lambda: f64,
+) -> String {
+    let toks = recency_weighted_tfidf(summaries, min_count, recency_lambda);
+    let mut toks = toks;
+    toks.truncate(max_keywords);
+    if toks.is_empty() {
+        return String::new();
+    }
+    let joined = toks
+        .into_iter()
+        .map(|(t, score)| format!("{t}({score:.2})"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("Recurring themes: {joined}")
+}
+
+/// Score every token across `summaries` (oldest-first) by recency-weighted
+/// TF-IDF, treating each summary as a document:
+///
+/// - `w_i = exp(-lambda * (n-1-i))` — decay weight for summary index `i`.
+/// - `tf_weighted(t) = sum of w_i over summaries containing t`.
+/// - `df(t)` = number of summaries containing `t`.
+/// - `idf(t) = ln((n + 1) / (df(t) + 1)) + 1`.
+/// - `score(t) = tf_weighted(t) * idf(t)`.
+///
+/// Tokens below `min_count` raw occurrences (across all summaries) are
+/// dropped before scoring. Returns tokens sorted by descending score, ties
+/// broken by ascending lexical order, so the result is fully deterministic.
+fn recency_weighted_tfidf(
+    summaries: &[String],
+    min_count: usize,
+    recency_lambda: f64,
+) -> Vec<(String, f64)> {
     use std::collections::HashMap;
     const STOP: &[&str] = &[
         "the", "and", "for", "with", "that", "this", "from", "have", "are", "was", "were", "you",
@@ -1053,29 +1298,49 @@ fn compute_reflection(summaries: &[String], min_count: usize, max_keywords: usiz
         "might", "should",
     ];
 
-    let mut freq: HashMap<String, usize> = HashMap::new();
-    for s in summaries {
+    let n = summaries.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut raw_count: HashMap<String, usize> = HashMap::new();
+    let mut tf_weighted: HashMap<String, f64> = HashMap::new();
+    let mut doc_freq: HashMap<String, usize> = HashMap::new();
+
+    for (i, s) in summaries.iter().enumerate() {
+        let weight = (-recency_lambda * (n - 1 - i) as f64).exp();
+        let mut seen_in_doc: std::collections::HashSet<String> = std::collections::HashSet::new();
         for t in s.split(|c: char| !c.is_alphanumeric()) {
             let t = t.to_lowercase();
             if t.len() < 3 || STOP.contains(&t.as_str()) {
                 continue;
             }
-            *freq.entry(t).or_insert(0) += 1;
+            *raw_count.entry(t.clone()).or_insert(0) += 1;
+            *tf_weighted.entry(t.clone()).or_insert(0.0) += weight;
+            seen_in_doc.insert(t);
+        }
+        for t in seen_in_doc {
+            *doc_freq.entry(t).or_insert(0) += 1;
         }
     }
-    let mut toks: Vec<(String, usize)> =
-        freq.into_iter().filter(|(_, c)| *c >= min_count).collect();
-    toks.sort_by(|a, b| b.1.cmp(&a.1));
-    toks.truncate(max_keywords);
-    if toks.is_empty() {
-        return String::new();
-    }
-    let joined = toks
+
+    let mut scored: Vec<(String, f64)> = tf_weighted
         .into_iter()
-        .map(|(t, c)| format!("{t}({c})"))
-        .collect::<Vec<_>>()
-        .join(", ");
-    format!("Recurring themes: {joined}")
+        .filter(|(t, _)| raw_count.get(t).copied().unwrap_or(0) >= min_count)
+        .map(|(t, tf)| {
+            let df = doc_freq.get(&t).copied().unwrap_or(0) as f64;
+            let idf = ((n as f64 + 1.0) / (df + 1.0)).ln() + 1.0;
+            let score = tf * idf;
+            (t, score)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    scored
 }
 
 #[derive(Debug, Serialize)]
@@ -1084,6 +1349,15 @@ pub struct Stats {
     pub archived: u64,
     pub by_lobe: Vec<(String, u64)>,
     pub last_updated: Option<String>,
+    /// Total content bytes across non-archived rows (optionally lobe-scoped).
+    pub bytes_hot: u64,
+    /// Total content bytes across archived rows (optionally lobe-scoped).
+    pub bytes_archived: u64,
+    /// Alias of `archived`, named to match the hot/archive/dag tier vocabulary
+    /// used elsewhere in `stats` and `recall_any`.
+    pub dag_nodes: u64,
+    /// Sum of `services::weight::op_weight` costs accrued so far (optionally lobe-scoped).
+    pub total_weight: u64,
 }
 
 // ---------- tiny SQL helpers (read-only) ----------
@@ -1100,63 +1374,10 @@ fn latest_id_in_lobe(memory: &Memory, lobe: &str) -> Result<Option<String>> {
     Ok(None)
 }
 
-fn recent_ids_in_lobe(memory: &Memory, lobe: &str, limit: usize) -> Result<Vec<String>> {
-    let mut stmt = memory.db.prepare(
-        "SELECT memory_id
-         FROM memories
-         WHERE lobe = ?1
-         ORDER BY updated_at DESC
-         LIMIT ?2",
-    )?;
-    let rows = stmt.query_map((lobe, limit as i64), |r| r.get::<_, String>(0))?;
-    let mut out = Vec::new();
-    for r in rows {
-        out.push(r?);
-    }
-    Ok(out)
-}
-
-fn count_rows(memory: &Memory, lobe: Option<&str>) -> Result<u64> {
-    let sql = match lobe {
-        Some(_) => "SELECT COUNT(*) FROM memories WHERE lobe=?1",
-        None => "SELECT COUNT(*) FROM memories",
-    };
-    let mut stmt = memory.db.prepare(sql)?;
-    let cnt: i64 = match lobe {
-        Some(l) => stmt.query_row([l], |r| r.get(0))?,
-        None => stmt.query_row([], |r| r.get(0))?,
-    };
-    Ok(cnt as u64)
-}
-
-fn count_archived(memory: &Memory, lobe: Option<&str>) -> Result<u64> {
-    let sql = match lobe {
-        Some(_) => "SELECT COUNT(*) FROM memories WHERE lobe=?1 AND archived_cid IS NOT NULL",
-        None => "SELECT COUNT(*) FROM memories WHERE archived_cid IS NOT NULL",
-    };
-    let mut stmt = memory.db.prepare(sql)?;
-    let cnt: i64 = match lobe {
-        Some(l) => stmt.query_row([l], |r| r.get(0))?,
-        None => stmt.query_row([], |r| r.get(0))?,
-    };
-    Ok(cnt as u64)
-}
-
-fn group_by_lobe(memory: &Memory, limit: usize) -> Result<Vec<(String, u64)>> {
-    let mut stmt = memory.db.prepare(
-        "SELECT lobe, COUNT(*) as c FROM memories GROUP BY lobe ORDER BY c DESC LIMIT ?1",
-    )?;
-    let rows = stmt.query_map([limit as i64], |r| {
-        let l: String = r.get(0)?;
-        let c: i64 = r.get(1)?;
-        Ok((l, c as u64))
-    })?;
-    let mut out = Vec::new();
-    for r in rows {
-        out.push(r?);
-    }
-    Ok(out)
-}
+// `recent_ids_in_lobe`, `count_rows`, `count_archived`, and `group_by_lobe`
+// now live behind `Commands::backend` (a `MemoryBackend`, see
+// `services::memory_backend` and `commands::helpers`), so a non-SQLite
+// backend can be wired in via `CommandsBuilder::with_backend`.
 
 fn max_updated(memory: &Memory) -> Result<Option<String>> {
     let mut stmt = memory.db.prepare("SELECT MAX(updated_at) FROM memories")?;
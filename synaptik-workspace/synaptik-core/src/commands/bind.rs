@@ -1,8 +1,12 @@
-/// Minimal three-way bind at line granularity.
+/// Three-way bind at line granularity, using a real diff3 merge rather than
+/// whole-string equality.
 /// - If both sides equal → keep
 /// - If left == base → take right
 /// - If right == base → take left
-/// - Else emit conflict block with Git-style markers
+/// - Else: diff base→left and base→right independently, walk them in lockstep
+///   over shared (unchanged) base lines, and for every span where the sides
+///   diverge, take the side that actually changed — only emitting Git-style
+///   conflict markers when both sides changed the same span differently.
 pub fn three_way_bind_lines(base: &str, left: &str, right: &str) -> (String, bool) {
     if left == right {
         return (left.to_string(), false);
@@ -14,17 +18,123 @@ pub fn three_way_bind_lines(base: &str, left: &str, right: &str) -> (String, boo
         return (left.to_string(), false);
     }
 
-    let mut out = String::new();
-    out.push_str("<<<<<<< LEFT\n");
-    out.push_str(left);
-    if !left.ends_with('\n') {
-        out.push('\n');
+    let base_lines = split_lines(base);
+    let left_lines = split_lines(left);
+    let right_lines = split_lines(right);
+
+    let matches_left = lcs_matches(&base_lines, &left_lines);
+    let matches_right = lcs_matches(&base_lines, &right_lines);
+
+    // Anchors: base indices that matched unchanged into *both* left and right.
+    let left_by_base: std::collections::HashMap<usize, usize> =
+        matches_left.iter().copied().collect();
+    let right_by_base: std::collections::HashMap<usize, usize> =
+        matches_right.iter().copied().collect();
+    let mut anchors: Vec<usize> = left_by_base
+        .keys()
+        .copied()
+        .filter(|bi| right_by_base.contains_key(bi))
+        .collect();
+    anchors.sort_unstable();
+
+    let mut out_lines: Vec<String> = Vec::new();
+    let mut had_conflict = false;
+    let (mut pb, mut pl, mut pr) = (0usize, 0usize, 0usize);
+
+    let mut flush_chunk = |pb: usize,
+                           bi: usize,
+                           pl: usize,
+                           li: usize,
+                           pr: usize,
+                           ri: usize,
+                           out: &mut Vec<String>,
+                           had_conflict: &mut bool| {
+        let base_chunk = &base_lines[pb..bi];
+        let left_chunk = &left_lines[pl..li];
+        let right_chunk = &right_lines[pr..ri];
+
+        if left_chunk == right_chunk {
+            out.extend(left_chunk.iter().cloned());
+        } else if left_chunk == base_chunk {
+            out.extend(right_chunk.iter().cloned());
+        } else if right_chunk == base_chunk {
+            out.extend(left_chunk.iter().cloned());
+        } else {
+            *had_conflict = true;
+            out.push("<<<<<<< LEFT".to_string());
+            out.extend(left_chunk.iter().cloned());
+            out.push("=======".to_string());
+            out.extend(right_chunk.iter().cloned());
+            out.push(">>>>>>> RIGHT".to_string());
+        }
+    };
+
+    for bi in anchors {
+        let li = left_by_base[&bi];
+        let ri = right_by_base[&bi];
+        flush_chunk(pb, bi, pl, li, pr, ri, &mut out_lines, &mut had_conflict);
+        out_lines.push(base_lines[bi].clone());
+        pb = bi + 1;
+        pl = li + 1;
+        pr = ri + 1;
     }
-    out.push_str("=======\n");
-    out.push_str(right);
-    if !right.ends_with('\n') {
+    flush_chunk(
+        pb,
+        base_lines.len(),
+        pl,
+        left_lines.len(),
+        pr,
+        right_lines.len(),
+        &mut out_lines,
+        &mut had_conflict,
+    );
+
+    let mut out = out_lines.join("\n");
+    if !out.is_empty() {
         out.push('\n');
     }
-    out.push_str(">>>>>>> RIGHT\n");
-    (out, true)
+    (out, had_conflict)
+}
+
+fn split_lines(s: &str) -> Vec<String> {
+    if s.is_empty() {
+        return Vec::new();
+    }
+    s.lines().map(|l| l.to_string()).collect()
+}
+
+/// Longest-common-subsequence matching between two line slices, returned as
+/// `(a_index, b_index)` pairs for each matched (equal) line, in ascending
+/// order of both indices. Classic O(n*m) DP + backtrace; fine at line-level
+/// granularity for the memory/contract file sizes this binds over.
+fn lcs_matches(a: &[String], b: &[String]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+    let mut dp = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if a[i] == b[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
 }
@@ -2,36 +2,80 @@ use anyhow::Result;
 
 use crate::services::memory::Memory;
 
-/// Tiny, deterministic keyword theme line (command-level helper).
-pub fn compute_reflection(summaries: &[String], min_count: usize, max_keywords: usize) -> String {
+/// Deterministic keyword theme line (command-level helper), scored by
+/// recency-weighted TF-IDF rather than raw frequency so that terms which
+/// recur recently and distinctively outrank generic boilerplate.
+///
+/// `summaries` must be oldest-first (index `0` = oldest, `n-1` = newest);
+/// `recency_lambda` controls how sharply older summaries are discounted:
+/// `w_i = exp(-lambda * (n-1-i))`.
+/// Shared across every keyword/search scorer in this crate (reflection
+/// themes here, BM25 DAG content search in `memory::dag`) so tokenization
+/// behaves the same everywhere a human reads the results.
+pub(crate) const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "have", "are", "was", "were", "you",
+    "your", "but", "not", "into", "over", "under", "then", "than", "there", "about", "just",
+    "like", "they", "them", "their", "will", "would", "could", "has", "had", "can", "may",
+    "might", "should",
+];
+
+pub fn compute_reflection(
+    summaries: &[String],
+    min_count: usize,
+    max_keywords: usize,
+    recency_lambda: f64,
+) -> String {
     use std::collections::HashMap;
-    const STOP: &[&str] = &[
-        "the", "and", "for", "with", "that", "this", "from", "have", "are", "was", "were", "you",
-        "your", "but", "not", "into", "over", "under", "then", "than", "there", "about", "just",
-        "like", "they", "them", "their", "will", "would", "could", "has", "had", "can", "may",
-        "might", "should",
-    ];
-
-    let mut freq: HashMap<String, usize> = HashMap::new();
-    for s in summaries {
+    const STOP: &[&str] = STOPWORDS;
+
+    let n = summaries.len();
+    if n == 0 {
+        return String::new();
+    }
+
+    let mut raw_count: HashMap<String, usize> = HashMap::new();
+    let mut tf_weighted: HashMap<String, f64> = HashMap::new();
+    let mut doc_freq: HashMap<String, usize> = HashMap::new();
+
+    for (i, s) in summaries.iter().enumerate() {
+        let weight = (-recency_lambda * (n - 1 - i) as f64).exp();
+        let mut seen_in_doc: std::collections::HashSet<String> = std::collections::HashSet::new();
         for t in s.split(|c: char| !c.is_alphanumeric()) {
             let t = t.to_lowercase();
             if t.len() < 3 || STOP.contains(&t.as_str()) {
                 continue;
             }
-            *freq.entry(t).or_insert(0) += 1;
+            *raw_count.entry(t.clone()).or_insert(0) += 1;
+            *tf_weighted.entry(t.clone()).or_insert(0.0) += weight;
+            seen_in_doc.insert(t);
+        }
+        for t in seen_in_doc {
+            *doc_freq.entry(t).or_insert(0) += 1;
         }
     }
-    let mut toks: Vec<(String, usize)> =
-        freq.into_iter().filter(|(_, c)| *c >= min_count).collect();
-    toks.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let mut toks: Vec<(String, f64)> = tf_weighted
+        .into_iter()
+        .filter(|(t, _)| raw_count.get(t).copied().unwrap_or(0) >= min_count)
+        .map(|(t, tf)| {
+            let df = doc_freq.get(&t).copied().unwrap_or(0) as f64;
+            let idf = ((n as f64 + 1.0) / (df + 1.0)).ln() + 1.0;
+            (t, tf * idf)
+        })
+        .collect();
+
+    toks.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
     toks.truncate(max_keywords);
     if toks.is_empty() {
         return String::new();
     }
     let joined = toks
         .into_iter()
-        .map(|(t, c)| format!("{t}({c})"))
+        .map(|(t, score)| format!("{t}({score:.2})"))
         .collect::<Vec<_>>()
         .join(", ");
     format!("Recurring themes: {joined}")
@@ -123,3 +167,117 @@ pub fn max_updated(memory: &Memory) -> Result<Option<String>> {
     Ok(None)
 }
 
+/// Wires the SQLite-backed `Memory` up as a
+/// [`MemoryBackend`](crate::services::memory_backend::MemoryBackend): the
+/// count/group/recency queries just forward to the free functions above,
+/// and `promote_all_hot_in_lobe`/`get_archived_cid`/`mark_archived` forward
+/// to `Memory`'s own methods of the same name.
+impl crate::services::memory_backend::MemoryBackend for Memory {
+    fn count_rows(&self, lobe: Option<&str>) -> Result<u64> {
+        count_rows(self, lobe)
+    }
+
+    fn count_archived(&self, lobe: Option<&str>) -> Result<u64> {
+        count_archived(self, lobe)
+    }
+
+    fn group_by_lobe(&self, limit: usize) -> Result<Vec<(String, u64)>> {
+        group_by_lobe(self, limit)
+    }
+
+    fn recent_ids_in_lobe(&self, lobe: &str, limit: usize) -> Result<Vec<String>> {
+        recent_ids_in_lobe(self, lobe, limit)
+    }
+
+    fn promote_all_hot_in_lobe(&self, lobe: &str) -> Result<Vec<(String, String)>> {
+        Memory::promote_all_hot_in_lobe(self, lobe)
+    }
+
+    fn get_archived_cid(&self, memory_id: &str) -> Result<Option<String>> {
+        Memory::get_archived_cid(self, memory_id)
+    }
+
+    fn mark_archived(&self, memory_id: &str, cid: &str, archived_at: &str) -> Result<()> {
+        Memory::mark_archived(self, memory_id, cid, archived_at)
+    }
+}
+
+// ---------- contract_events rollups (read-only) ----------
+
+/// Count violations per `violation_code` within `[window_start, window_end)`
+/// (RFC3339 UTC), sorted descending by count. Mirrors [`group_by_lobe`]'s
+/// grouped-count shape.
+pub fn count_violations_by_code(
+    memory: &Memory,
+    window_start: &str,
+    window_end: &str,
+) -> Result<Vec<(String, u64)>> {
+    let mut stmt = memory.db.prepare(
+        "SELECT violation_code, COUNT(*) as c
+         FROM contract_events
+         WHERE passed = 0
+           AND violation_code IS NOT NULL
+           AND timestamp >= ?1 AND timestamp < ?2
+         GROUP BY violation_code
+         ORDER BY c DESC",
+    )?;
+    let rows = stmt.query_map((window_start, window_end), |r| {
+        let code: String = r.get(0)?;
+        let c: i64 = r.get(1)?;
+        Ok((code, c as u64))
+    })?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r?);
+    }
+    Ok(out)
+}
+
+/// Count evaluations per `severity` bucket within `[window_start,
+/// window_end)` (RFC3339 UTC), sorted descending by count. Only rows with a
+/// non-NULL severity (i.e. rows that violated something) are counted.
+pub fn count_by_severity(
+    memory: &Memory,
+    window_start: &str,
+    window_end: &str,
+) -> Result<Vec<(String, u64)>> {
+    let mut stmt = memory.db.prepare(
+        "SELECT severity, COUNT(*) as c
+         FROM contract_events
+         WHERE severity IS NOT NULL
+           AND timestamp >= ?1 AND timestamp < ?2
+         GROUP BY severity
+         ORDER BY c DESC",
+    )?;
+    let rows = stmt.query_map((window_start, window_end), |r| {
+        let sev: String = r.get(0)?;
+        let c: i64 = r.get(1)?;
+        Ok((sev, c as u64))
+    })?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r?);
+    }
+    Ok(out)
+}
+
+/// Most recent `violation_code`s across all contract events, newest first.
+/// Parallels [`recent_ids_in_lobe`].
+pub fn recent_violation_codes(memory: &Memory, limit: usize) -> Result<Vec<String>> {
+    let mut stmt = memory.db.prepare(
+        "SELECT violation_code
+         FROM contract_events
+         WHERE violation_code IS NOT NULL
+         ORDER BY event_id DESC
+         LIMIT ?1",
+    )?;
+    use std::convert::TryFrom;
+    let limit_i64 = i64::try_from(limit).map_err(|_| anyhow::anyhow!("limit out of range for i64: {limit}"))?;
+    let rows = stmt.query_map([limit_i64], |r| r.get::<_, String>(0))?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r?);
+    }
+    Ok(out)
+}
+
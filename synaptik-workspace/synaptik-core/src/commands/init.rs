@@ -3,14 +3,15 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
 use once_cell::sync::OnceCell;
-use serde_json::json;
+use serde_json::{Value, json};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use contracts::assets::write_default_contracts;
 
-use crate::config::{CoreConfig, LogbookConfig};
+use crate::config::{AuditConfig, CoreConfig, LogbookConfig, ProvenanceEntry};
+use crate::services::logbook;
 
 #[derive(Debug, Clone)]
 pub struct InitReport {
@@ -18,6 +19,11 @@ pub struct InitReport {
     pub created: Vec<String>,
     pub existed: Vec<String>,
     pub config: CoreConfig,
+    /// Which layer/file set each final config key -- see
+    /// [`CoreConfig::load_layered`]. Surfaced so a surprising setting
+    /// (e.g. from `config.local.toml` or a `COGNIV_*` env var) can be
+    /// traced back to its source.
+    pub provenance: Vec<ProvenanceEntry>,
 }
 
 // ---------- single global init gate ----------
@@ -66,8 +72,17 @@ pub fn ensure_initialized() -> Result<InitReport> {
         &mut existed,
     )?;
 
-    // Load configuration (relative paths are resolved against root)
-    let config = CoreConfig::load(&root)?;
+    // Load configuration (relative paths are resolved against root); layers
+    // in `config.local.toml` / `COGNIV_*` env vars over the committed
+    // defaults and `config.toml`, tracking where each key came from.
+    let (config, provenance) = CoreConfig::load_layered(&root)?;
+    for entry in provenance.iter().filter(|e| e.layer != "default") {
+        tracing::info!(
+            "config key {} set by {} (overrides committed default)",
+            entry.key,
+            entry.layer
+        );
+    }
 
     // Derived directories from config
     ensure_parent_dir_abs(&config.memory.cache_path, &mut created, &mut existed)?;
@@ -83,14 +98,15 @@ pub fn ensure_initialized() -> Result<InitReport> {
     // Seed default contracts from the contracts crate (idempotent)
     let _ = write_default_contracts(&config.contracts.path);
 
-    // Logbook schema (per-stream JSONL files)
-    initialize_logbook_files(&config.logbook, &mut created, &mut existed)?;
+    // Logbook schema (per-stream, segmented/rotating JSONL files)
+    initialize_logbook_files(&config.logbook, &config.audit, &mut created, &mut existed)?;
 
     Ok(InitReport {
         root,
         created,
         existed,
         config,
+        provenance,
     })
 }
 
@@ -164,6 +180,7 @@ fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
 
 fn initialize_logbook_files(
     log_cfg: &LogbookConfig,
+    audit_cfg: &AuditConfig,
     created: &mut Vec<String>,
     existed: &mut Vec<String>,
 ) -> Result<()> {
@@ -176,15 +193,49 @@ fn initialize_logbook_files(
             "version": "1.0.0",
             "architecture": "hybrid_tiered"
         }
-    })
-    .to_string();
+    });
 
     ensure_dir_abs(&log_cfg.path, created, existed)?;
-    ensure_seeded_jsonl_abs(&log_cfg.aggregate, &init_event, created, existed)?;
-    ensure_seeded_jsonl_abs(&log_cfg.ethics_log, &init_event, created, existed)?;
-    ensure_seeded_jsonl_abs(&log_cfg.agent_actions, &init_event, created, existed)?;
-    ensure_seeded_jsonl_abs(&log_cfg.contract_violations, &init_event, created, existed)?;
-    ensure_seeded_jsonl_abs(&log_cfg.contracts_log, &init_event, created, existed)?;
+    ensure_seeded_jsonl_abs(
+        &log_cfg.aggregate,
+        log_cfg,
+        audit_cfg.retention_days,
+        &init_event,
+        created,
+        existed,
+    )?;
+    ensure_seeded_jsonl_abs(
+        &log_cfg.ethics_log,
+        log_cfg,
+        audit_cfg.retention_days,
+        &init_event,
+        created,
+        existed,
+    )?;
+    ensure_seeded_jsonl_abs(
+        &log_cfg.agent_actions,
+        log_cfg,
+        audit_cfg.retention_days,
+        &init_event,
+        created,
+        existed,
+    )?;
+    ensure_seeded_jsonl_abs(
+        &log_cfg.contract_violations,
+        log_cfg,
+        audit_cfg.retention_days,
+        &init_event,
+        created,
+        existed,
+    )?;
+    ensure_seeded_jsonl_abs(
+        &log_cfg.contracts_log,
+        log_cfg,
+        audit_cfg.retention_days,
+        &init_event,
+        created,
+        existed,
+    )?;
     Ok(())
 }
 
@@ -212,34 +263,27 @@ fn ensure_parent_dir_abs(
 
 fn ensure_seeded_jsonl_abs(
     path: &Path,
-    init_line: &str,
+    log_cfg: &LogbookConfig,
+    retention_days: u32,
+    init_event: &Value,
     created: &mut Vec<String>,
     existed: &mut Vec<String>,
 ) -> Result<()> {
     if path.exists() {
         existed.push(path.display().to_string());
         if fs::metadata(path)?.len() == 0 {
-            let mut f = OpenOptions::new()
-                .append(true)
-                .open(path)
-                .with_context(|| format!("Failed to open {:?} for appending", path))?;
-            f.write_all(init_line.as_bytes())?;
-            f.write_all(b"\n")?;
-            f.flush()?;
+            logbook::append_event(path, init_event, log_cfg, retention_days)?;
         }
         return Ok(());
     }
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).with_context(|| format!("create_dir_all({:?})", parent))?;
-    }
-    write_atomic(path, format!("{}\n", init_line).as_bytes())?;
+    logbook::append_event(path, init_event, log_cfg, retention_days)?;
     created.push(path.display().to_string());
     Ok(())
 }
 
 // ---------- defaults ----------
 
-const DEFAULT_CONFIG_TOML: &str = r#"
+pub(crate) const DEFAULT_CONFIG_TOML: &str = r#"
 [system]
 name = "cogniv"
 version = "0.1.0"
@@ -256,6 +300,8 @@ ethics_log         = "logbook/ethics.jsonl"
 agent_actions      = "logbook/actions.jsonl"
 contract_violations = "logbook/violations.jsonl"
 contracts_log      = "logbook/contracts.jsonl"
+max_segment_bytes  = 10485760
+compress_segments  = true
 
 [services]
 ethos_enabled     = true
@@ -280,4 +326,5 @@ reflection_max_keywords = 3
 reflection_pool_size = 20
 summary_min_len = 500
 log_preview_len = 160
+lobe_retain_versions = 5
 "#;
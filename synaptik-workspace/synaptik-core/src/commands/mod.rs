@@ -8,6 +8,9 @@ pub mod pons;
 pub mod recall;
 pub mod replay;
 
+use std::fmt;
+use std::str::FromStr;
+
 use serde::Serialize;
 
 #[derive(Debug, Clone, Copy)]
@@ -33,6 +36,86 @@ pub struct RecallResult {
     pub source: HitSource,
 }
 
+/// Named coercion applied to a recalled string by [`Commands::recall_typed`].
+///
+/// A lobe/key schema can name one of these by string via [`FromStr`]:
+/// `"bytes"`, `"int"`, `"float"`, `"bool"`, `"timestamp"` (RFC3339),
+/// `"timestamp|<fmt>"` (naive, chrono strftime), or `"timestamp_tz|<fmt>"`
+/// (timezone-aware).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conversion {
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    TimestampFmt(String),
+    TimestampTzFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (head, rest) = match s.split_once('|') {
+            Some((h, r)) => (h, Some(r)),
+            None => (s, None),
+        };
+        match (head, rest) {
+            ("bytes", None) => Ok(Conversion::Bytes),
+            ("int" | "integer", None) => Ok(Conversion::Integer),
+            ("float", None) => Ok(Conversion::Float),
+            ("bool" | "boolean", None) => Ok(Conversion::Boolean),
+            ("timestamp", None) => Ok(Conversion::Timestamp),
+            ("timestamp", Some(fmt)) => Ok(Conversion::TimestampFmt(fmt.to_string())),
+            ("timestamp_tz" | "timestamptz", Some(fmt)) => {
+                Ok(Conversion::TimestampTzFmt(fmt.to_string()))
+            }
+            _ => Err(ConversionError::UnknownConversion(s.to_string())),
+        }
+    }
+}
+
+/// Failure coercing a recalled string via a [`Conversion`].
+#[derive(Debug, Clone)]
+pub enum ConversionError {
+    UnknownConversion(String),
+    ParseInt(String),
+    ParseFloat(String),
+    ParseBool(String),
+    ParseTimestamp(String),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::UnknownConversion(s) => write!(f, "unknown conversion: {s:?}"),
+            ConversionError::ParseInt(s) => write!(f, "not a valid i64: {s:?}"),
+            ConversionError::ParseFloat(s) => write!(f, "not a valid f64: {s:?}"),
+            ConversionError::ParseBool(s) => write!(f, "not a valid bool: {s:?}"),
+            ConversionError::ParseTimestamp(s) => write!(f, "not a valid timestamp: {s:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl fmt::Display for Conversion {
+    /// Round-trips through [`Conversion::from_str`] -- used to persist a
+    /// schema (e.g. a pons metadata schema) as plain strings.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conversion::Bytes => write!(f, "bytes"),
+            Conversion::Integer => write!(f, "int"),
+            Conversion::Float => write!(f, "float"),
+            Conversion::Boolean => write!(f, "bool"),
+            Conversion::Timestamp => write!(f, "timestamp"),
+            Conversion::TimestampFmt(fmt_str) => write!(f, "timestamp|{fmt_str}"),
+            Conversion::TimestampTzFmt(fmt_str) => write!(f, "timestamp_tz|{fmt_str}"),
+        }
+    }
+}
+
 #[inline]
 pub(crate) fn bytes_to_string_owned(bytes: Vec<u8>) -> String {
     match String::from_utf8(bytes) {
@@ -1,8 +1,13 @@
 use anyhow::Result;
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Read;
 
 use crate::commands::Commands;
-use crate::utils::pons::{ObjectMetadata as PonsMetadata, ObjectRef as PonsObjectRef};
+use crate::utils::pons::{
+    ExactReader, ObjectMetadata as PonsMetadata, ObjectRef as PonsObjectRef, PonsRangePage,
+    PonsSchema,
+};
 
 impl Commands {
     /// Ensure a pons namespace exists under the shared root.
@@ -11,6 +16,25 @@ impl Commands {
         store.create_pons(pons)
     }
 
+    /// Ensure a pons namespace exists and declare a typed metadata schema for
+    /// it: `schema` maps field name -> a [`crate::commands::Conversion`]
+    /// string (e.g. `"int"`, `"timestamp"`, `"timestamp|%Y-%m-%d"`). Once
+    /// declared, `pons_put_object`'s `extra` fields named in the schema are
+    /// coerced through the matching conversion instead of staying opaque
+    /// strings.
+    pub fn pons_create_with_schema(
+        &self,
+        pons: &str,
+        schema: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let mut parsed = PonsSchema::new();
+        for (field, conversion) in schema {
+            parsed.insert(field.clone(), conversion.parse()?);
+        }
+        let store = self.pons_store()?;
+        store.create_pons_with_schema(pons, &parsed)
+    }
+
     /// Write bytes plus optional metadata into a pons/key stream.
     pub fn pons_put_object(
         &self,
@@ -25,6 +49,82 @@ impl Commands {
         Ok(obj)
     }
 
+    /// Like [`Self::pons_put_object`], but errors instead of committing if
+    /// `expected_blake3` disagrees with the digest computed for `data`.
+    pub fn pons_put_object_verified(
+        &self,
+        pons: &str,
+        key: &str,
+        data: &[u8],
+        media_type: Option<&str>,
+        extra: Option<Value>,
+        expected_blake3: Option<&str>,
+    ) -> Result<PonsObjectRef> {
+        let store = self.pons_store()?;
+        let (obj, _path) = store.put_object_with_meta_verified(
+            pons,
+            key,
+            data,
+            media_type,
+            extra,
+            expected_blake3,
+        )?;
+        Ok(obj)
+    }
+
+    /// Stream-write a large object instead of buffering it whole: bytes are
+    /// pulled from `reader` in bounded chunks while the blake3 etag is
+    /// computed incrementally, so memory use stays flat regardless of object
+    /// size. Produces the same `ObjectRef` shape as [`Self::pons_put_object`].
+    pub fn pons_put_object_stream(
+        &self,
+        pons: &str,
+        key: &str,
+        reader: impl Read,
+        media_type: Option<&str>,
+        extra: Option<Value>,
+    ) -> Result<PonsObjectRef> {
+        let store = self.pons_store()?;
+        let (obj, _path) = store.put_object_streaming(pons, key, reader, media_type, extra)?;
+        Ok(obj)
+    }
+
+    /// Like [`Self::pons_put_object_stream`], but errors instead of
+    /// committing if `expected_blake3` disagrees with the digest computed
+    /// while streaming `reader`.
+    pub fn pons_put_object_stream_verified(
+        &self,
+        pons: &str,
+        key: &str,
+        reader: impl Read,
+        media_type: Option<&str>,
+        extra: Option<Value>,
+        expected_blake3: Option<&str>,
+    ) -> Result<PonsObjectRef> {
+        let store = self.pons_store()?;
+        let (obj, _path) = store.put_object_streaming_verified(
+            pons,
+            key,
+            reader,
+            media_type,
+            extra,
+            expected_blake3,
+        )?;
+        Ok(obj)
+    }
+
+    /// Open a reader over a pons/key version (newest, if `version` is
+    /// `None`) instead of materializing the whole object into memory.
+    pub fn pons_open_object_reader(
+        &self,
+        pons: &str,
+        key: &str,
+        version: Option<&str>,
+    ) -> Result<ExactReader<std::fs::File>> {
+        let store = self.pons_store()?;
+        store.open_object_reader(pons, key, version)
+    }
+
     /// Read newest bytes for a pons/key.
     pub fn pons_get_latest_bytes(&self, pons: &str, key: &str) -> Result<Vec<u8>> {
         let store = self.pons_store()?;
@@ -48,6 +148,49 @@ impl Commands {
         store.get_object_version_with_meta(pons, key, version)
     }
 
+    /// Pull one field out of the latest version's `extra` metadata and
+    /// coerce it through `conversion`, parsed the same way as
+    /// [`Self::pons_create_with_schema`]'s schema strings (`"int"`,
+    /// `"timestamp|%Y-%m-%d"`, etc). Works whether or not a schema was
+    /// declared for this pons: a field already coerced at put time (stored
+    /// as `{"value": ..., "raw": ...}`) is re-converted from its `raw`
+    /// string, and a field never named in a schema -- a bare string -- is
+    /// converted directly. Returns `Ok(None)` if the key has no versions or
+    /// the field isn't present; an unparseable field is a `ConversionError`,
+    /// not a silently dropped value.
+    pub fn pons_get_typed(
+        &self,
+        pons: &str,
+        key: &str,
+        field: &str,
+        conversion: &str,
+    ) -> Result<Option<Value>> {
+        let conversion: crate::commands::Conversion = conversion
+            .parse()
+            .map_err(|e: crate::commands::ConversionError| anyhow::anyhow!(e.to_string()))?;
+        let store = self.pons_store()?;
+        let obj_ref = match store.get_object_latest_ref(pons, key) {
+            Ok(r) => r,
+            Err(_) => return Ok(None),
+        };
+        let meta = store.get_object_metadata(pons, key, &obj_ref.version)?;
+        let Some(Value::Object(map)) = meta.extra else {
+            return Ok(None);
+        };
+        let Some(value) = map.get(field) else {
+            return Ok(None);
+        };
+        let raw = match value {
+            Value::String(s) => s.clone(),
+            Value::Object(inner) => match inner.get("raw") {
+                Some(Value::String(s)) => s.clone(),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        crate::commands::recall::convert_recalled(&raw, &conversion).map(Some)
+    }
+
     /// List the latest refs under a pons namespace.
     pub fn pons_list_latest(
         &self,
@@ -58,4 +201,65 @@ impl Commands {
         let store = self.pons_store()?;
         store.list_latest(pons, prefix, limit)
     }
+
+    /// Write several `(key, bytes, media_type, extra)` items into a pons in
+    /// one call. Each item still goes through [`Self::pons_put_object`]'s
+    /// per-version blob dedup and key index update; the store has no
+    /// cross-key transaction, so a failure partway through leaves earlier
+    /// items in this batch committed -- the first error aborts the rest and
+    /// is returned to the caller.
+    pub fn pons_batch_put(
+        &self,
+        pons: &str,
+        items: &[(&str, &[u8], Option<&str>, Option<Value>)],
+    ) -> Result<Vec<PonsObjectRef>> {
+        let store = self.pons_store()?;
+        let mut out = Vec::with_capacity(items.len());
+        for (key, data, media_type, extra) in items {
+            let (obj, _path) =
+                store.put_object_with_meta(pons, key, data, *media_type, extra.clone())?;
+            out.push(obj);
+        }
+        Ok(out)
+    }
+
+    /// Read the latest bytes and metadata for several keys in one call.
+    /// A key with no versions yields `None` at its position rather than
+    /// failing the whole batch.
+    pub fn pons_batch_get(
+        &self,
+        pons: &str,
+        keys: &[&str],
+    ) -> Result<Vec<Option<(Vec<u8>, PonsMetadata)>>> {
+        let store = self.pons_store()?;
+        let mut out = Vec::with_capacity(keys.len());
+        for key in keys {
+            let obj_ref = match store.get_object_latest_ref(pons, key) {
+                Ok(r) => r,
+                Err(_) => {
+                    out.push(None);
+                    continue;
+                }
+            };
+            out.push(Some(store.get_object_version_with_meta(pons, key, &obj_ref.version)?));
+        }
+        Ok(out)
+    }
+
+    /// K2V-style bounded range read: latest refs for keys in `[start, end)`
+    /// under `pons` (`start` inclusive, `end` exclusive), optionally narrowed
+    /// by `prefix`. `next_cursor` (the last key returned) feeds back in as
+    /// the next call's `start` to page through a namespace too large to list
+    /// in one call; `partial` is `false` once the range is exhausted.
+    pub fn pons_list_range(
+        &self,
+        pons: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+        prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<PonsRangePage> {
+        let store = self.pons_store()?;
+        store.list_range(pons, start, end, prefix, limit)
+    }
 }
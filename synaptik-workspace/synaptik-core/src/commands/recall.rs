@@ -1,10 +1,34 @@
 use anyhow::Result;
-use crate::commands::{HitSource, Prefer, RecallResult, bytes_to_string_owned, Commands};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use crate::commands::{
+    Commands, Conversion, ConversionError, HitSource, Prefer, RecallResult, bytes_to_string_owned,
+};
+
+/// Cap on `recall_many_parallel`'s worker pool even when `workers` asks for
+/// more, or `available_parallelism` reports a very large machine -- past
+/// this there's no point opening more concurrent reads against one archive
+/// root.
+const RECALL_PARALLEL_MAX_WORKERS: usize = 16;
+
+/// Which filesystem read (if any) a batch id needs once the hot tier and
+/// archived-CID lookup -- both cheap `self.memory` reads -- have already
+/// been resolved sequentially on the calling thread.
+enum ParallelJob {
+    /// Resolved from the hot tier already; no further I/O needed.
+    Hot(String),
+    /// Known archived CID; the worker pool reads the blob off the archive root.
+    Archive(String),
+    /// No archived CID on record (or the tier order skips Archive); read
+    /// straight from the DAG.
+    Dag,
+    /// No tier in the requested order applies to this id.
+    Miss,
+}
 
 impl Commands {
     /// Newest → oldest memory_ids for a lobe.
     pub fn recent(&self, lobe: &str, n: usize) -> Result<Vec<String>> {
-        super::helpers::recent_ids_in_lobe(&self.memory, lobe, n)
+        self.backend().recent_ids_in_lobe(lobe, n)
     }
 
     /// Recall full text (auto: hot → archive → dag). Returns just the content string.
@@ -12,6 +36,22 @@ impl Commands {
         Ok(self.recall_any(memory_id, Prefer::Auto)?.map(|r| r.content))
     }
 
+    /// Recall straight from the archive tier, insisting on its content-hash
+    /// check rather than letting a corrupt blob fall through to whatever
+    /// [`Commands::recall_any`]'s tier order would have tried next.
+    /// `Ok(None)` means `memory_id` has no archived CID on record; a
+    /// recomputed-hash mismatch surfaces as an
+    /// [`crate::services::archivist::IntegrityError`] instead of silently
+    /// returning bytes.
+    pub fn recall_verified(&self, memory_id: &str) -> Result<Option<String>> {
+        let Some(cid) = self.backend().get_archived_cid(memory_id)? else {
+            return Ok(None);
+        };
+        let bytes = self.cold_store()?.retrieve(&cid)?;
+        self.accrue_recall_weight(memory_id, bytes.len())?;
+        Ok(Some(bytes_to_string_owned(bytes)))
+    }
+
     /// Layered recall returning which source was used. prefer: "hot"|"archive"|"dag"|"auto"
     pub fn recall_with_source(
         &self,
@@ -52,6 +92,19 @@ impl Commands {
 
     /// Centralized recall: one function to rule them all.
     /// Tries according to `Prefer`, returns the first hit with its source.
+    /// Fold a successful tier hit's cost into its lobe's running weight total.
+    /// Looks up the lobe via `Memory::lobe_key`, falling back to a sentinel
+    /// for ids recalled before their row had a recorded lobe/key.
+    fn accrue_recall_weight(&self, memory_id: &str, bytes_len: usize) -> Result<()> {
+        let lobe = self
+            .memory
+            .lobe_key(memory_id)?
+            .map(|(l, _)| l)
+            .unwrap_or_else(|| "unknown".to_string());
+        self.memory
+            .accrue_weight(&lobe, crate::services::weight::op_weight(bytes_len))
+    }
+
     pub fn recall_any(&self, memory_id: &str, prefer: Prefer) -> Result<Option<RecallResult>> {
         use Prefer::*;
         let order: &[Prefer] = match prefer {
@@ -65,6 +118,7 @@ impl Commands {
             match tier {
                 Prefer::Hot => {
                     if let Some(bytes) = self.memory.recall(memory_id)? {
+                        self.accrue_recall_weight(memory_id, bytes.len())?;
                         return Ok(Some(RecallResult {
                             memory_id: memory_id.to_owned(),
                             content: bytes_to_string_owned(bytes),
@@ -74,6 +128,7 @@ impl Commands {
                 }
                 Prefer::Archive => {
                     if let Some(bytes) = self.librarian.fetch_cold(&self.memory, memory_id)? {
+                        self.accrue_recall_weight(memory_id, bytes.len())?;
                         return Ok(Some(RecallResult {
                             memory_id: memory_id.to_owned(),
                             content: bytes_to_string_owned(bytes),
@@ -82,6 +137,7 @@ impl Commands {
                     }
                     if let Some(_cid) = self.ensure_archive_for(memory_id)? {
                         if let Some(bytes2) = self.librarian.fetch_cold(&self.memory, memory_id)? {
+                            self.accrue_recall_weight(memory_id, bytes2.len())?;
                             return Ok(Some(RecallResult {
                                 memory_id: memory_id.to_owned(),
                                 content: bytes_to_string_owned(bytes2),
@@ -92,6 +148,7 @@ impl Commands {
                 }
                 Prefer::Dag => {
                     if let Some(s) = crate::memory::dag::content_by_id(memory_id)? {
+                        self.accrue_recall_weight(memory_id, s.len())?;
                         return Ok(Some(RecallResult {
                             memory_id: memory_id.to_owned(),
                             content: s,
@@ -117,6 +174,7 @@ impl Commands {
                                     .unwrap_or("restored");
                                 self.memory.remember(memory_id, lobe, key, s2.as_bytes())?;
                             }
+                            self.accrue_recall_weight(memory_id, s2.len())?;
                             return Ok(Some(RecallResult {
                                 memory_id: memory_id.to_owned(),
                                 content: s2,
@@ -131,6 +189,21 @@ impl Commands {
         Ok(None)
     }
 
+    /// Recall like [`Commands::recall_any`], then coerce the content with `conversion`
+    /// instead of handing back a raw string. Returns `Ok(None)` on a plain recall miss;
+    /// a recalled value that fails to parse is a [`ConversionError`], not a silent
+    /// lossy string.
+    pub fn recall_typed(
+        &self,
+        memory_id: &str,
+        conversion: Conversion,
+    ) -> Result<Option<serde_json::Value>> {
+        let Some(hit) = self.recall_any(memory_id, Prefer::Auto)? else {
+            return Ok(None);
+        };
+        convert_recalled(&hit.content, &conversion).map(Some)
+    }
+
     /// Centralized batch recall (keeps order of input ids; drops misses).
     pub fn recall_many(&self, memory_ids: &[String], prefer: Prefer) -> Result<Vec<RecallResult>> {
         // Value-aware ordering: prefer higher value states first if table exists.
@@ -161,6 +234,195 @@ impl Commands {
         }
         Ok(out)
     }
+
+    /// Like [`Commands::recall_many`], but fans the archive/DAG tiers'
+    /// filesystem reads -- the actual I/O-bound cost once a batch falls
+    /// through the hot cache -- across a bounded worker pool, instead of
+    /// doing them one at a time. Unlike `recall_many`, output order always
+    /// matches `memory_ids` (misses dropped, same as `recall_many`), not a
+    /// value-weighted reordering.
+    ///
+    /// The hot-tier check, the archived-CID lookup, and every write-back
+    /// (re-caching a restored blob, promoting a DAG hit to hot) stay on the
+    /// calling thread: `Memory` wraps a `rusqlite::Connection`, which isn't
+    /// `Sync`, so only one thread may ever touch `self.memory` at a time.
+    /// Only the blob/content reads themselves -- `ColdStore::retrieve` and
+    /// `dag::content_by_id`, neither of which needs `&Memory` -- run in the
+    /// pool. `workers == 0` picks `available_parallelism` (capped);
+    /// `workers <= 1` (or a single id) just calls `recall_many`.
+    pub fn recall_many_parallel(
+        &self,
+        memory_ids: &[String],
+        prefer: Prefer,
+        workers: usize,
+    ) -> Result<Vec<RecallResult>> {
+        let workers = if workers == 0 {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4)
+                .min(RECALL_PARALLEL_MAX_WORKERS)
+        } else {
+            workers.min(RECALL_PARALLEL_MAX_WORKERS)
+        };
+        if workers <= 1 || memory_ids.len() <= 1 {
+            return self.recall_many(memory_ids, prefer);
+        }
+
+        use Prefer::*;
+        let order: &[Prefer] = match prefer {
+            Hot => &[Hot],
+            Archive => &[Archive],
+            Dag => &[Dag],
+            Auto => &[Hot, Archive, Dag],
+        };
+        let wants_hot = order.contains(&Prefer::Hot);
+        let wants_archive = order.contains(&Prefer::Archive);
+        let wants_dag = order.contains(&Prefer::Dag);
+
+        // Phase 1 (sequential, `self.memory`-only): classify each id's tier
+        // without doing the slow filesystem read yet.
+        let mut jobs: Vec<ParallelJob> = Vec::with_capacity(memory_ids.len());
+        for id in memory_ids {
+            if wants_hot {
+                if let Some(bytes) = self.memory.recall(id)? {
+                    jobs.push(ParallelJob::Hot(bytes_to_string_owned(bytes)));
+                    continue;
+                }
+            }
+            if wants_archive {
+                if let Some(cid) = self.backend().get_archived_cid(id)? {
+                    jobs.push(ParallelJob::Archive(cid));
+                    continue;
+                }
+            }
+            if wants_dag {
+                jobs.push(ParallelJob::Dag);
+                continue;
+            }
+            jobs.push(ParallelJob::Miss);
+        }
+
+        // Phase 2 (parallel, `self.memory`-free): the blob/content reads.
+        // `Arc<dyn ColdStore>` is `Send + Sync`, unlike `Memory`, so it's
+        // safe to clone into the pool.
+        let archivist = self.librarian.cold_store();
+        let pending = jobs
+            .iter()
+            .filter(|j| matches!(j, ParallelJob::Archive(_) | ParallelJob::Dag))
+            .count();
+        let (job_tx, job_rx): (Sender<(usize, String)>, Receiver<(usize, String)>) =
+            bounded(pending.max(1));
+        let (res_tx, res_rx): (
+            Sender<(usize, Result<Option<String>, String>)>,
+            Receiver<(usize, Result<Option<String>, String>)>,
+        ) = bounded(pending.max(1));
+
+        for (idx, job) in jobs.iter().enumerate() {
+            match job {
+                ParallelJob::Archive(cid) => {
+                    let _ = job_tx.send((idx, cid.clone()));
+                }
+                ParallelJob::Dag => {
+                    let _ = job_tx.send((idx, memory_ids[idx].clone()));
+                }
+                _ => {}
+            }
+        }
+        drop(job_tx);
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                let job_rx = job_rx.clone();
+                let res_tx = res_tx.clone();
+                let archivist = archivist.clone();
+                let jobs_ref = &jobs;
+                scope.spawn(move || {
+                    for (idx, key) in job_rx.iter() {
+                        let result: Result<Option<String>, String> = match &jobs_ref[idx] {
+                            ParallelJob::Archive(_) => match archivist.as_ref() {
+                                Some(a) => a
+                                    .retrieve(&key)
+                                    .map(|bytes| Some(bytes_to_string_owned(bytes)))
+                                    .map_err(|e| e.to_string()),
+                                None => Ok(None),
+                            },
+                            ParallelJob::Dag => {
+                                crate::memory::dag::content_by_id(&key).map_err(|e| e.to_string())
+                            }
+                            _ => Ok(None),
+                        };
+                        let _ = res_tx.send((idx, result));
+                    }
+                });
+            }
+        });
+        drop(res_tx);
+
+        let mut fetched: Vec<Option<Result<Option<String>, String>>> =
+            (0..memory_ids.len()).map(|_| None).collect();
+        for (idx, result) in res_rx.iter().take(pending) {
+            fetched[idx] = Some(result);
+        }
+
+        // Phase 3 (sequential): write-back, and the rare fallback to the
+        // ordinary sequential path when a parallel fetch came up empty.
+        let mut out = Vec::with_capacity(memory_ids.len());
+        for (idx, id) in memory_ids.iter().enumerate() {
+            match &jobs[idx] {
+                ParallelJob::Hot(content) => {
+                    out.push(RecallResult {
+                        memory_id: id.clone(),
+                        content: content.clone(),
+                        source: HitSource::Hot,
+                    });
+                }
+                ParallelJob::Archive(_) => match fetched[idx].take() {
+                    Some(Ok(Some(content))) => {
+                        let (lobe, key) = match crate::memory::dag::load_node_by_id(id)? {
+                            Some(node) => (
+                                node.get("lobe").and_then(|v| v.as_str()).unwrap_or("restored").to_string(),
+                                node.get("key").and_then(|v| v.as_str()).unwrap_or("restored").to_string(),
+                            ),
+                            None => ("restored".to_string(), "restored".to_string()),
+                        };
+                        self.memory.remember(id, &lobe, &key, content.as_bytes())?;
+                        out.push(RecallResult {
+                            memory_id: id.clone(),
+                            content,
+                            source: HitSource::Archive,
+                        });
+                    }
+                    _ => {
+                        if let Some(hit) = self.recall_any(id, prefer)? {
+                            out.push(hit);
+                        }
+                    }
+                },
+                ParallelJob::Dag => match fetched[idx].take() {
+                    Some(Ok(Some(content))) => {
+                        if self.memory.recall(id)?.is_none() {
+                            let _ = self.librarian.fetch_cold(&self.memory, id)?;
+                        }
+                        if self.memory.recall(id)?.is_some() {
+                            self.memory.promote_to_dag(id)?;
+                        }
+                        out.push(RecallResult {
+                            memory_id: id.clone(),
+                            content,
+                            source: HitSource::Dag,
+                        });
+                    }
+                    _ => {
+                        if let Some(hit) = self.recall_any(id, prefer)? {
+                            out.push(hit);
+                        }
+                    }
+                },
+                ParallelJob::Miss => {}
+            }
+        }
+        Ok(out)
+    }
 }
 
 fn parse_prefer(s: Option<&str>) -> Prefer {
@@ -171,3 +433,107 @@ fn parse_prefer(s: Option<&str>) -> Prefer {
         _ => Prefer::Auto,
     }
 }
+
+/// Coerce one raw string through a [`Conversion`]. Shared with
+/// `utils::pons`'s per-pons metadata schema, so a lobe/key recall schema and
+/// a pons `extra` schema apply exactly the same coercion rules.
+pub(crate) fn convert_recalled(raw: &str, conversion: &Conversion) -> Result<serde_json::Value> {
+    let trimmed = raw.trim();
+    let value = match conversion {
+        Conversion::Bytes => serde_json::Value::String(raw.to_string()),
+        Conversion::Integer => {
+            let n: i64 = trimmed
+                .parse()
+                .map_err(|_| ConversionError::ParseInt(trimmed.to_string()))?;
+            serde_json::Value::from(n)
+        }
+        Conversion::Float => {
+            let f: f64 = trimmed
+                .parse()
+                .map_err(|_| ConversionError::ParseFloat(trimmed.to_string()))?;
+            serde_json::Number::from_f64(f)
+                .map(serde_json::Value::Number)
+                .ok_or_else(|| ConversionError::ParseFloat(trimmed.to_string()))?
+        }
+        Conversion::Boolean => {
+            let b = match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => return Err(ConversionError::ParseBool(trimmed.to_string()).into()),
+            };
+            serde_json::Value::Bool(b)
+        }
+        Conversion::Timestamp => {
+            let dt = chrono::DateTime::parse_from_rfc3339(trimmed)
+                .map_err(|_| ConversionError::ParseTimestamp(trimmed.to_string()))?;
+            serde_json::Value::from(dt.timestamp_millis())
+        }
+        Conversion::TimestampFmt(fmt) => {
+            let naive = chrono::NaiveDateTime::parse_from_str(trimmed, fmt)
+                .map_err(|_| ConversionError::ParseTimestamp(trimmed.to_string()))?;
+            serde_json::Value::from(naive.and_utc().timestamp_millis())
+        }
+        Conversion::TimestampTzFmt(fmt) => {
+            let dt = chrono::DateTime::parse_from_str(trimmed, fmt)
+                .map_err(|_| ConversionError::ParseTimestamp(trimmed.to_string()))?;
+            serde_json::Value::from(dt.timestamp_millis())
+        }
+    };
+    Ok(value)
+}
+
+/// Coerce `raw` through `conversion` like [`convert_recalled`], but also
+/// render a canonical string form -- trimmed ints/floats, normalized
+/// `"true"`/`"false"`, RFC3339 timestamps regardless of the input format --
+/// suitable for storing in place of the original text. Used by
+/// `Commands::append`'s typed-content schema field, where the DAG node
+/// content has to stay a plain string.
+pub(crate) fn canonicalize_typed(
+    raw: &str,
+    conversion: &Conversion,
+) -> Result<(String, serde_json::Value)> {
+    let trimmed = raw.trim();
+    let (canonical, typed) = match conversion {
+        Conversion::Bytes => (raw.to_string(), serde_json::Value::String(raw.to_string())),
+        Conversion::Integer => {
+            let n: i64 = trimmed
+                .parse()
+                .map_err(|_| ConversionError::ParseInt(trimmed.to_string()))?;
+            (n.to_string(), serde_json::Value::from(n))
+        }
+        Conversion::Float => {
+            let f: f64 = trimmed
+                .parse()
+                .map_err(|_| ConversionError::ParseFloat(trimmed.to_string()))?;
+            let typed = serde_json::Number::from_f64(f)
+                .map(serde_json::Value::Number)
+                .ok_or_else(|| ConversionError::ParseFloat(trimmed.to_string()))?;
+            (f.to_string(), typed)
+        }
+        Conversion::Boolean => {
+            let b = match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => return Err(ConversionError::ParseBool(trimmed.to_string()).into()),
+            };
+            (b.to_string(), serde_json::Value::Bool(b))
+        }
+        Conversion::Timestamp => {
+            let dt = chrono::DateTime::parse_from_rfc3339(trimmed)
+                .map_err(|_| ConversionError::ParseTimestamp(trimmed.to_string()))?;
+            (dt.to_rfc3339(), serde_json::Value::from(dt.timestamp_millis()))
+        }
+        Conversion::TimestampFmt(fmt) => {
+            let naive = chrono::NaiveDateTime::parse_from_str(trimmed, fmt)
+                .map_err(|_| ConversionError::ParseTimestamp(trimmed.to_string()))?;
+            let dt = naive.and_utc();
+            (dt.to_rfc3339(), serde_json::Value::from(dt.timestamp_millis()))
+        }
+        Conversion::TimestampTzFmt(fmt) => {
+            let dt = chrono::DateTime::parse_from_str(trimmed, fmt)
+                .map_err(|_| ConversionError::ParseTimestamp(trimmed.to_string()))?;
+            (dt.to_rfc3339(), serde_json::Value::from(dt.timestamp_millis()))
+        }
+    };
+    Ok((canonical, typed))
+}
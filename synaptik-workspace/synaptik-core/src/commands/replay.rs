@@ -1,11 +1,19 @@
 use anyhow::{anyhow, Result};
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
 
-use crate::commands::Commands;
+use crate::commands::recall::canonicalize_typed;
+use crate::commands::recall::convert_recalled;
+use crate::commands::{Commands, Conversion};
 use crate::memory::dag::MemoryState as DagMemoryState;
 use crate::services::audit::record_action;
 use crate::services::ethos::{Decision, decision_gate, precheck};
 
+/// A path's typed-field schema: field name -> [`Conversion`], with "content"
+/// naming the snapshot text itself rather than a `meta` field. Parallels
+/// `utils::pons::PonsSchema`.
+pub type PathSchema = BTreeMap<String, Conversion>;
+
 impl Commands {
     /// Recall an immutable snapshot by content hash.
     pub fn replay_recall_snapshot(&self, snapshot_id: &str) -> Result<DagMemoryState> {
@@ -29,6 +37,82 @@ impl Commands {
         Ok(new_id)
     }
 
+    /// Bind base (nearest common ancestor) between two snapshot hashes. A
+    /// criss-cross merge can have more than one greatest common ancestor;
+    /// this returns the first one `bind_base` found, which is what callers
+    /// that only need *a* valid merge base (rather than every candidate)
+    /// want. Returns `None` if the two snapshots share no common ancestor.
+    pub fn replay_bind_base(&self, a_hash: &str, b_hash: &str) -> Result<Option<String>> {
+        Ok(crate::memory::dag::bind_base(a_hash, b_hash)?.into_iter().next())
+    }
+
+    /// Reconcile two diverged replay paths into one multi-parent node.
+    /// Resolves the lowest common ancestor of the two heads (minimizing
+    /// combined BFS distance, falling back to either path's recorded
+    /// `base_snapshot` if the ancestor sets are disjoint), runs the same
+    /// line-level three-way bind used by [`Commands::reconsolidate_paths`],
+    /// saves the result parented on both heads, and points `merged_path`'s
+    /// head at it. Returns the new hash and whether conflict markers were
+    /// emitted, so callers can prompt for resolution.
+    pub fn merge_paths(
+        &self,
+        path_a: &str,
+        path_b: &str,
+        merged_path: &str,
+    ) -> Result<(String, bool)> {
+        let head_a = self
+            .dag_head(path_a)?
+            .ok_or_else(|| anyhow!("no head for path: {}", path_a))?;
+        let head_b = self
+            .dag_head(path_b)?
+            .ok_or_else(|| anyhow!("no head for path: {}", path_b))?;
+        if head_a == head_b {
+            self.update_path_head(merged_path, &head_a)?;
+            return Ok((head_a, false));
+        }
+
+        let base_a = crate::memory::dag::path_base_snapshot(path_a)?;
+        let base_b = crate::memory::dag::path_base_snapshot(path_b)?;
+        let fallback = base_a.as_deref().or(base_b.as_deref());
+        let lca = crate::memory::dag::lowest_common_ancestor(&head_a, &head_b, fallback)?
+            .ok_or_else(|| anyhow!("no common ancestor for paths {} and {}", path_a, path_b))?;
+
+        let base_text = crate::memory::dag::recall_snapshot(&lca)?.content;
+        let a_text = crate::memory::dag::recall_snapshot(&head_a)?.content;
+        let b_text = crate::memory::dag::recall_snapshot(&head_b)?.content;
+        let (merged_text, had_conflicts) =
+            crate::commands::bind::three_way_bind_lines(&base_text, &a_text, &b_text);
+
+        let enrich = json!({
+            "op": "merge",
+            "actor": "core",
+            "parents_cids": [head_a, head_b],
+            "lca": lca,
+            "merge_conflicts": had_conflicts,
+        });
+        let mut meta_obj = serde_json::Map::new();
+        if let Value::Object(m) = enrich {
+            meta_obj = m;
+        }
+        let new_hash = blake3::hash(merged_text.as_bytes()).to_hex().to_string();
+        crate::memory::dag::save_node(
+            &new_hash,
+            &merged_text,
+            &Value::Object(meta_obj),
+            &[head_a.clone(), head_b.clone()],
+        )?;
+        self.update_path_head(merged_path, &new_hash)?;
+
+        record_action(
+            "commands",
+            "merge_created",
+            &json!({ "path_a": path_a, "path_b": path_b, "merged_path": merged_path, "hash": new_hash, "conflicts": had_conflicts }),
+            if had_conflicts { "medium" } else { "low" },
+        );
+
+        Ok((new_hash, had_conflicts))
+    }
+
     /// Fast-forward the target path to the source head.
     pub fn systems_consolidate(&self, src_path: &str, dst_path: &str) -> Result<String> {
         let src_head = self.dag_head(src_path)?.ok_or(anyhow!("no src head"))?;
@@ -55,7 +139,7 @@ impl Commands {
             return Ok(feat_head);
         }
 
-        let lca = crate::memory::dag::bind_base(&main_head, &feat_head)?;
+        let lca = crate::memory::dag::bind_base(&main_head, &feat_head)?.into_iter().next();
         let base_text = match lca.as_deref() { Some(h) => crate::memory::dag::recall_snapshot(h)?.content, None => String::new() };
         let left_text = crate::memory::dag::recall_snapshot(&feat_head)?.content;
         let right_text = crate::memory::dag::recall_snapshot(&main_head)?.content;
@@ -146,35 +230,100 @@ impl Commands {
         Ok(resolved_base)
     }
 
+    /// Declare (or replace) a path's typed-field schema: `schema` maps field
+    /// name -> a [`Conversion`] string (e.g. `"int"`, `"timestamp|%Y-%m-%d"`),
+    /// with the reserved name `"content"` applying to the snapshot text
+    /// itself. Once declared, `append` coerces/validates the matching
+    /// fields on every write instead of leaving them opaque strings.
+    pub fn declare_path_schema(&self, path: &str, schema: &BTreeMap<String, String>) -> Result<()> {
+        let path_norm = self.normalize_path_name(path);
+        let mut parsed = PathSchema::new();
+        for (field, conversion) in schema {
+            parsed.insert(field.clone(), conversion.parse()?);
+        }
+        let raw: BTreeMap<String, String> = parsed
+            .iter()
+            .map(|(field, conversion)| (field.clone(), conversion.to_string()))
+            .collect();
+        crate::memory::dag::set_path_schema(&path_norm, &raw)
+    }
+
+    /// The typed-field schema declared for a path via
+    /// [`Self::declare_path_schema`], if any.
+    pub fn path_schema(&self, path: &str) -> Result<PathSchema> {
+        let path_norm = self.normalize_path_name(path);
+        let raw = crate::memory::dag::path_schema(&path_norm)?;
+        let mut out = PathSchema::new();
+        for (field, conversion_str) in raw {
+            out.insert(field, conversion_str.parse()?);
+        }
+        Ok(out)
+    }
+
     /// Append content to a named path with provenance and ethos gating.
+    ///
+    /// If the path has a declared schema (see [`Self::declare_path_schema`]),
+    /// its `"content"` conversion (if any) coerces `content` to its
+    /// canonical form, and every other named field coerces the matching
+    /// string in `meta`. A field named in the schema that fails to parse is
+    /// a [`crate::commands::ConversionError`], surfaced *before* the
+    /// governance/ethos gate runs, so malformed typed data never reaches the
+    /// DAG.
     pub fn append(&self, path: &str, content: &str, meta: Option<Value>) -> Result<String> {
         let path_norm = self.normalize_path_name(path);
         if !crate::memory::dag::path_exists(&path_norm)? { return Err(anyhow!(format!("path '{}' not found; call branch() first", path_norm))); }
 
+        let schema = self.path_schema(&path_norm)?;
+
+        let (content, content_type) = match schema.get("content") {
+            Some(conversion) => {
+                let (canonical, _typed) = canonicalize_typed(content, conversion)?;
+                (canonical, Some(conversion.to_string()))
+            }
+            None => (content.to_string(), None),
+        };
+
+        let mut meta_obj = match meta.unwrap_or_else(|| json!({})) {
+            Value::Object(m) => m,
+            _ => serde_json::Map::new(),
+        };
+        for (field, conversion) in schema.iter().filter(|(f, _)| f.as_str() != "content") {
+            if let Some(Value::String(raw)) = meta_obj.get(field).cloned() {
+                let typed = convert_recalled(&raw, conversion)?;
+                meta_obj.insert(field.clone(), json!({ "value": typed, "raw": raw }));
+            }
+        }
+
         let governed_text = if self.config().services.ethos_enabled {
-            match self.govern_text("replay_append", content) {
+            match self.govern_text("replay_append", &content) {
                 Ok(Some(s)) => s,
                 Ok(None) => return Err(anyhow!("blocked by runtime")),
                 Err(e) => return Err(anyhow!("runtime error: {}", e)),
             }
-        } else { content.to_string() };
+        } else { content };
 
         let parent = self.dag_head(&path_norm)?;
         let base = crate::memory::dag::path_base_snapshot(&path_norm)?;
-        let enrich = json!({
-            "op": "append",
-            "ts": chrono::Utc::now().to_rfc3339(),
-            "actor": "core",
-            "path": path_norm,
-            "parents": parent.clone().into_iter().collect::<Vec<_>>(),
-            "base": base,
-            "content_hash": blake3::hash(governed_text.as_bytes()).to_hex().to_string(),
-        });
-        let bindd_meta = match meta.unwrap_or_else(|| json!({})) {
-            Value::Object(mut m) => { if let Value::Object(e) = enrich { m.extend(e); } Value::Object(m) }
-            _ => enrich,
-        };
-        let state = DagMemoryState { content: governed_text, meta: bindd_meta };
+        let mut enrich_obj = serde_json::Map::new();
+        enrich_obj.insert("op".into(), json!("append"));
+        enrich_obj.insert("ts".into(), json!(chrono::Utc::now().to_rfc3339()));
+        enrich_obj.insert("actor".into(), json!("core"));
+        enrich_obj.insert("path".into(), json!(path_norm));
+        enrich_obj.insert(
+            "parents".into(),
+            json!(parent.clone().into_iter().collect::<Vec<_>>()),
+        );
+        enrich_obj.insert("base".into(), json!(base));
+        enrich_obj.insert(
+            "content_hash".into(),
+            json!(blake3::hash(governed_text.as_bytes()).to_hex().to_string()),
+        );
+        if let Some(content_type) = content_type {
+            enrich_obj.insert("content_type".into(), json!(content_type));
+        }
+
+        meta_obj.extend(enrich_obj);
+        let state = DagMemoryState { content: governed_text, meta: Value::Object(meta_obj) };
         let id = self.replay_extend_path(&path_norm, state)?;
         Ok(id)
     }
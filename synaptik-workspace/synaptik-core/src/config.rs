@@ -1,10 +1,15 @@
 use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tracing;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoreConfig {
     #[serde(default)]
     pub system: SystemConfig,
@@ -24,13 +29,49 @@ pub struct CoreConfig {
     pub policies: PoliciesConfig,
 }
 
+/// Which layer/file ultimately set one dotted config key, as recorded by
+/// [`CoreConfig::load_layered`].
+#[derive(Debug, Clone)]
+pub struct ProvenanceEntry {
+    pub key: String,
+    pub layer: String,
+}
+
 impl CoreConfig {
+    /// Layered load: built-in defaults < `config.toml` < `SYNAPTIK__*`
+    /// process environment variables (see [`apply_env_var_overrides`]).
     pub fn load(root: &Path) -> Result<Self> {
+        Self::load_env(root, None)
+    }
+
+    /// Like [`Self::load`], but overlays a named `[env.<name>]` table over
+    /// the base config first (mirroring `wrangler.toml`'s `[env.production]`
+    /// sections): present env fields replace base fields, absent ones
+    /// inherit, and `table`-valued sections merge key-by-key rather than
+    /// being replaced wholesale. `name: None` behaves exactly like `load`.
+    pub fn load_env(root: &Path, name: Option<&str>) -> Result<Self> {
         let path = root.join("config.toml");
         let mut cfg = if path.exists() {
             let text = fs::read_to_string(&path)
                 .with_context(|| format!("reading config file {}", path.display()))?;
-            toml::from_str::<CoreConfig>(&text)
+            let mut base: toml::Value = toml::from_str(&text)
+                .with_context(|| format!("parsing config file {}", path.display()))?;
+
+            if let Some(name) = name {
+                let overlay = base
+                    .get("env")
+                    .and_then(|envs| envs.get(name))
+                    .cloned();
+                if let Some(overlay) = overlay {
+                    merge_toml_values(&mut base, &overlay);
+                }
+            }
+            // The `[env.*]` tree itself isn't part of `CoreConfig`'s schema.
+            if let toml::Value::Table(table) = &mut base {
+                table.remove("env");
+            }
+
+            base.try_into::<CoreConfig>()
                 .with_context(|| format!("parsing config file {}", path.display()))?
         } else {
             tracing::info!(
@@ -39,10 +80,155 @@ impl CoreConfig {
             );
             CoreConfig::default()
         };
+        apply_env_var_overrides(&mut cfg);
         cfg.resolve_paths(root);
+        cfg.validate(root)?;
         Ok(cfg)
     }
 
+    /// Load once, then watch `config.toml` for changes and atomically swap
+    /// in a freshly reloaded [`CoreConfig`] on every change, debounced
+    /// ~250ms (the watcher polls the file's mtime on that interval, which
+    /// naturally coalesces an editor's save burst into one reload). A
+    /// change that fails to parse is logged via `tracing::warn!` and the
+    /// previously-loaded config is kept in place rather than the process
+    /// crashing.
+    ///
+    /// Services should hold the returned `Arc<ArcSwap<CoreConfig>>` and call
+    /// `.load()` on it per operation (not once at startup), so toggles like
+    /// `services.ethos_enabled` or `cache.max_hot_memory_mb` take effect
+    /// without a restart. Dropping the returned [`WatchHandle`] stops the
+    /// watcher thread.
+    pub fn watch(root: &Path) -> Result<(Arc<ArcSwap<CoreConfig>>, WatchHandle)> {
+        let initial = CoreConfig::load(root)?;
+        let shared = Arc::new(ArcSwap::from_pointee(initial));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let watcher_shared = shared.clone();
+        let watcher_stop = stop.clone();
+        let watcher_root = root.to_path_buf();
+        let path = watcher_root.join("config.toml");
+
+        let thread = std::thread::Builder::new()
+            .name("config-watcher".into())
+            .spawn(move || {
+                let mut last_seen = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                while !watcher_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(Duration::from_millis(250));
+                    let modified = match fs::metadata(&path).and_then(|m| m.modified()) {
+                        Ok(m) => m,
+                        Err(_) => continue,
+                    };
+                    if Some(modified) == last_seen {
+                        continue;
+                    }
+                    last_seen = Some(modified);
+                    match CoreConfig::load(&watcher_root) {
+                        Ok(fresh) => watcher_shared.store(Arc::new(fresh)),
+                        Err(e) => tracing::warn!(
+                            "failed to reload {}: {e:#}; keeping previous config",
+                            path.display()
+                        ),
+                    }
+                }
+            })
+            .context("spawning config watcher thread")?;
+
+        Ok((
+            shared,
+            WatchHandle {
+                stop,
+                thread: Some(thread),
+            },
+        ))
+    }
+
+    /// Layered load with includes and provenance, for operators who want to
+    /// keep machine-local overrides (`config.local.toml`, gitignored) out of
+    /// the committed `config.toml`.
+    ///
+    /// Merges, in ascending precedence, like Mercurial's `%include`/`%unset`
+    /// config layering:
+    /// 1. the embedded [`crate::commands::init::DEFAULT_CONFIG_TOML`]
+    /// 2. `config.toml`
+    /// 3. `config.local.toml` (optional)
+    /// 4. `COGNIV_`-prefixed process environment variables, dotted via `__`
+    ///    (e.g. `COGNIV_MEMORY__CACHE_PATH` -> `memory.cache_path`)
+    ///
+    /// Any layer or included file may carry an `include = ["path", ...]`
+    /// array (resolved relative to the including file's directory, or
+    /// `root` for the two top-level files) pulling in more TOML merged at
+    /// that layer's position, and an `unset = ["dotted.key", ...]` array
+    /// removing keys inherited from lower layers. A cycle among includes is
+    /// rejected rather than looping forever.
+    ///
+    /// Returns the resolved config alongside a per-key [`ProvenanceEntry`]
+    /// list (sorted by key) recording which layer/file ultimately set each
+    /// value, so [`crate::commands::init::ensure_initialized`] can surface
+    /// where a surprising setting came from.
+    pub fn load_layered(root: &Path) -> Result<(Self, Vec<ProvenanceEntry>)> {
+        let mut merged: toml::Value = toml::from_str(crate::commands::init::DEFAULT_CONFIG_TOML)
+            .context("parsing embedded DEFAULT_CONFIG_TOML")?;
+        let mut provenance: BTreeMap<String, String> = BTreeMap::new();
+        flatten_keys(&merged, "", &mut provenance, "default");
+
+        let mut visiting: Vec<PathBuf> = Vec::new();
+
+        let config_path = root.join("config.toml");
+        if config_path.exists() {
+            merge_layer_file(
+                &mut merged,
+                &mut provenance,
+                &config_path,
+                "config.toml",
+                &mut visiting,
+            )?;
+        }
+
+        let local_path = root.join("config.local.toml");
+        if local_path.exists() {
+            merge_layer_file(
+                &mut merged,
+                &mut provenance,
+                &local_path,
+                "config.local.toml",
+                &mut visiting,
+            )?;
+        }
+
+        // The `[env.*]` overlay tree (see `load_env`) isn't part of the schema.
+        if let toml::Value::Table(table) = &mut merged {
+            table.remove("env");
+        }
+
+        const ENV_PREFIX: &str = "COGNIV_";
+        for (key, value) in std::env::vars() {
+            let Some(rest) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let segments: Vec<String> = rest.split("__").map(|s| s.to_ascii_lowercase()).collect();
+            if segments.iter().any(|s| s.is_empty()) {
+                continue;
+            }
+            let dotted = segments.join(".");
+            toml_set_path(&mut merged, &segments, parse_env_scalar(&value));
+            provenance.insert(dotted, format!("env:{key}"));
+        }
+
+        let mut cfg: CoreConfig = merged
+            .try_into()
+            .context("parsing layered configuration")?;
+        apply_env_var_overrides(&mut cfg);
+        cfg.resolve_paths(root);
+        cfg.validate(root)?;
+
+        let entries = provenance
+            .into_iter()
+            .map(|(key, layer)| ProvenanceEntry { key, layer })
+            .collect();
+        Ok((cfg, entries))
+    }
+
     fn resolve_paths(&mut self, root: &Path) {
         self.memory.cache_path = absolutize(root, &self.memory.cache_path);
         self.memory.dag_path = absolutize(root, &self.memory.dag_path);
@@ -53,9 +239,114 @@ impl CoreConfig {
         self.logbook.agent_actions = absolutize(root, &self.logbook.agent_actions);
         self.logbook.contract_violations = absolutize(root, &self.logbook.contract_violations);
         self.logbook.contracts_log = absolutize(root, &self.logbook.contracts_log);
+        self.logbook.checkpoints_log = absolutize(root, &self.logbook.checkpoints_log);
         self.contracts.path = absolutize(root, &self.contracts.path);
         self.contracts.wasm_module_path = absolutize(root, &self.contracts.wasm_module_path);
     }
+
+    /// Fail-fast sanity check run at the end of [`Self::load_env`] (so every
+    /// entry point, including [`Self::watch`]'s reload, goes through it):
+    /// cross-field invariants the struct itself can't express (reflection
+    /// pool sizing, the wasm contract-eval toggle's prerequisites, the
+    /// pattern-length/file-size relationship), plus containment of every
+    /// resolved path under `root`. A relative path in `config.toml` that
+    /// climbs out via `..` is rejected; a path the user wrote as absolute is
+    /// trusted as an explicit choice and left unchecked, even if it lands
+    /// outside `root`.
+    pub fn validate(&self, root: &Path) -> Result<()> {
+        if self.policies.reflection_min_count > self.policies.reflection_pool_size {
+            anyhow::bail!(
+                "policies.reflection_min_count ({}) must be <= policies.reflection_pool_size ({})",
+                self.policies.reflection_min_count,
+                self.policies.reflection_pool_size
+            );
+        }
+        if self.policies.reflection_max_keywords < 1 {
+            anyhow::bail!(
+                "policies.reflection_max_keywords ({}) must be >= 1",
+                self.policies.reflection_max_keywords
+            );
+        }
+        if self.cache.max_hot_memory_mb == 0 {
+            anyhow::bail!("cache.max_hot_memory_mb must be > 0");
+        }
+        let max_file_bytes = self.contracts.max_file_kb.saturating_mul(1024);
+        if self.contracts.max_pattern_len > max_file_bytes {
+            anyhow::bail!(
+                "contracts.max_pattern_len ({}) must be <= contracts.max_file_kb*1024 ({})",
+                self.contracts.max_pattern_len,
+                max_file_bytes
+            );
+        }
+        if self.contracts.wasm_enabled {
+            if !self.contracts.wasm_module_path.exists() {
+                anyhow::bail!(
+                    "contracts.wasm_module_path {:?} does not exist, but contracts.wasm_enabled is true",
+                    self.contracts.wasm_module_path
+                );
+            }
+            if self.contracts.wasm_export.trim().is_empty() {
+                anyhow::bail!(
+                    "contracts.wasm_export must be non-empty when contracts.wasm_enabled is true"
+                );
+            }
+        }
+
+        for (field, path) in [
+            ("memory.cache_path", &self.memory.cache_path),
+            ("memory.dag_path", &self.memory.dag_path),
+            ("memory.archive_path", &self.memory.archive_path),
+            ("logbook.path", &self.logbook.path),
+            ("logbook.aggregate", &self.logbook.aggregate),
+            ("logbook.ethics_log", &self.logbook.ethics_log),
+            ("logbook.agent_actions", &self.logbook.agent_actions),
+            ("logbook.contract_violations", &self.logbook.contract_violations),
+            ("logbook.contracts_log", &self.logbook.contracts_log),
+            ("logbook.checkpoints_log", &self.logbook.checkpoints_log),
+            ("contracts.path", &self.contracts.path),
+            ("contracts.wasm_module_path", &self.contracts.wasm_module_path),
+        ] {
+            assert_no_root_escape(root, field, path)?;
+        }
+        Ok(())
+    }
+
+    /// Write `CoreConfig::default()` to `<root>/config.toml` as a
+    /// discoverable starting file. A no-op if a config file already exists
+    /// there -- this never clobbers a user's config.
+    pub fn write_default_template(root: &Path) -> Result<()> {
+        let path = root.join("config.toml");
+        if path.exists() {
+            return Ok(());
+        }
+        let toml_text = CoreConfig::default().effective_toml()?;
+        fs::write(&path, toml_text)
+            .with_context(|| format!("writing default config template to {}", path.display()))
+    }
+
+    /// Serialize this (already-resolved) config back to TOML, e.g. to dump
+    /// exactly what the system is running with after defaults, file, env
+    /// overlays, and `SYNAPTIK__*` overrides have all been applied.
+    pub fn effective_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("serializing effective config to TOML")
+    }
+}
+
+/// Owns the background thread spawned by [`CoreConfig::watch`]. Dropping it
+/// signals the thread to stop and joins it, so the watcher never outlives
+/// its owner.
+pub struct WatchHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
 }
 
 impl Default for CoreConfig {
@@ -73,7 +364,7 @@ impl Default for CoreConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemConfig {
     #[serde(default = "SystemConfig::default_name")]
     pub name: String,
@@ -100,7 +391,7 @@ impl Default for SystemConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryConfig {
     #[serde(default = "MemoryConfig::default_cache_path")]
     pub cache_path: PathBuf,
@@ -108,6 +399,19 @@ pub struct MemoryConfig {
     pub dag_path: PathBuf,
     #[serde(default = "MemoryConfig::default_archive_path")]
     pub archive_path: PathBuf,
+    /// Which backend serves the cold (archived) tier. `Filesystem` (the
+    /// historical behavior) keeps blobs under `archive_path`; `S3` points
+    /// cold recall at a remote S3-compatible bucket instead.
+    #[serde(default)]
+    pub cold_store: ColdStoreKind,
+    /// Which engine backs the `services::storage_backend::StorageBackend`
+    /// surface for callers that only need it (see `benches/load_ingest.rs`).
+    /// `Sqlite` (the historical behavior) is `Memory` at `cache_path`;
+    /// `Sled` opts into the embedded KV engine instead. Does not affect
+    /// `Commands`, which still hardcodes `Memory` for FTS/contracts/consent/
+    /// snapshot features `StorageBackend` doesn't cover.
+    #[serde(default)]
+    pub hot_store: HotStoreKind,
 }
 
 impl MemoryConfig {
@@ -130,11 +434,74 @@ impl Default for MemoryConfig {
             cache_path: Self::default_cache_path(),
             dag_path: Self::default_dag_path(),
             archive_path: Self::default_archive_path(),
+            cold_store: ColdStoreKind::default(),
+            hot_store: HotStoreKind::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Selects the `services::cold_store::ColdStore` implementation the cold
+/// tier is built against. CID computation (`blake3(bytes)`, hex) is the same
+/// for every variant, so a blob archived under one backend stays retrievable
+/// by the same CID after switching to another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ColdStoreKind {
+    /// Local filesystem, rooted at `MemoryConfig::archive_path`. Default.
+    Filesystem,
+    /// Remote S3-compatible bucket, keyed by `prefix/cid` (or bare `cid` if
+    /// `prefix` is unset).
+    S3 {
+        bucket: String,
+        #[serde(default = "ColdStoreKind::default_endpoint")]
+        endpoint: String,
+        #[serde(default)]
+        prefix: Option<String>,
+        #[serde(default = "ColdStoreKind::default_region")]
+        region: String,
+    },
+}
+
+impl ColdStoreKind {
+    fn default_endpoint() -> String {
+        "https://s3.amazonaws.com".to_string()
+    }
+
+    fn default_region() -> String {
+        "us-east-1".to_string()
+    }
+}
+
+impl Default for ColdStoreKind {
+    fn default() -> Self {
+        ColdStoreKind::Filesystem
+    }
+}
+
+/// Selects the `services::storage_backend::StorageBackend` implementation
+/// built by `services::storage_backend::build_hot_store`. Unlike
+/// `ColdStoreKind`, this doesn't reach every caller yet -- `Commands` holds
+/// a concrete `Memory` for its full feature surface, so only callers that
+/// only need `StorageBackend`'s narrower surface (upsert/recall/promote/
+/// delete/scan) can honor this setting today.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HotStoreKind {
+    /// SQLite-backed `Memory`, rooted at `MemoryConfig::cache_path`. Default.
+    Sqlite,
+    /// Embedded `sled` KV store rooted at `path`, for write-heavy ingestion
+    /// that doesn't need SQL-queryable replay. Requires the `sled_backend`
+    /// build feature.
+    Sled { path: PathBuf },
+}
+
+impl Default for HotStoreKind {
+    fn default() -> Self {
+        HotStoreKind::Sqlite
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogbookConfig {
     #[serde(default = "LogbookConfig::default_path")]
     pub path: PathBuf,
@@ -148,6 +515,16 @@ pub struct LogbookConfig {
     pub contract_violations: PathBuf,
     #[serde(default = "LogbookConfig::default_contracts_log")]
     pub contracts_log: PathBuf,
+    #[serde(default = "LogbookConfig::default_checkpoints_log")]
+    pub checkpoints_log: PathBuf,
+    /// Once a stream's active segment reaches this many bytes, it's sealed
+    /// to a timestamped segment and a fresh active segment is started. `0`
+    /// disables rotation. See `services::logbook`.
+    #[serde(default = "LogbookConfig::default_max_segment_bytes")]
+    pub max_segment_bytes: u64,
+    /// Gzip-compress a segment as soon as it's sealed by rotation.
+    #[serde(default = "LogbookConfig::default_compress_segments")]
+    pub compress_segments: bool,
 }
 
 impl LogbookConfig {
@@ -174,6 +551,18 @@ impl LogbookConfig {
     fn default_contracts_log() -> PathBuf {
         PathBuf::from("logbook/contracts.jsonl")
     }
+
+    fn default_checkpoints_log() -> PathBuf {
+        PathBuf::from("logbook/checkpoints.jsonl")
+    }
+
+    fn default_max_segment_bytes() -> u64 {
+        10 * 1024 * 1024
+    }
+
+    fn default_compress_segments() -> bool {
+        true
+    }
 }
 
 impl Default for LogbookConfig {
@@ -185,11 +574,14 @@ impl Default for LogbookConfig {
             agent_actions: Self::default_agent_actions(),
             contract_violations: Self::default_contract_violations(),
             contracts_log: Self::default_contracts_log(),
+            checkpoints_log: Self::default_checkpoints_log(),
+            max_segment_bytes: Self::default_max_segment_bytes(),
+            compress_segments: Self::default_compress_segments(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServicesConfig {
     #[serde(default = "ServicesConfig::default_true")]
     pub ethos_enabled: bool,
@@ -260,17 +652,97 @@ pub struct CompactionPolicy {
     pub archive_to_dag: bool,
     #[serde(default)]
     pub summarizer: SummarizerKind,
+    /// Optional target length for future use. Accepts a plain integer
+    /// (chars, unchanged for back-compat) or a human-readable string like
+    /// `"1.5K"` / `"2000"` -- see [`deserialize_human_count`].
+    #[serde(default, deserialize_with = "deserialize_human_count")]
+    pub target_chars: Option<usize>,
+    /// How many delta-encoded appends `dag::extend_path_with_policy` will
+    /// chain before writing a full "keyframe" copy, bounding how far
+    /// `recall_snapshot` has to replay patches forward. `0` disables delta
+    /// encoding entirely (every append is a keyframe).
+    #[serde(default = "CompactionPolicy::default_delta_keyframe_interval")]
+    pub delta_keyframe_interval: u32,
+    /// Minimum byte-level similarity (0.0-1.0, common prefix+suffix over
+    /// the longer of the two snapshots) a new snapshot must share with its
+    /// parent before it's stored as a delta patch; below this a full
+    /// keyframe is written instead. See
+    /// [`crate::memory::dag::extend_path_with_policy`].
+    #[serde(default = "CompactionPolicy::default_delta_min_similarity")]
+    pub delta_min_similarity: f32,
+    /// Max attempts `Compactor::invoke_summarizer` makes against the
+    /// configured [`Summarizer`](crate::services::compactor::Summarizer)
+    /// before giving up and falling back to the heuristic snippet. `1`
+    /// (the default) preserves the old fail-fast-to-heuristic behavior.
+    #[serde(default = "CompactionPolicy::default_summarizer_max_attempts")]
+    pub summarizer_max_attempts: u32,
+    /// Base delay for the `base * 2^(attempt-1)` exponential backoff between
+    /// summarizer retries, in milliseconds.
+    #[serde(default = "CompactionPolicy::default_summarizer_base_delay_ms")]
+    pub summarizer_base_delay_ms: u64,
+    /// Upper bound the computed backoff delay is clamped to, in milliseconds.
+    #[serde(default = "CompactionPolicy::default_summarizer_max_delay_ms")]
+    pub summarizer_max_delay_ms: u64,
+    /// When set, the backoff delay is drawn uniformly from `[0, computed]`
+    /// ("full jitter") instead of used as-is, to avoid synchronized retry
+    /// storms across concurrent compactions.
     #[serde(default)]
-    pub target_chars: Option<usize>, // optional target length for future use
+    pub summarizer_full_jitter: bool,
+    /// Per-attempt wall-clock budget before an in-flight summarizer call is
+    /// abandoned and counted as a failed attempt, in milliseconds.
+    #[serde(default = "CompactionPolicy::default_summarizer_attempt_timeout_ms")]
+    pub summarizer_attempt_timeout_ms: u64,
+    /// How many distinct DAG nodes a level may hold (see
+    /// [`crate::services::memory::Memory::promote_to_dag`]'s `"level"` meta
+    /// tag) before [`crate::services::compactor::Compactor::compact_dag_level`]
+    /// merges the oldest `dag_merge_fanout` of them into one node a level up.
+    #[serde(default = "CompactionPolicy::default_dag_level_max_nodes")]
+    pub dag_level_max_nodes: u32,
+    /// How many sibling DAG nodes `compact_dag_level` concatenates and
+    /// summarizes into a single merged node per pass.
+    #[serde(default = "CompactionPolicy::default_dag_merge_fanout")]
+    pub dag_merge_fanout: u32,
 }
 
 impl CompactionPolicy {
     fn default_archive_to_dag() -> bool {
         true
     }
+
+    fn default_delta_keyframe_interval() -> u32 {
+        32
+    }
+
+    fn default_delta_min_similarity() -> f32 {
+        0.5
+    }
+
+    fn default_summarizer_max_attempts() -> u32 {
+        1
+    }
+
+    fn default_summarizer_base_delay_ms() -> u64 {
+        100
+    }
+
+    fn default_summarizer_max_delay_ms() -> u64 {
+        5_000
+    }
+
+    fn default_summarizer_attempt_timeout_ms() -> u64 {
+        2_000
+    }
+
+    fn default_dag_level_max_nodes() -> u32 {
+        16
+    }
+
+    fn default_dag_merge_fanout() -> u32 {
+        8
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractsConfig {
     #[serde(default = "ContractsConfig::default_path")]
     pub path: PathBuf,
@@ -284,7 +756,13 @@ pub struct ContractsConfig {
     pub max_rules: usize,
     #[serde(default = "ContractsConfig::default_max_pattern_len")]
     pub max_pattern_len: usize,
-    #[serde(default = "ContractsConfig::default_max_file_kb")]
+    /// Accepts a plain integer (kilobytes, unchanged for back-compat) or a
+    /// human-readable string like `"256KiB"` / `"1MB"` -- see
+    /// [`deserialize_human_kilobytes`].
+    #[serde(
+        default = "ContractsConfig::default_max_file_kb",
+        deserialize_with = "deserialize_human_kilobytes"
+    )]
     pub max_file_kb: usize,
     #[serde(default)]
     pub allow_allow_rules: bool,
@@ -296,6 +774,8 @@ pub struct ContractsConfig {
     pub wasm_module_path: PathBuf,
     #[serde(default = "ContractsConfig::default_wasm_export")]
     pub wasm_export: String,
+    #[serde(default)]
+    pub risk_aggregation: RiskAggregation,
 }
 
 impl ContractsConfig {
@@ -347,13 +827,91 @@ impl Default for ContractsConfig {
             wasm_enabled: Self::default_wasm_enabled(),
             wasm_module_path: Self::default_wasm_module_path(),
             wasm_export: Self::default_wasm_export(),
+            risk_aggregation: RiskAggregation::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// How `ethos::precheck` folds violated-rule severities into one effective
+/// risk label. `Max` (the historical behavior) can hide a proposal that
+/// trips many Low/Medium rules at once; `WeightedSum` and `CountThreshold`
+/// let that kind of pattern escalate instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RiskAggregation {
+    /// Effective risk is the single highest-severity violated rule (or the
+    /// explicit `risk` field), whichever ranks higher.
+    Max,
+    /// Effective risk is `sum(weight(severity))` across every violated rule,
+    /// bucketed back to a label via the `*_threshold` fields (lowest
+    /// threshold met by the score wins; below `medium_threshold` is Low).
+    WeightedSum {
+        #[serde(default = "RiskAggregation::default_low_weight")]
+        low_weight: f32,
+        #[serde(default = "RiskAggregation::default_medium_weight")]
+        medium_weight: f32,
+        #[serde(default = "RiskAggregation::default_high_weight")]
+        high_weight: f32,
+        #[serde(default = "RiskAggregation::default_critical_weight")]
+        critical_weight: f32,
+        #[serde(default = "RiskAggregation::default_medium_threshold")]
+        medium_threshold: f32,
+        #[serde(default = "RiskAggregation::default_high_threshold")]
+        high_threshold: f32,
+        #[serde(default = "RiskAggregation::default_critical_threshold")]
+        critical_threshold: f32,
+    },
+    /// Effective risk escalates to High once `medium_count_for_high` or more
+    /// Medium-severity rules are violated (Critical/High violations still
+    /// rank above that via the usual max comparison).
+    CountThreshold {
+        #[serde(default = "RiskAggregation::default_medium_count_for_high")]
+        medium_count_for_high: u32,
+    },
+}
+
+impl RiskAggregation {
+    fn default_low_weight() -> f32 {
+        1.0
+    }
+    fn default_medium_weight() -> f32 {
+        3.0
+    }
+    fn default_high_weight() -> f32 {
+        7.0
+    }
+    fn default_critical_weight() -> f32 {
+        15.0
+    }
+    fn default_medium_threshold() -> f32 {
+        3.0
+    }
+    fn default_high_threshold() -> f32 {
+        7.0
+    }
+    fn default_critical_threshold() -> f32 {
+        15.0
+    }
+    fn default_medium_count_for_high() -> u32 {
+        3
+    }
+}
+
+impl Default for RiskAggregation {
+    fn default() -> Self {
+        RiskAggregation::Max
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
-    #[serde(default = "CacheConfig::default_max_hot_memory_mb")]
+    /// Accepts a plain integer (megabytes, unchanged for back-compat) or a
+    /// human-readable string like `"50MB"` / `"1.5GiB"` -- see
+    /// [`deserialize_human_megabytes`].
+    #[serde(
+        default = "CacheConfig::default_max_hot_memory_mb",
+        deserialize_with = "deserialize_human_megabytes"
+    )]
     pub max_hot_memory_mb: usize,
 }
 
@@ -371,27 +929,52 @@ impl Default for CacheConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditConfig {
     #[serde(default = "AuditConfig::default_retention_days")]
     pub retention_days: u32,
+    /// Auto-seal the logbook (see `services::audit::seal_logbook`) every
+    /// this many hash-chained records appended since the last checkpoint.
+    /// `0` disables auto-sealing; checkpoints can still be requested
+    /// on demand.
+    #[serde(default = "AuditConfig::default_checkpoint_interval")]
+    pub checkpoint_interval: usize,
+    /// Ed25519 secret key (hex, optionally `ed25519:`-prefixed like
+    /// `contracts-signer`'s `--sk-hex`) used to sign checkpoint Merkle
+    /// roots. Checkpoints are written unsigned when this is unset.
+    #[serde(default)]
+    pub checkpoint_signing_key_hex: Option<String>,
+    /// Label stored as `signing_key_id` on a signed checkpoint.
+    #[serde(default = "AuditConfig::default_checkpoint_signing_key_id")]
+    pub checkpoint_signing_key_id: String,
 }
 
 impl AuditConfig {
     fn default_retention_days() -> u32 {
         365
     }
+
+    fn default_checkpoint_interval() -> usize {
+        100
+    }
+
+    fn default_checkpoint_signing_key_id() -> String {
+        "checkpoint".to_string()
+    }
 }
 
 impl Default for AuditConfig {
     fn default() -> Self {
         Self {
             retention_days: Self::default_retention_days(),
+            checkpoint_interval: Self::default_checkpoint_interval(),
+            checkpoint_signing_key_hex: None,
+            checkpoint_signing_key_id: Self::default_checkpoint_signing_key_id(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoliciesConfig {
     #[serde(default = "PoliciesConfig::default_promote_hot_threshold")]
     pub promote_hot_threshold: usize,
@@ -403,10 +986,25 @@ pub struct PoliciesConfig {
     pub reflection_max_keywords: usize,
     #[serde(default = "PoliciesConfig::default_reflection_pool_size")]
     pub reflection_pool_size: usize,
+    /// Decay rate `lambda` for recency-weighting in `compute_reflection`'s
+    /// TF-IDF scoring: `w_i = exp(-lambda * (n-1-i))` for summary index `i`
+    /// (oldest = 0, newest = n-1). Higher values favor recent summaries more
+    /// sharply; `0.0` degenerates to unweighted term frequency.
+    #[serde(default = "PoliciesConfig::default_reflection_recency_lambda")]
+    pub reflection_recency_lambda: f64,
     #[serde(default = "PoliciesConfig::default_summary_min_len")]
     pub summary_min_len: usize,
     #[serde(default = "PoliciesConfig::default_log_preview_len")]
     pub log_preview_len: usize,
+    /// How many of each key's newest versions `LobeStore::compact` keeps
+    /// (plus whatever `LATEST` points at) before pruning the rest.
+    #[serde(default = "PoliciesConfig::default_lobe_retain_versions")]
+    pub lobe_retain_versions: usize,
+    /// Hot-tier byte budget per lobe; `Commands::remember` evicts the oldest
+    /// hot rows (via `Compactor::evict_to_quota`) once a lobe exceeds this.
+    /// `None` (the default) means unlimited.
+    #[serde(default)]
+    pub max_hot_bytes_per_lobe: Option<u64>,
 }
 
 impl PoliciesConfig {
@@ -430,6 +1028,10 @@ impl PoliciesConfig {
         20
     }
 
+    fn default_reflection_recency_lambda() -> f64 {
+        0.15
+    }
+
     fn default_summary_min_len() -> usize {
         500
     }
@@ -437,6 +1039,10 @@ impl PoliciesConfig {
     fn default_log_preview_len() -> usize {
         160
     }
+
+    fn default_lobe_retain_versions() -> usize {
+        5
+    }
 }
 
 impl Default for PoliciesConfig {
@@ -447,12 +1053,401 @@ impl Default for PoliciesConfig {
             reflection_min_count: Self::default_reflection_min_count(),
             reflection_max_keywords: Self::default_reflection_max_keywords(),
             reflection_pool_size: Self::default_reflection_pool_size(),
+            reflection_recency_lambda: Self::default_reflection_recency_lambda(),
             summary_min_len: Self::default_summary_min_len(),
             log_preview_len: Self::default_log_preview_len(),
+            lobe_retain_versions: Self::default_lobe_retain_versions(),
+            max_hot_bytes_per_lobe: None,
+        }
+    }
+}
+
+/// Recursively overlay `overlay` onto `base`: matching tables merge
+/// key-by-key (so an env overlay only needs to list the fields it changes),
+/// while scalars and arrays in `overlay` replace `base` outright.
+fn merge_toml_values(base: &mut toml::Value, overlay: &toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
+            for (key, overlay_value) in overlay_table {
+                match base_table.get_mut(key) {
+                    Some(base_value) => merge_toml_values(base_value, overlay_value),
+                    None => {
+                        base_table.insert(key.clone(), overlay_value.clone());
+                    }
+                }
+            }
+        }
+        (base_value, overlay_value) => {
+            *base_value = overlay_value.clone();
+        }
+    }
+}
+
+/// Merge one `config.toml`/`config.local.toml`-style layer file into
+/// `base`, recursively pulling in its `include = [...]` files first (so
+/// they land at this layer's position in precedence order, with the
+/// layer's own keys then taking priority over anything they set), then
+/// applying its `unset = [...]` list against the merged result. `visiting`
+/// tracks the include chain currently being resolved so a cycle is
+/// rejected instead of recursing forever.
+fn merge_layer_file(
+    base: &mut toml::Value,
+    provenance: &mut BTreeMap<String, String>,
+    path: &Path,
+    layer_label: &str,
+    visiting: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if visiting.contains(&canonical) {
+        anyhow::bail!("config include cycle detected at {}", path.display());
+    }
+    visiting.push(canonical);
+
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("reading config layer {}", path.display()))?;
+    let mut value: toml::Value = toml::from_str(&text)
+        .with_context(|| format!("parsing config layer {}", path.display()))?;
+
+    let includes = take_string_array(&mut value, "include");
+    let unsets = take_string_array(&mut value, "unset");
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    for include in &includes {
+        let include_path = dir.join(include);
+        let include_label = include_path.display().to_string();
+        merge_layer_file(base, provenance, &include_path, &include_label, visiting)?;
+    }
+
+    flatten_keys(&value, "", provenance, layer_label);
+    merge_toml_values(base, &value);
+
+    for key in &unsets {
+        let segments: Vec<&str> = key.split('.').collect();
+        toml_remove_path(base, &segments);
+        provenance.remove(key);
+    }
+
+    visiting.pop();
+    Ok(())
+}
+
+/// Remove and return a top-level `key = [...]` array of strings from
+/// `value`, if present (used to pull `include`/`unset` out of a layer
+/// before it's merged, so they aren't mistaken for real config fields).
+fn take_string_array(value: &mut toml::Value, key: &str) -> Vec<String> {
+    let toml::Value::Table(table) = value else {
+        return Vec::new();
+    };
+    let Some(toml::Value::Array(items)) = table.remove(key) else {
+        return Vec::new();
+    };
+    items
+        .into_iter()
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
+/// Record the layer/file that set each leaf (non-table) value in `value`,
+/// keyed by its dotted path from the document root.
+fn flatten_keys(
+    value: &toml::Value,
+    prefix: &str,
+    provenance: &mut BTreeMap<String, String>,
+    layer_label: &str,
+) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() || prefix.is_empty() => {
+            for (k, v) in table {
+                let full = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{prefix}.{k}")
+                };
+                flatten_keys(v, &full, provenance, layer_label);
+            }
+        }
+        _ => {
+            if !prefix.is_empty() {
+                provenance.insert(prefix.to_string(), layer_label.to_string());
+            }
+        }
+    }
+}
+
+/// Set `base`'s value at a dotted key path, creating intermediate tables
+/// as needed (used for the `COGNIV_`-prefixed environment override layer).
+fn toml_set_path(base: &mut toml::Value, path: &[String], value: toml::Value) {
+    if path.is_empty() {
+        return;
+    }
+    if !matches!(base, toml::Value::Table(_)) {
+        *base = toml::Value::Table(toml::value::Table::new());
+    }
+    let toml::Value::Table(map) = base else {
+        return;
+    };
+    if path.len() == 1 {
+        map.insert(path[0].clone(), value);
+        return;
+    }
+    let entry = map
+        .entry(path[0].clone())
+        .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+    toml_set_path(entry, &path[1..], value);
+}
+
+/// Remove the value at a dotted key path, if present (used by `unset`).
+fn toml_remove_path(base: &mut toml::Value, path: &[&str]) {
+    if path.is_empty() {
+        return;
+    }
+    let toml::Value::Table(map) = base else {
+        return;
+    };
+    if path.len() == 1 {
+        map.remove(path[0]);
+        return;
+    }
+    if let Some(sub) = map.get_mut(path[0]) {
+        toml_remove_path(sub, &path[1..]);
+    }
+}
+
+/// Parse a `COGNIV_*` environment variable's string value into the most
+/// specific TOML scalar it looks like (bool, then integer, then float,
+/// falling back to a plain string).
+fn parse_env_scalar(value: &str) -> toml::Value {
+    if let Ok(b) = value.parse::<bool>() {
+        toml::Value::Boolean(b)
+    } else if let Ok(i) = value.parse::<i64>() {
+        toml::Value::Integer(i)
+    } else if let Ok(f) = value.parse::<f64>() {
+        toml::Value::Float(f)
+    } else {
+        toml::Value::String(value.to_string())
+    }
+}
+
+/// Either a bare TOML integer (the field's pre-existing unit, kept for
+/// back-compat with old configs) or a human-readable size string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum HumanSize {
+    Int(u64),
+    Str(String),
+}
+
+/// Parse a human-readable size like `"50MB"`, `"256KiB"`, or `"1.5GB"` into
+/// raw bytes. Decimal units (`B/KB/MB/GB/TB`) are ×1000; binary units
+/// (`KiB/MiB/GiB/TiB`) are ×1024; both are matched case-insensitively. A
+/// bare number with no unit suffix means bytes.
+fn parse_human_size_bytes(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split_at = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num_part, unit_part) = s.split_at(split_at);
+    let num: f64 = num_part
+        .parse()
+        .map_err(|_| format!("not a valid size: {s:?}"))?;
+    if num.is_sign_negative() {
+        return Err(format!("size must not be negative: {s:?}"));
+    }
+    let unit = unit_part.trim();
+    let multiplier: f64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "kb" => 1_000.0,
+        "mb" => 1_000_000.0,
+        "gb" => 1_000_000_000.0,
+        "tb" => 1_000_000_000_000.0,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        other => return Err(format!("unknown size unit {other:?} in {s:?}")),
+    };
+    Ok((num * multiplier).round() as u64)
+}
+
+/// Resolve a [`HumanSize`] into the field's native unit: a bare integer
+/// passes through unchanged (the pre-existing unit), a string is parsed as
+/// bytes via [`parse_human_size_bytes`] and then converted into
+/// `unit_bytes`-sized units (e.g. `1_000_000` for megabytes).
+fn human_size_in_unit(value: HumanSize, unit_bytes: u64) -> Result<u64, String> {
+    match value {
+        HumanSize::Int(n) => Ok(n),
+        HumanSize::Str(s) => {
+            let bytes = parse_human_size_bytes(&s)?;
+            Ok((bytes as f64 / unit_bytes as f64).round() as u64)
+        }
+    }
+}
+
+fn deserialize_human_megabytes<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = HumanSize::deserialize(deserializer)?;
+    human_size_in_unit(value, 1_000_000)
+        .map(|n| n as usize)
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_human_kilobytes<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = HumanSize::deserialize(deserializer)?;
+    human_size_in_unit(value, 1_000)
+        .map(|n| n as usize)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Like the byte-unit deserializers above, but for a plain count (e.g.
+/// `target_chars`): no implied byte unit, so a bare `"1.5K"` means 1500, not
+/// 1500 bytes.
+fn deserialize_human_count<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<HumanSize>::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(value) => human_size_in_unit(value, 1)
+            .map(|n| Some(n as usize))
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// Top of the precedence chain: after defaults and `config.toml` (including
+/// any `[env.<name>]` overlay) have produced `cfg`, walk `SYNAPTIK__*`
+/// process environment variables and apply any that name a known leaf
+/// field, using `__` as the nesting separator (e.g.
+/// `SYNAPTIK__CACHE__MAX_HOT_MEMORY_MB=64`,
+/// `SYNAPTIK__CONTRACTS__REQUIRE_SIGNATURE=true`). Unrecognized `SYNAPTIK__*`
+/// variables, and values that fail to parse as their field's type, are
+/// logged via `tracing::warn!` and otherwise ignored -- an override should
+/// never be able to crash startup.
+fn apply_env_var_overrides(cfg: &mut CoreConfig) {
+    const PREFIX: &str = "SYNAPTIK__";
+    for (key, value) in std::env::vars() {
+        let Some(rest) = key.strip_prefix(PREFIX) else {
+            continue;
+        };
+        let segments: Vec<String> = rest.split("__").map(|s| s.to_ascii_lowercase()).collect();
+        let path: Vec<&str> = segments.iter().map(String::as_str).collect();
+        match path.as_slice() {
+            ["system", "name"] => cfg.system.name = value,
+            ["system", "version"] => cfg.system.version = value,
+            ["memory", "cache_path"] => cfg.memory.cache_path = PathBuf::from(value),
+            ["memory", "dag_path"] => cfg.memory.dag_path = PathBuf::from(value),
+            ["memory", "archive_path"] => cfg.memory.archive_path = PathBuf::from(value),
+            ["logbook", "path"] => cfg.logbook.path = PathBuf::from(value),
+            ["logbook", "aggregate"] => cfg.logbook.aggregate = PathBuf::from(value),
+            ["logbook", "ethics_log"] => cfg.logbook.ethics_log = PathBuf::from(value),
+            ["logbook", "agent_actions"] => cfg.logbook.agent_actions = PathBuf::from(value),
+            ["logbook", "contract_violations"] => {
+                cfg.logbook.contract_violations = PathBuf::from(value)
+            }
+            ["logbook", "contracts_log"] => cfg.logbook.contracts_log = PathBuf::from(value),
+            ["logbook", "max_segment_bytes"] => {
+                set_u64(&mut cfg.logbook.max_segment_bytes, &key, &value)
+            }
+            ["logbook", "compress_segments"] => {
+                set_bool(&mut cfg.logbook.compress_segments, &key, &value)
+            }
+            ["services", "ethos_enabled"] => set_bool(&mut cfg.services.ethos_enabled, &key, &value),
+            ["services", "librarian_enabled"] => {
+                set_bool(&mut cfg.services.librarian_enabled, &key, &value)
+            }
+            ["services", "audit_enabled"] => set_bool(&mut cfg.services.audit_enabled, &key, &value),
+            ["contracts", "path"] => cfg.contracts.path = PathBuf::from(value),
+            ["contracts", "default_contract"] => cfg.contracts.default_contract = value,
+            ["contracts", "accept_custom"] => set_bool(&mut cfg.contracts.accept_custom, &key, &value),
+            ["contracts", "require_signature"] => {
+                set_bool(&mut cfg.contracts.require_signature, &key, &value)
+            }
+            ["contracts", "max_rules"] => set_usize(&mut cfg.contracts.max_rules, &key, &value),
+            ["contracts", "max_pattern_len"] => {
+                set_usize(&mut cfg.contracts.max_pattern_len, &key, &value)
+            }
+            ["contracts", "max_file_kb"] => set_usize(&mut cfg.contracts.max_file_kb, &key, &value),
+            ["contracts", "allow_allow_rules"] => {
+                set_bool(&mut cfg.contracts.allow_allow_rules, &key, &value)
+            }
+            ["contracts", "wasm_enabled"] => set_bool(&mut cfg.contracts.wasm_enabled, &key, &value),
+            ["contracts", "wasm_module_path"] => {
+                cfg.contracts.wasm_module_path = PathBuf::from(value)
+            }
+            ["contracts", "wasm_export"] => cfg.contracts.wasm_export = value,
+            ["cache", "max_hot_memory_mb"] => {
+                set_usize(&mut cfg.cache.max_hot_memory_mb, &key, &value)
+            }
+            ["audit", "retention_days"] => set_u32(&mut cfg.audit.retention_days, &key, &value),
+            ["policies", "promote_hot_threshold"] => {
+                set_usize(&mut cfg.policies.promote_hot_threshold, &key, &value)
+            }
+            ["policies", "auto_prune_duplicates"] => {
+                set_bool(&mut cfg.policies.auto_prune_duplicates, &key, &value)
+            }
+            ["policies", "reflection_min_count"] => {
+                set_usize(&mut cfg.policies.reflection_min_count, &key, &value)
+            }
+            ["policies", "reflection_max_keywords"] => {
+                set_usize(&mut cfg.policies.reflection_max_keywords, &key, &value)
+            }
+            ["policies", "reflection_pool_size"] => {
+                set_usize(&mut cfg.policies.reflection_pool_size, &key, &value)
+            }
+            ["policies", "reflection_recency_lambda"] => {
+                set_f64(&mut cfg.policies.reflection_recency_lambda, &key, &value)
+            }
+            ["policies", "summary_min_len"] => {
+                set_usize(&mut cfg.policies.summary_min_len, &key, &value)
+            }
+            ["policies", "log_preview_len"] => {
+                set_usize(&mut cfg.policies.log_preview_len, &key, &value)
+            }
+            _ => tracing::warn!("unrecognized environment override: {key}"),
         }
     }
 }
 
+fn set_bool(dest: &mut bool, key: &str, raw: &str) {
+    match raw.parse() {
+        Ok(v) => *dest = v,
+        Err(_) => tracing::warn!("{key}={raw:?} is not a valid bool; ignoring override"),
+    }
+}
+
+fn set_usize(dest: &mut usize, key: &str, raw: &str) {
+    match raw.parse() {
+        Ok(v) => *dest = v,
+        Err(_) => tracing::warn!("{key}={raw:?} is not a valid integer; ignoring override"),
+    }
+}
+
+fn set_u32(dest: &mut u32, key: &str, raw: &str) {
+    match raw.parse() {
+        Ok(v) => *dest = v,
+        Err(_) => tracing::warn!("{key}={raw:?} is not a valid integer; ignoring override"),
+    }
+}
+
+fn set_u64(dest: &mut u64, key: &str, raw: &str) {
+    match raw.parse() {
+        Ok(v) => *dest = v,
+        Err(_) => tracing::warn!("{key}={raw:?} is not a valid integer; ignoring override"),
+    }
+}
+
+fn set_f64(dest: &mut f64, key: &str, raw: &str) {
+    match raw.parse() {
+        Ok(v) => *dest = v,
+        Err(_) => tracing::warn!("{key}={raw:?} is not a valid number; ignoring override"),
+    }
+}
+
 fn absolutize(root: &Path, value: &Path) -> PathBuf {
     if value.is_absolute() {
         value.to_path_buf()
@@ -460,3 +1455,23 @@ fn absolutize(root: &Path, value: &Path) -> PathBuf {
         root.join(value)
     }
 }
+
+/// Reject a resolved config path that still carries a `..` component --
+/// the telltale sign `absolutize` joined a relative `config.toml` value
+/// (e.g. `"../../etc"`) onto `root` and the result climbed back out. A
+/// value the user wrote as a clean absolute path never goes through that
+/// join, so it has no such component and is left alone even if it lands
+/// outside `root` -- that's treated as an explicit choice, not a mistake.
+///
+/// This is a lexical check (no `canonicalize`) deliberately: `validate`
+/// runs before `ensure_initialized` has created most of these directories,
+/// so requiring them to already exist on disk would break a fresh init.
+fn assert_no_root_escape(root: &Path, field: &str, path: &Path) -> Result<()> {
+    if path
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        anyhow::bail!("{field} resolves to {path:?}, which escapes root {root:?} via `..`");
+    }
+    Ok(())
+}
@@ -0,0 +1,226 @@
+// src/fuzz.rs
+//! Differential-fuzzing harness for cross-tier recall parity, plus a
+//! panic-freedom check for the contract parser used by `precheck_text`.
+//!
+//! This module is the reusable core the fuzz targets under
+//! `fuzz/fuzz_targets/` link against (would be registered as `pub mod fuzz;`
+//! alongside `commands`/`config`/`memory`/`services`/`utils` in the crate
+//! root, which this snapshot doesn't carry -- see the other top-level
+//! modules for the same gap). It's deliberately free of `arbitrary`/
+//! `libfuzzer-sys` so `cargo test` can drive it too, not just a real fuzzer.
+//!
+//! [`decode_ops`] turns raw fuzzer bytes into an [`Op`] stream; [`run_ops`]
+//! replays that stream against a real `Memory` + `Archivist` + `Commands`
+//! stack (the same three types `tests/e2e_test.rs`'s
+//! `commands_recall_parity_across_tiers` and
+//! `commands_recall_heals_and_returns_all_tiers` already construct) and
+//! checks every recall against a shadow oracle. [`check_contract_parse`]
+//! exercises the TOML-to-`MoralContract` parse + `evaluate_input_against_rules`
+//! path that backs `precheck_text`.
+
+use crate::services::archivist::Archivist;
+use crate::services::memory::Memory;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// One step of a randomly generated operation stream, decoded from raw
+/// fuzzer bytes by [`decode_ops`]. `id_idx` selects among the ids already
+/// produced by an earlier `Remember` in the same stream (modulo the count
+/// seen so far), so later ops can target any previously-written id, not
+/// just the newest.
+#[derive(Debug, Clone)]
+pub enum Op {
+    /// Write a fresh row directly to the hot tier via `Memory::remember`.
+    Remember { lobe_tag: u8, bytes: Vec<u8> },
+    /// `Memory::promote_to_dag` on a previously-remembered id.
+    PromoteToDag { id_idx: u8 },
+    /// Archive a previously-remembered id's current hot bytes via `Archivist::archive`.
+    PromoteToArchive { id_idx: u8 },
+    /// Delete a previously-remembered id's hot row directly (simulates eviction).
+    DeleteHotRow { id_idx: u8 },
+    /// `Commands::recall_with_source(id, Some(prefer))` on a previously-remembered id.
+    Recall { id_idx: u8, prefer: &'static str },
+}
+
+/// Cursor over raw fuzzer bytes. Never panics or errors -- short input just
+/// decodes to zero/empty fields, same convention `arbitrary::Unstructured`
+/// uses, so `decode_ops` always produces *some* op stream.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn byte(&mut self) -> u8 {
+        let b = self.data.get(self.pos).copied().unwrap_or(0);
+        self.pos = self.pos.saturating_add(1);
+        b
+    }
+
+    fn bytes(&mut self, n: usize) -> Vec<u8> {
+        let start = self.pos.min(self.data.len());
+        let end = (start + n).min(self.data.len());
+        self.pos = end;
+        self.data[start..end].to_vec()
+    }
+
+    fn exhausted(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+}
+
+/// Decode up to `max_ops` [`Op`]s from raw fuzzer bytes.
+pub fn decode_ops(data: &[u8], max_ops: usize) -> Vec<Op> {
+    let mut cur = Cursor::new(data);
+    let mut ops = Vec::new();
+    while !cur.exhausted() && ops.len() < max_ops {
+        let op = match cur.byte() % 5 {
+            0 => {
+                let lobe_tag = cur.byte() % 4;
+                let len = usize::from(cur.byte()) % 64;
+                let bytes = cur.bytes(len);
+                Op::Remember { lobe_tag, bytes }
+            }
+            1 => Op::PromoteToDag { id_idx: cur.byte() },
+            2 => Op::PromoteToArchive { id_idx: cur.byte() },
+            3 => Op::DeleteHotRow { id_idx: cur.byte() },
+            _ => {
+                let prefer = match cur.byte() % 4 {
+                    0 => "hot",
+                    1 => "archive",
+                    2 => "dag",
+                    _ => "auto",
+                };
+                Op::Recall {
+                    id_idx: cur.byte(),
+                    prefer,
+                }
+            }
+        };
+        ops.push(op);
+    }
+    ops
+}
+
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A prefix unique to this process and call, so repeated `run_ops` calls in
+/// one long-lived fuzzer process (or one `cargo test` run) never collide
+/// over memory_id/lobe names.
+pub fn next_run_prefix() -> String {
+    let n = RUN_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("fuzz{}_{}", std::process::id(), n)
+}
+
+fn pick<'a>(ids: &'a [String], idx: u8) -> Option<&'a str> {
+    if ids.is_empty() {
+        None
+    } else {
+        Some(ids[usize::from(idx) % ids.len()].as_str())
+    }
+}
+
+/// Replay `ops` against `mem`/`arch`/`cmds` -- all three must already point
+/// at the same on-disk store (e.g. via `Commands::new`'s global singleton
+/// config, the same pattern `tests/e2e_test.rs` uses) -- checking the
+/// differential invariant after every `Recall`: a tier either returns
+/// byte-identical content to the shadow oracle, or `recall_with_source`
+/// returns `None` (legitimate once a hot row has been deleted before any
+/// promotion). Also checks that `archived_cid`, once set, always equals
+/// `blake3(content)`.
+///
+/// `db_path` is opened directly (mirroring `tests/e2e_test.rs`'s
+/// `open_sqlite` helper) to delete hot rows out from under `Memory`,
+/// simulating eviction without a public `Memory::forget` API.
+pub fn run_ops(
+    mem: &Memory,
+    arch: &Archivist,
+    cmds: &crate::commands::Commands,
+    db_path: &Path,
+    run_prefix: &str,
+    ops: &[Op],
+) -> Result<()> {
+    let mut oracle: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut ids: Vec<String> = Vec::new();
+
+    for op in ops {
+        match op {
+            Op::Remember { lobe_tag, bytes } => {
+                let id = format!("{run_prefix}-{}", ids.len());
+                let lobe = format!("{run_prefix}_lobe{lobe_tag}");
+                mem.remember(&id, &lobe, "k", bytes)?;
+                oracle.insert(id.clone(), bytes.clone());
+                ids.push(id);
+            }
+            Op::PromoteToDag { id_idx } => {
+                if let Some(id) = pick(&ids, *id_idx) {
+                    mem.promote_to_dag(id)?;
+                }
+            }
+            Op::PromoteToArchive { id_idx } => {
+                if let Some(id) = pick(&ids, *id_idx) {
+                    if let Some(bytes) = mem.recall(id)? {
+                        arch.archive(id, &bytes)?;
+                    }
+                }
+            }
+            Op::DeleteHotRow { id_idx } => {
+                if let Some(id) = pick(&ids, *id_idx) {
+                    let conn = rusqlite::Connection::open(db_path)?;
+                    conn.execute("DELETE FROM memories WHERE memory_id=?1", [id])?;
+                }
+            }
+            Op::Recall { id_idx, prefer } => {
+                let Some(id) = pick(&ids, *id_idx).map(str::to_string) else {
+                    continue;
+                };
+                let want = oracle.get(&id);
+                let got = cmds.recall_with_source(&id, Some(prefer))?;
+                match (want, got) {
+                    (Some(want_bytes), Some((content, _source))) => {
+                        if content.as_bytes() != want_bytes.as_slice() {
+                            bail!(
+                                "recall mismatch for {id} via {prefer}: tier returned bytes \
+                                 that differ from the shadow oracle"
+                            );
+                        }
+                    }
+                    (None, Some(_)) => {
+                        bail!("recall_with_source returned content for an id the oracle never saw: {id}");
+                    }
+                    // `Some(_), None` is a legitimate "not durable anywhere" state
+                    // (hot row deleted before any promotion); `None, None` is a
+                    // straightforward miss. Neither is a violation.
+                    (Some(_), None) | (None, None) => {}
+                }
+                if let (Some(cid), Some(want_bytes)) = (mem.get_archived_cid(&id)?, want) {
+                    let expected_cid = blake3::hash(want_bytes).to_hex().to_string();
+                    if cid != expected_cid {
+                        bail!("archived_cid mismatch for {id}: {cid} != blake3(content) {expected_cid}");
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Feed arbitrary bytes through the same TOML-to-`MoralContract` parse and
+/// `evaluate_input_against_rules` call `precheck_text` (via
+/// `services::ethos::precheck` / `services::audit::evaluate_and_audit_contract`)
+/// makes against the on-disk contract, proving the parser and evaluator
+/// never panic on malformed input. `data` is interpreted as lossy UTF-8,
+/// since TOML requires valid UTF-8 and a fuzzer's raw byte corpus isn't
+/// guaranteed to be one.
+pub fn check_contract_parse(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    if let Ok(contract) = toml::from_str::<contracts::MoralContract>(&text) {
+        let _ = contracts::evaluate_input_against_rules(&text, &contract);
+    }
+}
@@ -0,0 +1,185 @@
+// src/memory/blob.rs
+//! Content-defined chunking and blob dedup beneath the DAG.
+//!
+//! A snapshot's content used to be stored whole under its own content hash,
+//! so two snapshots that differ by a one-line edit paid for two full
+//! copies (`snapshot_meta`'s doc comment already notes multiple nodes
+//! sharing one hash -- the whole-payload case of the same waste). Splitting
+//! the payload into variable-length chunks via a rolling hash and storing
+//! each chunk once under its own blake3 hash means an edit only changes the
+//! chunks around it; everything else is shared.
+//!
+//! Boundaries are picked with a buzhash-style rolling hash over a fixed-size
+//! window: whenever the low [`CHUNK_MASK_BITS`] bits of the window hash are
+//! all zero, and we're past [`MIN_CHUNK_SIZE`], that byte ends a chunk. A
+//! chunk is force-cut at [`MAX_CHUNK_SIZE`] regardless, so pathological
+//! input (e.g. all-zero runs) can't produce an unbounded chunk.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::init::ensure_initialized_once;
+use crate::utils::path as pathutil;
+
+const MIN_CHUNK_SIZE: usize = 1 << 12; // 4 KiB
+const AVG_CHUNK_SIZE: usize = 1 << 13; // 8 KiB, via CHUNK_MASK_BITS below
+const MAX_CHUNK_SIZE: usize = 1 << 16; // 64 KiB
+const CHUNK_MASK_BITS: u32 = 13; // log2(AVG_CHUNK_SIZE)
+const WINDOW_SIZE: usize = 48;
+
+fn blobs_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("dag").join("blobs");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn blob_path(hash: &str) -> Result<PathBuf> {
+    Ok(blobs_dir()?.join(hash))
+}
+
+fn write_atomic(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
+    let root = ensure_initialized_once()?.root.clone();
+    let root = root.canonicalize().unwrap_or(root);
+    let _ = pathutil::assert_within_root_abs(&root, path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("create_dir_all({:?})", parent))?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes).with_context(|| format!("open temp file {:?}", tmp))?;
+    fs::rename(&tmp, path).with_context(|| format!("rename {:?} -> {:?}", tmp, path))?;
+    Ok(())
+}
+
+/// Buzhash-like rolling hash over a fixed window of the last [`WINDOW_SIZE`]
+/// bytes. Cheap rotate/xor per byte, same idea as rsync/restic's chunkers.
+struct RollingHash {
+    table: [u32; 256],
+    window: [u8; WINDOW_SIZE],
+    pos: usize,
+    filled: usize,
+    hash: u32,
+}
+
+impl RollingHash {
+    fn new() -> Self {
+        // Fixed pseudo-random byte->u32 table, derived from a small LCG so
+        // the chunker is deterministic across runs without needing a crate.
+        let mut table = [0u32; 256];
+        let mut seed: u32 = 0x2545F491;
+        for slot in table.iter_mut() {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            *slot = seed;
+        }
+        Self {
+            table,
+            window: [0u8; WINDOW_SIZE],
+            pos: 0,
+            filled: 0,
+            hash: 0,
+        }
+    }
+
+    /// Roll in one byte, returning the updated hash.
+    fn push(&mut self, byte: u8) -> u32 {
+        let outgoing = self.window[self.pos];
+        self.window[self.pos] = byte;
+        self.pos = (self.pos + 1) % WINDOW_SIZE;
+        if self.filled < WINDOW_SIZE {
+            self.filled += 1;
+        } else {
+            // Remove the outgoing byte's rotated contribution before adding
+            // the new one, keeping the hash a function of exactly the
+            // current window rather than all bytes ever seen.
+            let rotated_out = self.table[outgoing as usize].rotate_left((WINDOW_SIZE % 32) as u32);
+            self.hash ^= rotated_out;
+        }
+        self.hash = self.hash.rotate_left(1) ^ self.table[byte as usize];
+        self.hash
+    }
+}
+
+/// Split `data` into content-defined chunks, returning each chunk's byte
+/// slice in order. Boundaries fall wherever the rolling hash's low
+/// [`CHUNK_MASK_BITS`] bits are zero (past the minimum size), or at
+/// [`MAX_CHUNK_SIZE`] if none is found first.
+fn chunk_boundaries(data: &[u8]) -> Vec<(usize, usize)> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let mask: u32 = (1u32 << CHUNK_MASK_BITS) - 1;
+    let mut bounds = Vec::new();
+    let mut start = 0usize;
+    let mut roller = RollingHash::new();
+    for (i, &b) in data.iter().enumerate() {
+        let h = roller.push(b);
+        let len = i + 1 - start;
+        if len >= MIN_CHUNK_SIZE && (h & mask) == 0 {
+            bounds.push((start, i + 1));
+            start = i + 1;
+            roller = RollingHash::new();
+        } else if len >= MAX_CHUNK_SIZE {
+            bounds.push((start, i + 1));
+            start = i + 1;
+            roller = RollingHash::new();
+        }
+    }
+    if start < data.len() {
+        bounds.push((start, data.len()));
+    }
+    bounds
+}
+
+/// Split `data` into content-defined chunks, store each once under its own
+/// blake3 hash (a no-op if that chunk is already on disk), and return the
+/// ordered list of chunk hashes that reconstructs `data` via [`load_blob`].
+pub fn store_blob(data: &[u8]) -> Result<Vec<String>> {
+    let mut hashes = Vec::new();
+    for (start, end) in chunk_boundaries(data) {
+        let chunk = &data[start..end];
+        let hash = blake3::hash(chunk).to_hex().to_string();
+        let path = blob_path(&hash)?;
+        if !path.exists() {
+            write_atomic(&path, chunk)?;
+        }
+        hashes.push(hash);
+    }
+    Ok(hashes)
+}
+
+/// Reassemble the bytes for an ordered list of chunk hashes as produced by
+/// [`store_blob`].
+pub fn load_blob(chunk_hashes: &[String]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for hash in chunk_hashes {
+        let path = blob_path(hash)?;
+        let bytes = fs::read(&path)
+            .with_context(|| format!("missing chunk {} referenced by a retained node", hash))?;
+        out.extend_from_slice(&bytes);
+    }
+    Ok(out)
+}
+
+/// Delete every stored chunk not present in `referenced`. Callers build
+/// `referenced` by walking all retained (non-pruned) nodes' chunk lists, so
+/// this is safe to run after [`crate::memory::dag::prune`] has removed
+/// whichever node files it decided to drop. Returns the number of chunk
+/// files removed.
+pub fn gc_chunks(referenced: &std::collections::HashSet<String>) -> Result<usize> {
+    let dir = blobs_dir()?;
+    let mut removed = 0usize;
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.ends_with(".tmp") {
+            continue;
+        }
+        if !referenced.contains(name) {
+            let _ = fs::remove_file(&path);
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
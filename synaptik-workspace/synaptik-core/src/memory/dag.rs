@@ -11,16 +11,25 @@
 //!   compatibility, older nodes may contain a single `parent` string; readers map it
 //!   to `parents = [parent]`.
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{bail, Context, Result, anyhow};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as B64;
 use blake3;
+use once_cell::sync::OnceCell;
 use serde_json::Value;
 use std::{
+    collections::HashMap,
     fs,
-    io::Write,
+    io::{BufRead, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 
+use crate::commands::helpers::STOPWORDS;
 use crate::commands::init::ensure_initialized_once;
+use crate::config::CompactionPolicy;
+use crate::memory::blob;
+use crate::memory::simhash;
 use crate::utils::path as pathutil;
 
 // ---------- paths ----------
@@ -31,6 +40,26 @@ fn dag_nodes_dir() -> Result<PathBuf> {
     Ok(p)
 }
 
+fn dag_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("dag");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn dag_pack_path() -> Result<PathBuf> {
+    Ok(dag_dir()?.join("nodes.pack"))
+}
+
+fn dag_docket_path() -> Result<PathBuf> {
+    Ok(dag_dir()?.join("docket.json"))
+}
+
+fn offsets_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("offsets");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
 fn stream_refs_dir() -> Result<PathBuf> {
     let p = ensure_initialized_once()?.root.join("refs").join("streams");
     fs::create_dir_all(&p)?;
@@ -58,12 +87,24 @@ fn children_ref_dir() -> Result<PathBuf> {
     Ok(p)
 }
 
+fn copies_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("copies");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
 fn paths_ref_dir() -> Result<PathBuf> {
     let p = ensure_initialized_once()?.root.join("refs").join("paths");
     fs::create_dir_all(&p)?;
     Ok(p)
 }
 
+fn leaves_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("leaves");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
 fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
     // Enforce that DAG writes stay within the initialized root.
     let root = ensure_initialized_once()?.root.clone();
@@ -82,6 +123,571 @@ fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
     Ok(())
 }
 
+// ---------- packed node store ----------
+//
+// Nodes are appended to a single `dag/nodes.pack` file rather than written as
+// one JSON file each, so that the scan helpers below (`search_content_words`,
+// `resolve_parent_filename`, etc.) can enumerate lightweight offset-index
+// entries instead of opening one file per node. `dag/docket.json` records how
+// many bytes of the pack are valid; it is rewritten atomically *after* the
+// pack write lands, so a crash mid-append leaves trailing bytes past
+// `valid_len` that readers simply never see, and the next append starts
+// exactly at the old `valid_len`, silently overwriting that garbage.
+//
+// Migration from the legacy per-node-file layout is additive and
+// non-destructive: `migrate_legacy_nodes_to_pack` folds any un-indexed
+// legacy file into the pack without deleting the original, so `load_node`'s
+// legacy-directory fallback keeps working for anything not yet migrated (or
+// for a tree that predates this change entirely).
+
+const PACK_LEN_PREFIX_BYTES: usize = 4;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+struct Docket {
+    /// Identifies this pack instance (e.g. across a rebuild); not currently
+    /// relied on for correctness, only carried for diagnostics.
+    uuid: String,
+    generation: u64,
+    valid_len: u64,
+}
+
+/// A content-derived id in the same spirit as this file's other blake3-hash
+/// identifiers (`hash`, `cid`), rather than pulling in the `uuid` crate.
+fn generate_docket_uuid() -> String {
+    let seed = format!("{}-{}", chrono::Utc::now().to_rfc3339(), std::process::id());
+    blake3::hash(seed.as_bytes()).to_hex().to_string()
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct OffsetEntry {
+    /// Original node filename this entry indexes. Index files themselves are
+    /// named `sanitize(filename).json`, which is lossy, so the filename is
+    /// also carried inside the entry to make enumeration exact.
+    filename: String,
+    /// Byte offset of the JSON payload within `dag/nodes.pack` (just past
+    /// the length prefix).
+    offset: u64,
+    /// Length of the JSON payload in bytes.
+    length: u64,
+}
+
+fn read_docket() -> Result<Docket> {
+    let p = dag_docket_path()?;
+    if !p.exists() {
+        return Ok(Docket::default());
+    }
+    let bytes = fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+fn write_docket(d: &Docket) -> Result<()> {
+    let p = dag_docket_path()?;
+    write_atomic(&p, &serde_json::to_vec(d)?)
+}
+
+fn offset_index_path(filename: &str) -> Result<PathBuf> {
+    Ok(offsets_ref_dir()?.join(format!("{}.json", sanitize(filename))))
+}
+
+fn read_offset_index(filename: &str) -> Result<Option<OffsetEntry>> {
+    let p = offset_index_path(filename)?;
+    if !p.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or(None))
+}
+
+fn write_offset_index(filename: &str, entry: &OffsetEntry) -> Result<()> {
+    let p = offset_index_path(filename)?;
+    write_atomic(&p, &serde_json::to_vec(entry)?)
+}
+
+/// Append one node's JSON payload to `dag/nodes.pack` and register its
+/// offset under `filename`. See the module-level note above for the crash
+/// safety invariant (docket rewritten atomically, after, and last).
+fn append_node_to_pack(filename: &str, payload: &[u8]) -> Result<OffsetEntry> {
+    let pack_path = dag_pack_path()?;
+    let mut docket = read_docket()?;
+    if docket.uuid.is_empty() {
+        docket.uuid = generate_docket_uuid();
+    }
+
+    let mut record = Vec::with_capacity(PACK_LEN_PREFIX_BYTES + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(payload);
+
+    let mut f = fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .write(true)
+        .open(&pack_path)
+        .with_context(|| format!("open pack file {:?}", pack_path))?;
+    f.seek(SeekFrom::Start(docket.valid_len))?;
+    f.write_all(&record)?;
+    f.flush()?;
+    f.sync_all()?;
+
+    let entry = OffsetEntry {
+        filename: filename.to_string(),
+        offset: docket.valid_len + PACK_LEN_PREFIX_BYTES as u64,
+        length: payload.len() as u64,
+    };
+
+    docket.valid_len += record.len() as u64;
+    docket.generation += 1;
+    write_docket(&docket)?;
+
+    Ok(entry)
+}
+
+/// Read one node's JSON payload out of `dag/nodes.pack` at a known offset —
+/// a single seek + read, versus opening a per-node file.
+fn read_node_from_pack(entry: &OffsetEntry) -> Result<Value> {
+    let pack_path = dag_pack_path()?;
+    let mut f = fs::File::open(&pack_path)
+        .with_context(|| format!("open pack file {:?}", pack_path))?;
+    f.seek(SeekFrom::Start(entry.offset))?;
+    let mut buf = vec![0u8; entry.length as usize];
+    f.read_exact(&mut buf)?;
+    Ok(serde_json::from_slice(&buf)?)
+}
+
+/// Report produced by [`migrate_legacy_nodes_to_pack`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MigrationReport {
+    pub migrated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// One-time, idempotent migration: fold every legacy per-node JSON file
+/// under `dag/nodes` that doesn't yet have an offset-index entry into
+/// `dag/nodes.pack`. Safe to call on every startup — a file that already
+/// has an entry is skipped. Never deletes the legacy originals.
+pub fn migrate_legacy_nodes_to_pack() -> Result<MigrationReport> {
+    let dir = dag_nodes_dir()?;
+    let mut report = MigrationReport::default();
+    for e in fs::read_dir(&dir)? {
+        let path = e?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        if read_offset_index(&name)?.is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        let bytes = match fs::read(&path) {
+            Ok(b) => b,
+            Err(_) => {
+                report.failed += 1;
+                continue;
+            }
+        };
+        match append_node_to_pack(&name, &bytes) {
+            Ok(entry) if write_offset_index(&name, &entry).is_ok() => report.migrated += 1,
+            _ => report.failed += 1,
+        }
+    }
+    Ok(report)
+}
+
+fn ensure_migrated_once() {
+    static DONE: std::sync::Once = std::sync::Once::new();
+    DONE.call_once(|| {
+        let _ = migrate_legacy_nodes_to_pack();
+    });
+}
+
+/// Replay any journal left by a commit that crashed mid-flight, once per
+/// process. Called alongside [`ensure_migrated_once`] from [`save_node`] so
+/// every entry point that can write a node also recovers before writing.
+fn ensure_recovered_once() {
+    static DONE: std::sync::Once = std::sync::Once::new();
+    DONE.call_once(|| {
+        let _ = recover();
+    });
+}
+
+/// Enumerate every known node filename: the offset index (pack-backed
+/// nodes, freshly written or migrated) union anything left in the legacy
+/// `dag/nodes` directory that hasn't been migrated yet. Scan helpers use
+/// this instead of listing `dag_nodes_dir()` directly, so they open each
+/// node's content at most once (via [`load_node`], which prefers the pack)
+/// rather than per-candidate during the scan itself.
+fn enumerate_all_node_filenames() -> Result<Vec<String>> {
+    ensure_migrated_once();
+    let mut names: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+    if let Ok(dir) = offsets_ref_dir() {
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for ent in entries.flatten() {
+                let path = ent.path();
+                if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                    continue;
+                }
+                if let Ok(bytes) = fs::read(&path) {
+                    if let Ok(entry) = serde_json::from_slice::<OffsetEntry>(&bytes) {
+                        names.insert(entry.filename);
+                    }
+                }
+            }
+        }
+    }
+    if let Ok(dir) = dag_nodes_dir() {
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for ent in entries.flatten() {
+                let path = ent.path();
+                if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                        names.insert(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+    Ok(names.into_iter().collect())
+}
+
+// ---------- transactional index writes (journal) ----------
+//
+// `save_node` touches several index files (stream ref, id index, hash
+// index) that must land together or not at all — a crash between two of
+// the old independent `write_atomic` calls left the indexes inconsistent
+// with each other. `begin()`/`stage_node()`/`commit()` buffer those writes
+// and describe them as one intent record appended to `dag/journal.log`
+// before any of the target files are touched; the journal is fsynced,
+// then the individual `write_atomic` renames are performed, then the
+// journal is removed. A crash after the intent record lands but before
+// the journal is removed is recoverable: `recover()` (call on startup)
+// just re-applies the same writes, which is safe since `write_atomic` is
+// itself idempotent (same bytes, same destination).
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct JournalWrite {
+    path: String,
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+struct JournalRecord {
+    writes: Vec<JournalWrite>,
+}
+
+fn journal_path() -> Result<PathBuf> {
+    Ok(dag_dir()?.join("journal.log"))
+}
+
+fn apply_journal_record(record: &JournalRecord) -> Result<()> {
+    for w in &record.writes {
+        write_atomic(Path::new(&w.path), &w.payload)?;
+    }
+    Ok(())
+}
+
+fn clear_journal() -> Result<()> {
+    let p = journal_path()?;
+    if p.exists() {
+        fs::remove_file(&p)?;
+    }
+    Ok(())
+}
+
+/// A batch of index writes staged via [`begin`] and applied together by
+/// [`NodeTransaction::commit`].
+pub struct NodeTransaction {
+    writes: Vec<JournalWrite>,
+}
+
+/// Start a new transaction. Nothing is written to disk until `commit()`.
+pub fn begin() -> NodeTransaction {
+    NodeTransaction { writes: Vec::new() }
+}
+
+impl NodeTransaction {
+    /// Stage a write of `bytes` to `path` as part of this transaction.
+    pub fn stage_node(&mut self, path: &Path, bytes: Vec<u8>) -> Result<()> {
+        let root = ensure_initialized_once()?.root.clone();
+        let root = root.canonicalize().unwrap_or(root);
+        pathutil::assert_within_root_abs(&root, path)?;
+        self.writes.push(JournalWrite {
+            path: path.to_string_lossy().into_owned(),
+            payload: bytes,
+        });
+        Ok(())
+    }
+
+    /// Durably record the intent, apply every staged write, then clear the
+    /// intent. No-op if nothing was staged.
+    pub fn commit(self) -> Result<()> {
+        if self.writes.is_empty() {
+            return Ok(());
+        }
+        let record = JournalRecord {
+            writes: self.writes,
+        };
+        write_atomic(&journal_path()?, &serde_json::to_vec(&record)?)?;
+        apply_journal_record(&record)?;
+        clear_journal()?;
+        Ok(())
+    }
+}
+
+/// Replay a journal left behind by a commit that crashed after its intent
+/// record landed but before the journal was cleared. Safe to call
+/// unconditionally on startup; a missing, empty, or corrupt journal is a
+/// no-op (a corrupt journal can only mean the crash happened mid-write of
+/// the journal file itself, before any of its writes were ever intended to
+/// be visible, so it's simply discarded). Returns the number of writes
+/// replayed.
+pub fn recover() -> Result<usize> {
+    let p = journal_path()?;
+    if !p.exists() {
+        return Ok(0);
+    }
+    let bytes = fs::read(&p)?;
+    let record: JournalRecord = match serde_json::from_slice(&bytes) {
+        Ok(r) => r,
+        Err(_) => {
+            clear_journal()?;
+            return Ok(0);
+        }
+    };
+    let n = record.writes.len();
+    apply_journal_record(&record)?;
+    clear_journal()?;
+    Ok(n)
+}
+
+// ---------- in-memory stream ref cache ----------
+//
+// `read_stream_ref` is called on every `save_node`. Rather than
+// re-reading and re-parsing the ref JSON each time, cache the parsed
+// value keyed by (lobe,key) and only trust the cache while the backing
+// file's (inode, mtime, len) still matches what was cached — mirroring
+// dirstate's own trick of trusting cached file state until that state
+// says otherwise, rather than invalidating on a timer.
+
+#[derive(Debug, Clone)]
+struct CachedStreamRef {
+    fingerprint: (u64, i64, u64),
+    value: StreamRef,
+}
+
+static STREAM_REF_CACHE: OnceCell<Mutex<HashMap<String, CachedStreamRef>>> = OnceCell::new();
+
+fn stream_ref_cache() -> &'static Mutex<HashMap<String, CachedStreamRef>> {
+    STREAM_REF_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// (inode, mtime, len) for cache validation. Inode is unavailable off
+/// Unix, so non-Unix targets fall back to (0, mtime, len), which is still
+/// sound — it just can't distinguish a file replaced with identical
+/// mtime/len, an already-rare race the cache treats as acceptable.
+fn file_fingerprint(path: &Path) -> Option<(u64, i64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let len = meta.len();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((meta.ino(), meta.mtime(), len))
+    }
+    #[cfg(not(unix))]
+    {
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Some((0, mtime, len))
+    }
+}
+
+// ---------- inverted index (full-text search) ----------
+//
+// `save_node` tokenizes its content and appends the node's filename/ts to
+// each token's postings file under `refs/index/<token>.json`, so
+// `search_content_words` can look up only the postings for its query words
+// and intersect them, rather than opening every node. This trades the old
+// substring-containment match for whole-token matching — standard inverted
+// index behavior — while keeping the AND-across-words semantics.
+
+fn index_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("index");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct PostingEntry {
+    filename: String,
+    ts: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Postings {
+    token: String,
+    entries: Vec<PostingEntry>,
+}
+
+fn tokenize_content(content: &str) -> std::collections::BTreeSet<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn token_index_path(token: &str) -> Result<PathBuf> {
+    Ok(index_ref_dir()?.join(format!("{}.json", sanitize(token))))
+}
+
+fn read_postings(token: &str) -> Result<Postings> {
+    let p = token_index_path(token)?;
+    if !p.exists() {
+        return Ok(Postings {
+            token: token.to_string(),
+            entries: Vec::new(),
+        });
+    }
+    let bytes = fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or(Postings {
+        token: token.to_string(),
+        entries: Vec::new(),
+    }))
+}
+
+fn write_postings(postings: &Postings) -> Result<()> {
+    let p = token_index_path(&postings.token)?;
+    write_atomic(&p, &serde_json::to_vec(postings)?)
+}
+
+fn append_posting(token: &str, filename: &str, ts: &str) -> Result<()> {
+    let mut postings = read_postings(token)?;
+    if !postings.entries.iter().any(|e| e.filename == filename) {
+        postings.entries.push(PostingEntry {
+            filename: filename.to_string(),
+            ts: ts.to_string(),
+        });
+        write_postings(&postings)?;
+    }
+    Ok(())
+}
+
+/// Tokenize `content` (lowercase, split on non-alphanumeric, deduped) and
+/// append `filename`/`ts` to each token's postings list.
+fn index_node_tokens(filename: &str, ts: &str, content: &str) -> Result<()> {
+    for token in tokenize_content(content) {
+        append_posting(&token, filename, ts)?;
+    }
+    Ok(())
+}
+
+/// Every distinct token currently in the index. Only used by the
+/// typo-tolerant fallback below — O(vocabulary), not O(nodes).
+fn all_index_tokens() -> Result<Vec<String>> {
+    let dir = index_ref_dir()?;
+    let mut tokens = Vec::new();
+    for e in fs::read_dir(&dir)? {
+        let path = e?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        if let Ok(bytes) = fs::read(&path) {
+            if let Ok(p) = serde_json::from_slice::<Postings>(&bytes) {
+                tokens.push(p.token);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// True if `a` and `b` differ by at most one character insertion, deletion,
+/// or substitution. Used for the typo-tolerant index fallback.
+fn edit_distance_le_1(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > 1 {
+        return false;
+    }
+    let (shorter, longer) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    let mut i = 0usize;
+    let mut j = 0usize;
+    let mut edits = 0usize;
+    while i < shorter.len() && j < longer.len() {
+        if shorter[i] == longer[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        edits += 1;
+        if edits > 1 {
+            return false;
+        }
+        if shorter.len() == longer.len() {
+            i += 1;
+            j += 1;
+        } else {
+            j += 1;
+        }
+    }
+    edits += longer.len() - j;
+    edits <= 1
+}
+
+/// Resolve one query word to postings entries: an exact token match if the
+/// index has one, else a typo-tolerant fallback that unions postings from
+/// any indexed token within edit distance 1 or sharing a prefix of length
+/// at least 4.
+fn postings_for_query_word(word: &str) -> Result<Vec<PostingEntry>> {
+    let word = word.to_lowercase();
+    let exact = read_postings(&word)?.entries;
+    if !exact.is_empty() {
+        return Ok(exact);
+    }
+    let mut out: Vec<PostingEntry> = Vec::new();
+    let mut seen_files: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for token in all_index_tokens()? {
+        if token == word {
+            continue;
+        }
+        let shared_prefix =
+            word.chars().count() >= 4 && token.chars().count() >= 4 && {
+                let wp: String = word.chars().take(4).collect();
+                let tp: String = token.chars().take(4).collect();
+                wp == tp
+            };
+        if shared_prefix || edit_distance_le_1(&word, &token) {
+            for entry in read_postings(&token)?.entries {
+                if seen_files.insert(entry.filename.clone()) {
+                    out.push(entry);
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Rebuild `refs/index` from scratch by re-tokenizing every known node's
+/// content. Backfills the index for nodes written before this subsystem
+/// existed, or after a manual recovery. Returns the number of nodes
+/// (re-)indexed.
+pub fn rebuild_index() -> Result<usize> {
+    let mut indexed = 0usize;
+    for fname in enumerate_all_node_filenames()? {
+        if let Ok(v) = load_node(&fname) {
+            let content = node_content(&v).unwrap_or_default();
+            let ts = v.get("ts").and_then(|x| x.as_str()).unwrap_or("");
+            index_node_tokens(&fname, ts, &content)?;
+            indexed += 1;
+        }
+    }
+    Ok(indexed)
+}
+
 // ---------- tiny stream refs ----------
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
@@ -89,6 +695,11 @@ struct StreamRef {
     latest_node: Option<String>,
     last_hash: Option<String>,
     updated_at: Option<String>,
+    // Carried alongside the ref (rather than recovered from the file name)
+    // since `stream_key` sanitizes lobe/key lossily. Populated on write;
+    // `None` on refs written before copy tracking existed.
+    lobe: Option<String>,
+    key: Option<String>,
 }
 
 fn stream_key(lobe: &str, key: &str) -> String {
@@ -101,20 +712,47 @@ fn sanitize(s: &str) -> String {
         .collect()
 }
 
+fn stream_ref_path(lobe: &str, key: &str) -> Result<PathBuf> {
+    Ok(stream_refs_dir()?.join(format!("{}.json", stream_key(lobe, key))))
+}
+
 fn read_stream_ref(lobe: &str, key: &str) -> Result<StreamRef> {
-    let p = stream_refs_dir()?.join(format!("{}.json", stream_key(lobe, key)));
+    let cache_key = stream_key(lobe, key);
+    let p = stream_ref_path(lobe, key)?;
     if !p.exists() {
+        stream_ref_cache().lock().unwrap().remove(&cache_key);
         return Ok(StreamRef::default());
     }
+    let fingerprint = file_fingerprint(&p);
+    if let Some(fp) = fingerprint {
+        if let Some(cached) = stream_ref_cache().lock().unwrap().get(&cache_key) {
+            if cached.fingerprint == fp {
+                return Ok(cached.value.clone());
+            }
+        }
+    }
     let bytes = fs::read(&p)?;
     let v = serde_json::from_slice::<StreamRef>(&bytes).unwrap_or_default();
+    if let Some(fp) = fingerprint {
+        stream_ref_cache().lock().unwrap().insert(
+            cache_key,
+            CachedStreamRef {
+                fingerprint: fp,
+                value: v.clone(),
+            },
+        );
+    }
     Ok(v)
 }
 
-fn write_stream_ref(lobe: &str, key: &str, r: &StreamRef) -> Result<()> {
-    let p = stream_refs_dir()?.join(format!("{}.json", stream_key(lobe, key)));
-    write_atomic(&p, &serde_json::to_vec_pretty(r)?)?;
-    Ok(())
+/// Invalidate the in-memory cache entry for (lobe,key). The stream ref file
+/// itself is written as part of `save_node`'s journaled transaction, not
+/// through a standalone setter, so callers that stage it must invalidate the
+/// cache themselves once the transaction commits — renames can land on a
+/// different inode, so re-deriving the fingerprint on next read is simpler
+/// and cheap than pre-populating it here.
+fn invalidate_stream_ref_cache(lobe: &str, key: &str) {
+    stream_ref_cache().lock().unwrap().remove(&stream_key(lobe, key));
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -124,8 +762,12 @@ struct IdIndex {
     key: String,
 }
 
+fn id_index_path(id: &str) -> Result<PathBuf> {
+    Ok(ids_ref_dir()?.join(format!("{}.json", sanitize(id))))
+}
+
 fn write_id_index(id: &str, node: &str, lobe: &str, key: &str) -> Result<()> {
-    let p = ids_ref_dir()?.join(format!("{}.json", sanitize(id)));
+    let p = id_index_path(id)?;
     let idx = IdIndex {
         node: node.to_string(),
         lobe: lobe.to_string(),
@@ -156,12 +798,8 @@ struct HashIndex {
     node: String,
 }
 
-fn write_hash_index(hash: &str, node: &str) -> Result<()> {
-    let p = hashes_ref_dir()?.join(format!("{}.json", sanitize(hash)));
-    let idx = HashIndex {
-        node: node.to_string(),
-    };
-    write_atomic(&p, &serde_json::to_vec_pretty(&idx)?)
+fn hash_index_path(hash: &str) -> Result<PathBuf> {
+    Ok(hashes_ref_dir()?.join(format!("{}.json", sanitize(hash))))
 }
 
 fn read_hash_index(hash: &str) -> Result<Option<HashIndex>> {
@@ -179,986 +817,3682 @@ fn read_hash_index(hash: &str) -> Result<Option<HashIndex>> {
     Ok(Some(idx))
 }
 
-// ---------- parents helpers & optional reverse index ----------
+// ---------- short hash prefix resolution ----------
+//
+// `recall_snapshot`/`diverge_from`/`set_path_head` all took a full blake3
+// hex hash. A 16-way radix trie over every known hash's nibbles (the same
+// idea as Mercurial's node tree) lets a caller name a snapshot by an
+// unambiguous short prefix instead, the way a VCS lets you name a commit by
+// prefix. The trie lives in a process-lifetime cache rather than on disk --
+// same tradeoff as `stream_ref_cache` above -- and `save_node` inserts each
+// new hash into it directly, so it stays current without a rescan.
 
-fn read_children_index(hash: &str) -> Result<Vec<String>> {
-    let p = children_ref_dir()?.join(format!("{}.json", sanitize(hash)));
-    if !p.exists() {
-        return Ok(Vec::new());
-    }
-    let bytes = fs::read(&p)?;
-    let arr: Vec<String> = serde_json::from_slice(&bytes).unwrap_or_default();
-    Ok(arr)
+#[derive(Default)]
+struct HashTrieNode {
+    children: [Option<Box<HashTrieNode>>; 16],
+    /// Set only on a node reached by a hash's full 64 nibbles.
+    full_hash: Option<String>,
 }
 
-fn write_children_index(hash: &str, children: &[String]) -> Result<()> {
-    let p = children_ref_dir()?.join(format!("{}.json", sanitize(hash)));
-    write_atomic(&p, &serde_json::to_vec_pretty(children)?)
-}
+impl HashTrieNode {
+    fn insert(&mut self, hash: &str) {
+        let mut node = self;
+        for nibble in hash_nibbles(hash) {
+            node = node.children[nibble as usize].get_or_insert_with(Default::default);
+        }
+        node.full_hash = Some(hash.to_string());
+    }
 
-fn append_child_to_parent(hash: &str, child_node: &str) -> Result<()> {
-    let mut arr = read_children_index(hash)?;
-    if !arr.iter().any(|s| s == child_node) {
-        arr.push(child_node.to_string());
-        arr.sort();
-        write_children_index(hash, &arr)?;
+    /// The subtree reached by following `prefix`'s nibbles, or `None` if the
+    /// walk dead-ends.
+    fn descend(&self, prefix: &str) -> Option<&HashTrieNode> {
+        let mut node = self;
+        for nibble in hash_nibbles(prefix) {
+            node = node.children[nibble as usize].as_deref()?;
+        }
+        Some(node)
+    }
+
+    /// Every full hash reachable under this subtree, in nibble order.
+    fn collect_leaves(&self, out: &mut Vec<String>) {
+        if let Some(h) = &self.full_hash {
+            out.push(h.clone());
+        }
+        for child in self.children.iter().flatten() {
+            child.collect_leaves(out);
+        }
     }
-    Ok(())
 }
 
-// Back-compat helper: extract ordered parents (filenames or hashes).
-fn node_parents_list(v: &Value) -> Vec<String> {
-    if let Some(arr) = v.get("parents").and_then(|x| x.as_array()) {
-        let mut out = Vec::new();
-        for it in arr {
-            if let Some(s) = it.as_str() {
-                if !s.is_empty() {
-                    out.push(s.to_string());
-                }
-            }
-        }
-        return out;
-    }
-    if let Some(p) = v.get("parent").and_then(|x| x.as_str()) {
-        if !p.is_empty() {
-            return vec![p.to_string()];
-        }
-    }
-    Vec::new()
+fn hash_nibbles(hash: &str) -> impl Iterator<Item = u8> + '_ {
+    hash.bytes().filter_map(|b| (b as char).to_digit(16).map(|d| d as u8))
 }
 
-fn parent_filenames_from_node(v: &Value) -> Vec<String> {
-    let mut out: Vec<String> = Vec::new();
-    for p in node_parents_list(v) {
-        if p.ends_with(".json") {
-            out.push(p);
-        } else if let Some(fname) = resolve_parent_filename(&p).ok().flatten() {
-            out.push(fname);
-        }
-    }
-    out
-}
+static HASH_TRIE: OnceCell<Mutex<HashTrieNode>> = OnceCell::new();
 
-// Fallback parent filename resolver: first consult hash index; if missing, scan dag nodes directory
-// for a JSON file whose internal "hash" matches the requested parent hash. Returns filename if found.
-fn resolve_parent_filename(parent_hash: &str) -> Result<Option<String>> {
-    if let Some(idx) = read_hash_index(parent_hash)? {
-        return Ok(Some(idx.node));
-    }
-    let dir = match dag_nodes_dir() {
-        Ok(d) => d,
-        Err(_) => return Ok(None),
-    };
-    let entries = match fs::read_dir(&dir) {
-        Ok(e) => e,
-        Err(_) => return Ok(None),
-    };
-    for ent in entries.flatten() {
-        let p = ent.path();
-        if p.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
-        }
-        if let Some(fname) = p.file_name().and_then(|n| n.to_str()) {
-            match load_node(&fname.to_string()) {
-                Ok(v) => {
-                    if let Some(h) = v.get("hash").and_then(|x| x.as_str()) {
-                        if h == parent_hash {
-                            return Ok(Some(fname.to_string()));
-                        }
+fn hash_trie() -> &'static Mutex<HashTrieNode> {
+    HASH_TRIE.get_or_init(|| {
+        let mut root = HashTrieNode::default();
+        if let Ok(dir) = hashes_ref_dir() {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for ent in entries.flatten() {
+                    let path = ent.path();
+                    if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                        continue;
+                    }
+                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                        root.insert(stem);
                     }
                 }
-                Err(_) => continue,
             }
         }
-    }
-    Ok(None)
+        Mutex::new(root)
+    })
 }
 
-// ---------- public API (used by Memory) ----------
-
-/// Save a node for (lobe,key) stream if content changed. Returns the node file name.
-pub fn save_node(
-    id: &str,
-    content_utf8: &str,
-    meta: &serde_json::Value,
-    parents: &[String],
-) -> anyhow::Result<String> {
-    let lobe = meta
-        .get("lobe")
-        .and_then(|v| v.as_str())
-        .unwrap_or("unknown");
-    let key = meta
-        .get("key")
-        .and_then(|v| v.as_str())
-        .unwrap_or("default");
+fn hash_trie_insert(hash: &str) {
+    hash_trie().lock().unwrap().insert(hash);
+}
 
-    let h = blake3::hash(content_utf8.as_bytes()).to_hex().to_string();
+/// Failure resolving a short hash prefix via [`resolve_snapshot_prefix`].
+#[derive(Debug, Clone)]
+pub enum PrefixResolveError {
+    /// More than one known hash shares this prefix.
+    Ambiguous(Vec<String>),
+    /// No known hash starts with this prefix.
+    NotFound,
+}
 
-    // load last state for (lobe,key)
-    let mut sref = read_stream_ref(lobe, key)?;
-    if sref.last_hash.as_deref() == Some(&h) {
-        if let Some(latest) = sref.latest_node.clone() {
-            // Even if we don't write a new node, ensure this id is indexed to the latest node
-            let _ = write_id_index(id, &latest, lobe, key);
-            return Ok(latest); // idempotent: nothing to write
+impl std::fmt::Display for PrefixResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrefixResolveError::Ambiguous(candidates) => {
+                write!(f, "ambiguous hash prefix, {} candidates", candidates.len())
+            }
+            PrefixResolveError::NotFound => write!(f, "no snapshot matches that hash prefix"),
         }
-        // else: no latest yet — fall through and write one
     }
+}
 
-    let ts = chrono::Utc::now().to_rfc3339();
-    let fname = format!("{}__{}.json", ts.replace(':', "-"), sanitize(id));
-    let node_path = dag_nodes_dir()?.join(&fname);
-
-    // Determine ordered parents list (primary first). If none provided, default to current head.
-    let parent_list: Vec<String> = if !parents.is_empty() {
-        parents.to_vec()
-    } else {
-        sref.latest_node.clone().into_iter().collect()
-    };
+impl std::error::Error for PrefixResolveError {}
 
-    // Merge provided meta (if object) with our required fields. Always update updated_at and cid/hash.
-    let mut meta_obj: serde_json::Map<String, Value> = match meta.clone() {
-        Value::Object(m) => m,
-        _ => serde_json::Map::new(),
-    };
-    if !meta_obj.contains_key("lobe") {
-        meta_obj.insert("lobe".into(), Value::String(lobe.to_string()));
-    }
-    if !meta_obj.contains_key("key") {
-        meta_obj.insert("key".into(), Value::String(key.to_string()));
+/// Resolve a (possibly short) hex hash prefix to the one full snapshot
+/// hash it unambiguously names. Walks the prefix's nibbles down the trie;
+/// `Ok` only when exactly one leaf is reachable from there.
+pub fn resolve_snapshot_prefix(prefix: &str) -> std::result::Result<String, PrefixResolveError> {
+    let trie = hash_trie().lock().unwrap();
+    let subtree = trie.descend(prefix).ok_or(PrefixResolveError::NotFound)?;
+    let mut leaves = Vec::new();
+    subtree.collect_leaves(&mut leaves);
+    match leaves.len() {
+        0 => Err(PrefixResolveError::NotFound),
+        1 => Ok(leaves.into_iter().next().unwrap()),
+        _ => Err(PrefixResolveError::Ambiguous(leaves)),
     }
-    if !meta_obj.contains_key("created_at") {
-        meta_obj.insert("created_at".into(), Value::String(ts.clone()));
+}
+
+/// Accept either a full 64-char hex hash or an unambiguous short prefix,
+/// resolving the latter via [`resolve_snapshot_prefix`]. Used by every
+/// entry point that used to require a full hash (`recall_snapshot`,
+/// `diverge_from`, `set_path_head`) so any of them may be called with a
+/// VCS-style short hash.
+fn resolve_full_hash(id: &str) -> Result<String> {
+    if id.len() == 64 && id.bytes().all(|b| (b as char).is_ascii_hexdigit()) {
+        return Ok(id.to_string());
     }
-    // Always set these
-    meta_obj.insert("updated_at".into(), Value::String(ts.clone()));
-    meta_obj.insert("cid".into(), Value::String(h.clone()));
-    let summary_len = meta
-        .pointer("/summary")
-        .and_then(|v| v.as_str())
-        .map(|s| s.len())
-        .unwrap_or(0);
-    meta_obj.insert("summary_len".into(), serde_json::json!(summary_len));
+    resolve_snapshot_prefix(id).map_err(|e| anyhow!(e.to_string()))
+}
 
-    let node = serde_json::json!({
-        "id": id,
-        "ts": ts,
-        "lobe": lobe,
-        "key": key,
-        "parents": parent_list,
-        "hash": h,
-        "content": content_utf8,
-        "meta": Value::Object(meta_obj),
-    });
+// ---------- copy/rename tracking ----------
+//
+// `save_node` records provenance whenever content shows up under a new
+// (lobe,key) stream: an exact copy when the content's hash already has a
+// hash-index entry elsewhere, or a "soft" copy when the content is merely
+// similar (by shingle Jaccard similarity) to the prior head of some other
+// stream. Either way the destination node's meta gets a `copied_from`
+// pointer and an entry is appended to `refs/copies/<dest_stream>.json`, so
+// `copy_sources` can answer "where did this stream's content come from?"
+// without re-deriving it from content alone.
 
-    write_atomic(&node_path, &serde_json::to_vec_pretty(&node)?)?;
+const SOFT_COPY_SHINGLE_SIZE: usize = 3;
+const SOFT_COPY_JACCARD_THRESHOLD: f64 = 0.82;
 
-    sref.latest_node = Some(fname.clone());
-    sref.last_hash = Some(h.clone());
-    sref.updated_at = Some(ts);
-    write_stream_ref(lobe, key, &sref)?;
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct CopySource {
+    lobe: String,
+    key: String,
+    node: String,
+    // `None` for exact (hash-equal) copies; `Some(score)` for soft copies
+    // detected via shingle similarity.
+    similarity: Option<f64>,
+}
 
-    // Maintain quick indexes to avoid directory scans.
-    let _ = write_id_index(id, &fname, lobe, key);
-    let _ = write_hash_index(&h, &fname);
+fn copies_path(lobe: &str, key: &str) -> Result<PathBuf> {
+    Ok(copies_ref_dir()?.join(format!("{}.json", stream_key(lobe, key))))
+}
 
-    // Update reverse index: record this node as a child of each parent (by parent hash).
-    for pf in parent_filenames_from_node(&node) {
-        if let Ok(pnode) = load_node(&pf) {
-            if let Some(ph) = pnode.get("hash").and_then(|x| x.as_str()) {
-                let _ = append_child_to_parent(ph, &fname);
-            }
-        }
+fn read_copy_sources(lobe: &str, key: &str) -> Result<Vec<CopySource>> {
+    let p = copies_path(lobe, key)?;
+    if !p.exists() {
+        return Ok(Vec::new());
     }
-
-    Ok(fname)
+    let bytes = fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or_default())
 }
 
-/// Load a node by its filename (as returned by save_node).
-pub fn load_node(filename: &str) -> Result<Value> {
-    let p = dag_nodes_dir()?.join(filename);
-    let bytes = fs::read(&p).map_err(|_| anyhow!("node not found: {}", filename))?;
-    Ok(serde_json::from_slice(&bytes)?)
+fn append_copy_source(lobe: &str, key: &str, source: CopySource) -> Result<()> {
+    let mut sources = read_copy_sources(lobe, key)?;
+    sources.push(source);
+    write_atomic(&copies_path(lobe, key)?, &serde_json::to_vec_pretty(&sources)?)
 }
 
-/// Load a node by original memory id using the id index.
-pub fn load_node_by_id(id: &str) -> Result<Option<Value>> {
-    if let Some(idx) = read_id_index(id)? {
-        let v = load_node(&idx.node)?;
-        return Ok(Some(v));
-    }
-    Ok(None)
+/// Recorded copy/rename provenance for a (lobe,key) stream: every other
+/// stream whose content this stream's content was detected to have come
+/// from, in the order the copies were discovered.
+pub fn copy_sources(lobe: &str, key: &str) -> Result<Vec<Value>> {
+    Ok(read_copy_sources(lobe, key)?
+        .into_iter()
+        .map(|s| serde_json::json!(s))
+        .collect())
 }
 
-/// Return content string from a node by original memory id.
-pub fn content_by_id(id: &str) -> Result<Option<String>> {
-    if let Some(v) = load_node_by_id(id)? {
-        if let Some(s) = v.get("content").and_then(|x| x.as_str()) {
-            return Ok(Some(s.to_string()));
-        }
+/// Word-shingle set of size `SOFT_COPY_SHINGLE_SIZE`, used only as a cheap
+/// fingerprint for near-duplicate detection — not a general similarity
+/// metric.
+pub(crate) fn content_shingles(content: &str) -> std::collections::BTreeSet<String> {
+    let words: Vec<&str> = content
+        .split_whitespace()
+        .collect();
+    if words.len() < SOFT_COPY_SHINGLE_SIZE {
+        return words.iter().map(|w| w.to_lowercase()).collect();
     }
-    Ok(None)
+    words
+        .windows(SOFT_COPY_SHINGLE_SIZE)
+        .map(|w| w.join(" ").to_lowercase())
+        .collect()
 }
 
-/// Reindex a memory id to the latest node of a given (lobe, key) stream.
-/// Returns true if an index was written, false if no latest node exists yet.
-pub fn reindex_id_to_latest(id: &str, lobe: &str, key: &str) -> Result<bool> {
-    let sref = read_stream_ref(lobe, key)?;
-    if let Some(latest) = sref.latest_node {
-        let _ = write_id_index(id, &latest, lobe, key);
-        return Ok(true);
+fn jaccard_similarity(
+    a: &std::collections::BTreeSet<String>,
+    b: &std::collections::BTreeSet<String>,
+) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(b).count();
+    let union = a.union(b).count();
+    if union == 0 {
+        0.0
+    } else {
+        intersection as f64 / union as f64
     }
-    Ok(false)
 }
 
-// ---------- simple content search (newest-first) ----------
-
-/// Search DAG nodes for content containing all words (case-insensitive), newest-first.
-/// Returns a list of minimal dicts: [{"hash", "id", "ts"}]
-pub fn search_content_words(words: &[String], limit: usize) -> Result<Vec<Value>> {
-    let dir = dag_nodes_dir()?;
-    let mut names: Vec<String> = Vec::new();
+/// Every (lobe,key) stream other than the one currently being written to,
+/// for soft-copy candidate scanning. Only scans `refs/streams`, which is
+/// already one file per stream — not the node set.
+fn other_stream_refs(exclude_lobe: &str, exclude_key: &str) -> Result<Vec<(String, String, StreamRef)>> {
+    let dir = stream_refs_dir()?;
+    let mut out = Vec::new();
     for e in fs::read_dir(&dir)? {
         let path = e?.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                names.push(name.to_string());
-            }
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let bytes = match fs::read(&path) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        let r: StreamRef = match serde_json::from_slice(&bytes) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let (Some(lobe), Some(key)) = (r.lobe.clone(), r.key.clone()) else {
+            continue;
+        };
+        if lobe == exclude_lobe && key == exclude_key {
+            continue;
         }
+        out.push((lobe, key, r));
     }
-    // newest-first by filename (timestamp prefix, lexicographically sortable)
-    names.sort();
-    names.reverse();
+    Ok(out)
+}
 
-    let words_lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
-    let mut out: Vec<Value> = Vec::new();
-    for name in names {
-        let v = load_node(&name)?;
-        let content = v.get("content").and_then(|x| x.as_str()).unwrap_or("");
-        let lc = content.to_lowercase();
-        let mut ok = true;
-        for w in &words_lower {
-            if !lc.contains(w) {
-                ok = false;
-                break;
+/// Detect whether `content_utf8` (about to be saved to `(lobe,key)`) is a
+/// copy of content already living in some other stream. Tries an exact
+/// match via the hash index first (cheap, O(1)); if that misses, falls
+/// back to a shingle-similarity scan of other streams' current heads.
+fn detect_copy_source(
+    lobe: &str,
+    key: &str,
+    hash: &str,
+    content_utf8: &str,
+) -> Result<Option<CopySource>> {
+    if let Some(idx) = read_hash_index(hash)? {
+        if let Ok(node) = load_node(&idx.node) {
+            let src_lobe = node.get("lobe").and_then(|v| v.as_str()).unwrap_or("");
+            let src_key = node.get("key").and_then(|v| v.as_str()).unwrap_or("");
+            if !src_lobe.is_empty() && (src_lobe != lobe || src_key != key) {
+                return Ok(Some(CopySource {
+                    lobe: src_lobe.to_string(),
+                    key: src_key.to_string(),
+                    node: idx.node,
+                    similarity: None,
+                }));
             }
         }
-        if ok {
-            let hash = v.get("hash").and_then(|x| x.as_str()).unwrap_or("");
-            let id = v.get("id").and_then(|x| x.as_str()).unwrap_or("");
-            let ts = v.get("ts").and_then(|x| x.as_str()).unwrap_or("");
-            out.push(serde_json::json!({ "hash": hash, "id": id, "ts": ts }));
-            if out.len() >= limit {
-                break;
+    }
+
+    let shingles = content_shingles(content_utf8);
+    if shingles.is_empty() {
+        return Ok(None);
+    }
+    let mut best: Option<(f64, CopySource)> = None;
+    for (src_lobe, src_key, sref) in other_stream_refs(lobe, key)? {
+        let Some(latest) = sref.latest_node.clone() else {
+            continue;
+        };
+        let Ok(node) = load_node(&latest) else {
+            continue;
+        };
+        let other_content = node_content(&node).unwrap_or_default();
+        let score = jaccard_similarity(&shingles, &content_shingles(&other_content));
+        if score >= SOFT_COPY_JACCARD_THRESHOLD {
+            if best.as_ref().map(|(b, _)| score > *b).unwrap_or(true) {
+                best = Some((
+                    score,
+                    CopySource {
+                        lobe: src_lobe,
+                        key: src_key,
+                        node: latest,
+                        similarity: Some(score),
+                    },
+                ));
             }
         }
     }
-    Ok(out)
+    Ok(best.map(|(_, s)| s))
 }
 
-// ---------- Replay Mode (branching paths over immutable snapshots) ----------
+// ---------- parents helpers & optional reverse index ----------
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct MemoryState {
-    pub content: String,
-    pub meta: serde_json::Value,
+fn read_children_index(hash: &str) -> Result<Vec<String>> {
+    let p = children_ref_dir()?.join(format!("{}.json", sanitize(hash)));
+    if !p.exists() {
+        return Ok(Vec::new());
+    }
+    let bytes = fs::read(&p)?;
+    let arr: Vec<String> = serde_json::from_slice(&bytes).unwrap_or_default();
+    Ok(arr)
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
-struct PathRef {
-    name: String,
-    base_snapshot: String, // content hash used to seed the path
-    base_node: String,     // node filename for the base snapshot
-    head_node: String,     // current head node filename in this path
-    created_at: String,
-    updated_at: String,
+fn write_children_index(hash: &str, children: &[String]) -> Result<()> {
+    let p = children_ref_dir()?.join(format!("{}.json", sanitize(hash)));
+    write_atomic(&p, &serde_json::to_vec_pretty(children)?)
 }
 
-fn path_id_from_name(name: &str) -> String {
-    sanitize(name)
+fn append_child_to_parent(hash: &str, child_node: &str) -> Result<()> {
+    let mut arr = read_children_index(hash)?;
+    if !arr.iter().any(|s| s == child_node) {
+        arr.push(child_node.to_string());
+        arr.sort();
+        write_children_index(hash, &arr)?;
+    }
+    Ok(())
 }
 
-fn read_path_ref(path_name: &str) -> Result<Option<PathRef>> {
-    let id = path_id_from_name(path_name);
-    let p = paths_ref_dir()?.join(format!("{}.json", id));
+// ---------- nodemap: persistent hash/children indexes (avoid O(n) scans) ----------
+//
+// `children_of`, `snapshot_meta`, and `bind_base`'s index-miss paths used to
+// fall back to a full `enumerate_all_node_filenames()` scan with a
+// `load_node` per candidate. This closes that gap with one more append-only,
+// per-hash index alongside the existing single-winner `hashes` index and the
+// `children` reverse-adjacency index above (already maintained by
+// `append_child_to_parent`, but `children_of` simply wasn't consulting it):
+//   - `refs/hashnodes/<hash>.json`: every filename that has ever produced
+//     this content hash. Unlike `hashes`, which only remembers the most
+//     recent writer, this is a true multimap, so `snapshot_meta` (which
+//     intentionally binds metadata across *all* matches, since distinct
+//     lobes can commit identical content) can look it up directly instead
+//     of scanning every node to find the others.
+//
+// Both indexes are updated incrementally -- one append per `save_node` call,
+// never a rewrite of the whole thing. `nodemap_tip.json` records how many
+// nodes were indexed as of the last append; `ensure_nodemap_fresh_once`
+// compares that against the true on-disk node count once per process and
+// triggers `rebuild_nodemap` (a full rescan) on mismatch, so a tree that
+// predates this subsystem, or one where an index got out of sync, self-heals
+// instead of silently giving wrong answers.
+
+fn hashnodes_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("hashnodes");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn hashnodes_path(hash: &str) -> Result<PathBuf> {
+    Ok(hashnodes_ref_dir()?.join(format!("{}.json", sanitize(hash))))
+}
+
+fn read_hashnodes(hash: &str) -> Result<Vec<String>> {
+    let p = hashnodes_path(hash)?;
     if !p.exists() {
-        return Ok(None);
+        return Ok(Vec::new());
     }
     let bytes = fs::read(&p)?;
-    let r: PathRef = serde_json::from_slice(&bytes).unwrap_or_default();
-    if r.head_node.is_empty() {
-        return Ok(None);
+    Ok(serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+fn write_hashnodes(hash: &str, filenames: &[String]) -> Result<()> {
+    write_atomic(&hashnodes_path(hash)?, &serde_json::to_vec_pretty(filenames)?)
+}
+
+fn append_hashnode(hash: &str, filename: &str) -> Result<()> {
+    let mut arr = read_hashnodes(hash)?;
+    if !arr.iter().any(|s| s == filename) {
+        arr.push(filename.to_string());
+        write_hashnodes(hash, &arr)?;
+        record_refcount_delta(hash, 1)?;
     }
-    Ok(Some(r))
+    Ok(())
 }
 
-fn write_path_ref(path_name: &str, r: &PathRef) -> Result<()> {
-    let id = path_id_from_name(path_name);
-    let p = paths_ref_dir()?.join(format!("{}.json", id));
-    write_atomic(&p, &serde_json::to_vec_pretty(r)?)
+/// Remove `filename` from `hash`'s `hashnodes` bucket (called when a node
+/// that produced `hash` is physically deleted by [`prune`]/
+/// [`prune_near_duplicates`]). Returns `true` if `filename` was actually
+/// present -- a no-op removal (already absent) isn't itself a reference
+/// change, so it doesn't record a delta.
+fn remove_hashnode(hash: &str, filename: &str) -> Result<bool> {
+    let mut arr = read_hashnodes(hash)?;
+    let before = arr.len();
+    arr.retain(|s| s != filename);
+    if arr.len() == before {
+        return Ok(false);
+    }
+    write_hashnodes(hash, &arr)?;
+    record_refcount_delta(hash, -1)?;
+    Ok(true)
 }
 
-/// Recall a snapshot by its content-addressed hash id (blake3 hex).
-pub fn recall_snapshot(snapshot_id: &str) -> Result<MemoryState> {
-    let node_filename = if let Some(idx) = read_hash_index(snapshot_id)? {
+// ---------- branch-head (leaves) index ----------
+//
+// `refs/streams/<lobe>__<key>.json` (see `StreamRef` below) only tracks the
+// latest hash per stream, so a stream whose branches were forked via
+// `diverge_from`/`extend_path` under the same `(lobe, key)` loses every tip
+// but the last one written. `refs/leaves/<hash>.json` instead tracks every
+// hash that currently has no child: `save_node` inserts the new node's hash
+// and evicts its parents' hashes (they just grew a child), so the set
+// always holds exactly the current tips of every branch.
+
+fn leaf_path(hash: &str) -> Result<PathBuf> {
+    Ok(leaves_ref_dir()?.join(format!("{}.json", sanitize(hash))))
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct LeafEntry {
+    hash: String,
+    lobe: String,
+    key: String,
+    #[serde(default)]
+    path: Option<String>,
+    depth: u64,
+}
+
+fn read_leaf(hash: &str) -> Result<Option<LeafEntry>> {
+    let p = leaf_path(hash)?;
+    if !p.exists() {
+        return Ok(None);
+    }
+    Ok(serde_json::from_slice(&fs::read(&p)?).ok())
+}
+
+fn write_leaf(entry: &LeafEntry) -> Result<()> {
+    write_atomic(&leaf_path(&entry.hash)?, &serde_json::to_vec_pretty(entry)?)
+}
+
+fn remove_leaf(hash: &str) -> Result<()> {
+    let p = leaf_path(hash)?;
+    if p.exists() {
+        fs::remove_file(&p)?;
+    }
+    Ok(())
+}
+
+/// Tag an existing leaf with the named path that most recently extended it.
+/// A no-op if `hash` isn't currently a leaf (e.g. it's already been
+/// superseded by a later append on another thread) -- [`extend_path`] calls
+/// this right after `save_node`, so the miss window is negligible and not
+/// worth failing the append over.
+fn set_leaf_path(hash: &str, path_name: &str) -> Result<()> {
+    if let Some(mut entry) = read_leaf(hash)? {
+        entry.path = Some(path_name.to_string());
+        write_leaf(&entry)?;
+    }
+    Ok(())
+}
+
+/// Every current branch tip: a content hash with no child node, alongside
+/// the lobe/key stream it belongs to, the named path that last extended it
+/// (if any), and its depth (generation) from the root. A tip that never
+/// gets extended again, and whose accumulated RL value (see
+/// [`Commands::dag_canonical_head`]) stays low, is exactly the kind of
+/// abandoned branch [`dag_prune`] is meant to reclaim once its refcount
+/// drops to zero.
+pub fn dag_list_leaves() -> Result<Vec<Value>> {
+    let dir = leaves_ref_dir()?;
+    let mut out = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(bytes) = fs::read(&path) else { continue };
+        let Ok(leaf): std::result::Result<LeafEntry, _> = serde_json::from_slice(&bytes) else {
+            continue;
+        };
+        out.push(serde_json::to_value(&leaf)?);
+    }
+    Ok(out)
+}
+
+/// Walk the primary-parent chain from `leaf_hash` back to the branch's
+/// root, collecting each node's memory `id` -- the same `state_id` key the
+/// `values` table (see `services::reward::RewardSqliteSink`) is keyed by --
+/// so a caller can score the whole branch by summing learned RL values.
+pub fn leaf_branch_ids(leaf_hash: &str) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    let mut cur = read_hash_index(leaf_hash)?.map(|idx| idx.node);
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    while let Some(fname) = cur {
+        if !seen.insert(fname.clone()) {
+            break;
+        }
+        let node = load_node(&fname)?;
+        if let Some(id) = node.get("id").and_then(|x| x.as_str()) {
+            ids.push(id.to_string());
+        }
+        cur = match node_parents_list(&node).first() {
+            None => None,
+            Some(p) if p.ends_with(".json") => Some(p.clone()),
+            Some(p) => match read_hash_index(p)? {
+                Some(idx) => Some(idx.node),
+                None => resolve_parent_filename(p).ok().flatten(),
+            },
+        };
+    }
+    Ok(ids)
+}
+
+// ---------- reference-counted archive GC (era journal) ----------
+//
+// `hashnodes/<hash>.json` (above) already tracks which node filenames
+// currently produce a given content hash -- its length is the hash's live
+// reference count. What's missing is a way to know *when* that count last
+// changed, so a hash that just dropped to zero (e.g. mid branch-rewrite
+// reorg) isn't immediately treated as garbage: a blob that becomes briefly
+// unreferenced and then referenced again (the node re-added, or a new node
+// with the same content) shouldn't be deleted out from under that reorg.
+//
+// Every `append_hashnode`/`remove_hashnode` call stamps a monotonically
+// increasing "era" (one era per reference-count mutation) onto an
+// append-only journal (`dag/refcounts.journal.jsonl`) and records the hash's
+// most recent era in `refs/refcount_meta/<hash>.json`. `dag_prune(keep_eras)`
+// only reclaims a hash whose live count is zero AND whose last mutation era
+// is older than `keep_eras` eras ago -- the deferred-deletion grace window.
+
+fn refcount_era_path() -> Result<PathBuf> {
+    Ok(dag_dir()?.join("refcount_era.json"))
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+struct RefcountEra {
+    last_era: u64,
+}
+
+/// Bump and persist the global era counter, returning the freshly-assigned era.
+fn next_era() -> Result<u64> {
+    let p = refcount_era_path()?;
+    let mut era: RefcountEra = if p.exists() {
+        serde_json::from_slice(&fs::read(&p)?).unwrap_or_default()
+    } else {
+        RefcountEra::default()
+    };
+    era.last_era += 1;
+    write_atomic(&p, &serde_json::to_vec(&era)?)?;
+    Ok(era.last_era)
+}
+
+/// The most recently assigned era, without minting a new one.
+fn current_era() -> Result<u64> {
+    let p = refcount_era_path()?;
+    if !p.exists() {
+        return Ok(0);
+    }
+    let era: RefcountEra = serde_json::from_slice(&fs::read(&p)?).unwrap_or_default();
+    Ok(era.last_era)
+}
+
+fn refcount_journal_path() -> Result<PathBuf> {
+    Ok(dag_dir()?.join("refcounts.journal.jsonl"))
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct RefcountDeltaRecord {
+    era: u64,
+    cid: String,
+    delta: i64,
+    ts: String,
+}
+
+fn refcount_meta_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("refcount_meta");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn refcount_meta_path(hash: &str) -> Result<PathBuf> {
+    Ok(refcount_meta_dir()?.join(format!("{}.json", sanitize(hash))))
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+struct RefcountMeta {
+    last_era: u64,
+}
+
+fn read_refcount_meta(hash: &str) -> Result<RefcountMeta> {
+    let p = refcount_meta_path(hash)?;
+    if !p.exists() {
+        return Ok(RefcountMeta::default());
+    }
+    Ok(serde_json::from_slice(&fs::read(&p)?).unwrap_or_default())
+}
+
+/// Mint a new era, append `{era, cid: hash, delta}` to the journal, and
+/// stamp `hash`'s last-touched era. Returns the era assigned.
+fn record_refcount_delta(hash: &str, delta: i64) -> Result<u64> {
+    let era = next_era()?;
+    let record = RefcountDeltaRecord {
+        era,
+        cid: hash.to_string(),
+        delta,
+        ts: chrono::Utc::now().to_rfc3339(),
+    };
+    let path = refcount_journal_path()?;
+    let mut f = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    use std::io::Write as _;
+    writeln!(f, "{}", serde_json::to_string(&record)?)?;
+    write_atomic(&refcount_meta_path(hash)?, &serde_json::to_vec(&RefcountMeta { last_era: era })?)?;
+    Ok(era)
+}
+
+/// Current live count and last-mutation era for every content hash that has
+/// ever had a refcount delta recorded, for audit/dry-run inspection ahead
+/// of [`dag_prune`].
+pub fn dag_refcounts() -> Result<Vec<RefcountEntry>> {
+    let dir = refcount_meta_dir()?;
+    let mut out = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let hash = stem.to_string();
+        let meta = read_refcount_meta(&hash)?;
+        let live_count = read_hashnodes(&hash)?.len();
+        out.push(RefcountEntry {
+            cid: hash,
+            live_count,
+            last_era: meta.last_era,
+        });
+    }
+    Ok(out)
+}
+
+/// One content hash's current reference count (per [`dag_refcounts`]) plus
+/// the era it last changed in.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RefcountEntry {
+    pub cid: String,
+    pub live_count: usize,
+    pub last_era: u64,
+}
+
+/// Report from [`dag_prune`]: which hashes were eligible for reclamation
+/// (zero live references, settled for at least `keep_eras`), how many bytes
+/// their archive blobs held, and -- when `dry_run` is `false` -- which were
+/// actually deleted.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DagPruneReport {
+    pub current_era: u64,
+    pub keep_eras: u64,
+    pub examined: usize,
+    pub eligible: Vec<String>,
+    pub reclaimable_bytes: u64,
+    pub deleted: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// Reclaim archive blobs (`<archive_path>/<cid>`) for content hashes that no
+/// longer have any live DAG reference (`hashnodes` count of zero) and whose
+/// refcount last changed more than `keep_eras` eras ago -- the grace window
+/// that protects a blob briefly unreferenced during a branch reorg from
+/// being deleted before a would-be re-reference has a chance to land.
+///
+/// With `dry_run` true, nothing is deleted; `reclaimable_bytes` and
+/// `eligible` still report what a real run would reclaim, so an operator
+/// can audit before committing.
+pub fn dag_prune(keep_eras: u64, dry_run: bool) -> Result<DagPruneReport> {
+    let current = current_era()?;
+    let cutoff = current.saturating_sub(keep_eras);
+    let archive_path = ensure_initialized_once()?.config.memory.archive_path.clone();
+
+    let mut report = DagPruneReport {
+        current_era: current,
+        keep_eras,
+        dry_run,
+        ..Default::default()
+    };
+
+    for entry in dag_refcounts()? {
+        report.examined += 1;
+        if entry.live_count != 0 || entry.last_era > cutoff {
+            continue;
+        }
+        let blob_path = archive_path.join(&entry.cid);
+        let Ok(meta) = fs::metadata(&blob_path) else {
+            continue; // never archived, or already reclaimed
+        };
+        report.eligible.push(entry.cid.clone());
+        report.reclaimable_bytes += meta.len();
+        if !dry_run {
+            if fs::remove_file(&blob_path).is_ok() {
+                report.deleted.push(entry.cid.clone());
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn nodemap_tip_path() -> Result<PathBuf> {
+    Ok(dag_dir()?.join("nodemap_tip.json"))
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+struct NodemapTip {
+    indexed_count: u64,
+}
+
+fn read_nodemap_tip() -> Result<NodemapTip> {
+    let p = nodemap_tip_path()?;
+    if !p.exists() {
+        return Ok(NodemapTip::default());
+    }
+    let bytes = fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+fn write_nodemap_tip(tip: &NodemapTip) -> Result<()> {
+    write_atomic(&nodemap_tip_path()?, &serde_json::to_vec(tip)?)
+}
+
+/// Record one freshly-written node's hash/children nodemap entries and
+/// advance the tip counter. Called once per node from `save_node`.
+fn nodemap_record_node(filename: &str, hash: &str, parent_hashes: &[String]) -> Result<()> {
+    append_hashnode(hash, filename)?;
+    for ph in parent_hashes {
+        append_child_to_parent(ph, filename)?;
+    }
+    let mut tip = read_nodemap_tip()?;
+    tip.indexed_count += 1;
+    write_nodemap_tip(&tip)
+}
+
+/// Full rescan of every known node, rebuilding the hashnodes and children
+/// indexes from scratch and resetting the tip to the true node count. Safe
+/// to call at any time -- e.g. after a manual recovery, or whenever
+/// [`ensure_nodemap_fresh_once`] detects the tip has drifted.
+pub fn rebuild_nodemap() -> Result<usize> {
+    let names = enumerate_all_node_filenames()?;
+    // Clear first so a shrunk node set (e.g. after pruning) doesn't leave
+    // dangling entries behind.
+    for dir in [hashnodes_ref_dir()?, children_ref_dir()?] {
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for e in entries.flatten() {
+                let _ = fs::remove_file(e.path());
+            }
+        }
+    }
+    let mut indexed = 0usize;
+    for name in &names {
+        if let Ok(v) = load_node(name) {
+            let hash = v.get("hash").and_then(|x| x.as_str()).unwrap_or("");
+            if !hash.is_empty() {
+                let _ = append_hashnode(hash, name);
+            }
+            for pf in parent_filenames_from_node(&v) {
+                if let Ok(pnode) = load_node(&pf) {
+                    if let Some(ph) = pnode.get("hash").and_then(|x| x.as_str()) {
+                        let _ = append_child_to_parent(ph, name);
+                    }
+                }
+            }
+            indexed += 1;
+        }
+    }
+    write_nodemap_tip(&NodemapTip {
+        indexed_count: names.len() as u64,
+    })?;
+    Ok(indexed)
+}
+
+/// Self-heal once per process: if the recorded tip doesn't match the
+/// current node count, rebuild the nodemap from scratch. Cheap to call from
+/// every read path that relies on the nodemap, mirroring
+/// [`ensure_migrated_once`]'s pattern.
+fn ensure_nodemap_fresh_once() {
+    static DONE: std::sync::Once = std::sync::Once::new();
+    DONE.call_once(|| {
+        let tip = read_nodemap_tip().unwrap_or_default();
+        let actual = enumerate_all_node_filenames()
+            .map(|n| n.len() as u64)
+            .unwrap_or(0);
+        if tip.indexed_count != actual {
+            let _ = rebuild_nodemap();
+        }
+    });
+}
+
+// ---------- generation numbers (for bind_base's GCA walk) ----------
+//
+// Each node's generation is 1 + the max generation of its parents (0 for a
+// root). Stored per-hash alongside the rest of the nodemap and computed
+// incrementally at `save_node` time from its parents' already-cached
+// generations, so `bind_base` never has to walk the whole history just to
+// order its frontier.
+
+fn generations_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?.root.join("refs").join("generations");
+    fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn generation_path(hash: &str) -> Result<PathBuf> {
+    Ok(generations_ref_dir()?.join(format!("{}.json", sanitize(hash))))
+}
+
+pub(crate) fn read_generation(hash: &str) -> Result<Option<u64>> {
+    let p = generation_path(hash)?;
+    if !p.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).ok())
+}
+
+fn write_generation(hash: &str, generation: u64) -> Result<()> {
+    write_atomic(&generation_path(hash)?, &serde_json::to_vec(&generation)?)
+}
+
+// Back-compat helper: extract ordered parents (filenames or hashes).
+fn node_parents_list(v: &Value) -> Vec<String> {
+    if let Some(arr) = v.get("parents").and_then(|x| x.as_array()) {
+        let mut out = Vec::new();
+        for it in arr {
+            if let Some(s) = it.as_str() {
+                if !s.is_empty() {
+                    out.push(s.to_string());
+                }
+            }
+        }
+        return out;
+    }
+    if let Some(p) = v.get("parent").and_then(|x| x.as_str()) {
+        if !p.is_empty() {
+            return vec![p.to_string()];
+        }
+    }
+    Vec::new()
+}
+
+fn parent_filenames_from_node(v: &Value) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for p in node_parents_list(v) {
+        if p.ends_with(".json") {
+            out.push(p);
+        } else if let Some(fname) = resolve_parent_filename(&p).ok().flatten() {
+            out.push(fname);
+        }
+    }
+    out
+}
+
+// Fallback parent filename resolver: first consult hash index; if missing, scan dag nodes directory
+// for a JSON file whose internal "hash" matches the requested parent hash. Returns filename if found.
+pub(crate) fn resolve_parent_filename(parent_hash: &str) -> Result<Option<String>> {
+    if let Some(idx) = read_hash_index(parent_hash)? {
+        return Ok(Some(idx.node));
+    }
+    if let Some(fname) = read_hashnodes(parent_hash)?.into_iter().next() {
+        return Ok(Some(fname));
+    }
+    let names = match enumerate_all_node_filenames() {
+        Ok(n) => n,
+        Err(_) => return Ok(None),
+    };
+    for fname in names {
+        match load_node(&fname) {
+            Ok(v) => {
+                if let Some(h) = v.get("hash").and_then(|x| x.as_str()) {
+                    if h == parent_hash {
+                        return Ok(Some(fname));
+                    }
+                }
+            }
+            Err(_) => continue,
+        }
+    }
+    Ok(None)
+}
+
+// ---------- public API (used by Memory) ----------
+
+/// How a node's content is represented on disk. [`NodeBody::Chunks`] is the
+/// normal content-defined-chunking path (see `src/memory/blob.rs`);
+/// [`NodeBody::Delta`] is the compact patch-against-parent representation
+/// written by `extend_path_with_policy`'s delta-chained mode.
+enum NodeBody {
+    Chunks(Vec<String>),
+    Delta {
+        parent: String,
+        prefix_len: usize,
+        suffix_len: usize,
+        middle: String,
+    },
+}
+
+impl NodeBody {
+    fn insert_into(self, obj: &mut serde_json::Map<String, Value>) {
+        match self {
+            NodeBody::Chunks(chunks) => {
+                obj.insert("chunks".into(), serde_json::json!(chunks));
+            }
+            NodeBody::Delta {
+                parent,
+                prefix_len,
+                suffix_len,
+                middle,
+            } => {
+                obj.insert(
+                    "delta".into(),
+                    serde_json::json!({
+                        "parent": parent,
+                        "prefix_len": prefix_len,
+                        "suffix_len": suffix_len,
+                        "middle": middle,
+                    }),
+                );
+            }
+        }
+    }
+}
+
+/// Save a node for (lobe,key) stream if content changed. Returns the node file name.
+pub fn save_node(
+    id: &str,
+    content_utf8: &str,
+    meta: &serde_json::Value,
+    parents: &[String],
+) -> anyhow::Result<String> {
+    // Split content into content-defined chunks and store each once,
+    // deduped by its own hash, rather than embedding the whole payload in
+    // the node -- see `src/memory/blob.rs`. A snapshot that only edits a
+    // few lines then shares most of its chunks with its parent.
+    let chunk_hashes = blob::store_blob(content_utf8.as_bytes())?;
+    save_node_body(id, content_utf8, meta, parents, NodeBody::Chunks(chunk_hashes))
+}
+
+/// Save a node whose content is reconstructed by patching the given
+/// parent's content (see [`extend_path_with_policy`]) rather than storing
+/// it whole. `content_utf8` is still the full reconstructed content --
+/// needed for hashing and copy/rename detection -- the caller has already
+/// computed `prefix_len`/`suffix_len`/`middle` against the parent.
+#[allow(clippy::too_many_arguments)]
+fn save_delta_node(
+    id: &str,
+    content_utf8: &str,
+    parent_hash: &str,
+    prefix_len: usize,
+    suffix_len: usize,
+    middle: &str,
+    meta: &serde_json::Value,
+    parents: &[String],
+) -> anyhow::Result<String> {
+    save_node_body(
+        id,
+        content_utf8,
+        meta,
+        parents,
+        NodeBody::Delta {
+            parent: parent_hash.to_string(),
+            prefix_len,
+            suffix_len,
+            middle: middle.to_string(),
+        },
+    )
+}
+
+fn save_node_body(
+    id: &str,
+    content_utf8: &str,
+    meta: &serde_json::Value,
+    parents: &[String],
+    body: NodeBody,
+) -> anyhow::Result<String> {
+    ensure_migrated_once();
+    ensure_recovered_once();
+    ensure_nodemap_fresh_once();
+    let lobe = meta
+        .get("lobe")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let key = meta
+        .get("key")
+        .and_then(|v| v.as_str())
+        .unwrap_or("default");
+
+    let h = blake3::hash(content_utf8.as_bytes()).to_hex().to_string();
+
+    // load last state for (lobe,key)
+    let mut sref = read_stream_ref(lobe, key)?;
+    if sref.last_hash.as_deref() == Some(&h) {
+        if let Some(latest) = sref.latest_node.clone() {
+            // Even if we don't write a new node, ensure this id is indexed to the latest node
+            let _ = write_id_index(id, &latest, lobe, key);
+            return Ok(latest); // idempotent: nothing to write
+        }
+        // else: no latest yet — fall through and write one
+    }
+
+    // Copy/rename detection: does this content already exist under a
+    // different (lobe,key)? Checked before the node is written so the
+    // provenance can be folded into this node's own meta.
+    let copy_source = detect_copy_source(lobe, key, &h, content_utf8)?;
+
+    let ts = chrono::Utc::now().to_rfc3339();
+    let fname = format!("{}__{}.json", ts.replace(':', "-"), sanitize(id));
+
+    // Determine ordered parents list (primary first). If none provided, default to current head.
+    let parent_list: Vec<String> = if !parents.is_empty() {
+        parents.to_vec()
+    } else {
+        sref.latest_node.clone().into_iter().collect()
+    };
+
+    // Merge provided meta (if object) with our required fields. Always update updated_at and cid/hash.
+    let mut meta_obj: serde_json::Map<String, Value> = match meta.clone() {
+        Value::Object(m) => m,
+        _ => serde_json::Map::new(),
+    };
+    if !meta_obj.contains_key("lobe") {
+        meta_obj.insert("lobe".into(), Value::String(lobe.to_string()));
+    }
+    if !meta_obj.contains_key("key") {
+        meta_obj.insert("key".into(), Value::String(key.to_string()));
+    }
+    if !meta_obj.contains_key("created_at") {
+        meta_obj.insert("created_at".into(), Value::String(ts.clone()));
+    }
+    // Always set these
+    meta_obj.insert("updated_at".into(), Value::String(ts.clone()));
+    meta_obj.insert("cid".into(), Value::String(h.clone()));
+    let summary_len = meta
+        .pointer("/summary")
+        .and_then(|v| v.as_str())
+        .map(|s| s.len())
+        .unwrap_or(0);
+    meta_obj.insert("summary_len".into(), serde_json::json!(summary_len));
+    if let Some(ref cs) = copy_source {
+        meta_obj.insert(
+            "copied_from".into(),
+            serde_json::json!({
+                "lobe": cs.lobe,
+                "key": cs.key,
+                "node": cs.node,
+                "similarity": cs.similarity,
+            }),
+        );
+    }
+
+    let mut node_obj = serde_json::Map::new();
+    node_obj.insert("id".into(), Value::String(id.to_string()));
+    node_obj.insert("ts".into(), Value::String(ts.clone()));
+    node_obj.insert("lobe".into(), Value::String(lobe.to_string()));
+    node_obj.insert("key".into(), Value::String(key.to_string()));
+    node_obj.insert("parents".into(), serde_json::json!(parent_list));
+    node_obj.insert("hash".into(), Value::String(h.clone()));
+    body.insert_into(&mut node_obj);
+    node_obj.insert("meta".into(), Value::Object(meta_obj));
+    let node = Value::Object(node_obj);
+
+    let payload = serde_json::to_vec(&node)?;
+    // append_node_to_pack fsyncs the pack itself and rewrites the docket
+    // only after the bytes land (see the module note above), so the pack
+    // append is its own crash-safe step; the *index* writes that must be
+    // consistent with each other and with it (offset, stream ref, id, hash)
+    // go through one journaled transaction below.
+    let entry = append_node_to_pack(&fname, &payload)?;
+
+    sref.latest_node = Some(fname.clone());
+    sref.last_hash = Some(h.clone());
+    sref.updated_at = Some(ts.clone());
+    sref.lobe = Some(lobe.to_string());
+    sref.key = Some(key.to_string());
+
+    let mut tx = begin();
+    tx.stage_node(&offset_index_path(&fname)?, serde_json::to_vec(&entry)?)?;
+    tx.stage_node(
+        &stream_ref_path(lobe, key)?,
+        serde_json::to_vec_pretty(&sref)?,
+    )?;
+    tx.stage_node(
+        &id_index_path(id)?,
+        serde_json::to_vec_pretty(&IdIndex {
+            node: fname.clone(),
+            lobe: lobe.to_string(),
+            key: key.to_string(),
+        })?,
+    )?;
+    tx.stage_node(
+        &hash_index_path(&h)?,
+        serde_json::to_vec_pretty(&HashIndex { node: fname.clone() })?,
+    )?;
+    tx.commit()?;
+    invalidate_stream_ref_cache(lobe, key);
+
+    let _ = index_node_tokens(&fname, &ts, content_utf8);
+    if let Some(cs) = copy_source {
+        let _ = append_copy_source(lobe, key, cs);
+    }
+
+    // Record this node in the nodemap: its hash (for snapshot_meta's
+    // multi-match lookups) and its parent adjacency (for children_of),
+    // then advance the self-healing tip counter.
+    let parent_hashes: Vec<String> = parent_filenames_from_node(&node)
+        .into_iter()
+        .filter_map(|pf| load_node(&pf).ok())
+        .filter_map(|pnode| {
+            pnode
+                .get("hash")
+                .and_then(|x| x.as_str())
+                .map(|s| s.to_string())
+        })
+        .collect();
+    let _ = nodemap_record_node(&fname, &h, &parent_hashes);
+
+    let generation = parent_hashes
+        .iter()
+        .filter_map(|ph| read_generation(ph).ok().flatten())
+        .max()
+        .map(|g| g + 1)
+        .unwrap_or(0);
+    let _ = write_generation(&h, generation);
+
+    // This node's parents just grew a child, so they're no longer tips;
+    // this node is the new tip of whichever branch(es) it extends.
+    for ph in &parent_hashes {
+        let _ = remove_leaf(ph);
+    }
+    let _ = write_leaf(&LeafEntry {
+        hash: h.clone(),
+        lobe: lobe.to_string(),
+        key: key.to_string(),
+        path: None,
+        depth: generation,
+    });
+
+    let _ = simhash::record_fingerprint(&fname, &h, lobe, key, &ts, content_utf8);
+    hash_trie_insert(&h);
+
+    Ok(fname)
+}
+
+/// Reconstruction can't chain deeper than this many delta hops before it's
+/// treated as a broken parent chain rather than patiently replayed -- a
+/// legitimate chain is already bounded by `CompactionPolicy::delta_keyframe_interval`
+/// (a fresh keyframe every N appends), so this is only ever hit by
+/// corruption (e.g. a parent pointer cycle).
+const MAX_DELTA_CHAIN_DEPTH: usize = 10_000;
+
+/// Reconstruct a node's content: replaying a [`NodeBody::Delta`] patch
+/// against its parent if present, else preferring the chunked `chunks`
+/// list (see `src/memory/blob.rs`), and falling back to the legacy inline
+/// `content` string for nodes written before content-defined chunking
+/// existed.
+fn node_content(v: &Value) -> Result<String> {
+    node_content_bounded(v, &mut std::collections::HashSet::new(), 0)
+}
+
+fn node_content_bounded(
+    v: &Value,
+    visited_parents: &mut std::collections::HashSet<String>,
+    depth: usize,
+) -> Result<String> {
+    if let Some(delta) = v.get("delta") {
+        if depth >= MAX_DELTA_CHAIN_DEPTH {
+            return Err(anyhow!(
+                "broken delta parent chain: exceeded max depth ({})",
+                MAX_DELTA_CHAIN_DEPTH
+            ));
+        }
+        return reconstruct_delta(delta, visited_parents, depth);
+    }
+    if let Some(chunks) = v.get("chunks").and_then(|x| x.as_array()) {
+        let hashes: Vec<String> = chunks
+            .iter()
+            .filter_map(|c| c.as_str().map(|s| s.to_string()))
+            .collect();
+        let bytes = blob::load_blob(&hashes)?;
+        return Ok(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    Ok(v.get("content")
+        .and_then(|x| x.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Reconstruct a delta node's content by loading its parent (by hash,
+/// recursing through `node_content_bounded` so a chain of deltas replays
+/// back to its nearest keyframe) and splicing the stored prefix/middle/suffix
+/// back together. `middle` is base64-encoded since it's an arbitrary byte
+/// run, not necessarily valid UTF-8 on its own. `visited_parents` guards
+/// against a corrupted chain that cycles back on itself (a hash can't
+/// legitimately reappear as its own ancestor) -- without it, such a chain
+/// would recurse forever rather than surfacing as a clean error.
+fn reconstruct_delta(
+    delta: &Value,
+    visited_parents: &mut std::collections::HashSet<String>,
+    depth: usize,
+) -> Result<String> {
+    let parent_hash = delta
+        .get("parent")
+        .and_then(|x| x.as_str())
+        .ok_or_else(|| anyhow!("delta node missing parent hash"))?;
+    if !visited_parents.insert(parent_hash.to_string()) {
+        return Err(anyhow!(
+            "broken delta parent chain: cycle detected at {}",
+            parent_hash
+        ));
+    }
+    let prefix_len = delta
+        .get("prefix_len")
+        .and_then(|x| x.as_u64())
+        .unwrap_or(0) as usize;
+    let suffix_len = delta
+        .get("suffix_len")
+        .and_then(|x| x.as_u64())
+        .unwrap_or(0) as usize;
+    let middle = delta
+        .get("middle")
+        .and_then(|x| x.as_str())
+        .ok_or_else(|| anyhow!("delta node missing middle patch"))?;
+    let middle_bytes = B64.decode(middle).context("decode delta middle patch")?;
+
+    let idx = read_hash_index(parent_hash)?
+        .ok_or_else(|| anyhow!("delta parent not found: {}", parent_hash))?;
+    let parent_node = load_node(&idx.node)?;
+    let parent_content = node_content_bounded(&parent_node, visited_parents, depth + 1)?;
+    let parent_bytes = parent_content.as_bytes();
+    if prefix_len + suffix_len > parent_bytes.len() {
+        return Err(anyhow!(
+            "corrupt delta: prefix_len + suffix_len exceeds parent length"
+        ));
+    }
+
+    let mut out = Vec::with_capacity(prefix_len + middle_bytes.len() + suffix_len);
+    out.extend_from_slice(&parent_bytes[..prefix_len]);
+    out.extend_from_slice(&middle_bytes);
+    out.extend_from_slice(&parent_bytes[parent_bytes.len() - suffix_len..]);
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Load a node by its filename (as returned by save_node). Consults the
+/// offset index first (one seek + read into `dag/nodes.pack`); falls back
+/// to the legacy per-node file for anything not yet migrated.
+pub fn load_node(filename: &str) -> Result<Value> {
+    ensure_migrated_once();
+    if let Some(entry) = read_offset_index(filename)? {
+        if let Ok(v) = read_node_from_pack(&entry) {
+            return Ok(v);
+        }
+    }
+    let p = dag_nodes_dir()?.join(filename);
+    let bytes = fs::read(&p).map_err(|_| anyhow!("node not found: {}", filename))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Load a node by original memory id using the id index.
+pub fn load_node_by_id(id: &str) -> Result<Option<Value>> {
+    if let Some(idx) = read_id_index(id)? {
+        let v = load_node(&idx.node)?;
+        return Ok(Some(v));
+    }
+    Ok(None)
+}
+
+/// Return content string from a node by original memory id.
+pub fn content_by_id(id: &str) -> Result<Option<String>> {
+    if let Some(v) = load_node_by_id(id)? {
+        return Ok(Some(node_content(&v)?));
+    }
+    Ok(None)
+}
+
+/// Reindex a memory id to the latest node of a given (lobe, key) stream.
+/// Returns true if an index was written, false if no latest node exists yet.
+pub fn reindex_id_to_latest(id: &str, lobe: &str, key: &str) -> Result<bool> {
+    let sref = read_stream_ref(lobe, key)?;
+    if let Some(latest) = sref.latest_node {
+        let _ = write_id_index(id, &latest, lobe, key);
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+// ---------- simple content search (newest-first) ----------
+
+/// Search DAG nodes for content matching all words (case-insensitive, AND
+/// semantics), newest-first. Looks up each word's postings list (falling
+/// back to typo-tolerant matches per [`postings_for_query_word`]) and
+/// intersects them by filename, so only the surviving candidates' full
+/// bodies are loaded. Returns a list of minimal dicts: [{"hash", "id", "ts"}]
+/// Okapi BM25 term-frequency saturation (`k1`) and document-length
+/// normalization (`b`) -- the usual defaults.
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// Case-insensitive content terms with `compute_reflection`'s stopwords
+/// removed. This is a different, coarser tokenization than the inverted
+/// index's [`tokenize_content`] (which keeps every token, stopwords
+/// included, since it only needs exact postings lookups); BM25 needs
+/// "real" terms so scores aren't dominated by "the"/"and"/etc.
+fn bm25_terms(content: &str) -> Vec<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .filter(|t| t.len() >= 3 && !STOPWORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// Nodes containing every word in `words` (case-insensitive, same
+/// candidate selection as before), ranked by Okapi BM25 relevance instead
+/// of recency. `N` (corpus size) and `avgdl` (mean node length) are
+/// computed over every indexed node, same cost tradeoff as
+/// `resolve_parent_filename`'s full-scan fallback; `n_t` per query term
+/// comes straight from that term's postings list. Each result dict carries
+/// its `score`; ties break by recency, newest first.
+pub fn search_content_words(words: &[String], limit: usize) -> Result<Vec<Value>> {
+    if words.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut sets: Vec<std::collections::HashMap<String, String>> = Vec::new();
+    for w in words {
+        let mut set: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        for entry in postings_for_query_word(w)? {
+            set.insert(entry.filename, entry.ts);
+        }
+        sets.push(set);
+    }
+    sets.sort_by_key(|s| s.len());
+
+    let mut iter = sets.into_iter();
+    let mut candidates = match iter.next() {
+        Some(s) => s,
+        None => return Ok(Vec::new()),
+    };
+    for s in iter {
+        candidates.retain(|fname, _| s.contains_key(fname));
+        if candidates.is_empty() {
+            break;
+        }
+    }
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let terms: Vec<String> = words
+        .iter()
+        .map(|w| w.to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let all_filenames = enumerate_all_node_filenames()?;
+    let n_total = (all_filenames.len() as f64).max(1.0);
+    let mut doc_len: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    let mut total_len = 0.0f64;
+    for fname in &all_filenames {
+        let len = load_node(fname)
+            .ok()
+            .and_then(|v| node_content(&v).ok())
+            .map(|c| bm25_terms(&c).len())
+            .unwrap_or(0) as f64;
+        total_len += len;
+        doc_len.insert(fname.clone(), len);
+    }
+    let avgdl = (total_len / n_total).max(1.0);
+
+    let mut idf: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    for t in &terms {
+        let n_t = postings_for_query_word(t)?.len() as f64;
+        idf.insert(t.clone(), ((n_total - n_t + 0.5) / (n_t + 0.5) + 1.0).ln());
+    }
+
+    let mut scored: Vec<(String, String, f64)> = Vec::new();
+    for (fname, ts) in candidates.into_iter() {
+        let node = match load_node(&fname) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let tokens = bm25_terms(&node_content(&node).unwrap_or_default());
+        let mut tf: std::collections::HashMap<&str, f64> = std::collections::HashMap::new();
+        for t in &tokens {
+            *tf.entry(t.as_str()).or_insert(0.0) += 1.0;
+        }
+        let dl = doc_len.get(&fname).copied().unwrap_or(tokens.len() as f64);
+
+        let mut score = 0.0;
+        for t in &terms {
+            let f = tf.get(t.as_str()).copied().unwrap_or(0.0);
+            if f == 0.0 {
+                continue;
+            }
+            let idf_t = idf.get(t).copied().unwrap_or(0.0);
+            score +=
+                idf_t * (f * (BM25_K1 + 1.0)) / (f + BM25_K1 * (1.0 - BM25_B + BM25_B * dl / avgdl));
+        }
+        scored.push((fname, ts, score));
+    }
+
+    // descending score, then newest-first (ts sorts lexicographically)
+    scored.sort_by(|a, b| {
+        b.2.partial_cmp(&a.2)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.1.cmp(&a.1))
+    });
+    scored.truncate(limit);
+
+    let mut out: Vec<Value> = Vec::new();
+    for (fname, _ts, score) in scored {
+        if let Ok(v) = load_node(&fname) {
+            let hash = v.get("hash").and_then(|x| x.as_str()).unwrap_or("");
+            let id = v.get("id").and_then(|x| x.as_str()).unwrap_or("");
+            let ts = v.get("ts").and_then(|x| x.as_str()).unwrap_or("");
+            out.push(serde_json::json!({ "hash": hash, "id": id, "ts": ts, "score": score }));
+        }
+    }
+    Ok(out)
+}
+
+// ---------- Replay Mode (branching paths over immutable snapshots) ----------
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MemoryState {
+    pub content: String,
+    pub meta: serde_json::Value,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+struct PathRef {
+    name: String,
+    base_snapshot: String, // content hash used to seed the path
+    base_node: String,     // node filename for the base snapshot
+    head_node: String,     // current head node filename in this path
+    created_at: String,
+    updated_at: String,
+    // field name -> `Conversion::to_string()` (e.g. "int", "timestamp|%Y-%m-%d"),
+    // declared via `set_path_schema`/`Commands::declare_path_schema`. The
+    // reserved name "content" applies to the snapshot's own text rather than
+    // a `meta` field. Stored as plain strings (not `commands::Conversion`
+    // directly) so this module doesn't need to know that type -- mirrors
+    // `utils::pons::PonsSchema`'s on-disk representation.
+    #[serde(default)]
+    schema: std::collections::BTreeMap<String, String>,
+    /// Count of delta-encoded appends since the last full keyframe, used by
+    /// `extend_path_with_policy` to enforce
+    /// [`CompactionPolicy::delta_keyframe_interval`]. Always `0` for paths
+    /// that have never used delta encoding.
+    #[serde(default)]
+    deltas_since_keyframe: u32,
+}
+
+fn path_id_from_name(name: &str) -> String {
+    sanitize(name)
+}
+
+fn read_path_ref(path_name: &str) -> Result<Option<PathRef>> {
+    let id = path_id_from_name(path_name);
+    let p = paths_ref_dir()?.join(format!("{}.json", id));
+    if !p.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&p)?;
+    let r: PathRef = serde_json::from_slice(&bytes).unwrap_or_default();
+    if r.head_node.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(r))
+}
+
+fn write_path_ref(path_name: &str, r: &PathRef) -> Result<()> {
+    let id = path_id_from_name(path_name);
+    let p = paths_ref_dir()?.join(format!("{}.json", id));
+    write_atomic(&p, &serde_json::to_vec_pretty(r)?)
+}
+
+/// Recall a snapshot by its content-addressed hash id (blake3 hex).
+pub fn recall_snapshot(snapshot_id: &str) -> Result<MemoryState> {
+    let resolved = resolve_full_hash(snapshot_id)?;
+    let snapshot_id = resolved.as_str();
+    let node_filename = if let Some(idx) = read_hash_index(snapshot_id)? {
         idx.node
     } else {
         // Fallback: linear scan for robustness in early states
-        let dir = dag_nodes_dir()?;
         let mut found: Option<String> = None;
-        for e in fs::read_dir(&dir)? {
-            let path = e?.path();
-            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+        for name in enumerate_all_node_filenames()? {
+            if let Ok(v) = load_node(&name) {
+                if v.get("hash").and_then(|x| x.as_str()) == Some(snapshot_id) {
+                    found = Some(name);
+                    break;
+                }
+            }
+        }
+        found.ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_id))?
+    };
+
+    let v = load_node(&node_filename)?;
+    let content = node_content(&v)?;
+    // Merge top-level lobe/key/ts/id/hash with nested meta for convenient replay
+    let mut meta_map = serde_json::Map::new();
+    if let Some(m) = v.get("meta").and_then(|m| m.as_object()) {
+        for (k, vv) in m.iter() {
+            meta_map.insert(k.clone(), vv.clone());
+        }
+    }
+    for k in ["lobe", "key", "ts", "id", "hash"] {
+        if let Some(val) = v.get(k) {
+            meta_map.insert(k.to_string(), val.clone());
+        }
+    }
+    Ok(MemoryState {
+        content,
+        meta: Value::Object(meta_map),
+    })
+}
+
+/// Create or reset a named path to diverge from a specific snapshot.
+/// Returns the `path_id` (sanitized name).
+pub fn diverge_from(snapshot_id: &str, path_name: &str) -> Result<String> {
+    let resolved = resolve_full_hash(snapshot_id)?;
+    let snapshot_id = resolved.as_str();
+    // Resolve snapshot to node filename (use index; fallback to scan like recall)
+    let node_filename = if let Some(idx) = read_hash_index(snapshot_id)? {
+        idx.node
+    } else {
+        // Fallback: linear scan for robustness if index is missing
+        let mut found: Option<String> = None;
+        for name in enumerate_all_node_filenames()? {
+            if let Ok(v) = load_node(&name) {
+                if v.get("hash").and_then(|x| x.as_str()) == Some(snapshot_id) {
+                    found = Some(name);
+                    break;
+                }
+            }
+        }
+        found.ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_id))?
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+    let r = PathRef {
+        name: path_name.to_string(),
+        base_snapshot: snapshot_id.to_string(),
+        base_node: node_filename.clone(),
+        head_node: node_filename,
+        created_at: now.clone(),
+        updated_at: now,
+        // Fresh path: no delta chain yet, so its first `extend_path` call
+        // after this diverge treats the source snapshot (`head_node`) as a
+        // full-keyframe delta base rather than inheriting the source path's
+        // in-progress keyframe countdown.
+        schema: Default::default(),
+        deltas_since_keyframe: 0,
+    };
+    write_path_ref(path_name, &r)?;
+    Ok(path_id_from_name(path_name))
+}
+
+/// Append a new immutable snapshot to a named path and advance its head.
+/// Returns the new content-addressed snapshot id (blake3 hex).
+///
+/// Equivalent to [`extend_path_with_policy`] with `CompactionPolicy::default()`,
+/// which (via the `#[derive(Default)]` on that struct, not its serde
+/// defaults) leaves `delta_keyframe_interval` at `0` -- i.e. delta encoding
+/// off, every append a full keyframe, matching this function's historical
+/// behavior.
+pub fn extend_path(path_name: &str, state: MemoryState) -> Result<String> {
+    extend_path_with_policy(path_name, state, &CompactionPolicy::default())
+}
+
+/// Append a new immutable snapshot to a named path and advance its head,
+/// honoring `policy`'s delta-chained storage knobs.
+///
+/// When the new content is similar enough to the path's current head (at
+/// least `policy.delta_min_similarity`) and the path hasn't yet chained
+/// `policy.delta_keyframe_interval` deltas since its last full copy, the
+/// node is stored as a byte-level patch (common prefix/suffix against the
+/// parent's content) instead of a full copy; `recall_snapshot` replays the
+/// chain back to its nearest keyframe transparently via `node_content`.
+/// The node's declared `hash` is always the blake3 of the *reconstructed*
+/// full content, so CID-based indexing and verification are unaffected by
+/// which representation was chosen.
+pub fn extend_path_with_policy(
+    path_name: &str,
+    state: MemoryState,
+    policy: &CompactionPolicy,
+) -> Result<String> {
+    let mut r =
+        read_path_ref(path_name)?.ok_or_else(|| anyhow!("path not found: {}", path_name))?;
+
+    // Ensure meta has sensible lobe/key for replay isolation
+    let mut meta = match state.meta {
+        Value::Object(m) => Value::Object(m),
+        _ => Value::Object(serde_json::Map::new()),
+    };
+    if meta.get("lobe").is_none() {
+        meta.as_object_mut()
+            .unwrap()
+            .insert("lobe".into(), Value::String("replay".into()));
+    }
+    let key_default = path_id_from_name(path_name);
+    if meta.get("key").is_none() {
+        meta.as_object_mut()
+            .unwrap()
+            .insert("key".into(), Value::String(key_default.clone()));
+    }
+
+    // Timestamps
+    let now = chrono::Utc::now().to_rfc3339();
+    if meta.get("created_at").is_none() {
+        meta.as_object_mut()
+            .unwrap()
+            .insert("created_at".into(), Value::String(now.clone()));
+    }
+    meta.as_object_mut()
+        .unwrap()
+        .insert("updated_at".into(), Value::String(now.clone()));
+
+    // Content-addressed id
+    let new_hash = blake3::hash(state.content.as_bytes()).to_hex().to_string();
+    meta.as_object_mut()
+        .unwrap()
+        .insert("cid".into(), Value::String(new_hash.clone()));
+
+    let parent_content = load_node(&r.head_node).ok().and_then(|n| node_content(&n).ok());
+    let delta_plan = parent_content.as_deref().and_then(|parent| {
+        plan_delta(parent.as_bytes(), state.content.as_bytes(), policy, r.deltas_since_keyframe)
+    });
+
+    let _node_file = if let Some(DeltaPlan {
+        parent_hash,
+        prefix_len,
+        suffix_len,
+        middle,
+    }) = delta_plan
+    {
+        let node_file = save_delta_node(
+            &new_hash,
+            &state.content,
+            &parent_hash,
+            prefix_len,
+            suffix_len,
+            &middle,
+            &meta,
+            &[r.head_node.clone()],
+        )?;
+        r.deltas_since_keyframe += 1;
+        node_file
+    } else {
+        let node_file = save_node(&new_hash, &state.content, &meta, &[r.head_node.clone()])?;
+        r.deltas_since_keyframe = 0;
+        node_file
+    };
+
+    // Update path head and write back
+    let latest_idx =
+        read_hash_index(&new_hash)?.ok_or_else(|| anyhow!("hash index missing for new node"))?;
+    r.head_node = latest_idx.node;
+    r.updated_at = now;
+    write_path_ref(path_name, &r)?;
+
+    // save_node only knows (lobe,key); stamp the named path onto its leaf
+    // entry so dag_list_leaves can report which path last extended it.
+    let _ = set_leaf_path(&new_hash, path_name);
+
+    Ok(new_hash)
+}
+
+struct DeltaPlan {
+    parent_hash: String,
+    prefix_len: usize,
+    suffix_len: usize,
+    middle: String,
+}
+
+/// Decide whether `new` is worth storing as a delta against `parent`
+/// rather than a full keyframe, per `policy`. Returns `None` (write a
+/// keyframe) when the chain is already at `delta_keyframe_interval`, delta
+/// encoding is disabled (`delta_keyframe_interval == 0`), or the common
+/// prefix+suffix with `parent` falls short of `delta_min_similarity`.
+fn plan_delta(
+    parent: &[u8],
+    new: &[u8],
+    policy: &CompactionPolicy,
+    deltas_since_keyframe: u32,
+) -> Option<DeltaPlan> {
+    if policy.delta_keyframe_interval == 0 {
+        return None;
+    }
+    if deltas_since_keyframe + 1 >= policy.delta_keyframe_interval {
+        return None;
+    }
+    if parent.is_empty() {
+        return None;
+    }
+
+    let prefix_len = common_prefix_len(parent, new);
+    let max_suffix = parent.len().min(new.len()) - prefix_len;
+    let suffix_len = common_suffix_len(parent, new, max_suffix);
+
+    let longer = parent.len().max(new.len()).max(1) as f32;
+    let similarity = (prefix_len + suffix_len) as f32 / longer;
+    if similarity < policy.delta_min_similarity {
+        return None;
+    }
+
+    let parent_hash = blake3::hash(parent).to_hex().to_string();
+    let middle = &new[prefix_len..new.len() - suffix_len];
+    Some(DeltaPlan {
+        parent_hash,
+        prefix_len,
+        suffix_len,
+        middle: B64.encode(middle),
+    })
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
+}
+
+fn common_suffix_len(a: &[u8], b: &[u8], max: usize) -> usize {
+    (0..max)
+        .take_while(|&n| a[a.len() - 1 - n] == b[b.len() - 1 - n])
+        .count()
+}
+
+// ---------- Public helpers for paths (heads, base, ancestry) ----------
+
+/// Return true if a path ref exists.
+pub fn path_exists(path_name: &str) -> Result<bool> {
+    Ok(read_path_ref(path_name)?.is_some())
+}
+
+/// Return the current head snapshot hash for a named path, if any.
+pub fn path_head_hash(path_name: &str) -> Result<Option<String>> {
+    if let Some(r) = read_path_ref(path_name)? {
+        if r.head_node.is_empty() {
+            return Ok(None);
+        }
+        let v = load_node(&r.head_node)?;
+        let h = v
+            .get("hash")
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string();
+        if h.is_empty() { Ok(None) } else { Ok(Some(h)) }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Return the base snapshot hash recorded for a named path, if present.
+pub fn path_base_snapshot(path_name: &str) -> Result<Option<String>> {
+    if let Some(r) = read_path_ref(path_name)? {
+        if r.base_snapshot.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(r.base_snapshot))
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// The typed-field schema declared for a path via [`set_path_schema`], if
+/// any -- empty if the path has no schema (or doesn't exist).
+pub fn path_schema(path_name: &str) -> Result<std::collections::BTreeMap<String, String>> {
+    Ok(read_path_ref(path_name)?.map(|r| r.schema).unwrap_or_default())
+}
+
+/// Declare (or replace) the typed-field schema for an existing path: field
+/// name -> a [`crate::commands::Conversion`] string, with "content" naming
+/// the snapshot text itself rather than a `meta` field. `Commands::append`
+/// consults this to coerce/validate on write.
+pub fn set_path_schema(
+    path_name: &str,
+    schema: &std::collections::BTreeMap<String, String>,
+) -> Result<()> {
+    let mut r = read_path_ref(path_name)?
+        .ok_or_else(|| anyhow!("path '{}' not found; call branch() first", path_name))?;
+    r.schema = schema.clone();
+    write_path_ref(path_name, &r)
+}
+
+/// Update a path's head to point at an existing snapshot by its content hash.
+/// Fails if the hash is unknown.
+pub fn set_path_head(path_name: &str, snapshot_hash: &str) -> Result<()> {
+    let resolved = resolve_full_hash(snapshot_hash)?;
+    let snapshot_hash = resolved.as_str();
+    let idx = read_hash_index(snapshot_hash)?
+        .ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_hash))?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let r = if let Some(mut existing) = read_path_ref(path_name)? {
+        existing.head_node = idx.node;
+        existing.updated_at = now.clone();
+        existing
+    } else {
+        // Create a new path ref seeded at this snapshot
+        PathRef {
+            name: path_name.to_string(),
+            base_snapshot: snapshot_hash.to_string(),
+            base_node: idx.node.clone(),
+            head_node: idx.node,
+            created_at: now.clone(),
+            updated_at: now,
+            schema: Default::default(),
+            deltas_since_keyframe: 0,
+        }
+    };
+    write_path_ref(path_name, &r)
+}
+
+/// Return true if `ancestor_hash` is on the ancestor chain of `descendant_hash` (or equal).
+pub fn is_ancestor(ancestor_hash: &str, descendant_hash: &str) -> Result<bool> {
+    if ancestor_hash == descendant_hash {
+        return Ok(true);
+    }
+    let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    if let Some(idx) = read_hash_index(descendant_hash)? {
+        queue.push_back(idx.node);
+    }
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    while let Some(fname) = queue.pop_front() {
+        if !seen.insert(fname.clone()) {
+            continue;
+        }
+        let node = load_node(&fname)?;
+        if node.get("hash").and_then(|x| x.as_str()) == Some(ancestor_hash) {
+            return Ok(true);
+        }
+        for p in node_parents_list(&node) {
+            if p.is_empty() {
+                continue;
+            }
+            if p.ends_with(".json") {
+                queue.push_back(p);
+            } else if let Some(idx) = read_hash_index(&p)? {
+                queue.push_back(idx.node);
+            } else {
+                // Attempt fallback resolution: scan for a node whose internal hash matches `p`.
+                if let Some(fname) = resolve_parent_filename(&p).ok().flatten() {
+                    queue.push_back(fname);
+                } else {
+                    // Retain raw hash only if resolution failed; later iterations cannot load it directly
+                    // but this preserves prior behavior for completeness.
+                    queue.push_back(p);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// One link in an [`AncestryProof`]: a node's content hash, its parents'
+/// content hashes, and the payload that hash commits to. Self-contained --
+/// [`verify_ancestry_proof`] needs nothing else.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProofLink {
+    pub cid: String,
+    pub parent_cids: Vec<String>,
+    pub payload: String,
+}
+
+/// Ordered chain of [`ProofLink`]s from a descendant snapshot back to one
+/// of its ancestors, as returned by [`dag_ancestry_proof`]. `links[0].cid`
+/// is the descendant, `links.last().cid` is the ancestor, and each
+/// `links[i + 1].cid` appears in `links[i].parent_cids`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AncestryProof {
+    pub links: Vec<ProofLink>,
+}
+
+/// Canonicalize a node's raw parent reference -- a content hash, or (for
+/// nodes written before multi-parent merges) a node filename -- down to the
+/// parent's content hash, the same `hash` field [`save_node`] stamps on
+/// every node.
+fn canonical_parent_hash(raw: &str) -> Option<String> {
+    if raw.ends_with(".json") {
+        load_node(raw)
+            .ok()?
+            .get("hash")
+            .and_then(|x| x.as_str())
+            .map(|s| s.to_string())
+    } else {
+        Some(raw.to_string())
+    }
+}
+
+/// Build a compact, self-contained proof that `descendant_cid` descends
+/// from `ancestor_cid`: the ordered chain of `(cid, parent_cids, payload)`
+/// linking the two, suitable for handing to a remote or thin client that
+/// only has the two CIDs and wants to check the claim itself via
+/// [`verify_ancestry_proof`], without replaying the whole graph or ever
+/// touching this node store again. Mirrors [`is_ancestor`]'s BFS, but also
+/// records the path taken; returns `Ok(None)` if `descendant_cid` isn't
+/// found, or `ancestor_cid` isn't actually one of its ancestors.
+pub fn dag_ancestry_proof(
+    descendant_cid: &str,
+    ancestor_cid: &str,
+) -> Result<Option<AncestryProof>> {
+    let link_for = |fname: &str| -> Result<ProofLink> {
+        let node = load_node(fname)?;
+        let cid = node
+            .get("hash")
+            .and_then(|x| x.as_str())
+            .ok_or_else(|| anyhow!("node {} has no hash", fname))?
+            .to_string();
+        let payload = node_content(&node)?;
+        let parent_cids = node_parents_list(&node)
+            .iter()
+            .filter_map(|p| canonical_parent_hash(p))
+            .collect();
+        Ok(ProofLink { cid, parent_cids, payload })
+    };
+
+    let Some(start_idx) = read_hash_index(descendant_cid)? else {
+        return Ok(None);
+    };
+    let start_fname = start_idx.node;
+
+    if descendant_cid == ancestor_cid {
+        return Ok(Some(AncestryProof { links: vec![link_for(&start_fname)?] }));
+    }
+
+    let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    let mut came_from: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    queue.push_back(start_fname.clone());
+    seen.insert(start_fname.clone());
+
+    let mut found: Option<String> = None;
+    while let Some(fname) = queue.pop_front() {
+        let node = load_node(&fname)?;
+        if node.get("hash").and_then(|x| x.as_str()) == Some(ancestor_cid) {
+            found = Some(fname);
+            break;
+        }
+        for p in node_parents_list(&node) {
+            if p.is_empty() {
+                continue;
+            }
+            let parent_fname = if p.ends_with(".json") {
+                Some(p)
+            } else {
+                match read_hash_index(&p)? {
+                    Some(idx) => Some(idx.node),
+                    None => resolve_parent_filename(&p).ok().flatten(),
+                }
+            };
+            let Some(parent_fname) = parent_fname else {
                 continue;
+            };
+            if seen.insert(parent_fname.clone()) {
+                came_from.insert(parent_fname.clone(), fname.clone());
+                queue.push_back(parent_fname);
+            }
+        }
+    }
+
+    let Some(ancestor_fname) = found else {
+        return Ok(None);
+    };
+
+    let mut path = vec![ancestor_fname.clone()];
+    let mut cur = ancestor_fname;
+    while let Some(prev) = came_from.get(&cur) {
+        path.push(prev.clone());
+        cur = prev.clone();
+    }
+    path.reverse(); // descendant ..= ancestor
+
+    let links = path
+        .iter()
+        .map(|fname| link_for(fname))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(AncestryProof { links }))
+}
+
+/// Check a [`dag_ancestry_proof`] result with no DB or node-store access at
+/// all: every link's `cid` must equal `blake3::hash(payload)` (the same
+/// content hash [`save_node`] computes), each link but the last must name
+/// the next link's `cid` in its own `parent_cids` -- so the chain is
+/// actually a chain of real parent edges, not an arbitrary bag of
+/// individually-valid nodes -- and the chain must start at `descendant` and
+/// terminate at `ancestor`.
+pub fn verify_ancestry_proof(proof: &AncestryProof, descendant: &str, ancestor: &str) -> bool {
+    let Some(first) = proof.links.first() else {
+        return false;
+    };
+    if first.cid != descendant {
+        return false;
+    }
+    let Some(last) = proof.links.last() else {
+        return false;
+    };
+    if last.cid != ancestor {
+        return false;
+    }
+
+    for (i, link) in proof.links.iter().enumerate() {
+        let recomputed = blake3::hash(link.payload.as_bytes()).to_hex().to_string();
+        if recomputed != link.cid {
+            return false;
+        }
+        if i + 1 < proof.links.len() {
+            let next_cid = &proof.links[i + 1].cid;
+            if !link.parent_cids.iter().any(|p| p == next_cid) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+// ---------- verifiable membership proofs (path-relative) ----------
+
+/// One link in a [`MembershipProof`]: a node's content hash, its primary
+/// parent's content hash (`None` only for the root of a path), the payload
+/// committing to that hash, and the node's `meta` object alongside a
+/// `blake3` digest of its serialized form -- so a verifier that receives
+/// only this link (no access to the node store) can confirm both that the
+/// node is genuinely part of the path's history *and* that the provenance
+/// it cites hasn't been altered in transit.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MembershipLink {
+    pub hash: String,
+    pub parent_hash: Option<String>,
+    pub payload: String,
+    pub meta: Value,
+    pub meta_digest: String,
+}
+
+/// Ordered chain of [`MembershipLink`]s from a path's head down to a target
+/// content hash, as returned by [`dag_prove_membership`]. `links[0].hash` is
+/// the head, `links.last().hash` is the target, and `links[i].parent_hash`
+/// names `links[i + 1].hash`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MembershipProof {
+    pub links: Vec<MembershipLink>,
+}
+
+fn meta_digest(meta: &Value) -> Result<String> {
+    Ok(blake3::hash(&serde_json::to_vec(meta)?).to_hex().to_string())
+}
+
+/// Build a compact, self-contained inclusion proof that `target_hash` is
+/// one of the ancestors of `path_name`'s current head: the ordered chain of
+/// `(hash, parent_hash, blake3(content))` tuples linking the two, each
+/// carrying its node's `meta` and a digest of it. Mirrors
+/// [`dag_ancestry_proof`], but resolves the descendant from a path name (as
+/// [`Commands::path_ancestry_proof`] does) and additionally embeds
+/// provenance-bearing metadata so a verifier can recover citations without
+/// ever touching this node store. Returns `Ok(None)` if the path has no
+/// head, or `target_hash` isn't actually one of its ancestors.
+pub fn dag_prove_membership(path_name: &str, target_hash: &str) -> Result<Option<MembershipProof>> {
+    let Some(head) = path_head_hash(path_name)? else {
+        return Ok(None);
+    };
+    let Some(ancestry) = dag_ancestry_proof(&head, target_hash)? else {
+        return Ok(None);
+    };
+
+    let mut links = Vec::with_capacity(ancestry.links.len());
+    for (i, link) in ancestry.links.iter().enumerate() {
+        let parent_hash = ancestry.links.get(i + 1).map(|next| next.cid.clone());
+        let meta = snapshot_meta(&link.cid).unwrap_or(Value::Object(serde_json::Map::new()));
+        let digest = meta_digest(&meta)?;
+        links.push(MembershipLink {
+            hash: link.cid.clone(),
+            parent_hash,
+            payload: link.payload.clone(),
+            meta,
+            meta_digest: digest,
+        });
+    }
+    Ok(Some(MembershipProof { links }))
+}
+
+/// Check a [`dag_prove_membership`] proof against the head hash it claims
+/// to start from, with no DAG or DB access at all: every link's `hash` must
+/// equal `blake3::hash(payload)`, every link but the last must name the
+/// next link's `hash` as its `parent_hash`, every embedded `meta` must
+/// match its `meta_digest`, and the chain must start at `head_hash`.
+/// Returns the de-duplicated set of `provenance.sources` entries from every
+/// link on success -- these are the sources "proven reachable" from
+/// `head_hash` -- or `None` if any check fails.
+pub fn dag_verify_proof(head_hash: &str, proof: &MembershipProof) -> Result<Option<Vec<Value>>> {
+    let Some(first) = proof.links.first() else {
+        return Ok(None);
+    };
+    if first.hash != head_hash {
+        return Ok(None);
+    }
+
+    let mut sources: Vec<Value> = Vec::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    for (i, link) in proof.links.iter().enumerate() {
+        let recomputed = blake3::hash(link.payload.as_bytes()).to_hex().to_string();
+        if recomputed != link.hash {
+            return Ok(None);
+        }
+        if meta_digest(&link.meta)? != link.meta_digest {
+            return Ok(None);
+        }
+        if i + 1 < proof.links.len() {
+            if link.parent_hash.as_deref() != Some(proof.links[i + 1].hash.as_str()) {
+                return Ok(None);
+            }
+        } else if link.parent_hash.is_some() {
+            return Ok(None);
+        }
+
+        if let Some(arr) = link
+            .meta
+            .get("provenance")
+            .and_then(|p| p.get("sources"))
+            .and_then(|s| s.as_array())
+        {
+            for s in arr {
+                if seen.insert(provenance_source_key(s)) {
+                    sources.push(s.clone());
+                }
+            }
+        }
+    }
+
+    Ok(Some(sources))
+}
+
+/// Return the child (next) nodes of a given node via the `children` nodemap
+/// index, keyed by this node's content hash. Falls back to a full scan only
+/// if the node's own hash can't be resolved (e.g. a malformed node).
+pub fn children_of(filename: &str) -> Result<Vec<String>> {
+    ensure_nodemap_fresh_once();
+    if let Ok(v) = load_node(filename) {
+        if let Some(hash) = v.get("hash").and_then(|x| x.as_str()) {
+            return read_children_index(hash);
+        }
+    }
+    let mut kids = Vec::new();
+    for name in enumerate_all_node_filenames()? {
+        if let Ok(v) = load_node(&name) {
+            let parents = parent_filenames_from_node(&v);
+            if parents.iter().any(|p| p == filename) {
+                kids.push(name);
             }
-            let bytes = fs::read(&path)?;
-            if let Ok(v) = serde_json::from_slice::<Value>(&bytes) {
+        }
+    }
+    Ok(kids)
+}
+
+// ---------- tiny DAG pruner (MVP) ----------
+
+#[derive(Debug, Clone)]
+pub struct PruneReport {
+    pub examined: usize,
+    pub kept: usize,
+    pub removed: usize,
+    pub chunks_removed: usize,
+}
+
+// ---------- Snapshot metadata, citations, and path tracing ----------
+
+/// Return the `meta` object for a snapshot by its content hash id.
+///
+/// Multiple DAG nodes can share the same content hash (e.g. different lobes
+/// remembering identical content). Rather than relying on a single hash index
+/// entry, scan for all matching nodes and bind their metadata, prioritising
+/// the indexed node when available.
+pub fn snapshot_meta(snapshot_id: &str) -> Result<Value> {
+    ensure_nodemap_fresh_once();
+    let mut metas: Vec<serde_json::Map<String, Value>> = Vec::new();
+    let mut seen_files: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    let mut filenames = read_hashnodes(snapshot_id)?;
+    if filenames.is_empty() {
+        // Nodemap miss: fall back to a one-time scan rather than reporting
+        // "not found" outright (the tip-based self-heal should prevent
+        // this in practice, but a stale index shouldn't turn into a
+        // false negative).
+        for name in enumerate_all_node_filenames()? {
+            if let Ok(v) = load_node(&name) {
                 if v.get("hash").and_then(|x| x.as_str()) == Some(snapshot_id) {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        found = Some(name.to_string());
-                        break;
-                    }
+                    filenames.push(name);
+                }
+            }
+        }
+    }
+
+    for name in filenames {
+        if !seen_files.insert(name.clone()) {
+            continue;
+        }
+        let node = match load_node(&name) {
+            Ok(n) => n,
+            Err(_) => continue,
+        };
+        if node.get("hash").and_then(|x| x.as_str()) != Some(snapshot_id) {
+            continue;
+        }
+        if let Some(meta_obj) = node.get("meta").and_then(|m| m.as_object()) {
+            metas.push(meta_obj.clone());
+        } else {
+            metas.push(serde_json::Map::new());
+        }
+    }
+
+    if metas.is_empty() {
+        return Err(anyhow!("snapshot not found: {}", snapshot_id));
+    }
+
+    let mut binding = metas.remove(0);
+    for meta in metas.iter() {
+        bind_meta_maps(&mut binding, meta);
+    }
+
+    Ok(Value::Object(binding))
+}
+
+fn bind_meta_maps(
+    base: &mut serde_json::Map<String, Value>,
+    incoming: &serde_json::Map<String, Value>,
+) {
+    for (k, v) in incoming {
+        if k == "provenance" {
+            bind_provenance(base, v);
+            continue;
+        }
+        let should_set = !base.contains_key(k)
+            || base
+                .get(k)
+                .map(|existing| existing.is_null())
+                .unwrap_or(false);
+        if should_set {
+            base.insert(k.clone(), v.clone());
+        }
+    }
+}
+
+fn bind_provenance(base: &mut serde_json::Map<String, Value>, incoming: &Value) {
+    let incoming_obj = match incoming.as_object() {
+        Some(map) => map,
+        None => return,
+    };
+
+    let prov_entry = base
+        .entry("provenance".to_string())
+        .or_insert_with(|| Value::Object(serde_json::Map::new()));
+    if !prov_entry.is_object() {
+        *prov_entry = Value::Object(serde_json::Map::new());
+    }
+    let prov_map = prov_entry.as_object_mut().expect("provenance object");
+
+    let mut bindd_sources: Vec<Value> = prov_map
+        .get("sources")
+        .and_then(|s| s.as_array())
+        .map(|arr| arr.clone())
+        .unwrap_or_else(Vec::new);
+
+    let mut seen: std::collections::HashSet<String> =
+        bindd_sources.iter().map(provenance_source_key).collect();
+
+    if let Some(new_sources) = incoming_obj.get("sources").and_then(|s| s.as_array()) {
+        for src in new_sources {
+            let key = provenance_source_key(src);
+            if seen.insert(key) {
+                bindd_sources.push(src.clone());
+            }
+        }
+    }
+
+    prov_map.insert("sources".to_string(), Value::Array(bindd_sources));
+
+    for (k, value) in incoming_obj {
+        if k == "sources" {
+            continue;
+        }
+        prov_map.entry(k.clone()).or_insert_with(|| value.clone());
+    }
+}
+
+fn provenance_source_key(src: &Value) -> String {
+    serde_json::json!({
+        "kind": src.get("kind"),
+        "uri": src.get("uri"),
+        "cid": src.get("cid"),
+        "range": src.get("range"),
+    })
+    .to_string()
+}
+
+/// Flatten and return any provenance.sources listed in the snapshot meta; de-duplicates basic tuples.
+pub fn cite_sources(snapshot_id: &str) -> Result<Vec<Value>> {
+    let meta = snapshot_meta(snapshot_id)?;
+    let mut out: Vec<Value> = Vec::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    if let Some(arr) = meta
+        .get("provenance")
+        .and_then(|p| p.get("sources"))
+        .and_then(|s| s.as_array())
+    {
+        for s in arr {
+            let key = provenance_source_key(s);
+            if seen.insert(key) {
+                out.push(s.clone());
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Trace a named path from head backwards following parent pointers; newest -> oldest up to `limit`.
+/// Returns a vector of lightweight objects with id/hash/ts/lobe/key and source counts.
+pub fn trace_path(path_name: &str, limit: usize) -> Result<Vec<Value>> {
+    let r = read_path_ref(path_name)?.ok_or_else(|| anyhow!("path not found: {}", path_name))?;
+    let mut cur = Some(r.head_node);
+    let mut out: Vec<Value> = Vec::new();
+    let mut n = 0usize;
+    while let Some(fname) = cur {
+        if n >= limit {
+            break;
+        }
+        let node = load_node(&fname)?;
+        let meta = node
+            .get("meta")
+            .cloned()
+            .unwrap_or(Value::Object(serde_json::Map::new()));
+        let prov_count = meta
+            .get("provenance")
+            .and_then(|p| p.get("sources"))
+            .and_then(|s| s.as_array())
+            .map(|a| a.len())
+            .unwrap_or(0);
+        let item = serde_json::json!({
+            "filename": fname,
+            "id": node.get("id").and_then(|x| x.as_str()).unwrap_or_default(),
+            "hash": node.get("hash").and_then(|x| x.as_str()).unwrap_or_default(),
+            "ts": node.get("ts").and_then(|x| x.as_str()).unwrap_or_default(),
+            "lobe": node.get("lobe").and_then(|x| x.as_str()).unwrap_or_default(),
+            "key": node.get("key").and_then(|x| x.as_str()).unwrap_or_default(),
+            "provenance_sources": prov_count,
+        });
+        out.push(item);
+        // Choose the primary parent if multiple; prefer the first entry in `parents`.
+        let next_parent: Option<String> = {
+            let parents = node_parents_list(&node);
+            if let Some(p) = parents.first() {
+                if p.ends_with(".json") {
+                    Some(p.clone())
+                } else if let Some(idx) = read_hash_index(p)? {
+                    Some(idx.node)
+                } else if let Some(fname) = resolve_parent_filename(p)? {
+                    // legacy fallback: resolve bare hash to filename
+                    Some(fname)
+                } else {
+                    None
                 }
+            } else {
+                None
             }
+        };
+        cur = next_parent;
+        n += 1;
+    }
+    Ok(out)
+}
+
+/// Keep only the newest `keep_last_per_stream` nodes per (lobe,key).
+///
+/// Only prunes nodes still living as loose files under `dag/nodes` — a node
+/// that has already been folded into `dag/nodes.pack` has no per-file
+/// footprint to reclaim here, since the pack is append-only and reclaiming
+/// space from it requires compaction, which is out of scope for this pass.
+pub fn prune(keep_last_per_stream: usize) -> Result<PruneReport> {
+    let dir = dag_nodes_dir()?;
+    let mut by_stream: std::collections::BTreeMap<(String, String), Vec<(String, String, String)>> =
+        Default::default();
+    // collect: (lobe,key) -> [(ts, filename, hash)]
+    for e in fs::read_dir(&dir)? {
+        let path = e?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
         }
-        found.ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_id))?
-    };
+        let bytes = fs::read(&path)?;
+        let v: Value = match serde_json::from_slice(&bytes) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let lobe = v
+            .get("lobe")
+            .and_then(|x| x.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let key = v
+            .get("key")
+            .and_then(|x| x.as_str())
+            .unwrap_or("default")
+            .to_string();
+        let ts = v
+            .get("ts")
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string();
+        let hash = v
+            .get("hash")
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+        by_stream.entry((lobe, key)).or_default().push((ts, name, hash));
+    }
 
-    let v = load_node(&node_filename)?;
-    let content = v
-        .get("content")
-        .and_then(|x| x.as_str())
-        .unwrap_or_default()
-        .to_string();
-    // Merge top-level lobe/key/ts/id/hash with nested meta for convenient replay
-    let mut meta_map = serde_json::Map::new();
-    if let Some(m) = v.get("meta").and_then(|m| m.as_object()) {
-        for (k, vv) in m.iter() {
-            meta_map.insert(k.clone(), vv.clone());
+    let mut examined = 0usize;
+    let mut removed = 0usize;
+
+    for ((_lobe, _key), mut nodes) in by_stream {
+        // newest first by timestamp string (RFC3339 sorts fine lexicographically if we replaced ':' above)
+        nodes.sort_by(|a, b| b.0.cmp(&a.0));
+        examined += nodes.len();
+        if nodes.len() > keep_last_per_stream {
+            for (_ts, name, hash) in nodes.into_iter().skip(keep_last_per_stream) {
+                if !hash.is_empty() {
+                    let _ = remove_hashnode(&hash, &name);
+                }
+                let p = dir.join(name);
+                let _ = fs::remove_file(p);
+                removed += 1;
+            }
         }
     }
-    for k in ["lobe", "key", "ts", "id", "hash"] {
-        if let Some(val) = v.get(k) {
-            meta_map.insert(k.to_string(), val.clone());
+
+    // Any chunk not referenced by a still-retained node is dead weight;
+    // walk whatever's left (pack-indexed and legacy alike) to build the
+    // referenced set, then let gc_chunks reclaim the rest.
+    let mut referenced: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for fname in enumerate_all_node_filenames()? {
+        if let Ok(v) = load_node(&fname) {
+            if let Some(chunks) = v.get("chunks").and_then(|x| x.as_array()) {
+                for c in chunks {
+                    if let Some(h) = c.as_str() {
+                        referenced.insert(h.to_string());
+                    }
+                }
+            }
         }
     }
-    Ok(MemoryState {
-        content,
-        meta: Value::Object(meta_map),
+    let chunks_removed = blob::gc_chunks(&referenced)?;
+
+    Ok(PruneReport {
+        examined,
+        kept: examined.saturating_sub(removed),
+        removed,
+        chunks_removed,
     })
 }
 
-/// Create or reset a named path to diverge from a specific snapshot.
-/// Returns the `path_id` (sanitized name).
-pub fn diverge_from(snapshot_id: &str, path_name: &str) -> Result<String> {
-    // Resolve snapshot to node filename (use index; fallback to scan like recall)
-    let node_filename = if let Some(idx) = read_hash_index(snapshot_id)? {
-        idx.node
-    } else {
-        // Fallback: linear scan for robustness if index is missing
-        let dir = dag_nodes_dir()?;
-        let mut found: Option<String> = None;
-        for e in fs::read_dir(&dir)? {
-            let path = e?.path();
-            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+/// Within each (lobe,key) stream, cluster snapshots whose SimHash
+/// fingerprints (`src/memory/simhash.rs`) are all pairwise within
+/// `threshold` Hamming bits of one another, keep the newest node per
+/// cluster, fold the rest's `meta.provenance.sources` into it via
+/// [`bind_provenance`], and remove them. Complements `prune`'s newest-N
+/// rule for snapshots that are semantically redundant rather than
+/// byte-identical. Operates over the same legacy-directory node set
+/// `prune` does -- anything already migrated into `dag/nodes.pack` isn't
+/// independently removable yet.
+pub fn prune_near_duplicates(threshold: u32) -> Result<PruneReport> {
+    let dir = dag_nodes_dir()?;
+    let mut by_stream: std::collections::BTreeMap<(String, String), Vec<String>> =
+        Default::default();
+    for e in fs::read_dir(&dir)? {
+        let path = e?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let bytes = fs::read(&path)?;
+        let v: Value = match serde_json::from_slice(&bytes) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let lobe = v
+            .get("lobe")
+            .and_then(|x| x.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let key = v
+            .get("key")
+            .and_then(|x| x.as_str())
+            .unwrap_or("default")
+            .to_string();
+        by_stream.entry((lobe, key)).or_default().push(name.to_string());
+    }
+
+    let mut examined = 0usize;
+    let mut removed = 0usize;
+
+    for ((lobe, key), node_names) in by_stream {
+        let fp_by_node: std::collections::HashMap<String, u64> = simhash::stream_fingerprints(&lobe, &key)?
+            .into_iter()
+            .map(|e| (e.node, e.fingerprint))
+            .collect();
+
+        // (ts, node filename, fingerprint) for every node in this stream
+        // that has a recorded fingerprint.
+        let mut nodes: Vec<(String, String, u64)> = Vec::new();
+        for name in &node_names {
+            let Some(&fp) = fp_by_node.get(name) else {
                 continue;
+            };
+            let v = load_node(name)?;
+            let ts = v.get("ts").and_then(|x| x.as_str()).unwrap_or("").to_string();
+            nodes.push((ts, name.clone(), fp));
+        }
+        examined += nodes.len();
+
+        // Union-find over pairwise Hamming distance < threshold: a cluster
+        // is the transitive closure of "close enough", not just direct
+        // pairs, matching the request's "cluster of snapshots whose
+        // pairwise Hamming distance is under threshold" phrasing.
+        let n = nodes.len();
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn find(parent: &mut [usize], x: usize) -> usize {
+            if parent[x] != x {
+                parent[x] = find(parent, parent[x]);
             }
-            let bytes = fs::read(&path)?;
-            if let Ok(v) = serde_json::from_slice::<Value>(&bytes) {
-                if v.get("hash").and_then(|x| x.as_str()) == Some(snapshot_id) {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        found = Some(name.to_string());
-                        break;
+            parent[x]
+        }
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if simhash::distance(nodes[i].2, nodes[j].2) < threshold {
+                    let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                    if ri != rj {
+                        parent[ri] = rj;
                     }
                 }
             }
         }
-        found.ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_id))?
-    };
-    let now = chrono::Utc::now().to_rfc3339();
-    let r = PathRef {
-        name: path_name.to_string(),
-        base_snapshot: snapshot_id.to_string(),
-        base_node: node_filename.clone(),
-        head_node: node_filename,
-        created_at: now.clone(),
-        updated_at: now,
-    };
-    write_path_ref(path_name, &r)?;
-    Ok(path_id_from_name(path_name))
-}
 
-/// Append a new immutable snapshot to a named path and advance its head.
-/// Returns the new content-addressed snapshot id (blake3 hex).
-pub fn extend_path(path_name: &str, state: MemoryState) -> Result<String> {
-    let mut r =
-        read_path_ref(path_name)?.ok_or_else(|| anyhow!("path not found: {}", path_name))?;
+        let mut clusters: std::collections::HashMap<usize, Vec<usize>> = Default::default();
+        for i in 0..n {
+            let r = find(&mut parent, i);
+            clusters.entry(r).or_default().push(i);
+        }
 
-    // Ensure meta has sensible lobe/key for replay isolation
-    let mut meta = match state.meta {
-        Value::Object(m) => Value::Object(m),
-        _ => Value::Object(serde_json::Map::new()),
-    };
-    if meta.get("lobe").is_none() {
-        meta.as_object_mut()
-            .unwrap()
-            .insert("lobe".into(), Value::String("replay".into()));
+        for members in clusters.into_values() {
+            if members.len() <= 1 {
+                continue;
+            }
+            let mut members = members;
+            // newest first by ts (RFC3339 sorts lexicographically)
+            members.sort_by(|&a, &b| nodes[b].0.cmp(&nodes[a].0));
+            let survivor_name = nodes[members[0]].1.clone();
+            let mut survivor = load_node(&survivor_name)?;
+            let mut survivor_meta = survivor
+                .get("meta")
+                .and_then(|m| m.as_object())
+                .cloned()
+                .unwrap_or_default();
+
+            for &idx in &members[1..] {
+                let dup_name = &nodes[idx].1;
+                let dup = load_node(dup_name)?;
+                if let Some(dup_prov) = dup.get("meta").and_then(|m| m.get("provenance")) {
+                    bind_provenance(&mut survivor_meta, dup_prov);
+                }
+                if let Some(dup_hash) = dup.get("hash").and_then(|x| x.as_str()) {
+                    let _ = remove_hashnode(dup_hash, dup_name);
+                }
+                let _ = fs::remove_file(dir.join(dup_name));
+                removed += 1;
+            }
+
+            if let Some(obj) = survivor.as_object_mut() {
+                obj.insert("meta".to_string(), Value::Object(survivor_meta));
+            }
+            write_atomic(&dir.join(&survivor_name), &serde_json::to_vec_pretty(&survivor)?)?;
+        }
     }
-    let key_default = path_id_from_name(path_name);
-    if meta.get("key").is_none() {
-        meta.as_object_mut()
-            .unwrap()
-            .insert("key".into(), Value::String(key_default.clone()));
+
+    let mut referenced: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for fname in enumerate_all_node_filenames()? {
+        if let Ok(v) = load_node(&fname) {
+            if let Some(chunks) = v.get("chunks").and_then(|x| x.as_array()) {
+                for c in chunks {
+                    if let Some(h) = c.as_str() {
+                        referenced.insert(h.to_string());
+                    }
+                }
+            }
+        }
     }
+    let chunks_removed = blob::gc_chunks(&referenced)?;
 
-    // Timestamps
-    let now = chrono::Utc::now().to_rfc3339();
-    if meta.get("created_at").is_none() {
-        meta.as_object_mut()
-            .unwrap()
-            .insert("created_at".into(), Value::String(now.clone()));
+    Ok(PruneReport {
+        examined,
+        kept: examined.saturating_sub(removed),
+        removed,
+        chunks_removed,
+    })
+}
+
+// ---------- Merge helpers ----------
+
+/// Resolve a content hash to its node's parent hashes, via the nodemap
+/// (falling back to a scan through [`resolve_parent_filename`]) rather than
+/// by walking `node_parents_list` entries that may themselves be raw hashes
+/// or filenames -- `parent_filenames_from_node` already normalizes that.
+pub(crate) fn parent_hashes_of(hash: &str) -> Result<Vec<String>> {
+    let Some(fname) = resolve_parent_filename(hash)? else {
+        return Ok(Vec::new());
+    };
+    let node = load_node(&fname)?;
+    let mut out = Vec::new();
+    for pf in parent_filenames_from_node(&node) {
+        if let Ok(pnode) = load_node(&pf) {
+            if let Some(ph) = pnode.get("hash").and_then(|x| x.as_str()) {
+                out.push(ph.to_string());
+            }
+        }
     }
-    meta.as_object_mut()
-        .unwrap()
-        .insert("updated_at".into(), Value::String(now.clone()));
+    Ok(out)
+}
 
-    // Content-addressed id
-    let new_hash = blake3::hash(state.content.as_bytes()).to_hex().to_string();
-    meta.as_object_mut()
-        .unwrap()
-        .insert("cid".into(), Value::String(new_hash.clone()));
+const GCA_BIT_A: u8 = 0b01;
+const GCA_BIT_B: u8 = 0b10;
 
-    // Write new node, explicitly parented to current head
-    let _node_file = save_node(&new_hash, &state.content, &meta, &[r.head_node.clone()])?;
+/// Generation-ordered queue entry for [`bind_base`]'s GCA walk. `BinaryHeap`
+/// is a max-heap, so popping always yields the highest remaining
+/// generation -- the frontier advances from the tips downward, one
+/// generation level at a time, exactly like Mercurial's `ancestor.gca`.
+#[derive(Eq, PartialEq)]
+struct GcaQueueItem {
+    generation: u64,
+    hash: String,
+}
 
-    // Update path head and write back
-    let latest_idx =
-        read_hash_index(&new_hash)?.ok_or_else(|| anyhow!("hash index missing for new node"))?;
-    r.head_node = latest_idx.node;
-    r.updated_at = now;
-    write_path_ref(path_name, &r)?;
+impl Ord for GcaQueueItem {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.generation
+            .cmp(&other.generation)
+            .then_with(|| self.hash.cmp(&other.hash))
+    }
+}
 
-    Ok(new_hash)
+impl PartialOrd for GcaQueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-// ---------- Public helpers for paths (heads, base, ancestry) ----------
+/// Compute the greatest common ancestor(s) (bind base) between two snapshot
+/// hashes. A single priority-queue walk seeded with {a_hash: BIT_A, b_hash:
+/// BIT_B} pops the highest-generation node first and unions its
+/// "reached-from" bitmask into its parents; the first node(s) whose
+/// accumulated mask contains both bits are the nearest common ancestors. A
+/// node found this way is not expanded further, so its own ancestors (which
+/// would also carry both bits, just less minimally) are never reported --
+/// this is what keeps the result to the *nearest* ancestor(s) instead of
+/// the whole shared history. More than one entry in the returned `Vec`
+/// means a criss-cross merge: two incomparable greatest common ancestors.
+pub fn bind_base(a_hash: &str, b_hash: &str) -> Result<Vec<String>> {
+    ensure_nodemap_fresh_once();
+    if a_hash == b_hash {
+        return Ok(vec![a_hash.to_string()]);
+    }
+
+    let mut masks: std::collections::HashMap<String, u8> = std::collections::HashMap::new();
+    let mut heap: std::collections::BinaryHeap<GcaQueueItem> = std::collections::BinaryHeap::new();
+
+    for (hash, bit) in [(a_hash, GCA_BIT_A), (b_hash, GCA_BIT_B)] {
+        masks.insert(hash.to_string(), bit);
+        let generation = read_generation(hash)?.unwrap_or(0);
+        heap.push(GcaQueueItem {
+            generation,
+            hash: hash.to_string(),
+        });
+    }
 
-/// Return true if a path ref exists.
-pub fn path_exists(path_name: &str) -> Result<bool> {
-    Ok(read_path_ref(path_name)?.is_some())
-}
+    let mut gcas: Vec<String> = Vec::new();
+    let mut emitted: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut min_gca_generation: Option<u64> = None;
 
-/// Return the current head snapshot hash for a named path, if any.
-pub fn path_head_hash(path_name: &str) -> Result<Option<String>> {
-    if let Some(r) = read_path_ref(path_name)? {
-        if r.head_node.is_empty() {
-            return Ok(None);
+    while let Some(GcaQueueItem { generation, hash }) = heap.pop() {
+        // The frontier only shrinks from here: once it falls below every
+        // GCA found so far, whatever's left can only be one of their
+        // (non-minimal) ancestors.
+        if let Some(min_gen) = min_gca_generation {
+            if generation < min_gen {
+                break;
+            }
         }
-        let v = load_node(&r.head_node)?;
-        let h = v
-            .get("hash")
-            .and_then(|x| x.as_str())
-            .unwrap_or("")
-            .to_string();
-        if h.is_empty() { Ok(None) } else { Ok(Some(h)) }
-    } else {
-        Ok(None)
-    }
-}
 
-/// Return the base snapshot hash recorded for a named path, if present.
-pub fn path_base_snapshot(path_name: &str) -> Result<Option<String>> {
-    if let Some(r) = read_path_ref(path_name)? {
-        if r.base_snapshot.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(r.base_snapshot))
+        let mask = *masks.get(&hash).unwrap_or(&0);
+        if mask == (GCA_BIT_A | GCA_BIT_B) {
+            // A node with more than one child below the true GCA gets
+            // pushed onto `heap` once per child-propagation event, so a
+            // second (stale) pop of the same hash can land here after it's
+            // already been reported -- that's an ordinary branch point, not
+            // a criss-cross merge, and must not be double-counted.
+            if emitted.insert(hash.clone()) {
+                gcas.push(hash);
+                min_gca_generation =
+                    Some(min_gca_generation.map_or(generation, |g| g.min(generation)));
+            }
+            // Don't expand past a found GCA -- its parents would only ever
+            // surface strictly-older, non-minimal common ancestors.
+            continue;
+        }
+
+        for ph in parent_hashes_of(&hash)? {
+            let prior = masks.get(&ph).copied().unwrap_or(0);
+            let merged = prior | mask;
+            if merged == prior {
+                continue; // already fully propagated, no need to requeue
+            }
+            masks.insert(ph.clone(), merged);
+            let parent_generation = read_generation(&ph)?.unwrap_or(0);
+            heap.push(GcaQueueItem {
+                generation: parent_generation,
+                hash: ph,
+            });
         }
-    } else {
-        Ok(None)
     }
+
+    Ok(gcas)
 }
 
-/// Update a path's head to point at an existing snapshot by its content hash.
-/// Fails if the hash is unknown.
-pub fn set_path_head(path_name: &str, snapshot_hash: &str) -> Result<()> {
-    let idx = read_hash_index(snapshot_hash)?
-        .ok_or_else(|| anyhow!("snapshot not found: {}", snapshot_hash))?;
-    let now = chrono::Utc::now().to_rfc3339();
-    let r = if let Some(mut existing) = read_path_ref(path_name)? {
-        existing.head_node = idx.node;
-        existing.updated_at = now.clone();
-        existing
+// ---------- three-way merge of divergent paths ----------
+
+/// BFS backward from `head_hash` over `node_parents_list`, recording the
+/// shortest distance (in node hops) from the head to every ancestor hash
+/// reachable from it, including the head itself at distance 0.
+fn ancestor_distances(head_hash: &str) -> Result<std::collections::HashMap<String, usize>> {
+    let mut dist: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut queue: std::collections::VecDeque<(String, usize)> = std::collections::VecDeque::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    let start = if let Some(idx) = read_hash_index(head_hash)? {
+        Some(idx.node)
     } else {
-        // Create a new path ref seeded at this snapshot
-        PathRef {
-            name: path_name.to_string(),
-            base_snapshot: snapshot_hash.to_string(),
-            base_node: idx.node.clone(),
-            head_node: idx.node,
-            created_at: now.clone(),
-            updated_at: now,
+        let mut found = None;
+        for name in enumerate_all_node_filenames()? {
+            if let Ok(v) = load_node(&name) {
+                if v.get("hash").and_then(|x| x.as_str()) == Some(head_hash) {
+                    found = Some(name);
+                    break;
+                }
+            }
         }
+        found
     };
-    write_path_ref(path_name, &r)
-}
-
-/// Return true if `ancestor_hash` is on the ancestor chain of `descendant_hash` (or equal).
-pub fn is_ancestor(ancestor_hash: &str, descendant_hash: &str) -> Result<bool> {
-    if ancestor_hash == descendant_hash {
-        return Ok(true);
-    }
-    let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
-    if let Some(idx) = read_hash_index(descendant_hash)? {
-        queue.push_back(idx.node);
+    if let Some(fname) = start {
+        queue.push_back((fname, 0));
     }
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-    while let Some(fname) = queue.pop_front() {
+
+    while let Some((fname, d)) = queue.pop_front() {
         if !seen.insert(fname.clone()) {
             continue;
         }
-        let node = load_node(&fname)?;
-        if node.get("hash").and_then(|x| x.as_str()) == Some(ancestor_hash) {
-            return Ok(true);
+        let node = match load_node(&fname) {
+            Ok(n) => n,
+            Err(_) => continue,
+        };
+        if let Some(h) = node.get("hash").and_then(|x| x.as_str()) {
+            dist.entry(h.to_string()).or_insert(d);
         }
         for p in node_parents_list(&node) {
             if p.is_empty() {
                 continue;
             }
-            if p.ends_with(".json") {
-                queue.push_back(p);
+            let next = if p.ends_with(".json") {
+                Some(p)
             } else if let Some(idx) = read_hash_index(&p)? {
-                queue.push_back(idx.node);
+                Some(idx.node)
             } else {
-                // Attempt fallback resolution: scan for a node whose internal hash matches `p`.
-                if let Some(fname) = resolve_parent_filename(&p).ok().flatten() {
-                    queue.push_back(fname);
-                } else {
-                    // Retain raw hash only if resolution failed; later iterations cannot load it directly
-                    // but this preserves prior behavior for completeness.
-                    queue.push_back(p);
-                }
+                resolve_parent_filename(&p).ok().flatten()
+            };
+            if let Some(nf) = next {
+                queue.push_back((nf, d + 1));
             }
         }
     }
-    Ok(false)
+    Ok(dist)
 }
 
-/// Return the child (next) nodes of a given node *within the same stream* by scanning.
-// MVP: linear scan; fine for small graphs.
-pub fn children_of(filename: &str) -> Result<Vec<String>> {
-    let dir = dag_nodes_dir()?;
-    let mut kids = Vec::new();
-    for e in fs::read_dir(&dir)? {
-        let path = e?.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
-        }
-        let bytes = fs::read(&path)?;
-        if let Ok(v) = serde_json::from_slice::<Value>(&bytes) {
-            let parents = parent_filenames_from_node(&v);
-            if parents.iter().any(|p| p == filename) {
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    kids.push(name.to_string());
-                }
+/// Lowest common ancestor of two snapshot hashes, chosen as the one
+/// minimizing `dist_a + dist_b` among hashes reachable from both heads.
+/// Falls back to `fallback` (typically a path's recorded `base_snapshot`)
+/// if the two ancestor sets are disjoint.
+pub fn lowest_common_ancestor(
+    a_hash: &str,
+    b_hash: &str,
+    fallback: Option<&str>,
+) -> Result<Option<String>> {
+    if a_hash == b_hash {
+        return Ok(Some(a_hash.to_string()));
+    }
+    let dist_a = ancestor_distances(a_hash)?;
+    let dist_b = ancestor_distances(b_hash)?;
+    let mut best: Option<(String, usize)> = None;
+    for (hash, &da) in &dist_a {
+        if let Some(&db) = dist_b.get(hash) {
+            let total = da + db;
+            if best.as_ref().map(|(_, bt)| total < *bt).unwrap_or(true) {
+                best = Some((hash.clone(), total));
             }
         }
     }
-    Ok(kids)
+    if let Some((hash, _)) = best {
+        return Ok(Some(hash));
+    }
+    Ok(fallback.map(|s| s.to_string()))
 }
 
-// ---------- tiny DAG pruner (MVP) ----------
+// ---------- Structured diff between two snapshots ----------
 
-#[derive(Debug, Clone)]
-pub struct PruneReport {
-    pub examined: usize,
-    pub kept: usize,
-    pub removed: usize,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ChangeKind {
+    Add,
+    Mod,
+    Del,
 }
 
-// ---------- Snapshot metadata, citations, and path tracing ----------
-
-/// Return the `meta` object for a snapshot by its content hash id.
-///
-/// Multiple DAG nodes can share the same content hash (e.g. different lobes
-/// remembering identical content). Rather than relying on a single hash index
-/// entry, scan for all matching nodes and bind their metadata, prioritising
-/// the indexed node when available.
-pub fn snapshot_meta(snapshot_id: &str) -> Result<Value> {
-    let mut metas: Vec<serde_json::Map<String, Value>> = Vec::new();
-    let mut seen_files: std::collections::HashSet<String> = std::collections::HashSet::new();
+/// One (lobe,key) stream's change between two snapshots, as found by
+/// [`diff_snapshots`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChangeEntry {
+    pub lobe: String,
+    pub key: String,
+    pub kind: ChangeKind,
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+    pub provenance_sources: Vec<Value>,
+}
 
-    if let Some(idx) = read_hash_index(snapshot_id)? {
-        let node = load_node(&idx.node)?;
-        if let Some(meta_obj) = node.get("meta").and_then(|m| m.as_object()) {
-            metas.push(meta_obj.clone());
-        } else {
-            metas.push(serde_json::Map::new());
-        }
-        seen_files.insert(idx.node);
-    }
+/// Walk backward from `start` over [`parent_hashes_of`], recording the
+/// first (i.e. nearest to `start`) hash seen for each (lobe,key) stream.
+/// Expansion stops at (but still records) any hash in `stop_at` -- callers
+/// pass the GCA set so the walk only covers history since divergence,
+/// or an empty set to walk all the way back to the roots.
+fn collect_stream_heads(
+    start: &str,
+    stop_at: &std::collections::HashSet<String>,
+) -> Result<HashMap<(String, String), String>> {
+    let mut out: HashMap<(String, String), String> = HashMap::new();
+    let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    queue.push_back(start.to_string());
 
-    let dir = dag_nodes_dir()?;
-    for entry in fs::read_dir(&dir)? {
-        let path = entry?.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+    while let Some(h) = queue.pop_front() {
+        if !visited.insert(h.clone()) {
             continue;
         }
-        let name = match path.file_name().and_then(|n| n.to_str()) {
-            Some(n) => n.to_string(),
-            None => continue,
-        };
-        if seen_files.contains(&name) {
-            continue;
+        if let Ok(meta) = snapshot_meta(&h) {
+            let lobe = meta
+                .get("lobe")
+                .and_then(|x| x.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let key = meta
+                .get("key")
+                .and_then(|x| x.as_str())
+                .unwrap_or("default")
+                .to_string();
+            out.entry((lobe, key)).or_insert_with(|| h.clone());
         }
-        let bytes = fs::read(&path)?;
-        let node: Value = serde_json::from_slice(&bytes)?;
-        if node.get("hash").and_then(|x| x.as_str()) != Some(snapshot_id) {
+        if stop_at.contains(&h) {
             continue;
         }
-        if let Some(meta_obj) = node.get("meta").and_then(|m| m.as_object()) {
-            metas.push(meta_obj.clone());
-        } else {
-            metas.push(serde_json::Map::new());
+        for parent in parent_hashes_of(&h)? {
+            queue.push_back(parent);
         }
-        seen_files.insert(name);
     }
+    Ok(out)
+}
 
-    if metas.is_empty() {
-        return Err(anyhow!("snapshot not found: {}", snapshot_id));
+/// Structured diff between two snapshot hashes: resolves their greatest
+/// common ancestor via [`bind_base`], then for every (lobe,key) stream
+/// touched on either side since that ancestor, classifies the change as
+/// `Add` (new stream), `Del` (stream dropped), or `Mod` (stream's content
+/// hash differs) -- the same three-way model as a backup diff. Each entry
+/// carries both sides' content hashes and a merged, de-duplicated view of
+/// `provenance.sources` from whichever side(s) have a value, so a caller
+/// can tell where each changed fact came from -- the groundwork for a
+/// provenance-aware three-way merge, the way `bind_meta_maps` already
+/// merges metadata.
+pub fn diff_snapshots(a_hash: &str, b_hash: &str) -> Result<Vec<ChangeEntry>> {
+    let gcas = bind_base(a_hash, b_hash)?;
+    let gca_set: std::collections::HashSet<String> = gcas.iter().cloned().collect();
+
+    let a_streams = collect_stream_heads(a_hash, &gca_set)?;
+    let b_streams = collect_stream_heads(b_hash, &gca_set)?;
+
+    let empty: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut base_streams: HashMap<(String, String), String> = HashMap::new();
+    for gca in &gcas {
+        for (k, v) in collect_stream_heads(gca, &empty)? {
+            base_streams.entry(k).or_insert(v);
+        }
     }
 
-    let mut binding = metas.remove(0);
-    for meta in metas.iter() {
-        bind_meta_maps(&mut binding, meta);
+    let mut keys: std::collections::BTreeSet<(String, String)> = std::collections::BTreeSet::new();
+    keys.extend(a_streams.keys().cloned());
+    keys.extend(b_streams.keys().cloned());
+    keys.extend(base_streams.keys().cloned());
+
+    let mut out = Vec::new();
+    for (lobe, key) in keys {
+        let effective_a = a_streams
+            .get(&(lobe.clone(), key.clone()))
+            .or_else(|| base_streams.get(&(lobe.clone(), key.clone())))
+            .cloned();
+        let effective_b = b_streams
+            .get(&(lobe.clone(), key.clone()))
+            .or_else(|| base_streams.get(&(lobe.clone(), key.clone())))
+            .cloned();
+
+        if effective_a == effective_b {
+            continue;
+        }
+
+        let kind = match (&effective_a, &effective_b) {
+            (None, Some(_)) => ChangeKind::Add,
+            (Some(_), None) => ChangeKind::Del,
+            _ => ChangeKind::Mod,
+        };
+
+        let mut sources: Vec<Value> = Vec::new();
+        let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+        for h in [&effective_a, &effective_b].into_iter().flatten() {
+            if let Ok(cited) = cite_sources(h) {
+                for src in cited {
+                    if seen.insert(provenance_source_key(&src)) {
+                        sources.push(src);
+                    }
+                }
+            }
+        }
+
+        out.push(ChangeEntry {
+            lobe,
+            key,
+            kind,
+            old_hash: effective_a,
+            new_hash: effective_b,
+            provenance_sources: sources,
+        });
     }
 
-    Ok(Value::Object(binding))
+    Ok(out)
 }
 
-fn bind_meta_maps(
-    base: &mut serde_json::Map<String, Value>,
-    incoming: &serde_json::Map<String, Value>,
-) {
-    for (k, v) in incoming {
-        if k == "provenance" {
-            bind_provenance(base, v);
-            continue;
+// ---------- Graphviz/DOT export ----------
+
+/// DOT graph flavor a walk can be rendered into. `trace_path`/`cite_sources`
+/// only hand back JSON, which is awkward for eyeballing branch/diverge
+/// topology -- a directed graph reads naturally as "parent -> child"
+/// snapshot history. Kept as its own type (rather than hard-coding "digraph"
+/// and "->" into the renderer) so an undirected view is a second variant and
+/// a different edge token, not a second walker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    Digraph,
+}
+
+impl GraphKind {
+    fn keyword(self) -> &'static str {
+        match self {
+            GraphKind::Digraph => "digraph",
         }
-        let should_set = !base.contains_key(k)
-            || base
-                .get(k)
-                .map(|existing| existing.is_null())
-                .unwrap_or(false);
-        if should_set {
-            base.insert(k.clone(), v.clone());
+    }
+
+    fn edge_op(self) -> &'static str {
+        match self {
+            GraphKind::Digraph => "->",
         }
     }
 }
 
-fn bind_provenance(base: &mut serde_json::Map<String, Value>, incoming: &Value) {
-    let incoming_obj = match incoming.as_object() {
-        Some(map) => map,
-        None => return,
+/// One snapshot node ready to render into a DOT graph. `color` is left for
+/// the caller to fill in: this module has no notion of hot/archive/dag
+/// tiering (that's `Commands::recall_any`'s business), only the DAG shape.
+#[derive(Debug, Clone)]
+pub struct DotNode {
+    pub hash: String,
+    /// The node's memory id (`save_node`'s `id` argument) -- what
+    /// `Commands`' hot/archive stores key on, not the content hash.
+    pub id: String,
+    pub parent_hashes: Vec<String>,
+    pub label: String,
+    pub color: Option<String>,
+    pub is_divergence: bool,
+    /// Set on the node a walk started from: a named path's current head, or
+    /// the explicit `root_snapshot` requested. Rendered with extra
+    /// `peripheries` so it stands out from ordinary history at a glance.
+    pub is_head: bool,
+}
+
+/// Walk backward from a path's head (or an explicit snapshot hash), newest
+/// first, following every recorded parent link (not just the primary one
+/// `trace_path` follows) up to `limit` nodes -- a DOT export wants the full
+/// branch shape, including merge/diverge edges `trace_path` deliberately
+/// collapses. `path_name` wins if both are given.
+pub fn walk_for_dot(
+    path_name: Option<&str>,
+    root_snapshot: Option<&str>,
+    limit: usize,
+) -> Result<Vec<DotNode>> {
+    let start_filename = if let Some(p) = path_name {
+        let r = read_path_ref(p)?.ok_or_else(|| anyhow!("path not found: {}", p))?;
+        r.head_node
+    } else if let Some(snap) = root_snapshot {
+        let resolved = resolve_full_hash(snap)?;
+        let idx = read_hash_index(&resolved)?;
+        match idx {
+            Some(idx) => idx.node,
+            None => resolve_parent_filename(&resolved)?
+                .ok_or_else(|| anyhow!("snapshot not found: {}", resolved))?,
+        }
+    } else {
+        return Err(anyhow!(
+            "dag_export_dot requires a path_name or root_snapshot"
+        ));
     };
 
-    let prov_entry = base
-        .entry("provenance".to_string())
-        .or_insert_with(|| Value::Object(serde_json::Map::new()));
-    if !prov_entry.is_object() {
-        *prov_entry = Value::Object(serde_json::Map::new());
+    let mut out = Vec::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    queue.push_back(start_filename);
+
+    while let Some(filename) = queue.pop_front() {
+        if out.len() >= limit || !seen.insert(filename.clone()) {
+            continue;
+        }
+        let node = match load_node(&filename) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let hash = node
+            .get("hash")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let id = node
+            .get("id")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let parent_filenames = parent_filenames_from_node(&node);
+        let parent_hashes: Vec<String> = parent_filenames
+            .iter()
+            .filter_map(|f| load_node(f).ok())
+            .filter_map(|p| p.get("hash").and_then(|x| x.as_str()).map(String::from))
+            .collect();
+
+        let op = node
+            .get("meta")
+            .and_then(|m| m.get("op"))
+            .and_then(|x| x.as_str());
+        let ts = node.get("ts").and_then(|x| x.as_str()).unwrap_or("");
+        let content_preview = node_content(&node).unwrap_or_default();
+        let label_body = op.unwrap_or_else(|| content_preview.trim());
+        let short_hash = hash.get(0..12).unwrap_or(&hash);
+        let label = format!("{} {} {}", short_hash, ts, truncate_label(label_body, 40));
+
+        let is_divergence = children_of(&filename).map(|c| c.len() > 1).unwrap_or(false);
+        let is_head = out.is_empty();
+
+        out.push(DotNode {
+            hash,
+            id,
+            parent_hashes,
+            label,
+            color: None,
+            is_divergence,
+            is_head,
+        });
+
+        for parent_filename in parent_filenames {
+            queue.push_back(parent_filename);
+        }
     }
-    let prov_map = prov_entry.as_object_mut().expect("provenance object");
 
-    let mut bindd_sources: Vec<Value> = prov_map
-        .get("sources")
-        .and_then(|s| s.as_array())
-        .map(|arr| arr.clone())
-        .unwrap_or_else(Vec::new);
+    Ok(out)
+}
+
+/// Like [`walk_for_dot`], but over every node tagged with `lobe` instead of
+/// one path's ancestry -- a lobe can span several paths (or none at all,
+/// for loose `remember`/`append` writes never folded into a named path), so
+/// this scans every known node rather than following parent links from a
+/// single head. No node is a "head" here in the path sense, so `is_head` is
+/// always `false` and nodes are ordered newest-first by `ts`.
+pub fn walk_for_dot_lobe(lobe: &str, limit: usize) -> Result<Vec<DotNode>> {
+    let mut matches: Vec<(String, String, Value)> = Vec::new();
+    for filename in enumerate_all_node_filenames()? {
+        let node = match load_node(&filename) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if node.get("lobe").and_then(|x| x.as_str()) != Some(lobe) {
+            continue;
+        }
+        let ts = node.get("ts").and_then(|x| x.as_str()).unwrap_or("").to_string();
+        matches.push((filename, ts, node));
+    }
+    matches.sort_by(|a, b| b.1.cmp(&a.1));
+    matches.truncate(limit);
+
+    let known_filenames: std::collections::HashSet<String> =
+        matches.iter().map(|(f, _, _)| f.clone()).collect();
+
+    let mut out = Vec::with_capacity(matches.len());
+    for (filename, ts, node) in matches {
+        let hash = node
+            .get("hash")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let id = node
+            .get("id")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let parent_filenames = parent_filenames_from_node(&node);
+        let parent_hashes: Vec<String> = parent_filenames
+            .iter()
+            .filter(|f| known_filenames.contains(*f))
+            .filter_map(|f| load_node(f).ok())
+            .filter_map(|p| p.get("hash").and_then(|x| x.as_str()).map(String::from))
+            .collect();
 
-    let mut seen: std::collections::HashSet<String> =
-        bindd_sources.iter().map(provenance_source_key).collect();
+        let op = node
+            .get("meta")
+            .and_then(|m| m.get("op"))
+            .and_then(|x| x.as_str());
+        let content_preview = node_content(&node).unwrap_or_default();
+        let label_body = op.unwrap_or_else(|| content_preview.trim());
+        let short_hash = hash.get(0..12).unwrap_or(&hash);
+        let label = format!("{} {} {}", short_hash, ts, truncate_label(label_body, 40));
+        let is_divergence = children_of(&filename).map(|c| c.len() > 1).unwrap_or(false);
 
-    if let Some(new_sources) = incoming_obj.get("sources").and_then(|s| s.as_array()) {
-        for src in new_sources {
-            let key = provenance_source_key(src);
-            if seen.insert(key) {
-                bindd_sources.push(src.clone());
-            }
-        }
+        out.push(DotNode {
+            hash,
+            id,
+            parent_hashes,
+            label,
+            color: None,
+            is_divergence,
+            is_head: false,
+        });
     }
 
-    prov_map.insert("sources".to_string(), Value::Array(bindd_sources));
+    Ok(out)
+}
 
-    for (k, value) in incoming_obj {
-        if k == "sources" {
-            continue;
-        }
-        prov_map.entry(k.clone()).or_insert_with(|| value.clone());
+fn truncate_label(s: &str, max: usize) -> String {
+    let s = s.trim();
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max).collect();
+        format!("{}...", truncated)
     }
 }
 
-fn provenance_source_key(src: &Value) -> String {
-    serde_json::json!({
-        "kind": src.get("kind"),
-        "uri": src.get("uri"),
-        "cid": src.get("cid"),
-        "range": src.get("range"),
-    })
-    .to_string()
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', " ")
 }
 
-/// Flatten and return any provenance.sources listed in the snapshot meta; de-duplicates basic tuples.
-pub fn cite_sources(snapshot_id: &str) -> Result<Vec<Value>> {
-    let meta = snapshot_meta(snapshot_id)?;
-    let mut out: Vec<Value> = Vec::new();
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-    if let Some(arr) = meta
-        .get("provenance")
-        .and_then(|p| p.get("sources"))
-        .and_then(|s| s.as_array())
-    {
-        for s in arr {
-            let key = provenance_source_key(s);
-            if seen.insert(key) {
-                out.push(s.clone());
+/// Render a set of [`DotNode`]s (as produced by [`walk_for_dot`]) into a DOT
+/// source string, e.g. for piping into `dot -Tsvg`. Divergence points (a
+/// snapshot with more than one child) render as diamonds so branch points
+/// stand out from the usual linear history; the walk's head (`is_head`)
+/// gets a double border so the current tip is obvious even once the graph
+/// scrolls off-screen.
+pub fn render_dot(nodes: &[DotNode], kind: GraphKind) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{} memory_dag {{\n", kind.keyword()));
+    for n in nodes {
+        let shape = if n.is_divergence { "diamond" } else { "box" };
+        let peripheries = if n.is_head { 2 } else { 1 };
+        let fill = n.color.as_deref().unwrap_or("white");
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\", shape={}, peripheries={}, style=filled, fillcolor=\"{}\"];\n",
+            n.hash,
+            dot_escape(&n.label),
+            shape,
+            peripheries,
+            fill
+        ));
+    }
+    let known: std::collections::HashSet<&str> = nodes.iter().map(|n| n.hash.as_str()).collect();
+    for n in nodes {
+        for parent in &n.parent_hashes {
+            if known.contains(parent.as_str()) {
+                out.push_str(&format!(
+                    "  \"{}\" {} \"{}\";\n",
+                    parent,
+                    kind.edge_op(),
+                    n.hash
+                ));
             }
         }
     }
-    Ok(out)
+    out.push_str("}\n");
+    out
 }
 
-/// Trace a named path from head backwards following parent pointers; newest -> oldest up to `limit`.
-/// Returns a vector of lightweight objects with id/hash/ts/lobe/key and source counts.
-pub fn trace_path(path_name: &str, limit: usize) -> Result<Vec<Value>> {
+// ---------- Path archive export/import (backup/transfer) ----------
+
+/// Output/input layout for [`export_path_archive`]/[`import_archive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ArchiveWriterKind {
+    /// One file per snapshot, named `<blake3 hex>.json`, under a directory;
+    /// `manifest.json` sits alongside them.
+    Loose,
+    /// A single file: each snapshot's JSON payload appended back-to-back,
+    /// followed by the manifest JSON and an 8-byte little-endian trailer
+    /// giving the manifest's byte offset (so a reader can seek from EOF
+    /// without scanning the whole file).
+    Packed,
+}
+
+/// One exported snapshot: enough to verify and re-insert it without
+/// re-walking the DAG.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct ArchivedSnapshot {
+    hash: String,
+    content: String,
+    meta: Value,
+    /// Primary parent hash, if any -- archives only follow the primary
+    /// parent chain (matching `trace_path`'s existing walk), not the full
+    /// multi-parent DAG.
+    parent: Option<String>,
+}
+
+/// Manifest embedded in (or alongside) an archive: which path(s) it covers
+/// and every snapshot hash it contains, root-first (the order
+/// [`import_archive`] must insert in so each snapshot's parent already
+/// exists by the time it's inserted).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveManifest {
+    /// Exported path name -> its head snapshot hash at export time.
+    pub path_heads: std::collections::BTreeMap<String, String>,
+    /// Every snapshot hash covered, root (oldest) first.
+    pub snapshots: Vec<String>,
+}
+
+/// Walk `path_name`'s primary-parent chain back to its root, reconstructing
+/// each snapshot's content via `recall_snapshot` (which already replays any
+/// delta chain), root-first.
+fn collect_path_chain(path_name: &str) -> Result<Vec<ArchivedSnapshot>> {
     let r = read_path_ref(path_name)?.ok_or_else(|| anyhow!("path not found: {}", path_name))?;
+    let mut chain = Vec::new();
     let mut cur = Some(r.head_node);
-    let mut out: Vec<Value> = Vec::new();
-    let mut n = 0usize;
     while let Some(fname) = cur {
-        if n >= limit {
-            break;
-        }
         let node = load_node(&fname)?;
-        let meta = node
-            .get("meta")
-            .cloned()
-            .unwrap_or(Value::Object(serde_json::Map::new()));
-        let prov_count = meta
-            .get("provenance")
-            .and_then(|p| p.get("sources"))
-            .and_then(|s| s.as_array())
-            .map(|a| a.len())
-            .unwrap_or(0);
-        let item = serde_json::json!({
-            "filename": fname,
-            "id": node.get("id").and_then(|x| x.as_str()).unwrap_or_default(),
-            "hash": node.get("hash").and_then(|x| x.as_str()).unwrap_or_default(),
-            "ts": node.get("ts").and_then(|x| x.as_str()).unwrap_or_default(),
-            "lobe": node.get("lobe").and_then(|x| x.as_str()).unwrap_or_default(),
-            "key": node.get("key").and_then(|x| x.as_str()).unwrap_or_default(),
-            "provenance_sources": prov_count,
-        });
-        out.push(item);
-        // Choose the primary parent if multiple; prefer the first entry in `parents`.
+        let hash = node
+            .get("hash")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("node {} missing hash", fname))?
+            .to_string();
+        let content = node_content(&node)?;
+        let meta = node.get("meta").cloned().unwrap_or(Value::Object(serde_json::Map::new()));
         let next_parent: Option<String> = {
             let parents = node_parents_list(&node);
-            if let Some(p) = parents.first() {
-                if p.ends_with(".json") {
-                    Some(p.clone())
-                } else if let Some(idx) = read_hash_index(p)? {
-                    Some(idx.node)
-                } else if let Some(fname) = resolve_parent_filename(p)? {
-                    // legacy fallback: resolve bare hash to filename
-                    Some(fname)
-                } else {
-                    None
-                }
-            } else {
-                None
+            match parents.first() {
+                Some(p) if p.ends_with(".json") => Some(p.clone()),
+                Some(p) => read_hash_index(p)?.map(|idx| idx.node),
+                None => None,
             }
         };
+        let parent_hash = match &next_parent {
+            Some(pf) => load_node(pf).ok().and_then(|pn| {
+                pn.get("hash").and_then(|v| v.as_str()).map(|s| s.to_string())
+            }),
+            None => None,
+        };
+        chain.push(ArchivedSnapshot { hash, content, meta, parent: parent_hash });
         cur = next_parent;
-        n += 1;
     }
-    Ok(out)
+    chain.reverse(); // root-first
+    Ok(chain)
 }
 
-/// Keep only the newest `keep_last_per_stream` nodes per (lobe,key).
-pub fn prune(keep_last_per_stream: usize) -> Result<PruneReport> {
-    let dir = dag_nodes_dir()?;
-    let mut by_stream: std::collections::BTreeMap<(String, String), Vec<(String, String)>> =
-        Default::default();
-    // collect: (lobe,key) -> [(ts, filename)]
-    for e in fs::read_dir(&dir)? {
-        let path = e?.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
+/// Export `path_name` (and its reachable primary-parent ancestor chain) to
+/// `dest` in the given layout. Returns the manifest that was written.
+pub fn export_path_archive(
+    path_name: &str,
+    dest: &Path,
+    kind: ArchiveWriterKind,
+) -> Result<ArchiveManifest> {
+    let chain = collect_path_chain(path_name)?;
+    let head = chain
+        .last()
+        .map(|s| s.hash.clone())
+        .ok_or_else(|| anyhow!("path '{}' has no snapshots to export", path_name))?;
+    let mut path_heads = std::collections::BTreeMap::new();
+    path_heads.insert(path_name.to_string(), head);
+    let manifest = ArchiveManifest {
+        path_heads,
+        snapshots: chain.iter().map(|s| s.hash.clone()).collect(),
+    };
+
+    match kind {
+        ArchiveWriterKind::Loose => {
+            fs::create_dir_all(dest).with_context(|| format!("creating archive dir {:?}", dest))?;
+            for snap in &chain {
+                let path = dest.join(format!("{}.json", snap.hash));
+                write_atomic(&path, &serde_json::to_vec(snap)?)?;
+            }
+            write_atomic(&dest.join("manifest.json"), &serde_json::to_vec_pretty(&manifest)?)?;
+        }
+        ArchiveWriterKind::Packed => {
+            let mut buf: Vec<u8> = Vec::new();
+            for snap in &chain {
+                let bytes = serde_json::to_vec(snap)?;
+                buf.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+                buf.extend_from_slice(&bytes);
+            }
+            let manifest_offset = buf.len() as u64;
+            buf.extend_from_slice(&serde_json::to_vec(&manifest)?);
+            buf.extend_from_slice(&manifest_offset.to_le_bytes());
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).with_context(|| format!("creating {:?}", parent))?;
+            }
+            write_atomic(dest, &buf)?;
         }
-        let bytes = fs::read(&path)?;
-        let v: Value = match serde_json::from_slice(&bytes) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-        let lobe = v
-            .get("lobe")
-            .and_then(|x| x.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let key = v
-            .get("key")
-            .and_then(|x| x.as_str())
-            .unwrap_or("default")
-            .to_string();
-        let ts = v
-            .get("ts")
-            .and_then(|x| x.as_str())
-            .unwrap_or("")
-            .to_string();
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or_default()
-            .to_string();
-        by_stream.entry((lobe, key)).or_default().push((ts, name));
     }
 
-    let mut examined = 0usize;
-    let mut removed = 0usize;
+    Ok(manifest)
+}
 
-    for ((_lobe, _key), mut nodes) in by_stream {
-        // newest first by timestamp string (RFC3339 sorts fine lexicographically if we replaced ':' above)
-        nodes.sort_by(|a, b| b.0.cmp(&a.0));
-        examined += nodes.len();
-        if nodes.len() > keep_last_per_stream {
-            for (_ts, name) in nodes.into_iter().skip(keep_last_per_stream) {
-                let p = dir.join(name);
-                let _ = fs::remove_file(p);
-                removed += 1;
-            }
-        }
+/// Read a packed archive's trailing manifest without scanning the whole
+/// file: the last 8 bytes give the manifest's byte offset.
+fn read_packed_manifest(bytes: &[u8]) -> Result<ArchiveManifest> {
+    if bytes.len() < 8 {
+        bail!("packed archive too short to contain a trailer");
     }
+    let trailer_start = bytes.len() - 8;
+    let offset = u64::from_le_bytes(bytes[trailer_start..].try_into().unwrap()) as usize;
+    if offset > trailer_start {
+        bail!("packed archive trailer offset out of range");
+    }
+    let manifest_bytes = &bytes[offset..trailer_start];
+    Ok(serde_json::from_slice(manifest_bytes)?)
+}
 
-    Ok(PruneReport {
-        examined,
-        kept: examined.saturating_sub(removed),
-        removed,
-    })
+/// Read every `ArchivedSnapshot` out of a packed archive's body (everything
+/// before the manifest), in the order they were written (root-first, since
+/// that's the order `export_path_archive` wrote them in).
+fn read_packed_snapshots(bytes: &[u8], manifest_offset: usize) -> Result<Vec<ArchivedSnapshot>> {
+    let mut out = Vec::new();
+    let mut pos = 0usize;
+    while pos < manifest_offset {
+        if pos + 8 > manifest_offset {
+            bail!("packed archive body truncated mid-length-prefix");
+        }
+        let len = u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap()) as usize;
+        pos += 8;
+        if pos + len > manifest_offset {
+            bail!("packed archive body truncated mid-record");
+        }
+        out.push(serde_json::from_slice(&bytes[pos..pos + len])?);
+        pos += len;
+    }
+    Ok(out)
 }
 
-// ---------- Merge helpers ----------
+/// Report of an [`import_archive`] run.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ArchiveImportReport {
+    pub inserted: usize,
+    pub already_present: usize,
+    pub path_heads: std::collections::BTreeMap<String, String>,
+}
 
-/// Compute the bind base (lowest common ancestor) between two snapshot hashes.
-/// Returns Some(ancestor_hash) if found.
-pub fn bind_base(a_hash: &str, b_hash: &str) -> Result<Option<String>> {
-    if a_hash == b_hash {
-        return Ok(Some(a_hash.to_string()));
+/// Import an archive written by [`export_path_archive`] from `src`
+/// (a directory for [`ArchiveWriterKind::Loose`], a single file for
+/// [`ArchiveWriterKind::Packed`]). Every snapshot's bytes are re-hashed and
+/// checked against its declared id before being inserted; a snapshot
+/// already present locally (by content hash) is skipped. Snapshots are
+/// inserted root-first (the manifest's own order) so each one's parent is
+/// already in the DAG by the time it's linked. Every exported path's head
+/// is then re-pointed at its (possibly just-imported) snapshot.
+/// Load an archive's manifest and snapshots (not yet verified or inserted),
+/// shared by [`import_archive`] and [`import_archive_with_progress`].
+fn load_archive(src: &Path, kind: ArchiveWriterKind) -> Result<(ArchiveManifest, Vec<ArchivedSnapshot>)> {
+    match kind {
+        ArchiveWriterKind::Loose => {
+            let manifest_bytes = fs::read(src.join("manifest.json"))
+                .with_context(|| format!("reading manifest in {:?}", src))?;
+            let manifest: ArchiveManifest = serde_json::from_slice(&manifest_bytes)?;
+            let mut snaps = Vec::with_capacity(manifest.snapshots.len());
+            for hash in &manifest.snapshots {
+                let bytes = fs::read(src.join(format!("{}.json", hash)))
+                    .with_context(|| format!("reading snapshot {} from {:?}", hash, src))?;
+                snaps.push(serde_json::from_slice::<ArchivedSnapshot>(&bytes)?);
+            }
+            Ok((manifest, snaps))
+        }
+        ArchiveWriterKind::Packed => {
+            let bytes = fs::read(src).with_context(|| format!("reading packed archive {:?}", src))?;
+            let manifest = read_packed_manifest(&bytes)?;
+            let trailer_start = bytes.len() - 8;
+            let offset = u64::from_le_bytes(bytes[trailer_start..].try_into().unwrap()) as usize;
+            let snaps = read_packed_snapshots(&bytes[..offset], offset)?;
+            Ok((manifest, snaps))
+        }
     }
-    // Collect ancestors of A (by hash), including A.
-    let mut aset: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut qa: std::collections::VecDeque<String> = std::collections::VecDeque::new();
-    if let Some(idx) = read_hash_index(a_hash)? {
-        qa.push_back(idx.node);
-    } else {
-        // Fallback: scan dag_nodes_dir for a file whose internal "hash" matches a_hash
-        if let Ok(dir) = dag_nodes_dir() {
-            if let Ok(entries) = fs::read_dir(&dir) {
-                for ent in entries.flatten() {
-                    let p = ent.path();
-                    if p.extension().and_then(|s| s.to_str()) != Some("json") {
-                        continue;
-                    }
-                    if let Ok(v) = load_node(
-                        &p.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or_default()
-                            .to_string(),
-                    ) {
-                        if let Some(h) = v.get("hash").and_then(|x| x.as_str()) {
-                            if h == a_hash {
-                                if let Some(fname) = p.file_name().and_then(|n| n.to_str()) {
-                                    qa.push_back(fname.to_string());
-                                }
-                                break;
-                            }
-                        }
-                    }
+}
+
+/// Verify and insert `snapshots` (root-first, so each one's parent already
+/// exists by the time it's linked), skipping any already present locally.
+/// Checks `abort` (if given) between snapshots and bails with
+/// [`RestorationAborted`] the moment it's set, before touching the next
+/// one -- already-inserted snapshots are left in place, so a later retry
+/// over the same (or a superset) snapshot list picks up where this left
+/// off purely because insertion is a content-addressed no-op for anything
+/// already present.
+fn insert_archive_snapshots(
+    snapshots: &[ArchivedSnapshot],
+    abort: Option<&std::sync::atomic::AtomicBool>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<(usize, usize)> {
+    let total = snapshots.len();
+    let mut inserted = 0usize;
+    let mut already_present = 0usize;
+    for (i, snap) in snapshots.iter().enumerate() {
+        if let Some(flag) = abort {
+            if flag.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(RestorationAborted {
+                    inserted_before_abort: inserted,
+                    total,
                 }
+                .into());
             }
         }
-    }
-    while let Some(fname) = qa.pop_front() {
-        let node = match load_node(&fname) {
-            Ok(n) => n,
-            Err(_) => continue,
-        };
-        if let Some(h) = node.get("hash").and_then(|x| x.as_str()) {
-            aset.insert(h.to_string());
+
+        let actual_hash = blake3::hash(snap.content.as_bytes()).to_hex().to_string();
+        if actual_hash != snap.hash {
+            bail!(
+                "archive snapshot {} failed verification (content hashes to {})",
+                snap.hash,
+                actual_hash
+            );
         }
-        for p in node_parents_list(&node) {
-            if p.ends_with(".json") {
-                qa.push_back(p);
-            } else if let Some(idx) = read_hash_index(&p)? {
-                qa.push_back(idx.node);
-            } else if let Some(fname) = resolve_parent_filename(&p).ok().flatten() {
-                qa.push_back(fname);
-            }
+        if read_hash_index(&snap.hash)?.is_some() {
+            already_present += 1;
+        } else {
+            let parents: Vec<String> = snap.parent.clone().into_iter().collect();
+            let _ = save_node(&snap.hash, &snap.content, &snap.meta, &parents)?;
+            inserted += 1;
         }
+        on_progress(i + 1, total);
     }
-    // BFS from B until hitting any in A's ancestor set (nearest to B wins).
-    let mut qb: std::collections::VecDeque<String> = std::collections::VecDeque::new();
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-    if let Some(idx) = read_hash_index(b_hash)? {
-        qb.push_back(idx.node);
-    } else {
-        if let Ok(dir) = dag_nodes_dir() {
-            if let Ok(entries) = fs::read_dir(&dir) {
-                for ent in entries.flatten() {
-                    let p = ent.path();
-                    if p.extension().and_then(|s| s.to_str()) != Some("json") {
-                        continue;
-                    }
-                    if let Ok(v) = load_node(
-                        &p.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or_default()
-                            .to_string(),
-                    ) {
-                        if let Some(h) = v.get("hash").and_then(|x| x.as_str()) {
-                            if h == b_hash {
-                                if let Some(fname) = p.file_name().and_then(|n| n.to_str()) {
-                                    qb.push_back(fname.to_string());
-                                }
-                                break;
-                            }
-                        }
-                    }
-                }
+    Ok((inserted, already_present))
+}
+
+/// Import an archive written by [`export_path_archive`] from `src`
+/// (a directory for [`ArchiveWriterKind::Loose`], a single file for
+/// [`ArchiveWriterKind::Packed`]). Every snapshot's bytes are re-hashed and
+/// checked against its declared id before being inserted; a snapshot
+/// already present locally (by content hash) is skipped. Snapshots are
+/// inserted root-first (the manifest's own order) so each one's parent is
+/// already in the DAG by the time it's linked. Every exported path's head
+/// is then re-pointed at its (possibly just-imported) snapshot.
+pub fn import_archive(src: &Path, kind: ArchiveWriterKind) -> Result<ArchiveImportReport> {
+    let (manifest, snapshots) = load_archive(src, kind)?;
+    let (inserted, already_present) = insert_archive_snapshots(&snapshots, None, |_, _| {})?;
+
+    for (path_name, head) in &manifest.path_heads {
+        set_path_head(path_name, head)?;
+    }
+
+    Ok(ArchiveImportReport {
+        inserted,
+        already_present,
+        path_heads: manifest.path_heads,
+    })
+}
+
+/// Raised by [`import_archive_with_progress`] when `abort` was observed set
+/// between snapshots. Carries how far the restore got so a caller can
+/// report progress without having to re-derive it; the underlying DAG
+/// state is left exactly as it was at that point (every already-inserted
+/// snapshot stays, nothing is rolled back), so simply calling
+/// `import_archive_with_progress` again over the same archive resumes from
+/// there -- each already-present snapshot is a no-op, and path heads only
+/// get re-pointed once the whole manifest has been processed without
+/// aborting.
+#[derive(Debug)]
+pub struct RestorationAborted {
+    pub inserted_before_abort: usize,
+    pub total: usize,
+}
+
+impl std::fmt::Display for RestorationAborted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "restoration aborted after inserting {}/{} snapshot(s)",
+            self.inserted_before_abort, self.total
+        )
+    }
+}
+
+impl std::error::Error for RestorationAborted {}
+
+/// Same as [`import_archive`], but abortable and observable: `abort` is
+/// checked between every snapshot (returning [`RestorationAborted`]
+/// promptly rather than finishing once it's set), and `on_progress(done,
+/// total)` is called after each snapshot is processed (inserted or
+/// skipped as already-present). Safe to call again after an abort (or any
+/// other failure) over the same archive: already-inserted snapshots are
+/// detected and skipped, so only the missing ones are inserted, and path
+/// heads are re-pointed only on a run that completes without aborting.
+pub fn import_archive_with_progress(
+    src: &Path,
+    kind: ArchiveWriterKind,
+    abort: &std::sync::atomic::AtomicBool,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<ArchiveImportReport> {
+    let (manifest, snapshots) = load_archive(src, kind)?;
+    let (inserted, already_present) =
+        insert_archive_snapshots(&snapshots, Some(abort), on_progress)?;
+
+    for (path_name, head) in &manifest.path_heads {
+        set_path_head(path_name, head)?;
+    }
+
+    Ok(ArchiveImportReport {
+        inserted,
+        already_present,
+        path_heads: manifest.path_heads,
+    })
+}
+
+// ---------- Streaming JSON-lines ingestion ----------
+
+/// Path a [`StreamedSnapshot`] line is appended to when it doesn't name one
+/// explicitly.
+const DEFAULT_STREAM_PATH: &str = "stream:restored";
+
+/// One line of a `restore_snapshot_stream` input: a [`MemoryState`],
+/// optionally tagged with the path it belongs to (absent -> [`DEFAULT_STREAM_PATH`]).
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct StreamedSnapshot {
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(flatten)]
+    pub state: MemoryState,
+}
+
+/// Result of [`import_snapshot_stream`]: every newly-written snapshot id (in
+/// input order, batch by batch -- content already present elsewhere is
+/// deduplicated and doesn't appear here) and each touched path's final head.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SnapshotStreamReport {
+    pub inserted: Vec<String>,
+    pub path_heads: std::collections::BTreeMap<String, String>,
+}
+
+/// Consume a newline-delimited stream of [`StreamedSnapshot`] lines,
+/// decoding and writing in batches of `batch_size` rather than buffering the
+/// whole input -- lets an external producer pipe a long history into the DAG
+/// over stdin or a socket without holding it all in memory.
+///
+/// Each batch is fully decoded before anything is written, and a path's head
+/// only moves once every line in the batch has been written or deduplicated
+/// without error -- so a stream that cuts off mid-batch (a bad line, or the
+/// reader simply ending) leaves every path at its last fully-committed
+/// snapshot, never a partial one. Within a batch, content already stored
+/// anywhere in the DAG is deduplicated (no new node is written) while still
+/// advancing its path's pending head.
+pub fn import_snapshot_stream<R: BufRead>(
+    mut reader: R,
+    batch_size: usize,
+) -> Result<SnapshotStreamReport> {
+    let batch_size = batch_size.max(1);
+    let mut report = SnapshotStreamReport::default();
+    let mut line = String::new();
+    let mut batch: Vec<StreamedSnapshot> = Vec::with_capacity(batch_size);
+
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        let at_eof = read == 0;
+        if read > 0 {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                let snap: StreamedSnapshot =
+                    serde_json::from_str(trimmed).context("parse streamed snapshot line")?;
+                batch.push(snap);
             }
         }
+        if batch.len() >= batch_size || (at_eof && !batch.is_empty()) {
+            apply_snapshot_batch(std::mem::take(&mut batch), &mut report)?;
+        }
+        if at_eof {
+            break;
+        }
     }
-    while let Some(fname) = qb.pop_front() {
-        if !seen.insert(fname.clone()) {
+    Ok(report)
+}
+
+fn apply_snapshot_batch(
+    batch: Vec<StreamedSnapshot>,
+    report: &mut SnapshotStreamReport,
+) -> Result<()> {
+    // path -> (content hash, node filename) this batch currently has
+    // pending for it; PathRefs themselves aren't touched until every line
+    // below has written or deduplicated cleanly.
+    let mut pending: std::collections::BTreeMap<String, (String, String)> = Default::default();
+    let mut inserted: Vec<String> = Vec::new();
+
+    for snap in &batch {
+        let path_name = snap
+            .path
+            .clone()
+            .unwrap_or_else(|| DEFAULT_STREAM_PATH.to_string());
+        let hash = blake3::hash(snap.state.content.as_bytes())
+            .to_hex()
+            .to_string();
+
+        if let Some(idx) = read_hash_index(&hash)? {
+            // Already stored (from an earlier batch, another path, or this
+            // stream's own dedup) -- don't write a new node, just advance
+            // this path's pending head to the existing one.
+            pending.insert(path_name, (hash, idx.node));
             continue;
         }
-        let node = match load_node(&fname) {
-            Ok(n) => n,
-            Err(_) => continue,
+
+        let mut meta = match snap.state.meta.clone() {
+            Value::Object(m) => Value::Object(m),
+            _ => Value::Object(serde_json::Map::new()),
         };
-        if let Some(h) = node.get("hash").and_then(|x| x.as_str()) {
-            if aset.contains(h) {
-                return Ok(Some(h.to_string()));
-            }
+        if meta.get("lobe").is_none() {
+            meta.as_object_mut()
+                .unwrap()
+                .insert("lobe".into(), Value::String("replay".into()));
         }
-        for p in node_parents_list(&node) {
-            if p.ends_with(".json") {
-                qb.push_back(p);
-            } else if let Some(idx) = read_hash_index(&p)? {
-                qb.push_back(idx.node);
-            } else if let Some(fname) = resolve_parent_filename(&p).ok().flatten() {
-                qb.push_back(fname);
-            }
+        if meta.get("key").is_none() {
+            meta.as_object_mut()
+                .unwrap()
+                .insert("key".into(), Value::String(path_id_from_name(&path_name)));
         }
+        meta.as_object_mut()
+            .unwrap()
+            .insert("cid".into(), Value::String(hash.clone()));
+
+        let parent_node = match pending.get(&path_name) {
+            Some((_, node)) => Some(node.clone()),
+            None => read_path_ref(&path_name)?.map(|r| r.head_node),
+        };
+        let parents: Vec<String> = parent_node.into_iter().collect();
+        let node_file = save_node(&hash, &snap.state.content, &meta, &parents)?;
+        pending.insert(path_name, (hash.clone(), node_file));
+        inserted.push(hash);
     }
-    Ok(None)
+
+    // The whole batch wrote cleanly -- now, and only now, advance every
+    // touched path's head.
+    for (path_name, (hash, _)) in &pending {
+        set_path_head(path_name, hash)?;
+    }
+
+    report.inserted.extend(inserted);
+    report
+        .path_heads
+        .extend(pending.into_iter().map(|(p, (h, _))| (p, h)));
+    Ok(())
 }
@@ -0,0 +1,196 @@
+// src/memory/discovery.rs
+//! Set-discovery protocol for syncing two synaptik DAG stores: given a
+//! local store and a [`RemoteStore`] that only answers "do you have these
+//! hashes?", compute the minimal set of nodes the remote is missing with a
+//! bounded number of round trips -- the same sampling idea as Mercurial's
+//! discovery protocol. Each round probes a mix of near-head and
+//! randomly-aged hashes; a "yes" answer marks the whole ancestor subgraph
+//! of that hash as common (no further questions needed about it or
+//! anything behind it), a "no" leaves it -- and its descendants, which are
+//! already known missing by construction -- undecided for the next round.
+//! Reuses `dag`'s parent-walking (the same helper `bind_base` walks) and
+//! its per-hash generation numbers to bias sampling toward the tips.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::dag;
+
+/// What a sync peer exposes for discovery: nothing but "do you have these
+/// content hashes?". Any transport (HTTP, a second local store in tests,
+/// ...) just needs to answer that, one bool per input hash, same order.
+pub trait RemoteStore: Send + Sync {
+    fn has_hashes(&self, hashes: &[String]) -> Result<Vec<bool>>;
+}
+
+/// Result of [`discover`]: the common ground both sides agree on, and the
+/// local filenames that still need to ship.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryResult {
+    /// Hashes confirmed common -- every ancestor of each is common too.
+    pub common_base: Vec<String>,
+    /// Local node filenames the remote is missing, ready for a higher
+    /// layer to ship as the sync delta.
+    pub missing: Vec<String>,
+}
+
+const MAX_ROUNDS: usize = 10;
+const SAMPLE_SIZE: usize = 16;
+
+/// Cheap, seedable PRNG (xorshift64) so sampling stays deterministic under
+/// test without pulling in the `rand` crate for one call site.
+struct Xorshift64(u64);
+
+impl Xorshift64 {
+    fn new(seed: u64) -> Self {
+        Self(if seed == 0 { 0x9E3779B97F4A7C15 } else { seed })
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        if len == 0 { 0 } else { (self.next_u64() as usize) % len }
+    }
+}
+
+/// Run set discovery starting from `local_heads` against `remote`. `seed`
+/// drives the random half of each round's sample -- pass a fixed value for
+/// reproducible tests, something like the current time in production.
+pub fn discover(
+    local_heads: &[String],
+    remote: &dyn RemoteStore,
+    seed: u64,
+) -> Result<DiscoveryResult> {
+    let mut rng = Xorshift64::new(seed);
+
+    let mut common: HashSet<String> = HashSet::new();
+    let mut missing: HashSet<String> = HashSet::new();
+    let mut undecided: HashSet<String> = HashSet::new();
+    let mut generation_of: HashMap<String, u64> = HashMap::new();
+
+    // Seed `undecided` with every hash reachable from the heads, tagging
+    // each with its generation so sampling can mix "near the tips" with
+    // "scattered across history" draws.
+    let mut frontier: VecDeque<String> = local_heads.iter().cloned().collect();
+    let mut visited: HashSet<String> = HashSet::new();
+    while let Some(h) = frontier.pop_front() {
+        if !visited.insert(h.clone()) {
+            continue;
+        }
+        let gen = dag::read_generation(&h)?.unwrap_or(0);
+        generation_of.insert(h.clone(), gen);
+        undecided.insert(h.clone());
+        for parent in dag::parent_hashes_of(&h)? {
+            frontier.push_back(parent);
+        }
+    }
+
+    for _round in 0..MAX_ROUNDS {
+        if undecided.is_empty() {
+            break;
+        }
+        let sample = sample_undecided(&undecided, &generation_of, SAMPLE_SIZE, &mut rng);
+        if sample.is_empty() {
+            break;
+        }
+        let answers = remote.has_hashes(&sample)?;
+        let mut any_progress = false;
+        for (hash, has_it) in sample.iter().zip(answers.iter()) {
+            if !undecided.remove(hash) {
+                continue;
+            }
+            any_progress = true;
+            if *has_it {
+                mark_common(hash, &mut common, &mut undecided)?;
+            } else {
+                missing.insert(hash.clone());
+            }
+        }
+        if !any_progress {
+            break;
+        }
+    }
+
+    // Anything still undecided after the round budget is conservatively
+    // treated as missing: correct (if not maximally efficient), since the
+    // remote was never confirmed to have it.
+    for h in undecided {
+        missing.insert(h);
+    }
+
+    let mut missing_filenames = Vec::new();
+    for hash in &missing {
+        if let Some(fname) = dag::resolve_parent_filename(hash)? {
+            missing_filenames.push(fname);
+        }
+    }
+    missing_filenames.sort();
+
+    let mut common_base: Vec<String> = common.into_iter().collect();
+    common_base.sort();
+
+    Ok(DiscoveryResult {
+        common_base,
+        missing: missing_filenames,
+    })
+}
+
+/// A "yes" answer marks `hash` and every one of its ancestors as common,
+/// pulling them out of `undecided` so later rounds never re-ask about them.
+fn mark_common(
+    hash: &str,
+    common: &mut HashSet<String>,
+    undecided: &mut HashSet<String>,
+) -> Result<()> {
+    let mut stack = vec![hash.to_string()];
+    while let Some(h) = stack.pop() {
+        if !common.insert(h.clone()) {
+            continue;
+        }
+        undecided.remove(&h);
+        for parent in dag::parent_hashes_of(&h)? {
+            stack.push(parent);
+        }
+    }
+    Ok(())
+}
+
+/// Sample up to `n` hashes from `undecided`, mixing the highest-generation
+/// (nearest the heads) entries with ones drawn at random across the rest --
+/// concentrating probes where divergence is most likely while still
+/// occasionally checking deep history, the same bias Mercurial's discovery
+/// sampler uses.
+fn sample_undecided(
+    undecided: &HashSet<String>,
+    generation_of: &HashMap<String, u64>,
+    n: usize,
+    rng: &mut Xorshift64,
+) -> Vec<String> {
+    let mut pool: Vec<&String> = undecided.iter().collect();
+    pool.sort_by_key(|h| std::cmp::Reverse(generation_of.get(*h).copied().unwrap_or(0)));
+
+    let near_head_count = (n / 2).min(pool.len());
+    let mut sample: Vec<String> = pool[..near_head_count]
+        .iter()
+        .map(|s| (**s).clone())
+        .collect();
+
+    let mut remaining: Vec<&String> = pool[near_head_count..].to_vec();
+    let random_count = n.saturating_sub(sample.len()).min(remaining.len());
+    for _ in 0..random_count {
+        if remaining.is_empty() {
+            break;
+        }
+        let idx = rng.index(remaining.len());
+        sample.push(remaining.remove(idx).clone());
+    }
+
+    sample
+}
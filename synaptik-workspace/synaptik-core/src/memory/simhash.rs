@@ -0,0 +1,259 @@
+// src/memory/simhash.rs
+//! Near-duplicate snapshot detection via 64-bit SimHash fingerprints,
+//! searched through a BK-tree keyed by Hamming distance.
+//!
+//! `prune` only collapses exact re-saves of the newest N nodes per
+//! (lobe,key); two snapshots that differ by a reworded sentence still get
+//! their own content hash and both survive. A SimHash fingerprint is stable
+//! under small edits (most of its bits come from shingles the edit didn't
+//! touch), so nodes whose fingerprints are close in Hamming distance are
+//! almost certainly near-duplicates even though their hashes differ.
+//!
+//! Fingerprints are written one small JSON file per node under
+//! `refs/fingerprints/`, the same "recompute the in-memory structure from
+//! many small files" idiom the nodemap and generation-number indexes use --
+//! there's no single serialized tree to corrupt or keep in sync.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::commands::init::ensure_initialized_once;
+use crate::utils::path as pathutil;
+
+use super::dag;
+
+fn fingerprints_ref_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?
+        .root
+        .join("refs")
+        .join("fingerprints");
+    std::fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn fingerprint_path(node_filename: &str) -> Result<PathBuf> {
+    Ok(fingerprints_ref_dir()?.join(format!("{}.fp.json", node_filename)))
+}
+
+fn write_atomic(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
+    let root = ensure_initialized_once()?.root.clone();
+    let root = root.canonicalize().unwrap_or(root);
+    let _ = pathutil::assert_within_root_abs(&root, path)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("create_dir_all({:?})", parent))?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes).with_context(|| format!("open temp file {:?}", tmp))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("rename {:?} -> {:?}", tmp, path))?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct FingerprintEntry {
+    pub node: String,
+    pub hash: String,
+    pub lobe: String,
+    pub key: String,
+    pub ts: String,
+    pub fingerprint: u64,
+}
+
+fn token_hash64(token: &str) -> u64 {
+    let h = blake3::hash(token.as_bytes());
+    let bytes: [u8; 8] = h.as_bytes()[0..8].try_into().expect("8-byte slice");
+    u64::from_le_bytes(bytes)
+}
+
+/// Compute a 64-bit SimHash over `content`'s shingles: one weighted vote per
+/// bit across every shingle's hash, sign of the sum becomes the output bit.
+pub(crate) fn simhash64(content: &str) -> u64 {
+    let shingles = dag::content_shingles(content);
+    if shingles.is_empty() {
+        return 0;
+    }
+    let mut weights = [0i64; 64];
+    for shingle in &shingles {
+        let h = token_hash64(shingle);
+        for (bit, w) in weights.iter_mut().enumerate() {
+            if (h >> bit) & 1 == 1 {
+                *w += 1;
+            } else {
+                *w -= 1;
+            }
+        }
+    }
+    let mut out: u64 = 0;
+    for (bit, w) in weights.iter().enumerate() {
+        if *w > 0 {
+            out |= 1 << bit;
+        }
+    }
+    out
+}
+
+/// Record a node's fingerprint. Called from `save_node` alongside the
+/// nodemap/generation bookkeeping; best-effort like those, since a node
+/// missing its fingerprint is just invisible to similarity search rather
+/// than unusable.
+pub(crate) fn record_fingerprint(
+    node_filename: &str,
+    hash: &str,
+    lobe: &str,
+    key: &str,
+    ts: &str,
+    content: &str,
+) -> Result<()> {
+    let entry = FingerprintEntry {
+        node: node_filename.to_string(),
+        hash: hash.to_string(),
+        lobe: lobe.to_string(),
+        key: key.to_string(),
+        ts: ts.to_string(),
+        fingerprint: simhash64(content),
+    };
+    write_atomic(
+        &fingerprint_path(node_filename)?,
+        &serde_json::to_vec_pretty(&entry)?,
+    )
+}
+
+fn read_all_fingerprints() -> Result<Vec<FingerprintEntry>> {
+    let dir = fingerprints_ref_dir()?;
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        if let Ok(bytes) = std::fs::read(&path) {
+            if let Ok(e) = serde_json::from_slice::<FingerprintEntry>(&bytes) {
+                out.push(e);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+struct BkNode {
+    fingerprint: u64,
+    entry: FingerprintEntry,
+    /// Keyed by this child's Hamming distance from its parent, per the
+    /// classic BK-tree construction.
+    children: HashMap<u32, Box<BkNode>>,
+}
+
+#[derive(Default)]
+struct BkTree {
+    root: Option<Box<BkNode>>,
+}
+
+impl BkTree {
+    fn insert(&mut self, entry: FingerprintEntry) {
+        match &mut self.root {
+            None => {
+                self.root = Some(Box::new(BkNode {
+                    fingerprint: entry.fingerprint,
+                    entry,
+                    children: HashMap::new(),
+                }))
+            }
+            Some(root) => Self::insert_under(root, entry),
+        }
+    }
+
+    fn insert_under(node: &mut BkNode, entry: FingerprintEntry) {
+        let d = hamming(node.fingerprint, entry.fingerprint);
+        match node.children.get_mut(&d) {
+            Some(child) => Self::insert_under(child, entry),
+            None => {
+                node.children.insert(
+                    d,
+                    Box::new(BkNode {
+                        fingerprint: entry.fingerprint,
+                        entry,
+                        children: HashMap::new(),
+                    }),
+                );
+            }
+        }
+    }
+
+    /// All entries within `max_distance` of `query`. At each node, only
+    /// descend into children whose edge distance `d` satisfies the
+    /// triangle-inequality bound `|d - dist(node, query)| <= max_distance`
+    /// -- every other subtree is provably out of range and skipped.
+    fn find_similar(&self, query: u64, max_distance: u32) -> Vec<(FingerprintEntry, u32)> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            Self::search(root, query, max_distance, &mut out);
+        }
+        out
+    }
+
+    fn search(node: &BkNode, query: u64, max_distance: u32, out: &mut Vec<(FingerprintEntry, u32)>) {
+        let dist = hamming(node.fingerprint, query);
+        if dist <= max_distance {
+            out.push((node.entry.clone(), dist));
+        }
+        let lo = dist.saturating_sub(max_distance);
+        let hi = dist + max_distance;
+        for (&edge, child) in node.children.iter() {
+            if edge >= lo && edge <= hi {
+                Self::search(child, query, max_distance, out);
+            }
+        }
+    }
+}
+
+fn build_tree(entries: Vec<FingerprintEntry>) -> BkTree {
+    let mut tree = BkTree::default();
+    for e in entries {
+        tree.insert(e);
+    }
+    tree
+}
+
+/// Find snapshot hashes whose SimHash fingerprint is within `max_distance`
+/// Hamming bits of `snapshot_id`'s, nearest-first, excluding itself.
+/// Rebuilds the BK-tree from all recorded fingerprints for this one query --
+/// fine at this store's scale, same tradeoff the nodemap/generation indexes
+/// make elsewhere in this module.
+pub fn find_similar(snapshot_id: &str, max_distance: u32) -> Result<Vec<(String, u32)>> {
+    let entries = read_all_fingerprints()?;
+    let query = entries
+        .iter()
+        .find(|e| e.hash == snapshot_id)
+        .map(|e| e.fingerprint)
+        .ok_or_else(|| anyhow::anyhow!("no fingerprint recorded for snapshot: {}", snapshot_id))?;
+
+    let tree = build_tree(entries);
+    let mut matches: Vec<(String, u32)> = tree
+        .find_similar(query, max_distance)
+        .into_iter()
+        .filter(|(e, _)| e.hash != snapshot_id)
+        .map(|(e, d)| (e.hash, d))
+        .collect();
+    matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    matches.dedup_by(|a, b| a.0 == b.0);
+    Ok(matches)
+}
+
+/// All fingerprint entries for one (lobe,key) stream, for
+/// `dag::prune_near_duplicates` to cluster -- kept crate-internal since
+/// clustering/removal is `dag`'s job, not this module's.
+pub(crate) fn stream_fingerprints(lobe: &str, key: &str) -> Result<Vec<FingerprintEntry>> {
+    Ok(read_all_fingerprints()?
+        .into_iter()
+        .filter(|e| e.lobe == lobe && e.key == key)
+        .collect())
+}
+
+pub(crate) fn distance(a: u64, b: u64) -> u32 {
+    hamming(a, b)
+}
@@ -5,6 +5,12 @@
 //! - **No database writes here.** Memory is the *only* SQLite writer.
 //! - Librarian calls `archive(memory_id, bytes)` to get a CID, then
 //!   calls `Memory::mark_archived(memory_id, cid, ts)`.
+//! - A blob is shared across memory_ids, so it can't just be deleted when
+//!   one memory is forgotten -- `<root>/.refs.jsonl` is an append-only,
+//!   replayable journal of `insert`/`remove` ops that derives each cid's
+//!   live reference set; `gc` prunes blobs whose set has been empty longer
+//!   than the retention window. See the "reference-counted GC" section
+//!   below.
 //!
 //! MVP flow:
 //!   remember → Memory
@@ -13,17 +19,78 @@
 
 use anyhow::Result;
 use blake3;
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::sync::Arc;
 use std::{fs, path::PathBuf};
 
 use crate::services::audit::record_action;
+use crate::services::memid_index::{InclusionProof, MemidIndex};
+
+/// A cold-store blob's recomputed hash didn't match the CID it was
+/// requested under -- distinct from "missing" (a plain I/O not-found) so
+/// callers like [`crate::commands::Commands::dag_scrub`] can bucket the two
+/// separately.
+#[derive(Debug, Clone)]
+pub struct IntegrityError {
+    /// The CID the blob was requested under.
+    pub cid: String,
+    /// Alias of `cid` -- the hash the caller expected to recompute.
+    pub expected: String,
+    /// The hash actually recomputed from the blob's bytes.
+    pub actual: String,
+}
+
+impl std::fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "archived blob corrupted: cid {} recomputed to {} -- cold storage integrity check failed",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Stream `reader` through an incremental BLAKE3 hasher in fixed-size
+/// chunks rather than materializing it in one buffer up front. When
+/// `keep_bytes` is set the chunks are also retained and returned, so
+/// [`Archivist::retrieve`] can reuse this for its "verify then hand back
+/// the bytes" path while [`Archivist::verify`] uses `keep_bytes: false` to
+/// fsck a blob in near-constant memory.
+pub(crate) fn hash_streamed(
+    mut reader: impl Read,
+    keep_bytes: bool,
+) -> Result<(Option<Vec<u8>>, String)> {
+    const BUF_SIZE: usize = 64 * 1024;
+    let mut buf = [0u8; BUF_SIZE];
+    let mut hasher = blake3::Hasher::new();
+    let mut out = keep_bytes.then(Vec::new);
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        if let Some(out) = out.as_mut() {
+            out.extend_from_slice(&buf[..n]);
+        }
+    }
+    Ok((out, hasher.finalize().to_hex().to_string()))
+}
 
 /// Filesystem-backed content store (no DB).
 #[derive(Debug, Clone)]
 pub struct Archivist {
     /// Directory where blobs are written by CID, e.g. `.cogniv/archive/`
     root: PathBuf,
+    /// Authenticated `memory_id -> cid` index, shared across clones so
+    /// every handle onto this archive root sees the same state.
+    memid_index: Arc<MemidIndex>,
 }
 
 impl Archivist {
@@ -37,7 +104,8 @@ impl Archivist {
     pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
         let root = root.into();
         fs::create_dir_all(&root)?;
-        Ok(Self { root })
+        let memid_index = Arc::new(MemidIndex::open(&root)?);
+        Ok(Self { root, memid_index })
     }
 
     /// Archive raw bytes and return the CID (content hash).
@@ -69,6 +137,13 @@ impl Archivist {
             fs::write(&path, bytes)?;
         }
 
+        // `memory_id` now holds a live reference to `cid`.
+        self.append_ref_entry(RefOp::Insert, &cid, memory_id)?;
+
+        // Record/update the authenticated memory_id -> cid mapping so it
+        // can later be proven against `root_hash()` without touching SQLite.
+        self.memid_index.set(memory_id, &cid)?;
+
         // Lightweight audit (for traceability)
         record_action(
             "archivist",
@@ -94,7 +169,13 @@ impl Archivist {
     /// * `Ok(Vec<u8>)` — the archived bytes.
     ///
     /// # Behavior
-    /// - Reads `<root>/<cid>`.
+    /// - Streams `<root>/<cid>` through an incremental hasher rather than
+    ///   hashing one fully-materialized buffer, so verifying never needs
+    ///   more than one read-buffer's worth of memory beyond the object
+    ///   itself.
+    /// - Returns an [`IntegrityError`] if the recomputed hash doesn't match
+    ///   `cid` -- bit rot surfaces here instead of as garbage handed back to
+    ///   the caller.
     /// - Audits the read (can be removed if too chatty).
     pub fn retrieve(&self, cid: &str) -> Result<Vec<u8>> {
         let path = self.root.join(cid);
@@ -106,7 +187,29 @@ impl Archivist {
                 Self::MAX_OBJECT_BYTES
             );
         }
-        let bytes = fs::read(&path)?;
+        let file = fs::File::open(&path)?;
+        let (bytes, actual) = hash_streamed(file, true)?;
+        let bytes = bytes.expect("hash_streamed(_, true) always returns bytes");
+
+        if actual != cid {
+            record_action(
+                "archivist",
+                "integrity_mismatch",
+                &json!({
+                    "cid": cid,
+                    "actual_hash": actual,
+                    "bytes": bytes.len(),
+                    "ts": Utc::now().to_rfc3339(),
+                }),
+                "high",
+            );
+            return Err(IntegrityError {
+                cid: cid.to_string(),
+                expected: cid.to_string(),
+                actual,
+            }
+            .into());
+        }
 
         record_action(
             "archivist",
@@ -121,4 +224,269 @@ impl Archivist {
 
         Ok(bytes)
     }
+
+    /// Verify that `cid`'s on-disk blob still hashes to `cid`, without
+    /// returning (or ever fully materializing) its bytes -- the cheap path
+    /// [`crate::commands::Commands::dag_scrub`] uses to fsck an archive
+    /// root without paying for every object's content at once.
+    pub fn verify(&self, cid: &str) -> Result<()> {
+        let path = self.root.join(cid);
+        let file = fs::File::open(&path)?;
+        let (_, actual) = hash_streamed(file, false)?;
+        if actual != cid {
+            return Err(IntegrityError {
+                cid: cid.to_string(),
+                expected: cid.to_string(),
+                actual,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Cheaply check whether `cid` is present without reading or verifying
+    /// its bytes (unlike [`Self::retrieve`]/[`Self::verify`], this never
+    /// recomputes the hash).
+    pub fn exists(&self, cid: &str) -> Result<bool> {
+        Ok(self.root.join(cid).try_exists()?)
+    }
+
+    /// Record that `memory_id` no longer references `cid` (e.g. the memory
+    /// was forgotten or pruned). Appends a `remove` entry to the refcount
+    /// journal; doesn't itself touch the on-disk blob -- a blob is only
+    /// ever deleted by [`Self::gc`], once *every* referencing memory has
+    /// released it and the retention window has passed.
+    pub fn release(&self, memory_id: &str, cid: &str) -> Result<()> {
+        self.append_ref_entry(RefOp::Remove, cid, memory_id)
+    }
+
+    /// Delete on-disk blobs whose reference set has been empty for more
+    /// than `keep_days`. Returns the cids actually deleted.
+    ///
+    /// # Behavior
+    /// - Replays `.refs.jsonl` from scratch to derive current refcounts, so
+    ///   an interrupted GC is simply recovered by re-running this: deletions
+    ///   are idempotent (a missing file is not an error) and no GC-specific
+    ///   journal entries need to be written or replayed.
+    /// - A cid is eligible once its live set is empty AND it's been empty
+    ///   since before `Utc::now() - keep_days`; an insert after the set went
+    ///   empty un-tombstones it.
+    /// - Emits a `record_action("archivist", "gc_delete", ...)` entry per
+    ///   deleted cid.
+    pub fn gc(&self, keep_days: i64) -> Result<Vec<String>> {
+        let states = self.replay_refs()?;
+        let cutoff = Utc::now() - Duration::days(keep_days);
+        let mut deleted = Vec::new();
+        for (cid, state) in states {
+            if !state.live.is_empty() {
+                continue;
+            }
+            let Some(tombstoned_at) = state.tombstoned_at else {
+                continue;
+            };
+            if tombstoned_at > cutoff {
+                continue;
+            }
+            let path = self.root.join(&cid);
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    record_action(
+                        "archivist",
+                        "gc_delete",
+                        &json!({
+                            "cid": cid,
+                            "tombstoned_at": tombstoned_at.to_rfc3339(),
+                            "ts": Utc::now().to_rfc3339(),
+                        }),
+                        "low",
+                    );
+                    deleted.push(cid);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Current root hash of the authenticated `memory_id -> cid` index.
+    /// Publish this so a third party can later check a `(memory_id, cid)`
+    /// pair against it via [`Self::prove`] + [`crate::services::memid_index::verify`],
+    /// without trusting the database.
+    pub fn memid_root_hash(&self) -> [u8; 32] {
+        self.memid_index.root_hash()
+    }
+
+    /// Build an inclusion proof that `memory_id` currently maps to its
+    /// archived cid in the index. Returns `None` if `memory_id` has never
+    /// been archived.
+    pub fn prove_memid(&self, memory_id: &str) -> Option<InclusionProof> {
+        self.memid_index.prove(memory_id)
+    }
+
+    /// Walk every blob under `root` and verify it still hashes to its own
+    /// filename -- a first-class fsck for cold storage, run proactively
+    /// instead of waiting for a corrupted cid to surface via [`Self::retrieve`].
+    ///
+    /// # Behavior
+    /// - Streams each file in bounded chunks (respecting
+    ///   [`Self::MAX_OBJECT_BYTES`]) rather than loading it whole, so a scrub
+    ///   doesn't blow memory on a large archive.
+    /// - A blob whose recomputed hash doesn't match its filename is reported
+    ///   `corrupted`; a blob with no live reference in `.refs.jsonl` (per
+    ///   [`Self::replay_refs`]) is reported `orphaned` -- a read-only signal
+    ///   for [`Self::gc`] to eventually collect, not a deletion.
+    /// - The refcount journal itself (`.refs.jsonl`) is skipped; it isn't a
+    ///   content-addressed blob.
+    pub fn scrub(&self) -> Result<ScrubReport> {
+        let refs = self.replay_refs()?;
+        let mut report = ScrubReport::default();
+
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if name == REFS_JOURNAL_FILE {
+                continue;
+            }
+            report.scanned += 1;
+
+            let meta = entry.metadata()?;
+            if meta.len() > Self::MAX_OBJECT_BYTES as u64 {
+                record_action(
+                    "archivist",
+                    "integrity_mismatch",
+                    &json!({
+                        "cid": name,
+                        "reason": "exceeds MAX_OBJECT_BYTES",
+                        "bytes": meta.len(),
+                        "ts": Utc::now().to_rfc3339(),
+                    }),
+                    "high",
+                );
+                report.corrupted.push(name.to_string());
+                continue;
+            }
+
+            let mut file = fs::File::open(&path)?;
+            let mut hasher = blake3::Hasher::new();
+            let mut buf = [0u8; 64 * 1024];
+            loop {
+                let n = file.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                hasher.update(&buf[..n]);
+            }
+            let actual = hasher.finalize().to_hex().to_string();
+
+            if actual != name {
+                record_action(
+                    "archivist",
+                    "integrity_mismatch",
+                    &json!({
+                        "cid": name,
+                        "actual_hash": actual,
+                        "bytes": meta.len(),
+                        "ts": Utc::now().to_rfc3339(),
+                    }),
+                    "high",
+                );
+                report.corrupted.push(name.to_string());
+                continue;
+            }
+
+            let has_live_ref = refs.get(name).is_some_and(|s| !s.live.is_empty());
+            if !has_live_ref {
+                report.orphaned.push(name.to_string());
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Append one `insert`/`remove` op to `.refs.jsonl`.
+    fn append_ref_entry(&self, op: RefOp, cid: &str, memory_id: &str) -> Result<()> {
+        let entry = RefJournalEntry {
+            op,
+            cid: cid.to_string(),
+            memory_id: memory_id.to_string(),
+            ts: Utc::now(),
+        };
+        let path = self.root.join(REFS_JOURNAL_FILE);
+        let mut f = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(f, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// Replay `.refs.jsonl` top to bottom into a live reference set per
+    /// cid, tombstoning a cid the moment its set becomes empty and
+    /// clearing the tombstone on any later insert. A corrupt trailing line
+    /// (e.g. from a crash mid-write) is skipped rather than failing replay.
+    fn replay_refs(&self) -> Result<HashMap<String, RefcountState>> {
+        let mut states: HashMap<String, RefcountState> = HashMap::new();
+        let path = self.root.join(REFS_JOURNAL_FILE);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Ok(states);
+        };
+        for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+            let Ok(entry) = serde_json::from_str::<RefJournalEntry>(line) else {
+                continue;
+            };
+            let state = states.entry(entry.cid).or_default();
+            match entry.op {
+                RefOp::Insert => {
+                    state.live.insert(entry.memory_id);
+                    state.tombstoned_at = None;
+                }
+                RefOp::Remove => {
+                    state.live.remove(&entry.memory_id);
+                    if state.live.is_empty() && state.tombstoned_at.is_none() {
+                        state.tombstoned_at = Some(entry.ts);
+                    }
+                }
+            }
+        }
+        Ok(states)
+    }
+}
+
+/// Result of [`Archivist::scrub`]: how many blobs were checked, and which
+/// cids failed integrity (`corrupted`) or have no live reference
+/// (`orphaned`, a candidate for a future [`Archivist::gc`]).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScrubReport {
+    pub scanned: usize,
+    pub corrupted: Vec<String>,
+    pub orphaned: Vec<String>,
+}
+
+const REFS_JOURNAL_FILE: &str = ".refs.jsonl";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum RefOp {
+    Insert,
+    Remove,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RefJournalEntry {
+    op: RefOp,
+    cid: String,
+    memory_id: String,
+    ts: DateTime<Utc>,
+}
+
+/// A cid's derived GC state: every memory_id still holding a live
+/// reference, and (if that set is currently empty) when it became so.
+#[derive(Debug, Default)]
+struct RefcountState {
+    live: HashSet<String>,
+    tombstoned_at: Option<DateTime<Utc>>,
 }
@@ -0,0 +1,159 @@
+// synaptik-core/src/services/async_streamgate.rs
+//
+// Async counterpart to `streamgate::{LlmClient, StreamRuntime}` for callers
+// integrating with async HTTP/SSE LLM backends, so the gate can sit directly
+// on a streaming network response instead of dedicating a blocking thread
+// per generation. Gated behind the `async_runtime` feature since it's the
+// only thing in this crate that needs tokio/futures.
+//
+// All safeguards mirror the synchronous runtime exactly (same prompt
+// compilation, stop-phrase detection, windowed masking, token/byte caps,
+// `Finalized`/`audit` semantics) via the shared `pub(crate)` helpers in
+// `streamgate`; only the budget enforcement differs, using a `tokio::time`
+// timeout per token instead of polling `Instant::elapsed()`, so a stalled
+// stream is bounded the same way a slow model is.
+
+use std::time::{Duration, Instant};
+
+use futures::{Stream, StreamExt};
+
+use super::ethos::{ConstraintSpec, EthosContract, Proposal, RuntimeDecision};
+use super::streamgate::{hits_stop_phrase, prompt_compile, token_limit_reached, Finalized, GateError};
+use super::{audit, memory};
+
+/// Async counterpart to [`super::streamgate::LlmClient`]: `stream` yields a
+/// `futures::Stream` instead of a blocking `Iterator`.
+pub trait AsyncLlmClient {
+    type Stream: Stream<Item = String> + Unpin + Send;
+
+    async fn stream(&self, system_prompt: String) -> std::result::Result<Self::Stream, GateError>;
+}
+
+pub struct AsyncStreamRuntime<C: EthosContract, M: AsyncLlmClient> {
+    pub contract: C,
+    pub model: M,
+}
+
+impl<C: EthosContract, M: AsyncLlmClient> AsyncStreamRuntime<C, M> {
+    pub async fn generate(&self, p: Proposal) -> std::result::Result<Finalized, GateError> {
+        // Global safeguards to prevent resource exhaustion in absence of explicit constraints
+        const DEFAULT_MAX_TOKENS: usize = 512; // fallback token cap
+        const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024; // 64 KiB output cap
+        const DEFAULT_BUDGET_MS: u64 = 3_000; // wall-clock budget
+
+        let decision = self.contract.evaluate(&p);
+        audit::log_proposal(&p, &decision);
+
+        match decision {
+            RuntimeDecision::Stop { safe_template } => {
+                return Ok(Finalized::stopped(safe_template));
+            }
+            RuntimeDecision::Escalate { ref reason } => {
+                audit::log_escalation(&p, reason);
+                return Ok(Finalized::escalated(reason.clone()));
+            }
+            RuntimeDecision::Proceed | RuntimeDecision::Constrain(_) => {}
+        }
+
+        let constraints = match &decision {
+            RuntimeDecision::Constrain(spec) => Some(spec.clone()),
+            _ => None,
+        };
+
+        let sys_prompt = prompt_compile(&p, constraints.as_ref());
+        let mut stream = self.model.stream(sys_prompt).await?;
+        let start = Instant::now();
+        let budget = Duration::from_millis(DEFAULT_BUDGET_MS);
+
+        let mut buf = String::new();
+        let mut violated: Option<String> = None;
+
+        // Same conservative suffix-window sizing as the synchronous runtime,
+        // so cross-token matches are covered without remasking the whole buffer.
+        let window_bytes: usize = constraints
+            .as_ref()
+            .map(|spec| {
+                let max_pat = spec
+                    .mask_rules
+                    .iter()
+                    .map(|p| crate::services::masking::norm_lower(p).chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let margin = (max_pat.saturating_mul(8)).max(128).min(4096);
+                margin
+            })
+            .unwrap_or(0);
+
+        let fallback_max_tokens = DEFAULT_MAX_TOKENS;
+
+        loop {
+            let elapsed = start.elapsed();
+            if elapsed >= budget {
+                break;
+            }
+            let remaining = budget - elapsed;
+            let next = match tokio::time::timeout(remaining, stream.next()).await {
+                Ok(Some(tok)) => tok,
+                Ok(None) => break,  // stream ended
+                Err(_) => break,    // timed out waiting on the next token
+            };
+            let tok = next;
+
+            if let Some(spec) = &constraints {
+                if hits_stop_phrase(&buf, &tok, &spec.stop_phrases) {
+                    violated = Some("stop_phrase".to_string());
+                    break;
+                }
+
+                buf.push_str(&tok);
+                if !spec.mask_rules.is_empty() {
+                    if window_bytes == 0 || buf.len() <= window_bytes {
+                        buf = crate::services::masking::apply_mask_rules(&buf, &spec.mask_rules);
+                    } else {
+                        let target = buf.len() - window_bytes;
+                        let mut start = 0usize;
+                        for (i, _) in buf.char_indices() {
+                            if i <= target {
+                                start = i;
+                            } else {
+                                break;
+                            }
+                        }
+                        let tail = buf[start..].to_string();
+                        let masked_tail = crate::services::masking::apply_mask_rules(&tail, &spec.mask_rules);
+                        buf.truncate(start);
+                        buf.push_str(&masked_tail);
+                    }
+                }
+
+                if token_limit_reached(&buf, spec.max_tokens) {
+                    break;
+                }
+            } else {
+                buf.push_str(&tok);
+                if token_limit_reached(&buf, fallback_max_tokens) {
+                    break;
+                }
+            }
+
+            if buf.len() >= DEFAULT_MAX_OUTPUT_BYTES {
+                break;
+            }
+        }
+
+        let finalized = if let Some(lbl) = violated.clone() {
+            audit::log_violation(&p, &lbl, &buf);
+            Finalized::violated(buf, lbl)
+        } else {
+            Finalized::ok(buf)
+        };
+
+        if finalized.is_ok() {
+            let _ = memory::commit_snapshot_logged(&p, &decision, &finalized);
+        } else {
+            audit::log_violation(&p, "rejected_snapshot", &finalized.text);
+        }
+
+        Ok(finalized)
+    }
+}
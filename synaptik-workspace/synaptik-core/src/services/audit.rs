@@ -1,20 +1,32 @@
 //! services/audit.rs
 //! Contract-aware audit logbook: actions, ethics decisions, and raw contract evaluations.
 //!
-//! - Writes JSONL files under `.cogniv/logbook/`.
+//! - Writes JSONL files under `.cogniv/logbook/`, physically stored as
+//!   segmented, rotating, retention-pruned streams (see `services::logbook`).
 //! - Bridges to the `contracts` crate via `evaluate_contract_json` and normalizes results.
-
+//! - Every JSONL append is hash-chained (blake3): `append_jsonl` links each
+//!   record to the one before it, so `verify_chain` can prove a log file
+//!   wasn't edited, reordered, or truncated after the fact.
+
+use anyhow::{Context, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as B64;
+use blake3;
 use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use hex;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 use crate::commands::init::ensure_initialized_once;
-use crate::config::{CoreConfig, PoliciesConfig};
+use crate::config::{CoreConfig, LogbookConfig, PoliciesConfig, RiskAggregation};
+use crate::services::logbook;
 use contracts::assets::{read_verified_or_embedded, write_default_contracts};
 use contracts::{MoralContract, evaluate_input_against_rules};
 // ----------- Logbook paths -----------
@@ -163,6 +175,32 @@ pub fn record_action(agent: &str, action: &str, details: &Value, severity: &str)
     append_jsonl(&log_paths().actions, &entry);
 }
 
+/// Record a structured DB-commit event: `table`/`rowid`/`op` for one row
+/// touched by a write the caller's SQLite connection just committed.
+///
+/// # Side effects
+/// * Appends to `logbook.aggregate` (`logbook.jsonl` by default).
+///
+/// # Notes
+/// Emitted automatically by `Memory`'s (and `TDLearner`'s)
+/// `update_hook`/`commit_hook` when enabled via
+/// [`crate::services::memory::Memory::with_db_event_log`] -- batched per
+/// transaction and only flushed on commit, so a rolled-back write never
+/// produces an event.
+pub fn record_db_event(table: &str, rowid: i64, op: &str) {
+    if !audit_enabled() {
+        return;
+    }
+    let entry = json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "event": "db_change",
+        "table": table,
+        "rowid": rowid,
+        "op": op
+    });
+    append_jsonl(&log_paths().aggregate, &entry);
+}
+
 /// Evaluate a contract via the **contracts** package and **log** the evaluation.
 ///
 /// # Arguments
@@ -212,6 +250,39 @@ pub fn evaluate_and_audit_contract(
     Ok(result_json)
 }
 
+/// Same as [`evaluate_and_audit_contract`], but evaluates a `contract`
+/// supplied in-hand rather than the configured default loaded from disk --
+/// e.g. a contract handed to the runtime directly by a caller that doesn't
+/// want it registered under `contracts.path` first.
+///
+/// # Side effects
+/// * Appends a [`ContractEvalRecord`] to `contracts.jsonl`, same as the
+///   disk-backed variant.
+pub fn evaluate_and_audit_contract_value(
+    meta: &ContractEvalMeta,
+    contract: &MoralContract,
+    message: &str,
+) -> Result<Value, String> {
+    let t0 = std::time::Instant::now();
+    let result_struct = evaluate_input_against_rules(message, contract);
+    let latency = t0.elapsed().as_secs_f64() * 1000.0;
+
+    let result_json = serde_json::to_value(&result_struct).map_err(|e| e.to_string())?;
+    let rec = ContractEvalRecord {
+        timestamp: Utc::now(),
+        kind: meta.kind.clone(),
+        contract_name: meta.contract_name.clone(),
+        input_preview: redact_preview(message),
+        latency_ms: latency,
+        result: result_json.clone(),
+        metadata: meta.metadata.clone(),
+    };
+    if audit_enabled() {
+        append_jsonl(&log_paths().contracts, &rec);
+    }
+    Ok(result_json)
+}
+
 /// Log a normalized ethics decision and, if needed, a violation event.
 ///
 /// # Arguments
@@ -274,28 +345,309 @@ fn ensure_dirs() {
     }
 }
 
-/// Append a single JSON value as a line to a JSONL file.
+// -------------- tamper-evident hash chain --------------
+//
+// A JSONL file is append-only in name only -- anyone who can touch the file
+// directly can still edit a line, reorder lines, or truncate the tail
+// without leaving a trace. `append_jsonl` closes that gap: every object
+// record gets a `prev_hash` (the previous line's `entry_hash`, or
+// `GENESIS_HASH` for the first line) and an `entry_hash = blake3(
+// canonical_json(record_without_entry_hash) || prev_hash)`. Walking the
+// chain with `verify_chain` and recomputing each link catches edits,
+// reordering, and deletions -- the first broken link is where tampering (or
+// corruption) starts.
+
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000"; // 64 hex chars
+
+/// Per-file `prev_hash` cursor, so an append only has to hash its own
+/// record, not rescan the whole file. Seeded lazily the first time a given
+/// path is written, from that file's last line on disk (or `GENESIS_HASH` if
+/// it doesn't exist yet).
+fn chain_heads() -> &'static Mutex<HashMap<PathBuf, String>> {
+    static CELL: OnceCell<Mutex<HashMap<PathBuf, String>>> = OnceCell::new();
+    CELL.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn take_prev_hash(path: &Path) -> String {
+    let mut heads = chain_heads().lock().unwrap();
+    if let Some(hash) = heads.get(path) {
+        return hash.clone();
+    }
+    let seeded = last_entry_hash(path).unwrap_or_else(|| GENESIS_HASH.to_string());
+    heads.insert(path.to_path_buf(), seeded.clone());
+    seeded
+}
+
+fn last_entry_hash(path: &Path) -> Option<String> {
+    let events = logbook::read_events(path).ok()?;
+    let val = events.last()?;
+    val.get("entry_hash")?.as_str().map(str::to_string)
+}
+
+fn chain_entry_hash(canonical_record: &[u8], prev_hash: &str) -> String {
+    blake3::hash([canonical_record, prev_hash.as_bytes()].concat().as_slice()).to_hex().to_string()
+}
+
+/// Walk a hash-chained JSONL file in order, recomputing each `entry_hash`
+/// from its `prev_hash` and the rest of that line's fields. Returns `Ok(())`
+/// if every link is intact, or `Err(index)` with the line number of the
+/// first entry whose `prev_hash`/`entry_hash` no longer matches what the
+/// chain implies -- i.e. the first entry that was edited, reordered, or
+/// inserted out of band.
+pub fn verify_chain(path: &Path) -> std::result::Result<(), usize> {
+    let Ok(events) = logbook::read_events(path) else {
+        return Ok(());
+    };
+    let mut expected_prev = GENESIS_HASH.to_string();
+    for (i, event) in events.into_iter().enumerate() {
+        let Value::Object(mut map) = event else {
+            return Err(i);
+        };
+        let Some(Value::String(entry_hash)) = map.remove("entry_hash") else {
+            return Err(i);
+        };
+        let prev_hash = match map.get("prev_hash") {
+            Some(Value::String(s)) => s.clone(),
+            _ => return Err(i),
+        };
+        if prev_hash != expected_prev {
+            return Err(i);
+        }
+        let canonical = serde_json::to_vec(&Value::Object(map)).unwrap_or_default();
+        let recomputed = chain_entry_hash(&canonical, &prev_hash);
+        if recomputed != entry_hash {
+            return Err(i);
+        }
+        expected_prev = entry_hash;
+    }
+    Ok(())
+}
+
+/// Append a single JSON value as a hash-chained line to a JSONL file (see
+/// the module-level note above `GENESIS_HASH`).
 ///
 /// # Arguments
-/// * `path` — Destination path.
-/// * `val` — Any `Serialize` value.
+/// * `path` — Destination path; also the chain this record links onto.
+/// * `val` — Any `Serialize` value. Chaining only applies to object values;
+///   anything else is written as-is.
 ///
 /// # Returns
 /// Nothing. Creates parent directories if missing; ignores write errors to avoid crashing the caller.
 fn append_jsonl<P: AsRef<std::path::Path>, S: Serialize>(path: P, val: &S) {
     let path = path.as_ref();
-    if let Some(parent) = path.parent() {
-        let _ = fs::create_dir_all(parent);
+    let Ok(mut record) = serde_json::to_value(val) else {
+        return;
+    };
+    if let Value::Object(map) = &mut record {
+        let prev_hash = take_prev_hash(path);
+        map.insert("prev_hash".to_string(), Value::String(prev_hash.clone()));
+        let canonical = serde_json::to_vec(&record).unwrap_or_default();
+        let entry_hash = chain_entry_hash(&canonical, &prev_hash);
+        if let Value::Object(map) = &mut record {
+            map.insert("entry_hash".to_string(), Value::String(entry_hash.clone()));
+        }
+        chain_heads()
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), entry_hash.clone());
+        // Checkpoints are themselves chained entries (to detect tampering
+        // with checkpoints.jsonl itself), but they shouldn't count toward
+        // the *next* checkpoint's coverage -- that would let a seal fold
+        // itself in as its own leaf.
+        if path != log_paths().checkpoints {
+            record_checkpoint_candidate(path, entry_hash);
+        }
     }
-    if let Ok(mut f) = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-    {
-        let _ = writeln!(f, "{}", serde_json::to_string(val).unwrap());
+    write_line(path, &record);
+}
+
+fn write_line<S: Serialize>(path: &Path, val: &S) {
+    let Ok(record) = serde_json::to_value(val) else {
+        return;
+    };
+    let _ = logbook::append_event(path, &record, logbook_config(), audit_config().retention_days);
+}
+
+// -------------- Merkle checkpoints --------------
+//
+// The hash chain above proves a *single* log file wasn't tampered with, but
+// an operator who wants to anchor a whole audit window (possibly spanning
+// several log files) to one fingerprint they can archive or publish needs
+// something smaller: a checkpoint. `seal_logbook` takes every chained entry
+// hash appended since the last checkpoint, builds a Merkle tree over them
+// (pairing adjacent leaves, `blake3(left || right)`, duplicating the last
+// node on an odd level), and writes the root -- plus a link to the previous
+// checkpoint's root and an optional Ed25519 signature -- to
+// `checkpoints.jsonl`. `verify_checkpoint` rebuilds the tree from the
+// checkpoint's own referenced entries and confirms the root and signature
+// without needing the full logs.
+
+/// One entry folded into a checkpoint's Merkle tree: which log it came from
+/// (the logbook file name) and its chain `entry_hash`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointEntryRef {
+    pub log: String,
+    pub entry_hash: String,
+}
+
+/// A sealed checkpoint over every entry appended since the previous one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointRecord {
+    pub timestamp: DateTime<Utc>,
+    pub count: usize,
+    pub prev_checkpoint_root: String,
+    pub merkle_root: String,
+    pub entries: Vec<CheckpointEntryRef>,
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub signing_key_id: Option<String>,
+}
+
+/// Entries chained since the last seal, accumulated by `append_jsonl` and
+/// drained by `seal_logbook`.
+fn pending_checkpoint_entries() -> &'static Mutex<Vec<CheckpointEntryRef>> {
+    static CELL: OnceCell<Mutex<Vec<CheckpointEntryRef>>> = OnceCell::new();
+    CELL.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn record_checkpoint_candidate(path: &Path, entry_hash: String) {
+    let log = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+    let mut pending = pending_checkpoint_entries().lock().unwrap();
+    pending.push(CheckpointEntryRef { log, entry_hash });
+    let interval = audit_config().checkpoint_interval;
+    if interval > 0 && pending.len() >= interval {
+        drop(pending);
+        let _ = seal_logbook();
     }
 }
 
+/// Build a Merkle root over `leaves` (hex-encoded blake3 hashes), pairing
+/// adjacent leaves and hashing `blake3(left || right)` one level at a time;
+/// an odd node out at a level is paired with itself. Returns `GENESIS_HASH`
+/// for an empty leaf set.
+fn merkle_root(leaves: &[String]) -> String {
+    let mut level: Vec<blake3::Hash> = leaves
+        .iter()
+        .filter_map(|h| blake3::Hash::from_hex(h).ok())
+        .collect();
+    if level.is_empty() {
+        return GENESIS_HASH.to_string();
+    }
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity((level.len() + 1) / 2);
+        for pair in level.chunks(2) {
+            let left = pair[0];
+            let right = pair.get(1).copied().unwrap_or(left);
+            let combined = [left.as_bytes().as_slice(), right.as_bytes().as_slice()].concat();
+            next.push(blake3::hash(&combined));
+        }
+        level = next;
+    }
+    level[0].to_hex().to_string()
+}
+
+fn last_checkpoint_root() -> String {
+    let Ok(events) = logbook::read_events(&log_paths().checkpoints) else {
+        return GENESIS_HASH.to_string();
+    };
+    events
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<CheckpointRecord>(v).ok())
+        .last()
+        .map(|c| c.merkle_root)
+        .unwrap_or_else(|| GENESIS_HASH.to_string())
+}
+
+fn load_checkpoint_signing_key() -> Option<SigningKey> {
+    let hex_str = audit_config().checkpoint_signing_key_hex.as_deref()?;
+    let bytes = hex::decode(hex_str.trim_start_matches("ed25519:")).ok()?;
+    let bytes: [u8; 32] = bytes.try_into().ok()?;
+    Some(SigningKey::from_bytes(&bytes))
+}
+
+/// Seal every entry chained since the last checkpoint (or since the
+/// beginning, for the first one) into a new `CheckpointRecord`, appended to
+/// `checkpoints.jsonl`. Also called automatically by `append_jsonl` once
+/// `audit.checkpoint_interval` chained entries have accumulated.
+pub fn seal_logbook() -> Result<CheckpointRecord> {
+    let entries = {
+        let mut pending = pending_checkpoint_entries().lock().unwrap();
+        std::mem::take(&mut *pending)
+    };
+    let leaves: Vec<String> = entries.iter().map(|e| e.entry_hash.clone()).collect();
+    let mut record = CheckpointRecord {
+        timestamp: Utc::now(),
+        count: entries.len(),
+        prev_checkpoint_root: last_checkpoint_root(),
+        merkle_root: merkle_root(&leaves),
+        entries,
+        signature: None,
+        signing_key_id: None,
+    };
+
+    if let Some(signing_key) = load_checkpoint_signing_key() {
+        let msg = serde_json::to_vec(&record).context("serializing checkpoint for signing")?;
+        let signature: Signature = signing_key.sign(&msg);
+        record.signature = Some(B64.encode(signature.to_bytes()));
+        record.signing_key_id = Some(audit_config().checkpoint_signing_key_id.clone());
+    }
+
+    append_jsonl(&log_paths().checkpoints, &record);
+    Ok(record)
+}
+
+/// Find the checkpoint whose `merkle_root` is `root`, rebuild its Merkle
+/// tree from its own referenced entries, and confirm the stored root (and
+/// signature, if any) still matches.
+pub fn verify_checkpoint(root: &str) -> Result<()> {
+    let events = logbook::read_events(&log_paths().checkpoints)
+        .with_context(|| format!("reading {}", log_paths().checkpoints.display()))?;
+    let record = events
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<CheckpointRecord>(v).ok())
+        .find(|c| c.merkle_root == root)
+        .ok_or_else(|| anyhow::anyhow!("no checkpoint with merkle_root {root:?} found"))?;
+
+    let leaves: Vec<String> = record.entries.iter().map(|e| e.entry_hash.clone()).collect();
+    let recomputed = merkle_root(&leaves);
+    if recomputed != record.merkle_root {
+        anyhow::bail!(
+            "merkle root mismatch: stored={} recomputed={}",
+            record.merkle_root,
+            recomputed
+        );
+    }
+
+    if let Some(sig_b64) = &record.signature {
+        let signing_key = load_checkpoint_signing_key().ok_or_else(|| {
+            anyhow::anyhow!(
+                "checkpoint is signed but audit.checkpoint_signing_key_hex is not configured"
+            )
+        })?;
+        let verifying_key = signing_key.verifying_key();
+
+        let mut unsigned = record.clone();
+        unsigned.signature = None;
+        let msg = serde_json::to_vec(&unsigned).context("re-serializing checkpoint")?;
+
+        let sig_bytes = B64
+            .decode(sig_b64)
+            .context("decoding checkpoint signature")?;
+        let sig_bytes: [u8; 64] = sig_bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("checkpoint signature must be 64 bytes"))?;
+        let signature = Signature::from_bytes(&sig_bytes);
+        verifying_key
+            .verify(&msg, &signature)
+            .map_err(|e| anyhow::anyhow!("checkpoint signature invalid: {e}"))?;
+    }
+    Ok(())
+}
+
 /// Produce a privacy-safe preview of an input string for logging.
 ///
 /// # Arguments
@@ -337,6 +689,22 @@ fn policies() -> &'static PoliciesConfig {
     })
 }
 
+fn audit_config() -> &'static crate::config::AuditConfig {
+    static CELL: OnceCell<crate::config::AuditConfig> = OnceCell::new();
+    CELL.get_or_init(|| match ensure_initialized_once() {
+        Ok(report) => report.config.audit.clone(),
+        Err(_) => crate::config::AuditConfig::default(),
+    })
+}
+
+fn logbook_config() -> &'static LogbookConfig {
+    static CELL: OnceCell<LogbookConfig> = OnceCell::new();
+    CELL.get_or_init(|| match ensure_initialized_once() {
+        Ok(report) => report.config.logbook.clone(),
+        Err(_) => LogbookConfig::default(),
+    })
+}
+
 fn audit_enabled() -> bool {
     static CELL: OnceCell<bool> = OnceCell::new();
     *CELL.get_or_init(|| {
@@ -350,6 +718,12 @@ fn preview_len() -> usize {
     policies().log_preview_len
 }
 
+/// `contracts.risk_aggregation` from config, for `ethos::precheck` to fold
+/// violated-rule severities into an effective risk label/score.
+pub(crate) fn risk_aggregation() -> &'static RiskAggregation {
+    &contracts_settings().risk_aggregation
+}
+
 #[derive(Clone)]
 struct LogPaths {
     dir: PathBuf,
@@ -357,6 +731,8 @@ struct LogPaths {
     actions: PathBuf,
     violations: PathBuf,
     contracts: PathBuf,
+    checkpoints: PathBuf,
+    aggregate: PathBuf,
 }
 
 impl LogPaths {
@@ -367,6 +743,8 @@ impl LogPaths {
             actions: cfg.logbook.agent_actions.clone(),
             violations: cfg.logbook.contract_violations.clone(),
             contracts: cfg.logbook.contracts_log.clone(),
+            checkpoints: cfg.logbook.checkpoints_log.clone(),
+            aggregate: cfg.logbook.aggregate.clone(),
         }
     }
 }
@@ -382,6 +760,7 @@ impl Default for LogPaths {
 struct ContractsSettings {
     dir: PathBuf,
     default_contract: String,
+    risk_aggregation: RiskAggregation,
 }
 
 impl ContractsSettings {
@@ -389,6 +768,7 @@ impl ContractsSettings {
         Self {
             dir: cfg.contracts.path.clone(),
             default_contract: cfg.contracts.default_contract.clone(),
+            risk_aggregation: cfg.contracts.risk_aggregation.clone(),
         }
     }
 }
@@ -0,0 +1,277 @@
+// src/services/capability.rs
+//! Capability-scoped tool grants for the ethos/governance runtime.
+//!
+//! `Proposal.tools_requested` used to be pure decoration -- `ContractsDecider`
+//! only ever classified text, never checked whether a proposal was actually
+//! allowed to invoke the tools it named. This gives tool use an enforceable
+//! permission boundary: a [`Capability`] names one tool plus a
+//! [`ResourcePattern`] it's scoped to, matched against `Proposal.intent` --
+//! the closest thing a proposal carries to "what resource is this acting
+//! on" (the same label `govern_text` already passes as e.g.
+//! `"memory_storage"`, `"reflection_update"`).
+//!
+//! Grants are content-addressed (blake3, one small JSON file per grant) --
+//! the same "many small files, no single structure to corrupt" idiom
+//! `dag`'s snapshot nodes use -- and persist under `refs/capabilities/`.
+//! [`attenuate_capability`] derives a narrower grant from an existing one,
+//! recording the parent hash the way a DAG node records its parent link,
+//! and refuses a derived capability that widens scope or drops a
+//! constraint the parent already carried.
+//!
+//! MVP scope: there's no revoke path, only narrowing via attenuation, and
+//! `is_subset_of`/constraint-narrowing checks are structural (pattern
+//! segments, key presence) rather than semantic -- a constraint value could
+//! still be replaced with something less restrictive under the same key.
+//! Good enough to stop an ungranted tool from running; not a full
+//! capability calculus.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::commands::init::ensure_initialized_once;
+use crate::utils::path as pathutil;
+
+fn capabilities_dir() -> Result<PathBuf> {
+    let p = ensure_initialized_once()?
+        .root
+        .join("refs")
+        .join("capabilities");
+    std::fs::create_dir_all(&p)?;
+    Ok(p)
+}
+
+fn grant_path(hash: &str) -> Result<PathBuf> {
+    Ok(capabilities_dir()?.join(format!("{}.json", hash)))
+}
+
+fn active_index_path() -> Result<PathBuf> {
+    Ok(capabilities_dir()?.join("active.json"))
+}
+
+fn write_atomic(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
+    let root = ensure_initialized_once()?.root.clone();
+    let root = root.canonicalize().unwrap_or(root);
+    let _ = pathutil::assert_within_root_abs(&root, path)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// A glob-like scope pattern matched segment-by-segment against a `/`
+/// separated resource string (e.g. the proposal's `intent`), with `*`
+/// matching exactly one segment, or matching anything when it's the whole
+/// pattern.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResourcePattern(pub String);
+
+impl ResourcePattern {
+    pub fn matches(&self, resource: &str) -> bool {
+        if self.0 == "*" {
+            return true;
+        }
+        let pat_segs: Vec<&str> = self.0.split('/').collect();
+        let res_segs: Vec<&str> = resource.split('/').collect();
+        if pat_segs.len() != res_segs.len() {
+            return false;
+        }
+        pat_segs
+            .iter()
+            .zip(res_segs.iter())
+            .all(|(p, r)| *p == "*" || p == r)
+    }
+
+    /// True if every resource `self` matches, `other` also matches -- i.e.
+    /// narrowing `other` down to `self` only restricts, never widens.
+    fn is_subset_of(&self, other: &ResourcePattern) -> bool {
+        if other.0 == "*" {
+            return true;
+        }
+        if self.0 == "*" {
+            return false;
+        }
+        let self_segs: Vec<&str> = self.0.split('/').collect();
+        let other_segs: Vec<&str> = other.0.split('/').collect();
+        if self_segs.len() != other_segs.len() {
+            return false;
+        }
+        self_segs
+            .iter()
+            .zip(other_segs.iter())
+            .all(|(s, o)| *o == "*" || s == o)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capability {
+    pub tool: String,
+    pub scope: ResourcePattern,
+    pub constraints: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityGrant {
+    pub hash: String,
+    pub capability: Capability,
+    /// Hash of the capability this was attenuated from, if any.
+    pub parent: Option<String>,
+}
+
+fn grant_hash(capability: &Capability, parent: Option<&str>) -> Result<String> {
+    let canonical = serde_json::json!({
+        "tool": capability.tool,
+        "scope": capability.scope.0,
+        "constraints": capability.constraints,
+        "parent": parent,
+    });
+    Ok(blake3::hash(serde_json::to_vec(&canonical)?.as_slice())
+        .to_hex()
+        .to_string())
+}
+
+fn read_active_hashes() -> Result<Vec<String>> {
+    let p = active_index_path()?;
+    if !p.exists() {
+        return Ok(Vec::new());
+    }
+    let bytes = std::fs::read(&p)?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+fn append_active_hash(hash: &str) -> Result<()> {
+    let mut hashes = read_active_hashes()?;
+    if !hashes.iter().any(|h| h == hash) {
+        hashes.push(hash.to_string());
+        write_atomic(&active_index_path()?, &serde_json::to_vec_pretty(&hashes)?)?;
+    }
+    Ok(())
+}
+
+fn load_grant(hash: &str) -> Result<CapabilityGrant> {
+    let bytes = std::fs::read(grant_path(hash)?)
+        .map_err(|_| anyhow!("no capability grant recorded for hash: {}", hash))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Record a brand-new, unattenuated capability grant and mark it active.
+/// Idempotent: granting the same (tool, scope, constraints) twice returns
+/// the same hash rather than creating a duplicate file.
+pub fn grant_capability(tool: &str, scope: &str, constraints: Value) -> Result<String> {
+    let capability = Capability {
+        tool: tool.to_string(),
+        scope: ResourcePattern(scope.to_string()),
+        constraints,
+    };
+    let hash = grant_hash(&capability, None)?;
+    let grant = CapabilityGrant {
+        hash: hash.clone(),
+        capability,
+        parent: None,
+    };
+    write_atomic(&grant_path(&hash)?, &serde_json::to_vec_pretty(&grant)?)?;
+    append_active_hash(&hash)?;
+    Ok(hash)
+}
+
+/// Derive a narrower capability from an existing grant: the new scope must
+/// be a subset of the parent's, and every constraint key the parent carried
+/// must still be present in the result -- attenuation only adds
+/// restrictions, it never removes one the parent already imposed. Returns
+/// the new grant's hash.
+pub fn attenuate_capability(
+    parent_hash: &str,
+    scope: Option<&str>,
+    constraints: Option<Value>,
+) -> Result<String> {
+    let parent = load_grant(parent_hash)?;
+
+    let new_scope = match scope {
+        Some(s) => ResourcePattern(s.to_string()),
+        None => parent.capability.scope.clone(),
+    };
+    if !new_scope.is_subset_of(&parent.capability.scope) {
+        return Err(anyhow!(
+            "attenuated scope '{}' is not narrower than parent scope '{}'",
+            new_scope.0,
+            parent.capability.scope.0
+        ));
+    }
+
+    let new_constraints = match constraints {
+        Some(Value::Object(incoming)) => {
+            let mut merged = match &parent.capability.constraints {
+                Value::Object(base) => base.clone(),
+                _ => serde_json::Map::new(),
+            };
+            for (k, v) in incoming {
+                merged.insert(k, v);
+            }
+            Value::Object(merged)
+        }
+        Some(other) => other,
+        None => parent.capability.constraints.clone(),
+    };
+    if let (Value::Object(base), Value::Object(merged)) =
+        (&parent.capability.constraints, &new_constraints)
+    {
+        for k in base.keys() {
+            if !merged.contains_key(k) {
+                return Err(anyhow!(
+                    "attenuated capability dropped constraint '{}' the parent required",
+                    k
+                ));
+            }
+        }
+    }
+
+    let capability = Capability {
+        tool: parent.capability.tool.clone(),
+        scope: new_scope,
+        constraints: new_constraints,
+    };
+    let hash = grant_hash(&capability, Some(parent_hash))?;
+    let grant = CapabilityGrant {
+        hash: hash.clone(),
+        capability,
+        parent: Some(parent_hash.to_string()),
+    };
+    write_atomic(&grant_path(&hash)?, &serde_json::to_vec_pretty(&grant)?)?;
+    append_active_hash(&hash)?;
+    Ok(hash)
+}
+
+/// Every grant recorded as active (granted or attenuated). No revoke path
+/// exists yet, so this only grows.
+pub fn active_grants() -> Result<Vec<CapabilityGrant>> {
+    read_active_hashes()?
+        .into_iter()
+        .map(|h| load_grant(&h))
+        .collect()
+}
+
+/// Is `tool` covered by some active grant scoped to `resource`?
+pub fn is_covered(tool: &str, resource: &str) -> Result<bool> {
+    Ok(active_grants()?
+        .iter()
+        .any(|g| g.capability.tool == tool && g.capability.scope.matches(resource)))
+}
+
+/// Every requested tool not covered by an active grant scoped to
+/// `resource`, in request order.
+pub fn unsatisfied(tools: &[String], resource: &str) -> Result<Vec<String>> {
+    let grants = active_grants()?;
+    Ok(tools
+        .iter()
+        .filter(|t| {
+            !grants
+                .iter()
+                .any(|g| &g.capability.tool == *t && g.capability.scope.matches(resource))
+        })
+        .cloned()
+        .collect())
+}
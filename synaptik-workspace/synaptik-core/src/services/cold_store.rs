@@ -0,0 +1,198 @@
+// src/services/cold_store.rs
+//! `ColdStore`: pluggable backend for archived (cold-tier) blobs. The
+//! historical behavior is [`Archivist`]'s local filesystem store; `S3ColdStore`
+//! lets a deployment point cold recall at a remote S3-compatible bucket
+//! instead, while the hot (`Memory`) and DAG tiers stay local. CID
+//! computation (`blake3(bytes)`, hex) is identical across backends, so the
+//! bucket key equals the same content hash `Archivist` would have used --
+//! a blob archived locally is retrievable from S3 after a migration, and
+//! vice versa.
+
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::config::ColdStoreKind;
+use crate::services::archivist::{Archivist, IntegrityError, hash_streamed};
+
+/// Cold-tier object store: content-addressed by CID (`blake3(bytes)`, hex).
+/// Implementations must be safe to share across threads -- `recall_many_parallel`
+/// hands a cloned `Arc<dyn ColdStore>` into its worker pool, same as it does
+/// today with `Archivist`.
+pub trait ColdStore: fmt::Debug + Send + Sync {
+    /// Store `bytes` under their content hash and return the CID.
+    fn archive(&self, memory_id: &str, bytes: &[u8]) -> Result<String>;
+    /// Fetch bytes by CID. Implementations should verify the content hash
+    /// matches `cid` before returning, the way `Archivist::retrieve` does.
+    fn retrieve(&self, cid: &str) -> Result<Vec<u8>>;
+    /// Cheaply check whether `cid` is present, without fetching its bytes.
+    fn exists(&self, cid: &str) -> Result<bool>;
+    /// Verify `cid`'s blob still hashes to `cid`, without returning its
+    /// bytes -- the cheap fsck path `dag_scrub` walks over every referenced
+    /// CID, so implementations should stream rather than buffer.
+    fn verify(&self, cid: &str) -> Result<()>;
+}
+
+impl ColdStore for Archivist {
+    fn archive(&self, memory_id: &str, bytes: &[u8]) -> Result<String> {
+        Archivist::archive(self, memory_id, bytes)
+    }
+
+    fn retrieve(&self, cid: &str) -> Result<Vec<u8>> {
+        Archivist::retrieve(self, cid)
+    }
+
+    fn exists(&self, cid: &str) -> Result<bool> {
+        Archivist::exists(self, cid)
+    }
+
+    fn verify(&self, cid: &str) -> Result<()> {
+        Archivist::verify(self, cid)
+    }
+}
+
+/// Build the configured cold-store backend. `archive_path` is only used for
+/// [`ColdStoreKind::Filesystem`] (it's where `Archivist` keeps its blobs and
+/// its memid index); `S3` ignores it entirely.
+pub fn build_cold_store(archive_path: &Path, kind: &ColdStoreKind) -> Result<Arc<dyn ColdStore>> {
+    match kind {
+        ColdStoreKind::Filesystem => Ok(Arc::new(Archivist::open(archive_path)?)),
+        ColdStoreKind::S3 {
+            bucket,
+            endpoint,
+            prefix,
+            region,
+        } => Ok(Arc::new(S3ColdStore::new(
+            bucket.clone(),
+            endpoint.clone(),
+            prefix.clone(),
+            region.clone(),
+        ))),
+    }
+}
+
+/// S3-compatible cold storage: `bucket`/`prefix`/`cid` as the object key, so
+/// the same BLAKE3 CID `Archivist` would compute locally addresses the same
+/// object remotely.
+///
+/// This does **not** implement full AWS SigV4 request signing -- this crate
+/// has no HMAC/SHA-256 dependency to build that on. It speaks the plain S3
+/// REST verbs (`PUT`/`GET`/`HEAD` on `{endpoint}/{bucket}/{key}`) and, if
+/// `SYNAPTIK_S3_TOKEN` is set, sends it as a bearer `Authorization` header --
+/// enough for an S3-compatible endpoint fronted by a signing proxy, or one
+/// configured for static-token auth (e.g. a private MinIO gateway). Pointing
+/// this at unsigned AWS S3 requires a bucket policy that allows it, or a
+/// proxy in front that adds SigV4 signing.
+pub struct S3ColdStore {
+    bucket: String,
+    endpoint: String,
+    prefix: Option<String>,
+    region: String,
+}
+
+impl fmt::Debug for S3ColdStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3ColdStore")
+            .field("bucket", &self.bucket)
+            .field("endpoint", &self.endpoint)
+            .field("prefix", &self.prefix)
+            .field("region", &self.region)
+            .finish()
+    }
+}
+
+impl S3ColdStore {
+    pub fn new(bucket: String, endpoint: String, prefix: Option<String>, region: String) -> Self {
+        Self {
+            bucket,
+            endpoint,
+            prefix,
+            region,
+        }
+    }
+
+    fn key(&self, cid: &str) -> String {
+        match &self.prefix {
+            Some(p) if !p.is_empty() => format!("{p}/{cid}"),
+            _ => cid.to_string(),
+        }
+    }
+
+    fn object_url(&self, cid: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.endpoint.trim_end_matches('/'),
+            self.bucket,
+            self.key(cid)
+        )
+    }
+
+    fn authed(&self, req: ureq::Request) -> ureq::Request {
+        let req = req.set("x-amz-region", &self.region);
+        match std::env::var("SYNAPTIK_S3_TOKEN") {
+            Ok(token) if !token.is_empty() => req.set("Authorization", &format!("Bearer {token}")),
+            _ => req,
+        }
+    }
+}
+
+impl ColdStore for S3ColdStore {
+    fn archive(&self, _memory_id: &str, bytes: &[u8]) -> Result<String> {
+        let cid = blake3::hash(bytes).to_hex().to_string();
+        let url = self.object_url(&cid);
+        self.authed(ureq::put(&url))
+            .send_bytes(bytes)
+            .map_err(|e| anyhow!("S3 archive PUT failed for {url}: {e}"))?;
+        Ok(cid)
+    }
+
+    fn retrieve(&self, cid: &str) -> Result<Vec<u8>> {
+        let url = self.object_url(cid);
+        let resp = self
+            .authed(ureq::get(&url))
+            .call()
+            .map_err(|e| anyhow!("S3 retrieve GET failed for {url}: {e}"))?;
+        let (bytes, actual) = hash_streamed(resp.into_reader(), true)
+            .map_err(|e| anyhow!("S3 retrieve read failed for {url}: {e}"))?;
+        let bytes = bytes.expect("hash_streamed(_, true) always returns bytes");
+
+        if actual != cid {
+            return Err(IntegrityError {
+                cid: cid.to_string(),
+                expected: cid.to_string(),
+                actual,
+            }
+            .into());
+        }
+        Ok(bytes)
+    }
+
+    fn exists(&self, cid: &str) -> Result<bool> {
+        let url = self.object_url(cid);
+        match self.authed(ureq::head(&url)).call() {
+            Ok(_) => Ok(true),
+            Err(ureq::Error::Status(404, _)) => Ok(false),
+            Err(e) => Err(anyhow!("S3 exists HEAD failed for {url}: {e}")),
+        }
+    }
+
+    fn verify(&self, cid: &str) -> Result<()> {
+        let url = self.object_url(cid);
+        let resp = self
+            .authed(ureq::get(&url))
+            .call()
+            .map_err(|e| anyhow!("S3 verify GET failed for {url}: {e}"))?;
+        let (_, actual) = hash_streamed(resp.into_reader(), false)
+            .map_err(|e| anyhow!("S3 verify read failed for {url}: {e}"))?;
+        if actual != cid {
+            return Err(IntegrityError {
+                cid: cid.to_string(),
+                expected: cid.to_string(),
+                actual,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
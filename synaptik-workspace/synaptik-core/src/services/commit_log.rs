@@ -0,0 +1,181 @@
+//! services/commit_log.rs
+//! Write-ahead log for the stream runtime: durably records each
+//! `(Proposal, RuntimeDecision, Finalized)` triple *before*
+//! [`crate::services::memory::commit_snapshot`] applies it to the DAG, so a
+//! crash between the two can neither lose a commit (it's still in the log,
+//! unapplied) nor double-apply one (the log remembers what's already
+//! applied).
+//!
+//! Backed by a dedicated `ContractsStore` rather than a bespoke file format
+//! -- mirrors the existing `caps_store` used for capsule evaluation (see
+//! `memory::contracts_store`). Each record becomes a `SimCapsule`, giving it
+//! durable, content-hashed storage for free via
+//! `ContractsStore::ingest_capsule`; the store's hash-chained annotation log
+//! on a single well-known id then tracks sequencing and apply-state, via
+//! `annotate_tentative`/`commit_tentative`/`tentative_since`.
+
+use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use serde_json::json;
+
+use crate::services::ethos::{Proposal, RuntimeDecision};
+use crate::services::streamgate::Finalized;
+use contracts::api::{CapsAnnot, CapsId, Verdict};
+use contracts::capsule::SimCapsule;
+use contracts::store::ContractsStore;
+
+/// Fixed id whose annotation chain tracks sequencing/apply-state for every
+/// record appended to the log; the records themselves live as separate
+/// capsules, referenced from each annotation's `labels[0]`.
+const LOG_INDEX_ID: &str = "commit-log";
+
+/// A durably-appended record that has not yet been marked applied, as
+/// surfaced by [`CommitLog::replay_from`].
+pub struct PendingRecord {
+    pub seq: u64,
+    pub proposal: Proposal,
+    pub decision: RuntimeDecision,
+    pub finalized: Finalized,
+}
+
+pub struct CommitLog {
+    store: ContractsStore,
+}
+
+impl CommitLog {
+    pub fn open<P: AsRef<std::path::Path>>(root: P) -> Result<Self> {
+        Ok(Self {
+            store: ContractsStore::new(root)?,
+        })
+    }
+
+    /// Durably append `(proposal, decision, finalized)` ahead of applying it.
+    /// Returns the assigned sequence number; pass it to
+    /// [`CommitLog::mark_applied`] once `commit_snapshot` has run.
+    pub fn append(
+        &self,
+        proposal: &Proposal,
+        decision: &RuntimeDecision,
+        finalized: &Finalized,
+    ) -> Result<u64> {
+        let now_ms = now_ms();
+        let mut cap = SimCapsule::default();
+        cap.context = json!({
+            "proposal": proposal,
+            "decision": decision,
+            "finalized": finalized,
+        });
+        cap.meta.lobe = Some("commit_log".to_string());
+        cap.meta.t_start_ms = now_ms;
+        cap.meta.t_end_ms = now_ms;
+
+        // `ingest_capsule` stamps `capsule_hash` as a blake3 digest of the
+        // (hash-field-stripped) canonical JSON, so `handle.hash` already is
+        // "blake3 of the payload" -- no need to hash it again here.
+        let handle = self.store.ingest_capsule(cap)?;
+
+        let annot = CapsAnnot {
+            verdict: Verdict::Quarantine, // pending apply
+            risk: 0.0,
+            labels: vec![handle.id, handle.hash],
+            policy_ver: "commit_log/v1".to_string(),
+            patch_id: None,
+            ts_ms: now_ms,
+        };
+        self.store
+            .annotate_tentative(&LOG_INDEX_ID.to_string(), &annot)
+    }
+
+    /// Mark the record appended at `seq` as applied, so `replay_from` skips
+    /// it on future restarts.
+    pub fn mark_applied(&self, seq: u64) -> Result<()> {
+        self.store.commit_tentative(&LOG_INDEX_ID.to_string(), seq)
+    }
+
+    /// Every record appended at or after `since_seq` that has not yet been
+    /// marked applied, oldest first -- re-apply these (via
+    /// `memory::commit_snapshot` + `mark_applied`) on startup to recover from
+    /// a crash between append and apply.
+    pub fn replay_from(&self, since_seq: u64) -> Result<Vec<PendingRecord>> {
+        self.store
+            .tentative_since(&LOG_INDEX_ID.to_string(), since_seq)?
+            .into_iter()
+            .map(|(seq, annot)| self.load_record(seq, &annot))
+            .collect()
+    }
+
+    fn load_record(&self, seq: u64, annot: &CapsAnnot) -> Result<PendingRecord> {
+        let caps_id: &CapsId = annot
+            .labels
+            .first()
+            .with_context(|| format!("commit log entry {seq} missing capsule id"))?;
+        let cap = self
+            .store
+            .load_capsule(caps_id)?
+            .with_context(|| format!("commit log entry {seq}: capsule {caps_id} missing"))?;
+        let proposal: Proposal = serde_json::from_value(cap.context["proposal"].clone())
+            .with_context(|| format!("commit log entry {seq}: parse proposal"))?;
+        let decision: RuntimeDecision = serde_json::from_value(cap.context["decision"].clone())
+            .with_context(|| format!("commit log entry {seq}: parse decision"))?;
+        let finalized: Finalized = serde_json::from_value(cap.context["finalized"].clone())
+            .with_context(|| format!("commit log entry {seq}: parse finalized"))?;
+        Ok(PendingRecord {
+            seq,
+            proposal,
+            decision,
+            finalized,
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// `OnceCell`-cached singleton, mirroring `memory::contracts_store`: caches
+/// only successful initialization (failed attempts don't poison the cell,
+/// allowing retries) and replays any pending records on first use so crash
+/// recovery is automatic.
+pub fn commit_log() -> Option<&'static CommitLog> {
+    static CELL: OnceCell<CommitLog> = OnceCell::new();
+    if let Some(log) = CELL.get() {
+        return Some(log);
+    }
+    let root_dir = match crate::commands::init::ensure_initialized_once() {
+        Ok(r) => r.config.contracts.path.join("commit_log"),
+        Err(_) => return None,
+    };
+    let log = match CommitLog::open(root_dir) {
+        Ok(log) => log,
+        Err(_) => return None,
+    };
+    // Replay anything left pending by a crash between a prior append and its
+    // apply. Best-effort: a replay failure shouldn't block this session's
+    // own commits, so it's logged via audit rather than propagated.
+    if let Ok(pending) = log.replay_from(0) {
+        for record in pending {
+            match crate::services::memory::commit_snapshot(
+                &record.proposal,
+                &record.decision,
+                &record.finalized,
+            ) {
+                Ok(_) => {
+                    let _ = log.mark_applied(record.seq);
+                }
+                Err(e) => {
+                    crate::services::audit::record_action(
+                        "commitlog",
+                        "replay_failed",
+                        &json!({ "seq": record.seq, "error": e.to_string() }),
+                        "medium",
+                    );
+                }
+            }
+        }
+    }
+    let _ = CELL.set(log);
+    CELL.get()
+}
@@ -1,5 +1,9 @@
 // src/services/compactor.rs
+use std::time::{Duration, Instant};
+
 use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::config::{CompactionPolicy, SummarizerKind};
@@ -15,6 +19,32 @@ use contracts::api::CapsAnnot;
 use contracts::store::ContractsStore;
 use once_cell::sync::OnceCell;
 
+/// Pluggable summarization backend for `Compactor::invoke_summarizer`, so a
+/// remote/LLM summarizer can eventually sit behind the same retry/backoff
+/// plumbing without `Compactor` itself changing. Mirrors the blocking/async
+/// client split already used by `streamgate::LlmClient` /
+/// `async_streamgate::AsyncLlmClient`.
+pub trait Summarizer {
+    fn summarize(&self, kind: SummarizerKind, text: &str) -> Result<String>;
+
+    /// Non-blocking counterpart for async callers; defaults to delegating to
+    /// the blocking path so implementations only need to override this when
+    /// they have a genuinely async backend to call.
+    #[cfg(feature = "async_runtime")]
+    async fn summarize_async(&self, kind: SummarizerKind, text: &str) -> Result<String> {
+        self.summarize(kind, text)
+    }
+}
+
+/// Default [`Summarizer`] backed by [`Memory::summarize`]'s local heuristics.
+pub struct MemorySummarizer<'a>(pub &'a Memory);
+
+impl<'a> Summarizer for MemorySummarizer<'a> {
+    fn summarize(&self, kind: SummarizerKind, text: &str) -> Result<String> {
+        self.0.summarize(kind, text)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CompactionReport {
     pub lobe: String,
@@ -23,6 +53,10 @@ pub struct CompactionReport {
     pub archived: usize,
     pub compressed: usize,   // summarized & replaced when not dry_run
     pub regrets: usize,      // failed ethos / rejected summaries
+    /// Candidates that were compacted independently on two branches since
+    /// their fork and collapsed into one bind node by
+    /// [`Compactor::compact_across_branches`]; `0` outside that path.
+    pub merged: usize,
     pub notes: Vec<String>,
 }
 
@@ -35,11 +69,23 @@ impl Default for CompactionReport {
             archived: 0,
             compressed: 0,
             regrets: 0,
+            merged: 0,
             notes: vec![],
         }
     }
 }
 
+/// One prior compaction outcome found on a branch's archived-cid lineage:
+/// enough of the derived [`CapsAnnot`]/[`SimCapsule`] to compare two
+/// independent summarizations of the same key and pick a winner.
+struct LineageCompaction {
+    key: String,
+    cid: String,
+    risk: f32,
+    labels: Vec<String>,
+    summary: String,
+}
+
 pub struct Compactor<'a> {
     pub memory: &'a Memory,
     pub pons: Option<&'a PonsStore>,
@@ -100,6 +146,331 @@ impl<'a> Compactor<'a> {
         Ok(report)
     }
 
+    /// LSM-style leveled compaction over `lobe`'s already-archived DAG
+    /// chain -- distinct from [`Self::compact_lobe`], which archives and
+    /// summarizes *live* `memories` rows. `promote_to_dag`/
+    /// `promote_all_hot_in_lobe` only ever append one L0 node per promoted
+    /// record, so a long-lived lobe accumulates one DAG node per record
+    /// forever; this merges them upward instead. Once a level holds
+    /// `policy.dag_level_max_nodes` distinct nodes, the oldest
+    /// `policy.dag_merge_fanout` of them are concatenated, summarized
+    /// through the normal summarizer pipeline, and written as one new node
+    /// a level up whose `meta` records the covered child CIDs and
+    /// `[min_created_at, max_created_at]`. Every row that pointed at a
+    /// merged child is repointed to the new node's CID in a single
+    /// transaction ([`Memory::repoint_archived_cid`]), so a crash mid-merge
+    /// never leaves a row's `archived_cid` dangling on an orphaned node.
+    /// Idempotent: re-running finds nothing to merge once every level is
+    /// back under its threshold, and an unfinished remainder (fewer than
+    /// `dag_merge_fanout` nodes left over) is simply left for the next run.
+    pub fn compact_dag_level(&self, lobe: &str, policy: &CompactionPolicy) -> Result<CompactionReport> {
+        let mut report = CompactionReport {
+            lobe: lobe.to_string(),
+            dry_run: false,
+            ..Default::default()
+        };
+
+        let max_nodes = policy.dag_level_max_nodes.max(2);
+        let fanout = (policy.dag_merge_fanout.max(2) as usize).min(max_nodes as usize);
+        let by_level = self.memory.archived_cids_by_level(lobe)?;
+        report.candidates = by_level.values().map(Vec::len).sum();
+
+        for (level, cids) in by_level {
+            if (cids.len() as u32) < max_nodes {
+                report.notes.push(format!(
+                    "level {}: {} node(s), below threshold {} -- nothing to merge",
+                    level,
+                    cids.len(),
+                    max_nodes
+                ));
+                continue;
+            }
+            for chunk in cids.chunks(fanout) {
+                if chunk.len() < 2 {
+                    report.notes.push(format!(
+                        "level {}: leftover {} node(s) below fanout {} -- left for next run",
+                        level,
+                        chunk.len(),
+                        fanout
+                    ));
+                    continue;
+                }
+                match self.merge_dag_nodes(lobe, level, chunk, policy, &mut report) {
+                    Ok(repointed) => {
+                        report.archived += 1;
+                        report.merged += repointed;
+                    }
+                    Err(e) => report.notes.push(format!(
+                        "level {} merge of {} node(s) failed: {}",
+                        level,
+                        chunk.len(),
+                        e
+                    )),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Merge `children` (distinct DAG node CIDs, all at `level`) into one
+    /// new node at `level + 1`: concatenate their content, summarize it,
+    /// write the merged node, then repoint every row that referenced any of
+    /// `children` at the new CID. Returns how many rows were repointed.
+    fn merge_dag_nodes(
+        &self,
+        lobe: &str,
+        level: u32,
+        children: &[String],
+        policy: &CompactionPolicy,
+        report: &mut CompactionReport,
+    ) -> Result<usize> {
+        let mut states = Vec::with_capacity(children.len());
+        for cid in children {
+            states.push(crate::memory::dag::recall_snapshot(cid)?);
+        }
+
+        let created_at_of = |s: &crate::memory::dag::MemoryState| {
+            s.meta.get("created_at").and_then(|v| v.as_str()).unwrap_or("")
+        };
+        let min_created_at = states.iter().map(created_at_of).min().unwrap_or("").to_string();
+        let max_created_at = states.iter().map(created_at_of).max().unwrap_or("").to_string();
+        let key = states[0]
+            .meta
+            .get("key")
+            .and_then(|v| v.as_str())
+            .unwrap_or("default")
+            .to_string();
+
+        let concatenated = states
+            .iter()
+            .map(|s| s.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
+        let kind = self.choose_summarizer(policy);
+        let merge_id = format!("{}::dag-compact::L{}::{}", lobe, level + 1, children[0]);
+        let summary = self.invoke_summarizer(&merge_id, &concatenated, kind, policy, report);
+
+        let meta = serde_json::json!({
+            "lobe": lobe,
+            "key": key,
+            "level": level + 1,
+            "merged_children": children,
+            "min_created_at": min_created_at,
+            "max_created_at": max_created_at,
+            "summary_len": summary.len(),
+        });
+
+        let new_cid = crate::memory::dag::save_node(&merge_id, &summary, &meta, children)?;
+
+        let mut source_rows = Vec::new();
+        for cid in children {
+            source_rows.extend(self.memory.memory_ids_for_archived_cid(lobe, cid)?);
+        }
+        self.memory.repoint_archived_cid(&source_rows, &new_cid)?;
+        Ok(source_rows.len())
+    }
+
+    /// Compact two lobes that diverged from a shared origin (e.g. a `main`
+    /// lobe and a `feature` lobe branched off it via
+    /// [`Commands::branch`](crate::commands::Commands::branch)) while staying
+    /// aware that both sides may have already compacted the same memory on
+    /// their own. Finds the LCA of the two branches' archived-cid heads
+    /// (same `bind_base`/`lowest_common_ancestor` machinery
+    /// [`Commands::reconsolidate_paths`](crate::commands::Commands::reconsolidate_paths)
+    /// uses to bind replay paths), reconciles any key compacted
+    /// independently on both sides since the fork into a single two-parent
+    /// bind node, then runs the normal [`Self::compact_lobe`] pass on each
+    /// side for whatever is still un-compacted. Idempotent: once a pair is
+    /// reconciled the merge node becomes that key's shared ancestor, so a
+    /// later run (e.g. right before the branches are themselves bound
+    /// together) finds nothing left to merge.
+    pub fn compact_across_branches(
+        &self,
+        main: &str,
+        feature: &str,
+        policy: &CompactionPolicy,
+    ) -> Result<CompactionReport> {
+        let mut report = CompactionReport {
+            lobe: format!("{}+{}", main, feature),
+            dry_run: false,
+            ..Default::default()
+        };
+
+        if let Err(e) = self.reconcile_branch_lineages(main, feature, &mut report) {
+            report.notes.push(format!("branch reconciliation skipped: {}", e));
+        }
+
+        for (lobe, side) in [(main, "main"), (feature, "feature")] {
+            let side_report = self.compact_lobe(lobe, policy, false)?;
+            report.candidates += side_report.candidates;
+            report.archived += side_report.archived;
+            report.compressed += side_report.compressed;
+            report.regrets += side_report.regrets;
+            report
+                .notes
+                .extend(side_report.notes.into_iter().map(|n| format!("[{}:{}] {}", side, lobe, n)));
+        }
+
+        Ok(report)
+    }
+
+    /// Find the fork point of `main` and `feature`'s archived-cid chains and
+    /// collapse any key compacted independently on both sides since that
+    /// fork into one bind node, recording which keys were reconciled vs.
+    /// left freshly-compacted-on-one-side in `report.notes`.
+    fn reconcile_branch_lineages(
+        &self,
+        main: &str,
+        feature: &str,
+        report: &mut CompactionReport,
+    ) -> Result<()> {
+        let main_head = match self.memory.latest_archived_cid_in_lobe_public(main)? {
+            Some(h) => h,
+            None => {
+                report.notes.push(format!("'{}' has no archived history yet; nothing to reconcile", main));
+                return Ok(());
+            }
+        };
+        let feature_head = match self.memory.latest_archived_cid_in_lobe_public(feature)? {
+            Some(h) => h,
+            None => {
+                report.notes.push(format!("'{}' has no archived history yet; nothing to reconcile", feature));
+                return Ok(());
+            }
+        };
+        if main_head == feature_head {
+            report
+                .notes
+                .push(format!("'{}' and '{}' share the same archived head; nothing to reconcile", main, feature));
+            return Ok(());
+        }
+
+        let lca = crate::memory::dag::bind_base(&main_head, &feature_head)?.into_iter().next();
+        let lca = match lca {
+            Some(l) => l,
+            None => {
+                report.notes.push(format!(
+                    "no common ancestor between '{}' and '{}'; compacting independently",
+                    main, feature
+                ));
+                return Ok(());
+            }
+        };
+
+        let main_lineage = self.lineage_since(&main_head, &lca)?;
+        let mut feature_by_key: std::collections::HashMap<String, LineageCompaction> = self
+            .lineage_since(&feature_head, &lca)?
+            .into_iter()
+            .map(|c| (c.key.clone(), c))
+            .collect();
+
+        for main_compaction in main_lineage {
+            let Some(feature_compaction) = feature_by_key.remove(&main_compaction.key) else {
+                continue; // compacted only on `main` since the fork -- freshly compacted, nothing to reconcile
+            };
+
+            let (winner, winner_lobe) = if feature_compaction.risk < main_compaction.risk {
+                (&feature_compaction, feature)
+            } else {
+                (&main_compaction, main)
+            };
+
+            let mut labels = main_compaction.labels.clone();
+            labels.extend(feature_compaction.labels.iter().cloned());
+            labels.sort();
+            labels.dedup();
+
+            let merge_meta = serde_json::json!({
+                "op": "compaction_merge",
+                "lobe": winner_lobe,
+                "key": main_compaction.key,
+                "kept_verdict_from": winner_lobe,
+                "labels": labels,
+                "reconciled_cids": [main_compaction.cid, feature_compaction.cid],
+            });
+            crate::memory::dag::save_node(
+                &format!("{}::merged", main_compaction.key),
+                &winner.summary,
+                &merge_meta,
+                &[main_compaction.cid.clone(), feature_compaction.cid.clone()],
+            )?;
+
+            report.merged += 1;
+            report.notes.push(format!(
+                "reconciled '{}' (main {} vs feature {}): kept {}'s lower-risk verdict, unioned {} label(s)",
+                main_compaction.key,
+                short_cid(&main_compaction.cid),
+                short_cid(&feature_compaction.cid),
+                winner_lobe,
+                labels.len(),
+            ));
+        }
+
+        if !feature_by_key.is_empty() {
+            let mut keys: Vec<&str> = feature_by_key.keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            report.notes.push(format!(
+                "{} key(s) compacted only on '{}' since the fork: {}",
+                keys.len(),
+                feature,
+                keys.join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Walk a branch's archived-cid chain backward from `head` up to (not
+    /// including) `stop_at`, pulling each node's key and its derived
+    /// capsule's risk/labels/summary so two branches' histories can be
+    /// compared key-by-key.
+    fn lineage_since(&self, head: &str, stop_at: &str) -> Result<Vec<LineageCompaction>> {
+        let nodes = crate::memory::dag::walk_for_dot(None, Some(head), usize::MAX)?;
+        let store = contracts_store();
+
+        let mut out = Vec::new();
+        for node in nodes {
+            if node.hash == stop_at {
+                break;
+            }
+            let meta = match crate::memory::dag::snapshot_meta(&node.hash) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let Some(key) = meta.get("key").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let capsule_id = meta.get("capsule_id").and_then(|v| v.as_str());
+
+            let (risk, labels, summary) = match (store, capsule_id) {
+                (Some(store), Some(cid)) => {
+                    let annot = store.latest_annotation(&cid.to_string()).ok().flatten();
+                    let summary = store
+                        .load_capsule(&cid.to_string())
+                        .ok()
+                        .flatten()
+                        .and_then(|c| c.outputs.get("summary").and_then(|s| s.as_str().map(str::to_string)))
+                        .unwrap_or_default();
+                    match annot {
+                        Some(a) => (a.risk, a.labels, summary),
+                        None => (0.0, vec![], summary),
+                    }
+                }
+                _ => (0.0, vec![], String::new()),
+            };
+
+            out.push(LineageCompaction {
+                key: key.to_string(),
+                cid: node.hash,
+                risk,
+                labels,
+                summary,
+            });
+        }
+        Ok(out)
+    }
+
     // ---- Internal helpers --------------------------------------------------
 
     fn summarize_and_replace(
@@ -136,30 +507,32 @@ impl<'a> Compactor<'a> {
                 }
             }
 
-            // Generate summary.
-            let summary = match self.invoke_summarizer(&original, summarizer.clone()) {
-                Ok(s) => s,
-                Err(e) => {
-                    report.notes.push(format!("summarizer failed {}: {}", c.id, e));
-                    report.regrets += 1;
-                    continue;
-                }
-            };
+            // Generate summary (retries internally; always returns a usable
+            // summary, falling back to a heuristic snippet after retries are
+            // exhausted -- see `invoke_summarizer`).
+            let summary = self.invoke_summarizer(&c.id, &original, summarizer.clone(), policy, report);
 
             // Contracts evaluation for the derived summary; support AllowWithPatch masks.
             let verdict = self.eval_summary_with_contracts(&summary)?;
             let mut final_summary = summary.clone();
+            let mut token_map = serde_json::Map::new();
             let (verdict_variant, risk_score, _reason_opt, patched_applied) = {
                 match verdict {
                     (Verdict::Allow, _pat, risk, reason) => (Verdict::Allow, risk, reason, false),
-                    (Verdict::AllowWithPatch, Some(patterns), risk, reason) => {
-                        final_summary = crate::services::masking::apply_masks_ci(&final_summary, &patterns);
-                        report.notes.push(format!(
-                            "patched summary {} with {} mask(s)",
-                            c.id,
-                            patterns.len()
-                        ));
-                        (Verdict::AllowWithPatch, risk, reason, true)
+                    (Verdict::AllowWithPatch, Some(transforms), risk, reason) => {
+                        let result = crate::services::masking::apply_transform_pipeline(&final_summary, &transforms);
+                        final_summary = result.text;
+                        token_map = result.token_map;
+                        let applied = !result.notes.is_empty();
+                        if applied {
+                            report.notes.push(format!(
+                                "patched summary {} via {} transform(s): {}",
+                                c.id,
+                                result.notes.len(),
+                                result.notes.join("; ")
+                            ));
+                        }
+                        (Verdict::AllowWithPatch, risk, reason, applied)
                     }
                     (Verdict::AllowWithPatch, None, risk, reason) => {
                         (Verdict::AllowWithPatch, risk, reason, false)
@@ -180,10 +553,17 @@ impl<'a> Compactor<'a> {
             if let Some(store) = contracts_store() {
                 let parent_id = store.capsule_for_memory(&c.id).ok().flatten();
                 let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+                // Branch on capability rather than assuming every schema_ver
+                // can carry a "patched" label alongside "summary" -- matters
+                // once an older capsule has been migrated forward by
+                // `ContractsStore::load_capsule` under a schema_ver this
+                // build no longer stamps.
                 let labels = match (verdict_variant.clone(), patched_applied) {
                     (Verdict::Allow, _) => vec!["summary".into()],
-                    (Verdict::AllowWithPatch, true) => vec!["summary".into(), "patched".into()],
-                    (Verdict::AllowWithPatch, false) => vec!["summary".into()],
+                    (Verdict::AllowWithPatch, true) if contracts::supports_patch_labels(contracts::CURRENT) => {
+                        vec!["summary".into(), "patched".into()]
+                    }
+                    (Verdict::AllowWithPatch, _) => vec!["summary".into()],
                     (Verdict::Quarantine, _) => vec!["summary".into()], // Should not occur here due to continue.
                 };
                 let cap = SimCapsule {
@@ -191,7 +571,13 @@ impl<'a> Compactor<'a> {
                     context: serde_json::json!({ "lobe": lobe, "memory_id": c.id, "parent_capsule_id": parent_id }),
                     actions: serde_json::json!(["compaction_summarize"]),
                     outputs: serde_json::json!({ "summary": final_summary }),
-                    trace: serde_json::json!({ "summarizer": summarizer.as_str(), "orig_len": original.len(), "sum_len": final_summary.len(), "patched": patched_applied }),
+                    trace: {
+                        let mut t = serde_json::json!({ "summarizer": summarizer.as_str(), "orig_len": original.len(), "sum_len": final_summary.len(), "patched": patched_applied });
+                        if !token_map.is_empty() {
+                            t["token_map"] = serde_json::Value::Object(token_map);
+                        }
+                        t
+                    },
                     artifacts: vec![],
                     meta: CapsuleMeta {
                         capsule_id: None,
@@ -200,7 +586,7 @@ impl<'a> Compactor<'a> {
                         t_start_ms: now_ms,
                         t_end_ms: now_ms,
                         source: CapsuleSource::Derived,
-                        schema_ver: "1.0".to_string(),
+                        schema_ver: contracts::CURRENT.to_string(),
                         capsule_hash: None,
                         issuer_signature: None,
                         parent_id,
@@ -286,12 +672,49 @@ impl<'a> Compactor<'a> {
         Err(anyhow!("no accessor for original content"))
     }
 
-    fn invoke_summarizer(&self, text: &str, kind: SummarizerKind) -> Result<String> {
-        if let Ok(s) = self.memory.summarize(kind.clone(), text) {
-            if !s.trim().is_empty() {
-                return Ok(s);
+    /// Drive `summarizer` through `policy`'s retry/backoff/timeout knobs,
+    /// only falling back to the heuristic snippet once every attempt has
+    /// failed or come back empty. Attempt count and total backoff wait are
+    /// recorded into `report.notes` on both paths so operators can spot a
+    /// flaky summarizer backend even when it eventually succeeds.
+    fn invoke_summarizer(
+        &self,
+        id: &str,
+        text: &str,
+        kind: SummarizerKind,
+        policy: &CompactionPolicy,
+        report: &mut CompactionReport,
+    ) -> String {
+        let summarizer = MemorySummarizer(self.memory);
+        let max_attempts = policy.summarizer_max_attempts.max(1);
+        let timeout = Duration::from_millis(policy.summarizer_attempt_timeout_ms);
+        let mut total_wait_ms: u64 = 0;
+
+        for attempt in 1..=max_attempts {
+            match Self::call_with_deadline(&summarizer, kind.clone(), text, timeout) {
+                Ok(s) if !s.trim().is_empty() => {
+                    if attempt > 1 {
+                        report.notes.push(format!(
+                            "summarizer {} succeeded on attempt {}/{} (waited {}ms)",
+                            id, attempt, max_attempts, total_wait_ms
+                        ));
+                    }
+                    return s;
+                }
+                _ if attempt < max_attempts => {
+                    let delay = Self::backoff_delay(policy, attempt);
+                    total_wait_ms += delay.as_millis() as u64;
+                    std::thread::sleep(delay);
+                }
+                _ => {}
             }
         }
+
+        report.notes.push(format!(
+            "summarizer {} exhausted {} attempt(s) (waited {}ms total), falling back to heuristic snippet",
+            id, max_attempts, total_wait_ms
+        ));
+
         // Heuristic fallback to keep the pipeline robust
         let trimmed = text.trim();
         let snippet = if trimmed.len() > 512 {
@@ -299,15 +722,58 @@ impl<'a> Compactor<'a> {
         } else {
             trimmed.to_string()
         };
-        Ok(format!(
+        format!(
             "[summary:{} chars={}]\n{}",
             kind.as_str(),
             trimmed.len(),
             snippet
-        ))
+        )
+    }
+
+    /// Run one summarizer attempt and fail it if it overran `timeout`. Today's
+    /// only `Summarizer` impl is a local, effectively-instant computation
+    /// over `Memory`'s connection (which isn't `Sync`, so it can't be handed
+    /// to a watchdog thread); once a real remote/LLM-backed `Summarizer`
+    /// lands this is the seam where it gets one.
+    fn call_with_deadline(
+        summarizer: &impl Summarizer,
+        kind: SummarizerKind,
+        text: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let started = Instant::now();
+        let result = summarizer.summarize(kind, text);
+        let elapsed = started.elapsed();
+        if elapsed > timeout {
+            return Err(anyhow!(
+                "summarizer attempt exceeded {:?} (took {:?})",
+                timeout,
+                elapsed
+            ));
+        }
+        result
+    }
+
+    /// `base * 2^(attempt-1)` backoff, capped at `max_delay`, with optional
+    /// full jitter (`delay = rand(0..=computed)`).
+    fn backoff_delay(policy: &CompactionPolicy, attempt: u32) -> Duration {
+        let shift = attempt.saturating_sub(1).min(32);
+        let computed_ms = policy
+            .summarizer_base_delay_ms
+            .saturating_mul(1u64 << shift)
+            .min(policy.summarizer_max_delay_ms);
+        let delay_ms = if policy.summarizer_full_jitter && computed_ms > 0 {
+            StdRng::from_entropy().gen_range(0..=computed_ms)
+        } else {
+            computed_ms
+        };
+        Duration::from_millis(delay_ms)
     }
 
-    fn eval_summary_with_contracts(&self, summary: &str) -> Result<(Verdict, Option<Vec<String>>, f32, Option<String>)> {
+    fn eval_summary_with_contracts(
+        &self,
+        summary: &str,
+    ) -> Result<(Verdict, Option<Vec<crate::services::masking::Transform>>, f32, Option<String>)> {
         // Load default contract from configured directory and evaluate text.
         let cfg = ensure_initialized_once()?.config.clone();
         let contract_path = cfg.contracts.path.join(&cfg.contracts.default_contract);
@@ -332,18 +798,16 @@ impl<'a> Compactor<'a> {
             return Ok((Verdict::Allow, None, 0.0, None));
         }
 
-        // If any constraint encodes a mask directive, treat as AllowWithPatch; else Quarantine.
-        let mut mask_patterns: Vec<String> = Vec::new();
-        for c in &res.constraints {
-            let t = c.trim();
-            if let Some(stripped) = t.strip_prefix("mask:") {
-                let p = stripped.trim();
-                if !p.is_empty() { mask_patterns.push(p.to_string()); }
-            } else if let Some(stripped) = t.strip_prefix("redact:") {
-                let p = stripped.trim();
-                if !p.is_empty() { mask_patterns.push(p.to_string()); }
-            }
-        }
+        // If any constraint parses as a known transform directive (mask:,
+        // redact:, hash:, truncate:, tokenize:), treat as AllowWithPatch;
+        // else Quarantine. An unparseable/unknown prefix is simply dropped
+        // here, so a rule with only unrecognized constraints still falls
+        // through to Quarantine below.
+        let transforms: Vec<crate::services::masking::Transform> = res
+            .constraints
+            .iter()
+            .filter_map(|c| c.trim().parse::<crate::services::masking::Transform>().ok())
+            .collect();
 
         let mut max_rank = 0;
         for r in &res.violated_rules {
@@ -353,12 +817,39 @@ impl<'a> Compactor<'a> {
         let risk = sev_to_risk(max_rank);
         let reason = res.reason.clone();
 
-        if !mask_patterns.is_empty() {
-            Ok((Verdict::AllowWithPatch, Some(mask_patterns), risk, Some(reason)))
+        if !transforms.is_empty() {
+            Ok((Verdict::AllowWithPatch, Some(transforms), risk, Some(reason)))
         } else {
             Ok((Verdict::Quarantine, None, risk, Some(reason)))
         }
     }
+
+    /// Promote the oldest hot rows in `lobe` to the DAG until its hot-tier
+    /// byte total is at or under `max_hot_bytes`, or there are no hot rows
+    /// left. Returns the number of rows promoted.
+    pub fn evict_to_quota(&self, lobe: &str, max_hot_bytes: u64) -> Result<usize> {
+        let mut promoted = 0usize;
+        loop {
+            let (hot_bytes, _archived_bytes) = self.memory.byte_totals(Some(lobe))?;
+            if hot_bytes <= max_hot_bytes {
+                break;
+            }
+            match self.memory.oldest_hot_id_in_lobe(lobe)? {
+                Some(id) => {
+                    self.memory.promote_to_dag(&id)?;
+                    promoted += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(promoted)
+    }
+}
+
+/// First 8 hex chars of a cid for compact log lines; falls back to the
+/// whole string if it's shorter (e.g. a test fixture hash).
+fn short_cid(cid: &str) -> &str {
+    cid.get(0..8).unwrap_or(cid)
 }
 
 // -------------------- Contracts Store helper --------------------
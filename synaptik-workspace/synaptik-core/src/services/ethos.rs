@@ -5,9 +5,12 @@ use serde::{Deserialize, Serialize};
 
 use serde_json::json;
 
+use contracts::types::MoralContract;
+
 use crate::commands::init::ensure_initialized_once;
+use crate::config::RiskAggregation;
 use crate::services::audit::{
-    ContractEvalMeta, evaluate_and_audit_contract, record_ethics_decision,
+    ContractEvalMeta, evaluate_and_audit_contract, record_ethics_decision, risk_aggregation,
 };
 
 /// Verdict returned by [`precheck`]: normalized signal from contracts.
@@ -17,15 +20,19 @@ use crate::services::audit::{
 /// - `constraints` — list of soft constraints (e.g., `"request_clarification"`)
 /// - `passed` — overall ethics pass/fail
 /// - `reason` — human-readable rationale from the ethics contract
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EthosVerdict {
     pub risk: String,
     pub constraints: Vec<String>,
     pub passed: bool,
     pub reason: String,
+    /// Numeric aggregate behind `risk`, per the configured
+    /// `contracts.risk_aggregation` policy — lets callers reason about *how
+    /// far over the line* a proposal is, not just the top label.
+    pub score: f32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum Decision {
     Allow,
     AllowWithConstraints,
@@ -51,7 +58,7 @@ pub struct Proposal {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ConstraintSpec {
     #[serde(default)]
-    pub mask_rules: Vec<String>, // regex or literal patterns (we use literal substrings in MVP)
+    pub mask_rules: Vec<String>, // literal substrings, applied via contracts::apply_masks (see PatchOp::MaskRegex for regex rules)
     #[serde(default)]
     pub allow_tools: Vec<String>, // tool names
     #[serde(default)]
@@ -92,6 +99,29 @@ pub struct ContractsDecider;
 
 impl EthosContract for ContractsDecider {
     fn evaluate(&self, p: &Proposal) -> RuntimeDecision {
+        // Tool gate first: a proposal naming a tool it has no covering
+        // capability grant for is an escalation regardless of what the text
+        // contracts below would otherwise allow.
+        if !p.tools_requested.is_empty() {
+            match crate::services::capability::unsatisfied(&p.tools_requested, &p.intent) {
+                Ok(missing) if !missing.is_empty() => {
+                    return RuntimeDecision::Escalate {
+                        reason: format!(
+                            "missing capability grant for tool(s) [{}] on resource '{}'",
+                            missing.join(", "),
+                            p.intent
+                        ),
+                    };
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    return RuntimeDecision::Escalate {
+                        reason: format!("capability grant lookup failed: {}", e),
+                    };
+                }
+            }
+        }
+
         // Always evaluate contracts; no disable path.
         let cfg = ensure_initialized_once()
             .map(|r| r.config.clone())
@@ -134,6 +164,74 @@ impl EthosContract for ContractsDecider {
     }
 }
 
+/// An [`EthosContract`] that evaluates against a [`MoralContract`] handed to
+/// it directly, rather than one resolved by name from `contracts.path` --
+/// e.g. for callers (the Python bindings) that want to govern a stream with
+/// an ad hoc contract without registering it on disk first. Otherwise
+/// mirrors [`ContractsDecider`]'s tool gate and Stop/Constrain mapping.
+pub struct MoralContractDecider {
+    pub contract: std::sync::Arc<MoralContract>,
+}
+
+impl EthosContract for MoralContractDecider {
+    fn evaluate(&self, p: &Proposal) -> RuntimeDecision {
+        if !p.tools_requested.is_empty() {
+            match crate::services::capability::unsatisfied(&p.tools_requested, &p.intent) {
+                Ok(missing) if !missing.is_empty() => {
+                    return RuntimeDecision::Escalate {
+                        reason: format!(
+                            "missing capability grant for tool(s) [{}] on resource '{}'",
+                            missing.join(", "),
+                            p.intent
+                        ),
+                    };
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    return RuntimeDecision::Escalate {
+                        reason: format!("capability grant lookup failed: {}", e),
+                    };
+                }
+            }
+        }
+
+        let eval = match crate::services::audit::evaluate_and_audit_contract_value(
+            &ContractEvalMeta {
+                kind: "Ethics".into(),
+                contract_name: None,
+                metadata: json!({ "intent": p.intent }),
+            },
+            &self.contract,
+            &p.input,
+        ) {
+            Ok(v) => v,
+            Err(_) => json!({"passed": false}),
+        };
+
+        let passed = eval.get("passed").and_then(|v| v.as_bool()).unwrap_or(true);
+        if !passed {
+            return RuntimeDecision::Stop { safe_template: "I can’t assist with that. If you’re concerned about safety, consider reaching out to local resources for help.".to_string() };
+        }
+
+        let constraints = eval
+            .get("constraints")
+            .and_then(|v| v.as_array())
+            .map(|arr| !arr.is_empty())
+            .unwrap_or(false);
+        if constraints {
+            return RuntimeDecision::Constrain(ConstraintSpec {
+                mask_rules: vec![],
+                allow_tools: vec![],
+                stop_phrases: vec!["how to".into(), "step by step".into()],
+                max_tokens: ConstraintSpec::default_max_tokens(),
+                temperature_cap: ConstraintSpec::default_temperature_cap(),
+            });
+        }
+
+        RuntimeDecision::Proceed
+    }
+}
+
 /// Synchronous, contract-backed risk + ethics check.
 ///
 /// # Arguments
@@ -174,47 +272,24 @@ pub fn precheck(candidate_text: &str, intent_label: &str) -> Result<EthosVerdict
     let passed = ethics_val["passed"].as_bool().unwrap_or(true);
     let reason = ethics_val["reason"].as_str().unwrap_or("").to_string();
 
-    // Derive risk from either an explicit risk field, or from the highest rule severity.
-    fn sev_rank(s: &str) -> i32 {
-        match s.to_ascii_lowercase().as_str() {
-            "critical" => 4,
-            "high" => 3,
-            "medium" => 2,
-            "low" => 1,
-            _ => 0,
-        }
-    }
-    fn rank_to_label(r: i32) -> &'static str {
-        match r {
-            4 => "Critical",
-            3 => "High",
-            2 => "Medium",
-            1 => "Low",
-            _ => "Low",
-        }
-    }
-
     // Pull any explicit risk if present
-    let mut effective_rank = 0;
-    if let Some(rsk) = risk_val.get("risk").and_then(|v| v.as_str()) {
-        effective_rank = sev_rank(rsk);
-    }
-    // Merge in highest violated rule severity from ethics result
-    if let Some(arr) = ethics_val.get("violated_rules").and_then(|v| v.as_array()) {
-        for v in arr {
-            if let Some(sev) = v.get("severity").and_then(|s| s.as_str()) {
-                let r = sev_rank(sev);
-                if r > effective_rank {
-                    effective_rank = r;
-                }
-            }
-        }
-    }
-    // If we blocked but still somehow have Low, bump to at least High to reflect violation gravity
-    if !passed && effective_rank == 0 {
-        effective_rank = 3;
-    }
-    let risk = rank_to_label(effective_rank).to_string();
+    let explicit_risk = risk_val.get("risk").and_then(|v| v.as_str()).map(str::to_string);
+    // Collect every violated rule's severity from the ethics result
+    let violated_severities: Vec<String> = ethics_val
+        .get("violated_rules")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.get("severity").and_then(|s| s.as_str()).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (risk, score) = aggregate_risk(
+        explicit_risk.as_deref(),
+        &violated_severities.iter().map(String::as_str).collect::<Vec<_>>(),
+        passed,
+    );
     let constraints = ethics_val["constraints"]
         .as_array()
         .unwrap_or(&vec![])
@@ -229,9 +304,103 @@ pub fn precheck(candidate_text: &str, intent_label: &str) -> Result<EthosVerdict
         constraints,
         passed,
         reason,
+        score,
     })
 }
 
+fn sev_rank(s: &str) -> i32 {
+    match s.to_ascii_lowercase().as_str() {
+        "critical" => 4,
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+fn rank_to_label(r: i32) -> &'static str {
+    match r {
+        4 => "Critical",
+        3 => "High",
+        2 => "Medium",
+        1 => "Low",
+        _ => "Low",
+    }
+}
+
+/// Fold the explicit risk field and every violated rule's severity into one
+/// effective `(label, score)` pair, per the configured `RiskAggregation`
+/// policy (`contracts.risk_aggregation`).
+fn aggregate_risk(explicit_risk: Option<&str>, violated_severities: &[&str], passed: bool) -> (String, f32) {
+    match risk_aggregation() {
+        RiskAggregation::Max => {
+            let mut rank = explicit_risk.map(sev_rank).unwrap_or(0);
+            for sev in violated_severities {
+                rank = rank.max(sev_rank(sev));
+            }
+            // If we blocked but still somehow have Low, bump to at least High to reflect violation gravity
+            if !passed && rank == 0 {
+                rank = 3;
+            }
+            (rank_to_label(rank).to_string(), rank as f32)
+        }
+        RiskAggregation::WeightedSum {
+            low_weight,
+            medium_weight,
+            high_weight,
+            critical_weight,
+            medium_threshold,
+            high_threshold,
+            critical_threshold,
+        } => {
+            let weight = |rank: i32| -> f32 {
+                match rank {
+                    4 => *critical_weight,
+                    3 => *high_weight,
+                    2 => *medium_weight,
+                    1 => *low_weight,
+                    _ => 0.0,
+                }
+            };
+            let mut score = explicit_risk.map(sev_rank).map(weight).unwrap_or(0.0);
+            for sev in violated_severities {
+                score += weight(sev_rank(sev));
+            }
+            if !passed && score < *high_threshold {
+                score = *high_threshold;
+            }
+            let label = if score >= *critical_threshold {
+                "Critical"
+            } else if score >= *high_threshold {
+                "High"
+            } else if score >= *medium_threshold {
+                "Medium"
+            } else {
+                "Low"
+            };
+            (label.to_string(), score)
+        }
+        RiskAggregation::CountThreshold { medium_count_for_high } => {
+            let mut rank = explicit_risk.map(sev_rank).unwrap_or(0);
+            let mut medium_count = 0u32;
+            for sev in violated_severities {
+                let r = sev_rank(sev);
+                rank = rank.max(r);
+                if r == 2 {
+                    medium_count += 1;
+                }
+            }
+            if medium_count >= *medium_count_for_high {
+                rank = rank.max(3);
+            }
+            if !passed && rank == 0 {
+                rank = 3;
+            }
+            (rank_to_label(rank).to_string(), rank as f32)
+        }
+    }
+}
+
 // Contract checks are always enabled; no feature flag bypass.
 
 /// Map an [`EthosVerdict`] into an actionable gate decision.
@@ -0,0 +1,88 @@
+// src/services/hot_store.rs
+//! `HotStore`: the content-storage surface the hot tier needs --
+//! `remember`/`recall`/`forget` by `memory_id`. Parallels [`ColdStore`](crate::services::cold_store::ColdStore),
+//! which already abstracts cold-tier get/put, and
+//! [`MemoryBackend`](crate::services::memory_backend::MemoryBackend), which
+//! already abstracts the row/snapshot bookkeeping (counts, lobe grouping,
+//! recency, hot promotion, archived-cid pointer) -- together these three
+//! traits name every seam `Memory` currently hardcodes against
+//! `rusqlite::Connection`.
+//!
+//! `Memory` does **not** implement this trait yet: its dozens of SQL-backed
+//! methods would need to be rewritten to go through a trait object rather
+//! than `self.db.prepare(...)` directly, which is a much larger migration
+//! than this change attempts. What's here is the first step -- the trait
+//! itself, plus a real second implementation (`InMemoryHotStore`) that
+//! doesn't need SQLite at all, suitable for tests or a host (e.g. wasm32)
+//! that can't carry rusqlite. Making `Memory`/`Archivist`/`Librarian`
+//! generic over `HotStore`/`ColdStore`, and a wasm32 build target with an
+//! IndexedDB-backed `ColdStore`, are tracked as follow-up work.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Content storage for the hot tier, keyed by `memory_id`.
+pub trait HotStore: Send + Sync {
+    /// Store `bytes` under `memory_id` (lobe/key are carried for parity with
+    /// `Memory::remember`'s signature, even though this trait doesn't
+    /// expose lobe-scoped queries -- that bookkeeping lives in
+    /// `MemoryBackend`).
+    fn remember(&self, memory_id: &str, lobe: &str, key: &str, bytes: &[u8]) -> Result<()>;
+    /// Fetch bytes by `memory_id`, if present.
+    fn recall(&self, memory_id: &str) -> Result<Option<Vec<u8>>>;
+    /// Remove `memory_id` from the hot tier (e.g. after promotion to cold).
+    fn forget(&self, memory_id: &str) -> Result<()>;
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    #[allow(dead_code)] // carried for parity with `Memory`'s rows; not queried by this trait
+    lobe: String,
+    #[allow(dead_code)]
+    key: String,
+    bytes: Vec<u8>,
+}
+
+/// A `HotStore` backed by a plain `HashMap`, for tests and any host that
+/// can't carry rusqlite. No persistence, no lobe/recency queries -- just
+/// enough to exercise the recall-tier parity logic in `Commands::recall_any`
+/// et al. against something other than SQLite.
+#[derive(Debug, Default)]
+pub struct InMemoryHotStore {
+    rows: Mutex<HashMap<String, Row>>,
+}
+
+impl InMemoryHotStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl HotStore for InMemoryHotStore {
+    fn remember(&self, memory_id: &str, lobe: &str, key: &str, bytes: &[u8]) -> Result<()> {
+        self.rows.lock().unwrap().insert(
+            memory_id.to_string(),
+            Row {
+                lobe: lobe.to_string(),
+                key: key.to_string(),
+                bytes: bytes.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    fn recall(&self, memory_id: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .get(memory_id)
+            .map(|row| row.bytes.clone()))
+    }
+
+    fn forget(&self, memory_id: &str) -> Result<()> {
+        self.rows.lock().unwrap().remove(memory_id);
+        Ok(())
+    }
+}
@@ -2,12 +2,42 @@ use anyhow::Result;
 use rusqlite::{Connection, params};
 
 use crate::commands::init::ensure_initialized_once;
+use crate::services::memory::install_event_log_hooks;
 
-/// Minimal TD(λ) learner (λ treated as 0 for now). Stores values in the shared SQLite DB.
+/// Eligibility traces below this magnitude are pruned rather than tracked,
+/// since `gamma*lambda` decay makes their contribution to future updates
+/// negligible and this keeps the `eligibility` table small.
+const TRACE_EPSILON: f32 = 1e-4;
+
+/// How a state's eligibility trace is bumped when it's (re-)visited within
+/// an episode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceMode {
+    /// `e(s) += 1.0` -- repeat visits within an episode compound credit.
+    Accumulating,
+    /// `e(s) = 1.0` -- a revisit resets credit rather than stacking it.
+    Replacing,
+}
+
+/// TD(λ) learner backed by an eligibility-trace table, so a reward observed
+/// late in an episode propagates credit back to earlier states in the same
+/// trajectory rather than only updating the single preceding state (TD(0),
+/// i.e. `lambda == 0.0`). Stores values (and traces) in the shared SQLite DB.
 pub struct TDLearner {
     db_path: std::path::PathBuf,
+    /// SQLCipher key, if this learner was opened against an encrypted
+    /// `Memory::open_encrypted` database; applied to every connection this
+    /// learner opens so it can read/write the same encrypted DB.
+    key: Option<String>,
+    /// Mirrors `Memory::with_db_event_log`: when set, every connection this
+    /// learner opens installs the same `memories`/`steps` commit-event
+    /// hooks, so `td_update`'s writes to `steps`/`values` get a logbook
+    /// trail too.
+    event_log: bool,
     pub gamma: f32,
     pub alpha: f32,
+    pub lambda: f32,
+    pub trace_mode: TraceMode,
 }
 
 impl TDLearner {
@@ -15,24 +45,80 @@ impl TDLearner {
         let cfg = ensure_initialized_once()?.config.clone();
         Ok(Self {
             db_path: cfg.memory.cache_path,
+            key: None,
+            event_log: false,
             gamma: 0.95,
             alpha: 0.1,
+            lambda: 0.9,
+            trace_mode: TraceMode::Accumulating,
         })
     }
 
+    /// Toggle automatic logbook emission (see `event_log`) for every
+    /// connection this learner opens from here on.
+    pub fn with_event_log(mut self, enabled: bool) -> Self {
+        self.event_log = enabled;
+        self
+    }
+
     fn conn(&self) -> Result<Connection> {
-        Ok(Connection::open(&self.db_path)?)
+        let conn = Connection::open(&self.db_path)?;
+        #[cfg(feature = "sqlcipher")]
+        if let Some(key) = &self.key {
+            conn.pragma_update(None, "key", key)?;
+        }
+        if self.event_log {
+            install_event_log_hooks(&conn);
+        }
+        Ok(conn)
     }
 
     /// Construct a learner bound to a specific SQLite path (primarily for tests/tools).
     pub fn open_at(db_path: std::path::PathBuf) -> Self {
         Self {
             db_path,
+            key: None,
+            event_log: false,
             gamma: 0.95,
             alpha: 0.1,
+            lambda: 0.9,
+            trace_mode: TraceMode::Accumulating,
         }
     }
 
+    /// Construct a learner bound to an SQLCipher-encrypted DB at `db_path`,
+    /// keyed the same as the `Memory::open_encrypted` store it learns
+    /// alongside.
+    #[cfg(feature = "sqlcipher")]
+    pub fn open_at_encrypted(db_path: std::path::PathBuf, key: impl Into<String>) -> Self {
+        Self {
+            db_path,
+            key: Some(key.into()),
+            event_log: false,
+            gamma: 0.95,
+            alpha: 0.1,
+            lambda: 0.9,
+            trace_mode: TraceMode::Accumulating,
+        }
+    }
+
+    /// `eligibility` is owned by the learner alone (unlike `values`/`steps`,
+    /// which `RewardSqliteSink` seeds), so ensure it exists before every use
+    /// rather than assuming a prior `init_schema` call created it.
+    fn ensure_eligibility_table(&self, conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS eligibility (
+                episode_id TEXT NOT NULL,
+                state_id TEXT NOT NULL,
+                trace REAL NOT NULL,
+                PRIMARY KEY (episode_id, state_id)
+            );
+            "#,
+        )?;
+        Ok(())
+    }
+
     fn get_value(&self, state_id: &str) -> Result<f32> {
         let conn = self.conn()?;
         let mut stmt = match conn.prepare("SELECT value FROM \"values\" WHERE state_id=?1") {
@@ -54,34 +140,223 @@ impl TDLearner {
         }
     }
 
-    fn upsert_value(&self, state_id: &str, value: f32) -> Result<()> {
-        let conn = self.conn()?;
+    /// Apply one TD(λ) step for transition `s -(r)-> sp` within `episode_id`:
+    /// compute `delta = r + gamma*V(sp) - V(s)`, bump `s`'s trace per
+    /// `self.trace_mode`, then sweep every non-negligible trace in the
+    /// episode doing `V(k) += alpha*delta*e(k)` followed by
+    /// `e(k) *= gamma*lambda`, pruning traces that decay below
+    /// [`TRACE_EPSILON`]. Returns the updated `V(s)`.
+    pub fn td_update(&self, episode_id: &str, s: &str, r: f32, sp: Option<&str>) -> Result<f32> {
+        let mut conn = self.conn()?;
+        self.ensure_eligibility_table(&conn)?;
+        let tx = conn.transaction()?;
+
+        let v_s = tx_value(&tx, s)?;
+        let v_sp = match sp {
+            Some(id) => tx_value(&tx, id)?,
+            None => 0.0,
+        };
+        let delta = r + self.gamma * v_sp - v_s;
+
+        let prior_trace: f32 = tx
+            .query_row(
+                "SELECT trace FROM eligibility WHERE episode_id=?1 AND state_id=?2",
+                params![episode_id, s],
+                |row| row.get(0),
+            )
+            .unwrap_or(0.0);
+        let bumped_trace = match self.trace_mode {
+            TraceMode::Accumulating => prior_trace + 1.0,
+            TraceMode::Replacing => 1.0,
+        };
+        tx.execute(
+            "INSERT INTO eligibility(episode_id, state_id, trace) VALUES(?1, ?2, ?3)
+             ON CONFLICT(episode_id, state_id) DO UPDATE SET trace=excluded.trace",
+            params![episode_id, s, bumped_trace],
+        )?;
+
+        let traces: Vec<(String, f32)> = {
+            let mut stmt =
+                tx.prepare("SELECT state_id, trace FROM eligibility WHERE episode_id=?1")?;
+            stmt.query_map(params![episode_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
         let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut new_v_s = v_s;
+        for (state_id, trace) in traces {
+            let v_k = tx_value(&tx, &state_id)?;
+            let new_v_k = v_k + self.alpha * delta * trace;
+            tx.execute(
+                "INSERT INTO \"values\"(state_id, value, updated_ms) VALUES(?1, ?2, ?3)
+                 ON CONFLICT(state_id) DO UPDATE SET value=excluded.value, updated_ms=excluded.updated_ms",
+                params![state_id, new_v_k, now_ms],
+            )?;
+            if state_id == s {
+                new_v_s = new_v_k;
+            }
+
+            let decayed = trace * self.gamma * self.lambda;
+            if decayed.abs() < TRACE_EPSILON {
+                tx.execute(
+                    "DELETE FROM eligibility WHERE episode_id=?1 AND state_id=?2",
+                    params![episode_id, state_id],
+                )?;
+            } else {
+                tx.execute(
+                    "UPDATE eligibility SET trace=?3 WHERE episode_id=?1 AND state_id=?2",
+                    params![episode_id, state_id, decayed],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(new_v_s)
+    }
+
+    /// Drop every eligibility trace for `episode_id`, so the next episode's
+    /// credit assignment starts clean. Called by
+    /// [`StepAssembler::end_episode`].
+    pub fn clear_traces(&self, episode_id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        self.ensure_eligibility_table(&conn)?;
         conn.execute(
-            "INSERT INTO \"values\"(state_id, value, updated_ms) VALUES(?1, ?2, ?3)
-             ON CONFLICT(state_id) DO UPDATE SET value=excluded.value, updated_ms=excluded.updated_ms",
-            params![state_id, value, now_ms],
+            "DELETE FROM eligibility WHERE episode_id=?1",
+            params![episode_id],
         )?;
         Ok(())
     }
 
-    pub fn td_update(&self, s: &str, r: f32, sp: Option<&str>) -> Result<f32> {
-        let v_s = self.get_value(s)?;
-        let v_sp = match sp {
-            Some(id) => self.get_value(id)?,
-            None => 0.0,
+    /// Current learned value for a state, or `0.0` if it has never been updated.
+    pub fn value_of(&self, state_id: &str) -> Result<f32> {
+        self.get_value(state_id)
+    }
+
+    /// Replay every recorded `steps` row for `lobe` (oldest first, by
+    /// `(ts_ms, id)` for a deterministic result) and apply one TD(0) sweep:
+    /// `V(s) <- V(s) + alpha*(r + gamma*V(s') - V(s))`, upserting into
+    /// `values` as it goes so later steps in the same sweep see earlier
+    /// steps' updates. A NULL `next_state_id` is terminal (`V(s')=0`); a
+    /// state not yet in `values` defaults to `0.0`. Runs as one
+    /// transaction and returns the number of steps applied.
+    pub fn learn_td0(&self, lobe: &str, alpha: f32, gamma: f32) -> Result<usize> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        let rows: Vec<(Option<String>, f32, Option<String>)> = {
+            let mut stmt = tx.prepare(
+                "SELECT state_id, reward, next_state_id FROM steps WHERE lobe=?1 ORDER BY ts_ms ASC, id ASC",
+            )?;
+            stmt.query_map(params![lobe], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
         };
-        let td_error = r + self.gamma * v_sp - v_s;
-        let new_v = v_s + self.alpha * td_error;
-        self.upsert_value(s, new_v)?;
-        Ok(new_v)
+
+        let mut cache: std::collections::HashMap<String, f32> = std::collections::HashMap::new();
+        let mut applied = 0usize;
+        for (state_id, reward, next_state_id) in rows {
+            let Some(s) = state_id else { continue };
+            let v_s = match cache.get(&s) {
+                Some(v) => *v,
+                None => tx_value(&tx, &s)?,
+            };
+            let v_sp = match &next_state_id {
+                Some(sp) => match cache.get(sp) {
+                    Some(v) => *v,
+                    None => tx_value(&tx, sp)?,
+                },
+                None => 0.0,
+            };
+            let new_v = v_s + alpha * (reward + gamma * v_sp - v_s);
+            cache.insert(s.clone(), new_v);
+
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            tx.execute(
+                "INSERT INTO \"values\"(state_id, value, updated_ms) VALUES(?1, ?2, ?3)
+                 ON CONFLICT(state_id) DO UPDATE SET value=excluded.value, updated_ms=excluded.updated_ms",
+                params![s, new_v, now_ms],
+            )?;
+            applied += 1;
+        }
+
+        tx.commit()?;
+        Ok(applied)
+    }
+
+    /// The `action_capsule_id` whose recorded transitions out of `state_id`
+    /// (within `lobe`) have the highest mean `reward + gamma*V(next)`,
+    /// using the learner's current `gamma` and the values already in
+    /// `values` (does not itself call [`TDLearner::learn_td0`]). `None` if
+    /// no transitions from this state have been recorded. Ties break on
+    /// `action_capsule_id` so the result is deterministic.
+    pub fn best_action(&self, lobe: &str, state_id: &str) -> Result<Option<String>> {
+        let conn = self.conn()?;
+        let rows: Vec<(String, f32, Option<String>)> = {
+            let mut stmt = conn.prepare(
+                "SELECT action_capsule_id, reward, next_state_id FROM steps WHERE lobe=?1 AND state_id=?2",
+            )?;
+            stmt.query_map(params![lobe, state_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        let mut totals: std::collections::HashMap<String, (f32, u32)> = std::collections::HashMap::new();
+        for (action, reward, next_state_id) in rows {
+            let v_sp = match next_state_id.as_deref() {
+                Some(sp) => self.get_value(sp)?,
+                None => 0.0,
+            };
+            let q = reward + self.gamma * v_sp;
+            let entry = totals.entry(action).or_insert((0.0, 0));
+            entry.0 += q;
+            entry.1 += 1;
+        }
+
+        let mut scored: Vec<(String, f32)> = totals
+            .into_iter()
+            .map(|(action, (sum, n))| (action, sum / n as f32))
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        Ok(scored.into_iter().next().map(|(action, _)| action))
+    }
+}
+
+/// Read `values.value` for `state_id` through an open transaction (rather
+/// than a fresh connection, which would contend with the write lock
+/// `learn_td0`'s transaction already holds). Missing row or missing table
+/// both default to `0.0`, matching [`TDLearner::get_value`].
+fn tx_value(tx: &rusqlite::Transaction<'_>, state_id: &str) -> Result<f32> {
+    let mut stmt = match tx.prepare("SELECT value FROM \"values\" WHERE state_id=?1") {
+        Ok(s) => s,
+        Err(e) => {
+            let msg = e.to_string();
+            if msg.contains("no such table") {
+                return Ok(0.0);
+            }
+            return Err(e.into());
+        }
+    };
+    let mut rows = stmt.query([state_id])?;
+    if let Some(row) = rows.next()? {
+        Ok(row.get::<_, f32>(0)?)
+    } else {
+        Ok(0.0)
     }
 }
 
 /// Step assembler: writes steps and triggers TD updates.
 pub struct StepAssembler {
     db_path: std::path::PathBuf,
+    /// Mirrors `TDLearner`'s `key`; see its doc comment.
+    key: Option<String>,
+    /// Mirrors `TDLearner`'s `event_log`; see its doc comment.
+    event_log: bool,
     learner: TDLearner,
+    episode_id: std::sync::Mutex<String>,
 }
 
 impl StepAssembler {
@@ -89,22 +364,80 @@ impl StepAssembler {
         let cfg = ensure_initialized_once()?.config.clone();
         Ok(Self {
             db_path: cfg.memory.cache_path.clone(),
+            key: None,
+            event_log: false,
             learner: TDLearner::open_default()?,
+            episode_id: std::sync::Mutex::new(contracts::api::uuidv7()),
         })
     }
 
+    /// Toggle automatic logbook emission (see `event_log`) on both this
+    /// assembler's own connections and the `TDLearner` it drives.
+    pub fn with_event_log(mut self, enabled: bool) -> Self {
+        self.event_log = enabled;
+        self.learner = self.learner.with_event_log(enabled);
+        self
+    }
+
     fn conn(&self) -> Result<Connection> {
-        Ok(Connection::open(&self.db_path)?)
+        let conn = Connection::open(&self.db_path)?;
+        #[cfg(feature = "sqlcipher")]
+        if let Some(key) = &self.key {
+            conn.pragma_update(None, "key", key)?;
+        }
+        if self.event_log {
+            install_event_log_hooks(&conn);
+        }
+        Ok(conn)
     }
 
     /// Construct an assembler bound to a specific SQLite path (primarily for tests/tools).
     pub fn open_at(db_path: std::path::PathBuf) -> Result<Self> {
         Ok(Self {
             db_path: db_path.clone(),
+            key: None,
+            event_log: false,
             learner: TDLearner::open_at(db_path),
+            episode_id: std::sync::Mutex::new(contracts::api::uuidv7()),
+        })
+    }
+
+    /// Construct an assembler bound to an SQLCipher-encrypted DB at
+    /// `db_path`, keyed the same as the `Memory::open_encrypted` store it
+    /// assembles steps alongside.
+    #[cfg(feature = "sqlcipher")]
+    pub fn open_at_encrypted(db_path: std::path::PathBuf, key: impl Into<String>) -> Result<Self> {
+        let key = key.into();
+        Ok(Self {
+            db_path: db_path.clone(),
+            key: Some(key.clone()),
+            event_log: false,
+            learner: TDLearner::open_at_encrypted(db_path, key),
+            episode_id: std::sync::Mutex::new(contracts::api::uuidv7()),
         })
     }
 
+    /// Current episode id that `record_step` is accumulating eligibility
+    /// traces under.
+    pub fn current_episode(&self) -> String {
+        self.episode_id.lock().unwrap().clone()
+    }
+
+    /// Start a fresh trajectory: subsequent `record_step` calls accumulate
+    /// eligibility traces under a new episode id until `end_episode` (or a
+    /// terminal step) clears them. Returns the new episode id.
+    pub fn begin_episode(&self) -> String {
+        let id = contracts::api::uuidv7();
+        *self.episode_id.lock().unwrap() = id.clone();
+        id
+    }
+
+    /// Drop the current episode's eligibility traces, so credit from this
+    /// trajectory stops bleeding into the next one.
+    pub fn end_episode(&self) -> Result<()> {
+        self.learner.clear_traces(&self.current_episode())
+    }
+
     pub fn record_step(
         &self,
         lobe: &str,
@@ -119,7 +452,14 @@ impl StepAssembler {
             "INSERT INTO steps (lobe, state_id, action_capsule_id, reward, next_state_id, ts_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![lobe, state_id, action_capsule_id, reward, next_state_id, ts_ms],
         )?;
-        let _ = self.learner.td_update(state_id, reward, next_state_id)?;
+        let episode_id = self.current_episode();
+        let _ = self.learner.td_update(&episode_id, state_id, reward, next_state_id)?;
+        if next_state_id.is_none() {
+            // Terminal transition: this trajectory is over, so clear its
+            // traces and start the next one fresh.
+            self.end_episode()?;
+            self.begin_episode();
+        }
         Ok(())
     }
 
@@ -7,12 +7,13 @@ use blake3;
 use chrono::Utc;
 use serde_json::json;
 use std::num::NonZeroU32;
+use std::sync::Arc;
 
 use summary::{Language, Summarizer};
 
 use crate::config::PoliciesConfig;
-use crate::services::archivist::Archivist;
 use crate::services::audit::record_action;
+use crate::services::cold_store::ColdStore;
 use crate::services::memory::Memory;
 use crate::commands::init::ensure_initialized_once;
 
@@ -25,16 +26,16 @@ use once_cell::sync::OnceCell;
 
 #[derive(Debug)]
 pub struct Librarian {
-    archivist: Option<Archivist>,
+    cold_store: Option<Arc<dyn ColdStore>>,
     settings: LibrarianSettings,
     // Optional injected contracts store to reduce global coupling
     contracts: Option<ContractsStore>,
 }
 
 impl Librarian {
-    pub fn new(archivist: Option<Archivist>, settings: LibrarianSettings) -> Self {
+    pub fn new(cold_store: Option<Arc<dyn ColdStore>>, settings: LibrarianSettings) -> Self {
         Self {
-            archivist,
+            cold_store,
             settings,
             contracts: None,
         }
@@ -51,6 +52,15 @@ impl Librarian {
         contracts_store()
     }
 
+    /// A handle onto cold storage, for callers that want to read blobs
+    /// directly (e.g. a parallel batch recall) without going through
+    /// `fetch_cold`'s `&Memory` re-cache step. `Arc<dyn ColdStore>` is
+    /// `Clone`/`Send`/`Sync`, unlike `Memory`'s `rusqlite::Connection`, so
+    /// it's safe to hand out and use from other threads.
+    pub(crate) fn cold_store(&self) -> Option<Arc<dyn ColdStore>> {
+        self.cold_store.clone()
+    }
+
     /// Main ingest path: summarize (always, if long) → optional reflect → Memory write.
     /// Returns the `memory_id`.
     pub fn ingest_text(
@@ -244,9 +254,9 @@ impl Librarian {
         Ok(memory_id)
     }
 
-    // Promote to archive: file -> CID via Archivist; then Memory writes archived_cid.
+    // Promote to archive: bytes -> CID via the configured ColdStore; then Memory writes archived_cid.
     pub fn promote_to_archive(&self, memory: &Memory, memory_id: &str) -> Result<Option<String>> {
-        let Some(arch) = &self.archivist else {
+        let Some(arch) = &self.cold_store else {
             return Ok(None);
         };
         if let Some(bytes) = memory.recall(memory_id)? {
@@ -254,6 +264,11 @@ impl Librarian {
             let cid = arch.archive(memory_id, &bytes)?;
             let ts = chrono::Utc::now().to_rfc3339();
             memory.mark_archived(memory_id, &cid, &ts)?;
+            let lobe = memory
+                .lobe_key(memory_id)?
+                .map(|(l, _)| l)
+                .unwrap_or_else(|| "unknown".to_string());
+            memory.accrue_weight(&lobe, crate::services::weight::op_weight(bytes.len()))?;
             crate::services::audit::record_action(
                 "librarian",
                 "memory_promoted",
@@ -311,10 +326,10 @@ impl Librarian {
         Ok(cold)
     }
 
-    /// Fetch only from cold storage via Archivist if a CID exists; re-caches on success.
+    /// Fetch only from cold storage via the configured ColdStore if a CID exists; re-caches on success.
     pub fn fetch_cold(&self, memory: &Memory, memory_id: &str) -> Result<Option<Vec<u8>>> {
         if let Some(cid) = memory.get_archived_cid(memory_id)? {
-            if let Some(arch) = &self.archivist {
+            if let Some(arch) = &self.cold_store {
                 match arch.retrieve(&cid) {
                     Ok(bytes) => {
                         // Try to restore under original lobe/key from DAG metadata; fallback to stable defaults
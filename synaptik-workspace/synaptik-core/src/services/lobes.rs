@@ -8,11 +8,21 @@
 //!
 //! This module provides atomic write, version scanning, and safe key sanitation,
 //! without involving the SQLite cache or DAG layers. It is purely filesystem-backed.
+//!
+//! - Versions accumulate forever under `versions/` unless compacted. `compact`
+//!   (and the `compact_lobe`/`compact_all` sweeps over it) prunes old versions
+//!   per key, keeping the newest `retain` plus whatever `LATEST` points at.
+//! - Compaction is modeled on a LevelDB-style edit log: deletions are first
+//!   appended to `objects/MANIFEST` as edit records, then performed, then
+//!   closed out with a checkpoint record once the batch completes. `open`
+//!   replays any un-checkpointed tail left by a crash mid-batch.
 
 use anyhow::{Context, Result};
 use blake3;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     fs,
     ffi::OsStr,
     io::Write,
@@ -32,7 +42,9 @@ impl LobeStore {
     pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
         let root = root.into();
         fs::create_dir_all(root.join("objects"))?;
-        Ok(Self { root })
+        let store = Self { root };
+        store.reconcile_manifest()?;
+        Ok(store)
     }
 
     /// Ensure a given lobe exists (idempotent).
@@ -66,6 +78,8 @@ impl LobeStore {
         let latest_path = self.root.join("objects").join(&lobe).join(&key).join("LATEST");
         write_atomic(latest_path.as_path(), version_id.as_bytes())?;
 
+        self.update_index_for_put(&lobe, &key, &version_id, data.len() as u64);
+
         Ok((version_id, etag, file_path))
     }
 
@@ -105,8 +119,13 @@ impl LobeStore {
 
     /// List the latest version of keys under a given lobe.
     ///
-    /// - Traverses recursively from the lobe directory.
-    /// - Uses the `LATEST` pointer or fallback scanning.
+    /// - Serves from `objects/<lobe>/.index` when it parses cleanly, so no
+    ///   filesystem traversal or per-key stat is needed.
+    /// - If the index is missing, truncated, or its header doesn't match,
+    ///   it's treated as a pure cache: falls back to the full directory
+    ///   scan and rebuilds the index from the result (best-effort; a
+    ///   rebuild failure doesn't fail the call, since the index is only a
+    ///   cache).
     /// - Returns up to `limit` entries of `(key, version_id, size_bytes)`.
     pub fn list_latest(&self, lobe: &str, prefix: Option<&str>, limit: usize) -> Result<Vec<(String, String, u64)>> {
         let lobe = sanitize_key(lobe)?;
@@ -117,8 +136,46 @@ impl LobeStore {
             return Ok(Vec::new());
         }
 
+        let entries = match self.read_index(&lobe) {
+            Some(entries) => entries,
+            None => {
+                let entries = self.scan_all_entries(&lobe_dir)?;
+                let _ = self.write_index(&lobe, &entries);
+                entries
+            }
+        };
+
         let mut out = Vec::new();
-        let mut stack = vec![lobe_dir.clone()];
+        for (key_rel, version, size) in entries {
+            if !key_rel.starts_with(pref) {
+                continue;
+            }
+            out.push((key_rel, version, size));
+            if out.len() >= limit {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Explicitly repair `objects/<lobe>/.index` from a full directory
+    /// scan, discarding whatever (if anything) is currently on disk.
+    /// Returns the number of keys indexed.
+    pub fn rebuild_index(&self, lobe: &str) -> Result<usize> {
+        let lobe = sanitize_key(lobe)?;
+        let lobe_dir = self.root.join("objects").join(&lobe);
+        let entries = self.scan_all_entries(&lobe_dir)?;
+        self.write_index(&lobe, &entries)?;
+        Ok(entries.len())
+    }
+
+    /// Walk every key under `lobe_dir`, resolving each one's latest version
+    /// and size exactly as [`Self::list_latest`] did before the index
+    /// existed. The only filesystem-heavy path left; everything else reads
+    /// the cached index.
+    fn scan_all_entries(&self, lobe_dir: &Path) -> Result<Vec<(String, String, u64)>> {
+        let mut out = Vec::new();
+        let mut stack = vec![lobe_dir.to_path_buf()];
         while let Some(dir) = stack.pop() {
             for entry in fs::read_dir(&dir)? {
                 let entry = entry?;
@@ -127,22 +184,17 @@ impl LobeStore {
                 if path.is_dir() && path.file_name().map(|s| s != OsStr::new("versions")).unwrap_or(false) {
                     let versions = path.join("versions");
                     if versions.is_dir() {
-                        // derive key relative to lobe root
-                        let key_rel = path.strip_prefix(&lobe_dir).unwrap().to_string_lossy().replace('\\', "/");
-                        if !key_rel.starts_with(pref) { continue; }
+                        let key_rel = path.strip_prefix(lobe_dir).unwrap().to_string_lossy().replace('\\', "/");
 
-                        // latest version via pointer or scan
                         let latest = match fs::read_to_string(path.join("LATEST")) {
                             Ok(s) => s.trim().to_string(),
                             Err(_) => self.scan_latest_version(&path)?,
                         };
 
-                        // file size of latest object
                         let fpath = versions.join(format!("{latest}.bin"));
                         let sz = fs::metadata(&fpath)?.len();
 
                         out.push((key_rel, latest, sz));
-                        if out.len() >= limit { return Ok(out); }
                     } else {
                         stack.push(path);
                     }
@@ -152,6 +204,190 @@ impl LobeStore {
         Ok(out)
     }
 
+    /// Keep `objects/<lobe>/.index` in sync with one `put_object`, without
+    /// a directory walk: upsert this key's entry into whatever the index
+    /// currently holds and rewrite it. If the index isn't currently
+    /// readable (missing/stale), it's left alone -- the next
+    /// [`Self::list_latest`] cache miss rebuilds it from a full scan,
+    /// which will already see this write.
+    fn update_index_for_put(&self, lobe: &str, key: &str, version_id: &str, size: u64) {
+        let Some(mut entries) = self.read_index(lobe) else {
+            return;
+        };
+        match entries.iter_mut().find(|(k, _, _)| k == key) {
+            Some(existing) => *existing = (key.to_string(), version_id.to_string(), size),
+            None => entries.push((key.to_string(), version_id.to_string(), size)),
+        }
+        let _ = self.write_index(lobe, &entries);
+    }
+
+    fn index_path(&self, lobe: &str) -> PathBuf {
+        self.root.join("objects").join(lobe).join(".index")
+    }
+
+    /// Read and decode `objects/<lobe>/.index`. Returns `None` on any
+    /// problem at all (missing file, truncated read, bad magic/version) --
+    /// the index is a pure cache, so every failure mode is just a miss.
+    fn read_index(&self, lobe: &str) -> Option<Vec<(String, String, u64)>> {
+        let bytes = fs::read(self.index_path(lobe)).ok()?;
+        decode_index(&bytes)
+    }
+
+    fn write_index(&self, lobe: &str, entries: &[(String, String, u64)]) -> Result<()> {
+        write_atomic(&self.index_path(lobe), &encode_index(entries))
+    }
+
+    /// Prune old versions of a single `(lobe, key)`, keeping the newest
+    /// `retain` versions plus whatever `LATEST` points at (even if older).
+    ///
+    /// Returns the number of versions deleted. A no-op if the key has no
+    /// `versions/` directory or already fits within `retain`.
+    pub fn compact(&self, lobe: &str, key: &str, retain: usize) -> Result<usize> {
+        let lobe = sanitize_key(lobe)?;
+        let key = sanitize_key(key)?;
+        let base = self.root.join("objects").join(&lobe).join(&key);
+        let versions_dir = base.join("versions");
+        if !versions_dir.is_dir() {
+            return Ok(0);
+        }
+
+        // Filenames sort lexicographically by `{ts_ms}-{hash12}`, so the
+        // newest `retain` are simply the tail after sorting.
+        let mut versions: Vec<String> = fs::read_dir(&versions_dir)?
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                e.file_name()
+                    .to_str()
+                    .and_then(|n| n.strip_suffix(".bin"))
+                    .map(|v| v.to_string())
+            })
+            .collect();
+        versions.sort();
+
+        let mut keep: HashSet<String> = versions.iter().rev().take(retain).cloned().collect();
+        if let Ok(latest) = fs::read_to_string(base.join("LATEST")) {
+            keep.insert(latest.trim().to_string());
+        }
+
+        let to_delete: Vec<String> = versions.into_iter().filter(|v| !keep.contains(v)).collect();
+        if to_delete.is_empty() {
+            return Ok(0);
+        }
+
+        for version in &to_delete {
+            self.append_manifest(&ManifestEntry::Delete {
+                lobe: lobe.clone(),
+                key: key.clone(),
+                version: version.clone(),
+            })?;
+            delete_version_file(&versions_dir, version)?;
+        }
+        self.append_manifest(&ManifestEntry::Checkpoint)?;
+
+        Ok(to_delete.len())
+    }
+
+    /// Run [`Self::compact`] over every key under `lobe`. Returns the total
+    /// number of versions deleted.
+    pub fn compact_lobe(&self, lobe: &str, retain: usize) -> Result<usize> {
+        let lobe_s = sanitize_key(lobe)?;
+        let lobe_dir = self.root.join("objects").join(&lobe_s);
+        if !lobe_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut total = 0;
+        let mut stack = vec![lobe_dir.clone()];
+        while let Some(dir) = stack.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if !path.is_dir() || path.file_name() == Some(OsStr::new("versions")) {
+                    continue;
+                }
+                if path.join("versions").is_dir() {
+                    let key_rel = path
+                        .strip_prefix(&lobe_dir)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/");
+                    total += self.compact(&lobe_s, &key_rel, retain)?;
+                } else {
+                    stack.push(path);
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Run [`Self::compact_lobe`] over every lobe in the store. Returns the
+    /// total number of versions deleted.
+    pub fn compact_all(&self, retain: usize) -> Result<usize> {
+        let objects_dir = self.root.join("objects");
+        if !objects_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut total = 0;
+        for entry in fs::read_dir(&objects_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !path.is_dir() {
+                continue; // skips the MANIFEST file itself
+            }
+            let lobe = path.file_name().unwrap().to_string_lossy().into_owned();
+            total += self.compact_lobe(&lobe, retain)?;
+        }
+        Ok(total)
+    }
+
+    /// Replay any un-checkpointed tail of `objects/MANIFEST` left by a crash
+    /// mid-batch: retry each pending delete (idempotent -- a missing file is
+    /// not an error), then close the tail out with a checkpoint record.
+    /// Absent `MANIFEST` is treated as an empty, already-consistent store.
+    fn reconcile_manifest(&self) -> Result<()> {
+        let path = self.manifest_path();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Ok(());
+        };
+
+        let entries: Vec<ManifestEntry> = contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect();
+
+        let tail_start = entries
+            .iter()
+            .rposition(|e| matches!(e, ManifestEntry::Checkpoint))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let tail = &entries[tail_start..];
+        if tail.is_empty() {
+            return Ok(());
+        }
+
+        for entry in tail {
+            if let ManifestEntry::Delete { lobe, key, version } = entry {
+                let versions_dir = self.root.join("objects").join(lobe).join(key).join("versions");
+                delete_version_file(&versions_dir, version)?;
+            }
+        }
+        self.append_manifest(&ManifestEntry::Checkpoint)?;
+        Ok(())
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.root.join("objects").join("MANIFEST")
+    }
+
+    fn append_manifest(&self, entry: &ManifestEntry) -> Result<()> {
+        let path = self.manifest_path();
+        let mut f = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(f, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
     /// Internal: scan the `versions/` directory to find the latest version.
     ///
     /// Picks the lexicographically max filename (timestamps ensure ordering).
@@ -173,8 +409,100 @@ impl LobeStore {
     }
 }
 
+// ---------- on-disk list index ----------
+//
+// `objects/<lobe>/.index` is a fixed-layout binary cache (inspired by
+// Mercurial's dirstate-v2 format) so `list_latest` can read it without any
+// deserialization beyond little-endian integer decoding:
+//
+//   header:  magic: u32 | format_version: u16 | entry_count: u32
+//   record*: key_len: u16 | key bytes (utf-8)
+//            version_id_len: u8 | version_id bytes (utf-8)
+//            size_bytes: u64
+
+const INDEX_MAGIC: u32 = 0x4C4F_4245; // "LOBE"
+const INDEX_FORMAT_VERSION: u16 = 1;
+const INDEX_HEADER_LEN: usize = 4 + 2 + 4;
+
+fn encode_index(entries: &[(String, String, u64)]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(INDEX_HEADER_LEN + entries.len() * 32);
+    buf.extend_from_slice(&INDEX_MAGIC.to_le_bytes());
+    buf.extend_from_slice(&INDEX_FORMAT_VERSION.to_le_bytes());
+    buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (key, version_id, size) in entries {
+        let key_bytes = key.as_bytes();
+        buf.extend_from_slice(&(key_bytes.len() as u16).to_le_bytes());
+        buf.extend_from_slice(key_bytes);
+        let version_bytes = version_id.as_bytes();
+        buf.push(version_bytes.len() as u8);
+        buf.extend_from_slice(version_bytes);
+        buf.extend_from_slice(&size.to_le_bytes());
+    }
+    buf
+}
+
+/// Decode `bytes` into `(key, version_id, size_bytes)` records, or `None`
+/// if the header doesn't match or a record runs past the end of the
+/// buffer -- any corruption is just a cache miss, never an error.
+fn decode_index(bytes: &[u8]) -> Option<Vec<(String, String, u64)>> {
+    if bytes.len() < INDEX_HEADER_LEN {
+        return None;
+    }
+    let magic = u32::from_le_bytes(bytes[0..4].try_into().ok()?);
+    let format_version = u16::from_le_bytes(bytes[4..6].try_into().ok()?);
+    if magic != INDEX_MAGIC || format_version != INDEX_FORMAT_VERSION {
+        return None;
+    }
+    let entry_count = u32::from_le_bytes(bytes[6..10].try_into().ok()?) as usize;
+
+    let mut pos = INDEX_HEADER_LEN;
+    let mut out = Vec::with_capacity(entry_count);
+    for _ in 0..entry_count {
+        let key_len = u16::from_le_bytes(bytes.get(pos..pos + 2)?.try_into().ok()?) as usize;
+        pos += 2;
+        let key = std::str::from_utf8(bytes.get(pos..pos + key_len)?).ok()?.to_string();
+        pos += key_len;
+
+        let version_len = *bytes.get(pos)? as usize;
+        pos += 1;
+        let version_id = std::str::from_utf8(bytes.get(pos..pos + version_len)?)
+            .ok()?
+            .to_string();
+        pos += version_len;
+
+        let size = u64::from_le_bytes(bytes.get(pos..pos + 8)?.try_into().ok()?);
+        pos += 8;
+
+        out.push((key, version_id, size));
+    }
+    Some(out)
+}
+
 // ---------- helpers ----------
 
+/// One line of `objects/MANIFEST`: either a pending deletion or a
+/// checkpoint marking every edit before it as applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum ManifestEntry {
+    Delete {
+        lobe: String,
+        key: String,
+        version: String,
+    },
+    Checkpoint,
+}
+
+/// Delete `<versions_dir>/<version>.bin`, tolerating an already-missing file.
+fn delete_version_file(versions_dir: &Path, version: &str) -> Result<()> {
+    let path = versions_dir.join(format!("{version}.bin"));
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Atomically write bytes to a file.
 /// Uses a `.tmp` file then renames for crash-safety.
 fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
@@ -0,0 +1,238 @@
+//! services/logbook.rs
+//! Segmented, rotating, retention-enforced storage for the logbook streams
+//! written by [`crate::services::audit`].
+//!
+//! Each logical stream (e.g. `logbook/ethics.jsonl`) is one *active* segment
+//! file plus zero or more *sealed* segments left behind by earlier rotations.
+//! Records are framed LevelDB-log-style -- `[len: u32 LE][crc32: u32 LE]
+//! [payload]` -- rather than newline-delimited text, so a torn write at the
+//! tail (process killed mid-append) is detected by a length/CRC mismatch and
+//! that one incomplete record is dropped on read instead of corrupting every
+//! record before it.
+//!
+//! `append_event` routes a write to the active segment and, once it crosses
+//! `LogbookConfig::max_segment_bytes`, seals it to a timestamped filename
+//! (optionally gzip-compressing it) and prunes sealed segments older than
+//! `retention_days`. `read_events` stitches every sealed segment (compressed
+//! or not) and the active segment back into one chronological record list.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::LogbookConfig;
+
+const TIMESTAMP_FMT: &str = "%Y-%m-%dT%H-%M-%S%.9fZ";
+
+/// Append `val` to the active segment at `path`, rotating it to a sealed,
+/// timestamped segment (and pruning old sealed segments) once the active
+/// segment reaches `cfg.max_segment_bytes`.
+pub fn append_event(
+    path: &Path,
+    val: &Value,
+    cfg: &LogbookConfig,
+    retention_days: u32,
+) -> Result<()> {
+    let payload = serde_json::to_vec(val).context("serializing logbook record")?;
+    append_frame(path, &payload)?;
+    if cfg.max_segment_bytes > 0 {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if size >= cfg.max_segment_bytes {
+            rotate(path, cfg, retention_days)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read every record ever appended to the stream at `path`, oldest first:
+/// sealed segments (decompressing `.jsonl.gz` ones as needed) in rotation
+/// order, followed by whatever is in the active segment. A torn trailing
+/// record in any one segment is silently dropped rather than failing the
+/// whole read.
+pub fn read_events(path: &Path) -> Result<Vec<Value>> {
+    let mut events = Vec::new();
+    for sealed in sealed_segments(path)? {
+        let bytes = read_segment_bytes(&sealed)
+            .with_context(|| format!("reading sealed segment {:?}", sealed))?;
+        events.extend(decode_frames(&bytes));
+    }
+    if path.exists() {
+        let bytes = fs::read(path).with_context(|| format!("reading {:?}", path))?;
+        events.extend(decode_frames(&bytes));
+    }
+    Ok(events)
+}
+
+fn read_segment_bytes(path: &Path) -> Result<Vec<u8>> {
+    let raw = fs::read(path)?;
+    if path.extension().and_then(|e| e.to_str()) == Some("gz") {
+        let mut decoder = GzDecoder::new(raw.as_slice());
+        let mut out = Vec::new();
+        decoder.read_to_end(&mut out)?;
+        Ok(out)
+    } else {
+        Ok(raw)
+    }
+}
+
+fn append_frame(path: &Path, payload: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("create_dir_all({:?})", parent))?;
+    }
+    let mut f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {:?} for append", path))?;
+    f.write_all(&encode_frame(payload))?;
+    Ok(())
+}
+
+fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(8 + payload.len());
+    buf.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
+
+/// Decode as many complete, checksummed frames as are present in `bytes`.
+/// Stops (without erroring) at the first frame whose header promises more
+/// bytes than remain, or whose payload doesn't match its stored CRC -- both
+/// are the signature of a write that was torn by a crash or kill.
+fn decode_frames(bytes: &[u8]) -> Vec<Value> {
+    let mut out = Vec::new();
+    let mut pos = 0usize;
+    while pos + 8 <= bytes.len() {
+        let len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+        let crc = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap());
+        let start = pos + 8;
+        let Some(end) = start.checked_add(len).filter(|&e| e <= bytes.len()) else {
+            break;
+        };
+        let payload = &bytes[start..end];
+        if crc32fast::hash(payload) != crc {
+            break;
+        }
+        if let Ok(val) = serde_json::from_slice::<Value>(payload) {
+            out.push(val);
+        }
+        pos = end;
+    }
+    out
+}
+
+/// Seal the active segment at `path` to a timestamped filename, gzip it if
+/// `cfg.compress_segments`, and prune sealed segments of this stream older
+/// than `retention_days`. The caller's next `append_event`/`append_frame`
+/// recreates `path` as a fresh, empty active segment.
+fn rotate(path: &Path, cfg: &LogbookConfig, retention_days: u32) -> Result<()> {
+    let stem = stream_stem(path);
+    let dir = stream_dir(path);
+    let sealed = dir.join(format!("{stem}-{}.jsonl", Utc::now().format(TIMESTAMP_FMT)));
+    fs::rename(path, &sealed).with_context(|| format!("sealing {:?} -> {:?}", path, sealed))?;
+    if cfg.compress_segments {
+        compress_segment(&sealed)?;
+    }
+    enforce_retention(&dir, &stem, retention_days)?;
+    Ok(())
+}
+
+fn compress_segment(path: &Path) -> Result<()> {
+    let data = fs::read(path).with_context(|| format!("reading {:?}", path))?;
+    let gz_path = PathBuf::from(format!("{}.gz", path.display()));
+    let tmp = PathBuf::from(format!("{}.gz.tmp", path.display()));
+    {
+        let f = fs::File::create(&tmp).with_context(|| format!("creating {:?}", tmp))?;
+        let mut encoder = GzEncoder::new(f, Compression::default());
+        encoder.write_all(&data)?;
+        encoder.finish()?;
+    }
+    fs::rename(&tmp, &gz_path).with_context(|| format!("rename {:?} -> {:?}", tmp, gz_path))?;
+    fs::remove_file(path).with_context(|| format!("removing sealed plaintext {:?}", path))?;
+    Ok(())
+}
+
+/// Delete sealed segments of the `stem` stream under `dir` whose sealing
+/// time (parsed back out of the filename) is older than `retention_days`.
+/// `retention_days == 0` means "keep forever" -- mirrors the no-op-threshold
+/// convention used by `audit.checkpoint_interval`.
+fn enforce_retention(dir: &Path, stem: &str, retention_days: u32) -> Result<()> {
+    if retention_days == 0 {
+        return Ok(());
+    }
+    let cutoff = Utc::now() - Duration::days(retention_days as i64);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    let prefix = format!("{stem}-");
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(rest) = name.strip_prefix(&prefix) else {
+            continue;
+        };
+        let Some(ts) = rest
+            .strip_suffix(".jsonl.gz")
+            .or_else(|| rest.strip_suffix(".jsonl"))
+        else {
+            continue;
+        };
+        let Some(sealed_at) = parse_segment_timestamp(ts) else {
+            continue;
+        };
+        if sealed_at < cutoff {
+            let _ = fs::remove_file(&path);
+        }
+    }
+    Ok(())
+}
+
+fn parse_segment_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    chrono::NaiveDateTime::parse_from_str(ts, TIMESTAMP_FMT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Sealed segments belonging to the stream at `path` (both compressed and,
+/// if a prior run died between sealing and compressing, plain), oldest
+/// first -- the timestamped filename format sorts lexicographically in
+/// rotation order.
+fn sealed_segments(path: &Path) -> Result<Vec<PathBuf>> {
+    let dir = stream_dir(path);
+    let prefix = format!("{}-", stream_stem(path));
+    let mut found = Vec::new();
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            let Some(name) = p.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if name.starts_with(&prefix) && (name.ends_with(".jsonl") || name.ends_with(".jsonl.gz"))
+            {
+                found.push(p);
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+fn stream_stem(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("segment")
+        .to_string()
+}
+
+fn stream_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
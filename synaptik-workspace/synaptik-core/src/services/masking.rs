@@ -1,79 +1,234 @@
-use contracts::normalize::for_rules;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error as AnyhowError};
+use blake3;
+use contracts::normalize::{for_rules, normalized_with_spans};
+use serde_json::{Map, Value};
 
 /// Normalization shim to keep call-sites concise.
 pub fn norm_lower(s: &str) -> String { for_rules(s) }
 
 /// Build a normalized character view of `s` along with original byte spans.
 /// Each produced normalized char corresponds to an original (start,end) byte span.
-/// Characters removed by normalization emit no span entries.
+/// Characters removed by normalization (control, ANSI CSI, zero-width) emit no
+/// span entries. Delegates to `contracts::normalize` so masking recognizes the
+/// same ANSI/zero-width evasions as the streaming gate and evaluator.
 pub fn normalized_chars_with_spans(s: &str) -> (Vec<char>, Vec<(usize, usize)>) {
-    let mut chars = Vec::new();
-    let mut spans = Vec::new();
-    for (orig_start, ch) in s.char_indices() {
-        let orig_end = orig_start + ch.len_utf8();
-        let norm_frag = norm_lower(&ch.to_string());
-        if norm_frag.is_empty() { continue; }
-        for nc in norm_frag.chars() {
-            chars.push(nc);
-            spans.push((orig_start, orig_end));
-        }
-    }
-    (chars, spans)
+    normalized_with_spans(s)
 }
 
 /// Case-insensitive masking of literal patterns using normalization-aware span mapping.
 /// Replaces matches with the literal token "[masked]".
+///
+/// Delegates to `contracts::apply_masks`, the canonical implementation shared
+/// with `PatchOp::MaskText`, so StreamGate and Librarian consumers mask
+/// identically to the patch subsystem.
 pub fn apply_masks_ci(text: &str, patterns: &[String]) -> String {
-    if patterns.is_empty() { return text.to_string(); }
-    let mut out = text.to_string();
-    const MASK: &str = "[masked]";
-
-    for pat in patterns {
-        if pat.is_empty() { continue; }
-        let pat_chars: Vec<char> = norm_lower(pat).chars().collect();
-        if pat_chars.is_empty() { continue; }
-
-        // Recompute normalized view for current `out` so earlier replacements are visible.
-        let (norm_chars, spans) = normalized_chars_with_spans(&out);
-        if norm_chars.is_empty() { continue; }
-        if pat_chars.len() > norm_chars.len() { continue; }
-
-        // Collect original byte ranges for all matches in this pass.
-        let plen = pat_chars.len();
-        let mut ranges: Vec<(usize, usize)> = Vec::new();
-        let mut i = 0usize;
-        while i + plen <= norm_chars.len() {
-            let mut ok = true;
-            for j in 0..plen {
-                if norm_chars[i + j] != pat_chars[j] { ok = false; break; }
+    contracts::apply_masks(text, patterns)
+}
+
+/// True for a rule with no regex metacharacters, i.e. one that means
+/// exactly what it says as a literal phrase.
+fn is_plain_literal(rule: &str) -> bool {
+    !rule
+        .chars()
+        .any(|c| matches!(c, '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' | '\\'))
+}
+
+/// Mask `ConstraintSpec::mask_rules` against `text`. A plain-literal rule
+/// (no regex metacharacters, e.g. `"password"`) is matched obfuscation-aware
+/// via `contracts::apply_masks` -- confusable folding plus spacing/duplicate
+/// collapsing (see `contracts::normalize`) -- so `"p a s s w o r d"` and
+/// `"p4ssw0rd"` are caught too. A rule containing regex metacharacters is
+/// left on the plain normalized matcher, `contracts::apply_regex_masks`,
+/// since folding would corrupt character classes like `\d{3}`. An invalid
+/// regex rule is logged and skipped rather than aborting the rest.
+pub fn apply_mask_rules(text: &str, rules: &[String]) -> String {
+    let (literal, regex): (Vec<String>, Vec<String>) =
+        rules.iter().cloned().partition(|r| is_plain_literal(r));
+    let masked = contracts::apply_masks(text, &literal);
+    contracts::apply_regex_masks(&masked, &regex)
+}
+
+/// Stable per-build salt folded into [`Transform::Hash`]'s digest so the same
+/// secret collapses to the same token across capsules instead of a fresh
+/// digest (or the plaintext) leaking per occurrence.
+const HASH_TRANSFORM_SALT: &str = "synaptik-core/compactor/transform-hash-v1";
+
+/// One step of the constraint-driven sanitization pipeline
+/// `Compactor::eval_summary_with_contracts` builds from a rule's
+/// `constraints` list. Parsed via [`FromStr`] from a `"<directive>:<arg>"`
+/// string; an unrecognized directive (or an empty pattern where one's
+/// required) fails to parse, so the caller can tell a constraint went
+/// unhandled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transform {
+    /// `mask:<pat>` -- replace matches with `[masked]`.
+    Mask(String),
+    /// `redact:<pat>` -- same replacement as `Mask`; kept as a distinct
+    /// directive so contract authors can label intent (PII vs. policy text)
+    /// even though today's handling is identical.
+    Redact(String),
+    /// `hash:<pat>` -- replace matches with a stable salted digest, so the
+    /// same secret collapses to the same token across capsules without the
+    /// plaintext surviving anywhere in the summary.
+    Hash(String),
+    /// `truncate:<n>` -- cap the summary to at most `n` chars.
+    Truncate(usize),
+    /// `tokenize:<pat>` -- replace matches with numbered placeholders
+    /// (`[TOKEN_1]`, `[TOKEN_2]`, ...) and record the placeholder -> original
+    /// mapping (via [`apply_transform_pipeline`]'s `token_map`) so the
+    /// substitution is reversible for audit.
+    Tokenize(String),
+}
+
+impl FromStr for Transform {
+    type Err = AnyhowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(p) = s.strip_prefix("mask:") {
+            return non_empty(p).map(Transform::Mask);
+        }
+        if let Some(p) = s.strip_prefix("redact:") {
+            return non_empty(p).map(Transform::Redact);
+        }
+        if let Some(p) = s.strip_prefix("hash:") {
+            return non_empty(p).map(Transform::Hash);
+        }
+        if let Some(p) = s.strip_prefix("truncate:") {
+            let n: usize = p
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("truncate: expects an integer length, got {:?}", p.trim()))?;
+            return Ok(Transform::Truncate(n));
+        }
+        if let Some(p) = s.strip_prefix("tokenize:") {
+            return non_empty(p).map(Transform::Tokenize);
+        }
+        Err(anyhow!("unrecognized transform directive: {:?}", s))
+    }
+}
+
+fn non_empty(pattern: &str) -> Result<String, AnyhowError> {
+    let p = pattern.trim();
+    if p.is_empty() {
+        Err(anyhow!("transform directive is missing its pattern"))
+    } else {
+        Ok(p.to_string())
+    }
+}
+
+impl Transform {
+    /// Render back to the `"<directive>:<arg>"` form this was parsed from,
+    /// for pipeline notes and trace entries.
+    pub fn describe(&self) -> String {
+        match self {
+            Transform::Mask(p) => format!("mask:{p}"),
+            Transform::Redact(p) => format!("redact:{p}"),
+            Transform::Hash(p) => format!("hash:{p}"),
+            Transform::Truncate(n) => format!("truncate:{n}"),
+            Transform::Tokenize(p) => format!("tokenize:{p}"),
+        }
+    }
+
+    /// Original-byte-span matches of `pattern` in `text`, reusing the same
+    /// obfuscation-aware literal/regex split as [`apply_mask_rules`] so
+    /// `hash:`/`tokenize:` catch the same evasions `mask:`/`redact:` do.
+    fn pattern_spans(text: &str, pattern: &str) -> Vec<(usize, usize)> {
+        if is_plain_literal(pattern) {
+            contracts::apply_masks_with_spans(text, std::slice::from_ref(&pattern.to_string())).1
+        } else {
+            contracts::apply_regex_masks_with_spans(text, std::slice::from_ref(&pattern.to_string())).1
+        }
+    }
+
+    fn apply(&self, text: &str, token_seq: &mut usize, token_map: &mut Map<String, Value>) -> String {
+        match self {
+            Transform::Mask(p) | Transform::Redact(p) => {
+                apply_mask_rules(text, std::slice::from_ref(p))
             }
-            if ok {
-                let (s, _) = spans[i];
-                let (_, e) = spans[i + plen - 1];
-                ranges.push((s, e));
-                // Advance by 1 to allow overlapping matches (e.g., pattern "aa" in "aaa").
-                i += 1;
-            } else {
-                i += 1;
+            Transform::Hash(p) => {
+                let spans = Self::pattern_spans(text, p);
+                replace_spans(text, &spans, salted_hash_token)
             }
-        }
-        if ranges.is_empty() { continue; }
-
-        // Merge overlapping/adjacent ranges then replace from the end to keep indices stable.
-        ranges.sort_by_key(|r| r.0);
-        let mut binding: Vec<(usize, usize)> = Vec::new();
-        for (s, e) in ranges.into_iter() {
-            if let Some(last) = binding.last_mut() {
-                if s <= last.1 { last.1 = last.1.max(e); continue; }
+            Transform::Truncate(n) => {
+                if text.chars().count() > *n {
+                    text.chars().take(*n).collect()
+                } else {
+                    text.to_string()
+                }
+            }
+            Transform::Tokenize(p) => {
+                let spans = Self::pattern_spans(text, p);
+                replace_spans(text, &spans, |m| {
+                    *token_seq += 1;
+                    let token = format!("[TOKEN_{token_seq}]");
+                    token_map.insert(token.clone(), Value::String(m.to_string()));
+                    token
+                })
             }
-            binding.push((s, e));
         }
-        for (s, e) in binding.into_iter().rev() {
-            if s >= e || e > out.len() { continue; }
-            out.replace_range(s..e, MASK);
+    }
+}
+
+/// Result of [`apply_transform_pipeline`]: the transformed text, one
+/// human-readable note per transform that actually fired, and (if any
+/// `tokenize:` transforms fired) the placeholder -> original-match mapping
+/// for the capsule trace.
+#[derive(Debug, Clone, Default)]
+pub struct TransformPipelineResult {
+    pub text: String,
+    pub notes: Vec<String>,
+    pub token_map: Map<String, Value>,
+}
+
+/// Apply `transforms` to `text` in order, each seeing the previous step's
+/// output -- the extensible successor to the old fixed `mask:`/`redact:`
+/// two-case match in `Compactor::eval_summary_with_contracts`.
+pub fn apply_transform_pipeline(text: &str, transforms: &[Transform]) -> TransformPipelineResult {
+    let mut out = text.to_string();
+    let mut notes = Vec::new();
+    let mut token_map = Map::new();
+    let mut token_seq = 0usize;
+
+    for t in transforms {
+        let before = out.clone();
+        out = t.apply(&out, &mut token_seq, &mut token_map);
+        if out != before {
+            notes.push(t.describe());
         }
     }
 
-    out
+    TransformPipelineResult {
+        text: out,
+        notes,
+        token_map,
+    }
+}
+
+fn salted_hash_token(matched: &str) -> String {
+    let mut buf = Vec::with_capacity(HASH_TRANSFORM_SALT.len() + matched.len());
+    buf.extend_from_slice(HASH_TRANSFORM_SALT.as_bytes());
+    buf.extend_from_slice(matched.as_bytes());
+    let digest = blake3::hash(&buf).to_hex();
+    format!("[hash:{}]", &digest.as_str()[..16])
 }
 
+/// Replace each `(start, end)` span in `text` with `replacement(matched)`,
+/// computed left-to-right (so e.g. `Transform::Tokenize`'s counter numbers
+/// matches in reading order) but applied right-to-left against `out` so
+/// earlier byte offsets stay valid as later ones are rewritten.
+fn replace_spans(text: &str, spans: &[(usize, usize)], mut replacement: impl FnMut(&str) -> String) -> String {
+    let mut planned: Vec<(usize, usize, String)> = spans
+        .iter()
+        .map(|&(s, e)| (s, e, replacement(&text[s..e])))
+        .collect();
+    planned.sort_by_key(|r| r.0);
+
+    let mut out = text.to_string();
+    for (s, e, rep) in planned.into_iter().rev() {
+        out.replace_range(s..e, &rep);
+    }
+    out
+}
@@ -0,0 +1,222 @@
+// src/services/memid_index.rs
+//! Authenticated `memory_id -> cid` index for the Archivist.
+//!
+//! The Archivist is purely CID-addressed; the `memory_id -> cid` mapping
+//! otherwise lives only in SQLite and can't be independently proven. This
+//! is a fixed-depth sparse Merkle trie keyed by `blake3(memory_id)` (so the
+//! tree stays balanced regardless of how `memory_id`s are formatted), with
+//! leaves committing to the literal `memory_id` and `cid`. A third party
+//! can verify "this memory_id maps to this exact content" against a single
+//! published root hash, without trusting the database.
+//!
+//! - Leaf hash: `blake3(memory_id || cid)`.
+//! - Internal node hash: `blake3(left || right)`.
+//! - Empty subtrees at every depth hash to a fixed default, so the trie
+//!   never needs to materialize the full `2^256` leaf space.
+//! - The `(memory_id, cid)` pairs are the canonical persisted state
+//!   (`<archive_root>/.memid_index.jsonl`, one compacted entry per
+//!   `memory_id`); the trie itself is rebuilt from them on every query,
+//!   the same replay-over-rebuild approach `archivist::replay_refs` uses
+//!   for the refcount journal.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const DEPTH: usize = 256;
+const INDEX_FILE: &str = ".memid_index.jsonl";
+const EMPTY_LEAF: [u8; 32] = [0u8; 32];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexEntry {
+    memory_id: String,
+    cid: String,
+}
+
+/// Ordered sibling hashes from the leaf's level up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// Authenticated `memory_id -> cid` index, persisted under the archive root.
+#[derive(Debug)]
+pub struct MemidIndex {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, String>>,
+}
+
+impl MemidIndex {
+    /// Load (or initialize) the index under `archive_root`.
+    pub fn open(archive_root: &Path) -> Result<Self> {
+        let path = archive_root.join(INDEX_FILE);
+        let mut entries = BTreeMap::new();
+        if let Ok(contents) = fs::read_to_string(&path) {
+            for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+                if let Ok(entry) = serde_json::from_str::<IndexEntry>(line) {
+                    entries.insert(entry.memory_id, entry.cid);
+                }
+            }
+        }
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Record (or update, if `mark_archived` later points the same
+    /// `memory_id` at a new CID) one mapping, persisting the change
+    /// immediately so the index survives a restart.
+    pub fn set(&self, memory_id: &str, cid: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(memory_id.to_string(), cid.to_string());
+        persist(&self.path, &entries)
+    }
+
+    /// Current root hash of the trie over every recorded mapping.
+    pub fn root_hash(&self) -> [u8; 32] {
+        let entries = self.entries.lock().unwrap();
+        let leaves = leaf_list(&entries);
+        let defaults = default_hashes();
+        build(&leaves, 0, &defaults)
+    }
+
+    /// Build an inclusion proof for `memory_id`'s current mapping.
+    ///
+    /// Returns `None` if `memory_id` isn't present in the index.
+    pub fn prove(&self, memory_id: &str) -> Option<InclusionProof> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(memory_id)?;
+        let leaves = leaf_list(&entries);
+        let defaults = default_hashes();
+        let target = key_bits(memory_id);
+        let mut siblings = Vec::with_capacity(DEPTH);
+        build_with_proof(&leaves, 0, &defaults, &target, &mut siblings);
+        Some(InclusionProof { siblings })
+    }
+}
+
+/// Verify that `memory_id -> cid` is included under `root`, given `proof`.
+pub fn verify(root: &[u8; 32], memory_id: &str, cid: &str, proof: &InclusionProof) -> bool {
+    if proof.siblings.len() != DEPTH {
+        return false;
+    }
+    let key = key_bits(memory_id);
+    let mut hash = leaf_hash(memory_id, cid);
+    for (i, sibling) in proof.siblings.iter().enumerate() {
+        let depth = DEPTH - 1 - i;
+        hash = if bit_at(&key, depth) {
+            combine(sibling, &hash)
+        } else {
+            combine(&hash, sibling)
+        };
+    }
+    &hash == root
+}
+
+fn persist(path: &Path, entries: &BTreeMap<String, String>) -> Result<()> {
+    let mut buf = String::new();
+    for (memory_id, cid) in entries {
+        buf.push_str(&serde_json::to_string(&IndexEntry {
+            memory_id: memory_id.clone(),
+            cid: cid.clone(),
+        })?);
+        buf.push('\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, buf)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn leaf_list(entries: &BTreeMap<String, String>) -> Vec<([u8; 32], [u8; 32])> {
+    entries
+        .iter()
+        .map(|(memory_id, cid)| (key_bits(memory_id), leaf_hash(memory_id, cid)))
+        .collect()
+}
+
+fn key_bits(memory_id: &str) -> [u8; 32] {
+    *blake3::hash(memory_id.as_bytes()).as_bytes()
+}
+
+fn bit_at(key: &[u8; 32], depth: usize) -> bool {
+    let byte = key[depth / 8];
+    let bit_index = 7 - (depth % 8);
+    (byte >> bit_index) & 1 == 1
+}
+
+fn leaf_hash(memory_id: &str, cid: &str) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(memory_id.len() + cid.len());
+    buf.extend_from_slice(memory_id.as_bytes());
+    buf.extend_from_slice(cid.as_bytes());
+    *blake3::hash(&buf).as_bytes()
+}
+
+fn combine(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(left);
+    buf[32..].copy_from_slice(right);
+    *blake3::hash(&buf).as_bytes()
+}
+
+/// `default_hashes()[d]` is the hash of an empty subtree rooted at depth
+/// `d` (0 = root, `DEPTH` = leaf level), so branches with no entries don't
+/// need to be materialized.
+fn default_hashes() -> Vec<[u8; 32]> {
+    let mut defaults = vec![EMPTY_LEAF; DEPTH + 1];
+    for depth in (0..DEPTH).rev() {
+        defaults[depth] = combine(&defaults[depth + 1], &defaults[depth + 1]);
+    }
+    defaults
+}
+
+fn build(entries: &[([u8; 32], [u8; 32])], depth: usize, defaults: &[[u8; 32]]) -> [u8; 32] {
+    if entries.is_empty() {
+        return defaults[depth];
+    }
+    if depth == DEPTH {
+        return entries[0].1;
+    }
+    let (left, right): (Vec<_>, Vec<_>) = entries
+        .iter()
+        .copied()
+        .partition(|(key, _)| !bit_at(key, depth));
+    combine(
+        &build(&left, depth + 1, defaults),
+        &build(&right, depth + 1, defaults),
+    )
+}
+
+fn build_with_proof(
+    entries: &[([u8; 32], [u8; 32])],
+    depth: usize,
+    defaults: &[[u8; 32]],
+    target: &[u8; 32],
+    siblings: &mut Vec<[u8; 32]>,
+) -> [u8; 32] {
+    if entries.is_empty() {
+        return defaults[depth];
+    }
+    if depth == DEPTH {
+        return entries[0].1;
+    }
+    let (left, right): (Vec<_>, Vec<_>) = entries
+        .iter()
+        .copied()
+        .partition(|(key, _)| !bit_at(key, depth));
+    if bit_at(target, depth) {
+        let right_hash = build_with_proof(&right, depth + 1, defaults, target, siblings);
+        let left_hash = build(&left, depth + 1, defaults);
+        siblings.push(left_hash);
+        combine(&left_hash, &right_hash)
+    } else {
+        let left_hash = build_with_proof(&left, depth + 1, defaults, target, siblings);
+        let right_hash = build(&right, depth + 1, defaults);
+        siblings.push(right_hash);
+        combine(&left_hash, &right_hash)
+    }
+}
@@ -6,13 +6,22 @@
 //! - Tracks cold-storage pointers (`archived_cid` + `archived_at`) for Archivist.
 //! - Adds best-effort promotion helpers to write nodes into the DAG **linearly per lobe**.
 //! - Leaves reads DB-first for MVP (no DAG reads/pruning in this pass).
+//! - Mirrors `content`/`summary`/`reflection` into an external-content FTS5
+//!   index (`memories_fts`, kept in sync by triggers) so `search` can rank
+//!   hits without duplicating the text the index covers.
+//! - Offers MVCC-lite snapshot reads (`snapshot`/`recall_at`) so pruning and
+//!   summarization can tombstone/version rows instead of deleting or
+//!   overwriting content a live snapshot might still need.
 
 use anyhow::{Context, Result, bail};
 use blake3;
 use chrono::Utc;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::hooks::Action;
 use rusqlite::Connection;
 use serde_json::{json, Value};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::memory::dag;
@@ -27,6 +36,17 @@ use once_cell::sync::OnceCell;
 /// Expose `db` as `pub(crate)` if other services need read-only helpers internally.
 pub struct Memory {
     pub(crate) db: Connection,
+    /// Path this store was opened against, so read-only calls (`recall`)
+    /// can open their own reader connection instead of queuing behind the
+    /// single writer connection `db`.
+    db_path: String,
+    /// SQLCipher key, if opened via `open_encrypted`; applied to every
+    /// reader connection too.
+    key: Option<String>,
+    /// Outstanding [`Snapshot`]s taken via `snapshot()`, so pruning/compaction
+    /// can tell whether a row is still visible to the oldest live reader
+    /// before physically deleting or overwriting it.
+    snapshots: Arc<Mutex<SnapshotList>>,
 }
 
 /// Minimal candidate record for compaction.
@@ -36,6 +56,132 @@ pub struct MemoryCandidate {
     pub archived_cid: Option<String>,
 }
 
+/// One ranked result from [`Memory::search`].
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub memory_id: String,
+    pub lobe: String,
+    /// `bm25()` relevance score -- lower is more relevant, matching
+    /// SQLite's own convention for `ORDER BY`.
+    pub score: f64,
+    /// `snippet()`-highlighted excerpt from whichever of
+    /// `content`/`summary`/`reflection` scored the match.
+    pub snippet: String,
+}
+
+/// Page-count progress of an in-flight [`Memory::backup_to`]/[`Memory::restore_from`]
+/// call, reported between batches.
+#[derive(Debug, Clone, Copy)]
+pub struct BackupProgress {
+    /// Pages left to copy.
+    pub remaining: i32,
+    /// Total pages in the source database as of this batch.
+    pub total: i32,
+}
+
+/// One row of the `user_consent` table: an explicit, time-bounded grant that
+/// lets evaluation downgrade a specific `violation_code` from a hard
+/// violation to `allow_with_constraints` for this `(consenting_party,
+/// consented_party)` pair. See `contracts::ConsentGrant`/`ConsentContext` for
+/// how this flows into contract evaluation.
+#[derive(Debug, Clone)]
+pub struct UserConsent {
+    pub consenting_party: String,
+    pub consented_party: String,
+    pub violation_code: String,
+    /// RFC3339 UTC.
+    pub expires_at: String,
+    pub details: Value,
+}
+
+impl UserConsent {
+    fn from_row(row: &rusqlite::Row) -> Result<Self> {
+        let details_raw: String = row.get(4)?;
+        Ok(Self {
+            consenting_party: row.get(0)?,
+            consented_party: row.get(1)?,
+            violation_code: row.get(2)?,
+            expires_at: row.get(3)?,
+            details: serde_json::from_str(&details_raw).unwrap_or_else(|_| json!({})),
+        })
+    }
+
+    /// Build a `contracts::ConsentGrant` for this row, using
+    /// `"{consenting_party}:{consented_party}:{violation_code}"` as the
+    /// grant id so downstream logging can name exactly which grant applied.
+    /// A malformed `expires_at` is treated as already-expired (`0` unix
+    /// seconds) rather than panicking or granting indefinitely.
+    pub fn to_consent_grant(&self) -> contracts::ConsentGrant {
+        let expires_at_unix = chrono::DateTime::parse_from_rfc3339(&self.expires_at)
+            .map(|dt| dt.timestamp())
+            .unwrap_or(0);
+        contracts::ConsentGrant {
+            id: format!(
+                "{}:{}:{}",
+                self.consenting_party, self.consented_party, self.violation_code
+            ),
+            violation_code: self.violation_code.clone(),
+            expires_at_unix,
+        }
+    }
+}
+
+/// Registry of outstanding snapshot sequence numbers, keyed by `seq` with a
+/// refcount so two [`Snapshot`]s taken at the same `seq` don't let each
+/// other's `Drop` release it early. `oldest()` is what pruning consults to
+/// decide whether a row can be physically removed yet.
+#[derive(Debug, Default)]
+struct SnapshotList {
+    outstanding: std::collections::BTreeMap<i64, usize>,
+}
+
+impl SnapshotList {
+    fn acquire(&mut self, seq: i64) {
+        *self.outstanding.entry(seq).or_insert(0) += 1;
+    }
+
+    fn release(&mut self, seq: i64) {
+        if let std::collections::btree_map::Entry::Occupied(mut e) = self.outstanding.entry(seq) {
+            *e.get_mut() -= 1;
+            if *e.get() == 0 {
+                e.remove();
+            }
+        }
+    }
+
+    /// Lowest `seq` any live snapshot might still read at, or `None` if
+    /// nothing is outstanding.
+    fn oldest(&self) -> Option<i64> {
+        self.outstanding.keys().next().copied()
+    }
+}
+
+/// A point-in-time read handle from [`Memory::snapshot`]. Holding one pins
+/// `seq` as still-visible, so `prune_exact_duplicates_in_lobe` and
+/// `replace_with_summary` tombstone/version rows instead of deleting or
+/// overwriting them outright until every snapshot that could see the old
+/// content has been dropped. Pass it to [`Memory::recall_at`] to read
+/// `memory_id` as of this snapshot rather than the live row.
+pub struct Snapshot {
+    seq: i64,
+    registry: Arc<Mutex<SnapshotList>>,
+}
+
+impl Snapshot {
+    /// The sequence number this snapshot is pinned at.
+    pub fn seq(&self) -> i64 {
+        self.seq
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        if let Ok(mut list) = self.registry.lock() {
+            list.release(self.seq);
+        }
+    }
+}
+
 impl Memory {
     /// Open/create the SQLite DB and ensure schema.
     ///
@@ -44,6 +190,52 @@ impl Memory {
     /// - Opens SQLite and enables WAL (good for 1 writer + many readers).
     /// - Creates `memories` table and `(lobe, key)` index if they don't exist.
     pub fn open(db_path: &str) -> Result<Self> {
+        let db = Self::open_connection(db_path)?;
+        Self::init_schema(&db)?;
+        Ok(Self {
+            db,
+            db_path: db_path.to_string(),
+            key: None,
+            snapshots: Arc::new(Mutex::new(SnapshotList::default())),
+        })
+    }
+
+    /// Open/create the SQLite DB encrypted at rest via SQLCipher (requires
+    /// the `sqlcipher` build feature, which links SQLCipher in place of
+    /// stock SQLite). Identical to `open`, except it issues `PRAGMA
+    /// key`/`PRAGMA cipher_*` right after opening the connection and before
+    /// touching the schema, so `memories` and the `values`/`steps` tables
+    /// `StepAssembler` writes into are encrypted on disk -- logbook
+    /// previews and stored memory content can contain sensitive
+    /// conversation text that `open` otherwise leaves in plaintext.
+    #[cfg(feature = "sqlcipher")]
+    pub fn open_encrypted(db_path: &str, key: &str) -> Result<Self> {
+        let db = Self::open_connection(db_path)?;
+        apply_sqlcipher_key(&db, key)?;
+        Self::init_schema(&db)?;
+        Ok(Self {
+            db,
+            db_path: db_path.to_string(),
+            key: Some(key.to_string()),
+            snapshots: Arc::new(Mutex::new(SnapshotList::default())),
+        })
+    }
+
+    /// Open a fresh reader connection to the same database: WAL mode plus a
+    /// busy handler let this run concurrently with the single writer
+    /// connection (`self.db`) instead of queuing behind in-flight commits.
+    /// Schema is assumed to already exist (the writer's `open`/
+    /// `open_encrypted` created it), so this skips `init_schema`.
+    fn reader(&self) -> Result<Connection> {
+        let conn = Self::open_connection(&self.db_path)?;
+        #[cfg(feature = "sqlcipher")]
+        if let Some(key) = &self.key {
+            apply_sqlcipher_key(&conn, key)?;
+        }
+        Ok(conn)
+    }
+
+    fn open_connection(db_path: &str) -> Result<Connection> {
         if let Some(parent) = Path::new(db_path).parent() {
             std::fs::create_dir_all(parent)
                 .with_context(|| format!("creating parent dir for {}", db_path))?;
@@ -53,7 +245,30 @@ impl Memory {
             Connection::open(db_path).with_context(|| format!("opening sqlite at {}", db_path))?;
 
         db.busy_timeout(Duration::from_secs(5))?;
+        Ok(db)
+    }
 
+    /// Ensure schema exists on an already-open (and, if applicable, already
+    /// keyed) connection.
+    ///
+    /// Behavior:
+    /// - Enables WAL (good for 1 writer + many readers).
+    /// - Creates `memories` table and `(lobe, key)` index if they don't exist.
+    /// - Creates `memories_fts`, an external-content FTS5 index over
+    ///   `content`/`summary`/`reflection` (keyed on `memories.rowid`, not
+    ///   `memory_id`, since FTS5's own rowid-matching requires an integer),
+    ///   plus the AFTER INSERT/UPDATE/DELETE triggers that keep it in sync.
+    ///   External content means the index itself stores no text -- `search`
+    ///   reads the original column back out of `memories` for `snippet()`.
+    /// - Creates `seq_counter` (a single monotonic counter `snapshot()` reads
+    ///   from) and `memories_versions` (pre-images archived by
+    ///   `replace_with_summary` while a snapshot might still need them), plus
+    ///   `created_seq`/`updated_seq`/`tombstoned_seq`/`simhash` columns on
+    ///   `memories` -- added via `ALTER TABLE` for DBs that predate these
+    ///   columns, since `CREATE TABLE IF NOT EXISTS` alone won't add columns
+    ///   to an existing table. `simhash` is `remember`/`remember_with_summary`'s
+    ///   SimHash fingerprint of `content`, read by `find_near_duplicates_in_lobe`.
+    fn init_schema(db: &Connection) -> Result<()> {
         // WAL reduces writer/reader blocking; safe for our single-writer design.
         db.execute_batch(
             r#"
@@ -69,14 +284,118 @@ impl Memory {
               created_at    TEXT NOT NULL,     -- RFC3339 UTC
               updated_at    TEXT NOT NULL,     -- RFC3339 UTC
               archived_cid  TEXT,              -- content-addressed id from Archivist (blake3 hex)
-              archived_at   TEXT               -- when it was archived (RFC3339 UTC)
+              archived_at   TEXT,              -- when it was archived (RFC3339 UTC)
+              created_seq     INTEGER NOT NULL DEFAULT 0, -- seq_counter value this row was first written at
+              updated_seq     INTEGER NOT NULL DEFAULT 0, -- seq_counter value the current content was written at
+              tombstoned_seq  INTEGER,                    -- seq_counter value it was soft-deleted at; NULL while live
+              simhash         INTEGER NOT NULL DEFAULT 0  -- 64-bit SimHash of `content`, for near-duplicate detection
             );
 
             CREATE INDEX IF NOT EXISTS idx_mem_lobe_key ON memories(lobe, key);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts USING fts5(
+              content, summary, reflection,
+              content='memories',
+              content_rowid='rowid',
+              prefix='2 3'
+            );
+
+            CREATE TRIGGER IF NOT EXISTS memories_fts_ai AFTER INSERT ON memories BEGIN
+              INSERT INTO memories_fts(rowid, content, summary, reflection)
+              VALUES (new.rowid, CAST(new.content AS TEXT), new.summary, new.reflection);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS memories_fts_ad AFTER DELETE ON memories BEGIN
+              INSERT INTO memories_fts(memories_fts, rowid, content, summary, reflection)
+              VALUES ('delete', old.rowid, CAST(old.content AS TEXT), old.summary, old.reflection);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS memories_fts_au AFTER UPDATE ON memories BEGIN
+              INSERT INTO memories_fts(memories_fts, rowid, content, summary, reflection)
+              VALUES ('delete', old.rowid, CAST(old.content AS TEXT), old.summary, old.reflection);
+              INSERT INTO memories_fts(rowid, content, summary, reflection)
+              VALUES (new.rowid, CAST(new.content AS TEXT), new.summary, new.reflection);
+            END;
+
+            CREATE TABLE IF NOT EXISTS user_consent (
+              consenting_party TEXT NOT NULL,  -- who is granting the exception (e.g., the end user)
+              consented_party  TEXT NOT NULL,  -- who/what the grant applies to (e.g., an agent id)
+              violation_code   TEXT NOT NULL,  -- exact ContractRule.violation_code this grant covers
+              expires_at       TEXT NOT NULL,  -- RFC3339 UTC; grant is inert once now >= expires_at
+              details          TEXT NOT NULL DEFAULT '{}', -- caller-defined JSON (reason, approver, ...)
+              PRIMARY KEY (consenting_party, consented_party, violation_code)
+            );
+
+            CREATE TABLE IF NOT EXISTS contract_events (
+              event_id          INTEGER PRIMARY KEY AUTOINCREMENT,
+              timestamp         TEXT NOT NULL,     -- RFC3339 UTC
+              contract_name     TEXT NOT NULL,
+              contract_version  TEXT NOT NULL,
+              violation_code    TEXT,              -- NULL when the evaluation passed cleanly
+              severity          TEXT,              -- "none"|"low"|"medium"|"high"|"critical"
+              passed            INTEGER NOT NULL,  -- 0/1
+              constraints       TEXT NOT NULL DEFAULT '[]' -- merged constraints, JSON array
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_contract_events_ts ON contract_events(timestamp);
+            CREATE INDEX IF NOT EXISTS idx_contract_events_code ON contract_events(violation_code);
+
+            CREATE TABLE IF NOT EXISTS lobe_weights (
+              lobe          TEXT PRIMARY KEY,
+              total_weight  INTEGER NOT NULL DEFAULT 0, -- accumulated services::weight::op_weight cost
+              updated_at    TEXT NOT NULL               -- RFC3339 UTC of the last accrual
+            );
+
+            CREATE TABLE IF NOT EXISTS seq_counter (
+              id    INTEGER PRIMARY KEY CHECK (id = 0), -- single row, enforced by the CHECK
+              value INTEGER NOT NULL
+            );
+            INSERT OR IGNORE INTO seq_counter(id, value) VALUES (0, 0);
+
+            CREATE TABLE IF NOT EXISTS memories_versions (
+              version_id      INTEGER PRIMARY KEY AUTOINCREMENT,
+              memory_id       TEXT NOT NULL,
+              content         BLOB NOT NULL,
+              summary         TEXT,
+              valid_from_seq  INTEGER NOT NULL, -- seq this pre-image became current at
+              valid_to_seq    INTEGER NOT NULL  -- seq it was superseded/tombstoned at (exclusive)
+            );
+            CREATE INDEX IF NOT EXISTS idx_mem_versions_lookup ON memories_versions(memory_id, valid_to_seq);
             "#,
         )?;
 
-        Ok(Self { db })
+        // Pre-existing DBs were created before these columns existed; add
+        // them one at a time and ignore "duplicate column name" so this
+        // stays idempotent across repeated `init_schema` calls.
+        for stmt in [
+            "ALTER TABLE memories ADD COLUMN created_seq INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE memories ADD COLUMN updated_seq INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE memories ADD COLUMN tombstoned_seq INTEGER",
+            "ALTER TABLE memories ADD COLUMN simhash INTEGER NOT NULL DEFAULT 0",
+        ] {
+            let _ = db.execute(stmt, []);
+        }
+
+        Ok(())
+    }
+
+    /// Toggle automatic logbook emission from this connection's SQLite
+    /// `update_hook`/`commit_hook`/`rollback_hook`: when `enabled`, every
+    /// insert/update touching `memories` or `steps` is buffered (table,
+    /// rowid, operation) and flushed to the logbook only once its
+    /// transaction actually commits -- dropped untouched on rollback -- so
+    /// TD updates and promotions get an audit trail without every caller
+    /// having to call `audit::record_action` by hand. Disabling removes the
+    /// hooks and discards anything buffered but not yet committed.
+    pub fn with_db_event_log(self, enabled: bool) -> Self {
+        if enabled {
+            install_event_log_hooks(&self.db);
+        } else {
+            self.db.update_hook(None::<fn(Action, &str, &str, i64)>);
+            self.db.commit_hook(None::<fn() -> bool>);
+            self.db.rollback_hook(None::<fn()>);
+        }
+        self
     }
 
     // -------------------------------------------------------------------------
@@ -117,18 +436,23 @@ impl Memory {
     /// - On CONFLICT(memory_id): updates lobe/key/content and bumps `updated_at`.
     pub fn remember(&self, memory_id: &str, lobe: &str, key: &str, content: &[u8]) -> Result<()> {
         let now = Utc::now().to_rfc3339();
+        let seq = self.next_seq()?;
+        let simhash = simhash64(&String::from_utf8_lossy(content)) as i64;
         self.db.execute(
             r#"
-            INSERT INTO memories(memory_id, lobe, key, content, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+            INSERT INTO memories(memory_id, lobe, key, content, created_at, updated_at, created_seq, updated_seq, simhash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?5, ?6, ?6, ?7)
             ON CONFLICT(memory_id) DO UPDATE SET
-              lobe       = excluded.lobe,
-              key        = excluded.key,
-              content    = excluded.content,
-              updated_at = excluded.updated_at
+              lobe        = excluded.lobe,
+              key         = excluded.key,
+              content     = excluded.content,
+              updated_at  = excluded.updated_at,
+              updated_seq = excluded.updated_seq,
+              simhash     = excluded.simhash
             "#,
-            (memory_id, lobe, key, content, &now),
+            (memory_id, lobe, key, content, &now, seq, simhash),
         )?;
+        self.accrue_weight(lobe, crate::services::weight::op_weight(content.len()))?;
         Ok(())
     }
 
@@ -163,17 +487,21 @@ impl Memory {
         reflection: Option<&str>,
     ) -> Result<()> {
         let now = Utc::now().to_rfc3339();
+        let seq = self.next_seq()?;
+        let simhash = simhash64(&String::from_utf8_lossy(content)) as i64;
         self.db.execute(
             r#"
-            INSERT INTO memories(memory_id, lobe, key, content, summary, reflection, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+            INSERT INTO memories(memory_id, lobe, key, content, summary, reflection, created_at, updated_at, created_seq, updated_seq, simhash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8, ?8, ?9)
             ON CONFLICT(memory_id) DO UPDATE SET
-              lobe       = excluded.lobe,
-              key        = excluded.key,
-              content    = excluded.content,
-              summary    = excluded.summary,
-              reflection = COALESCE(NULLIF(excluded.reflection, ''), memories.reflection),
-              updated_at = excluded.updated_at
+              lobe        = excluded.lobe,
+              key         = excluded.key,
+              content     = excluded.content,
+              summary     = excluded.summary,
+              reflection  = COALESCE(NULLIF(excluded.reflection, ''), memories.reflection),
+              updated_at  = excluded.updated_at,
+              updated_seq = excluded.updated_seq,
+              simhash     = excluded.simhash
             "#,
             (
                 memory_id,
@@ -183,8 +511,14 @@ impl Memory {
                 summary,
                 reflection.unwrap_or(""), // empty string = "do not overwrite"
                 &now,
+                seq,
+                simhash,
             ),
         )?;
+        self.accrue_weight(
+            lobe,
+            crate::services::weight::op_weight(content.len() + summary.len()),
+        )?;
         Ok(())
     }
 
@@ -194,13 +528,16 @@ impl Memory {
 
     /// Fetch raw `content` bytes by `memory_id`.
     ///
+    /// Runs on its own reader connection (see `reader`) rather than the
+    /// writer connection `self.db`, so a burst of replay reads never queues
+    /// behind an in-flight commit.
+    ///
     /// Returns:
     /// - `Ok(Some(Vec<u8>))` if found,
     /// - `Ok(None)` if missing.
     pub fn recall(&self, memory_id: &str) -> Result<Option<Vec<u8>>> {
-        let mut stmt = self
-            .db
-            .prepare("SELECT content FROM memories WHERE memory_id=?1")?;
+        let reader = self.reader()?;
+        let mut stmt = reader.prepare("SELECT content FROM memories WHERE memory_id=?1")?;
         let mut rows = stmt.query([memory_id])?;
         if let Some(row) = rows.next()? {
             let bytes: Vec<u8> = row.get(0)?;
@@ -209,6 +546,107 @@ impl Memory {
         Ok(None)
     }
 
+    // -------------------------------------------------------------------------
+    // Snapshot isolation (MVCC-lite)
+    // -------------------------------------------------------------------------
+
+    /// Advance and return the global sequence counter. Called from every
+    /// content-mutating write (`remember`, `remember_with_summary`,
+    /// `replace_with_summary`, tombstoning in `prune_exact_duplicates_in_lobe`)
+    /// so `updated_seq`/`tombstoned_seq`/`memories_versions` ranges stay
+    /// ordered consistently with whatever a live `Snapshot` is pinned at.
+    fn next_seq(&self) -> Result<i64> {
+        self.db
+            .execute("UPDATE seq_counter SET value = value + 1 WHERE id = 0", [])?;
+        let seq = self
+            .db
+            .query_row("SELECT value FROM seq_counter WHERE id = 0", [], |r| r.get(0))?;
+        Ok(seq)
+    }
+
+    /// Current value of the sequence counter, without advancing it.
+    fn current_seq(&self) -> Result<i64> {
+        self.db
+            .query_row("SELECT value FROM seq_counter WHERE id = 0", [], |r| r.get(0))
+    }
+
+    /// Take a point-in-time read handle pinned at the current sequence
+    /// number. Hold it across a multi-key `recall_at` sequence to see a
+    /// consistent view even if `replace_with_summary`/
+    /// `prune_exact_duplicates_in_lobe` run concurrently; drop it once done
+    /// so pruning can reclaim tombstoned rows again.
+    pub fn snapshot(&self) -> Result<Snapshot> {
+        let seq = self.current_seq()?;
+        self.snapshots.lock().unwrap().acquire(seq);
+        Ok(Snapshot {
+            seq,
+            registry: self.snapshots.clone(),
+        })
+    }
+
+    /// Read `memory_id`'s content as it was visible at `snapshot`, instead
+    /// of the live row. Falls back to `memories_versions` when the live row
+    /// has since been overwritten (`replace_with_summary`) or tombstoned
+    /// (`prune_exact_duplicates_in_lobe`) more recently than `snapshot`.
+    pub fn recall_at(&self, snapshot: &Snapshot, memory_id: &str) -> Result<Option<Vec<u8>>> {
+        let reader = self.reader()?;
+        let live: Option<(Vec<u8>, i64, Option<i64>)> = reader
+            .query_row(
+                "SELECT content, updated_seq, tombstoned_seq FROM memories WHERE memory_id=?1",
+                [memory_id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .ok();
+
+        if let Some((content, updated_seq, tombstoned_seq)) = live {
+            let visible = snapshot.seq() >= updated_seq
+                && tombstoned_seq.map_or(true, |t| snapshot.seq() < t);
+            if visible {
+                return Ok(Some(content));
+            }
+        }
+
+        let mut stmt = reader.prepare(
+            "SELECT content FROM memories_versions
+             WHERE memory_id=?1 AND valid_from_seq <= ?2 AND ?2 < valid_to_seq
+             ORDER BY valid_to_seq DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query((memory_id, snapshot.seq()))?;
+        if let Some(row) = rows.next()? {
+            let content: Vec<u8> = row.get(0)?;
+            return Ok(Some(content));
+        }
+        Ok(None)
+    }
+
+    /// Physically delete rows tombstoned by `prune_exact_duplicates_in_lobe`
+    /// (and the `memories_versions` pre-images `replace_with_summary`
+    /// archived) that no outstanding snapshot can still read. Safe to call
+    /// opportunistically; a no-op while a live snapshot predates every
+    /// tombstone/version.
+    pub fn purge_tombstones(&self) -> Result<usize> {
+        let oldest_live = self.snapshots.lock().unwrap().oldest();
+        let tx = self.db.unchecked_transaction()?;
+        let deleted = match oldest_live {
+            Some(oldest) => {
+                tx.execute(
+                    "DELETE FROM memories_versions WHERE valid_to_seq <= ?1",
+                    [oldest],
+                )?;
+                tx.execute(
+                    "DELETE FROM memories WHERE tombstoned_seq IS NOT NULL AND tombstoned_seq <= ?1",
+                    [oldest],
+                )?
+            }
+            None => {
+                tx.execute("DELETE FROM memories_versions", [])?;
+                tx.execute("DELETE FROM memories WHERE tombstoned_seq IS NOT NULL", [])?
+            }
+        };
+        tx.commit()?;
+        Ok(deleted)
+    }
+
     /// Read the archived content id (CID) if this memory was promoted to cold storage.
     pub fn get_archived_cid(&self, memory_id: &str) -> Result<Option<String>> {
         let mut stmt = self
@@ -244,6 +682,74 @@ impl Memory {
         Ok(())
     }
 
+    /// Typo-tolerant full-text search over `content`/`summary`/`reflection`
+    /// via the `memories_fts` index (see `init_schema`), optionally scoped
+    /// to one `lobe`, ranked by BM25 (lower score = more relevant).
+    ///
+    /// Tries an exact match first, then falls back -- only if that came up
+    /// short of `limit` hits -- to a prefix match (`term*`) and finally to
+    /// naive edit-distance-1 variants (one dropped or adjacent-swapped
+    /// character per term) so a typo like "mroning" still surfaces
+    /// "morning" notes. Each fallback re-runs the whole query rather than
+    /// merging partial result sets, so BM25 ranking stays comparable within
+    /// one pass.
+    pub fn search(&self, lobe: Option<&str>, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let exact = self.search_fts(lobe, &fts_match_expr(query, false), limit)?;
+        if exact.len() >= limit || query.trim().is_empty() {
+            return Ok(exact);
+        }
+
+        let prefix = self.search_fts(lobe, &fts_match_expr(query, true), limit)?;
+        let best = if prefix.len() > exact.len() { prefix } else { exact };
+        if best.len() >= limit {
+            return Ok(best);
+        }
+
+        let fuzzy_expr = fts_fuzzy_expr(query);
+        if fuzzy_expr.is_empty() {
+            return Ok(best);
+        }
+        let fuzzy = self.search_fts(lobe, &fuzzy_expr, limit)?;
+        Ok(if fuzzy.len() > best.len() { fuzzy } else { best })
+    }
+
+    /// Run one already-built FTS5 `MATCH` expression and collect ranked hits.
+    fn search_fts(&self, lobe: Option<&str>, match_expr: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        if match_expr.is_empty() {
+            return Ok(vec![]);
+        }
+        let reader = self.reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT m.memory_id, m.lobe, bm25(memories_fts) AS score,
+                    snippet(memories_fts, -1, '[', ']', '…', 8) AS snip
+             FROM memories_fts
+             JOIN memories m ON m.rowid = memories_fts.rowid
+             WHERE memories_fts MATCH ?1
+               AND (?2 IS NULL OR m.lobe = ?2)
+             ORDER BY score
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map((match_expr, lobe, limit as i64), |row| {
+            Ok(SearchHit {
+                memory_id: row.get(0)?,
+                lobe: row.get(1)?,
+                score: row.get(2)?,
+                snippet: row.get(3)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Rebuild `memories_fts` from the current contents of `memories` --
+    /// needed once after the index is first created against an
+    /// already-populated database (new tables start empty) or if its
+    /// shadow tables are ever suspected out of sync.
+    pub fn rebuild_search_index(&self) -> Result<()> {
+        self.db
+            .execute("INSERT INTO memories_fts(memories_fts) VALUES ('rebuild')", [])?;
+        Ok(())
+    }
+
     /// Return all `memory_id`s that match an exact `(lobe, key)`.
     pub fn find_by_lobe_key(&self, lobe: &str, key: &str) -> Result<Vec<String>> {
         let mut stmt = self
@@ -265,6 +771,127 @@ impl Memory {
         Ok(iter.filter_map(|r| r.ok()).collect())
     }
 
+    // -------------------------------------------------------------------------
+    // Consent overrides (time-bounded exceptions to contract violations)
+    // -------------------------------------------------------------------------
+
+    /// Look up the grant (if any) that a `consenting_party` has given a
+    /// `consented_party` for one exact `violation_code`. Scoping is always by
+    /// `violation_code`, never by the rule's `action` — a grant is narrow by
+    /// design. Does not filter by expiry; callers checking whether a grant is
+    /// currently active should compare `expires_at` against the request time
+    /// (or build a [`contracts::ConsentContext`] via its `expires_at_unix`/
+    /// `now_unix` pair), since a row may still exist here if
+    /// [`Self::delete_expired_consent`] hasn't swept it yet.
+    pub fn find_consent_by_parties_and_code(
+        &self,
+        consenting_party: &str,
+        consented_party: &str,
+        violation_code: &str,
+    ) -> Result<Option<UserConsent>> {
+        let mut stmt = self.db.prepare(
+            "SELECT consenting_party, consented_party, violation_code, expires_at, details
+             FROM user_consent
+             WHERE consenting_party=?1 AND consented_party=?2 AND violation_code=?3",
+        )?;
+        let mut rows = stmt.query((consenting_party, consented_party, violation_code))?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(UserConsent::from_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Create or replace the grant for `(consenting_party, consented_party,
+    /// violation_code)`. A later call with the same triple overwrites the
+    /// prior `expires_at`/`details` rather than accumulating duplicate rows.
+    pub fn upsert_consent(
+        &self,
+        consenting_party: &str,
+        consented_party: &str,
+        violation_code: &str,
+        expires_at: &str,
+        details: &Value,
+    ) -> Result<()> {
+        let details_raw = serde_json::to_string(details).unwrap_or_else(|_| "{}".to_string());
+        self.db.execute(
+            r#"
+            INSERT INTO user_consent(consenting_party, consented_party, violation_code, expires_at, details)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(consenting_party, consented_party, violation_code) DO UPDATE SET
+              expires_at = excluded.expires_at,
+              details    = excluded.details
+            "#,
+            (consenting_party, consented_party, violation_code, expires_at, &details_raw),
+        )?;
+        Ok(())
+    }
+
+    /// Revoke a grant outright, regardless of whether it has expired yet.
+    pub fn delete_consent(
+        &self,
+        consenting_party: &str,
+        consented_party: &str,
+        violation_code: &str,
+    ) -> Result<()> {
+        self.db.execute(
+            "DELETE FROM user_consent WHERE consenting_party=?1 AND consented_party=?2 AND violation_code=?3",
+            (consenting_party, consented_party, violation_code),
+        )?;
+        Ok(())
+    }
+
+    /// Sweep every grant whose `expires_at` is already behind `now` (RFC3339
+    /// UTC). Returns the number of rows deleted. This is housekeeping, not a
+    /// correctness requirement: an expired-but-unswept row still behaves as
+    /// "no grant" at evaluation time, since evaluation re-checks expiry
+    /// against the request timestamp itself.
+    pub fn delete_expired_consent(&self, now: &str) -> Result<usize> {
+        let n = self
+            .db
+            .execute("DELETE FROM user_consent WHERE expires_at < ?1", [now])?;
+        Ok(n)
+    }
+
+    // -------------------------------------------------------------------------
+    // Contract violation audit ledger
+    // -------------------------------------------------------------------------
+
+    /// Append one row to `contract_events` for a single contract evaluation.
+    /// `violation_code`/`severity` are `None` for a clean pass. `timestamp` is
+    /// caller-supplied (RFC3339 UTC) rather than read from the system clock,
+    /// so recording stays a pure function of its inputs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_contract_event(
+        &self,
+        timestamp: &str,
+        contract_name: &str,
+        contract_version: &str,
+        violation_code: Option<&str>,
+        severity: Option<&str>,
+        passed: bool,
+        constraints: &[String],
+    ) -> Result<()> {
+        let constraints_raw = serde_json::to_string(constraints).unwrap_or_else(|_| "[]".to_string());
+        self.db.execute(
+            r#"
+            INSERT INTO contract_events(
+                timestamp, contract_name, contract_version, violation_code, severity, passed, constraints
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            (
+                timestamp,
+                contract_name,
+                contract_version,
+                violation_code,
+                severity,
+                passed as i64,
+                &constraints_raw,
+            ),
+        )?;
+        Ok(())
+    }
+
     // -------------------------------------------------------------------------
     // Promotion to DAG (linear per lobe) — MVP-safe, best-effort
     // -------------------------------------------------------------------------
@@ -326,11 +953,15 @@ impl Memory {
         let parent_cid = self.latest_archived_cid_in_lobe(&lobe)?;
         let parents = parent_cid.into_iter().collect::<Vec<_>>();
 
-        // Small, stable metadata for the DAG
+        // Small, stable metadata for the DAG. `level: 0` marks this as a
+        // freshly-promoted, single-record node -- see
+        // `Compactor::compact_dag_level`, which merges L0 nodes into
+        // higher levels once a lobe accumulates enough of them.
         let mut meta = json!({
             "cid": cid,
             "lobe": lobe,
             "key": key,
+            "level": 0,
             "summary_len": summary_opt.as_deref().map(str::len).unwrap_or(0),
             "created_at": created_at,
             "updated_at": updated_at,
@@ -356,6 +987,7 @@ impl Memory {
         // Mark row as archived
         let now = Utc::now().to_rfc3339();
         self.mark_archived(memory_id, &cid, &now)?;
+        self.accrue_weight(&lobe, crate::services::weight::op_weight(content.len()))?;
         Ok(())
     }
 
@@ -384,6 +1016,74 @@ impl Memory {
         Ok(out)
     }
 
+    /// Group `lobe`'s currently-archived rows by their DAG node's compaction
+    /// `"level"` tag (see `promote_to_dag`'s meta; missing/unparseable means
+    /// an un-migrated or freshly-promoted L0 node), one entry per distinct
+    /// `archived_cid`, oldest-first within a level by the earliest
+    /// `created_at` among rows pointing at it. This is the candidate set
+    /// `Compactor::compact_dag_level` merges from.
+    pub(crate) fn archived_cids_by_level(
+        &self,
+        lobe: &str,
+    ) -> Result<std::collections::BTreeMap<u32, Vec<String>>> {
+        let mut stmt = self.db.prepare(
+            "SELECT archived_cid, MIN(created_at) AS first_seen
+             FROM memories
+             WHERE lobe=?1 AND archived_cid IS NOT NULL
+             GROUP BY archived_cid
+             ORDER BY first_seen ASC",
+        )?;
+        let cids = stmt
+            .query_map([lobe], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        let mut by_level: std::collections::BTreeMap<u32, Vec<String>> =
+            std::collections::BTreeMap::new();
+        for cid in cids {
+            let level = dag::snapshot_meta(&cid)
+                .ok()
+                .and_then(|meta| meta.get("level").and_then(|v| v.as_u64()))
+                .unwrap_or(0) as u32;
+            by_level.entry(level).or_default().push(cid);
+        }
+        Ok(by_level)
+    }
+
+    /// `lobe`'s rows currently pointing at `cid` -- more than one once a
+    /// prior merge has repointed several original rows at the same merged
+    /// node.
+    pub(crate) fn memory_ids_for_archived_cid(&self, lobe: &str, cid: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT memory_id FROM memories WHERE lobe=?1 AND archived_cid=?2")?;
+        let ids = stmt
+            .query_map((lobe, cid), |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(ids)
+    }
+
+    /// Repoint every row in `ids` at `new_cid`, in one transaction -- used
+    /// after `Compactor::compact_dag_level` collapses several archived
+    /// nodes into one, so a crash mid-merge can't leave some rows pointing
+    /// at the old (now orphaned) CIDs and others at the new one. Only the
+    /// `archived_cid` pointer changes; `content`/`summary`/`archived_at`
+    /// are untouched.
+    pub(crate) fn repoint_archived_cid(&self, ids: &[String], new_cid: &str) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let tx = self.db.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare("UPDATE memories SET archived_cid=?1 WHERE memory_id=?2")?;
+            for id in ids {
+                stmt.execute((new_cid, id.as_str()))?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     /// Promote the most recent non-archived row in a lobe (if any).
     /// Returns Some((memory_id, cid)) if one was promoted.
     pub fn promote_latest_hot_in_lobe(&self, lobe: &str) -> Result<Option<(String, String)>> {
@@ -412,6 +1112,93 @@ impl Memory {
         Ok(None)
     }
 
+    /// The oldest non-archived row in a lobe, if any -- same ordering
+    /// `promote_all_hot_in_lobe` promotes in, but a peek rather than a write,
+    /// so quota eviction can promote one row at a time and re-check the budget.
+    pub fn oldest_hot_id_in_lobe(&self, lobe: &str) -> Result<Option<String>> {
+        let mut stmt = self.db.prepare(
+            "SELECT memory_id FROM memories
+             WHERE lobe=?1 AND archived_cid IS NULL
+             ORDER BY created_at ASC
+             LIMIT 1",
+        )?;
+        let mut rows = stmt.query([lobe])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row.get(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// `(hot_bytes, archived_bytes)`: total content size of non-archived vs.
+    /// archived rows, scoped to `lobe` if given. Backs `Commands::stats`'s
+    /// `bytes_hot`/`bytes_archived` and quota enforcement in `Commands::remember`.
+    pub fn byte_totals(&self, lobe: Option<&str>) -> Result<(u64, u64)> {
+        let (hot, archived): (i64, i64) = match lobe {
+            Some(l) => {
+                let hot = self.db.query_row(
+                    "SELECT COALESCE(SUM(LENGTH(content)),0) FROM memories WHERE lobe=?1 AND archived_cid IS NULL",
+                    [l],
+                    |r| r.get(0),
+                )?;
+                let archived = self.db.query_row(
+                    "SELECT COALESCE(SUM(LENGTH(content)),0) FROM memories WHERE lobe=?1 AND archived_cid IS NOT NULL",
+                    [l],
+                    |r| r.get(0),
+                )?;
+                (hot, archived)
+            }
+            None => {
+                let hot = self.db.query_row(
+                    "SELECT COALESCE(SUM(LENGTH(content)),0) FROM memories WHERE archived_cid IS NULL",
+                    [],
+                    |r| r.get(0),
+                )?;
+                let archived = self.db.query_row(
+                    "SELECT COALESCE(SUM(LENGTH(content)),0) FROM memories WHERE archived_cid IS NOT NULL",
+                    [],
+                    |r| r.get(0),
+                )?;
+                (hot, archived)
+            }
+        };
+        Ok((hot.max(0) as u64, archived.max(0) as u64))
+    }
+
+    /// Fold `weight` (see [`crate::services::weight::op_weight`]) into `lobe`'s
+    /// running `total_weight`, creating the row on first use.
+    pub fn accrue_weight(&self, lobe: &str, weight: u64) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        self.db.execute(
+            r#"
+            INSERT INTO lobe_weights(lobe, total_weight, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(lobe) DO UPDATE SET
+              total_weight = lobe_weights.total_weight + excluded.total_weight,
+              updated_at   = excluded.updated_at
+            "#,
+            (lobe, weight as i64, &now),
+        )?;
+        Ok(())
+    }
+
+    /// Sum of accrued `total_weight`, scoped to `lobe` if given.
+    pub fn lobe_weight_total(&self, lobe: Option<&str>) -> Result<u64> {
+        let total: i64 = match lobe {
+            Some(l) => self.db.query_row(
+                "SELECT COALESCE(SUM(total_weight),0) FROM lobe_weights WHERE lobe=?1",
+                [l],
+                |r| r.get(0),
+            )?,
+            None => self.db.query_row(
+                "SELECT COALESCE(SUM(total_weight),0) FROM lobe_weights",
+                [],
+                |r| r.get(0),
+            )?,
+        };
+        Ok(total.max(0) as u64)
+    }
+
     /// Remove exact-duplicate rows within a lobe, keeping the most recently updated copy of each unique content.
     /// Returns the number of rows deleted.
     pub fn prune_exact_duplicates_in_lobe(&self, lobe: &str) -> Result<usize> {
@@ -437,22 +1224,162 @@ impl Memory {
         }
         drop(stmt);
 
-        if to_delete.is_empty() {
+        self.delete_or_tombstone(&to_delete)
+    }
+
+    /// Shared by `prune_exact_duplicates_in_lobe`/`prune_near_duplicates_in_lobe`:
+    /// hard-delete `ids` when no snapshot is outstanding, otherwise
+    /// tombstone them so `recall_at` can still serve a snapshot that
+    /// predates this prune.
+    fn delete_or_tombstone(&self, ids: &[String]) -> Result<usize> {
+        if ids.is_empty() {
             return Ok(0);
         }
 
-        let tx = self.db.unchecked_transaction()?;
-        let mut del = tx.prepare("DELETE FROM memories WHERE memory_id=?1")?;
+        let oldest_live = self.snapshots.lock().unwrap().oldest();
         let mut cnt = 0usize;
-        for id in &to_delete {
-            del.execute([id])?;
-            cnt += 1;
+        if oldest_live.is_some() {
+            let seq = self.next_seq()?;
+            let tx = self.db.unchecked_transaction()?;
+            let mut tomb = tx.prepare(
+                "UPDATE memories SET tombstoned_seq=?1 WHERE memory_id=?2 AND tombstoned_seq IS NULL",
+            )?;
+            for id in ids {
+                cnt += tomb.execute((seq, id))?;
+            }
+            tomb.finalize()?;
+            tx.commit()?;
+        } else {
+            let tx = self.db.unchecked_transaction()?;
+            let mut del = tx.prepare("DELETE FROM memories WHERE memory_id=?1")?;
+            for id in ids {
+                del.execute([id])?;
+                cnt += 1;
+            }
+            del.finalize()?;
+            tx.commit()?;
         }
-        del.finalize()?;
-        tx.commit()?;
         Ok(cnt)
     }
 
+    /// Find near-duplicate pairs within `lobe` via SimHash banding:
+    /// fingerprints (`simhash`, set on every write -- see `simhash64`) are
+    /// split into 4 x 16-bit bands, and only rows sharing at least one band
+    /// value are compared, avoiding the full O(n^2) pairwise scan. Returns
+    /// `(memory_id_a, memory_id_b, hamming_distance)` triples -- each
+    /// unordered pair reported once -- for every pair within `threshold`
+    /// bits of each other.
+    pub fn find_near_duplicates_in_lobe(
+        &self,
+        lobe: &str,
+        threshold: u32,
+    ) -> Result<Vec<(String, String, u32)>> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT memory_id, simhash FROM memories WHERE lobe=?1")?;
+        let rows: Vec<(String, i64)> = stmt
+            .query_map([lobe], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        drop(stmt);
+
+        let mut buckets: std::collections::HashMap<(u8, u16), Vec<usize>> =
+            std::collections::HashMap::new();
+        for (i, (_, fp)) in rows.iter().enumerate() {
+            let fp = *fp as u64;
+            for band in 0..4u8 {
+                let value = ((fp >> (band as u32 * 16)) & 0xFFFF) as u16;
+                buckets.entry((band, value)).or_default().push(i);
+            }
+        }
+
+        let mut seen_pairs: std::collections::HashSet<(usize, usize)> =
+            std::collections::HashSet::new();
+        let mut out = Vec::new();
+        for members in buckets.values() {
+            for a in 0..members.len() {
+                for b in (a + 1)..members.len() {
+                    let (i, j) = (members[a].min(members[b]), members[a].max(members[b]));
+                    if i == j || !seen_pairs.insert((i, j)) {
+                        continue;
+                    }
+                    let dist = hamming_distance(rows[i].1 as u64, rows[j].1 as u64);
+                    if dist <= threshold {
+                        out.push((rows[i].0.clone(), rows[j].0.clone(), dist));
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Collapse near-duplicate clusters within `lobe` (see
+    /// `find_near_duplicates_in_lobe`), keeping the most-recently-updated
+    /// member of each cluster. Clusters are formed by union-find over the
+    /// reported pairs, so a chain of near-dupes (A~B, B~C) collapses to one
+    /// survivor even when A and C aren't within `threshold` of each other
+    /// directly. Deletion goes through `delete_or_tombstone`, same as
+    /// `prune_exact_duplicates_in_lobe`.
+    pub fn prune_near_duplicates_in_lobe(&self, lobe: &str, threshold: u32) -> Result<usize> {
+        let pairs = self.find_near_duplicates_in_lobe(lobe, threshold)?;
+        if pairs.is_empty() {
+            return Ok(0);
+        }
+
+        fn find(parent: &mut std::collections::HashMap<String, String>, x: &str) -> String {
+            let p = parent.get(x).cloned().unwrap_or_else(|| x.to_string());
+            if p == x {
+                x.to_string()
+            } else {
+                let root = find(parent, &p);
+                parent.insert(x.to_string(), root.clone());
+                root
+            }
+        }
+
+        let mut parent: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        for (a, b, _) in &pairs {
+            parent.entry(a.clone()).or_insert_with(|| a.clone());
+            parent.entry(b.clone()).or_insert_with(|| b.clone());
+            let ra = find(&mut parent, a);
+            let rb = find(&mut parent, b);
+            if ra != rb {
+                parent.insert(ra, rb);
+            }
+        }
+
+        let mut clusters: std::collections::HashMap<String, Vec<String>> =
+            std::collections::HashMap::new();
+        for id in parent.keys().cloned().collect::<Vec<_>>() {
+            let root = find(&mut parent, &id);
+            clusters.entry(root).or_default().push(id);
+        }
+
+        let mut to_delete: Vec<String> = Vec::new();
+        for members in clusters.values() {
+            let mut survivor: Option<(String, String)> = None; // (updated_at, memory_id)
+            for id in members {
+                let updated_at: Option<String> = self
+                    .db
+                    .query_row(
+                        "SELECT updated_at FROM memories WHERE memory_id=?1",
+                        [id],
+                        |r| r.get(0),
+                    )
+                    .ok();
+                let Some(updated_at) = updated_at else { continue };
+                if survivor.as_ref().map_or(true, |(best, _)| updated_at > *best) {
+                    survivor = Some((updated_at, id.clone()));
+                }
+            }
+            if let Some((_, survivor_id)) = survivor {
+                to_delete.extend(members.iter().filter(|id| **id != survivor_id).cloned());
+            }
+        }
+
+        self.delete_or_tombstone(&to_delete)
+    }
+
     // -------------------------------------------------------------------------
     // Compaction helpers (minimal API for Compactor)
     // -------------------------------------------------------------------------
@@ -525,13 +1452,46 @@ impl Memory {
         self.get_content(memory_id)
     }
 
-    /// Replace the `content` with a compacted summary, also store it in `summary` and bump timestamp.
+    /// Unconditionally delete a row by `memory_id`. A no-op, not an error,
+    /// if it's already gone. Unlike `prune_exact_duplicates_in_lobe`, this
+    /// always hard-deletes -- callers wanting snapshot-safe removal should
+    /// go through that path instead.
+    pub fn delete(&self, memory_id: &str) -> Result<()> {
+        self.db
+            .execute("DELETE FROM memories WHERE memory_id=?1", [memory_id])?;
+        Ok(())
+    }
+
+    /// Replace the `content` with a compacted summary, also store it in
+    /// `summary` and bump timestamp/seq. If a snapshot is outstanding, the
+    /// pre-image is archived into `memories_versions` first so `recall_at`
+    /// can still serve it to a snapshot taken before this call.
     pub fn replace_with_summary(&self, memory_id: &str, summary: &str) -> Result<()> {
         let now = Utc::now().to_rfc3339();
-        self.db.execute(
-            "UPDATE memories SET content=?1, summary=?2, updated_at=?3 WHERE memory_id=?4",
-            (summary.as_bytes(), summary, &now, memory_id),
+        let seq = self.next_seq()?;
+        let oldest_live = self.snapshots.lock().unwrap().oldest();
+        let tx = self.db.unchecked_transaction()?;
+        if oldest_live.is_some() {
+            let old: Option<(Vec<u8>, Option<String>, i64)> = tx
+                .query_row(
+                    "SELECT content, summary, updated_seq FROM memories WHERE memory_id=?1",
+                    [memory_id],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+                )
+                .ok();
+            if let Some((old_content, old_summary, old_updated_seq)) = old {
+                tx.execute(
+                    "INSERT INTO memories_versions(memory_id, content, summary, valid_from_seq, valid_to_seq)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    (memory_id, &old_content, &old_summary, old_updated_seq, seq),
+                )?;
+            }
+        }
+        tx.execute(
+            "UPDATE memories SET content=?1, summary=?2, updated_at=?3, updated_seq=?4 WHERE memory_id=?5",
+            (summary.as_bytes(), summary, &now, seq, memory_id),
         )?;
+        tx.commit()?;
         Ok(())
     }
 
@@ -604,6 +1564,283 @@ impl Memory {
         };
         Ok(out)
     }
+
+    // -------------------------------------------------------------------------
+    // Online backup / restore (live point-in-time snapshots)
+    // -------------------------------------------------------------------------
+
+    /// Copy this store to `dest_path` via SQLite's online backup API, so a
+    /// consistent `.cogniv` snapshot can be taken without stopping the
+    /// single-writer thread `Memory` is designed to run behind (unlike the
+    /// `run_bench` harness's file-size sampling, which can't guarantee the
+    /// file it measures isn't mid-write). Steps `pages_per_batch` pages at a
+    /// time, sleeping `pause` between batches so the backup doesn't starve
+    /// concurrent writers, and calls `on_progress` after every batch.
+    pub fn backup_to(
+        &self,
+        dest_path: &str,
+        pages_per_batch: i32,
+        pause: Duration,
+        on_progress: impl FnMut(BackupProgress),
+    ) -> Result<()> {
+        if let Some(parent) = Path::new(dest_path).parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating parent dir for {}", dest_path))?;
+        }
+        let mut dest = Connection::open(dest_path)
+            .with_context(|| format!("opening backup destination {}", dest_path))?;
+        run_backup(&self.db, &mut dest, pages_per_batch, pause, on_progress)
+            .with_context(|| format!("backing up to {}", dest_path))
+    }
+
+    /// Restore this store in place from a snapshot previously written by
+    /// [`Memory::backup_to`], via the same online backup API (source ->
+    /// `self.db`) so callers holding this `Memory` never observe a
+    /// half-copied file. Same batching/progress contract as `backup_to`.
+    pub fn restore_from(
+        &mut self,
+        src_path: &str,
+        pages_per_batch: i32,
+        pause: Duration,
+        on_progress: impl FnMut(BackupProgress),
+    ) -> Result<()> {
+        let src = Connection::open(src_path)
+            .with_context(|| format!("opening restore source {}", src_path))?;
+        run_backup(&src, &mut self.db, pages_per_batch, pause, on_progress)
+            .with_context(|| format!("restoring {} into live store", src_path))
+    }
+}
+
+/// Lowercase, alphanumeric-delimited tokens of a search query -- also the
+/// boundary where a caller's free text stops and FTS5 syntax starts, so
+/// every token below is quoted before it's ever joined back into a MATCH
+/// expression.
+fn fts_tokens(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_ascii_lowercase())
+        .collect()
+}
+
+/// Build a `memories_fts` `MATCH` expression requiring every token
+/// (quoted, so punctuation/FTS5 operators in `query` can't leak through),
+/// ANDed together. `prefix` appends `*` to each quoted token for a
+/// `term*` prefix query instead of an exact one.
+fn fts_match_expr(query: &str, prefix: bool) -> String {
+    fts_tokens(query)
+        .into_iter()
+        .map(|t| if prefix { format!("\"{t}\"*") } else { format!("\"{t}\"") })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Build a fallback `MATCH` expression of edit-distance-1 variants per
+/// token -- one dropped character (catches an extra typed letter) and one
+/// adjacent transposition (catches swapped letters) -- each used as a
+/// prefix query, ORed within a token and ANDed across tokens. Tokens under
+/// 3 characters are skipped (too short for a meaningful fallback, and
+/// their variants would over-match). Returns `""` if every token was
+/// skipped, so callers can tell "nothing left to try" from "no results".
+fn fts_fuzzy_expr(query: &str) -> String {
+    let mut per_token = Vec::new();
+    for token in fts_tokens(query) {
+        let chars: Vec<char> = token.chars().collect();
+        if chars.len() < 3 {
+            continue;
+        }
+
+        let mut variants: Vec<String> = vec![format!("\"{token}\"*")];
+        for i in 0..chars.len() {
+            let mut dropped = chars.clone();
+            dropped.remove(i);
+            variants.push(format!("\"{}\"*", dropped.into_iter().collect::<String>()));
+
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                variants.push(format!("\"{}\"*", swapped.into_iter().collect::<String>()));
+            }
+        }
+        variants.sort();
+        variants.dedup();
+        per_token.push(format!("({})", variants.join(" OR ")));
+    }
+    per_token.join(" AND ")
+}
+
+/// 64-bit SimHash fingerprint of `text`, for `find_near_duplicates_in_lobe`.
+/// Shingles `fts_tokens(text)` into overlapping 3-grams (the whole token
+/// list if there are fewer than 3), hashes each shingle with blake3 and
+/// takes its first 8 bytes as a 64-bit word, then lets each hashed bit vote
+/// +1/-1 into a per-bit accumulator so the final fingerprint bit is
+/// majority-set across all shingles. Paraphrases that share most of their
+/// wording land close in Hamming distance even though their raw bytes (and
+/// blake3 content hash) differ completely.
+fn simhash64(text: &str) -> u64 {
+    let tokens = fts_tokens(text);
+    let shingles: Vec<String> = if tokens.is_empty() {
+        Vec::new()
+    } else if tokens.len() < 3 {
+        vec![tokens.join(" ")]
+    } else {
+        tokens.windows(3).map(|w| w.join(" ")).collect()
+    };
+
+    let mut acc = [0i64; 64];
+    for shingle in &shingles {
+        let hash = blake3::hash(shingle.as_bytes());
+        let mut word_bytes = [0u8; 8];
+        word_bytes.copy_from_slice(&hash.as_bytes()[..8]);
+        let word = u64::from_le_bytes(word_bytes);
+        for (bit, slot) in acc.iter_mut().enumerate() {
+            if (word >> bit) & 1 == 1 {
+                *slot += 1;
+            } else {
+                *slot -= 1;
+            }
+        }
+    }
+
+    let mut fingerprint = 0u64;
+    for (bit, slot) in acc.iter().enumerate() {
+        if *slot > 0 {
+            fingerprint |= 1 << bit;
+        }
+    }
+    fingerprint
+}
+
+/// Count of differing bits between two 64-bit SimHash fingerprints.
+fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Issue the `PRAGMA key`/`PRAGMA cipher_*` sequence SQLCipher expects
+/// immediately after opening a connection and before any other statement
+/// touches the database, so the file is encrypted (or, for an existing
+/// file, correctly decrypted) from the very first read/write.
+#[cfg(feature = "sqlcipher")]
+fn apply_sqlcipher_key(db: &Connection, key: &str) -> Result<()> {
+    db.pragma_update(None, "key", key)
+        .context("setting SQLCipher key")?;
+    db.pragma_update(None, "cipher_page_size", 4096)
+        .context("setting cipher_page_size")?;
+    db.pragma_update(None, "kdf_iter", 256_000)
+        .context("setting kdf_iter")?;
+    Ok(())
+}
+
+/// Install `update_hook`/`commit_hook`/`rollback_hook` on `db` so writes to
+/// `memories` or `steps` are buffered per-transaction and flushed to the
+/// logbook (via `audit::record_db_event`) only once that transaction
+/// commits; shared by [`Memory::with_db_event_log`] and `TDLearner`, whose
+/// connections write into the same SQLite file.
+pub(crate) fn install_event_log_hooks(db: &Connection) {
+    let buf: Arc<Mutex<Vec<(String, i64, &'static str)>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let update_buf = Arc::clone(&buf);
+    db.update_hook(Some(
+        move |action: Action, _db_name: &str, table: &str, rowid: i64| {
+            if table != "memories" && table != "steps" {
+                return;
+            }
+            let op = match action {
+                Action::SQLITE_INSERT => "insert",
+                Action::SQLITE_UPDATE => "update",
+                Action::SQLITE_DELETE => "delete",
+                _ => "unknown",
+            };
+            update_buf.lock().unwrap().push((table.to_string(), rowid, op));
+        },
+    ));
+
+    let commit_buf = Arc::clone(&buf);
+    db.commit_hook(Some(move || {
+        let events = std::mem::take(&mut *commit_buf.lock().unwrap());
+        for (table, rowid, op) in events {
+            audit::record_db_event(&table, rowid, op);
+        }
+        false // never veto the commit; this hook only observes.
+    }));
+
+    let rollback_buf = Arc::clone(&buf);
+    db.rollback_hook(Some(move || {
+        rollback_buf.lock().unwrap().clear();
+    }));
+}
+
+/// Drive a `rusqlite` online backup to completion, pausing `pause` between
+/// `pages_per_batch`-page steps (and backing off the same amount on
+/// `Busy`/`Locked`, since both mean a concurrent writer currently holds the
+/// lock this step wanted) so it never holds the source's write lock for long
+/// enough to starve the single writer using it.
+fn run_backup(
+    src: &Connection,
+    dest: &mut Connection,
+    pages_per_batch: i32,
+    pause: Duration,
+    mut on_progress: impl FnMut(BackupProgress),
+) -> Result<()> {
+    let backup = Backup::new(src, dest).context("starting online backup")?;
+    loop {
+        let step = backup
+            .step(pages_per_batch)
+            .context("stepping online backup")?;
+        let progress = backup.progress();
+        on_progress(BackupProgress {
+            remaining: progress.remaining,
+            total: progress.pagecount,
+        });
+        match step {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {
+                if !pause.is_zero() {
+                    std::thread::sleep(pause);
+                }
+            }
+            StepResult::Busy | StepResult::Locked => {
+                std::thread::sleep(pause.max(Duration::from_millis(10)));
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------
+// contracts::ViolationRecorder bridge
+// -------------------------------------------------------------------------
+
+/// Persists every evaluation passed to it as one `contract_events` row.
+/// Borrow this over a `Memory` handle and pass it to
+/// `contracts::evaluate_and_record` to opt a call site into the audit
+/// ledger; plain `evaluate_input_against_rules` never records.
+pub struct MemoryViolationRecorder<'a> {
+    pub memory: &'a Memory,
+}
+
+impl<'a> contracts::ViolationRecorder for MemoryViolationRecorder<'a> {
+    fn record(&self, contract: &contracts::MoralContract, result: &contracts::EvaluationResult) {
+        let severity = result
+            .primary_violation_code
+            .as_deref()
+            .and_then(|code| {
+                result
+                    .violated_rules
+                    .iter()
+                    .find(|r| r.violation_code.as_deref() == Some(code))
+            })
+            .and_then(|r| r.severity.clone());
+
+        let _ = self.memory.record_contract_event(
+            &Utc::now().to_rfc3339(),
+            &contract.name,
+            &contract.version,
+            result.primary_violation_code.as_deref(),
+            severity.as_deref(),
+            result.passed,
+            &result.constraints,
+        );
+    }
 }
 
 // -------------------- Contracts Store helper --------------------
@@ -656,25 +1893,120 @@ impl Memory {
     pub fn latest_archived_cid_in_lobe_public(&self, lobe: &str) -> Result<Option<String>> {
         self.latest_archived_cid_in_lobe(lobe)
     }
+
+    /// Export `path_name`'s reachable ancestor chain to `dest` as a
+    /// portable archive (see `dag::ArchiveWriterKind` for the loose/packed
+    /// layout choice) -- a real backup/transfer path for a replay path
+    /// instead of relying on the live SQLite store.
+    pub fn export_path_archive(
+        &self,
+        path_name: &str,
+        dest: &std::path::Path,
+        writer_kind: crate::memory::dag::ArchiveWriterKind,
+    ) -> Result<crate::memory::dag::ArchiveManifest> {
+        crate::memory::dag::export_path_archive(path_name, dest, writer_kind)
+    }
+
+    /// Import an archive written by [`Memory::export_path_archive`] from
+    /// `src`, verifying each snapshot's bytes against its declared blake3 id
+    /// and skipping any already present locally, then re-pointing every
+    /// exported path's head.
+    pub fn import_archive(
+        &self,
+        src: &std::path::Path,
+        writer_kind: crate::memory::dag::ArchiveWriterKind,
+    ) -> Result<crate::memory::dag::ArchiveImportReport> {
+        crate::memory::dag::import_archive(src, writer_kind)
+    }
+
+    /// Abortable, resumable counterpart to [`Memory::import_archive`]:
+    /// `abort` is checked between snapshots (bailing with
+    /// `dag::RestorationAborted` promptly once set) and `on_progress(done,
+    /// total)` is called after each one. Safe to call again after an abort
+    /// -- already-inserted snapshots are skipped, so only the remainder is
+    /// restored.
+    pub fn import_archive_with_progress(
+        &self,
+        src: &std::path::Path,
+        writer_kind: crate::memory::dag::ArchiveWriterKind,
+        abort: &std::sync::atomic::AtomicBool,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<crate::memory::dag::ArchiveImportReport> {
+        crate::memory::dag::import_archive_with_progress(src, writer_kind, abort, on_progress)
+    }
+
+    /// Ingest a newline-delimited stream of externally-produced snapshots
+    /// (see `dag::StreamedSnapshot`) in bounded batches of `batch_size`
+    /// rather than buffering the whole input -- so a long history can be
+    /// piped in over stdin or a socket without holding it all in memory.
+    /// Each batch's lines are content-addressed and deduplicated, and every
+    /// touched path's head only advances once its whole batch has written
+    /// cleanly, so a stream truncated mid-batch leaves the DAG at a clean
+    /// snapshot boundary.
+    pub fn restore_snapshot_stream<R: std::io::BufRead>(
+        &self,
+        reader: R,
+        batch_size: usize,
+    ) -> Result<crate::memory::dag::SnapshotStreamReport> {
+        crate::memory::dag::import_snapshot_stream(reader, batch_size)
+    }
 }
 
 // -------------------------------------------------------------------------
 // Stream runtime write barrier (MVP: audit-only commit)
 // -------------------------------------------------------------------------
 
-/// Best-effort snapshot commit for stream runtime. For the MVP we only audit the commit
-/// attempt rather than persisting the streamed output in SQLite.
+/// Snapshot commit for stream runtime: persists `finalized`'s text into the
+/// content-addressed DAG (not just the audit trail) so it's replayable via
+/// `recall_snapshot`, alongside the existing audit record.
+///
+/// Only `FinalizedStatus::Ok` output is actually appended to a path --
+/// named after `proposal.intent`, created on the first commit for that
+/// intent and extended via `Memory::extend_path` on every commit after.
+/// `Violated`/`Stopped`/`Escalated` stay audit-only: no path is written to,
+/// but the snapshot id that commit *would* have produced is still computed
+/// (its content is content-addressed the same way either way) and returned
+/// alongside the audit record, so a replay can show the rejected branch
+/// without it ever becoming part of the path's real history.
+///
+/// Returns the snapshot id -- the new path head on `Ok`, the would-be id
+/// otherwise.
 pub fn commit_snapshot(
     proposal: &Proposal,
     decision: &RuntimeDecision,
     finalized: &Finalized,
-) -> Result<()> {
+) -> Result<String> {
     let status = match finalized.status {
         crate::services::streamgate::FinalizedStatus::Ok => "ok",
         crate::services::streamgate::FinalizedStatus::Violated => "violated",
         crate::services::streamgate::FinalizedStatus::Stopped => "stopped",
         crate::services::streamgate::FinalizedStatus::Escalated => "escalated",
     };
+
+    let path_name = format!("stream:{}", proposal.intent);
+    let would_be_id = blake3::hash(finalized.text.as_bytes()).to_hex().to_string();
+
+    let snapshot_id = if finalized.is_ok() {
+        if dag::path_exists(&path_name)? {
+            dag::extend_path(
+                &path_name,
+                dag::MemoryState {
+                    content: finalized.text.clone(),
+                    meta: serde_json::json!({ "lobe": "stream", "key": path_name }),
+                },
+            )?
+        } else {
+            // No path yet: write the first node directly (it has no
+            // predecessor to diff against), then seed the path ref off its
+            // own snapshot id so later commits extend it normally.
+            let seed_meta = serde_json::json!({ "lobe": "stream", "key": path_name });
+            let _ = dag::save_node(&path_name, &finalized.text, &seed_meta, &[]);
+            dag::diverge_from(&would_be_id, &path_name)?
+        }
+    } else {
+        would_be_id.clone()
+    };
+
     audit::record_action(
         "streamruntime",
         "commit_snapshot",
@@ -683,8 +2015,32 @@ pub fn commit_snapshot(
             "decision": decision,
             "status": status,
             "preview": String::from(finalized.text.chars().take(160).collect::<String>()),
+            "snapshot_id": snapshot_id,
         }),
         "low",
     );
-    Ok(())
+    Ok(snapshot_id)
+}
+
+/// `commit_snapshot`, fronted by the write-ahead [`crate::services::commit_log`]:
+/// the triple is durably appended *before* it's applied, and marked applied
+/// only after `commit_snapshot` returns successfully, so a crash in between
+/// leaves a pending entry that the log's next `commit_log()` init will
+/// replay, rather than losing or double-applying the commit.
+///
+/// Falls back to a bare `commit_snapshot` if the log store can't be opened
+/// (e.g. `.cogniv` isn't initialized yet) -- same best-effort-optional
+/// convention as `contracts_store()`.
+pub fn commit_snapshot_logged(
+    proposal: &Proposal,
+    decision: &RuntimeDecision,
+    finalized: &Finalized,
+) -> Result<String> {
+    let Some(log) = crate::services::commit_log::commit_log() else {
+        return commit_snapshot(proposal, decision, finalized);
+    };
+    let seq = log.append(proposal, decision, finalized)?;
+    let snapshot_id = commit_snapshot(proposal, decision, finalized)?;
+    log.mark_applied(seq)?;
+    Ok(snapshot_id)
 }
@@ -0,0 +1,123 @@
+// src/services/memory_backend.rs
+use anyhow::Result;
+
+/// Storage-agnostic surface for the row/snapshot bookkeeping `Commands`
+/// needs from the hot-memory store: counts, lobe grouping, recency
+/// ordering, hot -> DAG promotion, and the archived-snapshot pointer kept
+/// per row. [`Memory`](crate::services::memory::Memory) (SQLite, via
+/// `commands::helpers`) is the only implementation wired up by default;
+/// [`InMemoryBackend`] exists so tests can exercise `Commands` without a
+/// database file on disk. A caller wires an alternate store via
+/// `CommandsBuilder::with_backend`.
+///
+/// Path-head and snapshot-node storage (`crate::memory::dag`) are already
+/// their own flat-file store, independent of SQLite, so they aren't part
+/// of this trait.
+pub trait MemoryBackend {
+    fn count_rows(&self, lobe: Option<&str>) -> Result<u64>;
+    fn count_archived(&self, lobe: Option<&str>) -> Result<u64>;
+    fn group_by_lobe(&self, limit: usize) -> Result<Vec<(String, u64)>>;
+    fn recent_ids_in_lobe(&self, lobe: &str, limit: usize) -> Result<Vec<String>>;
+    fn promote_all_hot_in_lobe(&self, lobe: &str) -> Result<Vec<(String, String)>>;
+    fn get_archived_cid(&self, memory_id: &str) -> Result<Option<String>>;
+    fn mark_archived(&self, memory_id: &str, cid: &str, archived_at: &str) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Default)]
+struct InMemoryRow {
+    lobe: String,
+    archived_cid: Option<String>,
+}
+
+/// In-process [`MemoryBackend`] for unit tests: rows live only as long as
+/// the value does, ordered by insertion (most-recently-inserted first, the
+/// same "recent" ordering `Memory::recent_ids_in_lobe` gives from
+/// `updated_at DESC`). Not meant for production use -- there's no
+/// durability and `promote_all_hot_in_lobe` is a no-op, since DAG promotion
+/// needs a real content-addressed store behind it.
+#[derive(Default)]
+pub struct InMemoryBackend {
+    rows: std::sync::Mutex<Vec<(String, InMemoryRow)>>,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed a row the way a real write would have left one behind.
+    pub fn insert_row(&self, memory_id: &str, lobe: &str) {
+        let mut rows = self.rows.lock().unwrap();
+        rows.retain(|(id, _)| id != memory_id);
+        rows.push((
+            memory_id.to_string(),
+            InMemoryRow {
+                lobe: lobe.to_string(),
+                archived_cid: None,
+            },
+        ));
+    }
+}
+
+impl MemoryBackend for InMemoryBackend {
+    fn count_rows(&self, lobe: Option<&str>) -> Result<u64> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows
+            .iter()
+            .filter(|(_, r)| lobe.map(|l| r.lobe == l).unwrap_or(true))
+            .count() as u64)
+    }
+
+    fn count_archived(&self, lobe: Option<&str>) -> Result<u64> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows
+            .iter()
+            .filter(|(_, r)| {
+                lobe.map(|l| r.lobe == l).unwrap_or(true) && r.archived_cid.is_some()
+            })
+            .count() as u64)
+    }
+
+    fn group_by_lobe(&self, limit: usize) -> Result<Vec<(String, u64)>> {
+        let rows = self.rows.lock().unwrap();
+        let mut counts: std::collections::BTreeMap<String, u64> = std::collections::BTreeMap::new();
+        for (_, r) in rows.iter() {
+            *counts.entry(r.lobe.clone()).or_insert(0) += 1;
+        }
+        let mut out: Vec<(String, u64)> = counts.into_iter().collect();
+        out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        out.truncate(limit);
+        Ok(out)
+    }
+
+    fn recent_ids_in_lobe(&self, lobe: &str, limit: usize) -> Result<Vec<String>> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows
+            .iter()
+            .rev()
+            .filter(|(_, r)| r.lobe == lobe)
+            .take(limit)
+            .map(|(id, _)| id.clone())
+            .collect())
+    }
+
+    fn promote_all_hot_in_lobe(&self, _lobe: &str) -> Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+
+    fn get_archived_cid(&self, memory_id: &str) -> Result<Option<String>> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows
+            .iter()
+            .find(|(id, _)| id == memory_id)
+            .and_then(|(_, r)| r.archived_cid.clone()))
+    }
+
+    fn mark_archived(&self, memory_id: &str, cid: &str, _archived_at: &str) -> Result<()> {
+        let mut rows = self.rows.lock().unwrap();
+        if let Some((_, r)) = rows.iter_mut().find(|(id, _)| id == memory_id) {
+            r.archived_cid = Some(cid.to_string());
+        }
+        Ok(())
+    }
+}
@@ -2,21 +2,52 @@
 
 pub mod archivist;
 pub mod audit;
+#[cfg(feature = "async_runtime")]
+pub mod async_streamgate; // non-blocking counterpart to streamgate, for async HTTP/SSE model clients
+pub mod capability; // capability-scoped tool grants, enforced by ContractsDecider
+pub mod cold_store; // pluggable archive backend: Archivist (filesystem) or a remote S3-compatible store
+pub mod commit_log; // write-ahead log (ContractsStore-backed) fronting memory::commit_snapshot
 pub mod ethos; // used at the ingress (streamgate)
+pub mod hot_store; // content-storage surface (remember/recall/forget) that Memory hardcodes against rusqlite today
 pub mod librarian; // thin router: summarize/reflect -> memory; optional promote via archivist
+pub mod lobes; // versioned, content-addressed object store grouped into lobes
+pub mod logbook; // segmented/rotating/gzip-compressed storage backing the logbook streams
+pub mod memid_index; // authenticated memory_id -> cid index, keyed alongside archivist writes
 pub mod memory; // the ONLY SQLite writer // file-only cold store (CID <-> bytes)
+pub mod memory_backend; // storage-agnostic row/snapshot surface Commands uses; Memory is the only wired-up impl today
+pub mod relay; // dataspace relay: shares contracts/violations across StreamGate workers over a socket
 pub mod streamgate;
 pub mod compactor; // summarization + safety gate + replace
 pub mod masking;   // shared normalization + masking helpers
 pub mod reward;    // reward bus / sink for online learning hooks
 pub mod learner;   // step assembler + TD micro-learner
+pub mod registry;  // reads synaptik-admin's registry.jsonl, verifies + activates contract packs
+#[cfg(feature = "sled_backend")]
+pub mod sled_backend; // embedded log-structured KV StorageBackend, tuned for write-heavy ingestion
+pub mod storage_backend; // StorageBackend trait Memory (and sled_backend) implement
+pub mod weight;    // deterministic per-operation cost model backing Commands::stats's total_weight
+#[cfg(feature = "wasm_ethos")]
+pub mod wasm_decider; // pluggable WASM runtime for EthosContract (ship/update policy without recompiling)
 
 // Public API
 pub use archivist::Archivist;
+pub use cold_store::{ColdStore, S3ColdStore};
+pub use hot_store::{HotStore, InMemoryHotStore};
+pub use weight::op_weight;
+#[cfg(feature = "async_runtime")]
+pub use async_streamgate::{AsyncLlmClient, AsyncStreamRuntime};
+#[cfg(feature = "wasm_ethos")]
+pub use wasm_decider::{PolicyBlobs, WasmDecider};
 pub use librarian::Librarian;
+pub use lobes::LobeStore;
 pub use memory::Memory;
+pub use memory_backend::{InMemoryBackend, MemoryBackend};
 pub use streamgate::{
     Finalized, FinalizedStatus, GateDecision, GateError, LlmClient, StreamGate, StreamGateConfig,
     StreamRuntime, StreamingIndex,
 };
 pub use ethos::{ConstraintSpec, EthosContract, Proposal, RuntimeDecision};
+pub use capability::{Capability, CapabilityGrant, ResourcePattern};
+pub use storage_backend::StorageBackend;
+#[cfg(feature = "sled_backend")]
+pub use sled_backend::SledBackend;
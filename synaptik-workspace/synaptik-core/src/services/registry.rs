@@ -0,0 +1,356 @@
+//! In-crate consumer for `synaptik-admin`'s `registry.jsonl` GitOps log.
+//!
+//! `RegistryInit`/`RegistryPromote` only ever *append* PUBLISH/PROMOTE
+//! events and shell out to `contracts-signer` to produce a pack; nothing
+//! previously read the log back or loaded a pack into a running store.
+//! [`Registry`] closes that loop: it replays the event log to resolve each
+//! channel's current pack `uri` (a later `PROMOTE` or `ROLLBACK` overrides
+//! an earlier `PUBLISH`), fetches that pack, verifies its Ed25519
+//! signature and `key_id` against a pinned trust set via
+//! [`contracts::verify_pack`], and only then activates it -- staging the
+//! files into a scratch directory, re-reading each one back and checking
+//! its blake3 against the manifest (the same "write it, then verify what's
+//! actually on disk" idiom `read_verified_or_embedded` uses), and swapping
+//! it in for the live `contracts.path` only after every file checks out.
+//! The directory that was live before the swap is kept as a single backup
+//! slot, so a verification failure (nothing is swapped) or an explicit
+//! [`Registry::rollback`] can restore it without re-fetching anything.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine as _;
+use ed25519_dalek::VerifyingKey;
+use serde::Deserialize;
+
+use contracts::{verify_pack, ContractPack};
+
+use crate::commands::init::ensure_initialized_once;
+use crate::utils::path::Sandbox;
+
+/// One line of `registry.jsonl`, parsed loosely by `op` rather than as a
+/// strict tagged enum -- `PUBLISH`/`PROMOTE`/`ROLLBACK` don't share a
+/// field shape, and a permissive struct lets a future event carry extra
+/// fields without breaking replay of the events already understood.
+#[derive(Debug, Clone, Deserialize)]
+struct RawEvent {
+    op: String,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    uri: Option<String>,
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    to: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// What [`Registry::activate`] did, for callers that want to log or
+/// display it.
+#[derive(Debug, Clone)]
+pub struct ActivationReport {
+    pub channel: String,
+    pub uri: String,
+    pub pack_version: String,
+    pub files_activated: usize,
+}
+
+/// Reads `synaptik-admin`'s registry log, verifies packs against
+/// `trusted_keys` (`signing_key_id` -> public key), and activates them
+/// into `contracts_dir`.
+pub struct Registry {
+    registry_jsonl: PathBuf,
+    contracts_dir: PathBuf,
+    trusted_keys: BTreeMap<String, VerifyingKey>,
+}
+
+impl Registry {
+    /// Bind to `registry_jsonl` (the log `RegistryInit`/`RegistryPromote`
+    /// append to) and activate into the current store's configured
+    /// `contracts.path`.
+    pub fn open(
+        registry_jsonl: impl Into<PathBuf>,
+        trusted_keys: BTreeMap<String, VerifyingKey>,
+    ) -> Result<Self> {
+        let contracts_dir = ensure_initialized_once()?.config.contracts.path.clone();
+        Ok(Self {
+            registry_jsonl: registry_jsonl.into(),
+            contracts_dir,
+            trusted_keys,
+        })
+    }
+
+    /// As [`Registry::open`], but activating into an explicit directory
+    /// rather than the process-wide initialized store -- primarily for
+    /// tests.
+    pub fn open_at(
+        registry_jsonl: impl Into<PathBuf>,
+        contracts_dir: impl Into<PathBuf>,
+        trusted_keys: BTreeMap<String, VerifyingKey>,
+    ) -> Self {
+        Self {
+            registry_jsonl: registry_jsonl.into(),
+            contracts_dir: contracts_dir.into(),
+            trusted_keys,
+        }
+    }
+
+    fn read_events(&self) -> Result<Vec<RawEvent>> {
+        if !self.registry_jsonl.exists() {
+            return Ok(Vec::new());
+        }
+        let text = fs::read_to_string(&self.registry_jsonl)
+            .with_context(|| format!("read {:?}", self.registry_jsonl))?;
+        text.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                serde_json::from_str(l)
+                    .with_context(|| format!("parse registry event: {:?}", l))
+            })
+            .collect()
+    }
+
+    /// Fetch a pack from `uri`. `file://<path>` (as `synaptik-admin`
+    /// emits) and bare paths are read from disk; `http(s)://` is fetched
+    /// over the same `ureq` client the reward sinks use.
+    fn fetch_pack(&self, uri: &str) -> Result<ContractPack> {
+        let bytes = if let Some(path) = uri.strip_prefix("file://") {
+            fs::read(path).with_context(|| format!("read pack {:?}", path))?
+        } else if uri.starts_with("http://") || uri.starts_with("https://") {
+            let resp = ureq::get(uri)
+                .call()
+                .with_context(|| format!("fetch pack {:?}", uri))?;
+            let mut buf = Vec::new();
+            resp.into_reader()
+                .read_to_end(&mut buf)
+                .with_context(|| format!("read pack body {:?}", uri))?;
+            buf
+        } else {
+            fs::read(uri).with_context(|| format!("read pack {:?}", uri))?
+        };
+        serde_json::from_slice(&bytes).with_context(|| format!("parse pack {:?}", uri))
+    }
+
+    /// Replay every event in order, returning each channel's current pack
+    /// `uri`. A `PUBLISH` sets its channel's `uri` directly. A `PROMOTE`
+    /// carries only a `version` (the promoted `ContractPack.version`, not
+    /// a `uri`), so it's resolved by fetching every previously published
+    /// `uri` (most recent first) until one's `version` matches; a
+    /// `PROMOTE` whose version was never published is skipped rather than
+    /// failing the whole replay. A `ROLLBACK` sets its channel's `uri`
+    /// directly, same as `PUBLISH`. `pack_cache` avoids re-fetching a
+    /// `uri` already seen earlier in the same replay.
+    fn resolve_channels(
+        &self,
+        pack_cache: &mut HashMap<String, ContractPack>,
+    ) -> Result<HashMap<String, String>> {
+        let events = self.read_events()?;
+        let mut current: HashMap<String, String> = HashMap::new();
+        let mut published: Vec<String> = Vec::new();
+
+        for ev in &events {
+            match ev.op.as_str() {
+                "PUBLISH" => {
+                    let channel = ev
+                        .channel
+                        .clone()
+                        .ok_or_else(|| anyhow!("PUBLISH event missing channel"))?;
+                    let uri = ev
+                        .uri
+                        .clone()
+                        .ok_or_else(|| anyhow!("PUBLISH event missing uri"))?;
+                    current.insert(channel, uri.clone());
+                    published.push(uri);
+                }
+                "PROMOTE" => {
+                    let to = ev
+                        .to
+                        .clone()
+                        .ok_or_else(|| anyhow!("PROMOTE event missing `to`"))?;
+                    let version = ev
+                        .version
+                        .clone()
+                        .ok_or_else(|| anyhow!("PROMOTE event missing version"))?;
+                    let found = published.iter().rev().find_map(|uri| {
+                        let pack = match pack_cache.get(uri) {
+                            Some(p) => p.clone(),
+                            None => self.fetch_pack(uri).ok()?,
+                        };
+                        pack_cache.entry(uri.clone()).or_insert_with(|| pack.clone());
+                        (pack.version == version).then(|| uri.clone())
+                    });
+                    if let Some(uri) = found {
+                        current.insert(to, uri);
+                    }
+                    // else: nothing published so far carries this version;
+                    // leave `to`'s current pointer as it was.
+                }
+                "ROLLBACK" => {
+                    let channel = ev
+                        .channel
+                        .clone()
+                        .ok_or_else(|| anyhow!("ROLLBACK event missing channel"))?;
+                    let uri = ev
+                        .uri
+                        .clone()
+                        .ok_or_else(|| anyhow!("ROLLBACK event missing uri"))?;
+                    current.insert(channel, uri);
+                }
+                other => {
+                    // Forward-compatible: an event kind this reader
+                    // doesn't know yet is ignored rather than rejected.
+                    let _ = other;
+                }
+            }
+        }
+        Ok(current)
+    }
+
+    /// The pack `uri` currently resolved for `channel`, or `None` if the
+    /// channel has never been published.
+    pub fn resolve_channel(&self, channel: &str) -> Result<Option<String>> {
+        let mut cache = HashMap::new();
+        Ok(self.resolve_channels(&mut cache)?.remove(channel))
+    }
+
+    fn staging_name(&self) -> Result<String> {
+        let live = self
+            .contracts_dir
+            .file_name()
+            .ok_or_else(|| anyhow!("contracts dir {:?} has no file name", self.contracts_dir))?;
+        Ok(format!("{}.staging", live.to_string_lossy()))
+    }
+
+    fn backup_name(&self) -> Result<String> {
+        let live = self
+            .contracts_dir
+            .file_name()
+            .ok_or_else(|| anyhow!("contracts dir {:?} has no file name", self.contracts_dir))?;
+        Ok(format!("{}.prev", live.to_string_lossy()))
+    }
+
+    /// Resolve `channel`'s current pack, verify it against `trusted_keys`,
+    /// stage its files, re-verify each one's blake3 by reading it back off
+    /// disk, and only then swap it in for the live `contracts_dir` --
+    /// keeping whatever was live before as a one-slot backup. Nothing is
+    /// swapped if any step fails, so a half-verified or corrupt pack never
+    /// reaches the live directory.
+    pub fn activate(&self, channel: &str) -> Result<ActivationReport> {
+        let mut cache = HashMap::new();
+        let current = self.resolve_channels(&mut cache)?;
+        let uri = current
+            .get(channel)
+            .cloned()
+            .ok_or_else(|| anyhow!("no published version for channel {:?}", channel))?;
+        let pack = match cache.remove(&uri) {
+            Some(p) => p,
+            None => self.fetch_pack(&uri)?,
+        };
+        verify_pack(&pack, &self.trusted_keys)
+            .with_context(|| format!("verify pack {:?} for channel {:?}", uri, channel))?;
+
+        let parent = self
+            .contracts_dir
+            .parent()
+            .ok_or_else(|| anyhow!("contracts dir {:?} has no parent", self.contracts_dir))?;
+        fs::create_dir_all(parent).with_context(|| format!("create_dir_all {:?}", parent))?;
+
+        let staged_name = self.staging_name()?;
+        let staged_path = parent.join(&staged_name);
+        if staged_path.exists() {
+            fs::remove_dir_all(&staged_path)
+                .with_context(|| format!("remove stale staging dir {:?}", staged_path))?;
+        }
+
+        let sandbox = Sandbox::open(parent)?;
+        sandbox.ensure_dir(Path::new(&staged_name))?;
+
+        for entry in &pack.files {
+            let rel = entry.path.strip_prefix("contracts/").unwrap_or(&entry.path);
+            let blob_b64 = pack
+                .blobs
+                .get(&entry.path)
+                .ok_or_else(|| anyhow!("pack missing blob for {:?}", entry.path))?;
+            let bytes = B64
+                .decode(blob_b64)
+                .map_err(|e| anyhow!("bad base64 for {:?}: {e}", entry.path))?;
+
+            let dest_rel = Path::new(&staged_name).join(rel);
+            if let Some(dir_rel) = dest_rel.parent() {
+                sandbox.ensure_dir(dir_rel)?;
+            }
+            sandbox
+                .create_write(&dest_rel)
+                .and_then(|mut f| Ok(f.write_all(&bytes)?))
+                .with_context(|| format!("stage {:?}", entry.path))?;
+
+            // Verified-reader-style check: read the file back off disk
+            // and recompute its hash, rather than trusting the bytes we
+            // just handed to `write_all`.
+            let mut readback = Vec::new();
+            sandbox
+                .open_read(&dest_rel)
+                .and_then(|mut f| Ok(f.read_to_end(&mut readback)?))
+                .with_context(|| format!("read back staged {:?}", entry.path))?;
+            let actual = blake3::hash(&readback).to_hex().to_string();
+            if actual != entry.blake3 {
+                let _ = fs::remove_dir_all(&staged_path);
+                bail!(
+                    "post-write verification failed for {:?}: manifest={} actual={}",
+                    entry.path,
+                    entry.blake3,
+                    actual
+                );
+            }
+        }
+
+        if self.contracts_dir.exists() {
+            let backup_path = parent.join(self.backup_name()?);
+            if backup_path.exists() {
+                fs::remove_dir_all(&backup_path)
+                    .with_context(|| format!("remove stale backup dir {:?}", backup_path))?;
+            }
+            fs::rename(&self.contracts_dir, &backup_path)
+                .with_context(|| format!("back up {:?}", self.contracts_dir))?;
+        }
+        fs::rename(&staged_path, &self.contracts_dir)
+            .with_context(|| format!("activate {:?}", self.contracts_dir))?;
+
+        Ok(ActivationReport {
+            channel: channel.to_string(),
+            uri,
+            pack_version: pack.version.clone(),
+            files_activated: pack.files.len(),
+        })
+    }
+
+    /// Restore the backup directory saved by the last [`Registry::activate`]
+    /// swap, discarding whatever is currently live. Errors if no backup
+    /// exists (nothing has been activated yet, or a previous rollback
+    /// already consumed it).
+    pub fn rollback(&self) -> Result<()> {
+        let parent = self
+            .contracts_dir
+            .parent()
+            .ok_or_else(|| anyhow!("contracts dir {:?} has no parent", self.contracts_dir))?;
+        let backup_path = parent.join(self.backup_name()?);
+        anyhow::ensure!(
+            backup_path.exists(),
+            "no previous contracts pack to roll back to at {:?}",
+            backup_path
+        );
+        if self.contracts_dir.exists() {
+            fs::remove_dir_all(&self.contracts_dir)
+                .with_context(|| format!("remove {:?}", self.contracts_dir))?;
+        }
+        fs::rename(&backup_path, &self.contracts_dir)
+            .with_context(|| format!("restore {:?}", self.contracts_dir))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+//! services/relay.rs
+//! Dataspace relay for distributed contract enforcement and decision
+//! auditing across many `StreamGate` workers.
+//!
+//! Modeled as a small Linda/tuplespace-style dataspace: a worker *asserts*
+//! interest in an `action` and the relay holds (and keeps fresh) the live
+//! `MoralContract` assertion for that action, pushing `AssertContract` /
+//! `RetractContract` to every interested peer as it changes -- so a worker
+//! can rebuild its `StreamingIndex` live (see
+//! `streamgate::StreamGate::swap_index`) without restarting. Conversely,
+//! every `CutAndReplace`/violation a worker's gate produces is re-asserted
+//! as a `Violation` and fanned out to every other connected peer, so a
+//! supervisor process can subscribe for real-time audit/monitoring.
+//!
+//! Wire protocol: each `RelayMessage` is JSON, framed as `[len: u32 LE]
+//! [payload]` -- the same length-prefixed framing `services::logbook` uses
+//! for its segments -- so a peer only needs "read 4 bytes, read that many
+//! bytes, parse JSON" to join, Rust or not.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A single dataspace message, in either direction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RelayMessage {
+    /// Worker -> relay: register interest in `action`. The relay replies
+    /// with the current `AssertContract` for it, or `RetractContract` if
+    /// none has been asserted yet.
+    AssertInterest { action: String },
+    /// Either direction: the live contract for `action` is (now) `contract`.
+    AssertContract { action: String, contract: Value },
+    /// Either direction: `action` no longer has an asserted contract.
+    RetractContract { action: String },
+    /// Worker -> relay -> every other connected peer: a `CutAndReplace` (or
+    /// other) violation just happened, for real-time audit/monitoring.
+    Violation {
+        action: String,
+        message: String,
+        ts: String,
+    },
+}
+
+/// Write one length-prefixed, JSON-encoded [`RelayMessage`] to `w`.
+pub fn send_message<W: Write>(w: &mut W, msg: &RelayMessage) -> Result<()> {
+    let payload = serde_json::to_vec(msg).context("serializing relay message")?;
+    w.write_all(&(payload.len() as u32).to_le_bytes())?;
+    w.write_all(&payload)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// Read one length-prefixed, JSON-encoded [`RelayMessage`] from `r`, or
+/// `Ok(None)` on a clean EOF between messages.
+pub fn recv_message<R: Read>(r: &mut R) -> Result<Option<RelayMessage>> {
+    let mut len_buf = [0u8; 4];
+    if let Err(e) = r.read_exact(&mut len_buf) {
+        return if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            Ok(None)
+        } else {
+            Err(e.into())
+        };
+    }
+    let len = u32::from_le_bytes(len_buf) as usize;
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload)
+        .context("reading relay message payload")?;
+    serde_json::from_slice(&payload)
+        .map(Some)
+        .context("parsing relay message")
+}
+
+/// The dataspace itself: the live contract per action, and the set of
+/// connected peers to fan assertions/retractions/violations out to.
+#[derive(Default)]
+struct DataSpace {
+    contracts: HashMap<String, Value>,
+    peers: HashMap<u64, Sender<RelayMessage>>,
+    next_peer_id: u64,
+}
+
+fn broadcast(space: &DataSpace, msg: &RelayMessage) {
+    for sender in space.peers.values() {
+        let _ = sender.send(msg.clone());
+    }
+}
+
+/// A running relay server: binds a TCP listener and accepts peers on a
+/// background thread for the lifetime of this handle.
+pub struct Relay {
+    addr: SocketAddr,
+    space: Arc<Mutex<DataSpace>>,
+}
+
+impl Relay {
+    /// Bind `addr` and start accepting peer connections in the background.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let listener = TcpListener::bind(addr).context("binding relay listener")?;
+        let addr = listener.local_addr().context("reading relay local address")?;
+        let space = Arc::new(Mutex::new(DataSpace::default()));
+        let accept_space = space.clone();
+        thread::spawn(move || accept_loop(listener, accept_space));
+        Ok(Self { addr, space })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Assert (publish or replace) the live contract for `action`, fanning
+    /// the update out to every connected peer.
+    pub fn assert_contract(&self, action: &str, contract: Value) {
+        let mut space = self.space.lock().unwrap();
+        space.contracts.insert(action.to_string(), contract.clone());
+        broadcast(
+            &space,
+            &RelayMessage::AssertContract { action: action.to_string(), contract },
+        );
+    }
+
+    /// Retract the contract for `action`, fanning the retraction out.
+    pub fn retract_contract(&self, action: &str) {
+        let mut space = self.space.lock().unwrap();
+        space.contracts.remove(action);
+        broadcast(&space, &RelayMessage::RetractContract { action: action.to_string() });
+    }
+
+    /// Publish a violation/`CutAndReplace` event to every connected peer.
+    pub fn publish_violation(&self, action: &str, message: &str) {
+        let space = self.space.lock().unwrap();
+        broadcast(
+            &space,
+            &RelayMessage::Violation {
+                action: action.to_string(),
+                message: message.to_string(),
+                ts: Utc::now().to_rfc3339(),
+            },
+        );
+    }
+}
+
+fn accept_loop(listener: TcpListener, space: Arc<Mutex<DataSpace>>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let space = space.clone();
+        thread::spawn(move || {
+            let _ = handle_peer(stream, space);
+        });
+    }
+}
+
+fn handle_peer(stream: TcpStream, space: Arc<Mutex<DataSpace>>) -> Result<()> {
+    let mut reader = stream.try_clone().context("cloning relay peer stream")?;
+    let mut writer = stream;
+    let (tx, rx) = channel::<RelayMessage>();
+    let peer_id = {
+        let mut s = space.lock().unwrap();
+        let id = s.next_peer_id;
+        s.next_peer_id += 1;
+        s.peers.insert(id, tx);
+        id
+    };
+
+    // One thread per peer drains its outgoing queue onto the socket, so a
+    // slow/blocked reader on the peer side can't stall the dataspace lock
+    // that `assert_contract`/`publish_violation` take to broadcast.
+    let writer_handle = thread::spawn(move || {
+        for msg in rx {
+            if send_message(&mut writer, &msg).is_err() {
+                break;
+            }
+        }
+    });
+
+    let result = (|| -> Result<()> {
+        while let Some(msg) = recv_message(&mut reader)? {
+            match msg {
+                RelayMessage::AssertInterest { action } => {
+                    let space = space.lock().unwrap();
+                    let reply = match space.contracts.get(&action) {
+                        Some(contract) => {
+                            RelayMessage::AssertContract { action, contract: contract.clone() }
+                        }
+                        None => RelayMessage::RetractContract { action },
+                    };
+                    if let Some(sender) = space.peers.get(&peer_id) {
+                        let _ = sender.send(reply);
+                    }
+                }
+                RelayMessage::AssertContract { action, contract } => {
+                    let mut s = space.lock().unwrap();
+                    s.contracts.insert(action.clone(), contract.clone());
+                    broadcast(&s, &RelayMessage::AssertContract { action, contract });
+                }
+                RelayMessage::RetractContract { action } => {
+                    let mut s = space.lock().unwrap();
+                    s.contracts.remove(&action);
+                    broadcast(&s, &RelayMessage::RetractContract { action });
+                }
+                RelayMessage::Violation { action, message, ts } => {
+                    let s = space.lock().unwrap();
+                    broadcast(&s, &RelayMessage::Violation { action, message, ts });
+                }
+            }
+        }
+        Ok(())
+    })();
+
+    space.lock().unwrap().peers.remove(&peer_id);
+    let _ = writer_handle.join();
+    result
+}
+
+/// A worker's connection to a [`Relay`]: assert interest in a contract,
+/// publish violations, and receive whatever the relay pushes down.
+pub struct RelayClient {
+    writer: Mutex<TcpStream>,
+    reader: Mutex<TcpStream>,
+}
+
+impl RelayClient {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let writer = TcpStream::connect(addr).context("connecting to relay")?;
+        let reader = writer.try_clone().context("cloning relay client stream")?;
+        Ok(Self { writer: Mutex::new(writer), reader: Mutex::new(reader) })
+    }
+
+    fn send(&self, msg: &RelayMessage) -> Result<()> {
+        send_message(&mut *self.writer.lock().unwrap(), msg)
+    }
+
+    /// Register interest in `action`'s contract. The relay's reply (an
+    /// `AssertContract` or `RetractContract`) arrives via [`Self::recv`].
+    pub fn assert_interest(&self, action: &str) -> Result<()> {
+        self.send(&RelayMessage::AssertInterest { action: action.to_string() })
+    }
+
+    /// Assert (publish or replace) the contract for `action` on behalf of
+    /// this peer, e.g. a contract-owning process pushing an update.
+    pub fn assert_contract(&self, action: &str, contract: Value) -> Result<()> {
+        self.send(&RelayMessage::AssertContract { action: action.to_string(), contract })
+    }
+
+    pub fn retract_contract(&self, action: &str) -> Result<()> {
+        self.send(&RelayMessage::RetractContract { action: action.to_string() })
+    }
+
+    /// Publish a `CutAndReplace`/violation for `action`, typically called
+    /// right after `StreamGate::push`/`finalize` returns one.
+    pub fn publish_violation(&self, action: &str, message: &str) -> Result<()> {
+        self.send(&RelayMessage::Violation {
+            action: action.to_string(),
+            message: message.to_string(),
+            ts: Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// Block for the next message the relay sends this peer (a pushed
+    /// contract update, retraction, or another peer's violation), or
+    /// `Ok(None)` if the relay closed the connection.
+    pub fn recv(&self) -> Result<Option<RelayMessage>> {
+        recv_message(&mut *self.reader.lock().unwrap())
+    }
+}
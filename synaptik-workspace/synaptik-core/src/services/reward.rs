@@ -1,7 +1,9 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use rusqlite::{params, Connection};
 use serde::Serialize;
+use serde_json::Value;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::commands::init::ensure_initialized_once;
 use contracts::api::{CapsAnnot, Verdict};
@@ -86,6 +88,169 @@ impl RewardSink for RewardSqliteSink {
     }
 }
 
+/// Attempts a failed POST gets before `post_with_retry` gives up, and the
+/// base delay doubled between each retry.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(200);
+
+/// POST `body` as JSON to `url` with `headers` attached, retrying with
+/// exponential backoff (`DEFAULT_BACKOFF * 2^attempt`) on failure. Returns
+/// the last error once `max_retries` retries (i.e. `max_retries + 1` total
+/// attempts) are exhausted.
+fn post_with_retry(url: &str, headers: &[(String, String)], body: &Value, max_retries: u32) -> Result<()> {
+    let mut last_err = None;
+    for attempt in 0..=max_retries {
+        let mut req = ureq::post(url);
+        for (k, v) in headers {
+            req = req.set(k, v);
+        }
+        match req.send_json(body.clone()) {
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                last_err = Some(e.to_string());
+                if attempt < max_retries {
+                    std::thread::sleep(DEFAULT_BACKOFF * 2u32.saturating_pow(attempt));
+                }
+            }
+        }
+    }
+    Err(anyhow!(
+        "POST to {url} failed after {} attempt(s): {}",
+        max_retries + 1,
+        last_err.unwrap_or_default()
+    ))
+}
+
+/// Notifies an HTTP endpoint of every [`RewardEvent`] by POSTing it as JSON
+/// -- e.g. a chat-ops bridge, a custom dashboard ingest, or an n8n/Zapier
+/// hook. `headers` are attached to every request (commonly an
+/// `Authorization` bearer token); failed deliveries retry with exponential
+/// backoff before `publish` surfaces an error.
+pub struct RewardWebhookSink {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub max_retries: u32,
+}
+
+impl RewardWebhookSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl RewardSink for RewardWebhookSink {
+    fn publish(&self, ev: &RewardEvent) -> Result<()> {
+        let body = serde_json::to_value(ev).context("serialize RewardEvent")?;
+        post_with_retry(&self.url, &self.headers, &body, self.max_retries)
+    }
+}
+
+/// Minimal percent-encoding for a Matrix room id / alias (`!opaque:server`
+/// or `#alias:server`) in a URL path segment -- just enough to escape the
+/// characters Matrix ids actually contain (`!`, `#`, `:`, `/`); no general
+/// crate for this is already a dependency.
+fn percent_encode_path_segment(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Posts a formatted message into a Matrix room via the client-server API
+/// (`PUT /_matrix/client/v3/rooms/{room}/send/m.room.message/{txn}`), so a
+/// quarantine verdict or channel promotion can page an operator room in
+/// real time. `access_token` is a Matrix user or application-service
+/// token with permission to post in `room_id`.
+pub struct MatrixSink {
+    pub homeserver: String,
+    pub room_id: String,
+    pub access_token: String,
+    pub max_retries: u32,
+}
+
+impl MatrixSink {
+    pub fn new(
+        homeserver: impl Into<String>,
+        room_id: impl Into<String>,
+        access_token: impl Into<String>,
+    ) -> Self {
+        Self {
+            homeserver: homeserver.into(),
+            room_id: room_id.into(),
+            access_token: access_token.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    fn send_text(&self, body: &str) -> Result<()> {
+        let txn = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+            self.homeserver.trim_end_matches('/'),
+            percent_encode_path_segment(&self.room_id),
+            txn,
+        );
+        let headers = vec![("Authorization".to_string(), format!("Bearer {}", self.access_token))];
+        let payload = serde_json::json!({ "msgtype": "m.text", "body": body });
+        post_with_retry(&url, &headers, &payload, self.max_retries)
+    }
+}
+
+impl RewardSink for MatrixSink {
+    fn publish(&self, ev: &RewardEvent) -> Result<()> {
+        self.send_text(&format!(
+            "[synaptik] lobe={} capsule={} verdict={} risk={:.2} value={:.2}",
+            ev.lobe, ev.capsule_id, ev.verdict, ev.risk, ev.value
+        ))
+    }
+}
+
+/// Broadcasts each [`RewardEvent`] to every wrapped sink -- e.g. the
+/// canonical [`RewardSqliteSink`] alongside a [`RewardWebhookSink`] or
+/// [`MatrixSink`] -- so persistence and live notification run together.
+/// Every sink is attempted regardless of earlier failures; if any failed,
+/// `publish` returns the first error once all have had their turn, so a
+/// down webhook can't silently swallow a failed SQLite write (or vice
+/// versa).
+pub struct FanoutSink {
+    sinks: Vec<Box<dyn RewardSink>>,
+}
+
+impl FanoutSink {
+    pub fn new(sinks: Vec<Box<dyn RewardSink>>) -> Self {
+        Self { sinks }
+    }
+}
+
+impl RewardSink for FanoutSink {
+    fn publish(&self, ev: &RewardEvent) -> Result<()> {
+        let mut first_err = None;
+        for sink in &self.sinks {
+            if let Err(e) = sink.publish(ev) {
+                if first_err.is_none() {
+                    first_err = Some(e);
+                }
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+}
+
 /// Map a contracts annotation into a scalar reward.
 pub fn reward_from_annotation(ann: &CapsAnnot) -> f32 {
     let base = match ann.verdict {
@@ -0,0 +1,236 @@
+//! `sled`-backed [`StorageBackend`] (requires the `sled_backend` build
+//! feature): an embedded, log-structured KV store tuned for write-heavy
+//! ingestion -- zstd-compressed values, a background flush interval, and a
+//! large page cache -- as an alternative to the SQLite-backed [`Memory`]
+//! for callers who care more about write throughput than SQL-queryable
+//! replay. Promotion/replay still goes through the same content-addressed
+//! DAG `Memory` uses, since that store is already backend-agnostic.
+
+use anyhow::{Context, Result};
+use blake3;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::memory::dag::{self, MemoryState};
+use crate::services::storage_backend::StorageBackend;
+
+/// Page cache size for the sled instance; large relative to SQLite's
+/// default page cache since sled is meant to be tuned for write-heavy
+/// ingestion where the working set of recently-written keys stays hot.
+const CACHE_CAPACITY_BYTES: u64 = 256 * 1024 * 1024;
+
+/// How often sled flushes its in-memory log to disk in the background.
+const FLUSH_EVERY_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RowMeta {
+    lobe: String,
+    key: String,
+    created_at: String,
+    updated_at: String,
+    archived_cid: Option<String>,
+}
+
+/// Embedded KV storage backend on top of `sled`, with the same
+/// `remember`/`recall`/`promote_to_dag`/`recall_snapshot` surface as
+/// [`Memory`](crate::services::memory::Memory).
+pub struct SledBackend {
+    content: sled::Tree,
+    meta: sled::Tree,
+    /// Most recently archived CID per lobe, for `promote_to_dag`'s linear
+    /// parent chain (mirrors `Memory::latest_archived_cid_in_lobe`).
+    lobe_heads: sled::Tree,
+    /// Secondary index keyed `"{lobe}\0{key}\0{memory_id}"` (value unused)
+    /// for `scan_by_lobe_key`, mirroring SQLite's `idx_mem_lobe_key`.
+    lobe_key_idx: sled::Tree,
+    /// Secondary index keyed `"{lobe}\0{updated_at}\0{memory_id}"` (value
+    /// unused) for `recent_in_lobe`: RFC3339 timestamps sort lexically, so
+    /// a reversed prefix scan over `"{lobe}\0"` yields newest-first order
+    /// without a full table scan.
+    recency_idx: sled::Tree,
+}
+
+/// Build a `lobe_key_idx` key. `\0` can't appear in `lobe`/`key`/`memory_id`
+/// (they're caller-supplied identifiers, not arbitrary text), so it's a safe
+/// separator for an unambiguous composite key.
+fn lobe_key_idx_key(lobe: &str, key: &str, memory_id: &str) -> Vec<u8> {
+    format!("{lobe}\0{key}\0{memory_id}").into_bytes()
+}
+
+/// Build a `recency_idx` key; see `SledBackend::recency_idx`.
+fn recency_idx_key(lobe: &str, updated_at: &str, memory_id: &str) -> Vec<u8> {
+    format!("{lobe}\0{updated_at}\0{memory_id}").into_bytes()
+}
+
+impl SledBackend {
+    /// Open (creating if missing) a sled database directory at `path`.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        std::fs::create_dir_all(path)
+            .with_context(|| format!("creating sled dir {:?}", path))?;
+
+        let db = sled::Config::new()
+            .path(path)
+            .cache_capacity(CACHE_CAPACITY_BYTES)
+            .use_compression(true)
+            .flush_every_ms(Some(FLUSH_EVERY_MS))
+            .open()
+            .with_context(|| format!("opening sled db at {:?}", path))?;
+
+        Ok(Self {
+            content: db.open_tree("content").context("opening content tree")?,
+            meta: db.open_tree("meta").context("opening meta tree")?,
+            lobe_heads: db.open_tree("lobe_heads").context("opening lobe_heads tree")?,
+            lobe_key_idx: db
+                .open_tree("lobe_key_idx")
+                .context("opening lobe_key_idx tree")?,
+            recency_idx: db
+                .open_tree("recency_idx")
+                .context("opening recency_idx tree")?,
+        })
+    }
+
+    fn load_meta(&self, memory_id: &str) -> Result<Option<RowMeta>> {
+        match self.meta.get(memory_id)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl StorageBackend for SledBackend {
+    fn remember(&self, memory_id: &str, lobe: &str, key: &str, content: &[u8]) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let previous = self.load_meta(memory_id)?;
+        let row_meta = match &previous {
+            Some(existing) => RowMeta {
+                lobe: lobe.to_string(),
+                key: key.to_string(),
+                created_at: existing.created_at.clone(),
+                updated_at: now,
+                archived_cid: existing.archived_cid.clone(),
+            },
+            None => RowMeta {
+                lobe: lobe.to_string(),
+                key: key.to_string(),
+                created_at: now.clone(),
+                updated_at: now,
+                archived_cid: None,
+            },
+        };
+
+        // Drop stale index entries before writing fresh ones -- `lobe`,
+        // `key`, and `updated_at` may all have changed since `previous`.
+        if let Some(prev) = &previous {
+            self.lobe_key_idx
+                .remove(lobe_key_idx_key(&prev.lobe, &prev.key, memory_id))?;
+            self.recency_idx
+                .remove(recency_idx_key(&prev.lobe, &prev.updated_at, memory_id))?;
+        }
+        self.lobe_key_idx
+            .insert(lobe_key_idx_key(lobe, key, memory_id), &[])?;
+        self.recency_idx
+            .insert(recency_idx_key(lobe, &row_meta.updated_at, memory_id), &[])?;
+
+        self.content.insert(memory_id, content)?;
+        self.meta
+            .insert(memory_id, serde_json::to_vec(&row_meta)?)?;
+        Ok(())
+    }
+
+    fn recall(&self, memory_id: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.content.get(memory_id)?.map(|ivec| ivec.to_vec()))
+    }
+
+    fn promote_to_dag(&self, memory_id: &str) -> Result<()> {
+        let mut row_meta = self
+            .load_meta(memory_id)?
+            .with_context(|| format!("no such memory_id: {memory_id}"))?;
+        let content = self
+            .content
+            .get(memory_id)?
+            .with_context(|| format!("no content for memory_id: {memory_id}"))?;
+
+        let cid = blake3::hash(&content).to_hex().to_string();
+        let parent_cid = self
+            .lobe_heads
+            .get(&row_meta.lobe)?
+            .map(|v| String::from_utf8_lossy(&v).to_string());
+        let parents: Vec<String> = parent_cid.into_iter().collect();
+
+        let meta = json!({
+            "cid": cid,
+            "lobe": row_meta.lobe,
+            "key": row_meta.key,
+            "summary_len": 0,
+            "created_at": row_meta.created_at,
+            "updated_at": row_meta.updated_at,
+        });
+
+        // Best-effort DAG write — never break the hot path, matching
+        // `Memory::promote_to_dag`.
+        let _ = dag::save_node(memory_id, &String::from_utf8_lossy(&content), &meta, &parents);
+
+        row_meta.archived_cid = Some(cid.clone());
+        self.meta
+            .insert(memory_id, serde_json::to_vec(&row_meta)?)?;
+        self.lobe_heads.insert(row_meta.lobe.as_str(), cid.as_str())?;
+        Ok(())
+    }
+
+    fn recall_snapshot(&self, snapshot_id: &str) -> Result<MemoryState> {
+        dag::recall_snapshot(snapshot_id)
+    }
+
+    fn delete(&self, memory_id: &str) -> Result<()> {
+        let Some(row_meta) = self.load_meta(memory_id)? else {
+            return Ok(());
+        };
+        self.lobe_key_idx
+            .remove(lobe_key_idx_key(&row_meta.lobe, &row_meta.key, memory_id))?;
+        self.recency_idx.remove(recency_idx_key(
+            &row_meta.lobe,
+            &row_meta.updated_at,
+            memory_id,
+        ))?;
+        self.content.remove(memory_id)?;
+        self.meta.remove(memory_id)?;
+        Ok(())
+    }
+
+    fn scan_by_lobe_key(&self, lobe: &str, key: &str) -> Result<Vec<String>> {
+        let prefix = format!("{lobe}\0{key}\0");
+        let mut out = Vec::new();
+        for entry in self.lobe_key_idx.scan_prefix(prefix.as_bytes()) {
+            let (idx_key, _) = entry?;
+            if let Some(memory_id) = idx_key
+                .rsplit(|&b| b == 0)
+                .next()
+                .map(|b| String::from_utf8_lossy(b).into_owned())
+            {
+                out.push(memory_id);
+            }
+        }
+        Ok(out)
+    }
+
+    fn recent_in_lobe(&self, lobe: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = format!("{lobe}\0");
+        let mut out = Vec::with_capacity(limit);
+        for entry in self.recency_idx.scan_prefix(prefix.as_bytes()).rev() {
+            if out.len() >= limit {
+                break;
+            }
+            let (idx_key, _) = entry?;
+            if let Some(memory_id) = idx_key
+                .rsplit(|&b| b == 0)
+                .next()
+                .map(|b| String::from_utf8_lossy(b).into_owned())
+            {
+                out.push(memory_id);
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,97 @@
+//! Pluggable storage backend behind [`Memory`]'s hot-path surface, so
+//! write-heavy chat ingestion and read-heavy replay can each pick the
+//! engine that suits them (see `benches/load_ingest.rs`'s side-by-side
+//! comparison, and `config::HotStoreKind`/`build_hot_store` for opting a
+//! deployment into it) without callers caring which one is underneath.
+//!
+//! This only covers the backend-agnostic slice of `Memory`'s surface --
+//! `commands::Commands` still hardcodes `Memory` (SQLite) directly for
+//! everything built on top that has no KV equivalent yet (full-text
+//! search, contracts/consent bookkeeping, MVCC snapshots), so switching
+//! `config.memory.hot_store` only affects callers that go through this
+//! trait, not `Commands` as a whole.
+
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::config::HotStoreKind;
+use crate::memory::dag::MemoryState;
+use crate::services::memory::Memory;
+
+/// The subset of `Memory`'s surface a storage engine must provide: upsert
+/// raw content, read it back, promote a row into the content-addressed DAG,
+/// replay an immutable DAG snapshot, delete a row, and scan by `(lobe,
+/// key)` or by recency -- enough for replay/compaction-style callers to run
+/// against either backend without rewriting DAG promotion logic.
+pub trait StorageBackend: Send + Sync {
+    /// Upsert raw content for `memory_id` under `(lobe, key)`.
+    fn remember(&self, memory_id: &str, lobe: &str, key: &str, content: &[u8]) -> Result<()>;
+
+    /// Fetch raw content by `memory_id`, if present.
+    fn recall(&self, memory_id: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Archive `memory_id`'s current content into the DAG, linearly
+    /// chained off the lobe's most recently archived node.
+    fn promote_to_dag(&self, memory_id: &str) -> Result<()>;
+
+    /// Replay an immutable DAG snapshot by content-addressed id (blake3 hex).
+    fn recall_snapshot(&self, snapshot_id: &str) -> Result<MemoryState>;
+
+    /// Remove `memory_id` (and any index entries an engine keeps for it).
+    /// A no-op, not an error, if the row is already gone.
+    fn delete(&self, memory_id: &str) -> Result<()>;
+
+    /// All `memory_id`s stored under an exact `(lobe, key)`.
+    fn scan_by_lobe_key(&self, lobe: &str, key: &str) -> Result<Vec<String>>;
+
+    /// Up to `limit` `memory_id`s in `lobe`, most-recently-updated first.
+    fn recent_in_lobe(&self, lobe: &str, limit: usize) -> Result<Vec<String>>;
+}
+
+impl StorageBackend for Memory {
+    fn remember(&self, memory_id: &str, lobe: &str, key: &str, content: &[u8]) -> Result<()> {
+        Memory::remember(self, memory_id, lobe, key, content)
+    }
+
+    fn recall(&self, memory_id: &str) -> Result<Option<Vec<u8>>> {
+        Memory::recall(self, memory_id)
+    }
+
+    fn promote_to_dag(&self, memory_id: &str) -> Result<()> {
+        Memory::promote_to_dag(self, memory_id)
+    }
+
+    fn recall_snapshot(&self, snapshot_id: &str) -> Result<MemoryState> {
+        Memory::recall_snapshot(self, snapshot_id)
+    }
+
+    fn delete(&self, memory_id: &str) -> Result<()> {
+        Memory::delete(self, memory_id)
+    }
+
+    fn scan_by_lobe_key(&self, lobe: &str, key: &str) -> Result<Vec<String>> {
+        Memory::find_by_lobe_key(self, lobe, key)
+    }
+
+    fn recent_in_lobe(&self, lobe: &str, limit: usize) -> Result<Vec<String>> {
+        crate::commands::helpers::recent_ids_in_lobe(self, lobe, limit)
+    }
+}
+
+/// Build the `StorageBackend` selected by `config.memory.hot_store`.
+/// `cache_path` is `MemoryConfig::cache_path`, used for `HotStoreKind::Sqlite`
+/// (`HotStoreKind::Sled`'s own `path` field is used instead for that variant).
+pub fn build_hot_store(cache_path: &Path, kind: &HotStoreKind) -> Result<Arc<dyn StorageBackend>> {
+    match kind {
+        HotStoreKind::Sqlite => Ok(Arc::new(Memory::open(&cache_path.to_string_lossy())?)),
+        #[cfg(feature = "sled_backend")]
+        HotStoreKind::Sled { path } => Ok(Arc::new(
+            crate::services::sled_backend::SledBackend::open(path)?,
+        )),
+        #[cfg(not(feature = "sled_backend"))]
+        HotStoreKind::Sled { .. } => anyhow::bail!(
+            "memory.hot_store = sled requested but this binary wasn't built with --features sled_backend"
+        ),
+    }
+}
@@ -1,9 +1,9 @@
 // synaptik-core/src/services/streamgate.rs
 
 use anyhow::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use contracts::types::{ContractRule, MoralContract};
-use contracts::normalize::for_rules;
+use contracts::normalize::{fold_for_matching, for_rules};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -168,28 +168,84 @@ impl StreamingIndex {
 
 pub struct StreamGate {
     index: Arc<StreamingIndex>,
-    _cfg: StreamGateConfig,
+    cfg: StreamGateConfig,
+    start: Instant,
+    tail: String, // rolling suffix of prior chunks, so matches can straddle pushes
     saw_violation: bool, // any non-idiom violation seen
     is_held: bool,       // streaming hold
     pending_cut_msg: Option<String>,
+    poll_buf: String, // chunks handed to poll_push but not yet evaluated
 }
 
 impl StreamGate {
     pub fn from_index(index: Arc<StreamingIndex>, cfg: StreamGateConfig) -> Self {
         Self {
             index,
-            _cfg: cfg,
+            cfg,
+            start: Instant::now(),
+            tail: String::new(),
             saw_violation: false,
             is_held: false,
+            poll_buf: String::new(),
             pending_cut_msg: None,
         }
     }
 
+    /// Swap in a new contract index in place, e.g. after a `services::relay`
+    /// peer pushes an `AssertContract`/`RetractContract` update for this
+    /// gate's action. Only the index changes; the rolling tail, budget
+    /// clock, and hold/violation state are left untouched so an in-flight
+    /// stream isn't reset by a live contract reload.
+    pub fn swap_index(&mut self, index: Arc<StreamingIndex>) {
+        self.index = index;
+    }
+
+    fn budget_exceeded(&self) -> bool {
+        self.cfg.budget_ms > 0 && self.start.elapsed().as_millis() as u64 >= self.cfg.budget_ms
+    }
+
+    fn budget_cut_message() -> String {
+        "Response time budget exceeded; stopping here.".to_string()
+    }
+
+    /// Trim `s` back down to at most `window_bytes`, snapped to the nearest
+    /// preceding char boundary so a multi-byte char is never split.
+    fn snap_tail(s: &str, window_bytes: usize) -> String {
+        if window_bytes == 0 || s.len() <= window_bytes {
+            return s.to_string();
+        }
+        let target = s.len() - window_bytes;
+        let mut start = 0usize;
+        for (i, _) in s.char_indices() {
+            if i <= target {
+                start = i;
+            } else {
+                break;
+            }
+        }
+        s[start..].to_string()
+    }
+
     pub fn push(&mut self, chunk: &str) -> GateDecision {
-        // budget/window enforcement can be added later; no-op for now
+        if self.budget_exceeded() {
+            self.saw_violation = true;
+            if self.cfg.fail_closed_on_finalize {
+                let msg = Self::budget_cut_message();
+                self.pending_cut_msg = Some(msg.clone());
+                return GateDecision::CutAndReplace(msg);
+            }
+            self.is_held = true;
+            return GateDecision::Hold;
+        }
+
+        // Evaluate the rolling window (prior tail + this chunk) so a banned
+        // phrase split across two `push` calls still matches, then trim the
+        // tail back down for the next call.
+        let mut window = self.tail.clone();
+        window.push_str(chunk);
+        let eval = self.index.evaluate_chunk(&window);
+        self.tail = Self::snap_tail(&window, self.cfg.window_bytes);
 
-        // Evaluate this chunk
-        let eval = self.index.evaluate_chunk(chunk);
         if eval.passed {
             // If we were already holding, keep holding (don’t flicker)
             return if self.is_held {
@@ -204,7 +260,7 @@ impl StreamGate {
         if eval
             .violated_rules
             .iter()
-            .any(|r| self.index.is_personal_threat(r, chunk))
+            .any(|r| self.index.is_personal_threat(r, &window))
         {
             let suggestion = self.index.suggestion_for(&eval.violated_rules);
             self.pending_cut_msg = Some(suggestion.clone());
@@ -219,7 +275,35 @@ impl StreamGate {
         GateDecision::Hold
     }
 
+    /// Non-blocking counterpart to `push`: buffers `chunk` instead of
+    /// evaluating it immediately, so a caller driving many gates off one
+    /// event loop can poll a cheap append rather than pay for a full
+    /// evaluation on every single chunk. Returns `None` ("pending") while
+    /// the buffered text is still short of `window_bytes`; once enough has
+    /// accumulated (or the time budget is up, which must be checked
+    /// promptly regardless of buffer size) it evaluates exactly as `push`
+    /// would and returns `Some(decision)`.
+    pub fn poll_push(&mut self, chunk: &str) -> Option<GateDecision> {
+        self.poll_buf.push_str(chunk);
+        if self.cfg.window_bytes > 0
+            && self.poll_buf.len() < self.cfg.window_bytes
+            && !self.budget_exceeded()
+        {
+            return None;
+        }
+        let buffered = std::mem::take(&mut self.poll_buf);
+        Some(self.push(&buffered))
+    }
+
     pub fn finalize(&mut self) -> GateDecision {
+        if self.budget_exceeded() && self.cfg.fail_closed_on_finalize {
+            let msg = self
+                .pending_cut_msg
+                .take()
+                .unwrap_or_else(Self::budget_cut_message);
+            return GateDecision::CutAndReplace(msg);
+        }
+
         if self.saw_violation {
             // If we have a suggestion from earlier, reuse; else derive from rules
             let msg = self.pending_cut_msg.take().unwrap_or_else(|| {
@@ -241,7 +325,7 @@ impl StreamGate {
 use crate::services::audit;
 use crate::services::ethos::{ConstraintSpec, EthosContract, Proposal, RuntimeDecision};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FinalizedStatus {
     Ok,
     Violated,
@@ -249,7 +333,7 @@ pub enum FinalizedStatus {
     Escalated,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finalized {
     pub status: FinalizedStatus,
     pub text: String,
@@ -365,14 +449,14 @@ impl<C: EthosContract, M: LlmClient> StreamRuntime<C, M> {
                 buf.push_str(&tok);
                 if !spec.mask_rules.is_empty() {
                     if window_bytes == 0 || buf.len() <= window_bytes {
-                        buf = crate::services::masking::apply_masks_ci(&buf, &spec.mask_rules);
+                        buf = crate::services::masking::apply_mask_rules(&buf, &spec.mask_rules);
                     } else {
                         let target = buf.len() - window_bytes;
                         // Find nearest char boundary <= target.
                         let mut start = 0usize;
                         for (i, _) in buf.char_indices() { if i <= target { start = i; } else { break; } }
                         let tail = buf[start..].to_string();
-                        let masked_tail = crate::services::masking::apply_masks_ci(&tail, &spec.mask_rules);
+                        let masked_tail = crate::services::masking::apply_mask_rules(&tail, &spec.mask_rules);
                         buf.truncate(start);
                         buf.push_str(&masked_tail);
                     }
@@ -405,7 +489,7 @@ impl<C: EthosContract, M: LlmClient> StreamRuntime<C, M> {
         // Memory write barrier
         if finalized.is_ok() {
             // Best-effort commit note: for MVP we log the commit event in memory::commit_snapshot
-            let _ = crate::services::memory::commit_snapshot(&p, &decision, &finalized);
+            let _ = crate::services::memory::commit_snapshot_logged(&p, &decision, &finalized);
         } else {
             audit::log_violation(&p, "rejected_snapshot", &finalized.text);
         }
@@ -414,7 +498,10 @@ impl<C: EthosContract, M: LlmClient> StreamRuntime<C, M> {
     }
 }
 
-fn prompt_compile(p: &Proposal, spec: Option<&ConstraintSpec>) -> String {
+// Shared with `async_streamgate` so the non-blocking runtime compiles the
+// same system prompt and enforces the same stop-phrase/token-limit rules as
+// this synchronous one, rather than drifting apart.
+pub(crate) fn prompt_compile(p: &Proposal, spec: Option<&ConstraintSpec>) -> String {
     let mut lines = vec![
         format!("You are an assistant. Intent: {}.", p.intent),
         "Adhere to the rules below strictly.".into(),
@@ -434,13 +521,14 @@ fn prompt_compile(p: &Proposal, spec: Option<&ConstraintSpec>) -> String {
     lines.join("\n")
 }
 
-fn norm_lower(s: &str) -> String { for_rules(s) }
-
-fn hits_stop_phrase(buf: &str, tok: &str, stop_phrases: &[String]) -> bool {
+// Stop phrases are matched obfuscation-aware (see contracts::normalize module
+// doc) so "s t o p" / "st0p" / Cyrillic look-alikes still trigger, unlike the
+// plain `norm` used for idiom allowlists and pronoun detection above.
+pub(crate) fn hits_stop_phrase(buf: &str, tok: &str, stop_phrases: &[String]) -> bool {
     if stop_phrases.is_empty() { return false; }
     let binding = format!("{}{}", buf, tok);
-    let hay = norm_lower(&binding);
-    stop_phrases.iter().any(|s| !s.is_empty() && hay.contains(&norm_lower(s)))
+    let hay = fold_for_matching(&binding);
+    stop_phrases.iter().any(|s| !s.is_empty() && hay.contains(&fold_for_matching(s)))
 }
 
 // Build a normalized view (case / rule normalization) along with original byte spans.
@@ -449,7 +537,7 @@ fn hits_stop_phrase(buf: &str, tok: &str, stop_phrases: &[String]) -> bool {
 // cannot accidentally shift and reveal trailing suffixes.
 // normalized_chars_with_spans and apply_masks moved to crate::services::masking
 
-fn token_limit_reached(buf: &str, max_tokens: usize) -> bool {
+pub(crate) fn token_limit_reached(buf: &str, max_tokens: usize) -> bool {
     if max_tokens == 0 { return false; }
     // rough approximation: whitespace tokens
     let cnt = buf.split_whitespace().count();
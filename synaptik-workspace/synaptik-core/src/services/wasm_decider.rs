@@ -0,0 +1,275 @@
+// synaptik-core/src/services/wasm_decider.rs
+//
+// Pluggable WASM runtime for `EthosContract`: loads an arbitrary `.wasm`
+// ethos module and calls its exported `evaluate`, so operators can ship or
+// update ethics policy without recompiling this crate. Mirrors the
+// host/guest ABI and sandboxing posture established in `contracts::sandbox`
+// (allocate/evaluate buffer marshalling, fuel metering, memory cap, import
+// allow-listing) but widens the allow-list to the minimal host capabilities
+// an ethos policy legitimately needs: `log`, `now_unix`, and a capped
+// `read_policy_blob`. Only compiled when the `wasm_ethos` feature is enabled.
+//
+// Guest ABI:
+// * allocate(len: i32) -> i32                  — guest reserves `len` bytes, returns a pointer
+// * evaluate(ptr: i32, len: i32) -> (i32, i32)  — guest reads the `Proposal` JSON at (ptr,len),
+//   returns (out_ptr, out_len) pointing at a `RuntimeDecision` JSON value.
+//
+// Host imports (module "env"):
+// * log(ptr: i32, len: i32)                          — best-effort diagnostic string
+// * now_unix() -> i64                                — seconds since the Unix epoch
+// * read_policy_blob(name_ptr: i32, name_len: i32) -> (i32, i32)
+//     looks up a host-provided named blob, writes it into guest memory via
+//     the guest's own `allocate`, and returns (ptr, len); an unknown name or
+//     a blob over `MAX_POLICY_BLOB_BYTES` yields (0, 0).
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use wasmtime::*;
+
+use super::ethos::{EthosContract, Proposal, RuntimeDecision};
+
+const WASM_MEMORY_MAX_BYTES: usize = 64 * 1024 * 1024; // 64 MiB, same cap as contracts::sandbox
+const DEFAULT_FUEL_BUDGET: u64 = 10_000_000; // same ATP budget as contracts::sandbox
+const DEFAULT_DEADLINE: Duration = Duration::from_millis(250);
+const MAX_POLICY_BLOB_BYTES: usize = 256 * 1024;
+const MAX_LOG_BYTES: usize = 4 * 1024;
+
+/// Host-provided named blobs a guest contract can read via
+/// `read_policy_blob` (e.g. an allow/deny list updated independently of the
+/// wasm module itself).
+pub type PolicyBlobs = HashMap<String, Vec<u8>>;
+
+struct HostState {
+    policy_blobs: PolicyBlobs,
+}
+
+fn allowed_imports() -> HashSet<(&'static str, &'static str)> {
+    [("env", "log"), ("env", "now_unix"), ("env", "read_policy_blob")]
+        .into_iter()
+        .collect()
+}
+
+/// Reject the module up front if it imports anything outside
+/// `allowed_imports()`, mirroring `contracts::sandbox::validate_imports`.
+fn validate_imports(module: &Module) -> Result<()> {
+    let allowed = allowed_imports();
+    for import in module.imports() {
+        let key = (import.module(), import.name());
+        if !allowed.contains(&key) {
+            return Err(anyhow!(
+                "rejected wasm ethos module: disallowed import {}::{}",
+                key.0,
+                key.1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Loads and runs a `.wasm` ethos module with a bounded fuel budget, a
+/// wall-clock deadline enforced via epoch interruption, and a memory cap. A
+/// module that traps, times out, or returns malformed JSON is mapped to
+/// `RuntimeDecision::Escalate` rather than ever panicking the host.
+pub struct WasmDecider {
+    engine: Engine,
+    module: Module,
+    policy_blobs: PolicyBlobs,
+    fuel_budget: u64,
+    deadline: Duration,
+}
+
+impl WasmDecider {
+    /// Load `wasm_bytes` with the default fuel budget and deadline. See
+    /// [`WasmDecider::with_limits`] to override either.
+    pub fn load(wasm_bytes: &[u8], policy_blobs: PolicyBlobs) -> Result<Self> {
+        Self::with_limits(wasm_bytes, policy_blobs, DEFAULT_FUEL_BUDGET, DEFAULT_DEADLINE)
+    }
+
+    pub fn with_limits(
+        wasm_bytes: &[u8],
+        policy_blobs: PolicyBlobs,
+        fuel_budget: u64,
+        deadline: Duration,
+    ) -> Result<Self> {
+        let mut config = Config::new();
+        config.wasm_memory64(false);
+        config.static_memory_maximum_size(WASM_MEMORY_MAX_BYTES as u64);
+        config.consume_fuel(true);
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config)?;
+
+        let module = Module::new(&engine, wasm_bytes)?;
+        validate_imports(&module)?;
+
+        Ok(Self {
+            engine,
+            module,
+            policy_blobs,
+            fuel_budget,
+            deadline,
+        })
+    }
+
+    fn build_linker(&self) -> Result<Linker<HostState>> {
+        let mut linker = Linker::new(&self.engine);
+
+        linker.func_wrap("env", "log", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            let memory = match caller.get_export("memory").and_then(|e| e.into_memory()) {
+                Some(m) => m,
+                None => return,
+            };
+            let len = (len.max(0) as usize).min(MAX_LOG_BYTES);
+            let mut buf = vec![0u8; len];
+            if memory.read(&caller, ptr as usize, &mut buf).is_ok() {
+                let msg = String::from_utf8_lossy(&buf);
+                eprintln!("[wasm_ethos] {msg}");
+            }
+        })?;
+
+        linker.func_wrap("env", "now_unix", |_caller: Caller<'_, HostState>| -> i64 {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0)
+        })?;
+
+        linker.func_wrap(
+            "env",
+            "read_policy_blob",
+            |mut caller: Caller<'_, HostState>, name_ptr: i32, name_len: i32| -> Result<(i32, i32)> {
+                let memory = caller
+                    .get_export("memory")
+                    .and_then(|e| e.into_memory())
+                    .ok_or_else(|| anyhow!("guest has no exported linear memory 'memory'"))?;
+
+                let mut name_bytes = vec![0u8; name_len.max(0) as usize];
+                memory
+                    .read(&caller, name_ptr as usize, &mut name_bytes)
+                    .map_err(|e| anyhow!("reading policy blob name: {e}"))?;
+                let name = String::from_utf8(name_bytes)
+                    .map_err(|e| anyhow!("policy blob name not valid UTF-8: {e}"))?;
+
+                let mut blob = caller.data().policy_blobs.get(&name).cloned().unwrap_or_default();
+                if blob.len() > MAX_POLICY_BLOB_BYTES {
+                    blob.truncate(MAX_POLICY_BLOB_BYTES);
+                }
+                if blob.is_empty() {
+                    return Ok((0, 0));
+                }
+
+                let allocate = caller
+                    .get_export("allocate")
+                    .and_then(|e| e.into_func())
+                    .ok_or_else(|| anyhow!("guest did not export 'allocate(len: i32) -> i32'"))?
+                    .typed::<i32, i32>(&caller)?;
+                let out_ptr = allocate.call(&mut caller, blob.len() as i32)?;
+
+                let memory = caller
+                    .get_export("memory")
+                    .and_then(|e| e.into_memory())
+                    .ok_or_else(|| anyhow!("guest has no exported linear memory 'memory'"))?;
+                memory
+                    .write(&mut caller, out_ptr as usize, &blob)
+                    .map_err(|e| anyhow!("writing policy blob into guest memory: {e}"))?;
+
+                Ok((out_ptr, blob.len() as i32))
+            },
+        )?;
+
+        Ok(linker)
+    }
+
+    fn run(&self, p: &Proposal) -> Result<RuntimeDecision> {
+        let linker = self.build_linker()?;
+        let mut store = Store::new(
+            &self.engine,
+            HostState {
+                policy_blobs: self.policy_blobs.clone(),
+            },
+        );
+        store.add_fuel(self.fuel_budget)?;
+        store.set_epoch_deadline(1);
+
+        // Watchdog: bump the engine's epoch after `deadline` so a looping or
+        // stalled guest is interrupted deterministically even if it never
+        // burns through its fuel budget (e.g. a tight loop with no calls).
+        let engine = self.engine.clone();
+        let done = Arc::new(AtomicBool::new(false));
+        let done_watchdog = done.clone();
+        let deadline = self.deadline;
+        let watchdog = std::thread::spawn(move || {
+            std::thread::sleep(deadline);
+            if !done_watchdog.load(Ordering::SeqCst) {
+                engine.increment_epoch();
+            }
+        });
+
+        let result = (|| -> Result<RuntimeDecision> {
+            let instance = linker.instantiate(&mut store, &self.module)?;
+
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .ok_or_else(|| anyhow!("guest did not export linear memory 'memory'"))?;
+            let allocate = instance
+                .get_typed_func::<i32, i32>(&mut store, "allocate")
+                .context("missing exported function 'allocate(len: i32) -> i32'")?;
+            let evaluate = instance
+                .get_typed_func::<(i32, i32), (i32, i32)>(&mut store, "evaluate")
+                .context("missing exported function 'evaluate(ptr: i32, len: i32) -> (i32, i32)'")?;
+
+            let input = serde_json::to_vec(p).context("serialize Proposal to JSON")?;
+            let in_ptr = allocate.call(&mut store, input.len() as i32)?;
+            memory
+                .write(&mut store, in_ptr as usize, &input)
+                .map_err(|e| anyhow!("writing Proposal into guest memory: {e}"))?;
+
+            let (out_ptr, out_len) = evaluate.call(&mut store, (in_ptr, input.len() as i32))?;
+            if out_len < 0 {
+                return Err(anyhow!("guest returned negative output length: {out_len}"));
+            }
+
+            let mut out_bytes = vec![0u8; out_len as usize];
+            memory
+                .read(&store, out_ptr as usize, &mut out_bytes)
+                .map_err(|e| anyhow!("reading RuntimeDecision from guest memory: {e}"))?;
+
+            serde_json::from_slice(&out_bytes).context("parse guest RuntimeDecision JSON")
+        })();
+
+        done.store(true, Ordering::SeqCst);
+        let _ = watchdog.join();
+
+        result
+    }
+}
+
+/// Traps raised by fuel exhaustion or epoch interruption surface through
+/// wasmtime's error chain with these markers; anything else is a guest-side
+/// fault (bad export, malformed JSON, ...).
+fn is_timeout_error(e: &anyhow::Error) -> bool {
+    let msg = e.to_string();
+    msg.contains("fuel") || msg.contains("epoch") || msg.contains("interrupt")
+}
+
+impl EthosContract for WasmDecider {
+    fn evaluate(&self, p: &Proposal) -> RuntimeDecision {
+        match self.run(p) {
+            Ok(decision) => decision,
+            Err(e) => {
+                let reason = if is_timeout_error(&e) {
+                    "contract_timeout"
+                } else {
+                    eprintln!("[wasm_ethos] contract execution failed: {e}");
+                    "contract_error"
+                };
+                RuntimeDecision::Escalate {
+                    reason: reason.to_string(),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// src/services/weight.rs
+//! Deterministic per-operation cost model backing `Commands::stats`'s
+//! `total_weight`: every metered operation (`Memory::remember`,
+//! `remember_with_summary`, `promote_to_dag` -- and so `promote_all_hot_in_lobe`/
+//! `promote_latest_hot_in_lobe`, which call it per row --, archive writes in
+//! `Librarian::promote_to_archive`/`Commands::ensure_archive_for`, and a
+//! successful tier hit in `Commands::recall_any`) folds a fixed base weight
+//! plus a per-byte weight into the lobe's running total via `Memory::accrue_weight`.
+
+/// Minimum cost of any metered operation, independent of payload size --
+/// covers the fixed overhead of a SQLite write/read or a cold-store round trip.
+pub const BASE_WEIGHT: u64 = 8;
+
+/// Additional cost per byte touched (written, archived, or returned).
+pub const PER_BYTE_WEIGHT: u64 = 1;
+
+/// `BASE_WEIGHT` plus `PER_BYTE_WEIGHT` for every byte in `bytes_touched`.
+pub fn op_weight(bytes_touched: usize) -> u64 {
+    BASE_WEIGHT + (bytes_touched as u64) * PER_BYTE_WEIGHT
+}
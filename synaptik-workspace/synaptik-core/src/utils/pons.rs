@@ -14,6 +14,14 @@
 //! - Handles large, non-DB artifacts (photos, audio, graphs, embeddings).
 //! - Provides atomic write, safe key sanitation, and version scanning.
 //! - No SQLite, no DAG — it is purely filesystem-backed.
+//! - Deduplicates identical payloads across versions/keys/pons via a
+//!   content-addressed blob store keyed by `etag` (see `BLOBS_DIR`).
+//! - Storage is pluggable via [`ObjectBackend`] — local disk by default
+//!   (see [`FsObjectBackend`]), with an S3-compatible backend available
+//!   behind the `s3_backend` feature. The hardlink-based blob dedup and the
+//!   on-disk key index are filesystem-shaped fast paths and stay
+//!   local-disk-only for now; core reads/writes/listing work against any
+//!   backend.
 //!
 //! # Background
 //! Pons grew out of robotics experiments where OpenCV pipelines emitted rapid
@@ -36,15 +44,41 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{
+    collections::HashSet,
     ffi::OsStr,
     fs,
-    io::Write,
+    io::{Read, Write},
     path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 const OBJECTS_DIR: &str = "objects";
 const VERSIONS_DIR: &str = "versions";
 const LATEST_FILE: &str = "LATEST";
+const BLOBS_DIR: &str = "_blobs";
+/// Chunk size for streaming put/get, bounding memory use regardless of
+/// object size (the OpenCV sensor-burst use case the module docs describe).
+const STREAM_CHUNK_LEN: usize = 64 * 1024;
+/// Logical key, relative to a pons's own namespace, holding its declared
+/// [`PonsSchema`] (see [`PonsStore::create_pons_with_schema`]).
+const SCHEMA_FILE: &str = "_schema.json";
+
+/// A per-pons metadata schema: field name -> the [`crate::commands::Conversion`]
+/// `put_object_with_meta` should coerce that field's `extra` value through.
+pub type PonsSchema = std::collections::BTreeMap<String, crate::commands::Conversion>;
+
+fn schema_key(pons: &str) -> String {
+    format!("{pons}/{SCHEMA_FILE}")
+}
+
+fn next_scratch_id() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ObjectRef {
@@ -55,6 +89,17 @@ pub struct ObjectRef {
     pub size_bytes: u64,
 }
 
+/// One bounded, resumable page from [`PonsStore::list_range`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct PonsRangePage {
+    pub refs: Vec<ObjectRef>,
+    /// Last-seen key; pass as the next call's `start` to resume. `None` once
+    /// the range is exhausted.
+    pub next_cursor: Option<String>,
+    /// `true` if keys remain in range beyond this page.
+    pub partial: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ObjectMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -73,27 +118,391 @@ struct ObjectSidecar {
     extra: Option<Value>,
 }
 
-/// Filesystem-backed store for versioned objects grouped into "pons".
-/// Root is typically `.cogniv/objects`.
+/// Refcount sidecar for a content-addressed blob (`_blobs/<hash[0:2]>/<hash>.json`).
+/// Incremented each time a version is linked to the blob, decremented when a
+/// version is removed; [`PonsStore::prune_blobs`] reclaims blobs at zero.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct BlobSidecar {
+    refcount: u64,
+}
+
+/// Retention knobs for [`PonsStore::gc_with_policy`], applied on top of
+/// whatever the caller's live set already protects. A version survives the
+/// sweep if it's live, or `keep_last_n` ranks it among the newest versions
+/// for its key, or `keep_newer_than` puts it inside the age window.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    pub keep_last_n: Option<usize>,
+    pub keep_newer_than: Option<Duration>,
+}
+
+/// Outcome of a [`PonsStore::gc_with_policy`] sweep.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    pub scanned_keys: usize,
+    pub versions_removed: usize,
+    pub bytes_removed: u64,
+}
+
+/// Bounds a reader to exactly `len` bytes, mirroring [`Read::read_exact`]'s
+/// short-read error semantics rather than [`std::io::Take`]'s silent early
+/// EOF: once the caller has consumed `len` bytes, further reads return
+/// `Ok(0)`; if the underlying reader runs dry first, the next read returns
+/// an `UnexpectedEof` error instead of quietly truncating the object.
+pub struct ExactReader<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: Read> ExactReader<R> {
+    pub fn new(inner: R, len: u64) -> Self {
+        Self {
+            inner,
+            remaining: len,
+        }
+    }
+}
+
+impl<R: Read> Read for ExactReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 {
+            return Ok(0);
+        }
+        let cap = buf.len().min(self.remaining as usize);
+        let n = self.inner.read(&mut buf[..cap])?;
+        if n == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("expected {} more bytes, got none", self.remaining),
+            ));
+        }
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+/// Pluggable persistence medium behind [`PonsStore`], factoring out the
+/// concrete filesystem operations (atomic write, read, directory listing,
+/// the `LATEST` pointer) so the same versioned-object semantics can target
+/// local disk or a remote object store with no caller-visible change —
+/// mirroring the `contracts` crate's `CapsuleBackend` split between
+/// `FsBackend` and its S3-compatible counterpart.
+///
+/// Keys are logical, `/`-separated paths relative to the store root (e.g.
+/// `<pons>/<key>/versions/<version>.bin`, `<pons>/<key>/LATEST`); backends
+/// map them onto whatever physical layout suits the medium.
+pub trait ObjectBackend: Send + Sync {
+    /// Atomically replace the object at `key` with `bytes`.
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Read the full contents at `key`, or `None` if absent.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// List every object key at or under `prefix`, ascending.
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Remove the object at `key`, if present. A no-op if it's already gone.
+    fn remove(&self, key: &str) -> Result<()>;
+
+    /// Copy an already-written object onto a second key — used to link a
+    /// content-addressed blob onto its per-version path. The default
+    /// implementation is a plain read-then-write; [`FsObjectBackend`]
+    /// overrides it with a real hardlink.
+    fn link(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let bytes = self
+            .get(src_key)?
+            .ok_or_else(|| anyhow::anyhow!("link: missing source key {src_key}"))?;
+        self.put_atomic(dst_key, &bytes)
+    }
+
+    /// The backend's root directory on local disk, if it has one. Most of
+    /// `PonsStore`'s behaviour that's inherently filesystem-shaped (hardlink
+    /// blob dedup, the on-disk key index, streaming I/O via `fs::File`)
+    /// keeps using real paths directly against this for speed; those
+    /// operations return an error for any backend that isn't local-disk
+    /// backed. The core read/write/list path always goes through the trait
+    /// instead, so it works against any backend regardless.
+    fn local_root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Default, local-disk [`ObjectBackend`]: the same layout `PonsStore` has
+/// always used, just behind the trait.
+pub struct FsObjectBackend {
+    root: PathBuf,
+}
+
+impl FsObjectBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl ObjectBackend for FsObjectBackend {
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        write_atomic(&self.path(key), bytes)
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(
+            fs::read(&path).with_context(|| format!("read {:?}", path))?,
+        ))
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let root = self.path(prefix);
+        if !root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut out = Vec::new();
+        let mut stack = vec![root];
+        while let Some(dir) = stack.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else {
+                    let rel = path
+                        .strip_prefix(&self.root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/");
+                    out.push(rel);
+                }
+            }
+        }
+        out.sort();
+        Ok(out)
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        if path.is_file() {
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    fn link(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let src = self.path(src_key);
+        let dst = self.path(dst_key);
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::hard_link(&src, &dst).is_err() {
+            let mut s = fs::File::open(&src)?;
+            let tmp = dst.with_extension("tmp");
+            {
+                let mut f = fs::File::create(&tmp)?;
+                std::io::copy(&mut s, &mut f)?;
+                f.sync_all()?;
+            }
+            fs::rename(&tmp, &dst)?;
+        }
+        Ok(())
+    }
+
+    fn local_root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
+
+/// S3-compatible remote [`ObjectBackend`], mirroring
+/// `contracts::backend::s3::S3Backend`'s use of the `rust-s3` crate. Keys
+/// map directly onto object keys under an optional bucket prefix (so
+/// `<pons>/<key>/versions/<version>.bin` becomes
+/// `<prefix>/<pons>/<key>/versions/<version>.bin`); there is no local-disk
+/// fast path, so `PonsStore` falls back to its generic, trait-only code
+/// path for every operation against this backend. The etag is whatever the
+/// sidecar recorded, or recomputed from the fetched bytes via blake3 if
+/// there's no sidecar.
+#[cfg(feature = "s3_backend")]
+pub struct S3ObjectBackend {
+    bucket: s3::bucket::Bucket,
+    prefix: String,
+}
+
+#[cfg(feature = "s3_backend")]
+impl S3ObjectBackend {
+    pub fn new(
+        bucket: &str,
+        region: s3::Region,
+        credentials: s3::creds::Credentials,
+        prefix: impl Into<String>,
+    ) -> Result<Self> {
+        let bucket = s3::bucket::Bucket::new(bucket, region, credentials)
+            .context("construct s3 bucket handle")?
+            .with_path_style();
+        Ok(Self {
+            bucket,
+            prefix: prefix.into(),
+        })
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix.trim_end_matches('/'), key)
+        }
+    }
+}
+
+#[cfg(feature = "s3_backend")]
+impl ObjectBackend for S3ObjectBackend {
+    fn put_atomic(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        self.bucket
+            .put_object(&self.full_key(key), bytes)
+            .map(|_| ())
+            .with_context(|| format!("s3 put_object {key}"))
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.bucket.get_object(&self.full_key(key)) {
+            Ok(resp) if resp.status_code() == 200 => Ok(Some(resp.bytes().to_vec())),
+            Ok(_) => Ok(None),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let full_prefix = self.full_key(prefix);
+        let pages = self
+            .bucket
+            .list(format!("{}/", full_prefix.trim_end_matches('/')), None)
+            .with_context(|| format!("s3 list {prefix}"))?;
+        let strip_len = if self.prefix.is_empty() {
+            0
+        } else {
+            self.prefix.trim_end_matches('/').len() + 1
+        };
+        let mut keys: Vec<String> = pages
+            .into_iter()
+            .flat_map(|p| p.contents)
+            .map(|o| o.key[strip_len.min(o.key.len())..].to_string())
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let _ = self.bucket.delete_object(&self.full_key(key));
+        Ok(())
+    }
+}
+
+/// Versioned object store grouped into "pons", backed by a pluggable
+/// [`ObjectBackend`] (local disk by default — see [`FsObjectBackend`]).
 pub struct PonsStore {
-    root: PathBuf, // e.g., .cogniv
+    backend: Arc<dyn ObjectBackend>,
 }
 
 impl PonsStore {
-    /// Open or initialize a pons store at the given root.
+    /// Open or initialize a pons store at the given root, using the default
+    /// local-disk backend.
     pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
         let root = root.into();
-        fs::create_dir_all(root.join(OBJECTS_DIR))?;
-        Ok(Self { root })
+        let backend = Arc::new(FsObjectBackend::new(root.join(OBJECTS_DIR))?);
+        Self::new_with_backend(backend)
+    }
+
+    /// Build a store against any [`ObjectBackend`] — local disk, an
+    /// S3-compatible bucket ([`S3ObjectBackend`], behind the `s3_backend`
+    /// feature), or a test double.
+    pub fn new_with_backend(backend: Arc<dyn ObjectBackend>) -> Result<Self> {
+        Ok(Self { backend })
+    }
+
+    /// Escape hatch for the filesystem-shaped fast paths (hardlink dedup,
+    /// the on-disk key index, streaming I/O) that haven't been generalized
+    /// to arbitrary backends yet.
+    fn local_root(&self) -> Result<&Path> {
+        self.backend.local_root().ok_or_else(|| {
+            anyhow::anyhow!("this PonsStore operation requires a local-disk backend")
+        })
     }
 
     /// Ensure a given pons namespace exists (idempotent).
     pub fn create_pons(&self, name: &str) -> Result<()> {
         let pons = sanitize_key(name)?;
-        fs::create_dir_all(self.pons_dir(&pons))?;
+        fs::create_dir_all(self.pons_dir(&pons)?)?;
         Ok(())
     }
 
+    /// Ensure a pons namespace exists and declare a typed metadata schema
+    /// for it: `put_object_with_meta` will coerce any `extra` field named in
+    /// `schema` through its declared [`Conversion`] instead of leaving it a
+    /// stringly-typed blob. Overwrites any schema already recorded for this
+    /// pons.
+    pub fn create_pons_with_schema(&self, name: &str, schema: &PonsSchema) -> Result<()> {
+        self.create_pons(name)?;
+        let pons = sanitize_key(name)?;
+        let serializable: std::collections::BTreeMap<String, String> = schema
+            .iter()
+            .map(|(field, conversion)| (field.clone(), conversion.to_string()))
+            .collect();
+        self.backend.put_atomic(
+            &schema_key(&pons),
+            &serde_json::to_vec_pretty(&serializable)?,
+        )
+    }
+
+    /// The metadata schema declared for a pons via
+    /// [`create_pons_with_schema`], if any.
+    pub fn get_pons_schema(&self, pons: &str) -> Result<Option<PonsSchema>> {
+        let pons = sanitize_key(pons)?;
+        let Some(bytes) = self.backend.get(&schema_key(&pons))? else {
+            return Ok(None);
+        };
+        let raw: std::collections::BTreeMap<String, String> = serde_json::from_slice(&bytes)?;
+        let mut out = PonsSchema::new();
+        for (field, conversion_str) in raw {
+            let conversion: crate::commands::Conversion = conversion_str
+                .parse()
+                .map_err(|e: crate::commands::ConversionError| anyhow::anyhow!(e.to_string()))?;
+            out.insert(field, conversion);
+        }
+        Ok(Some(out))
+    }
+
+    /// Coerce every `extra` field named in this pons's schema (if any)
+    /// through its declared [`Conversion`], storing `{"value": <typed>,
+    /// "raw": <original string>}` in place of the bare string. Fields not
+    /// named in the schema, or whose raw value isn't a JSON string, pass
+    /// through unchanged. A field that IS named in the schema but fails to
+    /// parse is a [`ConversionError`], not a silently dropped/`Null` value.
+    fn apply_schema(&self, pons: &str, extra: Option<Value>) -> Result<Option<Value>> {
+        let Some(Value::Object(mut map)) = extra else {
+            return Ok(extra);
+        };
+        let Some(schema) = self.get_pons_schema(pons)? else {
+            return Ok(Some(Value::Object(map)));
+        };
+        for (field, conversion) in schema.iter() {
+            let Some(Value::String(raw)) = map.get(field) else {
+                continue;
+            };
+            let typed = crate::commands::recall::convert_recalled(raw, conversion)?;
+            map.insert(
+                field.clone(),
+                serde_json::json!({ "value": typed, "raw": raw }),
+            );
+        }
+        Ok(Some(Value::Object(map)))
+    }
+
     /// Legacy helper that writes bytes without metadata.
     /// Prefer [`put_object_with_meta`] to capture media type and extras.
     pub fn put_object(
@@ -107,6 +516,9 @@ impl PonsStore {
     }
 
     /// Store a new object version with optional media type and metadata.
+    /// Routes through [`ObjectBackend`], so this works against local disk or
+    /// a remote backend; the local-disk path additionally gets hardlink
+    /// blob dedup and the on-disk key index.
     pub fn put_object_with_meta(
         &self,
         pons: &str,
@@ -116,26 +528,214 @@ impl PonsStore {
         extra: Option<Value>,
     ) -> Result<(ObjectRef, PathBuf)> {
         let (pons, key) = normalize_pair(pons, key)?;
-        let versions_dir = self.versions_dir(&pons, &key);
+        if self.backend.local_root().is_none() {
+            return self.put_object_with_meta_generic(pons, key, data, media_type, extra);
+        }
+
+        let versions_dir = self.versions_dir(&pons, &key)?;
         fs::create_dir_all(&versions_dir)?;
 
         let etag = blake3::hash(data).to_hex().to_string();
         let ts_ms = Utc::now().timestamp_millis();
         let version_id = format!("{}-{}", ts_ms, &etag[..12]);
         let data_path = data_path(&versions_dir, &version_id);
-        write_atomic(&data_path, data)?;
+        self.store_version_blob(&data_path, &etag, data)?;
 
+        let size_bytes = data.len() as u64;
+        let object_ref = self.finalize_version(
+            pons,
+            key,
+            &versions_dir,
+            version_id,
+            etag,
+            size_bytes,
+            media_type,
+            extra,
+        )?;
+        Ok((object_ref, data_path))
+    }
+
+    /// Like [`Self::put_object_with_meta`], but `expected_blake3` (if given)
+    /// is checked against the freshly computed digest before anything is
+    /// committed: a mismatch returns an error and leaves the pons/key's
+    /// `LATEST` pointer untouched, so a corrupted transfer never lands in
+    /// the store.
+    pub fn put_object_with_meta_verified(
+        &self,
+        pons: &str,
+        key: &str,
+        data: &[u8],
+        media_type: Option<&str>,
+        extra: Option<Value>,
+        expected_blake3: Option<&str>,
+    ) -> Result<(ObjectRef, PathBuf)> {
+        if let Some(expected) = expected_blake3 {
+            let actual = blake3::hash(data).to_hex().to_string();
+            if !actual.eq_ignore_ascii_case(expected) {
+                anyhow::bail!(
+                    "blake3 mismatch for {pons}/{key}: expected {expected}, computed {actual}"
+                );
+            }
+        }
+        self.put_object_with_meta(pons, key, data, media_type, extra)
+    }
+
+    /// Trait-only counterpart to [`Self::put_object_with_meta`] for backends
+    /// with no local disk underneath: no hardlink dedup, no on-disk key
+    /// index, just the plain `ObjectBackend` primitives against the logical
+    /// `<pons>/<key>/versions/<version>.bin` key layout.
+    fn put_object_with_meta_generic(
+        &self,
+        pons: String,
+        key: String,
+        data: &[u8],
+        media_type: Option<&str>,
+        extra: Option<Value>,
+    ) -> Result<(ObjectRef, PathBuf)> {
+        let etag = blake3::hash(data).to_hex().to_string();
+        let ts_ms = Utc::now().timestamp_millis();
+        let version_id = format!("{}-{}", ts_ms, &etag[..12]);
+        let size_bytes = data.len() as u64;
+
+        let data_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.bin");
+        self.backend.put_atomic(&data_key, data)?;
+
+        let extra = self.apply_schema(&pons, extra)?;
+        let sidecar = ObjectSidecar {
+            etag: etag.clone(),
+            size_bytes,
+            media_type: media_type.map(|s| s.to_string()),
+            extra,
+        };
+        let sidecar_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.json");
+        self.backend
+            .put_atomic(&sidecar_key, &serde_json::to_vec_pretty(&sidecar)?)?;
+
+        let latest_key = format!("{pons}/{key}/{LATEST_FILE}");
+        self.backend.put_atomic(&latest_key, version_id.as_bytes())?;
+
+        Ok((
+            ObjectRef {
+                pons,
+                key,
+                version: version_id,
+                etag,
+                size_bytes,
+            },
+            PathBuf::from(data_key),
+        ))
+    }
+
+    /// Like [`put_object_with_meta`], but for large payloads (depth maps,
+    /// audio, multi-MB frames): bytes are copied from `reader` through a
+    /// scratch file in `STREAM_CHUNK_LEN`-sized chunks while feeding a
+    /// streaming `blake3::Hasher`, so memory stays bounded regardless of
+    /// object size. The etag/version id aren't known until the copy
+    /// finishes, so the scratch file only becomes the content-addressed blob
+    /// (and then the versioned path) afterward.
+    ///
+    /// Local-disk backends only — see [`put_object_with_meta`] for the
+    /// generic, any-backend path.
+    pub fn put_object_streaming(
+        &self,
+        pons: &str,
+        key: &str,
+        reader: impl Read,
+        media_type: Option<&str>,
+        extra: Option<Value>,
+    ) -> Result<(ObjectRef, PathBuf)> {
+        let (pons, key) = normalize_pair(pons, key)?;
+        let versions_dir = self.versions_dir(&pons, &key)?;
+        fs::create_dir_all(&versions_dir)?;
+
+        let (etag, size_bytes) = self.store_blob_streaming(reader)?;
+        let ts_ms = Utc::now().timestamp_millis();
+        let version_id = format!("{}-{}", ts_ms, &etag[..12]);
+        let data_path = data_path(&versions_dir, &version_id);
+        self.link_version_to_blob(&data_path, &etag)?;
+
+        let object_ref = self.finalize_version(
+            pons,
+            key,
+            &versions_dir,
+            version_id,
+            etag,
+            size_bytes,
+            media_type,
+            extra,
+        )?;
+        Ok((object_ref, data_path))
+    }
+
+    /// Like [`Self::put_object_streaming`], but `expected_blake3` (if given)
+    /// is checked against the digest computed while copying `reader`,
+    /// before the version is finalized: a mismatch aborts before `LATEST`
+    /// or the key index are ever touched, so a corrupted transfer never
+    /// becomes visible even though its bytes were already streamed into a
+    /// content-addressed blob.
+    pub fn put_object_streaming_verified(
+        &self,
+        pons: &str,
+        key: &str,
+        reader: impl Read,
+        media_type: Option<&str>,
+        extra: Option<Value>,
+        expected_blake3: Option<&str>,
+    ) -> Result<(ObjectRef, PathBuf)> {
+        let (pons, key) = normalize_pair(pons, key)?;
+        let versions_dir = self.versions_dir(&pons, &key)?;
+        fs::create_dir_all(&versions_dir)?;
+
+        let (etag, size_bytes) = self.store_blob_streaming(reader)?;
+        if let Some(expected) = expected_blake3 {
+            if !etag.eq_ignore_ascii_case(expected) {
+                anyhow::bail!(
+                    "blake3 mismatch for {pons}/{key}: expected {expected}, computed {etag}"
+                );
+            }
+        }
+        let ts_ms = Utc::now().timestamp_millis();
+        let version_id = format!("{}-{}", ts_ms, &etag[..12]);
+        let data_path = data_path(&versions_dir, &version_id);
+        self.link_version_to_blob(&data_path, &etag)?;
+
+        let object_ref = self.finalize_version(
+            pons,
+            key,
+            &versions_dir,
+            version_id,
+            etag,
+            size_bytes,
+            media_type,
+            extra,
+        )?;
+        Ok((object_ref, data_path))
+    }
+
+    /// Shared tail of both `put_object_*` variants: write the sidecar, move
+    /// `LATEST`, upsert the key index, and hand back the resulting [`ObjectRef`].
+    fn finalize_version(
+        &self,
+        pons: String,
+        key: String,
+        versions_dir: &Path,
+        version_id: String,
+        etag: String,
+        size_bytes: u64,
+        media_type: Option<&str>,
+        extra: Option<Value>,
+    ) -> Result<ObjectRef> {
+        let extra = self.apply_schema(&pons, extra)?;
         let sidecar = ObjectSidecar {
             etag: etag.clone(),
-            size_bytes: data.len() as u64,
+            size_bytes,
             media_type: media_type.map(|s| s.to_string()),
             extra,
         };
-        let sidecar_path = sidecar_path(&versions_dir, &version_id);
         let sidecar_bytes = serde_json::to_vec_pretty(&sidecar)?;
-        write_atomic(&sidecar_path, &sidecar_bytes)?;
+        write_atomic(&sidecar_path(versions_dir, &version_id), &sidecar_bytes)?;
 
-        let latest_path = self.key_dir(&pons, &key).join(LATEST_FILE);
+        let latest_path = self.key_dir(&pons, &key)?.join(LATEST_FILE);
         write_atomic(latest_path.as_path(), version_id.as_bytes())?;
 
         let object_ref = ObjectRef {
@@ -143,14 +743,38 @@ impl PonsStore {
             key,
             version: version_id,
             etag,
-            size_bytes: sidecar.size_bytes,
+            size_bytes,
         };
-        Ok((object_ref, data_path))
+        self.index_upsert(
+            &object_ref.pons,
+            &object_ref.key,
+            &object_ref.version,
+            &object_ref.etag,
+            object_ref.size_bytes,
+        )?;
+        Ok(object_ref)
     }
 
-    /// Read the latest version of an object as raw bytes.
+    /// Read the latest version of an object as raw bytes. Routes through
+    /// [`ObjectBackend`] so this works against local disk or a remote backend.
     pub fn get_object_latest(&self, pons: &str, key: &str) -> Result<Vec<u8>> {
         let (pons, key) = normalize_pair(pons, key)?;
+        if self.backend.local_root().is_none() {
+            let latest_key = format!("{pons}/{key}/{LATEST_FILE}");
+            let version_bytes = self
+                .backend
+                .get(&latest_key)?
+                .ok_or_else(|| anyhow::anyhow!("no versions found for {pons}/{key}"))?;
+            let version_id = String::from_utf8(version_bytes)
+                .context("LATEST pointer is not utf8")?
+                .trim()
+                .to_string();
+            let data_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.bin");
+            return self
+                .backend
+                .get(&data_key)?
+                .ok_or_else(|| anyhow::anyhow!("missing object data for {data_key}"));
+        }
         let latest = self.latest_version(&pons, &key)?;
         self.read_version_bytes(&pons, &key, &latest)
     }
@@ -158,16 +782,82 @@ impl PonsStore {
     /// Retrieve the latest [`ObjectRef`] for a `(pons, key)` pair.
     pub fn get_object_latest_ref(&self, pons: &str, key: &str) -> Result<ObjectRef> {
         let (pons, key) = normalize_pair(pons, key)?;
+        if self.backend.local_root().is_none() {
+            let latest_key = format!("{pons}/{key}/{LATEST_FILE}");
+            let version_bytes = self
+                .backend
+                .get(&latest_key)?
+                .ok_or_else(|| anyhow::anyhow!("no versions found for {pons}/{key}"))?;
+            let version_id = String::from_utf8(version_bytes)
+                .context("LATEST pointer is not utf8")?
+                .trim()
+                .to_string();
+            return self.get_object_ref_generic(&pons, &key, &version_id);
+        }
         let version_id = self.latest_version(&pons, &key)?;
         self.get_object_ref(&pons, &key, &version_id)
     }
 
-    /// Read a specific object version as raw bytes.
+    /// Read a specific object version as raw bytes. Routes through
+    /// [`ObjectBackend`] so this works against local disk or a remote backend.
     pub fn get_object_version(&self, pons: &str, key: &str, version_id: &str) -> Result<Vec<u8>> {
         let (pons, key) = normalize_pair(pons, key)?;
+        if self.backend.local_root().is_none() {
+            let data_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.bin");
+            return self
+                .backend
+                .get(&data_key)?
+                .ok_or_else(|| anyhow::anyhow!("missing object data for {data_key}"));
+        }
         self.read_version_bytes(&pons, &key, version_id)
     }
 
+    /// Open a specific version for streaming reads, instead of loading the
+    /// whole object into memory via [`get_object_version`]. The returned
+    /// reader is bounded to the version's recorded size and mirrors
+    /// [`Read::read_exact`]'s short-read error semantics: it errors if the
+    /// underlying file turns out to hold fewer bytes than expected.
+    ///
+    /// Local-disk backends only.
+    pub fn get_object_version_reader(
+        &self,
+        pons: &str,
+        key: &str,
+        version_id: &str,
+    ) -> Result<ExactReader<fs::File>> {
+        let (pons, key) = normalize_pair(pons, key)?;
+        let versions_dir = self.versions_dir(&pons, &key)?;
+        let path = data_path(&versions_dir, version_id);
+        let file = fs::File::open(&path).with_context(|| format!("open {:?}", path))?;
+
+        let size_bytes = match load_sidecar(&versions_dir, version_id)? {
+            Some(s) => s.size_bytes,
+            None => file.metadata()?.len(),
+        };
+        Ok(ExactReader::new(file, size_bytes))
+    }
+
+    /// Like [`Self::get_object_version_reader`], but defaults to the newest
+    /// version when `version` is `None` -- the streaming counterpart to
+    /// [`Self::get_object_latest`] for payloads too large to buffer whole.
+    ///
+    /// Local-disk backends only.
+    pub fn open_object_reader(
+        &self,
+        pons: &str,
+        key: &str,
+        version: Option<&str>,
+    ) -> Result<ExactReader<fs::File>> {
+        let version_id = match version {
+            Some(v) => v.to_string(),
+            None => {
+                let (pons, key) = normalize_pair(pons, key)?;
+                self.latest_version(&pons, &key)?
+            }
+        };
+        self.get_object_version_reader(pons, key, &version_id)
+    }
+
     /// Read a specific version, returning bytes alongside metadata.
     pub fn get_object_version_with_meta(
         &self,
@@ -188,7 +878,21 @@ impl PonsStore {
         version_id: &str,
     ) -> Result<ObjectMetadata> {
         let (pons, key) = normalize_pair(pons, key)?;
-        let versions_dir = self.versions_dir(&pons, &key);
+        if self.backend.local_root().is_none() {
+            let sidecar_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.json");
+            return Ok(match self.backend.get(&sidecar_key)? {
+                Some(bytes) => {
+                    let sidecar: ObjectSidecar = serde_json::from_slice(&bytes)
+                        .with_context(|| format!("parse sidecar {sidecar_key}"))?;
+                    ObjectMetadata {
+                        media_type: sidecar.media_type,
+                        extra: sidecar.extra,
+                    }
+                }
+                None => ObjectMetadata::default(),
+            });
+        }
+        let versions_dir = self.versions_dir(&pons, &key)?;
         let sidecar = load_sidecar(&versions_dir, version_id)?;
         Ok(match sidecar {
             Some(s) => ObjectMetadata {
@@ -202,7 +906,10 @@ impl PonsStore {
     /// Construct an [`ObjectRef`] for an existing version, recomputing metadata if needed.
     pub fn get_object_ref(&self, pons: &str, key: &str, version_id: &str) -> Result<ObjectRef> {
         let (pons, key) = normalize_pair(pons, key)?;
-        let versions_dir = self.versions_dir(&pons, &key);
+        if self.backend.local_root().is_none() {
+            return self.get_object_ref_generic(&pons, &key, version_id);
+        }
+        let versions_dir = self.versions_dir(&pons, &key)?;
         let sidecar = load_sidecar(&versions_dir, version_id)?;
         let data_path = data_path(&versions_dir, version_id);
 
@@ -225,6 +932,38 @@ impl PonsStore {
         })
     }
 
+    /// Trait-only counterpart to [`Self::get_object_ref`]: loads the sidecar
+    /// (or recomputes the etag from the fetched bytes if there's no
+    /// sidecar) via [`ObjectBackend::get`] alone.
+    fn get_object_ref_generic(&self, pons: &str, key: &str, version_id: &str) -> Result<ObjectRef> {
+        let sidecar_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.json");
+        let (etag, size_bytes) = match self.backend.get(&sidecar_key)? {
+            Some(bytes) => {
+                let sidecar: ObjectSidecar = serde_json::from_slice(&bytes)
+                    .with_context(|| format!("parse sidecar {sidecar_key}"))?;
+                (sidecar.etag, sidecar.size_bytes)
+            }
+            None => {
+                let data_key = format!("{pons}/{key}/{VERSIONS_DIR}/{version_id}.bin");
+                let bytes = self
+                    .backend
+                    .get(&data_key)?
+                    .ok_or_else(|| anyhow::anyhow!("missing object data for {data_key}"))?;
+                (
+                    blake3::hash(&bytes).to_hex().to_string(),
+                    bytes.len() as u64,
+                )
+            }
+        };
+        Ok(ObjectRef {
+            pons: pons.to_string(),
+            key: key.to_string(),
+            version: version_id.to_string(),
+            etag,
+            size_bytes,
+        })
+    }
+
     /// List the latest version for keys under a pons, returning at most `limit` refs.
     ///
     /// Deterministic and scalable(ish): traverses the directory tree in lexicographic
@@ -252,11 +991,14 @@ impl PonsStore {
     /// - When `prefix` is provided, only keys with the normalized path starting with that
     ///   prefix are considered for both cursor comparison and output.
     ///
-    /// Notes and future work:
-    /// - For very large stores, walking the filesystem is still O(n). A persistent
-    ///   B-Tree or on-disk index keyed by `<key_rel>` → `<latest_version>` would
-    ///   provide O(log n) seek plus O(k) page reads. Hook here to swap in such an
-    ///   index when available.
+    /// On a local-disk backend this is backed by the persistent on-disk key
+    /// index (`objects/<pons>/.index`, see the module-level comment near
+    /// `INDEX_MAGIC`): `prefix`/`start_after` positioning is a binary search
+    /// over sorted fixed-width records, so this is O(log n) seek plus O(k)
+    /// page reads rather than a full directory walk. The index is rebuilt
+    /// transparently from a directory scan if it's missing or unreadable.
+    /// On any other backend this falls back to listing every key under the
+    /// pons via [`ObjectBackend::list`] and grouping in memory.
     pub fn list_latest_page(
         &self,
         pons: &str,
@@ -264,8 +1006,6 @@ impl PonsStore {
         start_after: Option<&str>,
         limit: usize,
     ) -> Result<Vec<ObjectRef>> {
-        use std::collections::BTreeSet;
-
         let pons = sanitize_key(pons)?;
         let pref_norm = if let Some(raw) = prefix {
             let trimmed = raw.trim();
@@ -288,28 +1028,180 @@ impl PonsStore {
             None
         };
 
-        let pons_dir = self.pons_dir(&pons);
-        if !pons_dir.exists() || limit == 0 {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        if self.backend.local_root().is_none() {
+            return self.list_latest_page_generic(&pons, &pref_norm, cursor_norm.as_deref(), limit);
+        }
+
+        let pons_dir = self.pons_dir(&pons)?;
+        if !pons_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        self.list_latest_page_indexed(&pons, &pref_norm, cursor_norm.as_deref(), limit)
+    }
+
+    /// K2V-style bounded range read: latest object refs for keys in
+    /// `[start, end)` under `pons` (`start` inclusive, `end` exclusive),
+    /// optionally narrowed by `prefix`, at most `limit` entries.
+    ///
+    /// Built on [`Self::list_latest_page`] rather than a new index traversal:
+    /// that cursor is exclusive, so an inclusive `start` is fetched as its own
+    /// probe via [`Self::get_object_latest_ref`] and prepended, then one extra
+    /// entry beyond `limit` is requested from the page so `partial`/
+    /// `next_cursor` can be determined without a second round-trip. Returns
+    /// `next_cursor: Some(last key returned)` when more keys remain in range;
+    /// a caller resumes by passing that cursor back in as the next `start`.
+    pub fn list_range(
+        &self,
+        pons: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+        prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<PonsRangePage> {
+        if limit == 0 {
+            return Ok(PonsRangePage { refs: Vec::new(), next_cursor: None, partial: false });
+        }
+
+        let pons_norm = sanitize_key(pons)?;
+        let start_norm = match start {
+            Some(s) if !s.trim().is_empty() => Some(sanitize_key(s.trim())?),
+            _ => None,
+        };
+        let end_norm = match end {
+            Some(e) if !e.trim().is_empty() => Some(sanitize_key(e.trim())?),
+            _ => None,
+        };
+        let pref_norm = match prefix {
+            Some(p) if !p.trim().is_empty() => Some(sanitize_key(p.trim())?),
+            _ => None,
+        };
+
+        let mut candidates: Vec<ObjectRef> = Vec::new();
+        if let Some(s) = &start_norm {
+            if pref_norm.as_deref().map(|p| s.starts_with(p)).unwrap_or(true) {
+                if let Ok(r) = self.get_object_latest_ref(&pons_norm, s) {
+                    candidates.push(r);
+                }
+            }
+        }
+        let page = self.list_latest_page(&pons_norm, pref_norm.as_deref(), start_norm.as_deref(), limit + 1)?;
+        candidates.extend(page);
+
+        let mut refs = Vec::with_capacity(limit.min(candidates.len()));
+        let mut partial = false;
+        for r in candidates {
+            if let Some(e) = &end_norm {
+                if r.key.as_str() >= e.as_str() {
+                    partial = false;
+                    break;
+                }
+            }
+            if refs.len() == limit {
+                partial = true;
+                break;
+            }
+            refs.push(r);
+        }
+
+        let next_cursor = if partial { refs.last().map(|r| r.key.clone()) } else { None };
+        Ok(PonsRangePage { refs, next_cursor, partial })
+    }
+
+    /// Trait-only counterpart to [`Self::list_latest_page_indexed`] for
+    /// backends with no on-disk key index: lists every object key under the
+    /// pons via [`ObjectBackend::list`], groups by key, and picks the
+    /// lexicographically greatest version per key (version ids are
+    /// `<ts_ms>-<etag12>`, so lexicographic order is chronological order)
+    /// before applying the same prefix/cursor/limit rules as the indexed path.
+    fn list_latest_page_generic(
+        &self,
+        pons: &str,
+        pref_norm: &str,
+        cursor_norm: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRef>> {
+        use std::collections::BTreeMap;
+
+        let pons_prefix = format!("{pons}/");
+        let versions_marker = format!("/{VERSIONS_DIR}/");
+        let mut latest: BTreeMap<String, String> = BTreeMap::new();
+        for full_key in self.backend.list(&pons_prefix)? {
+            let rel = match full_key.strip_prefix(&pons_prefix) {
+                Some(r) => r,
+                None => continue,
+            };
+            let marker_pos = match rel.rfind(&versions_marker) {
+                Some(i) => i,
+                None => continue,
+            };
+            let key_rel = &rel[..marker_pos];
+            let file = &rel[marker_pos + versions_marker.len()..];
+            let version = match file.strip_suffix(".bin") {
+                Some(v) => v,
+                None => continue,
+            };
+            latest
+                .entry(key_rel.to_string())
+                .and_modify(|v| {
+                    if version > v.as_str() {
+                        *v = version.to_string();
+                    }
+                })
+                .or_insert_with(|| version.to_string());
+        }
+
+        let start_key = cursor_norm.unwrap_or(pref_norm);
+        let mut out = Vec::with_capacity(limit.min(128));
+        for (key, version) in latest {
+            let behind = match cursor_norm {
+                Some(cur) => key.as_str() <= cur,
+                None => key.as_str() < start_key,
+            };
+            if behind {
+                continue;
+            }
+            if !pref_norm.is_empty() && !key.starts_with(pref_norm) {
+                continue;
+            }
+            out.push(self.get_object_ref_generic(pons, &key, &version)?);
+            if out.len() >= limit {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Directory-walk fallback that rebuilds the on-disk key index from
+    /// scratch: a full lexicographic traversal mirroring the pre-index
+    /// `list_latest_page`, except unpaginated (it collects every key, not
+    /// just a page), since the index has to describe the whole pons.
+    /// Local-disk backends only — the index itself is a local-disk fast path.
+    fn scan_all_latest(&self, pons: &str) -> Result<Vec<IndexEntry>> {
+        use std::collections::BTreeSet;
+
+        let pons_dir = self.pons_dir(pons)?;
+        if !pons_dir.is_dir() {
             return Ok(Vec::new());
         }
 
-        // Lexicographically ordered frontier for deterministic traversal.
         let mut frontier: BTreeSet<PathBuf> = BTreeSet::new();
         frontier.insert(pons_dir.clone());
 
-        let mut out = Vec::with_capacity(limit.min(128));
+        let mut out = Vec::new();
         while let Some(dir) = frontier.iter().next().cloned() {
             frontier.remove(&dir);
 
-            // Collect child directories and insert into frontier (BTreeSet keeps them sorted).
             for entry in fs::read_dir(&dir)? {
                 let entry = entry?;
                 let path = entry.path();
                 if !path.is_dir() {
                     continue;
                 }
-
-                // Skip the VERSIONS leaf directory itself.
                 if path
                     .file_name()
                     .map(|s| s == OsStr::new(VERSIONS_DIR))
@@ -325,37 +1217,166 @@ impl PonsStore {
                         .unwrap()
                         .to_string_lossy()
                         .replace('\\', "/");
-
-                    if !pref_norm.is_empty() && !key_rel.starts_with(&pref_norm) {
-                        continue;
-                    }
-                    if let Some(cur) = &cursor_norm {
-                        if key_rel <= *cur {
-                            // Not past the cursor yet; skip.
-                            continue;
-                        }
-                    }
-
                     let latest = match fs::read_to_string(path.join(LATEST_FILE)) {
                         Ok(s) => s.trim().to_string(),
                         Err(_) => self.scan_latest_version(&versions)?,
                     };
-                    let obj_ref = self.get_object_ref(&pons, &key_rel, &latest)?;
-                    out.push(obj_ref);
-                    if out.len() >= limit {
-                        return Ok(out);
-                    }
+                    let obj_ref = self.get_object_ref(pons, &key_rel, &latest)?;
+                    out.push(IndexEntry {
+                        key: key_rel,
+                        version: obj_ref.version,
+                        etag_hex: obj_ref.etag,
+                        size_bytes: obj_ref.size_bytes,
+                    });
                 } else {
                     frontier.insert(path);
                 }
             }
         }
 
+        out.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(out)
+    }
+
+    /// Every key under a pons, lexicographically sorted. Reuses the same
+    /// directory walk as the key index rebuild rather than a third copy.
+    fn list_keys(&self, pons: &str) -> Result<Vec<String>> {
+        Ok(self
+            .scan_all_latest(pons)?
+            .into_iter()
+            .map(|e| e.key)
+            .collect())
+    }
+
+    /// Every version id on disk for a `(pons, key)`, ascending. Version ids
+    /// are `<ts_ms>-<etag12>`, so lexicographic order is chronological order.
+    fn list_versions(&self, pons: &str, key: &str) -> Result<Vec<String>> {
+        let versions_dir = self.versions_dir(pons, key)?;
+        let mut out = Vec::new();
+        if versions_dir.is_dir() {
+            for entry in fs::read_dir(&versions_dir)? {
+                let entry = entry?;
+                if !entry.file_type()?.is_file() {
+                    continue;
+                }
+                let name = entry.file_name();
+                if let Some(stem) = name.to_str().and_then(|n| n.strip_suffix(".bin")) {
+                    out.push(stem.to_string());
+                }
+            }
+        }
+        out.sort();
         Ok(out)
     }
 
+    /// Mark-sweep GC across a `pons`, Garage-block-GC style: `live` is the
+    /// set of `(key, version)` pairs still reachable from `ObjectRef`s on the
+    /// memory DAG — this store has no notion of "in use" on its own, so
+    /// callers must supply it. A version survives if it's live, or `policy`
+    /// says to keep it regardless (newest N, or newer than some age);
+    /// everything else has its `.bin`/`.json` removed.
+    ///
+    /// `LATEST` is repaired *before* any deletion happens: if the pointed-to
+    /// version would be swept, the pointer is moved to the newest surviving
+    /// version (or removed if none survive) first, so a crash mid-sweep can
+    /// only leave behind not-yet-deleted files, never a pointer to a missing
+    /// one.
+    ///
+    /// Local-disk backends only.
+    pub fn gc_with_policy(
+        &self,
+        pons: &str,
+        live: &HashSet<(String, String)>,
+        policy: &RetentionPolicy,
+    ) -> Result<GcReport> {
+        let pons = sanitize_key(pons)?;
+        let mut report = GcReport::default();
+
+        for key in self.list_keys(&pons)? {
+            report.scanned_keys += 1;
+            let versions = self.list_versions(&pons, &key)?;
+            let total = versions.len();
+            let mut keep = vec![false; total];
+
+            for (i, vid) in versions.iter().enumerate() {
+                if live.contains(&(key.clone(), vid.clone())) {
+                    keep[i] = true;
+                }
+            }
+            if let Some(n) = policy.keep_last_n {
+                for slot in keep.iter_mut().skip(total.saturating_sub(n)) {
+                    *slot = true;
+                }
+            }
+            if let Some(max_age) = policy.keep_newer_than {
+                let now_ms = Utc::now().timestamp_millis();
+                for (i, vid) in versions.iter().enumerate() {
+                    if let Some(ts_ms) = parse_version_timestamp(vid) {
+                        let age_ms = now_ms.saturating_sub(ts_ms);
+                        if age_ms >= 0 && (age_ms as u128) < max_age.as_millis() {
+                            keep[i] = true;
+                        }
+                    }
+                }
+            }
+
+            let key_dir = self.key_dir(&pons, &key)?;
+            let latest_path = key_dir.join(LATEST_FILE);
+            let current_latest = fs::read_to_string(&latest_path)
+                .ok()
+                .map(|s| s.trim().to_string());
+
+            if let Some(cur) = &current_latest {
+                let still_kept = versions
+                    .iter()
+                    .position(|v| v == cur)
+                    .map(|i| keep[i])
+                    .unwrap_or(false);
+                if !still_kept {
+                    let newest_kept = versions
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .find(|(i, _)| keep[*i])
+                        .map(|(_, v)| v.clone());
+                    match newest_kept {
+                        Some(vid) => write_atomic(&latest_path, vid.as_bytes())?,
+                        None => {
+                            let _ = fs::remove_file(&latest_path);
+                        }
+                    }
+                }
+            }
+
+            let versions_dir = self.versions_dir(&pons, &key)?;
+            for (i, vid) in versions.iter().enumerate() {
+                if keep[i] {
+                    continue;
+                }
+                let bin = data_path(&versions_dir, vid);
+                if let Ok(meta) = fs::metadata(&bin) {
+                    report.bytes_removed += meta.len();
+                }
+                // Drop this version's reference to its content-addressed
+                // blob before unlinking the version files, so the blob can
+                // eventually hit refcount zero and be reclaimed by
+                // prune_blobs -- otherwise every blob_incref from
+                // store_version_blob is permanent and dedup'd bytes are
+                // never actually freed.
+                if let Ok(Some(sidecar)) = load_sidecar(&versions_dir, vid) {
+                    let _ = self.decref_blob(&sidecar.etag);
+                }
+                let _ = fs::remove_file(&bin);
+                let _ = fs::remove_file(sidecar_path(&versions_dir, vid));
+                report.versions_removed += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
     fn latest_version(&self, pons: &str, key: &str) -> Result<String> {
-        let key_dir = self.key_dir(pons, key);
+        let key_dir = self.key_dir(pons, key)?;
         let pointer = key_dir.join(LATEST_FILE);
         if let Ok(s) = fs::read_to_string(&pointer) {
             let trimmed = s.trim();
@@ -367,7 +1388,7 @@ impl PonsStore {
     }
 
     fn read_version_bytes(&self, pons: &str, key: &str, version_id: &str) -> Result<Vec<u8>> {
-        let versions_dir = self.versions_dir(pons, key);
+        let versions_dir = self.versions_dir(pons, key)?;
         let path = data_path(&versions_dir, version_id);
         Ok(fs::read(&path).with_context(|| format!("read {:?}", path))?)
     }
@@ -396,19 +1417,446 @@ impl PonsStore {
         best.ok_or_else(|| anyhow::anyhow!("no versions found under {:?}", versions_dir))
     }
 
-    fn pons_dir(&self, pons: &str) -> PathBuf {
-        self.root.join(OBJECTS_DIR).join(pons)
+    fn pons_dir(&self, pons: &str) -> Result<PathBuf> {
+        Ok(self.local_root()?.join(pons))
+    }
+
+    fn key_dir(&self, pons: &str, key: &str) -> Result<PathBuf> {
+        Ok(self.pons_dir(pons)?.join(key))
+    }
+
+    fn versions_dir(&self, pons: &str, key: &str) -> Result<PathBuf> {
+        Ok(self.key_dir(pons, key)?.join(VERSIONS_DIR))
+    }
+
+    fn blobs_root(&self) -> Result<PathBuf> {
+        Ok(self.local_root()?.join(BLOBS_DIR))
     }
 
-    fn key_dir(&self, pons: &str, key: &str) -> PathBuf {
-        self.pons_dir(pons).join(key)
+    fn blob_dir(&self, hash: &str) -> Result<PathBuf> {
+        Ok(self.blobs_root()?.join(&hash[..2]))
     }
 
-    fn versions_dir(&self, pons: &str, key: &str) -> PathBuf {
-        self.key_dir(pons, key).join(VERSIONS_DIR)
+    fn blob_path(&self, hash: &str) -> Result<PathBuf> {
+        Ok(self.blob_dir(hash)?.join(format!("{hash}.bin")))
+    }
+
+    fn blob_sidecar_path(&self, hash: &str) -> Result<PathBuf> {
+        Ok(self.blob_dir(hash)?.join(format!("{hash}.json")))
+    }
+
+    /// Write `data` into the content-addressed blob store (deduping
+    /// identical payloads across versions/keys/pons, the way Garage's block
+    /// layer dedups by content hash) and make `data_path` a hardlink to the
+    /// blob so the immutable per-version file still reads back byte-for-byte
+    /// unchanged.
+    fn store_version_blob(&self, data_path: &Path, hash: &str, data: &[u8]) -> Result<()> {
+        let blob_path = self.blob_path(hash)?;
+        if !blob_path.is_file() {
+            write_atomic(&blob_path, data)?;
+        }
+        self.blob_incref(hash)?;
+        self.link_version_to_blob(data_path, hash)
+    }
+
+    /// Streaming counterpart to [`PonsStore::store_version_blob`]: copies
+    /// `reader` through a scratch file in `STREAM_CHUNK_LEN`-sized chunks
+    /// while feeding a streaming `blake3::Hasher`, so the whole payload never
+    /// has to live in memory at once. The scratch file becomes the blob
+    /// (an atomic rename) only if no blob for that hash exists yet.
+    fn store_blob_streaming(&self, mut reader: impl Read) -> Result<(String, u64)> {
+        let blobs_root = self.blobs_root()?;
+        fs::create_dir_all(&blobs_root)?;
+        let scratch_path = blobs_root.join(format!(
+            ".put-{}-{}-{}.tmp",
+            std::process::id(),
+            Utc::now().timestamp_millis(),
+            next_scratch_id()
+        ));
+
+        let (etag, size_bytes) = {
+            let mut scratch = fs::File::create(&scratch_path)?;
+            let mut hasher = blake3::Hasher::new();
+            let mut buf = [0u8; STREAM_CHUNK_LEN];
+            let mut total: u64 = 0;
+            loop {
+                let n = reader.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                hasher.update(&buf[..n]);
+                scratch.write_all(&buf[..n])?;
+                total += n as u64;
+            }
+            scratch.sync_all()?;
+            (hasher.finalize().to_hex().to_string(), total)
+        };
+
+        let blob_path = self.blob_path(&etag)?;
+        if blob_path.is_file() {
+            let _ = fs::remove_file(&scratch_path);
+        } else {
+            if let Some(parent) = blob_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&scratch_path, &blob_path)?;
+        }
+        self.blob_incref(&etag)?;
+        Ok((etag, size_bytes))
+    }
+
+    /// Link a versioned path onto an already-written blob, via a hardlink
+    /// where possible; falls back to a streamed copy (never the whole blob
+    /// in memory at once) when hardlinking isn't possible, e.g. the versions
+    /// dir and the blob store end up on different filesystems.
+    fn link_version_to_blob(&self, data_path: &Path, hash: &str) -> Result<()> {
+        let blob_path = self.blob_path(hash)?;
+        if fs::hard_link(&blob_path, data_path).is_err() {
+            let mut src = fs::File::open(&blob_path)?;
+            let tmp = data_path.with_extension("tmp");
+            {
+                let mut f = fs::File::create(&tmp)?;
+                std::io::copy(&mut src, &mut f)?;
+                f.sync_all()?;
+            }
+            fs::rename(&tmp, data_path)?;
+        }
+        Ok(())
+    }
+
+    fn blob_incref(&self, hash: &str) -> Result<()> {
+        let path = self.blob_sidecar_path(hash)?;
+        let mut sidecar = load_blob_sidecar(&path)?.unwrap_or_default();
+        sidecar.refcount += 1;
+        write_atomic(&path, &serde_json::to_vec_pretty(&sidecar)?)
+    }
+
+    /// Decrement a blob's refcount (saturating at zero). Call this when a
+    /// version that referenced the blob is removed; the underlying bytes
+    /// are only actually reclaimed by a later [`PonsStore::prune_blobs`] call.
+    pub fn decref_blob(&self, hash: &str) -> Result<()> {
+        let path = self.blob_sidecar_path(hash)?;
+        let mut sidecar = load_blob_sidecar(&path)?.unwrap_or_default();
+        sidecar.refcount = sidecar.refcount.saturating_sub(1);
+        write_atomic(&path, &serde_json::to_vec_pretty(&sidecar)?)
+    }
+
+    /// Sweep every content-addressed blob whose refcount has reached zero,
+    /// removing its data file and refcount sidecar. Returns the hashes that
+    /// were removed.
+    pub fn prune_blobs(&self) -> Result<Vec<String>> {
+        let root = self.blobs_root()?;
+        if !root.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut removed = Vec::new();
+        for shard in fs::read_dir(&root)? {
+            let shard = shard?;
+            if !shard.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(shard.path())? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.extension().and_then(OsStr::to_str) != Some("json") {
+                    continue;
+                }
+                let sidecar = load_blob_sidecar(&path)?.unwrap_or_default();
+                if sidecar.refcount == 0 {
+                    let hash = path
+                        .file_stem()
+                        .and_then(OsStr::to_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    let _ = fs::remove_file(shard.path().join(format!("{hash}.bin")));
+                    let _ = fs::remove_file(&path);
+                    removed.push(hash);
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    fn index_path(&self, pons: &str) -> Result<PathBuf> {
+        Ok(self.pons_dir(pons)?.join(INDEX_FILE))
+    }
+
+    /// Open the on-disk index for `pons`, rebuilding it from a directory scan
+    /// if the file is absent, unreadable, or carries a bad magic header.
+    /// Returns `None` only when the pons truly has no entries (an empty
+    /// index is still written for next time, but there is nothing to open).
+    fn open_or_rebuild_index(&self, pons: &str, path: &Path) -> Result<Option<fs::File>> {
+        if let Ok(mut f) = fs::File::open(path) {
+            if read_index_count(&mut f).is_ok() {
+                return Ok(Some(f));
+            }
+        }
+        let entries = self.scan_all_latest(pons)?;
+        self.write_index(pons, &entries)?;
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(fs::File::open(path)?))
+    }
+
+    fn read_all_index_entries(&self, file: &mut fs::File) -> Result<Vec<IndexEntry>> {
+        let count = read_index_count(file)?;
+        let mut out = Vec::with_capacity(count as usize);
+        for i in 0..count as usize {
+            let rec = read_index_record(file, i)?;
+            let key = read_index_heap_string(file, count, rec.key_off, rec.key_len)?;
+            let version = read_index_heap_string(file, count, rec.ver_off, rec.ver_len)?;
+            out.push(IndexEntry {
+                key,
+                version,
+                etag_hex: hex_encode(&rec.etag),
+                size_bytes: rec.size_bytes,
+            });
+        }
+        Ok(out)
+    }
+
+    /// Rewrite the whole index with `key` upserted in sorted position, via
+    /// the same atomic temp-rename primitive used for data and sidecar
+    /// writes. The index is small relative to object payloads, so a
+    /// whole-file read-modify-write is simpler than an in-place splice and
+    /// keeps the same crash-safety guarantee as everything else in this file.
+    fn index_upsert(
+        &self,
+        pons: &str,
+        key: &str,
+        version: &str,
+        etag_hex: &str,
+        size_bytes: u64,
+    ) -> Result<()> {
+        let path = self.index_path(pons)?;
+        let mut entries = match self.open_or_rebuild_index(pons, &path)? {
+            Some(mut f) => self.read_all_index_entries(&mut f)?,
+            None => Vec::new(),
+        };
+
+        let entry = IndexEntry {
+            key: key.to_string(),
+            version: version.to_string(),
+            etag_hex: etag_hex.to_string(),
+            size_bytes,
+        };
+        match entries.binary_search_by(|e| e.key.as_str().cmp(key)) {
+            Ok(pos) => entries[pos] = entry,
+            Err(pos) => entries.insert(pos, entry),
+        }
+        self.write_index(pons, &entries)
+    }
+
+    fn write_index(&self, pons: &str, entries: &[IndexEntry]) -> Result<()> {
+        let mut heap = Vec::new();
+        let mut records = Vec::with_capacity(entries.len() * INDEX_RECORD_LEN);
+        for e in entries {
+            let key_off = heap.len() as u32;
+            heap.extend_from_slice(e.key.as_bytes());
+            let key_len = u16::try_from(e.key.len())
+                .with_context(|| format!("pons index key too long: {}", e.key))?;
+
+            let ver_off = heap.len() as u32;
+            heap.extend_from_slice(e.version.as_bytes());
+            let ver_len = u16::try_from(e.version.len())
+                .with_context(|| format!("pons index version too long: {}", e.version))?;
+
+            records.extend_from_slice(&key_off.to_be_bytes());
+            records.extend_from_slice(&key_len.to_be_bytes());
+            records.extend_from_slice(&ver_off.to_be_bytes());
+            records.extend_from_slice(&ver_len.to_be_bytes());
+            records.extend_from_slice(&hex_decode_32(&e.etag_hex)?);
+            records.extend_from_slice(&e.size_bytes.to_be_bytes());
+        }
+
+        let mut out = Vec::with_capacity(INDEX_HEADER_LEN + records.len() + heap.len());
+        out.extend_from_slice(INDEX_MAGIC);
+        out.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        out.extend_from_slice(&records);
+        out.extend_from_slice(&heap);
+
+        write_atomic(&self.index_path(pons)?, &out)
+    }
+
+    /// Binary-search the on-disk index for the page of entries matching
+    /// `prefix`/`start_after`, reading only the touched fixed-size records
+    /// and the key/version bytes they point at — never the whole file.
+    fn list_latest_page_indexed(
+        &self,
+        pons: &str,
+        pref_norm: &str,
+        cursor_norm: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRef>> {
+        let path = self.index_path(pons)?;
+        let mut file = match self.open_or_rebuild_index(pons, &path)? {
+            Some(f) => f,
+            None => return Ok(Vec::new()),
+        };
+
+        let count = read_index_count(&mut file)?;
+        let start_key = cursor_norm.unwrap_or(pref_norm);
+
+        // Binary search for the first record not "behind" start_key: strictly
+        // greater than the cursor when one was given, else the first >= prefix.
+        let mut lo = 0usize;
+        let mut hi = count as usize;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let rec = read_index_record(&mut file, mid)?;
+            let key = read_index_heap_string(&mut file, count, rec.key_off, rec.key_len)?;
+            let behind = match cursor_norm {
+                Some(cur) => key.as_str() <= cur,
+                None => key.as_str() < start_key,
+            };
+            if behind {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let mut out = Vec::with_capacity(limit.min(128));
+        let mut i = lo;
+        while i < count as usize && out.len() < limit {
+            let rec = read_index_record(&mut file, i)?;
+            let key = read_index_heap_string(&mut file, count, rec.key_off, rec.key_len)?;
+
+            if !pref_norm.is_empty() {
+                if key.as_str() < pref_norm {
+                    i += 1;
+                    continue;
+                }
+                if !key.starts_with(pref_norm) {
+                    // Sorted order: every later key is >= this one too, so
+                    // once we're past the prefix block we're done for good.
+                    break;
+                }
+            }
+
+            let version = read_index_heap_string(&mut file, count, rec.ver_off, rec.ver_len)?;
+            out.push(ObjectRef {
+                pons: pons.to_string(),
+                key,
+                version,
+                etag: hex_encode(&rec.etag),
+                size_bytes: rec.size_bytes,
+            });
+            i += 1;
+        }
+
+        Ok(out)
     }
 }
 
+// ---------- on-disk key index (objects/<pons>/.index) ----------
+//
+// Sorted, fixed-width binary index so `list_latest_page` can binary-search
+// a `start_after`/`prefix` position and then read a contiguous page of
+// `limit` records directly, instead of walking every per-key directory —
+// the O(log n) index this module's doc comment on `list_latest_page` used
+// to flag as future work. Modeled on Mercurial's dirstate-v2 approach:
+// fixed-width records sorted by key, with the key/version strings living in
+// a trailing heap, parsed lazily via seek rather than deserialized in full
+// on open.
+//
+// Layout: `[12-byte magic][u32 BE record_count][records...][heap bytes...]`.
+// Record (52 bytes, offsets relative to the start of the heap):
+// `key_off: u32 BE`, `key_len: u16 BE`, `ver_off: u32 BE`, `ver_len: u16 BE`,
+// `etag: [u8; 32]` (raw blake3 digest), `size_bytes: u64 BE`.
+//
+// A missing file, bad magic, or any parse failure transparently triggers a
+// rebuild from the existing per-key directory scan (`scan_all_latest`) —
+// the index is a cache of ground truth, not ground truth itself.
+
+const INDEX_FILE: &str = ".index";
+const INDEX_MAGIC: &[u8; 12] = b"PONSIDX_v1\0\0";
+const INDEX_RECORD_LEN: usize = 4 + 2 + 4 + 2 + 32 + 8; // 52 bytes
+const INDEX_HEADER_LEN: usize = INDEX_MAGIC.len() + 4; // magic + record_count
+
+struct IndexEntry {
+    key: String,
+    version: String,
+    etag_hex: String,
+    size_bytes: u64,
+}
+
+struct RawIndexRecord {
+    key_off: u32,
+    key_len: u16,
+    ver_off: u32,
+    ver_len: u16,
+    etag: [u8; 32],
+    size_bytes: u64,
+}
+
+fn read_index_count(file: &mut fs::File) -> Result<u32> {
+    use std::io::{Read, Seek, SeekFrom};
+    file.seek(SeekFrom::Start(0))?;
+    let mut magic = [0u8; 12];
+    file.read_exact(&mut magic)?;
+    if &magic != INDEX_MAGIC {
+        anyhow::bail!("pons index magic mismatch");
+    }
+    let mut count_buf = [0u8; 4];
+    file.read_exact(&mut count_buf)?;
+    Ok(u32::from_be_bytes(count_buf))
+}
+
+fn read_index_record(file: &mut fs::File, idx: usize) -> Result<RawIndexRecord> {
+    use std::io::{Read, Seek, SeekFrom};
+    let pos = INDEX_HEADER_LEN as u64 + (idx as u64) * (INDEX_RECORD_LEN as u64);
+    file.seek(SeekFrom::Start(pos))?;
+    let mut buf = [0u8; INDEX_RECORD_LEN];
+    file.read_exact(&mut buf)?;
+    Ok(RawIndexRecord {
+        key_off: u32::from_be_bytes(buf[0..4].try_into().unwrap()),
+        key_len: u16::from_be_bytes(buf[4..6].try_into().unwrap()),
+        ver_off: u32::from_be_bytes(buf[6..10].try_into().unwrap()),
+        ver_len: u16::from_be_bytes(buf[10..12].try_into().unwrap()),
+        etag: buf[12..44].try_into().unwrap(),
+        size_bytes: u64::from_be_bytes(buf[44..52].try_into().unwrap()),
+    })
+}
+
+fn read_index_heap_string(
+    file: &mut fs::File,
+    count: u32,
+    off: u32,
+    len: u16,
+) -> Result<String> {
+    use std::io::{Read, Seek, SeekFrom};
+    let heap_start = INDEX_HEADER_LEN as u64 + (count as u64) * (INDEX_RECORD_LEN as u64);
+    file.seek(SeekFrom::Start(heap_start + off as u64))?;
+    let mut buf = vec![0u8; len as usize];
+    file.read_exact(&mut buf)?;
+    String::from_utf8(buf).context("pons index heap string is not utf8")
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push(HEX[(b >> 4) as usize] as char);
+        s.push(HEX[(b & 0x0f) as usize] as char);
+    }
+    s
+}
+
+fn hex_decode_32(s: &str) -> Result<[u8; 32]> {
+    if s.len() != 64 {
+        anyhow::bail!("expected 64 hex chars for a blake3 etag, got {}", s.len());
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .with_context(|| format!("invalid hex byte in etag at position {i}"))?;
+    }
+    Ok(out)
+}
+
 // ---------- helpers ----------
 
 /// Atomically write bytes to a file.
@@ -446,6 +1894,21 @@ fn load_sidecar(versions_dir: &Path, version: &str) -> Result<Option<ObjectSidec
     Ok(Some(sidecar))
 }
 
+/// Extract the millisecond timestamp prefix from a `<ts_ms>-<etag12>` version id.
+fn parse_version_timestamp(version_id: &str) -> Option<i64> {
+    version_id.split('-').next()?.parse::<i64>().ok()
+}
+
+fn load_blob_sidecar(path: &Path) -> Result<Option<BlobSidecar>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let bytes = fs::read(path).with_context(|| format!("read blob sidecar {:?}", path))?;
+    let sidecar = serde_json::from_slice(&bytes)
+        .with_context(|| format!("parse blob sidecar {:?}", path))?;
+    Ok(Some(sidecar))
+}
+
 fn normalize_pair(pons: &str, key: &str) -> Result<(String, String)> {
     Ok((sanitize_key(pons)?, sanitize_key(key)?))
 }
@@ -0,0 +1,56 @@
+use synaptik_core::commands::ensure_initialized_once;
+use synaptik_core::services::audit::{record_ethics_decision, verify_chain};
+
+// Ethics logging shares one process-wide `.cogniv/logbook/ethics.jsonl` (see
+// `audit::log_paths`), so serialize tests that read/write it to avoid one
+// test's tampering landing between another's append and verify.
+fn ethics_log_guard() -> std::sync::MutexGuard<'static, ()> {
+    use std::sync::{Mutex, Once};
+    static mut PTR: *const Mutex<()> = std::ptr::null();
+    static INIT: Once = Once::new();
+    unsafe {
+        INIT.call_once(|| {
+            let b = Box::new(Mutex::new(()));
+            PTR = Box::into_raw(b);
+        });
+        (&*PTR).lock().unwrap()
+    }
+}
+
+fn ethics_log_path() -> std::path::PathBuf {
+    ensure_initialized_once().expect("init").config.logbook.ethics_log.clone()
+}
+
+#[test]
+fn chained_ethics_decisions_verify_intact() {
+    let _guard = ethics_log_guard();
+
+    record_ethics_decision(
+        "memory_storage",
+        true,
+        "Low",
+        &["request_clarification".to_string()],
+        "routine store",
+    );
+    record_ethics_decision("memory_storage", true, "Low", &[], "routine store again");
+
+    verify_chain().expect("freshly appended chain should verify intact");
+}
+
+#[test]
+fn tampering_with_a_logged_entry_breaks_the_chain() {
+    let _guard = ethics_log_guard();
+
+    record_ethics_decision("memory_storage", true, "Low", &[], "before tamper");
+    verify_chain().expect("chain intact before tamper");
+
+    let path = ethics_log_path();
+    let contents = std::fs::read_to_string(&path).expect("read ethics log");
+    let mut lines: Vec<&str> = contents.lines().filter(|l| !l.trim().is_empty()).collect();
+    let last = lines.len() - 1;
+    let tampered_last = lines[last].replacen("\"before tamper\"", "\"edited after the fact\"", 1);
+    lines[last] = &tampered_last;
+    std::fs::write(&path, lines.join("\n") + "\n").expect("write tampered ethics log");
+
+    assert_eq!(verify_chain(), Err(last));
+}
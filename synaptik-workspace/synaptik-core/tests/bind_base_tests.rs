@@ -0,0 +1,36 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use synaptik_core::commands::Commands;
+use synaptik_core::memory::dag::bind_base;
+
+#[test]
+fn bind_base_reports_a_single_gca_for_an_ordinary_merge() -> anyhow::Result<()> {
+    // An ordinary two-way branch (not a criss-cross merge) must report
+    // exactly one GCA. The common ancestor is reached once per branch
+    // (main_head's parent chain and feat_head's parent chain both
+    // propagate into it), which previously re-pushed it onto bind_base's
+    // queue a second time and double-reported it as if two incomparable
+    // ancestors existed.
+    let ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let main_path = format!("main_bindbase_{}", ns);
+    let feat_path = format!("feat_bindbase_{}", ns);
+
+    let cmds = Commands::new("ignored", None)?;
+
+    let base = cmds.branch(&main_path, None, Some("chat"))?;
+    let _ = cmds.branch(&feat_path, Some(&base), None)?;
+
+    let _ = cmds.append(&main_path, "main content A", None)?;
+    let _ = cmds.append(&feat_path, "feature content B", None)?;
+
+    let main_head = cmds.dag_head(&main_path)?.expect("main head");
+    let feat_head = cmds.dag_head(&feat_path)?.expect("feat head");
+
+    let gcas = bind_base(&main_head, &feat_head)?;
+    assert_eq!(gcas, vec![base]);
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use synaptik_core::commands::bind::three_way_bind_lines;
+
+#[test]
+fn independent_hunks_merge_without_conflict() {
+    let base = "one\ntwo\nthree\nfour\nfive\n";
+    let left = "one\nTWO\nthree\nfour\nfive\n";
+    let right = "one\ntwo\nthree\nfour\nFIVE\n";
+
+    let (merged, had_conflict) = three_way_bind_lines(base, left, right);
+    assert!(!had_conflict);
+    assert_eq!(merged, "one\nTWO\nthree\nfour\nFIVE\n");
+}
+
+#[test]
+fn same_line_divergent_edits_produce_conflict_markers() {
+    let base = "one\ntwo\nthree\n";
+    let left = "one\nLEFT\nthree\n";
+    let right = "one\nRIGHT\nthree\n";
+
+    let (merged, had_conflict) = three_way_bind_lines(base, left, right);
+    assert!(had_conflict);
+    assert_eq!(
+        merged,
+        "one\n<<<<<<< LEFT\nLEFT\n=======\nRIGHT\n>>>>>>> RIGHT\nthree\n"
+    );
+}
+
+#[test]
+fn duplicate_heavy_base_merges_edits_against_the_right_occurrence() {
+    // All three base lines are identical, so a naive content-based match
+    // could anchor an edit against the wrong occurrence; index-based LCS
+    // matching must keep each occurrence distinct.
+    let base = "same\nsame\nsame\n";
+    let left = "same\nLEFT\nsame\n";
+    let right = "same\nsame\nsame\n"; // right == base, so left should win outright
+
+    let (merged, had_conflict) = three_way_bind_lines(base, left, right);
+    assert!(!had_conflict);
+    assert_eq!(merged, left);
+}
+
+#[test]
+fn duplicate_heavy_base_with_edits_on_both_sides_merges_per_occurrence() {
+    let base = "same\nsame\nsame\nsame\n";
+    let left = "same\nLEFT\nsame\nsame\n";
+    let right = "same\nsame\nsame\nRIGHT\n";
+
+    let (merged, had_conflict) = three_way_bind_lines(base, left, right);
+    assert!(!had_conflict);
+    assert_eq!(merged, "same\nLEFT\nsame\nRIGHT\n");
+}
@@ -1139,6 +1139,13 @@ fn compactor_compacts_and_reports_ethos() {
         archive_to_dag: false,
         summarizer: synaptik_core::config::SummarizerKind::Heuristic,
         target_chars: None,
+        delta_keyframe_interval: 32,
+        delta_min_similarity: 0.5,
+        summarizer_max_attempts: 1,
+        summarizer_base_delay_ms: 100,
+        summarizer_max_delay_ms: 5_000,
+        summarizer_full_jitter: false,
+        summarizer_attempt_timeout_ms: 2_000,
     };
     let report = comp
         .compact_lobe(&lobe, &policy, false)
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use rusqlite::Connection;
-use synaptik_core::services::learner::StepAssembler;
+use synaptik_core::services::learner::{StepAssembler, TDLearner};
 use synaptik_core::services::memory::Memory;
 
 fn open_sqlite<P: AsRef<std::path::Path>>(p: P) -> Connection {
@@ -132,3 +132,63 @@ fn assembler_record_from_reward_finds_next_state_in_lobe() {
         .ok();
     assert_eq!(got_next.as_deref(), Some(s2));
 }
+
+#[test]
+fn learn_td0_sweeps_steps_in_order_and_best_action_picks_higher_reward() {
+    let db_path = tmp_db("learn_td0");
+    let conn = open_sqlite(&db_path);
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "values" (
+            state_id TEXT PRIMARY KEY,
+            value REAL NOT NULL,
+            updated_ms INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS steps (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            lobe TEXT NOT NULL,
+            state_id TEXT,
+            action_capsule_id TEXT NOT NULL,
+            reward REAL NOT NULL,
+            next_state_id TEXT,
+            ts_ms INTEGER NOT NULL
+        );
+        "#,
+    )
+    .expect("init schema");
+
+    // s1 -[good]-> s2 (terminal), and a worse alternative action from s1
+    // straight to terminal, so best_action should prefer "good".
+    conn.execute(
+        "INSERT INTO steps (lobe, state_id, action_capsule_id, reward, next_state_id, ts_ms) VALUES ('rl', 's1', 'good', 1.0, 's2', 1000)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO steps (lobe, state_id, action_capsule_id, reward, next_state_id, ts_ms) VALUES ('rl', 's2', 'terminal', 0.5, NULL, 2000)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO steps (lobe, state_id, action_capsule_id, reward, next_state_id, ts_ms) VALUES ('rl', 's1', 'bad', -1.0, NULL, 1500)",
+        [],
+    )
+    .unwrap();
+
+    let learner = TDLearner::open_at(db_path.clone());
+    let applied = learner.learn_td0("rl", 0.5, 0.9).expect("learn_td0");
+    assert_eq!(applied, 3, "all three steps in the lobe should be swept");
+
+    // s2 is terminal: V(s2) <- 0 + 0.5*(0.5 + 0.9*0 - 0) = 0.25
+    let v_s2 = learner.value_of("s2").expect("value_of s2");
+    assert!((v_s2 - 0.25).abs() < 1e-6, "v_s2 = {v_s2}");
+
+    // s1's "good" step ran before "bad" in ts_ms order and bootstraps off
+    // the not-yet-updated V(s2)=0: V(s1) <- 0 + 0.5*(1.0 + 0.9*0 - 0) = 0.5,
+    // then "bad" updates it again: 0.5 + 0.5*(-1.0 + 0.9*0 - 0.5) = -0.25
+    let v_s1 = learner.value_of("s1").expect("value_of s1");
+    assert!((v_s1 - (-0.25)).abs() < 1e-6, "v_s1 = {v_s1}");
+
+    let action = learner.best_action("rl", "s1").expect("best_action");
+    assert_eq!(action.as_deref(), Some("good"));
+}
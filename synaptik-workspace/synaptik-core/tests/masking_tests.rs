@@ -1,50 +1,9 @@
-// (Removed unused index_for / gate_for helpers to silence dead_code warnings.)
-
-// Helper that invokes internal apply_masks by going through gate push & finalize flows when the
-// contract encodes a mask directive. For direct unit tests of masking normalization spans,
-// we re-import the function (cfg(test) in the module would be cleaner, but we test via behavior).
+use contracts::apply_masks;
 
 #[test]
 fn zero_width_obfuscated_sequence_is_fully_masked() {
-    // Simulate a mask rule by constructing a synthetic post-processing scenario:
-    // We directly call the same normalization logic by recreating the problematic pattern.
     let orig = "pa\u{200b}ssword token"; // zero-width space inside
-    // We rely on the normalized version matching "password"; emulate mask application with pattern.
-    let patterns = vec!["password".to_string()];
-    let masked = {
-        // Reuse compactor's apply_masks_ci logic via a minimal reimplementation here to validate
-        // span correctness; duplicated intentionally to avoid making internal function pub.
-        fn norm_lower(s: &str) -> String { contracts::normalize::for_rules(s) }
-        fn normalized_chars_with_spans(s: &str) -> (Vec<char>, Vec<(usize, usize)>) {
-            let mut chars = Vec::new();
-            let mut spans = Vec::new();
-            for (start, ch) in s.char_indices() {
-                if ch.is_control() && ch != '\n' && ch != '\t' { continue; }
-                let end = start + ch.len_utf8();
-                let nf = norm_lower(&ch.to_string());
-                if nf.is_empty() { continue; }
-                for c in nf.chars() { chars.push(c); spans.push((start,end)); }
-            }
-            (chars, spans)
-        }
-        let mut out = orig.to_string();
-        for pat in patterns.iter() {
-            let pchars: Vec<char> = norm_lower(pat).chars().collect();
-            let (nchars, spans) = normalized_chars_with_spans(&out);
-            let plen = pchars.len();
-            if plen == 0 || plen > nchars.len() { continue; }
-            let mut i=0; let mut ranges=Vec::<(usize,usize)>::new();
-            while i + plen <= nchars.len() {
-                if (0..plen).all(|j| nchars[i+j]==pchars[j]) { let (s,_)=spans[i]; let (_,e)=spans[i+plen-1]; ranges.push((s,e)); i+=plen; } else { i+=1; }
-            }
-            if ranges.is_empty() { continue; }
-            ranges.sort_by_key(|r| r.0);
-            let mut binding: Vec<(usize,usize)> = Vec::new();
-            for (s,e) in ranges { if let Some(last)=binding.last_mut() { if s <= last.1 { last.1 = last.1.max(e); continue; }} binding.push((s,e)); }
-            for (s,e) in binding.into_iter().rev() { out.replace_range(s..e, "[masked]"); }
-        }
-        out
-    };
+    let masked = apply_masks(orig, &["password".to_string()]);
     assert!(!masked.contains("ssword"), "Suffix leaked: {masked}");
     assert!(masked.contains("[masked]"), "Mask token missing: {masked}");
 }
@@ -54,13 +13,19 @@ fn combining_marks_are_masked_as_whole() {
     // e + combining acute = "eÌ" which normalizes to something comparable; ensure full span masked.
     let orig = "user: pa\u{301}ss keys"; // combining acute after 'a'
     let patterns = vec!["pa\u{301}ss".to_string(), "pass".to_string()];
-    // Use same scratch masking harness.
-    fn norm_lower(s: &str) -> String { contracts::normalize::for_rules(s) }
-    fn normalized_chars_with_spans(s: &str) -> (Vec<char>, Vec<(usize, usize)>) {
-        let mut chars = Vec::new(); let mut spans = Vec::new();
-        for (start,ch) in s.char_indices() { if ch.is_control() && ch!='\n' && ch!='\t' { continue; } let end=start+ch.len_utf8(); let nf=norm_lower(&ch.to_string()); if nf.is_empty(){continue;} for c in nf.chars(){chars.push(c); spans.push((start,end));}} (chars,spans)
-    }
-    let mut out = orig.to_string();
-    for pat in patterns.iter() { let pchars: Vec<char> = norm_lower(pat).chars().collect(); let (nchars,spans)=normalized_chars_with_spans(&out); let plen=pchars.len(); if plen==0||plen>nchars.len(){continue;} let mut i=0; let mut ranges: Vec<(usize,usize)> = Vec::new(); while i+plen<=nchars.len(){ if (0..plen).all(|j| nchars[i+j]==pchars[j]) { let (s,_)=spans[i]; let (_,e)=spans[i+plen-1]; ranges.push((s,e)); i+=plen;} else { i+=1; }} if ranges.is_empty(){continue;} ranges.sort_by_key(|r| r.0); let mut binding: Vec<(usize,usize)> = Vec::new(); for (s,e) in ranges { if let Some(last)=binding.last_mut(){ if s<=last.1 { last.1=last.1.max(e); continue; }} binding.push((s,e)); } for (s,e) in binding.into_iter().rev(){ out.replace_range(s..e, "[masked]"); } }
-    assert!(!out.contains("ss keys"), "Partial leak after combining mark: {out}");
+    let masked = apply_masks(orig, &patterns);
+    assert!(!masked.contains("ss keys"), "Partial leak after combining mark: {masked}");
+}
+
+#[test]
+fn ansi_wrapped_phrase_normalizes_contiguous() {
+    // A phrase wrapped in SGR color codes still reads as "kill" once normalized.
+    let orig = "\u{1b}[31mki\u{1b}[0mll";
+    assert_eq!(contracts::normalize::for_rules(orig), "kill");
+}
+
+#[test]
+fn control_chars_stripped_but_tab_and_newline_kept() {
+    let orig = "line1\n\tkill\u{07}switch";
+    assert_eq!(contracts::normalize::for_rules(orig), "line1\n\tkillswitch");
 }
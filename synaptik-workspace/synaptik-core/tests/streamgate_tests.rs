@@ -59,6 +59,23 @@ fn generic_harm_holds_then_cuts_on_finalize() {
     }
 }
 
+#[test]
+fn straddled_phrase_across_pushes_is_caught() {
+    let contract = default_contract();
+    let mut g = gate_from_contract(contract, "say");
+
+    // Neither half alone contains the banned phrase, but the tail window
+    // carries "I want to ki" into the next push so "kill" still matches.
+    match g.push("I want to ki") {
+        GateDecision::Pass => {}
+        other => panic!("first half alone shouldn't trigger, got {:?}", other),
+    }
+    match g.push("ll") {
+        GateDecision::Hold => {}
+        other => panic!("straddled phrase should hold, got {:?}", other),
+    }
+}
+
 #[test]
 fn debug_generic_harm() {
     // Debug test to see what's happening